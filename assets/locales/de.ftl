@@ -0,0 +1,489 @@
+tab-search = Suche
+tab-settings = Einstellungen
+tab-statistics = Statistik
+theme-light = Heller Modus
+theme-dark = Dunkler Modus
+
+settings-title = Anwendungseinstellungen
+settings-subtitle = Sucheinstellungen, indizierte Orte und Desktop-Optionen festlegen
+settings-save = Alle Änderungen speichern
+section-search = Sucheinstellungen
+section-macros = Suchmakros
+section-history = Suchverlauf
+section-profile = Profil
+section-index-dirs = Indizierte Ordner
+section-system = System- und Desktop-Einstellungen
+section-opening = Dateien öffnen
+section-shortcuts = Tastenkürzel
+section-appearance = Darstellung und Design
+section-language = Sprache
+section-problems = Probleme
+section-activity = Aktivitätsprotokoll
+section-data = Datenverwaltung
+section-privacy = Datenschutz und lokale Sicherheit
+
+language-hint = Sprache von Menüs, Beschriftungen und Meldungen
+language-auto = Automatisch ({ $language })
+
+profile-hint = Jedes Profil hat eigene Ordner, Ausschlüsse und einen eigenen Index. Beim Wechseln öffnet sich ein Fenster für das gewählte Profil.
+profile-active = Aktives Profil
+profile-new-placeholder = Neues Profil, z. B. Arbeit
+profile-create = Anlegen
+profile-failed = Profilwechsel fehlgeschlagen: { $error }
+unlock-title = Index entsperren
+unlock-hint = Der Suchindex dieses Profils ist verschlüsselt. Gib seine Passphrase ein, um ihn zu öffnen.
+unlock-choose-title = Passphrase wählen
+unlock-choose-hint = Der Index wird mit dieser Passphrase verschlüsselt, die bei jedem Start abgefragt wird. Vergessen lässt sie sich nicht wiederherstellen.
+unlock-placeholder = Passphrase
+unlock-button = Entsperren
+unlock-busy = Index wird geöffnet…
+unlock-wrong = Diese Passphrase öffnet den Index nicht.
+unlock-needed = Gib die Passphrase ein, um fortzufahren.
+
+search-placeholder-all = Alles durchsuchen (Namen, Text, Dokumente, Code)...
+search-placeholder-text = Dateiinhalte durchsuchen...
+search-placeholder-name = Dateinamen durchsuchen...
+search-clear = Suche leeren
+search-button = Suchen
+search-searching = Suche läuft...
+results-found = { $count } Ergebnisse gefunden
+
+export-label = Exportieren:
+export-done = { $count } Ergebnisse nach { $path } exportiert
+export-failed = Export fehlgeschlagen: { $error }
+
+home-pinned = Angeheftet
+home-recent = Zuletzt geändert
+
+drop-not-folder = Ziehen Sie einen Ordner hierher, um ihn zum Index hinzuzufügen
+remote-invalid = Geben Sie einen entfernten Ordner als ssh://, webdav://, webdavs:// oder smb://benutzer@host/pfad ein
+remote-unsupported = smb://-Freigaben lassen sich nur unter Windows hinzufügen; Freigabe einbinden und den eingebundenen Ordner hinzufügen
+remote-password-failed = Das Passwort konnte nicht im Schlüsselbund gespeichert werden: { $error }
+drop-already-indexed = { $path } ist bereits indiziert
+drop-indexing-about = { $path } wird indiziert (etwa { $count } Dateien)
+drop-indexing-over = { $path } wird indiziert (über { $count } Dateien)
+
+estimate-running = Dateien in { $path } werden gezählt...
+estimate-failed = { $path } konnte nicht durchsucht werden: { $error }
+estimate-title = Diesen Ordner indizieren?
+estimate-files = { $indexable } von { $files } Dateien werden gelesen, { $size }
+estimate-files-over = { $indexable } von über { $files } Dateien werden gelesen, { $size }
+estimate-names-only = Für diesen Ordner werden nur Dateinamen indiziert
+estimate-too-large = { $count } Dateien über dem Limit von { $limit } MB werden übersprungen
+estimate-time = Die Indizierung dauert etwa { $time } und vergrößert den Index um etwa { $size }
+estimate-confirm = Indizieren
+estimate-cancel = Abbrechen
+
+volume-title = Dieses Laufwerk indizieren?
+volume-found = { $label } wurde unter { $path } angeschlossen
+volume-note = Nur Dateinamen werden indiziert. Sie bleiben durchsuchbar, während das Laufwerk getrennt ist, und werden als offline markiert.
+volume-confirm = Namen indizieren
+volume-cancel = Nicht jetzt
+
+reconnect-title = Diese Datei ist offline
+reconnect-drive = Sie liegt auf dem Wechseldatenträger { $volume }. Schließen Sie das Laufwerk an und prüfen Sie erneut.
+reconnect-share = Ihr Ordner ist gerade nicht erreichbar. Verbinden Sie die Freigabe oder das Laufwerk erneut und prüfen Sie dann noch einmal.
+reconnect-check = Erneut prüfen
+reconnect-cancel = Abbrechen
+reconnect-done = { $path } ist wieder verfügbar
+reconnect-still = { $path } ist weiterhin offline
+
+trash-bin = Papierkorb
+trash-bin-windows = Papierkorb
+trash-title = In den { $bin } verschieben?
+trash-more = …und { $count } weitere
+trash-note = Sie werden auch aus dem Index entfernt und nach dem Wiederherstellen erneut indiziert.
+trash-confirm = Verschieben
+trash-cancel = Abbrechen
+
+open-with-title = { $name } öffnen mit
+open-with-other = Andere Anwendung…
+open-with-cancel = Abbrechen
+
+stats-title = Indexstatistik
+stats-subtitle = Was der Index enthält und wie viel Speicherplatz er belegt
+stats-loading = Statistik wird erstellt...
+stats-failed = Die Indexstatistik konnte nicht gelesen werden: { $error }
+stats-refresh = Aktualisieren
+stats-storage = Speicher
+stats-documents = Dokumente
+stats-search-index = Suchindex
+stats-metadata-db = Metadatenbank
+stats-filename-index = Dateinamenindex
+stats-total = Gesamt auf dem Datenträger
+stats-cache = Abfrage-Cache
+stats-cache-ratio = { $percent } % von { $count } Suchen aus dem Cache beantwortet
+stats-cache-none = Noch keine Suchen
+stats-by-root = Nach Ordner
+stats-by-extension = Nach Dateityp
+stats-largest = Größte Dokumente
+stats-no-documents = Noch nichts indiziert
+stats-files = { $count } Dateien
+stats-more = und { $count } weitere
+
+max-results-title = Maximale Suchergebnisse
+max-results-hint = Begrenzt die Zahl der zurückgegebenen Suchergebnisse für mehr Geschwindigkeit
+exclude-title = Ausschlussmuster (durch Kommas getrennt)
+exclude-hint = Ordner- und Dateimuster, die beim Indizieren übersprungen werden (z. B. *.git, target, node_modules)
+extensions-title = Eigene Dateiendungen
+extensions-hint = Weitere Dateiformate, die als reiner Text indiziert werden (durch Kommas getrennt)
+extensions-placeholder = z. B. log, env, conf, sdp
+hotkey-title = Globales Tastenkürzel für die Suche
+hotkey-hint = Tastenkürzel, das FindAll von überall aus öffnet
+quick-hotkey-title = Tastenkürzel für die Schnellsuche
+quick-hotkey-hint = Öffnet eine kleine, stets sichtbare Suchleiste; leer lassen, um sie auszuschalten
+
+macros-hint = @Name ins Suchfeld tippen, um einen gespeicherten Suchbaustein einzusetzen
+macros-add = Hinzufügen
+macros-label = Makros:
+
+history-enabled = Suchen merken, die ich ausführe oder aus denen ich ein Ergebnis öffne
+history-empty = Noch keine Suchen gemerkt
+history-clear = Nicht angeheftete Suchen löschen
+history-button = Suchverlauf
+history-dropdown-empty = Suchen, die Sie ausführen oder aus denen Sie ein Ergebnis öffnen, erscheinen hier
+history-no-match = Keine frühere Suche passt
+history-manage = Verlauf verwalten
+history-uses-one = 1 Suche
+history-uses = { $count } Suchen
+history-pin-hint = Diese Suche oben halten
+history-delete = Aus dem Verlauf löschen
+pin = Anheften
+unpin = Lösen
+
+dirs-empty = Keine Ordner zum Indizieren eingerichtet.
+dirs-add = Ordner zum Index hinzufügen
+
+system-tray = Beim Schließen des Fensters in den Infobereich minimieren
+system-autostart = FindAll beim Systemstart automatisch starten
+system-context-menu = „Mit FindAll suchen“ zum Windows-Kontextmenü hinzufügen
+system-gitignore = .gitignore-Regeln beim Durchsuchen von Repository-Ordnern beachten
+system-mft = Ganze NTFS-Laufwerke direkt aus der MFT lesen, um Dateinamen schnell zu indizieren (erfordert Administratorrechte)
+system-code-aware = getUserName und get_user_name im Quellcode gleich behandeln (gilt für neu indizierte Dateien)
+
+opening-editor-title = Editor für Text und Code
+opening-editor-hint = Öffnet Ergebnisse an der passenden Zeile; {file} und {line} werden eingesetzt
+opening-pdf-title = PDF-Betrachter
+opening-pdf-hint = Öffnet PDFs auf der passenden Seite; {file} und {page} werden eingesetzt
+
+shortcuts-hint = Pfeiltasten bewegen durch die Ergebnisse, F3 springt durch die Treffer der Vorschau; ein leeres Tastenkürzel ist ausgeschaltet
+shortcuts-invalid = Kein gültiges Tastenkürzel
+shortcuts-off = Aus
+
+appearance-theme-title = Farbschema
+appearance-theme-hint = Zwischen dem dunklen und hellen Modus von Windows 11 wechseln
+appearance-dark = Dunkles Design
+
+problems-none = Bei den letzten Durchläufen wurden keine Dateien übersprungen.
+problems-failed = Fehlgeschlagen
+problems-timeout = Zeitüberschreitung
+problems-crashed = Abgestürzt
+problems-summary = { $count } Dateien konnten nicht gelesen werden und wurden übersprungen. Sie werden automatisch erneut versucht, sobald sie sich ändern.
+problems-retry = Beim nächsten Durchlauf alle erneut versuchen
+
+schedule-title = Geplante Aktualisierung
+schedule-hint = Aktualisiert im Hintergrund: off, daily HH:MM (z. B. daily 02:00) oder every Nh (z. B. every 6h).
+schedule-invalid = Kein gültiger Zeitplan
+schedule-never = nie
+schedule-none = nicht geplant
+schedule-summary = Letzter Lauf: { $last } · Nächster Lauf: { $next }
+refresh-title = Index aktualisieren
+refresh-hint = Indiziert nur Ordner neu, deren Inhalt sich seit der letzten Aktualisierung geändert hat, und entfernt gelöschte Dateien.
+refresh-button = Aktualisieren
+rebuild-title = Index vollständig neu aufbauen
+rebuild-hint = Löscht alle zwischengespeicherten Indexdaten und durchsucht alle eingerichteten Ordner neu.
+rebuild-button = Neu aufbauen
+
+privacy-local = FindAll arbeitet vollständig lokal. Weder Daten noch Suchanfragen verlassen jemals Ihren Computer.
+privacy-data-path = Lokaler Datenordner:
+privacy-index-path = Tantivy-Suchindex:
+privacy-db-path = Redb-Datenbank:
+privacy-unknown = Unbekannt
+
+db-corrupted = Die Metadatenbank war beschädigt und wurde zurückgesetzt. Vollständige Neuindizierung empfohlen.
+banner-dismiss = Schließen
+search-error = Fehler: { $error }
+quick-placeholder = Dateien suchen...
+quick-more = { $count } weitere im großen Fenster
+quick-footer = Eingabe zum Öffnen, Esc zum Schließen
+app-tagline = Sofortige lokale Suche
+match-case-hint = Groß-/Kleinschreibung beachten
+match-word-hint = Nur ganze Wörter
+mode-all = Alles
+mode-text = Text
+mode-file = Datei
+theme-switch-light = Zum hellen Design wechseln
+theme-switch-dark = Zum dunklen Design wechseln
+open-settings = Einstellungen öffnen
+
+filters-active = Aktive Filter:
+filters-show = Filteroptionen einblenden
+filters-hide = Filteroptionen ausblenden
+filters-title = Filteroptionen
+filters-reset = Alle Filter zurücksetzen
+filter-extension = Dateiendung
+filter-size = Größenbereich
+filter-size-min = Min.
+filter-size-max = Max.
+filter-date = Zuletzt geändert
+date-anytime = Beliebig
+date-today = Heute
+date-week = Letzte Woche
+date-month = Letzter Monat
+filter-scope = Suchbereich
+scope-all = Alles
+scope-text = Volltext
+scope-name = Dateiname
+filter-flags = Suchoptionen
+flag-case = Groß-/Kleinschreibung
+flag-word = Ganzes Wort
+flag-best = Nur beste Treffer
+flag-full-path = Ganzen Pfad durchsuchen
+results-show-all = Alle { $total } Ergebnisse zeigen ({ $hidden } weniger sichere Treffer ausgeblendet)
+
+menu-open = Öffnen
+menu-open-with = Öffnen mit…
+menu-open-folder = Enthaltenden Ordner öffnen
+menu-copy-path = Pfad kopieren
+menu-copy-picked = { $count } ausgewählte Elemente kopieren
+menu-copy-folder = Ordner kopieren
+menu-copy-file = Datei kopieren
+menu-copy-text = Text kopieren
+menu-copy-matched = Gefundenen Text kopieren
+menu-pin = Auf der Startseite anheften
+menu-reindex = Jetzt neu indizieren
+menu-remove = Aus dem Index entfernen
+menu-trash-picked = { $count } ausgewählte Elemente in den { $bin } verschieben
+menu-trash = In den { $bin } verschieben
+menu-exclude-folder = Diesen Ordner nicht indizieren
+menu-exclude-file = Diese Datei nicht indizieren
+menu-exclude-parent = Enthaltenden Ordner ausschließen
+menu-exclude-ext = Alle .{ $ext }-Dateien ausschließen
+
+column-name = Name
+column-folder = Ordner
+column-ext = Endung
+column-size = Größe
+column-modified = Geändert
+column-score = Relevanz
+group-no-folder = (kein Ordner)
+group-by-folder = Nach Ordner gruppieren
+sort-title = Sortierung und Ansicht
+sort-relevance = Relevanz
+sort-date = Änderungsdatum
+sort-size = Dateigröße
+sort-name = Dateiname
+layout-cards = Karten
+layout-table = Tabelle
+categories = Kategorien
+
+welcome-title = FindAll Sofortsuche
+welcome-subtitle = Blitzschnelle lokale Suche in Texten, Dokumenten und Dateinamen
+welcome-shortcuts = Tastenkürzel
+shortcut-global = Globales Suchfenster
+shortcut-focus = Suchfeld fokussieren
+shortcut-navigate = Durch Ergebnisse blättern
+shortcut-open = Gewählte Datei öffnen
+shortcut-open-folder = Enthaltenden Ordner öffnen
+shortcut-copy-path = Dateipfad kopieren
+welcome-features = Profi-Suchfunktionen
+tip-scope-title = Volltext oder Dateiname
+tip-scope = Suchbereich in der oberen Leiste umschalten
+tip-extension-title = Filter nach Endung
+tip-extension = PDF, MD, RS, TXT und Code in der Seitenleiste filtern
+tip-exact-title = Exakte Treffer und Schreibweise
+tip-exact = Mit „Aa“ und „W“ genauer suchen
+tip-preview-title = Sofortige Dokumentvorschau
+tip-preview = Textausschnitte und Tabellen direkt ansehen
+welcome-status = Indexstatus: { $count } Dateien indiziert ({ $size })
+
+no-results-title = Keine passenden Ergebnisse gefunden
+no-results-hint = Passen Sie die Suche an oder lockern Sie die Filter
+no-results-tips = Vorschläge zur Fehlersuche:
+no-results-tip-spelling = • Schreibweise prüfen oder einfachere Suchbegriffe versuchen
+no-results-tip-mode = • Zwischen Volltext- und Dateinamensuche wechseln
+no-results-tip-filters = • Aktive Endungsfilter in der linken Seitenleiste entfernen
+result-open = Öffnen
+result-folder = Ordner
+unknown-size = Unbekannte Größe
+unknown-date = Unbekanntes Datum
+
+preview-loading = Dokumentinhalt wird geladen...
+preview-select = Ein Suchergebnis für die Vorschau auswählen
+preview-hint = Hier erscheinen Textausschnitte und die Dokumentvorschau
+preview-title = Dokumentvorschau
+preview-match = Treffer { $current } von { $total }
+preview-previous = Vorheriger Treffer (Umschalt+F3)
+preview-next = Nächster Treffer (F3)
+preview-snippets = Passende Ausschnitte
+preview-content = Dokumentinhalt
+preview-elements = { $count } Strukturelemente gelesen
+preview-page = Seite 1 von { $pages }
+preview-binary = Binärdatei ({ $mime }), erste { $shown } von { $size }
+preview-window-start = Langes Dokument: { $size } um den Anfang gezeigt ({ $start } bis { $end } von { $total })
+preview-window-match = Langes Dokument: { $size } um den ersten Treffer gezeigt ({ $start } bis { $end } von { $total })
+owner-line = Besitzer: { $owner }
+read-only = Schreibgeschützt
+hidden = Versteckt
+
+details-title = Details
+details-not-indexed = Nicht indiziert
+details-current = Aktuell, indiziert am { $date }
+details-changed = Auf der Festplatte geändert seit der Indizierung am { $date }
+details-size = Größe
+details-modified = Geändert
+details-created = Erstellt
+details-hash = Inhalts-Hash
+details-parser = Parser
+details-index = Index
+notes-title = Notizen
+notes-placeholder = Notiz hinzufügen, z. B. an Steuerberater geschickt
+notes-attributes = Attribute, z. B. client: Acme, year: 2025
+notes-save = Speichern
+
+watcher-unwatched = Nicht überwacht: { $folders }
+watcher-error = Letzter Fehler: { $error }
+watcher-events = { $count } Änderungen erfasst
+status-files = { $count } Dateien indiziert
+eta-hours = Restzeit: { $hours } h { $minutes } min
+eta-minutes = Restzeit: { $minutes } min { $seconds } s
+eta-seconds = Restzeit: { $seconds } s
+indexing-resume = Fortsetzen
+indexing-pause = Pausieren
+indexing-cancel = Abbrechen
+indexing-resume-previous = Vorherige Indizierung fortsetzen ({ $files } Dateien erledigt)
+
+root-content = Dateiinhalte indizieren
+root-symlinks = Symbolischen Links folgen
+root-separate = Eigener Index
+root-depth = Max. Tiefe
+root-include = Nur diese, z. B. *.rs, *.md
+root-exclude = Zusätzlich ausschließen, z. B. *.bak
+
+sensitive-title = Vertrauliche Dateien
+sensitive-hint = Schlüssel, Passwortspeicher und Geheimnisse, die auf diese Muster passen, werden nie gelesen; Muster mit / gelten für ganze Pfade
+sensitive-names-only = Nur Namen
+sensitive-skip = Ganz überspringen
+sensitive-index = Normal indizieren
+
+encryption-title = Indexverschlüsselung
+encryption-hint = Verschlüsselt Suchindex, Metadaten und Dateinamen auf der Festplatte. Wirkt ab dem nächsten Start, der den vorhandenen Index umwandelt
+encryption-off = Aus
+encryption-keychain = Schlüssel im Schlüsselbund des Systems
+encryption-passphrase = Passphrase
+
+activity-enabled = Aufzeichnen, was indiziert, übersprungen und entfernt wird, und welche Suchen ich ausführe
+activity-note = Liegt auf diesem Computer im Datenordner und wird ab einigen Megabyte rotiert; bei eingeschalteter Indexverschlüsselung werden die Einträge mit dem Index verschlüsselt
+activity-filter = Nach Pfad, Suche oder Ereignis filtern, z. B. skipped
+activity-empty = Noch nichts aufgezeichnet
+activity-clear = Aktivitätsprotokoll löschen
+
+root-hidden = Versteckte Dateien
+root-system = Systemdateien
+
+root-elevated = Dateien lesen, auf die ich keinen Zugriff habe (erfordert Administrator)
+
+open-failed = Die Datei konnte nicht geöffnet werden: { $error }
+
+openers-hint = Befehle für eine Dateiendung, die statt der Standard-App oder des Editors verwendet werden. {} oder {file} ist der Pfad, {line} und {page} die Fundstelle; eine URL öffnet sich in der zuständigen App
+openers-add = Hinzufügen
+
+reindex-done = { $count } Datei(en) neu indiziert
+
+result-changed = Geändert · Neu indizieren
+changed-detail = Seit der Indizierung geändert
+
+query-error = { $message } (Spalte { $column })
+query-try = Versuchen:
+
+did-you-mean = Meinten Sie:
+
+similar-title = Ähnliche Dateien
+
+menu-compare = Inhalte vergleichen
+compare-running = Inhalte werden verglichen…
+compare-failed = Vergleich fehlgeschlagen: { $error }
+diff-summary = { $removed } Zeile(n) entfernt, { $added } hinzugefügt
+diff-identical = Die gelesenen Inhalte sind identisch
+diff-skipped = … { $count } unveränderte Zeile(n) …
+
+root-removable = Auf einem Wechsellaufwerk; seine Dateien bleiben als offline gelistet, solange es nicht angeschlossen ist
+volume-offer = Beim Anschließen von Wechsellaufwerken anbieten, deren Dateinamen zu indizieren
+
+result-offline = Offline · Verbinden
+offline-detail = Offline (Laufwerk getrennt oder Freigabe nicht verbunden)
+
+cloud-title = Nur online verfügbare Cloud-Dateien
+cloud-hint = Nicht heruntergeladene Dateien von OneDrive, Dropbox, Google Drive und iCloud werden nur nach Namen indiziert, da das Lesen sie herunterlädt. Ordner hier eintragen, deren Dateien zum Indizieren heruntergeladen werden dürfen
+cloud-placeholder = z. B. C:\Users\ich\OneDrive\Dokumente
+
+remote-add = Entfernten Ordner hinzufügen
+
+root-rescan = Neu durchsuchen
+root-rescan-global = Globaler Zeitplan
+remote-placeholder = Entfernter Ordner, z. B. ssh://user@host/docs oder smb://user@server/share
+remote-password = Passwort (optional)
+remote-hint = Unterstützt ssh://, webdav://, webdavs:// und smb://. Passwörter liegen im Schlüsselbund des Systems; SSH-Server müssen in ~/.ssh/known_hosts stehen und auch Ihren Agenten oder Ihre Schlüssel akzeptieren
+
+recent-load-failed = Zuletzt verwendete Dateien konnten nicht geladen werden: { $error }
+history-update-failed = Suchverlauf konnte nicht aktualisiert werden: { $error }
+settings-reloaded = Einstellungen von der Festplatte neu geladen
+settings-reload-failed = Einstellungen konnten nicht neu geladen werden: { $error }
+preview-failed = Vorschaufehler: { $error }
+details-failed = Dateidetails konnten nicht gelesen werden: { $error }
+note-saved = Notiz gespeichert
+note-save-failed = Notiz konnte nicht gespeichert werden: { $error }
+text-copied = { $count } Zeichen Text kopiert
+text-copy-failed = Text konnte nicht kopiert werden: { $error }
+files-copy-failed = Dateien konnten nicht kopiert werden: { $error }
+open-with-app-failed = Öffnen mit { $app } fehlgeschlagen: { $error }
+open-with-failed = Öffnen mit fehlgeschlagen: { $error }
+reindex-failed = Neuindizierung fehlgeschlagen: { $error }
+index-removed = Aus dem Index entfernt
+index-remove-failed = Entfernen aus dem Index fehlgeschlagen: { $error }
+trash-moved-one = In den Papierkorb verschoben
+trash-moved = { $count } Elemente in den Papierkorb verschoben
+trash-failed = Verschieben in den Papierkorb fehlgeschlagen: { $error }
+exclude-done = Von der Indizierung ausgeschlossen, { $count } Datei(en) entfernt
+exclude-failed = Ausschließen fehlgeschlagen: { $error }
+status-rebuilding = Index wird neu aufgebaut...
+status-refreshing = Index wird aktualisiert...
+status-paused = Angehalten
+status-resuming = Wird fortgesetzt...
+status-resuming-interrupted = Unterbrochener Scan wird fortgesetzt...
+status-cancelling = Wird abgebrochen...
+pin-failed = Datei konnte nicht angeheftet werden: { $error }
+unpin-failed = Datei konnte nicht losgelöst werden: { $error }
+folder-picker-title = Zu indizierenden Ordner auswählen
+live-partial = Live-Aktualisierung: { $watched } von { $total } Ordnern
+live-pending = Live · { $count } ausstehend
+live = Live
+
+action-open = Ausgewähltes Ergebnis öffnen
+action-reveal = Im Ordner anzeigen
+action-copy-path = Pfad kopieren
+action-clear = Schließen, leeren oder ausblenden
+action-next-pane = Nächster Bereich
+action-previous-pane = Vorheriger Bereich
+
+category-documents = Dokumente
+category-spreadsheets = Tabellen
+category-presentations = Präsentationen
+category-code = Quellcode
+category-data = Daten
+category-images = Bilder
+category-audio = Audio
+category-video = Video
+category-archives = Archive
+category-email = E-Mail
+category-ebooks = E-Books
+category-other = Andere
+
+focus-search = Suchfeld
+focus-filters = Filteroptionen
+focus-results = Ergebnisliste
+focus-preview = Vorschaubereich
+announce-no-results = Keine Ergebnisse
+announce-one-result = 1 Ergebnis
+announce-results = { $count } Ergebnisse
+announce-results-hidden = { $count } Ergebnisse, { $hidden } weitere ausgeblendet
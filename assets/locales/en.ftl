@@ -0,0 +1,492 @@
+# English, the reference catalog. Every key used in the code must be here;
+# other catalogs fall back to it for keys they lack.
+
+tab-search = Search View
+tab-settings = Settings
+tab-statistics = Statistics
+theme-light = Light Mode
+theme-dark = Dark Mode
+
+settings-title = Application Settings
+settings-subtitle = Configure search preferences, indexed locations, and desktop options
+settings-save = Save All Changes
+section-search = Search Configuration
+section-macros = Query Macros
+section-history = Search History
+section-profile = Profile
+section-index-dirs = Index Directories
+section-system = System & Desktop Preferences
+section-opening = Opening Files
+section-shortcuts = Keyboard Shortcuts
+section-appearance = Appearance & Theme
+section-language = Language
+section-problems = Problems
+section-activity = Activity Log
+section-data = Data Management
+section-privacy = Privacy & Local Security
+
+language-hint = Language of menus, labels and messages
+language-auto = Automatic ({ $language })
+
+profile-hint = Each profile keeps its own folders, excludes and index. Switching opens a window for the chosen profile.
+profile-active = Active profile
+profile-new-placeholder = New profile, e.g. Work
+profile-create = Create
+profile-failed = Could not switch profiles: { $error }
+unlock-title = Unlock the Index
+unlock-hint = The search index of this profile is encrypted. Enter its passphrase to open it.
+unlock-choose-title = Choose a Passphrase
+unlock-choose-hint = The index will be encrypted with this passphrase, which is asked for at every start. It can't be recovered if forgotten.
+unlock-placeholder = Passphrase
+unlock-button = Unlock
+unlock-busy = Opening the index…
+unlock-wrong = That passphrase doesn't open the index.
+unlock-needed = Enter the passphrase to continue.
+
+search-placeholder-all = Search everything (names, text, documents, code)...
+search-placeholder-text = Search file contents...
+search-placeholder-name = Search filenames...
+search-clear = Clear search
+search-button = Search
+search-searching = Searching...
+results-found = { $count } results found
+
+export-label = Export:
+export-done = Exported { $count } results to { $path }
+export-failed = Export failed: { $error }
+
+home-pinned = Pinned
+home-recent = Recently Modified
+
+drop-not-folder = Drop a folder to add it to the index
+remote-invalid = Enter a remote folder as ssh://, webdav://, webdavs:// or smb://user@host/path
+remote-unsupported = smb:// shares can only be added on Windows; mount the share and add the mounted folder
+remote-password-failed = Could not save the password in the keychain: { $error }
+drop-already-indexed = { $path } is already indexed
+drop-indexing-about = Indexing { $path } (about { $count } files)
+drop-indexing-over = Indexing { $path } (over { $count } files)
+
+estimate-running = Counting files in { $path }...
+estimate-failed = Could not look through { $path }: { $error }
+estimate-title = Index this folder?
+estimate-files = { $indexable } of { $files } files to parse, { $size }
+estimate-files-over = { $indexable } of over { $files } files to parse, { $size }
+estimate-names-only = Only file names are indexed for this folder
+estimate-too-large = { $count } files over the { $limit } MB limit are skipped
+estimate-time = Indexing takes about { $time } and adds about { $size } to the index
+estimate-confirm = Index
+estimate-cancel = Cancel
+
+volume-title = Index this drive?
+volume-found = { $label } was plugged in at { $path }
+volume-note = Only file names are indexed. They stay searchable while the drive is unplugged, marked as offline.
+volume-confirm = Index names
+volume-cancel = Not now
+
+reconnect-title = This file is offline
+reconnect-drive = It is on the removable drive { $volume }. Plug the drive in, then check again.
+reconnect-share = Its folder can't be reached right now. Reconnect the share or drive, then check again.
+reconnect-check = Check again
+reconnect-cancel = Cancel
+reconnect-done = { $path } is available again
+reconnect-still = { $path } is still offline
+
+trash-bin = Trash
+trash-bin-windows = Recycle Bin
+trash-title = Move to { $bin }?
+trash-more = …and { $count } more
+trash-note = They are removed from the index too, and indexed again if restored.
+trash-confirm = Move
+trash-cancel = Cancel
+
+open-with-title = Open { $name } with
+open-with-other = Other application…
+open-with-cancel = Cancel
+
+stats-title = Index Statistics
+stats-subtitle = What the index holds and the disk space it takes
+stats-loading = Gathering statistics...
+stats-failed = Could not read the index statistics: { $error }
+stats-refresh = Refresh
+stats-storage = Storage
+stats-documents = Documents
+stats-search-index = Search index
+stats-metadata-db = Metadata database
+stats-filename-index = Filename index
+stats-total = Total on disk
+stats-cache = Query cache
+stats-cache-ratio = { $percent }% of { $count } searches answered from the cache
+stats-cache-none = No searches yet
+stats-by-root = By Folder
+stats-by-extension = By File Type
+stats-largest = Largest Documents
+stats-no-documents = Nothing indexed yet
+stats-files = { $count } files
+stats-more = and { $count } more
+
+max-results-title = Maximum Search Results
+max-results-hint = Limits total search results returned for performance
+exclude-title = Exclude Patterns (comma separated)
+exclude-hint = Folder and file patterns to skip during indexing (e.g. *.git, target, node_modules)
+extensions-title = Custom File Extensions
+extensions-hint = Additional file formats to index as plain text (comma separated)
+extensions-placeholder = e.g. log, env, conf, sdp
+hotkey-title = Global Search Hotkey
+hotkey-hint = Keyboard shortcut to summon FindAll from anywhere
+quick-hotkey-title = Quick Search Hotkey
+quick-hotkey-hint = Opens a small always-on-top search palette; leave empty to turn it off
+
+macros-hint = Type @name in the search box to expand a saved query snippet
+macros-add = Add
+macros-label = Macros:
+
+history-enabled = Remember searches I run or open a result from
+history-empty = No searches remembered yet
+history-clear = Clear unpinned searches
+history-button = Search history
+history-dropdown-empty = Searches you run or open a result from appear here
+history-no-match = No past searches match
+history-manage = Manage history
+history-uses-one = 1 search
+history-uses = { $count } searches
+history-pin-hint = Keep this search at the top
+history-delete = Delete from history
+pin = Pin
+unpin = Unpin
+
+dirs-empty = No directories configured for indexing.
+dirs-add = Add Directory to Index
+
+system-tray = Minimize to system tray on window close
+system-autostart = Start FindAll automatically when system starts
+system-context-menu = Add 'Search with FindAll' to Windows right-click context menu
+system-gitignore = Respect .gitignore rules when scanning repository folders
+system-mft = Read whole NTFS drives straight from the MFT for fast filename indexing (needs administrator rights)
+system-code-aware = Match getUserName and get_user_name alike in source code (applies to newly indexed files)
+
+opening-editor-title = Editor for text and code
+opening-editor-hint = Opens results at the matching line; {file} and {line} are filled in
+opening-pdf-title = PDF viewer
+opening-pdf-hint = Opens PDFs at the matching page; {file} and {page} are filled in
+
+shortcuts-hint = Arrow keys move through results and F3 steps through preview matches; leave a shortcut empty to turn it off
+shortcuts-invalid = Not a valid shortcut
+shortcuts-off = Off
+
+appearance-theme-title = Color Theme
+appearance-theme-hint = Switch between Windows 11 Dark mode and Light mode
+appearance-dark = Dark Theme
+
+problems-none = No files were skipped during the last scans.
+problems-failed = Failed
+problems-timeout = Timed out
+problems-crashed = Crashed
+problems-summary = { $count } files could not be parsed and were skipped. They are retried automatically once they change on disk.
+problems-retry = Retry All on Next Scan
+
+schedule-title = Scheduled Refresh
+schedule-hint = Runs a refresh in the background: off, daily HH:MM (e.g. daily 02:00) or every Nh (e.g. every 6h).
+schedule-invalid = Not a valid schedule
+schedule-never = never
+schedule-none = not scheduled
+schedule-summary = Last run: { $last } · Next run: { $next }
+refresh-title = Refresh Index
+refresh-hint = Re-indexes only folders whose contents changed since the last refresh and drops deleted files.
+refresh-button = Refresh
+rebuild-title = Force Complete Index Rebuild
+rebuild-hint = Clears all cached search index data and performs a fresh scan across all configured directories.
+rebuild-button = Full Rebuild
+
+privacy-local = FindAll operates 100% locally. Zero data or search queries ever leave your machine.
+privacy-data-path = Local App Data Path:
+privacy-index-path = Tantivy Search Index:
+privacy-db-path = Redb KV Store:
+privacy-unknown = Unknown
+
+db-corrupted = Metadata database was corrupted and has been reset. Full re-index recommended.
+banner-dismiss = Dismiss
+search-error = Error: { $error }
+quick-placeholder = Search files...
+quick-more = { $count } more in the full window
+quick-footer = Enter to open, Esc to close
+app-tagline = Instant Local Search
+match-case-hint = Match case
+match-word-hint = Match whole word
+mode-all = All
+mode-text = Text
+mode-file = File
+theme-switch-light = Switch to light theme
+theme-switch-dark = Switch to dark theme
+open-settings = Open settings
+
+filters-active = Active Filters:
+filters-show = Show filter options
+filters-hide = Hide filter options
+filters-title = Filter Options
+filters-reset = Reset All Filters
+filter-extension = File Extension
+filter-size = Size Range
+filter-size-min = Min
+filter-size-max = Max
+filter-date = Last Modified
+date-anytime = Anytime
+date-today = Today
+date-week = Past Week
+date-month = Past Month
+filter-scope = Search Scope
+scope-all = All
+scope-text = Full Text
+scope-name = Filename
+filter-flags = Match Flags
+flag-case = Match Case
+flag-word = Whole Word
+flag-best = Best Matches Only
+flag-full-path = Match Full Path
+results-show-all = Show all { $total } results ({ $hidden } lower-confidence matches hidden)
+
+menu-open = Open
+menu-open-with = Open with…
+menu-open-folder = Open containing folder
+menu-copy-path = Copy path
+menu-copy-picked = Copy { $count } picked items
+menu-copy-folder = Copy folder
+menu-copy-file = Copy file
+menu-copy-text = Copy text
+menu-copy-matched = Copy matched text
+menu-pin = Pin to home
+menu-reindex = Re-index now
+menu-remove = Remove from index
+menu-trash-picked = Move { $count } picked items to { $bin }
+menu-trash = Move to { $bin }
+menu-exclude-folder = Exclude this folder from indexing
+menu-exclude-file = Exclude this file from indexing
+menu-exclude-parent = Exclude containing folder
+menu-exclude-ext = Exclude all .{ $ext } files
+
+column-name = Name
+column-folder = Folder
+column-ext = Ext
+column-size = Size
+column-modified = Modified
+column-score = Score
+group-no-folder = (no folder)
+group-by-folder = Group by Folder
+sort-title = Sort & View
+sort-relevance = Relevance Score
+sort-date = Date Modified
+sort-size = File Size
+sort-name = File Name
+layout-cards = Cards
+layout-table = Table
+categories = Categories
+
+welcome-title = FindAll Instant Search
+welcome-subtitle = Ultrafast local text, document, and filename search engine
+welcome-shortcuts = Keyboard Shortcuts
+shortcut-global = Global Search Window
+shortcut-focus = Focus Search Input
+shortcut-navigate = Navigate Results
+shortcut-open = Open Selected File
+shortcut-open-folder = Open Containing Folder
+shortcut-copy-path = Copy File Path
+welcome-features = Pro Search Capabilities
+tip-scope-title = Full Text vs Filename
+tip-scope = Toggle search scope in top bar
+tip-extension-title = Extension Filter
+tip-extension = Filter PDF, MD, RS, TXT, Code in sidebar
+tip-exact-title = Exact & Case Match
+tip-exact = Use 'Aa' and 'W' flags for precision
+tip-preview-title = Instant Document Preview
+tip-preview = Inspect text snippets & tables live
+welcome-status = Index Status: { $count } files indexed ({ $size })
+
+no-results-title = No matching results found
+no-results-hint = Try adjusting your query or expanding search filters
+no-results-tips = Troubleshooting Suggestions:
+no-results-tip-spelling = • Check spelling or try simpler keywords
+no-results-tip-mode = • Switch between Full Text and Filename search modes
+no-results-tip-filters = • Clear active file extension filters in the left sidebar
+result-open = Open
+result-folder = Folder
+unknown-size = Unknown size
+unknown-date = Unknown date
+
+preview-loading = Loading document contents...
+preview-select = Select a search result to preview
+preview-hint = Snippets and document preview will appear here
+preview-title = Document Preview
+preview-match = Match { $current } of { $total }
+preview-previous = Previous match (Shift+F3)
+preview-next = Next match (F3)
+preview-snippets = Matching Snippets
+preview-content = Document Content
+preview-elements = { $count } structural elements parsed
+preview-page = Page 1 of { $pages }
+preview-binary = Binary file ({ $mime }), first { $shown } of { $size }
+preview-window-start = Long document: showing { $size } around the start ({ $start } to { $end } of { $total })
+preview-window-match = Long document: showing { $size } around the first match ({ $start } to { $end } of { $total })
+owner-line = Owner: { $owner }
+read-only = Read-only
+hidden = Hidden
+
+details-title = Details
+details-not-indexed = Not indexed
+details-current = Up to date, indexed { $date }
+details-changed = Changed on disk since it was indexed { $date }
+details-size = Size
+details-modified = Modified
+details-created = Created
+details-hash = Content hash
+details-parser = Parser
+details-index = Index
+notes-title = Notes
+notes-placeholder = Add a note, e.g. sent to accountant
+notes-attributes = Attributes, e.g. client: Acme, year: 2025
+notes-save = Save
+
+watcher-unwatched = Not watched: { $folders }
+watcher-error = Last error: { $error }
+watcher-events = { $count } changes picked up
+status-files = { $count } files indexed
+eta-hours = ETA: { $hours }h { $minutes }m
+eta-minutes = ETA: { $minutes }m { $seconds }s
+eta-seconds = ETA: { $seconds }s
+indexing-resume = Resume
+indexing-pause = Pause
+indexing-cancel = Cancel
+indexing-resume-previous = Resume previous indexing ({ $files } files done)
+
+root-content = Index file contents
+root-symlinks = Follow symlinks
+root-separate = Separate index
+root-depth = Max depth
+root-include = Include only, e.g. *.rs, *.md
+root-exclude = Also exclude, e.g. *.bak
+
+sensitive-title = Sensitive Files
+sensitive-hint = Keys, password stores and secrets matching these patterns are never read; patterns with a / match whole paths
+sensitive-names-only = Names only
+sensitive-skip = Skip entirely
+sensitive-index = Index normally
+
+encryption-title = Index Encryption
+encryption-hint = Encrypts the search index, metadata and file names on disk. Takes effect at the next start, which converts the existing index
+encryption-off = Off
+encryption-keychain = Key in OS keychain
+encryption-passphrase = Passphrase
+
+activity-enabled = Record what is indexed, skipped and removed, and the searches I run
+activity-note = Kept on this computer in the data folder and rotated at a few megabytes; entries are encrypted with the index when index encryption is on
+activity-filter = Filter by path, query or event, e.g. skipped
+activity-empty = Nothing recorded yet
+activity-clear = Clear activity log
+
+root-hidden = Hidden files
+root-system = System files
+
+root-elevated = Read files I have no access to (needs administrator)
+
+open-failed = Failed to open the file: { $error }
+
+openers-hint = Commands for one extension, used instead of the default app or the editor. {} or {file} is the path, {line} and {page} where the search matched; a URL opens in the app handling it
+openers-add = Add
+
+reindex-done = Re-indexed { $count } file(s)
+
+result-changed = Changed · Re-index
+changed-detail = Changed since indexed
+
+query-error = { $message } (column { $column })
+query-try = Try:
+
+did-you-mean = Did you mean:
+
+similar-title = Similar files
+
+menu-compare = Compare contents
+compare-running = Comparing contents…
+compare-failed = Failed to compare: { $error }
+diff-summary = { $removed } line(s) removed, { $added } added
+diff-identical = The parsed contents are identical
+diff-skipped = … { $count } unchanged line(s) …
+
+root-removable = On a removable drive; its files stay listed as offline while it is unplugged
+volume-offer = Offer to index file names on removable drives when they are plugged in
+
+result-offline = Offline · Reconnect
+offline-detail = Offline (drive unplugged or share disconnected)
+
+cloud-title = Online-only Cloud Files
+cloud-hint = OneDrive, Dropbox, Google Drive and iCloud files that aren't downloaded are indexed by name only, since reading them downloads them. List folders whose files may be downloaded to index their content
+cloud-placeholder = e.g. C:\Users\me\OneDrive\Documents
+
+remote-add = Add Remote Folder
+
+root-rescan = Rescan
+root-rescan-global = Global schedule
+remote-placeholder = Remote folder, e.g. ssh://user@host/docs or smb://user@server/share
+remote-password = Password (optional)
+remote-hint = Supports ssh://, webdav://, webdavs:// and smb://. Passwords are kept in the OS keychain; SSH servers must be in ~/.ssh/known_hosts and also accept your agent or keys
+
+recent-load-failed = Failed to load recent files: { $error }
+history-update-failed = Failed to update search history: { $error }
+settings-reloaded = Settings reloaded from disk
+settings-reload-failed = Could not reload settings: { $error }
+preview-failed = Preview error: { $error }
+details-failed = Failed to read file details: { $error }
+note-saved = Note saved
+note-save-failed = Failed to save note: { $error }
+text-copied = Copied { $count } characters of text
+text-copy-failed = Failed to copy the text: { $error }
+files-copy-failed = Failed to copy files: { $error }
+open-with-app-failed = Could not open with { $app }: { $error }
+open-with-failed = Open with failed: { $error }
+reindex-failed = Failed to re-index: { $error }
+index-removed = Removed from index
+index-remove-failed = Failed to remove from index: { $error }
+trash-moved-one = Moved to the trash
+trash-moved = Moved { $count } items to the trash
+trash-failed = Failed to move to the trash: { $error }
+exclude-done = Excluded from indexing, { $count } file(s) removed
+exclude-failed = Failed to exclude: { $error }
+status-rebuilding = Rebuilding index...
+status-refreshing = Refreshing index...
+status-paused = Paused
+status-resuming = Resuming...
+status-resuming-interrupted = Resuming interrupted scan...
+status-cancelling = Cancelling...
+pin-failed = Failed to pin file: { $error }
+unpin-failed = Failed to unpin file: { $error }
+folder-picker-title = Select Folder to Index
+live-partial = Live updates: { $watched } of { $total } folders
+live-pending = Live · { $count } pending
+live = Live
+
+action-open = Open selected result
+action-reveal = Show in folder
+action-copy-path = Copy path
+action-clear = Close, clear or hide
+action-next-pane = Next pane
+action-previous-pane = Previous pane
+
+category-documents = Documents
+category-spreadsheets = Spreadsheets
+category-presentations = Presentations
+category-code = Source Code
+category-data = Data
+category-images = Images
+category-audio = Audio
+category-video = Video
+category-archives = Archives
+category-email = Email
+category-ebooks = E-books
+category-other = Other
+
+focus-search = Search box
+focus-filters = Filter options
+focus-results = Results list
+focus-preview = Preview panel
+announce-no-results = No results
+announce-one-result = 1 result
+announce-results = { $count } results
+announce-results-hidden = { $count } results, { $hidden } more hidden
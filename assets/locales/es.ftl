@@ -0,0 +1,489 @@
+tab-search = Búsqueda
+tab-settings = Configuración
+tab-statistics = Estadísticas
+theme-light = Modo claro
+theme-dark = Modo oscuro
+
+settings-title = Configuración de la aplicación
+settings-subtitle = Configura las preferencias de búsqueda, las ubicaciones indexadas y las opciones de escritorio
+settings-save = Guardar todos los cambios
+section-search = Configuración de búsqueda
+section-macros = Macros de consulta
+section-history = Historial de búsqueda
+section-profile = Perfil
+section-index-dirs = Carpetas indexadas
+section-system = Preferencias del sistema y del escritorio
+section-opening = Abrir archivos
+section-shortcuts = Atajos de teclado
+section-appearance = Apariencia y tema
+section-language = Idioma
+section-problems = Problemas
+section-activity = Registro de actividad
+section-data = Gestión de datos
+section-privacy = Privacidad y seguridad local
+
+language-hint = Idioma de los menús, etiquetas y mensajes
+language-auto = Automático ({ $language })
+
+profile-hint = Cada perfil tiene sus propias carpetas, exclusiones e índice. Al cambiar se abre una ventana para el perfil elegido.
+profile-active = Perfil activo
+profile-new-placeholder = Perfil nuevo, p. ej. Trabajo
+profile-create = Crear
+profile-failed = No se pudo cambiar de perfil: { $error }
+unlock-title = Desbloquear el índice
+unlock-hint = El índice de búsqueda de este perfil está cifrado. Introduce su frase de contraseña para abrirlo.
+unlock-choose-title = Elige una frase de contraseña
+unlock-choose-hint = El índice se cifrará con esta frase de contraseña, que se pedirá en cada inicio. Si la olvidas, no se puede recuperar.
+unlock-placeholder = Frase de contraseña
+unlock-button = Desbloquear
+unlock-busy = Abriendo el índice…
+unlock-wrong = Esa frase de contraseña no abre el índice.
+unlock-needed = Introduce la frase de contraseña para continuar.
+
+search-placeholder-all = Buscar en todo (nombres, texto, documentos, código)...
+search-placeholder-text = Buscar en el contenido de los archivos...
+search-placeholder-name = Buscar nombres de archivo...
+search-clear = Borrar búsqueda
+search-button = Buscar
+search-searching = Buscando...
+results-found = { $count } resultados encontrados
+
+export-label = Exportar:
+export-done = { $count } resultados exportados a { $path }
+export-failed = Error al exportar: { $error }
+
+home-pinned = Fijados
+home-recent = Modificados recientemente
+
+drop-not-folder = Suelta una carpeta para añadirla al índice
+remote-invalid = Escribe una carpeta remota como ssh://, webdav://, webdavs:// o smb://usuario@host/ruta
+remote-unsupported = Los recursos smb:// solo se pueden añadir en Windows; monta el recurso y añade la carpeta montada
+remote-password-failed = No se pudo guardar la contraseña en el llavero: { $error }
+drop-already-indexed = { $path } ya está indexada
+drop-indexing-about = Indexando { $path } (unos { $count } archivos)
+drop-indexing-over = Indexando { $path } (más de { $count } archivos)
+
+estimate-running = Contando archivos en { $path }...
+estimate-failed = No se pudo recorrer { $path }: { $error }
+estimate-title = ¿Indexar esta carpeta?
+estimate-files = { $indexable } de { $files } archivos para leer, { $size }
+estimate-files-over = { $indexable } de más de { $files } archivos para leer, { $size }
+estimate-names-only = En esta carpeta solo se indexan los nombres de archivo
+estimate-too-large = Se omiten { $count } archivos por encima del límite de { $limit } MB
+estimate-time = La indexación tarda unos { $time } y añade unos { $size } al índice
+estimate-confirm = Indexar
+estimate-cancel = Cancelar
+
+volume-title = ¿Indexar esta unidad?
+volume-found = Se conectó { $label } en { $path }
+volume-note = Solo se indexan los nombres de archivo. Se pueden seguir buscando mientras la unidad está desconectada, marcados como sin conexión.
+volume-confirm = Indexar nombres
+volume-cancel = Ahora no
+
+reconnect-title = Este archivo no está disponible
+reconnect-drive = Está en la unidad extraíble { $volume }. Conecte la unidad y vuelva a comprobarlo.
+reconnect-share = No se puede acceder a su carpeta ahora mismo. Vuelva a conectar el recurso compartido o la unidad y compruébelo de nuevo.
+reconnect-check = Comprobar de nuevo
+reconnect-cancel = Cancelar
+reconnect-done = { $path } vuelve a estar disponible
+reconnect-still = { $path } sigue sin conexión
+
+trash-bin = Papelera
+trash-bin-windows = Papelera de reciclaje
+trash-title = ¿Mover a la { $bin }?
+trash-more = …y { $count } más
+trash-note = También se quitan del índice y se vuelven a indexar si se restauran.
+trash-confirm = Mover
+trash-cancel = Cancelar
+
+open-with-title = Abrir { $name } con
+open-with-other = Otra aplicación…
+open-with-cancel = Cancelar
+
+stats-title = Estadísticas del índice
+stats-subtitle = Qué contiene el índice y cuánto espacio ocupa
+stats-loading = Recopilando estadísticas...
+stats-failed = No se pudieron leer las estadísticas del índice: { $error }
+stats-refresh = Actualizar
+stats-storage = Almacenamiento
+stats-documents = Documentos
+stats-search-index = Índice de búsqueda
+stats-metadata-db = Base de datos de metadatos
+stats-filename-index = Índice de nombres de archivo
+stats-total = Total en disco
+stats-cache = Caché de consultas
+stats-cache-ratio = { $percent } % de { $count } búsquedas respondidas desde la caché
+stats-cache-none = Aún no hay búsquedas
+stats-by-root = Por carpeta
+stats-by-extension = Por tipo de archivo
+stats-largest = Documentos más grandes
+stats-no-documents = Aún no hay nada indexado
+stats-files = { $count } archivos
+stats-more = y { $count } más
+
+max-results-title = Máximo de resultados
+max-results-hint = Limita el total de resultados devueltos para mejorar el rendimiento
+exclude-title = Patrones de exclusión (separados por comas)
+exclude-hint = Patrones de carpetas y archivos que se omiten al indexar (p. ej. *.git, target, node_modules)
+extensions-title = Extensiones personalizadas
+extensions-hint = Formatos adicionales que se indexan como texto sin formato (separados por comas)
+extensions-placeholder = p. ej. log, env, conf, sdp
+hotkey-title = Atajo global de búsqueda
+hotkey-hint = Atajo de teclado para abrir FindAll desde cualquier lugar
+quick-hotkey-title = Atajo de búsqueda rápida
+quick-hotkey-hint = Abre una pequeña paleta de búsqueda siempre visible; déjalo vacío para desactivarla
+
+macros-hint = Escribe @nombre en el cuadro de búsqueda para expandir un fragmento de consulta guardado
+macros-add = Añadir
+macros-label = Macros:
+
+history-enabled = Recordar las búsquedas que ejecuto o de las que abro un resultado
+history-empty = Aún no hay búsquedas recordadas
+history-clear = Borrar las búsquedas no fijadas
+history-button = Historial de búsqueda
+history-dropdown-empty = Aquí aparecen las búsquedas que ejecutas o de las que abres un resultado
+history-no-match = Ninguna búsqueda anterior coincide
+history-manage = Gestionar el historial
+history-uses-one = 1 búsqueda
+history-uses = { $count } búsquedas
+history-pin-hint = Mantener esta búsqueda arriba
+history-delete = Eliminar del historial
+pin = Fijar
+unpin = Desfijar
+
+dirs-empty = No hay carpetas configuradas para indexar.
+dirs-add = Añadir carpeta al índice
+
+system-tray = Minimizar a la bandeja del sistema al cerrar la ventana
+system-autostart = Iniciar FindAll automáticamente al arrancar el sistema
+system-context-menu = Añadir «Buscar con FindAll» al menú contextual de Windows
+system-gitignore = Respetar las reglas de .gitignore al explorar carpetas de repositorios
+system-mft = Leer unidades NTFS completas directamente de la MFT para indexar nombres rápidamente (requiere derechos de administrador)
+system-code-aware = Tratar igual getUserName y get_user_name en el código fuente (se aplica a archivos indexados de nuevo)
+
+opening-editor-title = Editor de texto y código
+opening-editor-hint = Abre los resultados en la línea coincidente; se rellenan {file} y {line}
+opening-pdf-title = Visor de PDF
+opening-pdf-hint = Abre los PDF en la página coincidente; se rellenan {file} y {page}
+
+shortcuts-hint = Las flechas recorren los resultados y F3 las coincidencias de la vista previa; deja un atajo vacío para desactivarlo
+shortcuts-invalid = No es un atajo válido
+shortcuts-off = Desactivado
+
+appearance-theme-title = Tema de color
+appearance-theme-hint = Cambiar entre el modo oscuro y el claro de Windows 11
+appearance-dark = Tema oscuro
+
+problems-none = No se omitió ningún archivo en los últimos análisis.
+problems-failed = Error
+problems-timeout = Tiempo agotado
+problems-crashed = Bloqueo
+problems-summary = No se pudieron analizar { $count } archivos y se omitieron. Se reintentan automáticamente cuando cambien en el disco.
+problems-retry = Reintentar todos en el próximo análisis
+
+schedule-title = Actualización programada
+schedule-hint = Actualiza en segundo plano: off, daily HH:MM (p. ej. daily 02:00) o every Nh (p. ej. every 6h).
+schedule-invalid = No es una programación válida
+schedule-never = nunca
+schedule-none = sin programar
+schedule-summary = Última ejecución: { $last } · Próxima: { $next }
+refresh-title = Actualizar el índice
+refresh-hint = Vuelve a indexar solo las carpetas cuyo contenido cambió desde la última actualización y quita los archivos eliminados.
+refresh-button = Actualizar
+rebuild-title = Forzar la reconstrucción completa del índice
+rebuild-hint = Borra todos los datos del índice en caché y vuelve a explorar todas las carpetas configuradas.
+rebuild-button = Reconstruir todo
+
+privacy-local = FindAll funciona 100 % en local. Ningún dato ni búsqueda sale nunca de tu equipo.
+privacy-data-path = Carpeta de datos local:
+privacy-index-path = Índice de búsqueda Tantivy:
+privacy-db-path = Almacén Redb:
+privacy-unknown = Desconocida
+
+db-corrupted = La base de metadatos estaba dañada y se ha restablecido. Se recomienda reindexar todo.
+banner-dismiss = Cerrar
+search-error = Error: { $error }
+quick-placeholder = Buscar archivos...
+quick-more = { $count } más en la ventana completa
+quick-footer = Intro para abrir, Esc para cerrar
+app-tagline = Búsqueda local instantánea
+match-case-hint = Coincidir mayúsculas
+match-word-hint = Solo palabras completas
+mode-all = Todo
+mode-text = Texto
+mode-file = Archivo
+theme-switch-light = Cambiar al tema claro
+theme-switch-dark = Cambiar al tema oscuro
+open-settings = Abrir la configuración
+
+filters-active = Filtros activos:
+filters-show = Mostrar opciones de filtro
+filters-hide = Ocultar opciones de filtro
+filters-title = Opciones de filtro
+filters-reset = Restablecer todos los filtros
+filter-extension = Extensión
+filter-size = Rango de tamaño
+filter-size-min = Mín.
+filter-size-max = Máx.
+filter-date = Última modificación
+date-anytime = Cualquier fecha
+date-today = Hoy
+date-week = Última semana
+date-month = Último mes
+filter-scope = Ámbito de búsqueda
+scope-all = Todo
+scope-text = Texto completo
+scope-name = Nombre de archivo
+filter-flags = Opciones de coincidencia
+flag-case = Mayúsculas y minúsculas
+flag-word = Palabra completa
+flag-best = Solo las mejores coincidencias
+flag-full-path = Buscar en la ruta completa
+results-show-all = Mostrar los { $total } resultados ({ $hidden } coincidencias menos fiables ocultas)
+
+menu-open = Abrir
+menu-open-with = Abrir con…
+menu-open-folder = Abrir la carpeta contenedora
+menu-copy-path = Copiar la ruta
+menu-copy-picked = Copiar { $count } elementos elegidos
+menu-copy-folder = Copiar la carpeta
+menu-copy-file = Copiar el archivo
+menu-copy-text = Copiar el texto
+menu-copy-matched = Copiar el texto coincidente
+menu-pin = Fijar en el inicio
+menu-reindex = Reindexar ahora
+menu-remove = Quitar del índice
+menu-trash-picked = Mover { $count } elementos elegidos a la { $bin }
+menu-trash = Mover a la { $bin }
+menu-exclude-folder = Excluir esta carpeta del índice
+menu-exclude-file = Excluir este archivo del índice
+menu-exclude-parent = Excluir la carpeta contenedora
+menu-exclude-ext = Excluir todos los archivos .{ $ext }
+
+column-name = Nombre
+column-folder = Carpeta
+column-ext = Ext.
+column-size = Tamaño
+column-modified = Modificado
+column-score = Puntuación
+group-no-folder = (sin carpeta)
+group-by-folder = Agrupar por carpeta
+sort-title = Orden y vista
+sort-relevance = Relevancia
+sort-date = Fecha de modificación
+sort-size = Tamaño
+sort-name = Nombre
+layout-cards = Tarjetas
+layout-table = Tabla
+categories = Categorías
+
+welcome-title = Búsqueda instantánea FindAll
+welcome-subtitle = Buscador local ultrarrápido de texto, documentos y nombres de archivo
+welcome-shortcuts = Atajos de teclado
+shortcut-global = Ventana de búsqueda global
+shortcut-focus = Ir al cuadro de búsqueda
+shortcut-navigate = Recorrer los resultados
+shortcut-open = Abrir el archivo seleccionado
+shortcut-open-folder = Abrir la carpeta contenedora
+shortcut-copy-path = Copiar la ruta del archivo
+welcome-features = Funciones de búsqueda avanzadas
+tip-scope-title = Texto completo o nombre
+tip-scope = Cambia el ámbito de búsqueda en la barra superior
+tip-extension-title = Filtro de extensión
+tip-extension = Filtra PDF, MD, RS, TXT y código en la barra lateral
+tip-exact-title = Coincidencia exacta y de mayúsculas
+tip-exact = Usa «Aa» y «W» para afinar
+tip-preview-title = Vista previa instantánea
+tip-preview = Examina fragmentos de texto y tablas al instante
+welcome-status = Estado del índice: { $count } archivos indexados ({ $size })
+
+no-results-title = No se encontraron resultados
+no-results-hint = Prueba a ajustar la consulta o ampliar los filtros
+no-results-tips = Sugerencias:
+no-results-tip-spelling = • Revisa la ortografía o prueba palabras más sencillas
+no-results-tip-mode = • Cambia entre la búsqueda de texto completo y de nombres
+no-results-tip-filters = • Quita los filtros de extensión activos en la barra lateral izquierda
+result-open = Abrir
+result-folder = Carpeta
+unknown-size = Tamaño desconocido
+unknown-date = Fecha desconocida
+
+preview-loading = Cargando el contenido del documento...
+preview-select = Selecciona un resultado para verlo
+preview-hint = Aquí aparecerán los fragmentos y la vista previa
+preview-title = Vista previa del documento
+preview-match = Coincidencia { $current } de { $total }
+preview-previous = Coincidencia anterior (Mayús+F3)
+preview-next = Coincidencia siguiente (F3)
+preview-snippets = Fragmentos coincidentes
+preview-content = Contenido del documento
+preview-elements = { $count } elementos estructurales analizados
+preview-page = Página 1 de { $pages }
+preview-binary = Archivo binario ({ $mime }), primeros { $shown } de { $size }
+preview-window-start = Documento largo: se muestran { $size } en torno al inicio ({ $start } a { $end } de { $total })
+preview-window-match = Documento largo: se muestran { $size } en torno a la primera coincidencia ({ $start } a { $end } de { $total })
+owner-line = Propietario: { $owner }
+read-only = Solo lectura
+hidden = Oculto
+
+details-title = Detalles
+details-not-indexed = Sin indexar
+details-current = Al día, indexado el { $date }
+details-changed = Cambiado en el disco desde que se indexó el { $date }
+details-size = Tamaño
+details-modified = Modificado
+details-created = Creado
+details-hash = Hash del contenido
+details-parser = Analizador
+details-index = Índice
+notes-title = Notas
+notes-placeholder = Añade una nota, p. ej. enviado al gestor
+notes-attributes = Atributos, p. ej. client: Acme, year: 2025
+notes-save = Guardar
+
+watcher-unwatched = Sin vigilar: { $folders }
+watcher-error = Último error: { $error }
+watcher-events = { $count } cambios detectados
+status-files = { $count } archivos indexados
+eta-hours = Restante: { $hours } h { $minutes } min
+eta-minutes = Restante: { $minutes } min { $seconds } s
+eta-seconds = Restante: { $seconds } s
+indexing-resume = Reanudar
+indexing-pause = Pausar
+indexing-cancel = Cancelar
+indexing-resume-previous = Reanudar la indexación anterior ({ $files } archivos hechos)
+
+root-content = Indexar el contenido
+root-symlinks = Seguir enlaces simbólicos
+root-separate = Índice aparte
+root-depth = Profundidad máx.
+root-include = Incluir solo, p. ej. *.rs, *.md
+root-exclude = Excluir también, p. ej. *.bak
+
+sensitive-title = Archivos sensibles
+sensitive-hint = Las claves, almacenes de contraseñas y secretos que coinciden con estos patrones nunca se leen; los patrones con / se aplican a rutas completas
+sensitive-names-only = Solo nombres
+sensitive-skip = Omitir por completo
+sensitive-index = Indexar normalmente
+
+encryption-title = Cifrado del índice
+encryption-hint = Cifra en el disco el índice de búsqueda, los metadatos y los nombres de archivo. Se aplica en el próximo inicio, que convierte el índice existente
+encryption-off = Desactivado
+encryption-keychain = Clave en el llavero del sistema
+encryption-passphrase = Frase de contraseña
+
+activity-enabled = Registrar lo que se indexa, se omite y se quita, y las búsquedas que ejecuto
+activity-note = Se guarda en este equipo, en la carpeta de datos, y se rota a partir de unos pocos megabytes; con el cifrado del índice activado, las entradas se cifran con el índice
+activity-filter = Filtrar por ruta, búsqueda o evento, p. ej. skipped
+activity-empty = Aún no hay nada registrado
+activity-clear = Borrar el registro de actividad
+
+root-hidden = Archivos ocultos
+root-system = Archivos de sistema
+
+root-elevated = Leer archivos a los que no tengo acceso (requiere administrador)
+
+open-failed = No se pudo abrir el archivo: { $error }
+
+openers-hint = Comandos para una extensión, usados en lugar de la aplicación predeterminada o del editor. {} o {file} es la ruta, {line} y {page} donde coincidió la búsqueda; una URL se abre en la aplicación que la gestiona
+openers-add = Añadir
+
+reindex-done = { $count } archivo(s) reindexado(s)
+
+result-changed = Modificado · Reindexar
+changed-detail = Modificado desde que se indexó
+
+query-error = { $message } (columna { $column })
+query-try = Prueba:
+
+did-you-mean = Quizás quisiste decir:
+
+similar-title = Archivos similares
+
+menu-compare = Comparar el contenido
+compare-running = Comparando el contenido…
+compare-failed = No se pudo comparar: { $error }
+diff-summary = { $removed } línea(s) eliminada(s), { $added } añadida(s)
+diff-identical = El contenido analizado es idéntico
+diff-skipped = … { $count } línea(s) sin cambios …
+
+root-removable = En una unidad extraíble; sus archivos siguen apareciendo como sin conexión mientras está desconectada
+volume-offer = Ofrecer indexar los nombres de archivo de las unidades extraíbles al conectarlas
+
+result-offline = Sin conexión · Reconectar
+offline-detail = Sin conexión (unidad desconectada o recurso compartido no disponible)
+
+cloud-title = Archivos de la nube solo en línea
+cloud-hint = Los archivos de OneDrive, Dropbox, Google Drive e iCloud que no están descargados se indexan solo por nombre, porque leerlos los descarga. Indica las carpetas cuyos archivos se pueden descargar para indexar su contenido
+cloud-placeholder = p. ej. C:\Users\yo\OneDrive\Documentos
+
+remote-add = Añadir carpeta remota
+
+root-rescan = Reexplorar
+root-rescan-global = Programación global
+remote-placeholder = Carpeta remota, p. ej. ssh://user@host/docs o smb://user@server/share
+remote-password = Contraseña (opcional)
+remote-hint = Admite ssh://, webdav://, webdavs:// y smb://. Las contraseñas se guardan en el llavero del sistema; los servidores SSH deben estar en ~/.ssh/known_hosts y aceptar también tu agente o tus claves
+
+recent-load-failed = No se pudieron cargar los archivos recientes: { $error }
+history-update-failed = No se pudo actualizar el historial de búsqueda: { $error }
+settings-reloaded = Configuración recargada desde el disco
+settings-reload-failed = No se pudo recargar la configuración: { $error }
+preview-failed = Error de vista previa: { $error }
+details-failed = No se pudieron leer los detalles del archivo: { $error }
+note-saved = Nota guardada
+note-save-failed = No se pudo guardar la nota: { $error }
+text-copied = Se copiaron { $count } caracteres de texto
+text-copy-failed = No se pudo copiar el texto: { $error }
+files-copy-failed = No se pudieron copiar los archivos: { $error }
+open-with-app-failed = No se pudo abrir con { $app }: { $error }
+open-with-failed = Error al abrir con: { $error }
+reindex-failed = No se pudo reindexar: { $error }
+index-removed = Eliminado del índice
+index-remove-failed = No se pudo eliminar del índice: { $error }
+trash-moved-one = Movido a la papelera
+trash-moved = Se movieron { $count } elementos a la papelera
+trash-failed = No se pudo mover a la papelera: { $error }
+exclude-done = Excluido de la indexación, { $count } archivo(s) eliminado(s)
+exclude-failed = No se pudo excluir: { $error }
+status-rebuilding = Reconstruyendo el índice...
+status-refreshing = Actualizando el índice...
+status-paused = En pausa
+status-resuming = Reanudando...
+status-resuming-interrupted = Reanudando el análisis interrumpido...
+status-cancelling = Cancelando...
+pin-failed = No se pudo fijar el archivo: { $error }
+unpin-failed = No se pudo desfijar el archivo: { $error }
+folder-picker-title = Seleccionar carpeta para indexar
+live-partial = Actualizaciones en vivo: { $watched } de { $total } carpetas
+live-pending = En vivo · { $count } pendientes
+live = En vivo
+
+action-open = Abrir el resultado seleccionado
+action-reveal = Mostrar en la carpeta
+action-copy-path = Copiar ruta
+action-clear = Cerrar, borrar u ocultar
+action-next-pane = Panel siguiente
+action-previous-pane = Panel anterior
+
+category-documents = Documentos
+category-spreadsheets = Hojas de cálculo
+category-presentations = Presentaciones
+category-code = Código fuente
+category-data = Datos
+category-images = Imágenes
+category-audio = Audio
+category-video = Vídeo
+category-archives = Archivos comprimidos
+category-email = Correo
+category-ebooks = Libros electrónicos
+category-other = Otros
+
+focus-search = Cuadro de búsqueda
+focus-filters = Opciones de filtro
+focus-results = Lista de resultados
+focus-preview = Panel de vista previa
+announce-no-results = Sin resultados
+announce-one-result = 1 resultado
+announce-results = { $count } resultados
+announce-results-hidden = { $count } resultados, { $hidden } más ocultos
@@ -0,0 +1,489 @@
+tab-search = Recherche
+tab-settings = Paramètres
+tab-statistics = Statistiques
+theme-light = Mode clair
+theme-dark = Mode sombre
+
+settings-title = Paramètres de l'application
+settings-subtitle = Configurer les préférences de recherche, les emplacements indexés et les options du bureau
+settings-save = Enregistrer toutes les modifications
+section-search = Configuration de la recherche
+section-macros = Macros de requête
+section-history = Historique de recherche
+section-profile = Profil
+section-index-dirs = Dossiers indexés
+section-system = Préférences système et bureau
+section-opening = Ouverture des fichiers
+section-shortcuts = Raccourcis clavier
+section-appearance = Apparence et thème
+section-language = Langue
+section-problems = Problèmes
+section-activity = Journal d'activité
+section-data = Gestion des données
+section-privacy = Confidentialité et sécurité locale
+
+language-hint = Langue des menus, libellés et messages
+language-auto = Automatique ({ $language })
+
+profile-hint = Chaque profil a ses propres dossiers, exclusions et index. Changer de profil ouvre une fenêtre pour le profil choisi.
+profile-active = Profil actif
+profile-new-placeholder = Nouveau profil, p. ex. Travail
+profile-create = Créer
+profile-failed = Impossible de changer de profil : { $error }
+unlock-title = Déverrouiller l'index
+unlock-hint = L'index de recherche de ce profil est chiffré. Saisissez sa phrase secrète pour l'ouvrir.
+unlock-choose-title = Choisir une phrase secrète
+unlock-choose-hint = L'index sera chiffré avec cette phrase secrète, demandée à chaque démarrage. Elle ne peut pas être récupérée en cas d'oubli.
+unlock-placeholder = Phrase secrète
+unlock-button = Déverrouiller
+unlock-busy = Ouverture de l'index…
+unlock-wrong = Cette phrase secrète n'ouvre pas l'index.
+unlock-needed = Saisissez la phrase secrète pour continuer.
+
+search-placeholder-all = Tout rechercher (noms, texte, documents, code)...
+search-placeholder-text = Rechercher dans le contenu des fichiers...
+search-placeholder-name = Rechercher des noms de fichiers...
+search-clear = Effacer la recherche
+search-button = Rechercher
+search-searching = Recherche en cours...
+results-found = { $count } résultats trouvés
+
+export-label = Exporter :
+export-done = { $count } résultats exportés vers { $path }
+export-failed = Échec de l'export : { $error }
+
+home-pinned = Épinglés
+home-recent = Modifiés récemment
+
+drop-not-folder = Déposez un dossier pour l'ajouter à l'index
+remote-invalid = Saisissez un dossier distant sous la forme ssh://, webdav://, webdavs:// ou smb://utilisateur@hôte/chemin
+remote-unsupported = Les partages smb:// ne peuvent être ajoutés que sous Windows ; montez le partage et ajoutez le dossier monté
+remote-password-failed = Impossible d'enregistrer le mot de passe dans le trousseau : { $error }
+drop-already-indexed = { $path } est déjà indexé
+drop-indexing-about = Indexation de { $path } (environ { $count } fichiers)
+drop-indexing-over = Indexation de { $path } (plus de { $count } fichiers)
+
+estimate-running = Comptage des fichiers de { $path }...
+estimate-failed = Impossible de parcourir { $path } : { $error }
+estimate-title = Indexer ce dossier ?
+estimate-files = { $indexable } fichiers à lire sur { $files }, { $size }
+estimate-files-over = { $indexable } fichiers à lire sur plus de { $files }, { $size }
+estimate-names-only = Seuls les noms de fichiers sont indexés pour ce dossier
+estimate-too-large = { $count } fichiers au-delà de la limite de { $limit } Mo sont ignorés
+estimate-time = L'indexation prend environ { $time } et ajoute environ { $size } à l'index
+estimate-confirm = Indexer
+estimate-cancel = Annuler
+
+volume-title = Indexer ce lecteur ?
+volume-found = { $label } a été branché sur { $path }
+volume-note = Seuls les noms de fichiers sont indexés. Ils restent consultables lorsque le lecteur est débranché, marqués hors ligne.
+volume-confirm = Indexer les noms
+volume-cancel = Plus tard
+
+reconnect-title = Ce fichier est hors ligne
+reconnect-drive = Il se trouve sur le lecteur amovible { $volume }. Branchez le lecteur, puis vérifiez à nouveau.
+reconnect-share = Son dossier est inaccessible pour le moment. Reconnectez le partage ou le lecteur, puis vérifiez à nouveau.
+reconnect-check = Vérifier à nouveau
+reconnect-cancel = Annuler
+reconnect-done = { $path } est de nouveau disponible
+reconnect-still = { $path } est toujours hors ligne
+
+trash-bin = Corbeille
+trash-bin-windows = Corbeille
+trash-title = Déplacer vers la { $bin } ?
+trash-more = …et { $count } de plus
+trash-note = Ils sont aussi retirés de l'index, et réindexés s'ils sont restaurés.
+trash-confirm = Déplacer
+trash-cancel = Annuler
+
+open-with-title = Ouvrir { $name } avec
+open-with-other = Autre application…
+open-with-cancel = Annuler
+
+stats-title = Statistiques de l'index
+stats-subtitle = Ce que contient l'index et l'espace disque qu'il occupe
+stats-loading = Collecte des statistiques...
+stats-failed = Impossible de lire les statistiques de l'index : { $error }
+stats-refresh = Actualiser
+stats-storage = Stockage
+stats-documents = Documents
+stats-search-index = Index de recherche
+stats-metadata-db = Base de métadonnées
+stats-filename-index = Index des noms de fichiers
+stats-total = Total sur le disque
+stats-cache = Cache des requêtes
+stats-cache-ratio = { $percent } % de { $count } recherches servies par le cache
+stats-cache-none = Aucune recherche pour l'instant
+stats-by-root = Par dossier
+stats-by-extension = Par type de fichier
+stats-largest = Documents les plus volumineux
+stats-no-documents = Rien n'est encore indexé
+stats-files = { $count } fichiers
+stats-more = et { $count } de plus
+
+max-results-title = Nombre maximal de résultats
+max-results-hint = Limite le nombre total de résultats renvoyés pour de meilleures performances
+exclude-title = Motifs d'exclusion (séparés par des virgules)
+exclude-hint = Motifs de dossiers et de fichiers ignorés lors de l'indexation (par ex. *.git, target, node_modules)
+extensions-title = Extensions personnalisées
+extensions-hint = Formats supplémentaires à indexer comme texte brut (séparés par des virgules)
+extensions-placeholder = par ex. log, env, conf, sdp
+hotkey-title = Raccourci global de recherche
+hotkey-hint = Raccourci clavier pour ouvrir FindAll depuis n'importe où
+quick-hotkey-title = Raccourci de recherche rapide
+quick-hotkey-hint = Ouvre une petite palette de recherche toujours au premier plan ; laisser vide pour la désactiver
+
+macros-hint = Tapez @nom dans la zone de recherche pour insérer un fragment de requête enregistré
+macros-add = Ajouter
+macros-label = Macros :
+
+history-enabled = Mémoriser les recherches que je lance ou dont j'ouvre un résultat
+history-empty = Aucune recherche mémorisée pour l'instant
+history-clear = Effacer les recherches non épinglées
+history-button = Historique des recherches
+history-dropdown-empty = Les recherches que vous lancez ou dont vous ouvrez un résultat apparaissent ici
+history-no-match = Aucune recherche passée ne correspond
+history-manage = Gérer l'historique
+history-uses-one = 1 recherche
+history-uses = { $count } recherches
+history-pin-hint = Garder cette recherche en haut
+history-delete = Supprimer de l'historique
+pin = Épingler
+unpin = Désépingler
+
+dirs-empty = Aucun dossier configuré pour l'indexation.
+dirs-add = Ajouter un dossier à l'index
+
+system-tray = Réduire dans la zone de notification à la fermeture de la fenêtre
+system-autostart = Lancer FindAll automatiquement au démarrage du système
+system-context-menu = Ajouter « Rechercher avec FindAll » au menu contextuel de Windows
+system-gitignore = Respecter les règles .gitignore lors de l'analyse des dépôts
+system-mft = Lire des lecteurs NTFS entiers directement depuis la MFT pour indexer rapidement les noms (droits d'administrateur requis)
+system-code-aware = Traiter getUserName et get_user_name de la même façon dans le code source (s'applique aux fichiers nouvellement indexés)
+
+opening-editor-title = Éditeur de texte et de code
+opening-editor-hint = Ouvre les résultats à la ligne trouvée ; {file} et {line} sont remplacés
+opening-pdf-title = Lecteur PDF
+opening-pdf-hint = Ouvre les PDF à la page trouvée ; {file} et {page} sont remplacés
+
+shortcuts-hint = Les flèches parcourent les résultats et F3 les correspondances de l'aperçu ; laissez un raccourci vide pour le désactiver
+shortcuts-invalid = Raccourci non valide
+shortcuts-off = Désactivé
+
+appearance-theme-title = Thème de couleurs
+appearance-theme-hint = Basculer entre les modes sombre et clair de Windows 11
+appearance-dark = Thème sombre
+
+problems-none = Aucun fichier n'a été ignoré lors des dernières analyses.
+problems-failed = Échec
+problems-timeout = Délai dépassé
+problems-crashed = Plantage
+problems-summary = { $count } fichiers n'ont pas pu être analysés et ont été ignorés. Ils sont réessayés automatiquement dès qu'ils changent sur le disque.
+problems-retry = Tout réessayer à la prochaine analyse
+
+schedule-title = Actualisation planifiée
+schedule-hint = Actualise en arrière-plan : off, daily HH:MM (par ex. daily 02:00) ou every Nh (par ex. every 6h).
+schedule-invalid = Planification non valide
+schedule-never = jamais
+schedule-none = non planifiée
+schedule-summary = Dernière exécution : { $last } · Prochaine : { $next }
+refresh-title = Actualiser l'index
+refresh-hint = Réindexe uniquement les dossiers modifiés depuis la dernière actualisation et retire les fichiers supprimés.
+refresh-button = Actualiser
+rebuild-title = Forcer la reconstruction complète de l'index
+rebuild-hint = Efface toutes les données d'index en cache et analyse à nouveau tous les dossiers configurés.
+rebuild-button = Tout reconstruire
+
+privacy-local = FindAll fonctionne à 100 % en local. Aucune donnée ni recherche ne quitte jamais votre machine.
+privacy-data-path = Dossier de données local :
+privacy-index-path = Index de recherche Tantivy :
+privacy-db-path = Base Redb :
+privacy-unknown = Inconnu
+
+db-corrupted = La base de métadonnées était corrompue et a été réinitialisée. Une réindexation complète est recommandée.
+banner-dismiss = Fermer
+search-error = Erreur : { $error }
+quick-placeholder = Rechercher des fichiers...
+quick-more = { $count } de plus dans la fenêtre complète
+quick-footer = Entrée pour ouvrir, Échap pour fermer
+app-tagline = Recherche locale instantanée
+match-case-hint = Respecter la casse
+match-word-hint = Mot entier uniquement
+mode-all = Tout
+mode-text = Texte
+mode-file = Fichier
+theme-switch-light = Passer au thème clair
+theme-switch-dark = Passer au thème sombre
+open-settings = Ouvrir les paramètres
+
+filters-active = Filtres actifs :
+filters-show = Afficher les options de filtre
+filters-hide = Masquer les options de filtre
+filters-title = Options de filtre
+filters-reset = Réinitialiser tous les filtres
+filter-extension = Extension
+filter-size = Plage de taille
+filter-size-min = Min
+filter-size-max = Max
+filter-date = Dernière modification
+date-anytime = N'importe quand
+date-today = Aujourd'hui
+date-week = Semaine passée
+date-month = Mois passé
+filter-scope = Portée de la recherche
+scope-all = Tout
+scope-text = Texte intégral
+scope-name = Nom de fichier
+filter-flags = Options de correspondance
+flag-case = Respecter la casse
+flag-word = Mot entier
+flag-best = Meilleurs résultats uniquement
+flag-full-path = Chercher dans le chemin complet
+results-show-all = Afficher les { $total } résultats ({ $hidden } correspondances moins fiables masquées)
+
+menu-open = Ouvrir
+menu-open-with = Ouvrir avec…
+menu-open-folder = Ouvrir le dossier parent
+menu-copy-path = Copier le chemin
+menu-copy-picked = Copier { $count } éléments choisis
+menu-copy-folder = Copier le dossier
+menu-copy-file = Copier le fichier
+menu-copy-text = Copier le texte
+menu-copy-matched = Copier le texte trouvé
+menu-pin = Épingler à l'accueil
+menu-reindex = Réindexer maintenant
+menu-remove = Retirer de l'index
+menu-trash-picked = Déplacer { $count } éléments choisis vers la { $bin }
+menu-trash = Déplacer vers la { $bin }
+menu-exclude-folder = Exclure ce dossier de l'indexation
+menu-exclude-file = Exclure ce fichier de l'indexation
+menu-exclude-parent = Exclure le dossier parent
+menu-exclude-ext = Exclure tous les fichiers .{ $ext }
+
+column-name = Nom
+column-folder = Dossier
+column-ext = Ext.
+column-size = Taille
+column-modified = Modifié
+column-score = Score
+group-no-folder = (aucun dossier)
+group-by-folder = Grouper par dossier
+sort-title = Tri et affichage
+sort-relevance = Pertinence
+sort-date = Date de modification
+sort-size = Taille
+sort-name = Nom
+layout-cards = Cartes
+layout-table = Tableau
+categories = Catégories
+
+welcome-title = Recherche instantanée FindAll
+welcome-subtitle = Moteur de recherche local ultrarapide pour textes, documents et noms de fichiers
+welcome-shortcuts = Raccourcis clavier
+shortcut-global = Fenêtre de recherche globale
+shortcut-focus = Aller à la zone de recherche
+shortcut-navigate = Parcourir les résultats
+shortcut-open = Ouvrir le fichier sélectionné
+shortcut-open-folder = Ouvrir le dossier parent
+shortcut-copy-path = Copier le chemin du fichier
+welcome-features = Fonctions de recherche avancées
+tip-scope-title = Texte intégral ou nom de fichier
+tip-scope = Changez la portée de la recherche dans la barre du haut
+tip-extension-title = Filtre par extension
+tip-extension = Filtrez PDF, MD, RS, TXT et code dans le panneau latéral
+tip-exact-title = Correspondance exacte et casse
+tip-exact = Utilisez « Aa » et « W » pour plus de précision
+tip-preview-title = Aperçu instantané des documents
+tip-preview = Examinez extraits et tableaux en direct
+welcome-status = État de l'index : { $count } fichiers indexés ({ $size })
+
+no-results-title = Aucun résultat correspondant
+no-results-hint = Essayez de modifier la requête ou d'élargir les filtres
+no-results-tips = Suggestions :
+no-results-tip-spelling = • Vérifiez l'orthographe ou essayez des mots-clés plus simples
+no-results-tip-mode = • Basculez entre la recherche en texte intégral et par nom de fichier
+no-results-tip-filters = • Retirez les filtres d'extension actifs dans le panneau de gauche
+result-open = Ouvrir
+result-folder = Dossier
+unknown-size = Taille inconnue
+unknown-date = Date inconnue
+
+preview-loading = Chargement du contenu du document...
+preview-select = Sélectionnez un résultat à prévisualiser
+preview-hint = Les extraits et l'aperçu du document s'affichent ici
+preview-title = Aperçu du document
+preview-match = Correspondance { $current } sur { $total }
+preview-previous = Correspondance précédente (Maj+F3)
+preview-next = Correspondance suivante (F3)
+preview-snippets = Extraits correspondants
+preview-content = Contenu du document
+preview-elements = { $count } éléments structurels analysés
+preview-page = Page 1 sur { $pages }
+preview-binary = Fichier binaire ({ $mime }), premiers { $shown } sur { $size }
+preview-window-start = Document long : { $size } affichés autour du début ({ $start } à { $end } sur { $total })
+preview-window-match = Document long : { $size } affichés autour de la première correspondance ({ $start } à { $end } sur { $total })
+owner-line = Propriétaire : { $owner }
+read-only = Lecture seule
+hidden = Masqué
+
+details-title = Détails
+details-not-indexed = Non indexé
+details-current = À jour, indexé le { $date }
+details-changed = Modifié sur le disque depuis son indexation le { $date }
+details-size = Taille
+details-modified = Modifié
+details-created = Créé
+details-hash = Empreinte du contenu
+details-parser = Analyseur
+details-index = Index
+notes-title = Notes
+notes-placeholder = Ajouter une note, par ex. envoyé au comptable
+notes-attributes = Attributs, par ex. client: Acme, year: 2025
+notes-save = Enregistrer
+
+watcher-unwatched = Non surveillés : { $folders }
+watcher-error = Dernière erreur : { $error }
+watcher-events = { $count } modifications détectées
+status-files = { $count } fichiers indexés
+eta-hours = Restant : { $hours } h { $minutes } min
+eta-minutes = Restant : { $minutes } min { $seconds } s
+eta-seconds = Restant : { $seconds } s
+indexing-resume = Reprendre
+indexing-pause = Pause
+indexing-cancel = Annuler
+indexing-resume-previous = Reprendre l'indexation précédente ({ $files } fichiers traités)
+
+root-content = Indexer le contenu
+root-symlinks = Suivre les liens symboliques
+root-separate = Index séparé
+root-depth = Profondeur max.
+root-include = Inclure uniquement, par ex. *.rs, *.md
+root-exclude = Exclure aussi, par ex. *.bak
+
+sensitive-title = Fichiers sensibles
+sensitive-hint = Les clés, coffres de mots de passe et secrets correspondant à ces motifs ne sont jamais lus ; les motifs contenant / portent sur le chemin complet
+sensitive-names-only = Noms uniquement
+sensitive-skip = Ignorer complètement
+sensitive-index = Indexer normalement
+
+encryption-title = Chiffrement de l'index
+encryption-hint = Chiffre sur le disque l'index de recherche, les métadonnées et les noms de fichiers. Prend effet au prochain démarrage, qui convertit l'index existant
+encryption-off = Désactivé
+encryption-keychain = Clé dans le trousseau du système
+encryption-passphrase = Phrase secrète
+
+activity-enabled = Enregistrer ce qui est indexé, ignoré et retiré, ainsi que mes recherches
+activity-note = Conservé sur cet ordinateur dans le dossier de données et renouvelé au-delà de quelques mégaoctets ; les entrées sont chiffrées avec l'index quand le chiffrement est activé
+activity-filter = Filtrer par chemin, recherche ou événement, par ex. skipped
+activity-empty = Rien d'enregistré pour l'instant
+activity-clear = Effacer le journal d'activité
+
+root-hidden = Fichiers masqués
+root-system = Fichiers système
+
+root-elevated = Lire les fichiers auxquels je n'ai pas accès (administrateur requis)
+
+open-failed = Impossible d'ouvrir le fichier : { $error }
+
+openers-hint = Commandes pour une extension, utilisées à la place de l'application par défaut ou de l'éditeur. {} ou {file} est le chemin, {line} et {page} l'endroit trouvé ; une URL s'ouvre dans l'application qui la gère
+openers-add = Ajouter
+
+reindex-done = { $count } fichier(s) réindexé(s)
+
+result-changed = Modifié · Réindexer
+changed-detail = Modifié depuis l'indexation
+
+query-error = { $message } (colonne { $column })
+query-try = Essayez :
+
+did-you-mean = Vouliez-vous dire :
+
+similar-title = Fichiers similaires
+
+menu-compare = Comparer le contenu
+compare-running = Comparaison du contenu…
+compare-failed = Échec de la comparaison : { $error }
+diff-summary = { $removed } ligne(s) supprimée(s), { $added } ajoutée(s)
+diff-identical = Les contenus analysés sont identiques
+diff-skipped = … { $count } ligne(s) inchangée(s) …
+
+root-removable = Sur un lecteur amovible ; ses fichiers restent listés hors ligne tant qu'il est débranché
+volume-offer = Proposer d'indexer les noms de fichiers des lecteurs amovibles lors de leur branchement
+
+result-offline = Hors ligne · Reconnecter
+offline-detail = Hors ligne (lecteur débranché ou partage déconnecté)
+
+cloud-title = Fichiers cloud en ligne uniquement
+cloud-hint = Les fichiers OneDrive, Dropbox, Google Drive et iCloud non téléchargés sont indexés par leur nom seulement, car les lire les télécharge. Indiquez les dossiers dont les fichiers peuvent être téléchargés pour indexer leur contenu
+cloud-placeholder = par ex. C:\Users\moi\OneDrive\Documents
+
+remote-add = Ajouter un dossier distant
+
+root-rescan = Réanalyser
+root-rescan-global = Planification globale
+remote-placeholder = Dossier distant, par ex. ssh://user@host/docs ou smb://user@server/share
+remote-password = Mot de passe (facultatif)
+remote-hint = Prend en charge ssh://, webdav://, webdavs:// et smb://. Les mots de passe sont conservés dans le trousseau du système ; les serveurs SSH doivent figurer dans ~/.ssh/known_hosts et accepter aussi votre agent ou vos clés
+
+recent-load-failed = Impossible de charger les fichiers récents : { $error }
+history-update-failed = Impossible de mettre à jour l'historique de recherche : { $error }
+settings-reloaded = Paramètres rechargés depuis le disque
+settings-reload-failed = Impossible de recharger les paramètres : { $error }
+preview-failed = Erreur d'aperçu : { $error }
+details-failed = Impossible de lire les détails du fichier : { $error }
+note-saved = Note enregistrée
+note-save-failed = Impossible d'enregistrer la note : { $error }
+text-copied = { $count } caractères de texte copiés
+text-copy-failed = Impossible de copier le texte : { $error }
+files-copy-failed = Impossible de copier les fichiers : { $error }
+open-with-app-failed = Impossible d'ouvrir avec { $app } : { $error }
+open-with-failed = Échec de « Ouvrir avec » : { $error }
+reindex-failed = Échec de la réindexation : { $error }
+index-removed = Retiré de l'index
+index-remove-failed = Impossible de retirer de l'index : { $error }
+trash-moved-one = Déplacé vers la corbeille
+trash-moved = { $count } éléments déplacés vers la corbeille
+trash-failed = Impossible de déplacer vers la corbeille : { $error }
+exclude-done = Exclu de l'indexation, { $count } fichier(s) retiré(s)
+exclude-failed = Impossible d'exclure : { $error }
+status-rebuilding = Reconstruction de l'index...
+status-refreshing = Actualisation de l'index...
+status-paused = En pause
+status-resuming = Reprise...
+status-resuming-interrupted = Reprise de l'analyse interrompue...
+status-cancelling = Annulation...
+pin-failed = Impossible d'épingler le fichier : { $error }
+unpin-failed = Impossible de désépingler le fichier : { $error }
+folder-picker-title = Sélectionner le dossier à indexer
+live-partial = Mises à jour en direct : { $watched } dossiers sur { $total }
+live-pending = En direct · { $count } en attente
+live = En direct
+
+action-open = Ouvrir le résultat sélectionné
+action-reveal = Afficher dans le dossier
+action-copy-path = Copier le chemin
+action-clear = Fermer, effacer ou masquer
+action-next-pane = Volet suivant
+action-previous-pane = Volet précédent
+
+category-documents = Documents
+category-spreadsheets = Feuilles de calcul
+category-presentations = Présentations
+category-code = Code source
+category-data = Données
+category-images = Images
+category-audio = Audio
+category-video = Vidéo
+category-archives = Archives
+category-email = E-mail
+category-ebooks = Livres numériques
+category-other = Autres
+
+focus-search = Champ de recherche
+focus-filters = Options de filtre
+focus-results = Liste des résultats
+focus-preview = Panneau d'aperçu
+announce-no-results = Aucun résultat
+announce-one-result = 1 résultat
+announce-results = { $count } résultats
+announce-results-hidden = { $count } résultats, { $hidden } de plus masqués
@@ -0,0 +1,1189 @@
+//! The `flash-search` command line. Without a subcommand the app opens its
+//! window; each subcommand opens the index itself, runs, and exits with one
+//! of the [`exit_code`]s. `search` is answered by the running instance when
+//! there is one.
+
+use crate::commands;
+use crate::error::{FlashError, Result};
+use crate::iced_ui::{format_eta, format_size};
+use crate::indexer::searcher::{SearchParams, SearchResult};
+use crate::system::ipc::ResultRecord;
+use crate::{get_app_data_dir, indexer, scanner, settings, system};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Results a search prints unless `--limit` says otherwise
+const DEFAULT_RESULT_LIMIT: usize = 20;
+
+/// Exit codes of the command line
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// The command failed
+    pub const FAILURE: i32 = 1;
+    /// The arguments didn't parse; clap exits with this itself
+    pub const USAGE: i32 = 2;
+    /// Another instance holds the index the command needs
+    pub const ALREADY_RUNNING: i32 = 3;
+    /// The index is encrypted and no passphrase, or a wrong one, was given
+    pub const LOCKED: i32 = 4;
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "flash-search", version, about = "Ultrafast local text search")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Print JSON instead of text
+    #[arg(short, long, global = true)]
+    pub json: bool,
+
+    /// Profile to use, each with its own folders and index; defaults to the
+    /// one last chosen in the window
+    #[arg(short, long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Folder to open the window in
+    #[arg(value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// Same as `search <QUERY>`, kept for scripts written against it
+    #[arg(
+        short = 'c',
+        long = "cli",
+        short_alias = 's',
+        alias = "search",
+        value_name = "QUERY",
+        hide = true
+    )]
+    legacy_query: Option<String>,
+
+    /// Same as `parse-errors`
+    #[arg(long = "parse-errors", hide = true)]
+    legacy_parse_errors: bool,
+}
+
+impl Cli {
+    /// The command to run, or `None` to open the window.
+    pub fn command(&self) -> Option<Command> {
+        if let Some(query) = &self.legacy_query {
+            return Some(Command::Search {
+                query: query.clone(),
+                limit: DEFAULT_RESULT_LIMIT,
+                format: None,
+                paths_only: false,
+                null: false,
+            });
+        }
+        if self.legacy_parse_errors {
+            return Some(Command::ParseErrors);
+        }
+        self.command.clone()
+    }
+
+    /// The folder to open the window in, if one was given and exists.
+    pub fn initial_dir(&self) -> Option<String> {
+        self.dir
+            .as_ref()
+            .filter(|dir| dir.is_dir())
+            .map(|dir| dir.to_string_lossy().to_string())
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Search the index
+    #[command(after_help = RESULT_FIELDS_HELP)]
+    Search {
+        query: String,
+        /// Most results to print
+        #[arg(short = 'n', long, default_value_t = DEFAULT_RESULT_LIMIT)]
+        limit: usize,
+        /// How to print the results; `--json` is short for `--format json`
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+        /// Print only paths, one per line; short for `--format paths`
+        #[arg(long, conflicts_with = "format")]
+        paths_only: bool,
+        /// End each path with a NUL instead of a newline, for `fzf --read0`
+        /// and `xargs -0`; implies `--paths-only`
+        #[arg(short = '0', long, conflicts_with = "format")]
+        null: bool,
+    },
+    /// List indexed files whose content resembles a file's
+    Similar {
+        path: PathBuf,
+        /// Most results to print
+        #[arg(short = 'n', long, default_value_t = DEFAULT_RESULT_LIMIT)]
+        limit: usize,
+    },
+    /// Index folders, or every configured root, and wait until it's done
+    Index {
+        /// Folders to index instead of the configured roots; remote folders
+        /// are given as `ssh://`, `webdav://`, `webdavs://` or
+        /// `smb://user@host/path`
+        paths: Vec<PathBuf>,
+        /// Only pick up what changed since the last scan
+        #[arg(long, conflicts_with = "dry_run")]
+        refresh: bool,
+        /// Ask for the password of each remote folder given and save it in
+        /// the OS keychain
+        #[arg(long, conflicts_with = "dry_run")]
+        ask_password: bool,
+        /// Count what would be indexed and estimate the time and index size,
+        /// without indexing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Parse files or folders again, even if they haven't changed
+    Reindex {
+        /// Files or folders under an indexed root
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Show what the indexer is doing and how large the index is
+    Status,
+    /// Keep the index up to date with file changes until interrupted
+    Watch,
+    /// Remove files from the index, without touching them on disk
+    Purge {
+        /// Files or folders to remove, with everything beneath them
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        paths: Vec<PathBuf>,
+        /// Empty the whole index
+        #[arg(long)]
+        all: bool,
+    },
+    /// Cross-check the search index, metadata and filename index
+    Verify {
+        /// Queue the discrepancies to be fixed at the next start
+        #[arg(long)]
+        repair: bool,
+    },
+    /// Write search results to a file
+    Export {
+        query: String,
+        /// File to write
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Taken from the output's extension when left out
+        #[arg(short, long, value_enum)]
+        format: Option<ExportFormat>,
+        /// Most results to write
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Show or change settings
+    Settings {
+        #[command(subcommand)]
+        action: SettingsCommand,
+    },
+    /// List files that could not be parsed during indexing
+    ParseErrors,
+    /// Show, change or run the scheduled re-index
+    Schedule {
+        /// `off`, `daily HH:MM`, `every Nh`, or `run` to refresh now
+        spec: Vec<String>,
+    },
+    /// Merge the search index into a single segment
+    Optimize,
+    /// Back up the index to a zip archive
+    Backup { archive: PathBuf },
+    /// Restore a backup the next time the app starts
+    Restore { archive: PathBuf },
+    /// Answer launcher plugins such as PowerToys Run, Raycast and Alfred:
+    /// JSON requests on stdin, one per line, or a single query
+    Launcher {
+        /// Answer this one query with its items and exit
+        query: Option<String>,
+        /// Print the answer as an Alfred script filter
+        #[arg(long, requires = "query")]
+        alfred: bool,
+        /// Most results per query
+        #[arg(short = 'n', long, default_value_t = system::launcher::DEFAULT_LIMIT)]
+        limit: usize,
+    },
+    /// Serve the index to AI assistants over the Model Context Protocol,
+    /// on stdin and stdout
+    Mcp,
+    /// Print a completion script for a shell
+    #[command(after_help = "\
+Load it for the current session:
+  bash        source <(flash-search completions bash)
+  zsh         source <(flash-search completions zsh)
+  fish        flash-search completions fish | source
+  PowerShell  flash-search completions powershell | Out-String | Invoke-Expression")]
+    Completions { shell: clap_complete::Shell },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SettingsCommand {
+    /// Print every setting, or the one at KEY
+    Get {
+        /// Setting name; nested ones are joined with dots, e.g. `watcher.debounce_ms`
+        key: Option<String>,
+    },
+    /// Change the setting at KEY
+    Set {
+        key: String,
+        /// JSON, or a plain string
+        value: String,
+    },
+}
+
+/// Listed under `search --help`, so scripts know what they can rely on
+const RESULT_FIELDS_HELP: &str = "\
+Result fields (json, ndjson and csv):
+  path      Full path of the file
+  title     Document title, if it has one
+  score     Relevance; higher is better
+  size      Size in bytes
+  modified  Last modified, in seconds since the Unix epoch
+  snippet   Best matching passage, as plain text
+
+Empty fields are null in JSON and blank in CSV. Plain output prints
+`score | path`, one result per line; paths output only the path.
+
+Pick a file with fzf:
+  flash-search search QUERY -0 | fzf --read0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// `score | path` per line
+    Plain,
+    /// One JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated, with a header row
+    Csv,
+    /// Paths only, one per line
+    Paths,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+/// Runs `command` and returns the code to exit with.
+pub async fn run(command: Command, json: bool) -> i32 {
+    let result = match command {
+        Command::Search {
+            query,
+            limit,
+            format,
+            paths_only,
+            null,
+        } => {
+            let format = match format {
+                Some(format) => format,
+                None if paths_only || null => OutputFormat::Paths,
+                None if json => OutputFormat::Json,
+                None => OutputFormat::Plain,
+            };
+            run_search(&query, limit, format, null).await
+        }
+        Command::Similar { path, limit } => {
+            let format = if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Plain
+            };
+            run_similar(&path, limit, format).await
+        }
+        Command::Index {
+            paths,
+            dry_run: true,
+            ..
+        } => run_index_dry_run(&paths, json),
+        Command::Index {
+            paths,
+            refresh,
+            ask_password,
+            ..
+        } => run_index(&paths, refresh, ask_password, json).await,
+        Command::Reindex { paths } => run_reindex(&paths).await,
+        Command::Status => run_status(json).await,
+        Command::Watch => run_watch().await,
+        Command::Purge { paths, all } => run_purge(&paths, all),
+        Command::Verify { repair } => run_verify(repair, json),
+        Command::Export {
+            query,
+            output,
+            format,
+            limit,
+        } => run_export(&query, &output, format, limit).await,
+        Command::Settings { action } => run_settings(action, json),
+        Command::ParseErrors => run_parse_errors(json),
+        Command::Schedule { spec } => run_schedule(&spec).await,
+        Command::Optimize => run_optimize(),
+        Command::Backup { archive } => run_backup(&archive),
+        Command::Restore { archive } => run_restore(&archive),
+        Command::Launcher {
+            query,
+            alfred,
+            limit,
+        } => run_launcher(query.as_deref(), alfred, limit).await,
+        Command::Mcp => run_mcp().await,
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut <Cli as clap::CommandFactory>::command(),
+                "flash-search",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => exit_code::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            match e {
+                FlashError::AlreadyRunning { .. } => exit_code::ALREADY_RUNNING,
+                FlashError::Locked { .. } => exit_code::LOCKED,
+                _ => exit_code::FAILURE,
+            }
+        }
+    }
+}
+
+/// Opens the stores like the window does, asking for the index passphrase
+/// on the terminal if the index is locked and none is in
+/// [`system::vault::PASSPHRASE_ENV`].
+fn setup_app() -> Result<(
+    Arc<commands::AppState>,
+    flume::Receiver<scanner::ProgressEvent>,
+)> {
+    use std::io::IsTerminal;
+
+    match crate::setup_app() {
+        Err(FlashError::Locked { new, .. }) if std::io::stdin().is_terminal() => {
+            let prompt = if new {
+                "Choose a passphrase for the index: "
+            } else {
+                "Index passphrase: "
+            };
+            system::vault::provide_passphrase(rpassword::prompt_password(prompt)?);
+            crate::setup_app()
+        }
+        result => result,
+    }
+}
+
+/// Lets Ctrl+C stop a long-running command cleanly; a second one exits at
+/// once.
+fn stop_on_ctrl_c() {
+    let _ = ctrlc::set_handler(|| {
+        if crate::SHUTDOWN_FLAG.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!("Stopping...");
+    });
+}
+
+async fn search_locally(
+    query: &str,
+    limit: usize,
+    state: &Arc<commands::AppState>,
+) -> Result<Vec<SearchResult>> {
+    commands::search_combined_internal(
+        SearchParams::builder()
+            .query(query)
+            .limit(limit)
+            .case_sensitive(false)
+            .build(),
+        false,
+        state,
+    )
+    .await
+    .map_err(|e| FlashError::search(query, e))
+}
+
+async fn run_search(query: &str, limit: usize, format: OutputFormat, null: bool) -> Result<()> {
+    let results: Vec<ResultRecord> = match setup_app() {
+        Ok((state, _)) => {
+            let results = search_locally(query, limit, &state).await?;
+            if results.is_empty() {
+                suggest_spelling(query, &state);
+            }
+            results.iter().map(ResultRecord::from).collect()
+        }
+        // The running instance owns the index, so it answers instead
+        Err(FlashError::AlreadyRunning { .. }) => {
+            let mut results = system::ipc::search(query).await?;
+            results.truncate(limit);
+            results
+        }
+        Err(e) => return Err(e),
+    };
+    print_results(&results, format, null, std::io::stdout().lock())
+}
+
+async fn run_similar(path: &Path, limit: usize, format: OutputFormat) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let path = std::fs::canonicalize(path)?;
+    let results: Vec<ResultRecord> =
+        commands::more_like_this_internal(path.to_string_lossy().to_string(), limit, &state)
+            .await
+            .map_err(FlashError::index)?
+            .iter()
+            .map(ResultRecord::from)
+            .collect();
+    print_results(&results, format, false, std::io::stdout().lock())
+}
+
+/// Prints a respelling of `query`, which found nothing, to stderr so it
+/// stays out of piped output.
+fn suggest_spelling(query: &str, state: &commands::AppState) {
+    if let Ok(Some(suggestion)) = commands::spelling_suggestion_internal(query, state) {
+        eprintln!(
+            "Did you mean: {} (flash-search search \"{}\")",
+            suggestion.describe(),
+            suggestion.query.replace('"', "\\\""),
+        );
+    }
+}
+
+/// Writes `results` in `format`; `null` ends each path with a NUL in the
+/// paths format.
+fn print_results(
+    results: &[ResultRecord],
+    format: OutputFormat,
+    null: bool,
+    mut out: impl std::io::Write,
+) -> Result<()> {
+    let to_io = |e: serde_json::Error| FlashError::Io(Arc::new(e.into()));
+    match format {
+        OutputFormat::Plain => {
+            for res in results {
+                writeln!(out, "{} | {}", res.score, res.path)?;
+            }
+        }
+        OutputFormat::Paths => {
+            let end = if null { b'\0' } else { b'\n' };
+            for res in results {
+                out.write_all(res.path.as_bytes())?;
+                out.write_all(&[end])?;
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, results).map_err(to_io)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for res in results {
+                serde_json::to_writer(&mut out, res).map_err(to_io)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for res in results {
+                writer
+                    .serialize(res)
+                    .map_err(|e| FlashError::Io(Arc::new(e.into())))?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn run_index_dry_run(paths: &[PathBuf], json: bool) -> Result<()> {
+    // Walking needs no lock, so it works while the app runs
+    let settings = settings::SettingsManager::new(&get_app_data_dir()?).load()?;
+    let roots = if paths.is_empty() {
+        commands::index_roots_internal(settings.index_roots.clone())
+    } else {
+        paths
+            .iter()
+            .map(|path| Ok(settings.root_profile(&std::fs::canonicalize(path)?)))
+            .collect::<Result<Vec<_>>>()?
+    };
+    let estimates: Vec<_> = roots
+        .iter()
+        .map(|root| scanner::estimate::estimate_scan(root, &settings, commands::SCAN_ESTIMATE_CAP))
+        .collect();
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&estimates).unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    for estimate in &estimates {
+        println!(
+            "{}: {}{} files, {} to parse ({})",
+            estimate.root,
+            if estimate.truncated { "over " } else { "" },
+            estimate.files_seen,
+            estimate.indexable_files,
+            format_size(estimate.indexable_bytes)
+        );
+        for category in &estimate.by_category {
+            println!(
+                "  {:<13}{:>9} files  {:>10}",
+                category.category.to_string(),
+                category.files,
+                format_size(category.bytes)
+            );
+        }
+        if estimate.too_large > 0 {
+            println!(
+                "  {} files over the {} MB limit are skipped",
+                estimate.too_large, settings.index_file_size_limit_mb
+            );
+        }
+        println!(
+            "  About {} to index, adding about {} to the index",
+            format_eta(estimate.estimated_secs),
+            format_size(estimate.estimated_index_bytes)
+        );
+    }
+    Ok(())
+}
+
+async fn run_index(paths: &[PathBuf], refresh: bool, ask_password: bool, json: bool) -> Result<()> {
+    if ask_password {
+        let urls = paths
+            .iter()
+            .filter_map(|path| scanner::remote::RemoteUrl::parse(&path.to_string_lossy()));
+        for url in urls {
+            let password = rpassword::prompt_password(format!("Password for {url}: "))?;
+            scanner::remote::credentials::store(&url, &password)?;
+        }
+    }
+    let (state, progress_rx) = setup_app()?;
+    stop_on_ctrl_c();
+
+    let settings = state.settings_cache.load_full();
+    let roots = if paths.is_empty() {
+        commands::index_roots_internal(settings.index_roots.clone())
+    } else {
+        paths
+            .iter()
+            .map(|path| {
+                // Remote URLs aren't on disk to canonicalize
+                if scanner::remote::is_remote(&path.to_string_lossy()) {
+                    return Ok(settings.root_profile(path));
+                }
+                Ok(settings.root_profile(&std::fs::canonicalize(path)?))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let cancel = state.indexing_cancel.clone();
+    let interrupt = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            while !crate::is_shutting_down() {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    let printer = (!json).then(|| {
+        tokio::spawn(async move {
+            while let Ok(event) = progress_rx.recv_async().await {
+                eprint!(
+                    "\r\x1b[K{} ({} / {})",
+                    event.status, event.processed, event.total
+                );
+            }
+        })
+    });
+
+    let exclude_patterns = settings.exclude_list();
+    let failures = if refresh {
+        state
+            .scanner
+            .refresh_roots(roots, exclude_patterns, cancel.clone())
+            .await
+    } else {
+        state
+            .scanner
+            .scan_roots(roots, exclude_patterns, cancel.clone())
+            .await
+    };
+    interrupt.abort();
+    if let Some(printer) = printer {
+        printer.abort();
+        eprintln!();
+    }
+
+    let status = commands::get_index_status_internal(&state)
+        .await
+        .map_err(FlashError::index)?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&status).unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Indexed {} of {} files ({} skipped, {} skipped: no access, {} failed to parse); {} documents in the index",
+            status.processed,
+            status.total,
+            status.skipped,
+            status.no_access,
+            status.errors,
+            status.files_indexed
+        );
+    }
+
+    if cancel.load(Ordering::Relaxed) {
+        return Err(FlashError::index(
+            "Indexing was interrupted; `index` picks it up again",
+        ));
+    }
+    match failures.as_slice() {
+        [] => Ok(()),
+        [(root, e)] => Err(FlashError::index(format!("Indexing {root} failed: {e}"))),
+        _ => {
+            for (root, e) in &failures {
+                eprintln!("Indexing {root} failed: {e}");
+            }
+            Err(FlashError::index(format!(
+                "Indexing failed for {} roots",
+                failures.len()
+            )))
+        }
+    }
+}
+
+async fn run_reindex(paths: &[PathBuf]) -> Result<()> {
+    let (state, _) = setup_app()?;
+    for path in paths {
+        let path = std::fs::canonicalize(path)?;
+        let found = commands::reindex_path_internal(&path, state.clone())
+            .await
+            .map_err(FlashError::index)?;
+        println!("Re-indexed {found} file(s) under {}", path.display());
+    }
+    Ok(())
+}
+
+async fn run_status(json: bool) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let status = commands::get_index_status_internal(&state)
+        .await
+        .map_err(FlashError::index)?;
+    let stats = commands::get_index_statistics_internal(&state)
+        .await
+        .map_err(FlashError::index)?;
+    let watcher = commands::get_watcher_status_internal(&state);
+
+    if json {
+        let report = serde_json::json!({
+            "status": status,
+            "statistics": stats,
+            "watcher": watcher,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!("Status: {}", status.status);
+    println!("Documents: {}", status.files_indexed);
+    match stats.size_limit_bytes {
+        Some(limit) => println!(
+            "Index size: {:.1} MB of {:.1} MB",
+            stats.total_size_bytes as f64 / 1_048_576.0,
+            limit as f64 / 1_048_576.0
+        ),
+        None => println!(
+            "Index size: {:.1} MB",
+            stats.total_size_bytes as f64 / 1_048_576.0
+        ),
+    }
+    for root in &stats.content_capped_roots {
+        println!("Names only (size cap): {root}");
+    }
+    println!(
+        "Watching: {} of {} roots",
+        watcher.watched_roots.len(),
+        watcher.watched_roots.len() + watcher.unwatched_roots.len()
+    );
+    if !status.roots.is_empty() {
+        println!("Last scan:");
+        for root in &status.roots {
+            println!(
+                "  {} {} / {} ({} skipped, {} no access, {} errors){}",
+                root.root,
+                root.content.processed,
+                root.content.total,
+                root.skipped,
+                root.no_access,
+                root.errors,
+                if root.finished { "" } else { " running" }
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn run_watch() -> Result<()> {
+    let (state, _) = setup_app()?;
+    stop_on_ctrl_c();
+
+    tokio::spawn(system::ipc::serve(state.clone()));
+    system::scheduler::spawn_scheduler(state.clone());
+    system::connectivity::spawn_connectivity_monitor(state.clone());
+
+    let watcher = commands::get_watcher_status_internal(&state);
+    println!(
+        "Watching {} folder(s) for changes. Press Ctrl+C to stop.",
+        watcher.watched_roots.len()
+    );
+    for root in &watcher.unwatched_roots {
+        println!("Not watched: {root}");
+    }
+    while !crate::is_shutting_down() {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    state.indexer.commit()?;
+    if let Some(filename_index) = &state.filename_index {
+        filename_index.commit()?;
+    }
+    Ok(())
+}
+
+async fn run_launcher(query: Option<&str>, alfred: bool, limit: usize) -> Result<()> {
+    use system::launcher::{self, Backend};
+
+    let backend = match setup_app() {
+        Ok((state, _)) => Backend::Local(state),
+        // Plugins keep working while the window is open
+        Err(FlashError::AlreadyRunning { .. }) => Backend::Remote,
+        Err(e) => return Err(e),
+    };
+    let reply = match query {
+        None => return launcher::serve_stdio(backend).await,
+        Some(query) if alfred => launcher::alfred(query, limit, &backend).await?,
+        Some(query) => launcher::items(query, limit, &backend).await?,
+    };
+    println!("{reply}");
+    Ok(())
+}
+
+async fn run_mcp() -> Result<()> {
+    let (state, _) = setup_app()?;
+    system::mcp::serve_stdio(state).await
+}
+
+fn run_purge(paths: &[PathBuf], all: bool) -> Result<()> {
+    let (state, _) = setup_app()?;
+    if all {
+        commands::purge_index_internal(&state).map_err(FlashError::index)?;
+        println!("Emptied the index.");
+        return Ok(());
+    }
+    for path in paths {
+        // A path already gone from disk can't be canonicalized, but is
+        // recorded as it was given
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+        let removed = commands::purge_path_internal(&path, &state).map_err(FlashError::index)?;
+        println!("Removed {removed} file(s) under {}", path.display());
+    }
+    Ok(())
+}
+
+/// Cross-checks the metadata store, the search index and the filename index
+/// and prints the discrepancies.
+///
+/// `repair` queues the offenders in the recovery journal; the next start of
+/// the app re-indexes the files that exist and prunes the rest.
+fn run_verify(repair: bool, json: bool) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let mut report = indexer::integrity::verify_index(
+        &state.indexer,
+        &state.metadata_db,
+        state.filename_index.as_deref(),
+    )?;
+
+    let queued = if repair {
+        let queued = indexer::integrity::repair_index(&report, &state.metadata_db)?;
+        report.repaired = queued > 0;
+        queued
+    } else {
+        0
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Checked {} metadata records, {} indexed documents, {} filename entries",
+        report.metadata_records, report.indexed_documents, report.filename_entries
+    );
+    let sections = [
+        ("Missing from the search index", &report.missing_documents),
+        (
+            "Indexed without a metadata record",
+            &report.orphaned_documents,
+        ),
+        ("Indexed more than once", &report.duplicate_documents),
+        ("Out of date", &report.hash_mismatches),
+        ("Deleted from disk", &report.deleted_files),
+        ("Missing from the filename index", &report.missing_filenames),
+        ("Stale filename entries", &report.orphaned_filenames),
+    ];
+    for (label, paths) in sections {
+        if paths.is_empty() {
+            continue;
+        }
+        println!("{label} ({}):", paths.len());
+        for path in paths {
+            println!("  {path}");
+        }
+    }
+    if report.is_consistent() {
+        println!("Index is consistent.");
+    } else if queued > 0 {
+        println!("Queued {queued} file(s) for repair on the next start.");
+    } else {
+        println!(
+            "Found {} issue(s). Run with --repair to fix them.",
+            report.issue_count()
+        );
+    }
+    Ok(())
+}
+
+async fn run_export(
+    query: &str,
+    output: &Path,
+    format: Option<ExportFormat>,
+    limit: usize,
+) -> Result<()> {
+    let format = match format {
+        Some(format) => format,
+        None => match output.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("md") => ExportFormat::Markdown,
+            _ => {
+                return Err(FlashError::config(
+                    "export",
+                    "can't tell the format from the file name; pass --format csv, json or markdown",
+                ));
+            }
+        },
+    };
+    let (state, _) = setup_app()?;
+    let results = search_locally(query, limit, &state).await?;
+
+    let path = output.to_string_lossy();
+    match format {
+        ExportFormat::Csv => commands::export_results_csv(&results, &path),
+        ExportFormat::Json => commands::export_results_json(&results, &path),
+        ExportFormat::Markdown => commands::export_results_markdown(&results, &path),
+    }
+    .map_err(|e| FlashError::config("export", e))?;
+    println!("Exported {} result(s) to {path}", results.len());
+    Ok(())
+}
+
+/// The value at a dotted `key` in `settings`.
+fn setting_mut<'a>(
+    settings: &'a mut serde_json::Value,
+    key: &str,
+) -> Result<&'a mut serde_json::Value> {
+    key.split('.').try_fold(settings, |value, name| {
+        value
+            .get_mut(name)
+            .ok_or_else(|| FlashError::config(key, "no such setting"))
+    })
+}
+
+fn run_settings(action: SettingsCommand, json: bool) -> Result<()> {
+    match action {
+        SettingsCommand::Get { key } => {
+            // Reading needs no lock, so it works while the app runs
+            let settings = settings::SettingsManager::new(&get_app_data_dir()?).load()?;
+            let mut settings = serde_json::to_value(&settings)
+                .map_err(|e| FlashError::config("settings", e.to_string()))?;
+            let value = match &key {
+                Some(key) => setting_mut(&mut settings, key)?,
+                None => &mut settings,
+            };
+            match value {
+                serde_json::Value::String(s) if !json => println!("{s}"),
+                value => println!(
+                    "{}",
+                    serde_json::to_string_pretty(value).unwrap_or_default()
+                ),
+            }
+        }
+        SettingsCommand::Set { key, value } => {
+            let (state, _) = setup_app()?;
+            let mut settings = serde_json::to_value(state.settings_cache.load().as_ref())
+                .map_err(|e| FlashError::config("settings", e.to_string()))?;
+            *setting_mut(&mut settings, &key)? =
+                serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            let settings: settings::AppSettings = serde_json::from_value(settings)
+                .map_err(|e| FlashError::config(&key, e.to_string()))?;
+            commands::save_settings_internal(&settings, &state)
+                .map_err(|e| FlashError::config(&key, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints files that could not be parsed during indexing.
+fn run_parse_errors(json: bool) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let errors = state.metadata_db.get_parse_errors()?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&errors).unwrap_or_default()
+        );
+    } else if errors.is_empty() {
+        println!("No parse failures recorded.");
+    } else {
+        for record in errors {
+            println!("{:?} | {} | {}", record.kind, record.path, record.message);
+        }
+    }
+    Ok(())
+}
+
+/// Shows or changes the re-index schedule, or runs a scheduled refresh now.
+///
+/// With no arguments prints the schedule and its last and next run; `run`
+/// refreshes every index root; anything else is parsed as a new schedule.
+async fn run_schedule(args: &[String]) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let spec = args.join(" ");
+
+    if spec == "run" {
+        println!("Refreshing index...");
+        commands::run_scheduled_refresh_internal(&state)
+            .await
+            .map_err(|e| FlashError::config("schedule", e))?;
+    } else if !spec.is_empty() {
+        let schedule: settings::ReindexSchedule = spec.parse()?;
+        commands::set_reindex_schedule_internal(schedule, &state)
+            .map_err(|e| FlashError::config("schedule", e))?;
+    }
+
+    let status = commands::get_schedule_status_internal(&state)
+        .map_err(|e| FlashError::config("schedule", e))?;
+    let format_time = |ts: Option<u64>| {
+        ts.and_then(|secs| jiff::Timestamp::from_second(i64::try_from(secs).ok()?).ok())
+            .map_or_else(
+                || "never".to_string(),
+                |t| {
+                    t.to_zoned(jiff::tz::TimeZone::system())
+                        .strftime("%Y-%m-%d %H:%M")
+                        .to_string()
+                },
+            )
+    };
+    println!("Schedule: {}", status.schedule);
+    println!("Last run: {}", format_time(status.last_run));
+    println!("Next run: {}", format_time(status.next_run));
+    Ok(())
+}
+
+/// Merges the search index into a single segment.
+fn run_optimize() -> Result<()> {
+    let (state, _) = setup_app()?;
+    match state.indexer.optimize()? {
+        0 => println!("Index is already a single segment."),
+        merged => println!("Merged {merged} segments into one."),
+    }
+    Ok(())
+}
+
+/// Writes a backup of the index to `dest`.
+fn run_backup(dest: &Path) -> Result<()> {
+    let (state, _) = setup_app()?;
+    let manifest = system::backup::create_backup(&state, &get_app_data_dir()?, dest)?;
+    println!(
+        "Backed up {} documents to {}",
+        manifest.documents,
+        dest.display()
+    );
+    Ok(())
+}
+
+/// Validates the backup at `archive` and stages it to replace the current
+/// index at the next start.
+fn run_restore(archive: &Path) -> Result<()> {
+    let manifest = system::backup::stage_restore(archive, &get_app_data_dir()?)?;
+    println!(
+        "Backup of {} documents from Flash Search {} will be restored the next time Flash Search starts.",
+        manifest.documents, manifest.app_version
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("flash-search").chain(args.iter().copied()))
+            .expect("arguments parse")
+    }
+
+    #[test]
+    fn test_legacy_flags_map_to_subcommands() {
+        let cli = parse(&["--cli", "needle", "--json"]);
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command(),
+            Some(Command::Search { query, limit: DEFAULT_RESULT_LIMIT, format: None, .. })
+                if query == "needle"
+        ));
+        assert!(matches!(
+            parse(&["-s", "needle"]).command(),
+            Some(Command::Search { .. })
+        ));
+        assert!(matches!(
+            parse(&["--parse-errors"]).command(),
+            Some(Command::ParseErrors)
+        ));
+        // A bare folder opens the window
+        let cli = parse(&["/some/folder"]);
+        assert!(cli.command().is_none());
+        assert_eq!(cli.dir, Some(PathBuf::from("/some/folder")));
+    }
+
+    #[test]
+    fn test_subcommands_parse() {
+        assert!(matches!(
+            parse(&["search", "needle", "-n", "5", "--format", "ndjson"]).command(),
+            Some(Command::Search {
+                limit: 5,
+                format: Some(OutputFormat::Ndjson),
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&["index", "--refresh", "/a", "/b"]).command(),
+            Some(Command::Index { paths, refresh: true, dry_run: false, .. }) if paths.len() == 2
+        ));
+        assert!(matches!(
+            parse(&["index", "--dry-run", "/a"]).command(),
+            Some(Command::Index {
+                dry_run: true,
+                refresh: false,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&["reindex", "/a/report.pdf"]).command(),
+            Some(Command::Reindex { paths }) if paths.len() == 1
+        ));
+        assert!(Cli::try_parse_from(["flash-search", "reindex"]).is_err());
+        assert!(matches!(
+            parse(&["similar", "/a/report.pdf", "-n", "3"]).command(),
+            Some(Command::Similar { limit: 3, .. })
+        ));
+        assert_eq!(format_eta(45), "45 s");
+        assert_eq!(format_eta(7500), "2 h 5 min");
+        assert!(matches!(
+            parse(&["search", "needle", "-0"]).command(),
+            Some(Command::Search {
+                null: true,
+                format: None,
+                ..
+            })
+        ));
+        assert!(
+            Cli::try_parse_from(["flash-search", "search", "x", "--paths-only", "-f", "csv"])
+                .is_err()
+        );
+        assert!(matches!(
+            parse(&["settings", "set", "theme", "dark"]).command(),
+            Some(Command::Settings {
+                action: SettingsCommand::Set { .. }
+            })
+        ));
+        // `purge` needs something to remove
+        assert!(Cli::try_parse_from(["flash-search", "purge"]).is_err());
+        assert!(Cli::try_parse_from(["flash-search", "purge", "--all", "/a"]).is_err());
+        assert_eq!(
+            parse(&["status", "--profile", "Work"]).profile.as_deref(),
+            Some("Work")
+        );
+    }
+
+    #[test]
+    fn test_result_formats() {
+        let results = [
+            ResultRecord {
+                path: "/docs/a.txt".to_string(),
+                title: Some("A, first".to_string()),
+                score: 1.5,
+                size: Some(10),
+                modified: Some(1_700_000_000),
+                snippet: Some("the needle".to_string()),
+            },
+            ResultRecord {
+                path: "/docs/b".to_string(),
+                title: None,
+                score: 0.5,
+                size: None,
+                modified: None,
+                snippet: None,
+            },
+        ];
+        let print = |format| {
+            let mut out = Vec::new();
+            print_results(&results, format, false, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            print(OutputFormat::Plain),
+            "1.5 | /docs/a.txt\n0.5 | /docs/b\n"
+        );
+        assert_eq!(
+            print(OutputFormat::Csv),
+            "path,title,score,size,modified,snippet\n\
+             /docs/a.txt,\"A, first\",1.5,10,1700000000,the needle\n\
+             /docs/b,,0.5,,,\n"
+        );
+        assert_eq!(print(OutputFormat::Paths), "/docs/a.txt\n/docs/b\n");
+        let mut out = Vec::new();
+        print_results(&results, OutputFormat::Paths, true, &mut out).unwrap();
+        assert_eq!(out, b"/docs/a.txt\0/docs/b\0");
+        let lines: Vec<ResultRecord> = print(OutputFormat::Ndjson)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, results);
+        let array: Vec<ResultRecord> = serde_json::from_str(&print(OutputFormat::Json)).unwrap();
+        assert_eq!(array, results);
+    }
+
+    #[test]
+    fn test_setting_keys_follow_dots() {
+        let mut settings = serde_json::json!({ "watcher": { "debounce_ms": 500 } });
+        *setting_mut(&mut settings, "watcher.debounce_ms").unwrap() = 250.into();
+        assert_eq!(settings["watcher"]["debounce_ms"], 250);
+        assert!(setting_mut(&mut settings, "watcher.nope").is_err());
+    }
+}
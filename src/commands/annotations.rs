@@ -0,0 +1,86 @@
+use crate::commands::AppState;
+use crate::metadata::FileAnnotation;
+use crate::parsers::{ParsedDocument, parse_file_isolated};
+use crate::scanner::sensitive::SensitiveMatcher;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Gets the note and attributes attached to a file.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub fn get_file_annotation_internal(
+    path: &str,
+    state: &Arc<AppState>,
+) -> Result<Option<FileAnnotation>, String> {
+    state
+        .metadata_db
+        .get_annotation(Path::new(path))
+        .map_err(|e| e.to_string())
+}
+
+/// Attaches a note and `key: value` attributes to a file and re-indexes it so
+/// the annotation becomes searchable. An empty note and attribute list removes
+/// the annotation.
+///
+/// # Errors
+///
+/// Returns an error if the annotation cannot be stored or the file cannot be
+/// re-indexed.
+pub async fn set_file_annotation_internal(
+    path: String,
+    note: String,
+    attributes: String,
+    state: Arc<AppState>,
+) -> Result<(), String> {
+    let file_path = Path::new(&path);
+    let annotation = FileAnnotation {
+        note: note.trim().to_string(),
+        attributes: FileAnnotation::parse_attributes(&attributes),
+        updated_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    state
+        .metadata_db
+        .set_annotation(file_path, &annotation)
+        .map_err(|e| e.to_string())?;
+
+    // Files that were never indexed pick the annotation up on their first scan
+    let Some(meta) = state
+        .metadata_db
+        .get_metadata(file_path)
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(());
+    };
+
+    // Content isn't stored in the index, so the document is rebuilt from disk
+    let settings = state.settings_cache.load();
+    let timeout = Duration::from_secs(settings.parse_timeout_secs.max(1));
+    let mut doc = if SensitiveMatcher::from_settings(&settings).hides_content(file_path) {
+        ParsedDocument::filename_only(file_path)
+    } else {
+        parse_file_isolated(file_path, settings.enable_ocr, timeout)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    doc.annotations = (!annotation.is_empty()).then(|| annotation.search_text());
+    doc.attributes = meta.attributes;
+
+    state
+        .indexer
+        .remove_document(&path)
+        .map_err(|e| e.to_string())?;
+    state
+        .indexer
+        .add_document(&doc, meta.modified, meta.size)
+        .map_err(|e| e.to_string())?;
+    state.indexer.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::commands::AppState;
+use crate::system::backup::{self, BackupManifest};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Snapshots the search index, the metadata and the filename index into one
+/// archive at `dest`.
+///
+/// # Errors
+///
+/// Returns an error if a store cannot be read or the archive cannot be written.
+pub async fn create_backup_internal(
+    state: &Arc<AppState>,
+    dest: PathBuf,
+) -> Result<BackupManifest, String> {
+    let app_data_dir = crate::get_app_data_dir().map_err(|e| e.to_string())?;
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || backup::create_backup(&state, &app_data_dir, &dest))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Validates the archive at `archive` and stages it. It replaces the current
+/// index the next time the app starts.
+///
+/// # Errors
+///
+/// Returns an error if the archive is unreadable, was made by an incompatible
+/// version, or cannot be unpacked.
+pub async fn restore_backup_internal(archive: PathBuf) -> Result<BackupManifest, String> {
+    let app_data_dir = crate::get_app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || backup::stage_restore(&archive, &app_data_dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
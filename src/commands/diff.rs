@@ -0,0 +1,102 @@
+use crate::commands::AppState;
+use crate::models::{ContentDiff, DiffLine};
+use crate::parsers::parse_file_isolated;
+use similar::{ChangeTag, TextDiff};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Unchanged lines kept on either side of a change
+const DIFF_CONTEXT: usize = 3;
+
+/// Compares the parsed text of two files, so copies in different formats or
+/// with different metadata can be told apart by what they actually say.
+///
+/// # Errors
+///
+/// Returns an error if either file cannot be parsed.
+pub async fn diff_files_internal(
+    left: String,
+    right: String,
+    state: &Arc<AppState>,
+) -> Result<ContentDiff, String> {
+    let (enable_ocr, timeout) = {
+        let settings = state.settings_cache.load();
+        (
+            settings.enable_ocr,
+            Duration::from_secs(settings.parse_timeout_secs.max(1)),
+        )
+    };
+    let (old, new) = tokio::try_join!(
+        parse_file_isolated(Path::new(&left), enable_ocr, timeout),
+        parse_file_isolated(Path::new(&right), enable_ocr, timeout),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(diff_texts(left, right, &old.content, &new.content))
+}
+
+/// Diffs `old`, the text of `left`, against `new`, the text of `right`, by
+/// line. Runs of unchanged lines away from any change collapse into
+/// [`DiffLine::Skipped`]; identical texts give no lines at all.
+pub fn diff_texts(left: String, right: String, old: &str, new: &str) -> ContentDiff {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+    let (mut added, mut removed) = (0, 0);
+    let mut shown_until = 0;
+    for group in diff.grouped_ops(DIFF_CONTEXT) {
+        let start = group.first().map_or(shown_until, |op| op.old_range().start);
+        if start > shown_until {
+            lines.push(DiffLine::Skipped(start - shown_until));
+        }
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches(['\r', '\n']).to_string();
+                lines.push(match change.tag() {
+                    ChangeTag::Equal => DiffLine::Same(line),
+                    ChangeTag::Delete => {
+                        removed += 1;
+                        DiffLine::Removed(line)
+                    }
+                    ChangeTag::Insert => {
+                        added += 1;
+                        DiffLine::Added(line)
+                    }
+                });
+            }
+        }
+        shown_until = group.last().map_or(shown_until, |op| op.old_range().end);
+    }
+    let total = diff.old_slices().len();
+    if !lines.is_empty() && total > shown_until {
+        lines.push(DiffLine::Skipped(total - shown_until));
+    }
+
+    ContentDiff {
+        left,
+        right,
+        lines,
+        added,
+        removed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_texts_keeps_context_around_changes() {
+        let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let new = old.replace("line 10\n", "line ten\n");
+        let diff = diff_texts("a".into(), "b".into(), &old, &new);
+
+        assert_eq!((diff.added, diff.removed), (1, 1));
+        assert_eq!(diff.lines.first(), Some(&DiffLine::Skipped(6)));
+        assert!(diff.lines.contains(&DiffLine::Removed("line 10".into())));
+        assert!(diff.lines.contains(&DiffLine::Added("line ten".into())));
+        assert_eq!(diff.lines.last(), Some(&DiffLine::Skipped(7)));
+
+        let same = diff_texts("a".into(), "b".into(), &old, &old);
+        assert!(same.lines.is_empty());
+    }
+}
@@ -1,16 +1,60 @@
 use crate::indexer::searcher::SearchResult;
+use crate::system::ipc::plain_snippet;
+
+/// Formats `export_results` writes, by file extension
+pub const EXPORT_FORMATS: &[&str] = &["csv", "json", "md"];
+
+fn timestamp(secs: Option<u64>) -> String {
+    secs.and_then(|secs| jiff::Timestamp::from_second(i64::try_from(secs).ok()?).ok())
+        .map(|ts| ts.to_string())
+        .unwrap_or_default()
+}
+
+fn first_snippet(result: &SearchResult) -> String {
+    result
+        .snippets
+        .first()
+        .map(|html| plain_snippet(html))
+        .unwrap_or_default()
+}
+
+/// Writes `results` to `path` as `format`, one of [`EXPORT_FORMATS`].
+pub fn export_results(results: &[SearchResult], format: &str, path: &str) -> Result<(), String> {
+    match format {
+        "csv" => export_results_csv(results, path),
+        "json" => export_results_json(results, path),
+        "md" => export_results_markdown(results, path),
+        _ => Err(format!("Unknown export format {format}")),
+    }
+}
 
 pub fn export_results_csv(results: &[SearchResult], path: &str) -> Result<(), String> {
     let mut wtr = csv::Writer::from_path(path).map_err(|e| e.to_string())?;
 
     // Write header
-    wtr.write_record(["Score", "File Path", "Title"])
-        .map_err(|e| e.to_string())?;
+    wtr.write_record([
+        "Score",
+        "File Path",
+        "Title",
+        "Size",
+        "Modified",
+        "Created",
+        "Snippet",
+    ])
+    .map_err(|e| e.to_string())?;
 
     for r in results {
         let title = r.title.as_deref().unwrap_or("");
-        wtr.write_record(&[r.score.to_string(), r.file_path.clone(), title.to_string()])
-            .map_err(|e| e.to_string())?;
+        wtr.write_record(&[
+            r.score.to_string(),
+            r.file_path.clone(),
+            title.to_string(),
+            r.size.map(|size| size.to_string()).unwrap_or_default(),
+            timestamp(r.modified),
+            timestamp(r.created),
+            first_snippet(r),
+        ])
+        .map_err(|e| e.to_string())?;
     }
 
     wtr.flush().map_err(|e| e.to_string())?;
@@ -22,3 +66,22 @@ pub fn export_results_json(results: &[SearchResult], path: &str) -> Result<(), S
     std::fs::write(path, json).map_err(|e| e.to_string())?;
     Ok(())
 }
+
+/// Writes `results` as a Markdown table.
+pub fn export_results_markdown(results: &[SearchResult], path: &str) -> Result<(), String> {
+    // Pipes would end the cell and line breaks the row
+    let cell = |text: &str| text.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let mut table =
+        String::from("| Title | File Path | Size | Modified | Snippet |\n|---|---|---|---|---|\n");
+    for r in results {
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            cell(r.title.as_deref().unwrap_or("")),
+            cell(&r.file_path),
+            r.size.map(|size| size.to_string()).unwrap_or_default(),
+            timestamp(r.modified),
+            cell(&first_snippet(r)),
+        ));
+    }
+    std::fs::write(path, table).map_err(|e| e.to_string())
+}
@@ -1,9 +1,19 @@
 use crate::commands::AppState;
+use crate::indexer::integrity;
 use crate::indexer::searcher::IndexStatistics;
-use crate::models::{IndexStatus, RecentFile};
-use std::path::PathBuf;
+use crate::metadata::{FileAttributes, FileMetadata, ParseErrorRecord};
+use crate::models::{
+    DetailedStatistics, IndexStatus, IndexedFileSize, IntegrityReport, PipelineStatus, RecentFile,
+    ScanEstimate, UsageGroup, WatcherStatus,
+};
+use crate::parsers::ParsedDocument;
+use crate::scanner::exclude::ExcludeMatcher;
+use crate::settings::{AppSettings, IndexRoot, IndexSizePolicy};
+use crate::system::activity::{self, Activity, ActivityEntry, Source};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, info, warn};
 
 /// Starts the indexing process.
 ///
@@ -11,7 +21,92 @@ use tracing::error;
 ///
 /// Returns an error if the indexing task cannot be spawned or fails.
 pub async fn start_indexing_internal(path: String, state: Arc<AppState>) -> Result<(), String> {
-    let path = PathBuf::from(path);
+    run_indexing(vec![PathBuf::from(path)], state).await
+}
+
+/// Files the dry run walks before it stops counting
+pub const SCAN_ESTIMATE_CAP: usize = 500_000;
+
+/// Dry run of indexing `path` with its root profile and the current
+/// settings: what would be parsed and roughly how long it takes.
+///
+/// # Errors
+///
+/// Returns an error if `path` is not a folder or the walk cannot run.
+pub async fn estimate_scan_internal(
+    path: String,
+    state: &Arc<AppState>,
+) -> Result<ScanEstimate, String> {
+    if !Path::new(&path).is_dir() {
+        return Err(format!("{path} is not a folder"));
+    }
+    let settings = state.settings_cache.load_full();
+    tokio::task::spawn_blocking(move || {
+        let profile = settings.root_profile(Path::new(&path));
+        crate::scanner::estimate::estimate_scan(&profile, &settings, SCAN_ESTIMATE_CAP)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Configured index roots, falling back to the home directory when none are set.
+#[must_use]
+pub fn index_roots_internal(index_roots: Vec<IndexRoot>) -> Vec<IndexRoot> {
+    if index_roots.is_empty() {
+        crate::commands::get_home_dir_internal()
+            .ok()
+            .map(IndexRoot::new)
+            .into_iter()
+            .collect()
+    } else {
+        index_roots
+    }
+}
+
+/// Cancels the running scan. Batches already written stay indexed and the
+/// scan remains pending so [`resume_indexing_internal`] can pick it up.
+pub fn cancel_indexing_internal(state: &Arc<AppState>) {
+    state
+        .indexing_cancel
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    // Paused stages only notice the cancel once they wake up
+    state.scanner.resume();
+}
+
+/// Pauses the running scan between batches.
+pub fn pause_indexing_internal(state: &Arc<AppState>) {
+    state.scanner.pause();
+}
+
+/// Resumes a paused scan, or restarts scans that were cancelled or cut short
+/// by an exit. Files committed before the interruption are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the pending scans cannot be read.
+pub async fn resume_indexing_internal(state: Arc<AppState>) -> Result<(), String> {
+    if state.scanner.is_paused() {
+        state.scanner.resume();
+        return Ok(());
+    }
+
+    let pending: Vec<PathBuf> = state
+        .metadata_db
+        .get_pending_scans()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|checkpoint| PathBuf::from(checkpoint.root))
+        .filter(|root| root.exists())
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    run_indexing(pending, state).await
+}
+
+/// Replaces any running scan with one that walks `roots`, several at a time.
+async fn run_indexing(roots: Vec<PathBuf>, state: Arc<AppState>) -> Result<(), String> {
     let previous_handle = {
         let mut handle_guard = state.indexing_handle.lock();
         handle_guard.take()
@@ -19,16 +114,12 @@ pub async fn start_indexing_internal(path: String, state: Arc<AppState>) -> Resu
 
     // Gracefully cancel previous indexing if still running
     if let Some(handle) = previous_handle {
-        state
-            .indexing_cancel
-            .store(true, std::sync::atomic::Ordering::Relaxed);
+        cancel_indexing_internal(&state);
         let _ = handle.await;
     }
 
-    // Reset the cancel flag for the new indexing run
-    state
-        .indexing_cancel
-        .store(false, std::sync::atomic::Ordering::Relaxed);
+    // Reset the control flags for the new indexing run
+    state.reset_scan_control();
 
     let mut handle_guard = state.indexing_handle.lock();
     let state_clone = state.clone();
@@ -36,17 +127,18 @@ pub async fn start_indexing_internal(path: String, state: Arc<AppState>) -> Resu
 
     let handle = tokio::spawn(async move {
         let settings = state_clone.settings_cache.load();
-        let mut exclude_patterns = settings.exclude_patterns.clone();
-        for folder in &settings.exclude_folders {
-            exclude_patterns.push(folder.clone());
-        }
+        let exclude_patterns = settings.exclude_list();
 
-        if let Err(e) = state_clone
+        let roots = roots
+            .iter()
+            .map(|path| settings.root_profile(path))
+            .collect();
+        let failures = state_clone
             .scanner
-            .scan_directory(path, exclude_patterns, cancel_flag)
-            .await
-        {
-            error!("Indexing error: {}", e);
+            .scan_roots(roots, exclude_patterns, cancel_flag)
+            .await;
+        for (root, e) in failures {
+            error!("Indexing error in {}: {}", root, e);
         }
     });
 
@@ -55,13 +147,431 @@ pub async fn start_indexing_internal(path: String, state: Arc<AppState>) -> Resu
     Ok(())
 }
 
-/// Gets the current status of the indexer.
+/// Reports which roots get live updates and how the watcher is keeping up.
+#[must_use]
+pub fn get_watcher_status_internal(state: &Arc<AppState>) -> WatcherStatus {
+    state.watcher.lock().status()
+}
+
+/// Reports how full the scan pipeline's queues and memory budget are.
+#[must_use]
+pub fn get_pipeline_status_internal(state: &Arc<AppState>) -> PipelineStatus {
+    state.scanner.pipeline_status()
+}
+
+/// Cross-checks the metadata store, the search index and the filename index.
+/// With `repair`, the offenders are re-indexed or pruned in the background
+/// through the watcher's crash recovery.
+///
+/// # Errors
+///
+/// Returns an error if a store cannot be read or the repair cannot be queued.
+pub async fn verify_index_internal(
+    state: &Arc<AppState>,
+    repair: bool,
+) -> Result<IntegrityReport, String> {
+    let state_for_check = state.clone();
+    let mut report = tokio::task::spawn_blocking(move || {
+        integrity::verify_index(
+            &state_for_check.indexer,
+            &state_for_check.metadata_db,
+            state_for_check.filename_index.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    if repair && !report.is_consistent() {
+        integrity::repair_index(&report, &state.metadata_db).map_err(|e| e.to_string())?;
+        state
+            .watcher
+            .lock()
+            .recover_pending_batches()
+            .map_err(|e| e.to_string())?;
+        report.repaired = true;
+    }
+    Ok(report)
+}
+
+/// Force-merges the search index into a single segment. Refused while a scan
+/// is running, since its commits keep adding segments.
+///
+/// # Errors
+///
+/// Returns an error if indexing is in progress or the merge fails.
+pub async fn optimize_index_internal(state: &Arc<AppState>) -> Result<usize, String> {
+    let busy = state
+        .indexing_handle
+        .lock()
+        .as_ref()
+        .is_some_and(|handle| !handle.is_finished());
+    if busy {
+        return Err("Indexing is in progress; optimize once it has finished".to_string());
+    }
+    let indexer = state.indexer.clone();
+    tokio::task::spawn_blocking(move || indexer.optimize())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Warms every index shard so the first searches of a session don't stall
+/// while their segments are paged in. Returns how many segments were warmed.
+///
+/// # Errors
+///
+/// Returns an error if a segment cannot be read.
+pub async fn warm_index_internal(state: &Arc<AppState>) -> Result<usize, String> {
+    let indexer = state.indexer.clone();
+    tokio::task::spawn_blocking(move || indexer.warm())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Brings the index back under the size cap from the settings once it has
+/// outgrown it. Per the cap's policy, the lowest-priority root still indexing
+/// content falls back to names only, or the documents modified longest ago
+/// lose their content. Each call gives up one step's worth, and nothing while
+/// a scan runs. Returns whether anything was given up.
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read or rewritten, or the settings
+/// cannot be saved.
+pub async fn enforce_index_size_cap(state: &Arc<AppState>) -> Result<bool, String> {
+    let settings = state.settings_cache.load_full();
+    let Some(limit) = settings.index_size_limit_bytes() else {
+        return Ok(false);
+    };
+    let busy = state
+        .indexing_handle
+        .lock()
+        .as_ref()
+        .is_some_and(|handle| !handle.is_finished());
+    if busy {
+        return Ok(false);
+    }
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || {
+        let size = state.indexer.get_statistics().map_err(|e| e.to_string())?;
+        if size.total_size_bytes <= limit {
+            return Ok(false);
+        }
+        match settings.index_size_policy {
+            IndexSizePolicy::FilenameOnly => cap_lowest_priority_root(&state, &settings),
+            IndexSizePolicy::EvictOldest => {
+                evict_oldest_content(&state, size.total_size_bytes, size.total_documents, limit)
+            }
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Switches the last root in the list that still indexes content to names
+/// only, and indexes it again that way.
+fn cap_lowest_priority_root(state: &Arc<AppState>, settings: &AppSettings) -> Result<bool, String> {
+    let Some(root) = settings
+        .index_roots
+        .iter()
+        .rev()
+        .find(|root| root.indexes_content())
+    else {
+        warn!("Index is over its size cap, but no root indexes content anymore");
+        return Ok(false);
+    };
+    info!(
+        "Index is over its size cap; {} falls back to names only",
+        root.path
+    );
+    let path = PathBuf::from(&root.path);
+    let mut capped = settings.clone();
+    for entry in &mut capped.index_roots {
+        entry.content_capped |= entry.path == root.path;
+    }
+    super::save_settings_internal(&capped, state)?;
+
+    state.indexer.clear_root(&path).map_err(|e| e.to_string())?;
+    // Reclaim the cleared documents' space before the root comes back
+    state.indexer.optimize().map_err(|e| e.to_string())?;
+    state
+        .metadata_db
+        .remove_tree(&path)
+        .map_err(|e| e.to_string())?;
+    state.watcher.lock().queue_refresh(&path);
+    Ok(true)
+}
+
+/// Re-indexes the documents modified longest ago by name only. Each document
+/// is assumed to take an even share of the index, and enough of them go to
+/// land a tenth below the cap, so the next check doesn't evict right away.
+fn evict_oldest_content(
+    state: &Arc<AppState>,
+    size: u64,
+    documents: usize,
+    limit: u64,
+) -> Result<bool, String> {
+    let files = state
+        .indexer
+        .get_files_with_content()
+        .map_err(|e| e.to_string())?;
+    if files.is_empty() {
+        warn!("Index is over its size cap, but no document has content left to evict");
+        return Ok(false);
+    }
+    let per_document = (size / u64::try_from(documents.max(1)).unwrap_or(u64::MAX)).max(1);
+    let excess = size.saturating_sub(limit - limit / 10);
+    let count = usize::try_from(excess.div_ceil(per_document)).unwrap_or(usize::MAX);
+
+    let docs: Vec<(ParsedDocument, u64, u64)> = files
+        .into_iter()
+        .take(count)
+        .map(|(path, modified, file_size)| {
+            let mut doc = ParsedDocument::filename_only(Path::new(&path));
+            doc.attributes = FileAttributes::read(Path::new(&path));
+            doc.annotations = state
+                .metadata_db
+                .get_annotation(Path::new(&path))
+                .ok()
+                .flatten()
+                .map(|a| a.search_text());
+            (doc, modified, file_size)
+        })
+        .collect();
+    state
+        .indexer
+        .add_documents_batch(&docs)
+        .and_then(|()| state.indexer.commit())
+        .and_then(|()| state.indexer.optimize())
+        .map_err(|e| e.to_string())?;
+    info!(
+        "Index is over its size cap; dropped the content of the {} oldest documents",
+        docs.len()
+    );
+    Ok(true)
+}
+
+/// Opens and drops index shards to match the separate-index flag of each
+/// root. A root whose documents changed index loses its metadata records and
+/// is queued for a catch-up refresh, which indexes it afresh.
+///
+/// # Errors
+///
+/// Returns an error if a shard cannot be opened or the metadata update fails.
+pub fn sync_index_shards(state: &Arc<AppState>, roots: &[IndexRoot]) -> Result<(), String> {
+    let moved = state
+        .indexer
+        .sync_shards(roots)
+        .map_err(|e| e.to_string())?;
+    for root in moved {
+        state
+            .metadata_db
+            .remove_tree(&root)
+            .map_err(|e| e.to_string())?;
+        state.watcher.lock().queue_refresh(&root);
+    }
+    Ok(())
+}
+
+/// Empties the index holding `root` and scans it again from scratch. Other
+/// roots keep their documents; a root with a separate index only has its own
+/// shard cleared.
+///
+/// # Errors
+///
+/// Returns an error if the index or metadata cannot be cleared, or the scan
+/// cannot be started.
+pub async fn rebuild_root_internal(root: String, state: Arc<AppState>) -> Result<(), String> {
+    let root = PathBuf::from(root);
+    state.indexer.clear_root(&root).map_err(|e| e.to_string())?;
+    state
+        .metadata_db
+        .remove_tree(&root)
+        .map_err(|e| e.to_string())?;
+    run_indexing(vec![root], state).await
+}
+
+/// Parses `path`, or every file beneath it, again and commits, even if
+/// nothing changed since it was last indexed. Returns how many files were
+/// found.
+///
+/// # Errors
+///
+/// Returns an error if `path` doesn't lie under an indexed root, or the
+/// files cannot be indexed.
+pub async fn reindex_path_internal(path: &Path, state: Arc<AppState>) -> Result<usize, String> {
+    let settings = state.settings_cache.load_full();
+    let root = settings
+        .index_roots
+        .iter()
+        .filter(|root| path.starts_with(&root.path))
+        .max_by_key(|root| root.path.len())
+        .cloned()
+        .ok_or_else(|| format!("{} is not under an indexed folder", path.display()))?;
+    state
+        .scanner
+        .reindex_path(
+            root,
+            path,
+            settings.exclude_list(),
+            Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Drops `path`, and everything recorded beneath it, from the metadata
+/// store, the search index and the filename index. The files stay on disk
+/// and come back with the next full scan of their root. Returns how many
+/// files were removed.
+///
+/// # Errors
+///
+/// Returns an error if a store cannot be updated.
+pub fn purge_path_internal(path: &Path, state: &Arc<AppState>) -> Result<usize, String> {
+    purge_paths(&[path], state)
+}
+
+/// Moves `paths` to the Recycle Bin or Trash, where they can be restored
+/// from, and drops them from the three stores right away rather than waiting
+/// for the watcher. Returns how many indexed files were removed.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be moved, in which case the index is
+/// left alone, or if a store cannot be updated.
+pub fn trash_paths_internal(paths: &[String], state: &Arc<AppState>) -> Result<usize, String> {
+    trash::delete_all(paths).map_err(|e| e.to_string())?;
+    purge_paths(paths, state)
+}
+
+/// Removes `paths` and what is beneath them from the stores, committing once.
+fn purge_paths(paths: &[impl AsRef<Path>], state: &Arc<AppState>) -> Result<usize, String> {
+    let mut removed = Vec::new();
+    for path in paths {
+        removed.extend(
+            state
+                .metadata_db
+                .remove_tree(path.as_ref())
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    state
+        .indexer
+        .remove_documents_batch(&removed)
+        .and_then(|()| state.indexer.commit())
+        .map_err(|e| e.to_string())?;
+    if let Some(filename_index) = &state.filename_index {
+        for path in paths {
+            filename_index
+                .remove_file(&path.as_ref().to_string_lossy())
+                .map_err(|e| e.to_string())?;
+        }
+        filename_index.commit().map_err(|e| e.to_string())?;
+    }
+    record_removed(&removed);
+    Ok(removed.len())
+}
+
+/// Records files removed from the index by hand in the activity log.
+fn record_removed(paths: &[String]) {
+    for path in paths {
+        activity::record(|| Activity::Removed {
+            path: path.clone(),
+            source: Source::User,
+        });
+    }
+}
+
+/// Adds `pattern` to the exclude patterns and drops what it matches from the
+/// three stores. The watcher gets the new pattern first, so changes to the
+/// files stop being indexed before they are removed. `pattern` is an absolute
+/// file or folder path, or a gitignore-style pattern such as `*.log` applied
+/// under every root. Returns how many files were removed.
+///
+/// # Errors
+///
+/// Returns an error if the settings cannot be saved or a store cannot be
+/// updated.
+pub fn exclude_from_index_internal(pattern: &str, state: &Arc<AppState>) -> Result<usize, String> {
+    let pattern = pattern.trim().to_string();
+    if pattern.is_empty() {
+        return Err("Nothing to exclude".to_string());
+    }
+    let mut settings = state.settings_cache.load().as_ref().clone();
+    if !settings.exclude_patterns.contains(&pattern) {
+        settings.exclude_patterns.push(pattern.clone());
+        state
+            .settings_manager
+            .save(&settings)
+            .map_err(|e| e.to_string())?;
+        state
+            .watcher
+            .lock()
+            .update_watch_list(&settings.index_roots, &settings.exclude_list())
+            .map_err(|e| e.to_string())?;
+        state.settings_cache.store(Arc::new(settings.clone()));
+    }
+
+    if Path::new(&pattern).is_absolute() {
+        return purge_path_internal(Path::new(&pattern), state);
+    }
+    let matchers: Vec<ExcludeMatcher> = settings
+        .index_roots
+        .iter()
+        .map(|root| ExcludeMatcher::new(Path::new(&root.path), [&pattern]))
+        .collect();
+    let removed = state
+        .metadata_db
+        .remove_matching(|path| {
+            matchers
+                .iter()
+                .any(|matcher| matcher.is_excluded(Path::new(path), false))
+        })
+        .map_err(|e| e.to_string())?;
+    state
+        .indexer
+        .remove_documents_batch(&removed)
+        .and_then(|()| state.indexer.commit())
+        .map_err(|e| e.to_string())?;
+    if let Some(filename_index) = &state.filename_index {
+        filename_index
+            .remove_files_batch(removed.iter().cloned())
+            .and_then(|()| filename_index.commit())
+            .map_err(|e| e.to_string())?;
+    }
+    record_removed(&removed);
+    Ok(removed.len())
+}
+
+/// Empties the metadata store, the search index and the filename index.
+///
+/// # Errors
+///
+/// Returns an error if a store cannot be cleared.
+pub fn purge_index_internal(state: &Arc<AppState>) -> Result<(), String> {
+    state
+        .indexer
+        .clear()
+        .and_then(|()| state.indexer.commit())
+        .map_err(|e| e.to_string())?;
+    state.metadata_db.clear().map_err(|e| e.to_string())?;
+    if let Some(filename_index) = &state.filename_index {
+        filename_index.clear().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Gets the current status of the indexer, with the progress of each root
+/// of the current or last scan run.
 ///
 /// # Errors
 ///
 /// Returns an error if the index statistics cannot be retrieved.
 pub async fn get_index_status_internal(state: &Arc<AppState>) -> Result<IndexStatus, String> {
-    let is_running = {
+    let handle_running = {
         let mut handle_guard = state.indexing_handle.lock();
         let finished = handle_guard
             .as_ref()
@@ -73,18 +583,35 @@ pub async fn get_index_status_internal(state: &Arc<AppState>) -> Result<IndexSta
             handle_guard.is_some()
         }
     };
+    // Scans started from the UI or the scheduler run without a handle
+    let run = state.scanner.scan_status();
+    let is_running = handle_running || run.running;
 
-    let status = if is_running {
+    let status = if is_running && state.scanner.is_paused() {
+        "paused".to_string()
+    } else if is_running {
         "indexing".to_string()
     } else {
         "idle".to_string()
     };
 
     let index_stats = state.indexer.get_statistics().map_err(|e| e.to_string())?;
+    let roots = run.roots;
 
     Ok(IndexStatus {
         status,
         files_indexed: index_stats.total_documents,
+        operation: run.operation,
+        current_root: run.current_root,
+        processed: roots.iter().map(|root| root.content.processed).sum(),
+        total: roots.iter().map(|root| root.content.total).sum(),
+        started_at: run.started_at,
+        skipped: roots.iter().map(|root| root.skipped).sum(),
+        no_access: roots.iter().map(|root| root.no_access).sum(),
+        errors: roots.iter().map(|root| root.errors).sum(),
+        roots,
+        pipeline: state.scanner.pipeline_status(),
+        throughput: run.throughput,
     })
 }
 
@@ -96,7 +623,124 @@ pub async fn get_index_status_internal(state: &Arc<AppState>) -> Result<IndexSta
 pub async fn get_index_statistics_internal(
     state: &Arc<AppState>,
 ) -> Result<IndexStatistics, String> {
-    state.indexer.get_statistics().map_err(|e| e.to_string())
+    let mut stats = state.indexer.get_statistics().map_err(|e| e.to_string())?;
+    let settings = state.settings_cache.load();
+    stats.size_limit_bytes = settings.index_size_limit_bytes();
+    stats.content_capped_roots = settings
+        .index_roots
+        .iter()
+        .filter(|root| root.content_capped)
+        .map(|root| root.path.clone())
+        .collect();
+    Ok(stats)
+}
+
+/// Largest documents listed in the detailed statistics
+pub const LARGEST_DOCUMENTS: usize = 20;
+
+/// Bytes taken by a file, or by everything beneath a folder.
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| disk_usage(&e.path())).sum())
+        .unwrap_or_default()
+}
+
+/// Groups tracked files by extension and by the root they sit under, and
+/// keeps the `largest` biggest. Files outside every root count only towards
+/// their extension.
+#[must_use]
+pub fn tally_usage(
+    mut files: Vec<FileMetadata>,
+    roots: &[IndexRoot],
+    largest: usize,
+) -> DetailedStatistics {
+    let mut by_extension: HashMap<String, UsageGroup> = HashMap::new();
+    let mut by_root: Vec<UsageGroup> = roots
+        .iter()
+        .map(|root| UsageGroup {
+            label: root.path.clone(),
+            ..UsageGroup::default()
+        })
+        .collect();
+    // Nested roots: a file belongs to the deepest root holding it
+    let mut deepest_first: Vec<usize> = (0..roots.len()).collect();
+    deepest_first.sort_by_key(|&i| std::cmp::Reverse(roots[i].path.len()));
+
+    for file in &files {
+        let path = Path::new(&file.path);
+        let extension = path.extension().map_or_else(
+            || "(none)".to_string(),
+            |e| e.to_string_lossy().to_lowercase(),
+        );
+        let group = by_extension
+            .entry(extension)
+            .or_insert_with_key(|label| UsageGroup {
+                label: label.clone(),
+                ..UsageGroup::default()
+            });
+        group.documents += 1;
+        group.bytes += file.size;
+        if let Some(&i) = deepest_first
+            .iter()
+            .find(|&&i| path.starts_with(&roots[i].path))
+        {
+            by_root[i].documents += 1;
+            by_root[i].bytes += file.size;
+        }
+    }
+
+    let mut by_extension: Vec<UsageGroup> = by_extension.into_values().collect();
+    by_extension.sort_by(|a, b| {
+        b.documents
+            .cmp(&a.documents)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    files.sort_unstable_by(|a, b| b.size.cmp(&a.size));
+    files.truncate(largest);
+    DetailedStatistics {
+        by_extension,
+        by_root,
+        largest_documents: files
+            .into_iter()
+            .map(|file| IndexedFileSize {
+                path: file.path,
+                size: file.size,
+            })
+            .collect(),
+        ..DetailedStatistics::default()
+    }
+}
+
+/// Index statistics broken down by extension and root, with the disk space
+/// each store takes and the largest indexed documents.
+///
+/// # Errors
+///
+/// Returns an error if the index or the metadata store cannot be read.
+pub async fn get_detailed_statistics_internal(
+    state: &Arc<AppState>,
+) -> Result<DetailedStatistics, String> {
+    let summary = get_index_statistics_internal(state).await?;
+    let app_data_dir = crate::get_app_data_dir().map_err(|e| e.to_string())?;
+    let roots = state.settings_cache.load().index_roots.clone();
+    let metadata_db = state.metadata_db.clone();
+    tokio::task::spawn_blocking(move || {
+        let files = metadata_db.get_all_metadata().map_err(|e| e.to_string())?;
+        Ok(DetailedStatistics {
+            summary,
+            metadata_db_bytes: disk_usage(&app_data_dir.join("metadata.redb")),
+            filename_index_bytes: disk_usage(&app_data_dir.join("filename_index")),
+            ..tally_usage(files, &roots, LARGEST_DOCUMENTS)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Gets a list of recently indexed files.
@@ -122,3 +766,41 @@ pub async fn get_recent_files_internal(
         })
         .collect())
 }
+
+/// Gets every file that failed to parse during indexing, most recent first.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub fn get_parse_errors_internal(state: &Arc<AppState>) -> Result<Vec<ParseErrorRecord>, String> {
+    state
+        .metadata_db
+        .get_parse_errors()
+        .map_err(|e| e.to_string())
+}
+
+/// Clears recorded parse failures so the next scan retries those files.
+///
+/// # Errors
+///
+/// Returns an error if the database write fails.
+pub fn clear_parse_errors_internal(state: &Arc<AppState>) -> Result<(), String> {
+    state
+        .metadata_db
+        .clear_parse_errors()
+        .map_err(|e| e.to_string())
+}
+
+/// Gets up to `limit` activity log entries mentioning `filter`, newest first.
+pub fn get_activity_internal(filter: &str, limit: usize) -> Vec<ActivityEntry> {
+    activity::recent(limit, filter)
+}
+
+/// Deletes the activity log.
+///
+/// # Errors
+///
+/// Returns an error if a log file cannot be deleted.
+pub fn clear_activity_internal() -> Result<(), String> {
+    activity::clear().map_err(|e| e.to_string())
+}
@@ -1,28 +1,56 @@
+mod annotations;
 mod autostart;
+mod backup;
+mod diff;
 mod export;
 mod indexing;
+mod schedule;
+mod scopes;
 mod search;
 mod settings;
 mod system;
 
+pub use annotations::{get_file_annotation_internal, set_file_annotation_internal};
 pub use autostart::{is_auto_start_enabled, set_auto_start};
-pub use export::{export_results_csv, export_results_json};
+pub use backup::{create_backup_internal, restore_backup_internal};
+pub use diff::{diff_files_internal, diff_texts};
+pub use export::{
+    EXPORT_FORMATS, export_results, export_results_csv, export_results_json,
+    export_results_markdown,
+};
 pub use indexing::{
-    get_index_statistics_internal, get_index_status_internal, get_recent_files_internal,
-    start_indexing_internal,
+    LARGEST_DOCUMENTS, SCAN_ESTIMATE_CAP, cancel_indexing_internal, clear_activity_internal,
+    clear_parse_errors_internal, enforce_index_size_cap, estimate_scan_internal,
+    exclude_from_index_internal, get_activity_internal, get_detailed_statistics_internal,
+    get_index_statistics_internal, get_index_status_internal, get_parse_errors_internal,
+    get_pipeline_status_internal, get_recent_files_internal, get_watcher_status_internal,
+    index_roots_internal, optimize_index_internal, pause_indexing_internal, purge_index_internal,
+    purge_path_internal, rebuild_root_internal, reindex_path_internal, resume_indexing_internal,
+    start_indexing_internal, sync_index_shards, trash_paths_internal, verify_index_internal,
+    warm_index_internal,
+};
+pub use schedule::{
+    get_schedule_status_internal, run_root_refresh_internal, run_scheduled_refresh_internal,
+    set_reindex_schedule_internal,
 };
 pub use search::{
-    get_file_preview_highlighted_internal, get_file_preview_internal,
-    get_filename_index_stats_internal, search_filenames_internal, search_query_internal,
+    expand_query_macros, get_file_details_internal, get_file_preview_highlighted_internal,
+    get_file_preview_internal, get_file_text_internal, get_filename_index_stats_internal,
+    get_hex_preview_internal, get_preview_kind_internal, group_by_folder, hex_dump,
+    locate_match_internal, more_like_this_internal, search_combined_internal,
+    search_filenames_internal, search_query_internal, spelling_suggestion_internal,
 };
 pub use settings::{
     add_recent_search_internal, add_search_history_internal, clear_recent_searches_internal,
-    get_pinned_files_internal, get_recent_searches_internal, get_search_history_internal,
-    get_settings_internal, pin_file_internal, save_settings_internal, unpin_file_internal,
+    clear_search_history_internal, delete_search_history_internal, get_pinned_files_internal,
+    get_recent_searches_internal, get_search_history_internal, get_settings_internal,
+    migrate_user_lists, pin_file_internal, pin_search_history_internal, rank_search_history,
+    reload_settings_internal, save_settings_internal, unpin_file_internal,
 };
 pub use system::{
-    copy_to_clipboard_internal, export_results_internal, get_home_dir_internal,
-    open_folder_internal, select_folder_internal,
+    copy_files_to_clipboard_internal, copy_to_clipboard_internal, export_results_internal,
+    get_home_dir_internal, open_file_internal, open_folder_internal, open_with_app_internal,
+    open_with_choices_internal, open_with_internal, select_folder_internal,
 };
 
 use crate::indexer::{IndexManager, filename_index::FilenameIndex};
@@ -63,12 +91,21 @@ impl AppState {
         scanner: Arc<crate::scanner::Scanner>,
         db_corrupted: bool,
     ) -> Self {
-        let cache = settings_manager.load().unwrap_or_else(|e| {
+        let mut cache = settings_manager.load().unwrap_or_else(|e| {
             tracing::warn!("Failed to load settings (using defaults): {}", e);
             AppSettings::default()
         });
+        match migrate_user_lists(&mut cache, &metadata_db) {
+            Ok(true) => {
+                if let Err(e) = settings_manager.save(&cache) {
+                    tracing::warn!("Failed to save settings after moving history: {}", e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to move search history out of settings: {}", e),
+        }
         let mut watcher = watcher;
-        let _ = watcher.update_watch_list(&cache.index_dirs);
+        let _ = watcher.update_watch_list(&cache.index_roots, &cache.exclude_list());
         Self {
             indexer,
             metadata_db,
@@ -83,6 +120,13 @@ impl AppState {
             db_corrupted,
         }
     }
+
+    /// Clears a previous cancel or pause before a new scan starts.
+    pub fn reset_scan_control(&self) {
+        self.indexing_cancel
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.scanner.resume();
+    }
 }
 
 #[derive(Default)]
@@ -187,6 +231,8 @@ mod tests {
             SearchResult::builder()
                 .file_path("test.txt".to_string())
                 .score(1.0)
+                .size(Some(2048))
+                .modified(Some(0))
                 .matched_terms(vec![])
                 .snippets(vec![])
                 .build(),
@@ -194,7 +240,209 @@ mod tests {
 
         export_results_csv(&results, csv_path.to_str().unwrap()).unwrap();
         let content = std::fs::read_to_string(csv_path).unwrap();
-        assert!(content.contains("Score,File Path,Title"));
-        assert!(content.contains("1,test.txt,"));
+        assert!(content.contains("Score,File Path,Title,Size,Modified,Created,Snippet"));
+        assert!(content.contains("1,test.txt,,2048,1970-01-01T00:00:00Z,"));
+    }
+
+    #[test]
+    fn test_export_markdown_escapes_cells() {
+        let temp_dir = tempdir().unwrap();
+        let md_path = temp_dir.path().join("results.md");
+        let results = vec![
+            SearchResult::builder()
+                .file_path("a|b.txt".to_string())
+                .score(1.0)
+                .size(Some(10))
+                .matched_terms(vec![])
+                .snippets(vec!["the <b>needle</b>\nhere".to_string()])
+                .build(),
+        ];
+
+        export_results(&results, "md", md_path.to_str().unwrap()).unwrap();
+        let content = std::fs::read_to_string(md_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[0],
+            "| Title | File Path | Size | Modified | Snippet |"
+        );
+        assert_eq!(lines[2], "|  | a\\|b.txt | 10 |  | the needle here |");
+        assert!(export_results(&results, "xml", md_path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_preview_window_centers_on_first_match() {
+        use crate::models::ElementType;
+        use crate::parsers::PreviewElement;
+        let element = |content: &str| PreviewElement {
+            element_type: ElementType::NarrativeText,
+            content: content.to_string(),
+        };
+        let elements = vec![
+            element(&"a".repeat(100)),
+            element(&"b".repeat(100)),
+            element("xx NEEDLE xx"),
+            element(&"c".repeat(100)),
+        ];
+        let terms = vec!["needle".to_string()];
+
+        let (kept, range) = search::preview_window(elements.clone(), &terms, false, 40);
+        assert_eq!(range, 183..223);
+        assert!(kept.iter().any(|e| e.content.contains("NEEDLE")));
+        let kept_len: usize = kept.iter().map(|e| e.content.len()).sum();
+        assert_eq!(kept_len, 40);
+
+        // Without a match the window starts at the top
+        let (_, range) = search::preview_window(elements.clone(), &[], false, 40);
+        assert_eq!(range, 0..40);
+        // Short documents are kept whole
+        let (kept, range) = search::preview_window(elements, &terms, false, 1000);
+        assert_eq!((kept.len(), range), (4, 0..312));
+    }
+
+    #[test]
+    fn test_first_match_finds_line_and_page() {
+        use crate::models::ElementType;
+        use crate::parsers::PreviewElement;
+        let terms = vec!["needle".to_string()];
+        let text = "first line\nsecond\r\nthe Needle is here\nneedle again\n";
+        assert_eq!(search::first_match_line(text, &terms, false), Some(3));
+        assert_eq!(search::first_match_line(text, &terms, true), Some(4));
+        assert_eq!(search::first_match_line(text, &[], false), None);
+
+        let element = |element_type, content: &str| PreviewElement {
+            element_type,
+            content: content.to_string(),
+        };
+        let pages = vec![
+            element(ElementType::Title, "Report"),
+            element(ElementType::PageBreak, ""),
+            element(ElementType::NarrativeText, "nothing"),
+            element(ElementType::PageBreak, ""),
+            element(ElementType::NarrativeText, "a needle"),
+        ];
+        assert_eq!(search::first_match_page(&pages, &terms, false), Some(3));
+        assert_eq!(
+            search::first_match_page(&pages, &["haystack".to_string()], false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hex_dump_lines() {
+        let dump = hex_dump(b"MZ\x90\0hello, world!\nabc");
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "00000000  4d 5a 90 00 68 65 6c 6c  6f 2c 20 77 6f 72 6c 64  MZ..hello, world"
+        );
+        assert!(lines[1].starts_with("00000010  21 0a 61 62 63 "));
+        assert!(lines[1].ends_with("  !.abc"));
+    }
+
+    #[test]
+    fn test_group_by_folder_keeps_result_order() {
+        let paths = ["/repo/a/x.rs", "/repo/b/y.rs", "/repo/a/z.rs", "top.txt"];
+        let groups = group_by_folder(&paths, |p| p);
+        let summary: Vec<(&str, &[usize])> = groups
+            .iter()
+            .map(|g| (g.folder.as_str(), g.indices.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/repo/a", &[0, 2][..]),
+                ("/repo/b", &[1][..]),
+                ("", &[3][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_history_ranks_pinned_first() {
+        let item = |query: &str, frequency, last_used, pinned| crate::models::SearchHistoryItem {
+            query: query.to_string(),
+            frequency,
+            last_used,
+            pinned,
+        };
+        let mut history = vec![
+            item("old", 3, 10, false),
+            item("kept", 1, 5, true),
+            item("busy", 9, 1, false),
+            item("new", 3, 20, false),
+        ];
+        rank_search_history(&mut history);
+        let order: Vec<&str> = history.iter().map(|h| h.query.as_str()).collect();
+        assert_eq!(order, ["kept", "busy", "new", "old"]);
+    }
+
+    #[test]
+    fn test_tally_usage_groups_by_extension_and_deepest_root() {
+        let file = |path: &str, size| crate::metadata::FileMetadata {
+            path: path.to_string(),
+            modified: 1,
+            size,
+            content_hash: [0; 32],
+            indexed_at: 1,
+            attributes: crate::metadata::FileAttributes::default(),
+        };
+        let files = vec![
+            file("/docs/a.PDF", 300),
+            file("/docs/b.pdf", 100),
+            file("/docs/work/c.txt", 50),
+            file("/docs/work/Makefile", 10),
+            file("/elsewhere/d.txt", 5),
+        ];
+        let roots = [
+            crate::settings::IndexRoot::new("/docs"),
+            crate::settings::IndexRoot::new("/docs/work"),
+        ];
+        let stats = tally_usage(files, &roots, 2);
+
+        let extensions: Vec<(&str, usize, u64)> = stats
+            .by_extension
+            .iter()
+            .map(|g| (g.label.as_str(), g.documents, g.bytes))
+            .collect();
+        assert_eq!(
+            extensions,
+            [("pdf", 2, 400), ("txt", 2, 55), ("(none)", 1, 10)]
+        );
+        assert_eq!(
+            (stats.by_root[0].documents, stats.by_root[0].bytes),
+            (2, 400)
+        );
+        assert_eq!(
+            (stats.by_root[1].documents, stats.by_root[1].bytes),
+            (2, 60)
+        );
+        let largest: Vec<&str> = stats
+            .largest_documents
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(largest, ["/docs/a.PDF", "/docs/b.pdf"]);
+    }
+
+    #[test]
+    fn test_user_lists_move_out_of_settings() {
+        let dir = tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let mut settings: AppSettings = serde_json::from_str(
+            r#"{"pinned_files": ["/a.txt"], "recent_searches": ["q"],
+                "search_history": [{"query": "q", "frequency": 2, "last_used": 5}]}"#,
+        )
+        .unwrap();
+
+        assert!(migrate_user_lists(&mut settings, &db).unwrap());
+        assert_eq!(db.get_pinned_files().unwrap(), ["/a.txt"]);
+        assert_eq!(db.get_recent_searches().unwrap(), ["q"]);
+        assert_eq!(db.get_search_history().unwrap()[0].frequency, 2);
+
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(saved.get("pinned_files").is_none());
+        assert!(saved.get("search_history").is_none());
+        assert!(!migrate_user_lists(&mut settings, &db).unwrap());
     }
 }
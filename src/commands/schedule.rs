@@ -0,0 +1,111 @@
+use crate::commands::AppState;
+use crate::models::ScheduleStatus;
+use crate::settings::{IndexRoot, ReindexSchedule};
+use crate::system::scheduler::next_run;
+use std::sync::Arc;
+use tracing::error;
+
+/// Gets the configured re-index schedule with its last and next run.
+///
+/// # Errors
+///
+/// Returns an error if the last run cannot be read.
+pub fn get_schedule_status_internal(state: &Arc<AppState>) -> Result<ScheduleStatus, String> {
+    let schedule = state.settings_cache.load().reindex_schedule;
+    let last_run = state
+        .metadata_db
+        .get_last_scheduled_run()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ScheduleStatus {
+        schedule,
+        last_run,
+        next_run: next_run(schedule, last_run, &jiff::Zoned::now()),
+    })
+}
+
+/// Saves a new re-index schedule. A running scheduler picks it up on its next tick.
+///
+/// # Errors
+///
+/// Returns an error if the settings cannot be saved.
+pub fn set_reindex_schedule_internal(
+    schedule: ReindexSchedule,
+    state: &Arc<AppState>,
+) -> Result<(), String> {
+    let mut cache = state.settings_cache.load().as_ref().clone();
+    cache.reindex_schedule = schedule;
+    state
+        .settings_manager
+        .save(&cache)
+        .map_err(|e| e.to_string())?;
+    state.settings_cache.store(Arc::new(cache));
+    Ok(())
+}
+
+/// Runs an incremental refresh over every index root without a schedule of
+/// its own and records it as the last scheduled run.
+///
+/// # Errors
+///
+/// Returns an error if the run cannot be recorded.
+pub async fn run_scheduled_refresh_internal(state: &Arc<AppState>) -> Result<(), String> {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let settings = state.settings_cache.load();
+    let exclude_patterns = settings.exclude_list();
+
+    let roots: Vec<_> = crate::commands::index_roots_internal(settings.index_roots.clone())
+        .into_iter()
+        .filter(|root| root.rescan.is_none())
+        .collect();
+    let failures = state
+        .scanner
+        .refresh_roots(roots, exclude_patterns, state.indexing_cancel.clone())
+        .await;
+    for (root, e) in failures {
+        error!("Scheduled refresh of {root} failed: {e}");
+    }
+
+    state
+        .metadata_db
+        .set_last_scheduled_run(started_at)
+        .map_err(|e| e.to_string())
+}
+
+/// Runs an incremental refresh over `roots`, whose own schedules are due, and
+/// records it as their last run.
+///
+/// # Errors
+///
+/// Returns an error if a run cannot be recorded.
+pub async fn run_root_refresh_internal(
+    state: &Arc<AppState>,
+    roots: Vec<IndexRoot>,
+) -> Result<(), String> {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let exclude_patterns = state.settings_cache.load().exclude_list();
+    let paths: Vec<String> = roots.iter().map(|root| root.path.clone()).collect();
+    let failures = state
+        .scanner
+        .refresh_roots(roots, exclude_patterns, state.indexing_cancel.clone())
+        .await;
+    for (root, e) in failures {
+        error!("Scheduled refresh of {root} failed: {e}");
+    }
+
+    for path in paths {
+        state
+            .metadata_db
+            .set_root_last_run(&path, started_at)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use crate::commands::AppState;
+use crate::indexer::searcher::SearchParams;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// How many of the most recently modified files `is:recent` covers
+pub const RECENT_SCOPE_FILES: usize = 200;
+
+/// How many history entries, best ranked first, `in:history` runs again
+const HISTORY_SCOPE_QUERIES: usize = 20;
+
+/// Results kept from each history query `in:history` runs again
+const HISTORY_SCOPE_RESULTS: usize = 500;
+
+/// Results fetched from the index for a scoped search before the scopes
+/// narrow them down to the requested limit
+pub const SCOPED_SEARCH_LIMIT: usize = 5000;
+
+/// Operators that limit a search to the user's own curation: `is:pinned`,
+/// `is:recent`, `tag:<name>` and `in:history`. They are taken out of the
+/// query before it reaches the index, and a result has to satisfy all of
+/// them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryScopes {
+    /// Pinned files only
+    pub pinned: bool,
+    /// The [`RECENT_SCOPE_FILES`] most recently modified files only
+    pub recent: bool,
+    /// Files that queries in the search history find
+    pub history: bool,
+    /// Files annotated with each of these `tag:` attributes
+    pub tags: Vec<String>,
+}
+
+impl QueryScopes {
+    /// Splits the scope operators off `query`, returning them and the rest of
+    /// the query. Without any scopes the query comes back unchanged.
+    #[must_use]
+    pub fn extract(query: &str) -> (Self, String) {
+        let mut scopes = Self::default();
+        let mut rest = Vec::new();
+        for token in query.split_whitespace() {
+            let lower = token.to_lowercase();
+            match lower.as_str() {
+                "is:pinned" => scopes.pinned = true,
+                "is:recent" => scopes.recent = true,
+                "in:history" => scopes.history = true,
+                _ => match lower.strip_prefix("tag:").map(|t| t.trim_matches('"')) {
+                    Some(tag) if !tag.is_empty() => {
+                        if !scopes.tags.iter().any(|t| t == tag) {
+                            scopes.tags.push(tag.to_string());
+                        }
+                    }
+                    _ => rest.push(token),
+                },
+            }
+        }
+        if scopes.is_empty() {
+            return (scopes, query.to_string());
+        }
+        (scopes, rest.join(" "))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        !self.pinned && !self.recent && !self.history && self.tags.is_empty()
+    }
+
+    /// Paths every scope allows, or `None` when there are no scopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metadata database or the index cannot be read.
+    pub async fn allowed_paths(
+        &self,
+        state: &Arc<AppState>,
+    ) -> Result<Option<HashSet<String>>, String> {
+        let mut allowed: Option<HashSet<String>> = None;
+        let mut narrow = |paths: HashSet<String>| {
+            allowed = Some(match allowed.take() {
+                Some(current) => current.intersection(&paths).cloned().collect(),
+                None => paths,
+            });
+        };
+
+        if self.pinned {
+            let pinned = state
+                .metadata_db
+                .get_pinned_files()
+                .map_err(|e| e.to_string())?;
+            narrow(pinned.into_iter().collect());
+        }
+        if self.recent {
+            let recent = state
+                .indexer
+                .get_recent_files(RECENT_SCOPE_FILES)
+                .map_err(|e| e.to_string())?;
+            narrow(recent.into_iter().map(|r| r.file_path).collect());
+        }
+        for tag in &self.tags {
+            let tagged = state
+                .metadata_db
+                .files_with_tag(tag)
+                .map_err(|e| e.to_string())?;
+            narrow(tagged.into_iter().collect());
+        }
+        if self.history {
+            narrow(history_paths(state).await?);
+        }
+        Ok(allowed)
+    }
+}
+
+/// Files found by the best ranked queries in the search history. Queries
+/// that carry scopes themselves are skipped.
+async fn history_paths(state: &Arc<AppState>) -> Result<HashSet<String>, String> {
+    let history = crate::commands::get_search_history_internal(usize::MAX, state)?;
+    let case_sensitive = state.settings_cache.load().case_sensitive;
+    let mut paths = HashSet::new();
+    for item in history
+        .iter()
+        .filter(|item| QueryScopes::extract(&item.query).0.is_empty())
+        .take(HISTORY_SCOPE_QUERIES)
+    {
+        let params = SearchParams::builder()
+            .query(&item.query)
+            .limit(HISTORY_SCOPE_RESULTS)
+            .case_sensitive(case_sensitive)
+            .build();
+        let results = state
+            .indexer
+            .search(params)
+            .await
+            .map_err(|e| e.to_string())?;
+        paths.extend(results.into_iter().map(|r| r.file_path));
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_scopes() {
+        let (scopes, rest) = QueryScopes::extract("budget is:pinned TAG:ProjectX ext:pdf");
+        assert!(scopes.pinned);
+        assert!(!scopes.recent);
+        assert_eq!(scopes.tags, vec!["projectx"]);
+        assert_eq!(rest, "budget ext:pdf");
+
+        let (scopes, rest) = QueryScopes::extract("in:history is:recent");
+        assert!(scopes.history && scopes.recent);
+        assert_eq!(rest, "");
+
+        let (scopes, rest) = QueryScopes::extract("report  is:hidden");
+        assert!(scopes.is_empty());
+        assert_eq!(rest, "report  is:hidden");
+    }
+}
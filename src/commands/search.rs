@@ -1,9 +1,19 @@
 use crate::commands::AppState;
-use crate::indexer::searcher::{SearchParams, SearchResult};
-use crate::models::{FilenameIndexStats, FilenameSearchResult, PreviewResult};
+use crate::commands::scopes::{QueryScopes, SCOPED_SEARCH_LIMIT};
+use crate::indexer::category::FileCategory;
+use crate::indexer::filename_index::{self, EntryKind, FilenameQuery};
+use crate::indexer::query_parser::{ParsedQuery, expand_macros};
+use crate::indexer::searcher::{SearchParams, SearchResult, edit_distance};
+use crate::models::{
+    ElementType, FileDetails, FilenameIndexStats, FilenameSearchResult, FolderGroup, MatchLocation,
+    PreviewKind, PreviewResult, SpellingSuggestion,
+};
 use crate::parsers::{PreviewElement, parse_file_preview};
+use crate::system::activity::{self, Activity, SearchKind};
 use iced::widget::text::Highlighter as _;
 use mini_moka::sync::Cache;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
@@ -18,20 +28,111 @@ fn get_preview_cache() -> &'static Cache<(String, u64), Vec<PreviewElement>> {
     })
 }
 
-/// Performs a search query against the index.
+/// Performs a search query against the index. `@name` macros in the query
+/// are expanded first.
 ///
 /// # Errors
 ///
-/// Returns an error if the search query fails.
+/// Returns an error if the query is malformed or the search fails.
 pub async fn search_query_internal(
     params: SearchParams<'_>,
     state: &Arc<AppState>,
 ) -> Result<Vec<SearchResult>, String> {
-    state
+    let query = &expand_query_macros(params.query, state);
+    ParsedQuery::validate(query).map_err(|e| e.to_string())?;
+    let (scopes, rest) = QueryScopes::extract(query);
+    let allowed = scopes.allowed_paths(state).await?;
+    let mut results = state
         .indexer
-        .search(params)
+        .search(scoped_params(&params, &rest, allowed.is_some()))
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    keep_allowed(&mut results, allowed.as_ref(), params.limit);
+    mark_stale(&mut results, state);
+    record_search(query, SearchKind::Content, results.len());
+    Ok(results)
+}
+
+/// `query` with the `@name` macros saved in the settings expanded. Every
+/// search entry point goes through this, so front ends pass what was typed.
+#[must_use]
+pub fn expand_query_macros(query: &str, state: &AppState) -> String {
+    expand_macros(query, &state.settings_cache.load().query_macros)
+}
+
+/// `params` searching for `rest`, the query without its scope operators.
+/// A scoped search fetches [`SCOPED_SEARCH_LIMIT`] results so enough are
+/// left once the scopes narrow them.
+fn scoped_params<'a>(params: &SearchParams<'a>, rest: &'a str, scoped: bool) -> SearchParams<'a> {
+    SearchParams {
+        query: rest,
+        limit: if scoped {
+            params.limit.max(SCOPED_SEARCH_LIMIT)
+        } else {
+            params.limit
+        },
+        ..params.clone()
+    }
+}
+
+/// Drops results outside `allowed`, when a scope set it, and keeps the best
+/// `limit` of the rest.
+fn keep_allowed(results: &mut Vec<SearchResult>, allowed: Option<&HashSet<String>>, limit: usize) {
+    if let Some(allowed) = allowed {
+        results.retain(|r| allowed.contains(&r.file_path));
+        results.truncate(limit);
+    }
+}
+
+/// Flags results whose file on disk is newer than the version the metadata
+/// database recorded when it was indexed. One read transaction covers the
+/// whole list, plus a stat per file; results of offline roots are skipped.
+fn mark_stale(results: &mut [SearchResult], state: &AppState) {
+    let paths: Vec<&str> = results.iter().map(|r| r.file_path.as_str()).collect();
+    let Ok(indexed) = state.metadata_db.batch_indexed_mtimes(&paths) else {
+        return;
+    };
+    for (result, indexed) in results.iter_mut().zip(indexed) {
+        let Some(indexed) = indexed.filter(|_| !result.unavailable && !result.is_dir) else {
+            continue;
+        };
+        result.stale = std::fs::metadata(&result.file_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .is_some_and(|on_disk| on_disk.as_secs() > indexed);
+    }
+}
+
+/// Records a search that ran in the activity log.
+fn record_search(query: &str, kind: SearchKind, results: usize) {
+    activity::record(|| Activity::Search {
+        query: query.to_string(),
+        kind,
+        results,
+    });
+}
+
+/// Groups results by their containing folder. Groups are ordered by their
+/// first result, so the list's ranking carries over to the folders.
+pub fn group_by_folder<T>(results: &[T], path: impl Fn(&T) -> &str) -> Vec<FolderGroup> {
+    let mut groups: Vec<FolderGroup> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (i, result) in results.iter().enumerate() {
+        let folder = std::path::Path::new(path(result))
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let pos = *positions.entry(folder.clone()).or_insert_with(|| {
+            groups.push(FolderGroup {
+                folder,
+                indices: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[pos].indices.push(i);
+    }
+    groups
 }
 
 /// Gets a preview of the file content.
@@ -70,36 +171,329 @@ pub async fn get_file_preview_internal(
     }
 }
 
-fn highlight_search_matches(
-    spans: Vec<(String, Option<[f32; 4]>)>,
-    matched_terms: &[String],
+/// The parsed text of a file, its elements separated by blank lines.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed.
+pub async fn get_file_text_internal(path: String, enable_ocr: bool) -> Result<String, String> {
+    let elements = get_file_preview_internal(path, enable_ocr).await?;
+    Ok(elements
+        .iter()
+        .map(|element| element.content.trim())
+        .filter(|content| !content.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+/// Image formats the preview pane can decode
+const PREVIEW_IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "ico", "tif", "tiff", "svg",
+];
+
+/// Text of a PDF's first page kept for its preview
+const PDF_PAGE_CHARS: usize = 3_000;
+
+/// Bytes sniffed to tell text from binary data
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Leading bytes of a binary file shown in its hex dump
+const HEX_PREVIEW_BYTES: usize = 4 * 1024;
+
+/// `bytes` as lines of a hex editor: offset, sixteen bytes in hex, and the
+/// bytes again as ASCII with `.` for anything unprintable.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut out = String::with_capacity(bytes.len() * 4 + bytes.len() / 16 * 12);
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x}  ", line * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(b) => {
+                    let _ = write!(out, "{b:02x} ");
+                }
+                None => out.push_str("   "),
+            }
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        out.push(' ');
+        out.extend(chunk.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                char::from(b)
+            } else {
+                '.'
+            }
+        }));
+        out.push('\n');
+    }
+    out
+}
+
+/// The hex dump preview of a file that has no readable text.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn get_hex_preview_internal(path: &str) -> Result<PreviewKind, String> {
+    use std::io::Read;
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut head = Vec::with_capacity(HEX_PREVIEW_BYTES);
+    file.take(HEX_PREVIEW_BYTES as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| e.to_string())?;
+    let ext = std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(PreviewKind::Binary {
+        mime: FileCategory::classify(&ext).1.to_string(),
+        hex: hex_dump(&head),
+        size,
+    })
+}
+
+/// Whether the start of the file looks like binary data.
+fn looks_binary(path: &std::path::Path) -> bool {
+    use std::io::Read;
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)
+        .and_then(|f| f.take(SNIFF_BYTES as u64).read_to_end(&mut head))
+        .is_ok_and(|_| head.contains(&0))
+}
+
+/// Decides how the preview pane shows `path`: images and the first page of
+/// PDFs are shown as such, media, archives and binary files as a hex dump,
+/// and everything else as parsed text.
+///
+/// # Errors
+///
+/// Returns an error if a PDF cannot be parsed or a binary file read.
+pub async fn get_preview_kind_internal(
+    path: String,
+    state: &Arc<AppState>,
+) -> Result<PreviewKind, String> {
+    let ext = std::path::Path::new(&path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (category, mime) = FileCategory::classify(&ext);
+
+    if PREVIEW_IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return Ok(PreviewKind::Image { path });
+    }
+    if ext == "pdf" {
+        let enable_ocr = state.settings_cache.load().enable_ocr;
+        let elements = get_file_preview_internal(path, enable_ocr).await?;
+        let pages = 1 + elements
+            .iter()
+            .filter(|e| e.element_type == ElementType::PageBreak)
+            .count();
+        let text = elements
+            .iter()
+            .take_while(|e| e.element_type != ElementType::PageBreak)
+            .map(|e| e.content.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .chars()
+            .take(PDF_PAGE_CHARS)
+            .collect();
+        return Ok(PreviewKind::PdfPage { text, pages });
+    }
+    let binary = match category {
+        FileCategory::Audio | FileCategory::Video | FileCategory::Archive => true,
+        FileCategory::Other => looks_binary(std::path::Path::new(&path)),
+        _ => false,
+    };
+    if binary {
+        get_hex_preview_internal(&path)
+    } else {
+        Ok(PreviewKind::Text)
+    }
+}
+
+/// Largest text file [`locate_match_internal`] reads to find a match's line
+const LOCATE_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Line, counted from 1, of the first match of `terms` in `text`.
+#[must_use]
+pub fn first_match_line(text: &str, terms: &[String], case_sensitive: bool) -> Option<usize> {
+    let re = terms_regex(terms, case_sensitive)?;
+    text.lines()
+        .position(|line| re.is_match(line))
+        .map(|i| i + 1)
+}
+
+/// Page, counted from 1, of the first element matching `terms`. Pages end at
+/// the parser's page breaks.
+#[must_use]
+pub fn first_match_page(
+    elements: &[PreviewElement],
+    terms: &[String],
     case_sensitive: bool,
-) -> Vec<(String, Option<[f32; 4]>)> {
-    if matched_terms.is_empty() {
-        return spans;
+) -> Option<usize> {
+    let re = terms_regex(terms, case_sensitive)?;
+    let mut page = 1;
+    for element in elements {
+        if element.element_type == ElementType::PageBreak {
+            page += 1;
+        } else if re.is_match(&element.content) {
+            return Some(page);
+        }
     }
+    None
+}
 
-    // Escape terms and build a pattern like: (term1|term2|...)
-    let pattern = matched_terms
+/// Where `terms` first match in `path`: the line of a text or source file,
+/// read as it is on disk so the line agrees with an editor's, or the page of
+/// a PDF. `None` for other files and files without a match.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed.
+pub async fn locate_match_internal(
+    path: String,
+    terms: Vec<String>,
+    case_sensitive: bool,
+    enable_ocr: bool,
+) -> Result<Option<MatchLocation>, String> {
+    let ext = std::path::Path::new(&path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if ext == "pdf" {
+        let elements = get_file_preview_internal(path, enable_ocr).await?;
+        return Ok(
+            first_match_page(&elements, &terms, case_sensitive).map(|page| MatchLocation {
+                page: Some(page),
+                ..MatchLocation::default()
+            }),
+        );
+    }
+    let (category, mime) = FileCategory::classify(&ext);
+    let textual = mime.starts_with("text/")
+        || matches!(
+            category,
+            FileCategory::Code | FileCategory::Data | FileCategory::Other
+        );
+    if !textual {
+        return Ok(None);
+    }
+    tokio::task::spawn_blocking(move || {
+        let file = std::path::Path::new(&path);
+        let size = std::fs::metadata(file).map_err(|e| e.to_string())?.len();
+        if size > LOCATE_MAX_BYTES || looks_binary(file) {
+            return Ok(None);
+        }
+        let bytes = std::fs::read(file).map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&bytes);
+        Ok(
+            first_match_line(&text, &terms, case_sensitive).map(|line| MatchLocation {
+                line: Some(line),
+                ..MatchLocation::default()
+            }),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Bytes of parsed text a highlighted preview holds. Rendering a whole log
+/// file would stall the UI, so longer documents are cut to a window.
+const PREVIEW_WINDOW: usize = 20_000;
+
+/// Case-(in)sensitive alternation of `terms`, or `None` if there are none.
+fn terms_regex(terms: &[String], case_sensitive: bool) -> Option<regex::Regex> {
+    let pattern = terms
         .iter()
         .filter(|t| !t.is_empty())
         .map(|t| regex::escape(t))
         .collect::<Vec<_>>()
         .join("|");
-
     if pattern.is_empty() {
-        return spans;
+        return None;
     }
-
-    let regex_res = regex::RegexBuilder::new(&format!("({pattern})"))
+    regex::RegexBuilder::new(&format!("({pattern})"))
         .case_insensitive(!case_sensitive)
-        .build();
+        .build()
+        .ok()
+}
+
+/// Largest char boundary of `s` at or before `i`.
+fn char_floor(s: &str, mut i: usize) -> usize {
+    i = i.min(s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// Cuts `elements` to at most `budget` bytes of text centered on the first
+/// match of `terms`, or their start if nothing matches. Offsets count bytes
+/// of the elements' contents laid end to end. Returns the kept elements and
+/// the byte range they cover.
+pub fn preview_window(
+    elements: Vec<PreviewElement>,
+    terms: &[String],
+    case_sensitive: bool,
+    budget: usize,
+) -> (Vec<PreviewElement>, std::ops::Range<usize>) {
+    let total: usize = elements.iter().map(|e| e.content.len()).sum();
+    if total <= budget {
+        return (elements, 0..total);
+    }
+
+    let first_match = terms_regex(terms, case_sensitive).and_then(|re| {
+        let mut offset = 0;
+        elements.iter().find_map(|e| {
+            let found = re.find(&e.content).map(|m| offset + m.start());
+            offset += e.content.len();
+            found
+        })
+    });
+    let start = first_match
+        .unwrap_or(0)
+        .saturating_sub(budget / 2)
+        .min(total - budget);
+    let end = start + budget;
+
+    let mut kept = Vec::new();
+    let mut offset = 0;
+    let (mut first, mut last) = (None, 0);
+    for element in elements {
+        let (el_start, el_end) = (offset, offset + element.content.len());
+        offset = el_end;
+        if el_end <= start || el_start >= end {
+            continue;
+        }
+        let from = char_floor(&element.content, start.saturating_sub(el_start));
+        let to = char_floor(&element.content, end.min(el_end) - el_start);
+        first.get_or_insert(el_start + from);
+        last = el_start + to;
+        kept.push(PreviewElement {
+            element_type: element.element_type,
+            content: element.content[from..to].to_string(),
+        });
+    }
+    (kept, first.unwrap_or(start)..last.max(start))
+}
 
-    let Ok(re) = regex_res else {
-        return spans;
+/// Splits `spans` around occurrences of the matched terms and colors them.
+/// Also returns the positions of the match spans.
+fn highlight_search_matches(
+    spans: Vec<(String, Option<[f32; 4]>)>,
+    matched_terms: &[String],
+    case_sensitive: bool,
+) -> (Vec<(String, Option<[f32; 4]>)>, Vec<usize>) {
+    let Some(re) = terms_regex(matched_terms, case_sensitive) else {
+        return (spans, Vec::new());
     };
 
     let mut result = Vec::new();
+    let mut match_spans = Vec::new();
     for (text, color) in spans {
         if text.is_empty() {
             continue;
@@ -132,6 +526,7 @@ fn highlight_search_matches(
             if start > last_idx {
                 result.push((text[last_idx..start].to_string(), color));
             }
+            match_spans.push(result.len());
             result.push((text[start..end].to_string(), Some([1.0, 0.75, 0.0, 1.0])));
             last_idx = end;
         }
@@ -139,7 +534,7 @@ fn highlight_search_matches(
             result.push((text[last_idx..].to_string(), color));
         }
     }
-    result
+    (result, match_spans)
 }
 
 /// Gets a highlighted preview of the file content.
@@ -156,11 +551,14 @@ pub async fn get_file_preview_highlighted_internal(
     let settings = state.settings_cache.load();
     let case_sensitive = settings.case_sensitive;
     let enable_ocr = settings.enable_ocr;
+    let query = expand_macros(&query, &settings.query_macros);
     let matched_terms = extract_highlight_terms(&query, case_sensitive);
 
     let elements = get_file_preview_internal(path.clone(), enable_ocr).await?;
+    let total_len = elements.iter().map(|e| e.content.len()).sum();
+    let (elements_clone, window) =
+        preview_window(elements, &matched_terms, case_sensitive, PREVIEW_WINDOW);
 
-    let elements_clone = elements.clone();
     let matched_terms_clone = matched_terms.clone();
 
     let highlighted_elements = tokio::task::spawn_blocking(move || {
@@ -192,12 +590,13 @@ pub async fn get_file_preview_highlighted_internal(
             }
 
             // Overlay search matches if any
-            let processed_spans =
+            let (processed_spans, matches) =
                 highlight_search_matches(spans, &matched_terms_clone, case_sensitive);
 
             final_elements.push(crate::models::DocumentElementHighlight {
                 element_type: element.element_type,
                 spans: processed_spans,
+                matches,
             });
         }
         final_elements
@@ -208,10 +607,198 @@ pub async fn get_file_preview_highlighted_internal(
     Ok(PreviewResult {
         elements: highlighted_elements,
         matched_terms,
+        start: window.start,
+        end: window.end,
+        total_len,
+    })
+}
+
+/// Files whose content resembles that of `path`, most similar first, found
+/// from the distinctive terms of its parsed text.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be parsed or the index cannot be
+/// searched.
+pub async fn more_like_this_internal(
+    path: String,
+    limit: usize,
+    state: &Arc<AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    let enable_ocr = state.settings_cache.load().enable_ocr;
+    let text = get_file_text_internal(path.clone(), enable_ocr).await?;
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let indexer = state.indexer.clone();
+    tokio::task::spawn_blocking(move || indexer.more_like_this(&path, &text, limit))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Words shorter than this are never respelled
+const MIN_SUGGESTED_WORD: usize = 3;
+
+/// A respelling of `query` for when it found nothing: each plain word the
+/// index doesn't have is replaced by the closest indexed term, the more
+/// common one on a tie. Operators, phrases and excluded words are kept as
+/// typed. `None` when no word needs, or has, a correction.
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read.
+pub fn spelling_suggestion_internal(
+    query: &str,
+    state: &AppState,
+) -> Result<Option<SpellingSuggestion>, String> {
+    let mut tokens = Vec::new();
+    let mut corrections = Vec::new();
+    let mut in_phrase = false;
+    for token in query.split_whitespace() {
+        let quotes = token.matches('"').count();
+        let keep = in_phrase
+            || quotes > 0
+            || token.contains(':')
+            || token.starts_with(['-', '@', '/'])
+            || matches!(token, "AND" | "OR" | "NOT");
+        if quotes % 2 == 1 {
+            in_phrase = !in_phrase;
+        }
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if keep
+            || word.chars().count() < MIN_SUGGESTED_WORD
+            || !word.chars().all(char::is_alphabetic)
+        {
+            tokens.push(token.to_string());
+            continue;
+        }
+
+        let lower = word.to_lowercase();
+        let known = state
+            .indexer
+            .term_doc_freq(&lower)
+            .map_err(|e| e.to_string())?
+            > 0;
+        let best = if known {
+            None
+        } else {
+            closest_term(&lower, state)?
+        };
+        match best {
+            Some(term) => {
+                tokens.push(token.replacen(word, &term, 1));
+                corrections.push((word.to_string(), term));
+            }
+            None => tokens.push(token.to_string()),
+        }
+    }
+    Ok((!corrections.is_empty()).then(|| SpellingSuggestion {
+        query: tokens.join(" "),
+        corrections,
+    }))
+}
+
+/// The indexed term closest to `word`: fewest edits, then most documents.
+/// Short words may be one edit off, longer ones two.
+fn closest_term(word: &str, state: &AppState) -> Result<Option<String>, String> {
+    let max_distance = if word.chars().count() <= 4 { 1 } else { 2 };
+    let candidates = state
+        .indexer
+        .similar_terms(word, max_distance)
+        .map_err(|e| e.to_string())?;
+    Ok(candidates
+        .into_iter()
+        .min_by_key(|(term, freq)| (edit_distance(term, word), Reverse(*freq), term.clone()))
+        .map(|(term, _)| term))
+}
+
+/// Size and modification time of a file, from the metadata database or, for
+/// a file it has no record of, from the file itself.
+fn file_stat(path: &str, state: &AppState) -> Option<(u64, u64)> {
+    let path = std::path::Path::new(path);
+    if let Ok(Some(record)) = state.metadata_db.get_metadata(path) {
+        return Some((record.size, record.modified));
+    }
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    Some((meta.len(), modified))
+}
+
+/// Size, dates and index status of `path`: what is on disk, what the index
+/// recorded, and whether the file changed since.
+///
+/// # Errors
+///
+/// Returns an error if the metadata database cannot be read.
+pub fn get_file_details_internal(path: &str, state: &AppState) -> Result<FileDetails, String> {
+    let file = std::path::Path::new(path);
+    let record = state
+        .metadata_db
+        .get_metadata(file)
+        .map_err(|e| e.to_string())?;
+    let meta = std::fs::metadata(file).ok();
+    let modified = meta
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    let size = meta.as_ref().map(std::fs::Metadata::len);
+    let stale = record.as_ref().is_some_and(|r| {
+        modified.is_some_and(|m| m > r.modified) || size.is_some_and(|s| s != r.size)
+    });
+
+    Ok(FileDetails {
+        path: path.to_string(),
+        size,
+        modified,
+        created: record
+            .as_ref()
+            .and_then(|r| r.attributes.created)
+            .or_else(|| crate::metadata::FileAttributes::read(file).created),
+        content_hash: record
+            .as_ref()
+            .filter(|r| r.content_hash != [0; 32])
+            .map(|r| {
+                blake3::Hash::from_bytes(r.content_hash)
+                    .to_hex()
+                    .to_string()
+            }),
+        indexed_at: record.as_ref().map(|r| r.indexed_at),
+        parser: crate::parsers::parser_name(file),
+        stale,
     })
 }
 
-/// Searches for filenames in the filename index.
+fn query_filenames(
+    query: &str,
+    limit: usize,
+    match_path: bool,
+    state: &AppState,
+) -> Result<Vec<filename_index::FilenameSearchResult>, String> {
+    let Some(filename_index) = state.filename_index.as_ref() else {
+        return Err("Filename index not initialized".to_string());
+    };
+    let query = FilenameQuery::parse(query, match_path);
+    let keep = |path: &str| {
+        !query.has_size_filter()
+            || file_stat(path, state).is_some_and(|(size, _)| query.matches_size(size))
+    };
+    filename_index
+        .query(&query, limit, keep)
+        .map_err(|e| e.to_string())
+}
+
+/// Searches for files and directories in the filename index. The query takes
+/// the operators of the full-text syntax (`ext:`, `path:`, `size:`) along
+/// with wildcards like `*.rs` and `!word` exclusions; the rest is matched
+/// against names or, with `match_path`, whole paths. Sizes come from the
+/// metadata database, or from the file itself when it has no record.
+/// `@name` macros are expanded first.
 ///
 /// # Errors
 ///
@@ -219,24 +806,156 @@ pub async fn get_file_preview_highlighted_internal(
 pub async fn search_filenames_internal(
     query: String,
     limit: usize,
+    match_path: bool,
     state: &Arc<AppState>,
 ) -> Result<Vec<FilenameSearchResult>, String> {
-    state.filename_index.as_ref().map_or_else(
-        || Err("Filename index not initialized".to_string()),
-        |filename_index| {
-            filename_index
-                .search(&query, limit)
-                .map(|results| {
-                    results
-                        .into_iter()
-                        .map(|r| FilenameSearchResult {
-                            file_path: r.file_path,
-                            file_name: r.file_name,
-                        })
-                        .collect()
-                })
-                .map_err(|e| e.to_string())
-        },
+    let query = expand_query_macros(&query, state);
+    let results = query_filenames(&query, limit, match_path, state)?;
+    record_search(&query, SearchKind::Filename, results.len());
+    Ok(results
+        .into_iter()
+        .map(|r| FilenameSearchResult {
+            file_path: r.file_path,
+            file_name: r.file_name,
+            kind: r.kind,
+        })
+        .collect())
+}
+
+/// Weight of a filename match in a combined search, against content scores
+/// scaled to at most 1. An exact name outranks any content hit on its own.
+const NAME_WEIGHT: f32 = 1.5;
+
+/// Searches file contents and the filename index with one query and merges
+/// the hits by path. Content scores are scaled to the best of them, and a
+/// file whose name matched gains [`NAME_WEIGHT`] times how closely it did,
+/// so exact names come first and files matching both ways rise above those
+/// matching one. Filters in `params` apply to filename hits as well, and
+/// `@name` macros are expanded first.
+///
+/// # Errors
+///
+/// Returns an error if the query is malformed, or if both searches fail; a
+/// missing filename index just leaves content hits.
+pub async fn search_combined_internal(
+    params: SearchParams<'_>,
+    match_path: bool,
+    state: &Arc<AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    let query = &expand_query_macros(params.query, state);
+    ParsedQuery::validate(query).map_err(|e| e.to_string())?;
+    let (scopes, rest) = QueryScopes::extract(query);
+    let allowed = scopes.allowed_paths(state).await?;
+    let scoped = scoped_params(&params, &rest, allowed.is_some());
+    let content = state
+        .indexer
+        .search(scoped.clone())
+        .await
+        .map_err(|e| e.to_string());
+    let names = if state.filename_index.is_some() {
+        query_filenames(rest.trim_matches('"'), scoped.limit, match_path, state)
+    } else {
+        Ok(Vec::new())
+    };
+    let (content, names) = match (content, names) {
+        (Err(e), Err(_)) => return Err(e),
+        (content, names) => (content.unwrap_or_default(), names.unwrap_or_default()),
+    };
+
+    let top = content.iter().map(|r| r.score).fold(0.0, f32::max);
+    let mut merged: Vec<SearchResult> = content
+        .into_iter()
+        .map(|mut r| {
+            if top > 0.0 {
+                r.score /= top;
+            }
+            r
+        })
+        .collect();
+    let mut by_path: HashMap<String, usize> = merged
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.file_path.clone(), i))
+        .collect();
+    for hit in names {
+        let boost = NAME_WEIGHT * hit.relevance();
+        if let Some(&i) = by_path.get(&hit.file_path) {
+            merged[i].score += boost;
+        } else if let Some(result) = filename_hit(hit, boost, &params, state) {
+            by_path.insert(result.file_path.clone(), merged.len());
+            merged.push(result);
+        }
+    }
+
+    merged.sort_by(|a, b| b.score.total_cmp(&a.score));
+    if let Some(allowed) = &allowed {
+        merged.retain(|r| allowed.contains(&r.file_path));
+    }
+    merged.truncate(params.limit);
+    mark_stale(&mut merged, state);
+    record_search(query, SearchKind::All, merged.len());
+    Ok(merged)
+}
+
+/// A filename hit as a search result, or `None` if the filters in `params`
+/// rule it out. Folders pass only when nothing filters by file properties.
+fn filename_hit(
+    hit: filename_index::FilenameSearchResult,
+    score: f32,
+    params: &SearchParams<'_>,
+    state: &AppState,
+) -> Option<SearchResult> {
+    let is_dir = hit.kind == EntryKind::Directory;
+    let extension = std::path::Path::new(&hit.file_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .filter(|_| !is_dir);
+    let category = extension
+        .as_deref()
+        .map(|ext| FileCategory::classify(ext).0);
+
+    if let Some(extensions) = params.file_extensions.filter(|e| !e.is_empty()) {
+        let ext = extension.as_deref()?;
+        if !extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+            return None;
+        }
+    }
+    if let Some(categories) = params.categories.filter(|c| !c.is_empty())
+        && !categories.contains(&category?)
+    {
+        return None;
+    }
+
+    let stat = if is_dir {
+        None
+    } else {
+        file_stat(&hit.file_path, state)
+    };
+    let filters_stat =
+        params.min_size.is_some() || params.max_size.is_some() || params.min_modified.is_some();
+    if filters_stat {
+        let (size, modified) = stat?;
+        if params.min_size.is_some_and(|min| size < min)
+            || params.max_size.is_some_and(|max| size > max)
+            || params.min_modified.is_some_and(|min| modified < min)
+        {
+            return None;
+        }
+    }
+
+    Some(
+        SearchResult::builder()
+            .file_path(hit.file_path)
+            .score(score)
+            .title(Some(hit.file_name))
+            .extension(extension.map(Into::into))
+            .size(stat.map(|(size, _)| size))
+            .modified(stat.map(|(_, modified)| modified))
+            .matched_terms(Vec::new())
+            .snippets(Vec::new())
+            .category(category)
+            .is_dir(is_dir)
+            .build(),
     )
 }
 
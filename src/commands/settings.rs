@@ -1,13 +1,34 @@
 use crate::commands::AppState;
+use crate::metadata::MetadataDb;
+use crate::models::SearchHistoryItem;
+use crate::scanner::sensitive::SensitiveMatcher;
 use crate::settings::AppSettings;
-use crate::settings::SearchHistoryItem;
 use std::sync::Arc;
 
 pub fn get_settings_internal(state: &Arc<AppState>) -> Result<AppSettings, String> {
     Ok(state.settings_cache.load().as_ref().clone())
 }
 
+/// Carries over which roots the index size cap switched to names only, which
+/// a settings page opened earlier doesn't know about. Changing the cap or its
+/// policy lifts them all.
+fn keep_content_caps(settings: &AppSettings, current: &AppSettings) -> AppSettings {
+    let mut settings = settings.clone();
+    let cap_changed = settings.max_index_size_mb != current.max_index_size_mb
+        || settings.index_size_policy != current.index_size_policy;
+    for root in &mut settings.index_roots {
+        let was_capped = current
+            .index_roots
+            .iter()
+            .any(|c| c.path == root.path && c.content_capped);
+        root.content_capped = !cap_changed && (root.content_capped || was_capped);
+    }
+    settings
+}
+
 pub fn save_settings_internal(settings: &AppSettings, state: &Arc<AppState>) -> Result<(), String> {
+    let current = state.settings_cache.load_full();
+    let settings = &keep_content_caps(settings, &current);
     state.settings_cache.store(Arc::new(settings.clone()));
 
     state
@@ -15,133 +36,234 @@ pub fn save_settings_internal(settings: &AppSettings, state: &Arc<AppState>) ->
         .save(settings)
         .map_err(|e| e.to_string())?;
 
+    apply_settings(settings, &current, state)
+}
+
+/// Brings the watcher and the index shards in line with `settings`, which
+/// replaced `current`.
+fn apply_settings(
+    settings: &AppSettings,
+    current: &AppSettings,
+    state: &Arc<AppState>,
+) -> Result<(), String> {
     let mut watcher = state.watcher.lock();
 
     watcher
-        .update_watch_list(&settings.index_dirs)
+        .update_watch_list(&settings.index_roots, &settings.exclude_list())
         .map_err(|e| e.to_string())?;
+    watcher.set_sensitive_files(SensitiveMatcher::from_settings(settings));
 
     drop(watcher);
+    crate::system::activity::set_enabled(settings.activity_log);
+    crate::scanner::access::set_elevated_roots(&settings.index_roots);
+    crate::scanner::cloud::set_content_folders(&settings.cloud_content_folders);
+
+    super::sync_index_shards(state, &settings.index_roots)?;
+
+    // Roots released from the size cap get their contents indexed again
+    for root in &current.index_roots {
+        let released = root.content_capped
+            && settings
+                .index_roots
+                .iter()
+                .any(|r| r.path == root.path && !r.content_capped);
+        if released {
+            let path = std::path::Path::new(&root.path);
+            state
+                .metadata_db
+                .remove_tree(path)
+                .map_err(|e| e.to_string())?;
+            state.watcher.lock().queue_refresh(path);
+        }
+    }
     Ok(())
 }
 
+/// Whether two settings would be written out the same.
+fn same_settings(a: &AppSettings, b: &AppSettings) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Re-reads the settings file after it was changed outside the app, such as
+/// by `settings set` from the CLI, and applies it as a save would. Returns
+/// the new settings, or `None` if the file holds what is already applied,
+/// as it does after the app's own saves.
+pub fn reload_settings_internal(state: &Arc<AppState>) -> Result<Option<AppSettings>, String> {
+    let loaded = state.settings_manager.load().map_err(|e| e.to_string())?;
+    let current = state.settings_cache.load_full();
+    if same_settings(&loaded, &current) {
+        return Ok(None);
+    }
+    state.settings_cache.store(Arc::new(loaded.clone()));
+    apply_settings(&loaded, &current, state)?;
+    Ok(Some(loaded))
+}
+
 pub fn get_recent_searches_internal(state: &Arc<AppState>) -> Result<Vec<String>, String> {
-    Ok(state.settings_cache.load().recent_searches.clone())
+    state
+        .metadata_db
+        .get_recent_searches()
+        .map_err(|e| e.to_string())
 }
 
 pub fn add_recent_search_internal(query: String, state: &Arc<AppState>) -> Result<(), String> {
-    let mut cache = state.settings_cache.load().as_ref().clone();
-
-    let mut recent = cache.recent_searches.clone();
+    let mut recent = get_recent_searches_internal(state)?;
     recent.retain(|q| q != &query);
     recent.insert(0, query);
     recent.truncate(10);
 
-    cache.recent_searches = recent;
     state
-        .settings_manager
-        .save(&cache)
-        .map_err(|e| e.to_string())?;
-
-    state.settings_cache.store(Arc::new(cache));
-
-    Ok(())
+        .metadata_db
+        .set_recent_searches(&recent)
+        .map_err(|e| e.to_string())
 }
 
 pub fn clear_recent_searches_internal(state: &Arc<AppState>) -> Result<(), String> {
-    let mut cache = state.settings_cache.load().as_ref().clone();
-
-    cache.recent_searches = vec![];
     state
-        .settings_manager
-        .save(&cache)
-        .map_err(|e| e.to_string())?;
-
-    state.settings_cache.store(Arc::new(cache));
-    Ok(())
+        .metadata_db
+        .set_recent_searches(&[])
+        .map_err(|e| e.to_string())
 }
 
 pub fn add_search_history_internal(query: String, state: &Arc<AppState>) -> Result<(), String> {
-    let mut cache = state.settings_cache.load().as_ref().clone();
-
-    let mut history = cache.search_history.clone();
-
-    let mut found = false;
-    for item in &mut history {
-        if item.query == query {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    edit_search_history(state, |history| {
+        if let Some(item) = history.iter_mut().find(|item| item.query == query) {
             item.frequency += 1;
-            item.last_used = std::time::SystemTime::now()
-                .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            found = true;
-            break;
-        }
-    }
-
-    if !found {
-        history.insert(
-            0,
-            crate::settings::SearchHistoryItem {
+            item.last_used = now;
+        } else {
+            history.push(SearchHistoryItem {
                 query,
                 frequency: 1,
-                last_used: std::time::SystemTime::now()
-                    .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-            },
-        );
-    }
-
-    history.sort_by_key(|b| std::cmp::Reverse(b.frequency));
-    history.truncate(50);
-
-    cache.search_history = history;
-    state
-        .settings_manager
-        .save(&cache)
-        .map_err(|e| e.to_string())?;
-
-    state.settings_cache.store(Arc::new(cache));
-    Ok(())
+                last_used: now,
+                pinned: false,
+            });
+        }
+    })
 }
 
 pub fn get_search_history_internal(
     limit: usize,
     state: &Arc<AppState>,
 ) -> Result<Vec<SearchHistoryItem>, String> {
-    let mut history = state.settings_cache.load().search_history.clone();
+    let mut history = state
+        .metadata_db
+        .get_search_history()
+        .map_err(|e| e.to_string())?;
+    rank_search_history(&mut history);
     history.truncate(limit);
 
     Ok(history)
 }
 
-pub fn pin_file_internal(path: String, state: &Arc<AppState>) -> Result<(), String> {
-    let mut cache = state.settings_cache.load().as_ref().clone();
+/// Orders the history with pinned entries first, then by how often and how
+/// recently each query was run.
+pub fn rank_search_history(history: &mut [SearchHistoryItem]) {
+    history.sort_by_key(|item| {
+        (
+            std::cmp::Reverse(item.pinned),
+            std::cmp::Reverse(item.frequency),
+            std::cmp::Reverse(item.last_used),
+        )
+    });
+}
+
+/// Past searches kept; the lowest ranked go first
+const SEARCH_HISTORY_LIMIT: usize = 50;
+
+fn edit_search_history(
+    state: &Arc<AppState>,
+    edit: impl FnOnce(&mut Vec<SearchHistoryItem>),
+) -> Result<(), String> {
+    let mut history = get_search_history_internal(usize::MAX, state)?;
+    edit(&mut history);
+    rank_search_history(&mut history);
+    history.truncate(SEARCH_HISTORY_LIMIT);
+    state
+        .metadata_db
+        .set_search_history(&history)
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete_search_history_internal(query: &str, state: &Arc<AppState>) -> Result<(), String> {
+    edit_search_history(state, |history| history.retain(|item| item.query != query))
+}
+
+pub fn pin_search_history_internal(
+    query: &str,
+    pinned: bool,
+    state: &Arc<AppState>,
+) -> Result<(), String> {
+    edit_search_history(state, |history| {
+        for item in history.iter_mut().filter(|item| item.query == query) {
+            item.pinned = pinned;
+        }
+    })
+}
+
+pub fn clear_search_history_internal(state: &Arc<AppState>) -> Result<(), String> {
+    // Pinned entries are kept; unpin them to remove them
+    edit_search_history(state, |history| history.retain(|item| item.pinned))
+}
 
-    if !cache.pinned_files.contains(&path) {
-        cache.pinned_files.push(path);
+pub fn pin_file_internal(path: String, state: &Arc<AppState>) -> Result<(), String> {
+    let mut pinned = get_pinned_files_internal(state)?;
+    if !pinned.contains(&path) {
+        pinned.push(path);
         state
-            .settings_manager
-            .save(&cache)
+            .metadata_db
+            .set_pinned_files(&pinned)
             .map_err(|e| e.to_string())?;
-        state.settings_cache.store(Arc::new(cache));
     }
     Ok(())
 }
 
 pub fn unpin_file_internal(path: &str, state: &Arc<AppState>) -> Result<(), String> {
-    let mut cache = state.settings_cache.load().as_ref().clone();
-
-    cache.pinned_files.retain(|p| p != path);
+    let mut pinned = get_pinned_files_internal(state)?;
+    pinned.retain(|p| p != path);
     state
-        .settings_manager
-        .save(&cache)
-        .map_err(|e| e.to_string())?;
-    state.settings_cache.store(Arc::new(cache));
-    Ok(())
+        .metadata_db
+        .set_pinned_files(&pinned)
+        .map_err(|e| e.to_string())
 }
 
 pub fn get_pinned_files_internal(state: &Arc<AppState>) -> Result<Vec<String>, String> {
-    Ok(state.settings_cache.load().pinned_files.clone())
+    state
+        .metadata_db
+        .get_pinned_files()
+        .map_err(|e| e.to_string())
+}
+
+/// Moves the search history, recent searches and pins that older versions
+/// kept in the settings file into the metadata database, leaving lists
+/// already there alone. Returns whether `settings` changed and needs saving.
+pub fn migrate_user_lists(settings: &mut AppSettings, db: &MetadataDb) -> Result<bool, String> {
+    if settings.search_history.is_empty()
+        && settings.recent_searches.is_empty()
+        && settings.pinned_files.is_empty()
+    {
+        return Ok(false);
+    }
+    let history = db.get_search_history().map_err(|e| e.to_string())?;
+    if history.is_empty() {
+        db.set_search_history(&settings.search_history)
+            .map_err(|e| e.to_string())?;
+    }
+    let recent = db.get_recent_searches().map_err(|e| e.to_string())?;
+    if recent.is_empty() {
+        db.set_recent_searches(&settings.recent_searches)
+            .map_err(|e| e.to_string())?;
+    }
+    let pinned = db.get_pinned_files().map_err(|e| e.to_string())?;
+    if pinned.is_empty() {
+        db.set_pinned_files(&settings.pinned_files)
+            .map_err(|e| e.to_string())?;
+    }
+    settings.search_history.clear();
+    settings.recent_searches.clear();
+    settings.pinned_files.clear();
+    Ok(true)
 }
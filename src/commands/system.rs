@@ -1,4 +1,7 @@
+use crate::commands::locate_match_internal;
 use crate::indexer::searcher::SearchResult;
+use crate::models::{MatchLocation, OpenWithApp};
+use crate::settings::AppSettings;
 
 pub fn get_home_dir_internal() -> Result<String, String> {
     dirs::home_dir()
@@ -26,6 +29,105 @@ pub fn open_folder_internal(path: &str) -> Result<(), String> {
     }
 }
 
+/// Opens `path` with an application the user picks: the system "Open with"
+/// dialog on Windows, a file dialog for the application elsewhere.
+#[cfg_attr(target_os = "windows", allow(clippy::unused_async))]
+pub async fn open_with_internal(path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+        Command::new("rundll32.exe")
+            .arg("shell32.dll,OpenAs_RunDLL")
+            .arg(&path)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::process::Command;
+        let dialog = rfd::AsyncFileDialog::new().set_title("Open With");
+        #[cfg(target_os = "macos")]
+        let dialog = dialog.set_directory("/Applications");
+        let Some(app) = dialog.pick_file().await else {
+            return Ok(());
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = {
+            let mut command = Command::new("open");
+            command.arg("-a").arg(app.path());
+            command
+        };
+        #[cfg(not(target_os = "macos"))]
+        let mut command = Command::new(app.path());
+        command.arg(&path).spawn().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Applications registered for the type of `path`, for the "Open with"
+/// picker.
+#[must_use]
+pub fn open_with_choices_internal(path: &str) -> Vec<OpenWithApp> {
+    crate::system::open_with::applications_for(std::path::Path::new(path))
+}
+
+/// Opens `path` with `app`, one of the picker's choices.
+pub fn open_with_app_internal(path: &str, app: &OpenWithApp) -> Result<(), String> {
+    crate::system::open_with::launch(app, std::path::Path::new(path)).map_err(|e| e.to_string())
+}
+
+/// Opens `path` with the command configured for its extension, else where
+/// `terms` first match: text and source files in the configured editor at
+/// the line, PDFs in the configured viewer at the page. Everything else
+/// opens with the default application.
+pub async fn open_file_internal(
+    path: String,
+    terms: Vec<String>,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    let ext = std::path::Path::new(&path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let custom = settings.opener_for(&ext);
+    let editor = settings.editor_command.trim();
+    let pdf_viewer = settings.pdf_viewer_command.trim();
+    let wants_location = custom.map_or(!editor.is_empty() || !pdf_viewer.is_empty(), |c| {
+        c.contains("{line}") || c.contains("{page}")
+    });
+    let location = if wants_location && !terms.is_empty() {
+        locate_match_internal(
+            path.clone(),
+            terms,
+            settings.case_sensitive,
+            settings.enable_ocr,
+        )
+        .await
+        .unwrap_or_default()
+    } else {
+        None
+    };
+    let template = match (custom, location) {
+        (Some(command), _) => command,
+        (None, Some(MatchLocation { line: Some(_), .. })) if !editor.is_empty() => editor,
+        (None, Some(MatchLocation { page: Some(_), .. })) if !pdf_viewer.is_empty() => pdf_viewer,
+        _ => return opener::open(std::path::Path::new(&path)).map_err(|e| e.to_string()),
+    };
+    // Without a match the file opens at its start
+    let location = location.unwrap_or_default();
+    crate::system::open_with::open_template(
+        template,
+        &[
+            ("", path.clone()),
+            ("file", path),
+            ("line", location.line.unwrap_or(1).to_string()),
+            ("page", location.page.unwrap_or(1).to_string()),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
+
 pub async fn select_folder_internal() -> Result<Option<String>, String> {
     let handle = rfd::AsyncFileDialog::new()
         .set_title("Select Folder to Index")
@@ -43,28 +145,41 @@ pub fn copy_to_clipboard_internal(text: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Puts the files at `paths` on the clipboard as files rather than text, so
+/// pasting into a file manager copies them and pasting into an email
+/// attaches them.
+pub fn copy_files_to_clipboard_internal(paths: &[String]) -> Result<(), String> {
+    use arboard::Clipboard;
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set()
+        .file_list(paths)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Asks where to save `results` and writes them as `format`, one of
+/// [`EXPORT_FORMATS`](crate::commands::EXPORT_FORMATS). Returns the path
+/// written, or `None` if the dialog was cancelled.
 pub async fn export_results_internal(
     results: Vec<SearchResult>,
     format: String,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     let mut dialog = rfd::AsyncFileDialog::new()
         .set_title("Export Search Results")
         .set_file_name(format!("flash_search_results.{format}"));
 
-    if format == "csv" {
-        dialog = dialog.add_filter("CSV File", &["csv"]);
-    } else if format == "json" {
-        dialog = dialog.add_filter("JSON File", &["json"]);
-    }
-
-    if let Some(handle) = dialog.save_file().await {
-        let path = handle.path().to_string_lossy().to_string();
-        if format == "csv" {
-            crate::commands::export_results_csv(&results, &path)?;
-        } else if format == "json" {
-            crate::commands::export_results_json(&results, &path)?;
-        }
-    }
+    dialog = match format.as_str() {
+        "csv" => dialog.add_filter("CSV File", &["csv"]),
+        "json" => dialog.add_filter("JSON File", &["json"]),
+        "md" => dialog.add_filter("Markdown File", &["md"]),
+        _ => dialog,
+    };
 
-    Ok(())
+    let Some(handle) = dialog.save_file().await else {
+        return Ok(None);
+    };
+    let path = handle.path().to_string_lossy().to_string();
+    crate::commands::export_results(&results, &format, &path)?;
+    Ok(Some(path))
 }
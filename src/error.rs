@@ -12,6 +12,12 @@ pub enum FlashError {
     #[error("Parse error in {path}: {cause}")]
     Parse { path: PathBuf, cause: String },
 
+    #[error("Parser timed out after {seconds}s: {path}")]
+    ParseTimeout { path: PathBuf, seconds: u64 },
+
+    #[error("Parser panicked on {path}: {cause}")]
+    ParserPanic { path: PathBuf, cause: String },
+
     #[error("Index error: {msg}")]
     Index { msg: String, field: Option<String> },
 
@@ -49,6 +55,14 @@ pub enum FlashError {
         resource: String,
         identifier: String,
     },
+
+    #[error("Another Flash Search instance is running (holding {lock_path})")]
+    AlreadyRunning { lock_path: PathBuf },
+
+    /// The index stores are encrypted with a passphrase that wasn't given,
+    /// or that doesn't open them. `new` is set when one is being chosen.
+    #[error("The index is encrypted and needs its passphrase")]
+    Locked { new: bool, wrong: bool },
 }
 
 impl From<std::io::Error> for FlashError {
@@ -68,6 +82,20 @@ impl FlashError {
         }
     }
 
+    pub fn parse_timeout<P: Into<PathBuf>>(path: P, seconds: u64) -> Self {
+        Self::ParseTimeout {
+            path: path.into(),
+            seconds,
+        }
+    }
+
+    pub fn parser_panic<P: Into<PathBuf>, S: Into<String>>(path: P, cause: S) -> Self {
+        Self::ParserPanic {
+            path: path.into(),
+            cause: cause.into(),
+        }
+    }
+
     pub fn index<S: Into<String>>(msg: S) -> Self {
         Self::Index {
             msg: msg.into(),
@@ -166,6 +194,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_timeout_error() {
+        let err = FlashError::parse_timeout("/test/bomb.zip", 30);
+        assert_eq!(
+            err.to_string(),
+            "Parser timed out after 30s: /test/bomb.zip"
+        );
+    }
+
     #[test]
     fn test_index_error() {
         let err = FlashError::index_field("content", "Field not found in schema");
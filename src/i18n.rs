@@ -0,0 +1,192 @@
+//! Translated UI text. Each language has a catalog of `key = text` lines in
+//! the Fluent style under `assets/locales/`, compiled into the binary, where
+//! `{ $name }` stands for an argument. A key a catalog lacks falls back to
+//! English, and a key English lacks shows as itself.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A language with a catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 code, as stored in the settings
+    pub code: &'static str,
+    /// The language's name for itself
+    pub name: &'static str,
+    source: &'static str,
+}
+
+/// Value of the `language` setting that follows the system language
+pub const AUTO: &str = "auto";
+
+/// Every language with a catalog; the first is the fallback.
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        name: "English",
+        source: include_str!("../assets/locales/en.ftl"),
+    },
+    Language {
+        code: "de",
+        name: "Deutsch",
+        source: include_str!("../assets/locales/de.ftl"),
+    },
+    Language {
+        code: "es",
+        name: "Español",
+        source: include_str!("../assets/locales/es.ftl"),
+    },
+    Language {
+        code: "fr",
+        name: "Français",
+        source: include_str!("../assets/locales/fr.ftl"),
+    },
+];
+
+static CATALOGS: LazyLock<Vec<HashMap<&'static str, &'static str>>> =
+    LazyLock::new(|| LANGUAGES.iter().map(|l| parse(l.source)).collect());
+
+/// Index into [`LANGUAGES`] of the language text is shown in
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+fn parse(source: &str) -> HashMap<&str, &str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect()
+}
+
+/// The catalog language for a locale tag such as `de_DE.UTF-8` or `fr-CA`.
+fn language_for_tag(tag: &str) -> Option<usize> {
+    let primary = tag.split(['_', '-', '.', '@']).next()?.to_ascii_lowercase();
+    LANGUAGES.iter().position(|l| l.code == primary)
+}
+
+/// Locale tags the user prefers, most preferred first.
+#[cfg(not(windows))]
+fn system_locales() -> Vec<String> {
+    // LANGUAGE can list several, separated by colons
+    ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .flat_map(|value| value.split(':').map(str::to_string).collect::<Vec<_>>())
+        .filter(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX")
+        .collect()
+}
+
+/// Locale tags the user prefers, most preferred first.
+#[cfg(windows)]
+fn system_locales() -> Vec<String> {
+    use windows::Win32::Globalization::GetUserDefaultLocaleName;
+    // LOCALE_NAME_MAX_LENGTH
+    let mut name = [0u16; 85];
+    // SAFETY: the buffer is writable for its whole length, which is passed along.
+    let len = unsafe { GetUserDefaultLocaleName(&mut name) };
+    let Ok(len) = usize::try_from(len) else {
+        return Vec::new();
+    };
+    // The length includes the terminating NUL; 0 means the call failed
+    if len == 0 {
+        return Vec::new();
+    }
+    vec![String::from_utf16_lossy(&name[..len - 1])]
+}
+
+fn detected_index() -> Option<usize> {
+    system_locales()
+        .iter()
+        .find_map(|tag| language_for_tag(tag))
+}
+
+/// The system's language, if there is a catalog for it.
+pub fn detect() -> Option<Language> {
+    detected_index().map(|i| LANGUAGES[i])
+}
+
+/// Shows text in the language of the `language` setting: a code from
+/// [`LANGUAGES`], or [`AUTO`] for the system language. Anything else, or a
+/// system language without a catalog, is English.
+pub fn set_language(setting: &str) {
+    let index = if setting == AUTO {
+        detected_index()
+    } else {
+        LANGUAGES.iter().position(|l| l.code == setting)
+    };
+    CURRENT.store(index.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// The language text is shown in.
+pub fn current() -> Language {
+    LANGUAGES[CURRENT.load(Ordering::Relaxed)]
+}
+
+fn lookup(language: usize, key: &str) -> &str {
+    CATALOGS[language]
+        .get(key)
+        .or_else(|| CATALOGS[0].get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+fn format_text(language: usize, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut text = lookup(language, key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${name} }}"), &value.to_string());
+    }
+    text
+}
+
+/// The text for `key` in the current language.
+pub fn tr(key: &str) -> String {
+    lookup(CURRENT.load(Ordering::Relaxed), key).to_string()
+}
+
+/// The text for `key` in the current language, with each `{ $name }` replaced
+/// by its argument.
+pub fn tr_args(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    format_text(CURRENT.load(Ordering::Relaxed), key, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_cover_english() {
+        let english = &CATALOGS[0];
+        for (language, catalog) in LANGUAGES.iter().zip(CATALOGS.iter()).skip(1) {
+            for key in english.keys() {
+                assert!(
+                    catalog.contains_key(key),
+                    "{} catalog lacks {key}",
+                    language.code
+                );
+            }
+            for key in catalog.keys() {
+                assert!(english.contains_key(key), "{key} is not an English key");
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_falls_back() {
+        assert_eq!(lookup(1, "tab-settings"), "Einstellungen");
+        assert_eq!(lookup(1, "no-such-key"), "no-such-key");
+        assert_eq!(
+            format_text(0, "export-done", &[("count", &3), ("path", &"/tmp/r.md")]),
+            "Exported 3 results to /tmp/r.md"
+        );
+    }
+
+    #[test]
+    fn test_locale_tags_pick_a_language() {
+        assert_eq!(language_for_tag("de_DE.UTF-8"), Some(1));
+        assert_eq!(language_for_tag("fr-CA"), Some(3));
+        assert_eq!(language_for_tag("EN"), Some(0));
+        assert_eq!(language_for_tag("ja_JP"), None);
+    }
+}
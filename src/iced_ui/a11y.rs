@@ -0,0 +1,97 @@
+//! Keyboard focus regions and the text that tells where focus and results
+//! stand. iced has no accessibility tree, so nothing here reaches a screen
+//! reader directly: region names and result counts are surfaced through the
+//! window title, which screen readers do read, and control names are tooltips.
+
+use super::{Message, theme};
+use crate::i18n::{tr, tr_args};
+use iced::widget::{container, text, tooltip};
+use iced::{Element, Padding};
+
+/// Keyboard focus regions, in Tab order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusRegion {
+    #[default]
+    Search,
+    Filters,
+    Results,
+    Preview,
+}
+
+impl FocusRegion {
+    const ORDER: [Self; 4] = [Self::Search, Self::Filters, Self::Results, Self::Preview];
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        let idx = Self::ORDER.iter().position(|r| *r == self).unwrap_or(0);
+        Self::ORDER[(idx + 1) % Self::ORDER.len()]
+    }
+
+    #[must_use]
+    pub fn previous(self) -> Self {
+        let idx = Self::ORDER.iter().position(|r| *r == self).unwrap_or(0);
+        Self::ORDER[(idx + Self::ORDER.len() - 1) % Self::ORDER.len()]
+    }
+
+    #[must_use]
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Search => "focus-search",
+            Self::Filters => "focus-filters",
+            Self::Results => "focus-results",
+            Self::Preview => "focus-preview",
+        })
+    }
+}
+
+pub fn get_filters_input_id() -> iced::widget::Id {
+    static ID: std::sync::OnceLock<iced::widget::Id> = std::sync::OnceLock::new();
+    ID.get_or_init(iced::widget::Id::unique).clone()
+}
+
+/// Name an icon-only control in a tooltip shown on hover. Only sighted users
+/// get it; it is not exposed to assistive technology.
+pub fn with_tooltip<'a>(
+    content: impl Into<Element<'a, Message>>,
+    label: impl text::IntoFragment<'a>,
+) -> Element<'a, Message> {
+    tooltip(
+        content,
+        container(text(label).size(12))
+            .padding(Padding::from([4, 8]))
+            .style(theme::badge_container),
+        tooltip::Position::Bottom,
+    )
+    .gap(4)
+    .into()
+}
+
+/// Result count put in the window title when the result list changes.
+#[must_use]
+pub fn result_count_announcement(shown: usize, hidden: usize) -> String {
+    match (shown, hidden) {
+        (0, _) => tr("announce-no-results"),
+        (1, 0) => tr("announce-one-result"),
+        (n, 0) => tr_args("announce-results", &[("count", &n)]),
+        (n, h) => tr_args("announce-results-hidden", &[("count", &n), ("hidden", &h)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_region_cycles() {
+        assert_eq!(FocusRegion::Search.next(), FocusRegion::Filters);
+        assert_eq!(FocusRegion::Preview.next(), FocusRegion::Search);
+        assert_eq!(FocusRegion::Search.previous(), FocusRegion::Preview);
+    }
+
+    #[test]
+    fn test_result_count_announcement() {
+        assert_eq!(result_count_announcement(0, 0), "No results");
+        assert_eq!(result_count_announcement(1, 0), "1 result");
+        assert_eq!(result_count_announcement(3, 7), "3 results, 7 more hidden");
+    }
+}
@@ -1,28 +1,39 @@
 use crate::commands::AppState;
 use crate::commands::{
-    get_file_preview_highlighted_internal, search_filenames_internal, search_query_internal,
+    expand_query_macros, get_file_annotation_internal, get_file_details_internal,
+    get_file_preview_highlighted_internal, get_hex_preview_internal, get_preview_kind_internal,
+    search_combined_internal, search_filenames_internal, search_query_internal,
+    set_file_annotation_internal,
 };
 use crate::error::FlashError;
+use crate::i18n::{tr, tr_args};
+use crate::indexer::category::FileCategory;
+use crate::indexer::query_parser::{ParsedQuery, has_macro};
 use crate::indexer::searcher::{SearchParams, SearchResult};
 use crate::scanner::ProgressEvent;
 use crate::settings::AppSettings;
 use compact_str::CompactString;
-use iced::futures::SinkExt;
+use iced::futures::{SinkExt, StreamExt};
 use iced::widget::Id;
 use iced::{Element, Subscription, Task};
 use parking_lot::Mutex;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+pub mod a11y;
 pub mod icons;
 pub mod search;
 pub mod settings;
+pub mod shortcuts;
+pub mod statistics;
 pub mod theme;
+pub mod unlock;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Tab {
     Search,
     Settings,
+    Statistics,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +45,20 @@ pub struct FileItem {
     pub size: Option<u64>,
     pub modified: Option<u64>,
     pub snippets: Vec<String>,
+    /// Query terms the file matched, for opening it where they are
+    pub matched_terms: Vec<String>,
+    pub owner: Option<CompactString>,
+    pub readonly: bool,
+    pub hidden: bool,
+    pub category: Option<FileCategory>,
+    pub mime: Option<CompactString>,
+    pub created: Option<u64>,
+    /// Removable drive the file is on, by its volume identifier
+    pub volume: Option<CompactString>,
+    pub unavailable: bool,
+    /// Changed on disk since it was indexed
+    pub stale: bool,
+    pub is_dir: bool,
 }
 
 impl From<SearchResult> for FileItem {
@@ -56,24 +81,51 @@ impl From<SearchResult> for FileItem {
             size: r.size,
             modified: r.modified,
             snippets: r.snippets,
+            matched_terms: r.matched_terms,
+            owner: r.owner,
+            readonly: r.readonly,
+            hidden: r.hidden,
+            category: r.category,
+            mime: r.mime,
+            created: r.created,
+            volume: r.volume,
+            unavailable: r.unavailable,
+            stale: r.stale,
+            is_dir: r.is_dir,
         }
     }
 }
 
 impl From<crate::models::FilenameSearchResult> for FileItem {
     fn from(r: crate::models::FilenameSearchResult) -> Self {
-        let path_clone = r.file_path.clone();
+        let is_dir = r.kind == crate::models::EntryKind::Directory;
+        let extension = if is_dir {
+            None
+        } else {
+            std::path::Path::new(&r.file_path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(CompactString::from)
+        };
         Self {
             score: 1.0,
             path: r.file_path,
             title: r.file_name.to_string(),
-            extension: std::path::Path::new(&path_clone)
-                .extension()
-                .and_then(|e| e.to_str())
-                .map(CompactString::from),
+            extension,
             size: None,
             modified: None,
             snippets: Vec::new(),
+            matched_terms: Vec::new(),
+            owner: None,
+            readonly: false,
+            hidden: false,
+            category: None,
+            mime: None,
+            created: None,
+            volume: None,
+            unavailable: false,
+            stale: false,
+            is_dir,
         }
     }
 }
@@ -117,7 +169,10 @@ pub enum DateFilter {
     strum::EnumString,
 )]
 pub enum SearchMode {
+    /// Contents and names together
     #[default]
+    #[strum(serialize = "All")]
+    All,
     #[strum(serialize = "Full Text")]
     FullText,
     #[strum(serialize = "Filename")]
@@ -147,6 +202,20 @@ pub enum SortBy {
     Size,
     #[strum(serialize = "Name")]
     Name,
+    #[strum(serialize = "Folder")]
+    Folder,
+    #[strum(serialize = "Extension")]
+    Extension,
+}
+
+/// How the result list is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultsLayout {
+    /// One card per result with snippets
+    #[default]
+    Cards,
+    /// A compact row per result with sortable columns
+    Table,
 }
 
 pub fn get_search_input_id() -> Id {
@@ -154,6 +223,46 @@ pub fn get_search_input_id() -> Id {
     ID.get_or_init(Id::unique).clone()
 }
 
+pub fn get_preview_scroll_id() -> Id {
+    static ID: std::sync::OnceLock<Id> = std::sync::OnceLock::new();
+    ID.get_or_init(Id::unique).clone()
+}
+
+/// Search matches in a preview as (element, span) positions, in reading order.
+pub(crate) fn preview_matches(preview: &crate::models::PreviewResult) -> Vec<(usize, usize)> {
+    preview
+        .elements
+        .iter()
+        .enumerate()
+        .flat_map(|(e, element)| element.matches.iter().map(move |&s| (e, s)))
+        .collect()
+}
+
+/// Where a match sits in the preview, as a fraction of its text. Used as the
+/// scroll position, which is close enough when the preview is mostly text.
+fn preview_match_offset(preview: &crate::models::PreviewResult, (e, s): (usize, usize)) -> f32 {
+    let len = |element: &crate::models::DocumentElementHighlight, spans: usize| -> usize {
+        element.spans.iter().take(spans).map(|(t, _)| t.len()).sum()
+    };
+    let total: usize = preview
+        .elements
+        .iter()
+        .map(|el| len(el, el.spans.len()))
+        .sum();
+    let before: usize = preview
+        .elements
+        .iter()
+        .take(e)
+        .map(|el| len(el, el.spans.len()))
+        .sum::<usize>()
+        + preview.elements.get(e).map_or(0, |el| len(el, s));
+    if total == 0 {
+        0.0
+    } else {
+        before as f32 / total as f32
+    }
+}
+
 pub fn get_progress_subscription_id() -> Id {
     static ID: std::sync::OnceLock<Id> = std::sync::OnceLock::new();
     ID.get_or_init(Id::unique).clone()
@@ -175,6 +284,15 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// `2 h 5 min`, `4 min` or `30 s`
+pub fn format_eta(secs: u64) -> String {
+    match (secs / 3600, secs % 3600 / 60) {
+        (0, 0) => format!("{secs} s"),
+        (0, minutes) => format!("{minutes} min"),
+        (hours, minutes) => format!("{hours} h {minutes} min"),
+    }
+}
+
 /// # Panics
 ///
 /// Panics if the timestamp is out of range for the system's local time.
@@ -186,6 +304,22 @@ pub fn format_date(timestamp: u64) -> String {
         .to_string()
 }
 
+/// A change to one field of an index root's profile in the settings view.
+#[derive(Debug, Clone)]
+pub enum RootEdit {
+    Include(String),
+    Exclude(String),
+    MaxDepth(String),
+    IndexContent(bool),
+    FollowSymlinks(bool),
+    IncludeHidden(bool),
+    IncludeSystem(bool),
+    ElevatedAccess(bool),
+    SeparateIndex(bool),
+    /// Own refresh schedule, or `None` to follow the global one
+    Rescan(Option<crate::settings::ReindexSchedule>),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     TabChanged(Tab),
@@ -193,40 +327,150 @@ pub enum Message {
     SearchSubmitted,
     SearchResultsReceived(usize, Vec<FileItem>),
     SearchError(FlashError),
+    /// A respelling for the search with this id, which found nothing
+    SpellingSuggested(usize, Option<crate::models::SpellingSuggestion>),
     ResultSelected(usize),
     ItemHovered(Option<usize>),
     OpenFile(String),
     OpenFolder(String),
     CopyPath(String),
+    /// Puts the files themselves on the clipboard, to paste into a file
+    /// manager or an email
+    CopyFiles(Vec<String>),
+    /// Copies the parsed text of the file at the path
+    CopyContents(String),
+    /// Copies text shown with a result, such as its matched snippets
+    CopyText(String),
     ShowContextMenu(usize),
+    CloseContextMenu,
+    /// Lists the applications that can open the path
+    OpenWith(String),
+    OpenWithChoices(String, Vec<crate::models::OpenWithApp>),
+    LaunchWith(String, crate::models::OpenWithApp),
+    /// Lets the user pick an application not listed for the file
+    OpenWithOther(String),
+    CloseOpenWith,
+    /// Asks to plug in the drive or reconnect the share an offline result is on
+    PromptReconnect(String, Option<CompactString>),
+    CheckReconnect,
+    ReconnectChecked(String, bool),
+    CloseReconnect,
+    /// Parses the file, or everything in the folder, again even if unchanged
+    ReindexPath(String),
+    /// A re-index of the path finished, with how many files it found
+    Reindexed(String, usize),
+    RemoveFromIndex(String),
+    /// Asks to confirm moving these paths to the trash
+    MoveToTrash(Vec<String>),
+    ConfirmTrash,
+    CancelTrash,
+    Trashed(Vec<String>, Result<usize, String>),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    /// Adds a path or pattern to the exclude patterns and drops what it
+    /// matches from the index
+    ExcludeFromIndex(String),
+    Excluded(Result<usize, String>),
+    /// Escape: closes the quick search palette or an open menu, else clears
+    /// the search, else hides the window to the tray
+    EscapePressed,
+    /// A key press not handled by a fixed binding, matched against the
+    /// configurable shortcuts
+    KeyPressed(iced::keyboard::Key, iced::keyboard::Modifiers),
     // Filters
     FilterExtensionChanged(String),
     ToggleFilterExtension(String),
-    ToggleCategory(Vec<String>),
+    ToggleCategory(FileCategory),
     MinSizeChanged(String),
     MaxSizeChanged(String),
     SizeUnitChanged(String),
     DateFilterChanged(DateFilter),
     SearchModeChanged(SearchMode),
     SortByChanged(SortBy),
+    /// A table column header was pressed: sort by it, or flip its direction
+    SortColumnPressed(SortBy),
+    ResultsLayoutChanged(ResultsLayout),
+    ResultsScrolled(iced::widget::scrollable::Viewport),
+    ToggleGroupByFolder(bool),
+    ToggleFolderCollapsed(String),
     ToggleCaseSensitive(bool),
     ToggleWholeWord(bool),
+    ToggleConfidenceCutoff(bool),
+    ToggleMatchFullPath(bool),
+    ShowAllResults,
     ClearFilters,
     // Settings
     MaxResultsChanged(String),
     ExcludePatternsChanged(String),
+    SensitiveFilesChanged(crate::settings::SensitiveFiles),
+    SensitivePatternsChanged(String),
+    CloudContentFoldersChanged(String),
+    IndexEncryptionChanged(crate::settings::IndexEncryption),
     CustomExtensionsChanged(String),
     GlobalHotkeyChanged(String),
+    QuickSearchHotkeyChanged(String),
+    EditorCommandChanged(String),
+    PdfViewerCommandChanged(String),
+    OpenerExtensionChanged(String),
+    OpenerCommandChanged(String),
+    AddFileOpener,
+    RemoveFileOpener(usize),
+    ShortcutChanged(shortcuts::ShortcutAction, String),
+    /// A language code, or `auto` for the system language
+    LanguageChanged(String),
+    /// Opens a window for the named profile and closes this one
+    SwitchProfile(String),
+    NewProfileNameChanged(String),
+    CreateProfile,
+    UnlockPassphraseChanged(String),
+    Unlock,
+    Unlocked(Result<(), FlashError>),
+    ScheduleChanged(String),
+    MacroNameChanged(String),
+    MacroExpansionChanged(String),
+    AddQueryMacro,
+    RemoveQueryMacro(usize),
+    ApplyQueryMacro(String),
+    // Search history
+    ToggleSearchHistory,
+    RunHistoryQuery(String),
+    DeleteHistoryEntry(String),
+    PinHistoryEntry(String, bool),
+    /// Forgets every entry that isn't pinned
+    ClearSearchHistory,
+    ToggleSearchHistoryEnabled(bool),
+    // Problems
+    ParseErrorsLoaded(Vec<crate::metadata::ParseErrorRecord>),
+    /// The settings file was written, by this app or another process
+    SettingsFileChanged,
+    SettingsReloaded(Result<Option<Box<crate::settings::AppSettings>>, String>),
+    StatisticsLoaded(Result<crate::models::DetailedStatistics, String>),
+    ClearParseErrors,
+    // Activity log
+    ToggleActivityLog(bool),
+    ActivityFilterChanged(String),
+    ActivityLoaded(Vec<crate::system::activity::ActivityEntry>),
+    RefreshActivity,
+    ClearActivity,
     AddFolder,
+    RemoteRootChanged(String),
+    RemotePasswordChanged(String),
+    AddRemoteRoot,
     RemoveFolder(usize),
     ToggleMinimizeToTray(bool),
     ToggleAutoStart(bool),
     ToggleContextMenu(bool),
     ToggleGitignore(bool),
+    ToggleMftEnumeration(bool),
+    ToggleCodeAwareIndexing(bool),
     ToggleTheme,
     RebuildIndex,
+    RefreshIndex,
+    PauseIndexing,
+    ResumeIndexing,
+    CancelIndexing,
     IndexDirAdded(String),
     RemoveIndexDir(usize),
+    EditIndexRoot(usize, RootEdit),
     ExcludePatternAdded(String),
     RemoveExcludePattern(usize),
     SaveSettings,
@@ -235,31 +479,74 @@ pub enum Message {
     FontSizeChanged(crate::settings::FontSize),
     // Lifecycle
     PollProgress,
+    RefreshWatcherStatus,
     PollProgressResult(Option<ProgressEvent>),
-    PreviewLoaded(usize, crate::models::PreviewResult),
+    PreviewLoaded(
+        usize,
+        crate::models::PreviewKind,
+        crate::models::PreviewResult,
+    ),
+    NextPreviewMatch,
+    FileDetailsLoaded(crate::models::FileDetails),
+    /// Files similar to the one at this path
+    SimilarFilesLoaded(String, Vec<FileItem>),
+    /// Compares the parsed text of two files in the preview area
+    CompareContents(String, String),
+    ContentDiffLoaded(crate::models::ContentDiff),
+    CloseContentDiff,
+    PreviousPreviewMatch,
+    // Annotations
+    AnnotationNoteChanged(String),
+    AnnotationAttributesChanged(String),
+    SaveAnnotation,
     IndexRebuilt,
     RebuildProgress(f32),
     StatusUpdate(String),
     // Pinned
     PinFile(String),
     UnpinFile(String),
+    /// Recently modified files for the home view
+    RecentFilesLoaded(Vec<crate::models::RecentFile>),
     // System
     PickFolder,
     FolderPicked(Option<String>),
-    ExportResults(String), // format: "csv" or "json"
+    /// Dry run of the picked folder, to confirm before it is indexed
+    ScanEstimated(Result<crate::models::ScanEstimate, String>),
+    ConfirmScan,
+    CancelScan,
+    // Removable drives
+    PollVolumes,
+    VolumesPolled(Vec<crate::scanner::volumes::Volume>),
+    IndexVolume,
+    DismissVolume,
+    ToggleOfferRemovableIndexing(bool),
+    AddIndexRoot(String),
+    /// A file or folder dropped onto the window
+    FolderDropped(std::path::PathBuf),
+    ExportResults(String), // format: "csv", "json" or "md"
     WindowIdCaptured(iced::window::Id),
     WindowUnfocused(iced::window::Id),
+    /// The window's close button; hides to the tray when there is one
+    WindowCloseRequested(iced::window::Id),
+    /// Pause or resume from the tray menu
+    TogglePauseIndexing,
     DismissError,
     Quit,
     NoOp,
     ToggleSidebar,
     ToggleWindow,
     RestoreWindow,
+    ToggleQuickSearch,
+    /// The palette is up; carries the window size to go back to
+    QuickSearchOpened(iced::Size),
+    CloseQuickSearch,
     SelectPreviousResult,
     SelectNextResult,
     OpenSelectedResult,
     ShowSelectedInFolder,
     CopySelectedPath,
+    FocusNextRegion,
+    FocusPreviousRegion,
 }
 
 #[allow(clippy::struct_excessive_bools)]
@@ -267,38 +554,132 @@ pub struct App {
     pub(crate) state: Option<Arc<AppState>>,
     pub(crate) error: Option<String>,
     pub(crate) search_error: Option<String>,
+    /// A respelling offered when the last search found nothing
+    pub(crate) spelling_suggestion: Option<crate::models::SpellingSuggestion>,
     pub(crate) db_corrupted_dismissed: bool,
     pub(crate) active_tab: Tab,
     pub(crate) search_query: String,
     pub(crate) results: Vec<FileItem>,
+    /// Low-confidence results held back by the knee cutoff until "show all" is pressed
+    pub(crate) hidden_results: Vec<FileItem>,
     pub(crate) selected_index: Option<usize>,
     pub(crate) hovered_item_index: Option<usize>,
     pub(crate) is_searching: bool,
     pub(crate) search_id: usize,
     pub(crate) filter_extension: String,
     pub(crate) filter_extensions: std::collections::HashSet<String>,
+    pub(crate) filter_categories: std::collections::HashSet<FileCategory>,
     pub(crate) min_size: String,
     pub(crate) max_size: String,
     pub(crate) size_unit: String,
     pub(crate) date_filter: DateFilter,
     pub(crate) search_mode: SearchMode,
     pub(crate) sort_by: SortBy,
+    /// Sort opposite to the column's natural direction
+    pub(crate) sort_reversed: bool,
+    pub(crate) results_layout: ResultsLayout,
+    /// Leading results that are rendered; grows a page at a time as the list
+    /// is scrolled
+    pub(crate) results_shown: usize,
+    /// Result whose action menu is open
+    pub(crate) context_menu: Option<usize>,
+    /// Paths of results picked with Ctrl-click, for acting on several at once
+    pub(crate) marked: std::collections::HashSet<String>,
+    /// Modifier keys held down, so a click can tell whether to add to the
+    /// picked results
+    pub(crate) modifiers: iced::keyboard::Modifiers,
+    /// Paths waiting for the user to confirm moving them to the trash
+    pub(crate) trash_confirm: Option<Vec<String>>,
+    /// Path being opened and the applications offered for it
+    pub(crate) open_with: Option<(String, Vec<crate::models::OpenWithApp>)>,
+    /// Offline result the user wants back, with the drive it is on
+    pub(crate) reconnect: Option<(String, Option<CompactString>)>,
+    /// Results are shown under a header per containing folder
+    pub(crate) group_by_folder: bool,
+    /// Folder groups the user closed
+    pub(crate) collapsed_folders: std::collections::HashSet<String>,
     pub(crate) filter_size: String,
     pub(crate) files_indexed: i32,
     pub(crate) index_size: String,
     pub(crate) rebuild_status: Option<String>,
     pub(crate) rebuild_progress: Option<f32>,
     pub(crate) rebuild_eta: Option<u64>,
+    pub(crate) indexing_paused: bool,
+    /// Files already committed by scans that were cancelled or interrupted and
+    /// can be resumed
+    pub(crate) pending_scan_files: Option<u64>,
     pub(crate) is_dark: bool,
     pub(crate) sidebar_collapsed: bool,
     pub(crate) settings: AppSettings,
     pub(crate) new_index_dir: String,
     pub(crate) new_exclude_pattern: String,
+    pub(crate) new_macro_name: String,
+    pub(crate) new_macro_expansion: String,
+    pub(crate) new_opener_extension: String,
+    pub(crate) new_opener_command: String,
+    /// Re-index schedule as typed; applied to `settings` whenever it parses
+    pub(crate) schedule_input: String,
+    pub(crate) schedule_last_run: Option<u64>,
+    /// Latest watcher health, refreshed every few seconds for the status bar
+    pub(crate) watcher_status: Option<crate::models::WatcherStatus>,
+    pub(crate) parse_errors: Vec<crate::metadata::ParseErrorRecord>,
+    /// Latest activity log entries matching `activity_filter`
+    pub(crate) activity: Vec<crate::system::activity::ActivityEntry>,
+    pub(crate) activity_filter: String,
+    /// Filled in each time the statistics tab opens
+    pub(crate) statistics: Option<Result<crate::models::DetailedStatistics, String>>,
+    pub(crate) focus_region: a11y::FocusRegion,
+    /// Latest focus region or result count, shown in the window title for screen readers
+    pub(crate) announcement: Option<String>,
+    /// Note and `key: value` attributes being edited for the selected result
+    pub(crate) annotation_note: String,
+    pub(crate) annotation_attributes: String,
     pub(crate) preview_result: Option<crate::models::PreviewResult>,
+    pub(crate) preview_kind: crate::models::PreviewKind,
+    /// Disk and index details of the selected result
+    pub(crate) file_details: Option<crate::models::FileDetails>,
+    /// Files with content like the selected one's, most similar first
+    pub(crate) similar_files: Vec<FileItem>,
+    /// Comparison of two results' text, shown in place of the preview
+    pub(crate) content_diff: Option<crate::models::ContentDiff>,
+    /// Match in the preview that navigation is on
+    pub(crate) preview_match: usize,
     pub(crate) is_loading_preview: bool,
     #[allow(dead_code)]
-    pub(crate) tray_icon: Option<tray_icon::TrayIcon>,
+    pub(crate) tray_icon: Option<crate::system::tray::Tray>,
     pub(crate) window_id: Option<iced::window::Id>,
+    /// The window is shown as the quick search palette
+    pub(crate) quick_search: bool,
+    /// Window size from before the palette took over
+    pub(crate) restore_size: Option<iced::Size>,
+    /// Recently modified files, shown on the home view with the pinned ones
+    pub(crate) recent_files: Vec<crate::models::RecentFile>,
+    /// The search history dropdown under the search box is open
+    pub(crate) history_open: bool,
+    /// Past searches, ranked, as stored in the metadata database
+    pub(crate) search_history: Vec<crate::models::SearchHistoryItem>,
+    /// Pinned files, as stored in the metadata database
+    pub(crate) pinned_files: Vec<String>,
+    /// Every profile on disk, the default first
+    pub(crate) profiles: Vec<String>,
+    pub(crate) new_profile_name: String,
+    /// Shown instead of everything else while the index waits for its
+    /// passphrase
+    pub(crate) unlock: Option<unlock::UnlockPrompt>,
+    /// Dry run of a picked folder awaiting confirmation
+    pub(crate) scan_estimate: Option<crate::models::ScanEstimate>,
+    /// Removable drives seen at the last poll; `None` until the first one,
+    /// so drives plugged in before the start aren't offered
+    pub(crate) seen_volumes: Option<std::collections::HashSet<String>>,
+    /// A drive that was just plugged in, awaiting the choice to index it
+    pub(crate) new_volume: Option<crate::scanner::volumes::Volume>,
+    /// URL typed into the remote folder field, e.g. `ssh://user@host/path`
+    pub(crate) remote_root_input: String,
+    /// Password for the remote folder being added; saved to the keychain,
+    /// never to the settings
+    pub(crate) remote_password_input: String,
+    /// The current query is already counted in the search history
+    pub(crate) history_recorded: bool,
     pub(crate) progress_rx: Option<flume::Receiver<ProgressEvent>>,
     pub(crate) active_search_id: Arc<AtomicUsize>,
     pub(crate) active_preview_id: Arc<AtomicUsize>,
@@ -331,37 +712,88 @@ impl Default for App {
             state: None,
             error: None,
             search_error: None,
+            spelling_suggestion: None,
             db_corrupted_dismissed: false,
             active_tab: Tab::Search,
             search_query: String::new(),
             results: Vec::new(),
+            hidden_results: Vec::new(),
             selected_index: None,
             hovered_item_index: None,
             is_searching: false,
             search_id: 0,
             filter_extension: String::new(),
             filter_extensions: std::collections::HashSet::new(),
+            filter_categories: std::collections::HashSet::new(),
             min_size: String::new(),
             max_size: String::new(),
             size_unit: "MB".to_string(),
             date_filter: DateFilter::Anytime,
-            search_mode: SearchMode::FullText,
+            search_mode: SearchMode::All,
             sort_by: SortBy::default(),
+            sort_reversed: false,
+            results_layout: ResultsLayout::default(),
+            results_shown: RESULT_PAGE,
+            context_menu: None,
+            marked: std::collections::HashSet::new(),
+            modifiers: iced::keyboard::Modifiers::empty(),
+            trash_confirm: None,
+            open_with: None,
+            reconnect: None,
+            group_by_folder: false,
+            collapsed_folders: std::collections::HashSet::new(),
             filter_size: String::new(),
             files_indexed: 0,
             index_size: "0 MB".to_string(),
             rebuild_status: None,
             rebuild_progress: None,
             rebuild_eta: None,
+            indexing_paused: false,
+            pending_scan_files: None,
             is_dark: false,
             sidebar_collapsed: false,
             settings: AppSettings::default(),
             new_index_dir: String::new(),
             new_exclude_pattern: String::new(),
+            new_macro_name: String::new(),
+            new_macro_expansion: String::new(),
+            new_opener_extension: String::new(),
+            new_opener_command: String::new(),
+            schedule_input: String::new(),
+            schedule_last_run: None,
+            watcher_status: None,
+            parse_errors: Vec::new(),
+            activity: Vec::new(),
+            activity_filter: String::new(),
+            statistics: None,
+            focus_region: a11y::FocusRegion::default(),
+            announcement: None,
+            annotation_note: String::new(),
+            annotation_attributes: String::new(),
             preview_result: None,
+            preview_kind: crate::models::PreviewKind::Text,
+            file_details: None,
+            similar_files: Vec::new(),
+            content_diff: None,
+            preview_match: 0,
             is_loading_preview: false,
             tray_icon: None,
             window_id: None,
+            quick_search: false,
+            restore_size: None,
+            recent_files: Vec::new(),
+            history_open: false,
+            search_history: Vec::new(),
+            pinned_files: Vec::new(),
+            profiles: Vec::new(),
+            new_profile_name: String::new(),
+            unlock: None,
+            scan_estimate: None,
+            seen_volumes: None,
+            new_volume: None,
+            remote_root_input: String::new(),
+            remote_password_input: String::new(),
+            history_recorded: false,
             progress_rx: None,
             active_search_id: Arc::new(AtomicUsize::new(0)),
             active_preview_id: Arc::new(AtomicUsize::new(0)),
@@ -371,7 +803,7 @@ impl Default for App {
 
 impl App {
     fn new(
-        state: Result<Arc<AppState>, String>,
+        state: Result<Arc<AppState>, FlashError>,
         progress_rx: Option<flume::Receiver<ProgressEvent>>,
         initial_dir: Option<String>,
     ) -> Self {
@@ -384,6 +816,17 @@ impl App {
                     (index_stats.total_size_bytes as f64) / 1_048_576.0
                 );
                 let is_dark = matches!(settings.theme, crate::settings::Theme::Dark);
+                let pending_scan_files = pending_scan_files(&state);
+                let schedule_last_run = state.metadata_db.get_last_scheduled_run().ok().flatten();
+                let watcher_status = crate::commands::get_watcher_status_internal(&state);
+                let search_history =
+                    crate::commands::get_search_history_internal(usize::MAX, &state)
+                        .unwrap_or_default();
+                let pinned_files =
+                    crate::commands::get_pinned_files_internal(&state).unwrap_or_default();
+                let profiles = crate::get_base_data_dir()
+                    .map(|base| crate::system::profile::list(&base))
+                    .unwrap_or_default();
 
                 let mut app = Self {
                     state: Some(state),
@@ -391,6 +834,13 @@ impl App {
                     files_indexed: i32::try_from(index_stats.total_documents).unwrap_or(i32::MAX),
                     index_size,
                     is_dark,
+                    pending_scan_files,
+                    schedule_input: settings.reindex_schedule.to_string(),
+                    schedule_last_run,
+                    watcher_status: Some(watcher_status),
+                    search_history,
+                    pinned_files,
+                    profiles,
                     progress_rx,
                     ..Default::default()
                 };
@@ -410,7 +860,13 @@ impl App {
                 app
             }
             Err(e) => Self {
-                error: Some(e),
+                unlock: match e {
+                    FlashError::Locked { new, wrong } => {
+                        Some(unlock::UnlockPrompt::new(new, wrong))
+                    }
+                    _ => None,
+                },
+                error: Some(e.to_string()),
                 progress_rx,
                 ..Default::default()
             },
@@ -524,10 +980,12 @@ impl App {
 
         let mut query = self.search_query.clone();
 
+        // Quoting an `@name` macro would keep the search from expanding it
         if self.settings.whole_word
             && !query.starts_with('"')
             && !query.ends_with('"')
             && !query.contains(':')
+            && !has_macro(&query, &self.settings.query_macros)
         {
             query = format!("\"{query}\"");
         }
@@ -535,6 +993,19 @@ impl App {
         let max_results = self.settings.max_results;
         let mode = self.search_mode;
 
+        // A malformed query gets a hint under the search box rather than a search
+        if mode != SearchMode::Filename
+            && ParsedQuery::validate(&expand_query_macros(&query, &state)).is_err()
+        {
+            self.search_id += 1;
+            self.active_search_id
+                .store(self.search_id, Ordering::Relaxed);
+            self.is_searching = false;
+            self.results.clear();
+            self.hidden_results.clear();
+            return Task::none();
+        }
+
         let mut extensions: ahash::AHashSet<String> = self
             .filter_extension
             .split(',')
@@ -587,8 +1058,15 @@ impl App {
             Some(extensions.into_iter().collect())
         };
 
+        let categories: Option<Vec<FileCategory>> = if self.filter_categories.is_empty() {
+            None
+        } else {
+            Some(self.filter_categories.iter().copied().collect())
+        };
+
         self.is_searching = true;
         self.results.clear();
+        self.hidden_results.clear();
         self.preview_result = None;
         self.search_id += 1;
         let current_search_id = self.search_id;
@@ -596,6 +1074,7 @@ impl App {
             .store(current_search_id, Ordering::Relaxed);
         let active_search_id = self.active_search_id.clone();
         let case_sensitive = self.settings.case_sensitive;
+        let match_path = self.settings.match_full_path;
 
         Task::future(async move {
             if debounce {
@@ -608,7 +1087,9 @@ impl App {
 
             match mode {
                 SearchMode::Filename => {
-                    match search_filenames_internal(query.clone(), max_results, &state).await {
+                    match search_filenames_internal(query.clone(), max_results, match_path, &state)
+                        .await
+                    {
                         Ok(results) => {
                             let items: Vec<FileItem> =
                                 results.into_iter().map(FileItem::from).collect();
@@ -617,21 +1098,23 @@ impl App {
                         Err(e) => Message::SearchError(FlashError::search(&query, e)),
                     }
                 }
-                SearchMode::FullText => {
-                    match search_query_internal(
-                        SearchParams::builder()
-                            .query(&query)
-                            .limit(max_results)
-                            .maybe_min_size(min_size)
-                            .maybe_max_size(max_size)
-                            .maybe_min_modified(min_modified)
-                            .maybe_file_extensions(extension.as_deref())
-                            .case_sensitive(case_sensitive)
-                            .build(),
-                        &state,
-                    )
-                    .await
-                    {
+                SearchMode::All | SearchMode::FullText => {
+                    let params = SearchParams::builder()
+                        .query(&query)
+                        .limit(max_results)
+                        .maybe_min_size(min_size)
+                        .maybe_max_size(max_size)
+                        .maybe_min_modified(min_modified)
+                        .maybe_file_extensions(extension.as_deref())
+                        .maybe_categories(categories.as_deref())
+                        .case_sensitive(case_sensitive)
+                        .build();
+                    let results = if mode == SearchMode::All {
+                        search_combined_internal(params, match_path, &state).await
+                    } else {
+                        search_query_internal(params, &state).await
+                    };
+                    match results {
                         Ok(results) => {
                             let items: Vec<FileItem> =
                                 results.into_iter().map(FileItem::from).collect();
@@ -644,6 +1127,123 @@ impl App {
         })
     }
 
+    /// Looks for a respelling of the query after search `id` found nothing.
+    /// Filename searches don't go through the content terms, so get none.
+    fn suggest_spelling(&self, id: usize) -> Task<Message> {
+        let Some(state) = self.state.clone() else {
+            return Task::none();
+        };
+        if self.search_mode == SearchMode::Filename || self.search_query.trim().is_empty() {
+            return Task::none();
+        }
+        let query = self.search_query.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    crate::commands::spelling_suggestion_internal(&query, &state)
+                })
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .flatten()
+            },
+            move |suggestion| Message::SpellingSuggested(id, suggestion),
+        )
+    }
+
+    fn focus_region_changed(&mut self, region: a11y::FocusRegion) -> Task<Message> {
+        self.focus_region = region;
+        self.announcement = Some(region.label());
+        match region {
+            a11y::FocusRegion::Search => iced::widget::operation::focus(get_search_input_id()),
+            a11y::FocusRegion::Filters => {
+                iced::widget::operation::focus(a11y::get_filters_input_id())
+            }
+            a11y::FocusRegion::Results if self.selected_index.is_none() => {
+                if self.results.is_empty() {
+                    Task::none()
+                } else {
+                    Task::done(Message::ResultSelected(0))
+                }
+            }
+            a11y::FocusRegion::Results | a11y::FocusRegion::Preview => Task::none(),
+        }
+    }
+
+    /// Reload the recently modified files shown on the home view.
+    fn load_recent_files(&self) -> Task<Message> {
+        let Some(state) = self.state.clone() else {
+            return Task::none();
+        };
+        Task::future(async move {
+            match crate::commands::get_recent_files_internal(HOME_RECENT_FILES, &state).await {
+                Ok(files) => Message::RecentFilesLoaded(files),
+                Err(e) => Message::StatusUpdate(tr_args("recent-load-failed", &[("error", &e)])),
+            }
+        })
+    }
+
+    /// Apply a change to the stored search history and show the result.
+    fn edit_search_history(
+        &mut self,
+        edit: impl FnOnce(&Arc<AppState>) -> Result<(), String>,
+    ) -> Task<Message> {
+        let Some(state) = self.state.clone() else {
+            return Task::none();
+        };
+        let result = edit(&state);
+        self.search_history =
+            crate::commands::get_search_history_internal(usize::MAX, &state).unwrap_or_default();
+        match result {
+            Ok(()) => Task::none(),
+            Err(e) => Task::done(Message::StatusUpdate(tr_args(
+                "history-update-failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    /// Count the current query in the search history, once however many of
+    /// its results are opened.
+    fn record_search(&mut self) -> Task<Message> {
+        let query = self.search_query.trim().to_string();
+        if !self.settings.search_history_enabled || query.is_empty() || self.history_recorded {
+            return Task::none();
+        }
+        self.history_recorded = true;
+        self.edit_search_history(|state| crate::commands::add_search_history_internal(query, state))
+    }
+
+    /// Fill the note editor with the annotation stored for result `idx`.
+    fn load_annotation(&mut self, idx: usize) {
+        let annotation = self
+            .state
+            .as_ref()
+            .zip(self.results.get(idx))
+            .and_then(|(state, item)| get_file_annotation_internal(&item.path, state).ok())
+            .flatten()
+            .unwrap_or_default();
+        self.annotation_attributes = annotation.attributes_text();
+        self.annotation_note = annotation.note;
+    }
+
+    /// Move results below the score knee into `hidden_results`.
+    fn apply_confidence_cutoff(&mut self) {
+        self.hidden_results.clear();
+        if !self.settings.confidence_cutoff || self.search_mode == SearchMode::Filename {
+            return;
+        }
+
+        let scores: Vec<f32> = self.results.iter().map(|r| r.score).collect();
+        if let Some(threshold) = crate::indexer::searcher::confidence_threshold(&scores) {
+            let (kept, hidden) = std::mem::take(&mut self.results)
+                .into_iter()
+                .partition(|r| r.score >= threshold);
+            self.results = kept;
+            self.hidden_results = hidden;
+        }
+    }
+
     pub fn sort_results(&mut self) {
         match self.sort_by {
             SortBy::Relevance => {
@@ -664,18 +1264,89 @@ impl App {
             SortBy::Name => {
                 self.results.sort_by_key(|a| a.title.to_lowercase());
             }
+            SortBy::Folder => {
+                self.results.sort_by_cached_key(|a| {
+                    std::path::Path::new(&a.path)
+                        .parent()
+                        .map(|p| p.to_string_lossy().to_lowercase())
+                });
+            }
+            SortBy::Extension => {
+                self.results
+                    .sort_by_cached_key(|a| a.extension.as_deref().map(str::to_lowercase));
+            }
+        }
+        if self.sort_reversed {
+            self.results.reverse();
+        }
+    }
+
+    /// What an action on the result at `idx`, such as moving it to the
+    /// trash, takes: every picked result if it is one of several picked, in
+    /// result order, else just itself.
+    pub(crate) fn picked_targets(&self, idx: usize) -> Vec<String> {
+        let Some(item) = self.results.get(idx) else {
+            return Vec::new();
+        };
+        if self.marked.len() > 1 && self.marked.contains(&item.path) {
+            self.results
+                .iter()
+                .filter(|r| self.marked.contains(&r.path))
+                .map(|r| r.path.clone())
+                .collect()
+        } else {
+            vec![item.path.clone()]
         }
     }
 
+    /// Reads the activity log entries matching the filter off the UI thread.
+    fn load_activity(&self) -> Task<Message> {
+        let filter = self.activity_filter.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    crate::commands::get_activity_internal(&filter, settings::MAX_ACTIVITY_SHOWN)
+                })
+                .await
+                .unwrap_or_default()
+            },
+            Message::ActivityLoaded,
+        )
+    }
+
+    /// Adds `root` to the indexed folders, saves the settings and scans it.
+    fn add_index_root(&mut self, root: crate::settings::IndexRoot) -> Task<Message> {
+        self.settings.index_roots.push(root.clone());
+        let Some(state) = self.state.clone() else {
+            return Task::none();
+        };
+        state.reset_scan_control();
+        let save_task = self.save_settings();
+        let scan_task = Task::future(async move {
+            let _ = state
+                .scanner
+                .scan_directory(root, vec![], state.indexing_cancel.clone())
+                .await;
+            Message::IndexRebuilt
+        });
+        Task::batch(vec![save_task, scan_task])
+    }
+
     fn save_settings(&self) -> Task<Message> {
+        crate::system::activity::set_enabled(self.settings.activity_log);
+        crate::scanner::access::set_elevated_roots(&self.settings.index_roots);
+        crate::scanner::cloud::set_content_folders(&self.settings.cloud_content_folders);
         if let Some(state) = &self.state {
             let settings = self.settings.clone();
             let state = state.clone();
             return Task::perform(
                 async move {
                     let _ = state.settings_manager.save(&settings);
+                    // Background tasks such as the scheduler read the cache
+                    state.settings_cache.store(Arc::new(settings.clone()));
                     let mut watcher = state.watcher.lock();
-                    let _ = watcher.update_watch_list(&settings.index_dirs);
+                    let _ =
+                        watcher.update_watch_list(&settings.index_roots, &settings.exclude_list());
                 },
                 |()| Message::NoOp,
             );
@@ -689,34 +1360,217 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
     match message {
         Message::TabChanged(tab) => {
             app.active_tab = tab;
+            if app.active_tab == Tab::Settings
+                && let Some(state) = &app.state
+            {
+                app.schedule_last_run = state.metadata_db.get_last_scheduled_run().ok().flatten();
+                let state = state.clone();
+                return Task::batch([
+                    Task::future(async move {
+                        Message::ParseErrorsLoaded(
+                            crate::commands::get_parse_errors_internal(&state).unwrap_or_default(),
+                        )
+                    }),
+                    app.load_activity(),
+                ]);
+            }
+            if app.active_tab == Tab::Statistics
+                && let Some(state) = &app.state
+            {
+                let state = state.clone();
+                return Task::future(async move {
+                    Message::StatisticsLoaded(
+                        crate::commands::get_detailed_statistics_internal(&state).await,
+                    )
+                });
+            }
+            Task::none()
+        }
+        Message::SettingsFileChanged => {
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        crate::commands::reload_settings_internal(&state)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map(|settings| settings.map(Box::new))
+                },
+                Message::SettingsReloaded,
+            )
+        }
+        Message::SettingsReloaded(Ok(Some(settings))) => {
+            app.settings = *settings;
+            app.is_dark = matches!(app.settings.theme, crate::settings::Theme::Dark);
+            app.schedule_input = app.settings.reindex_schedule.to_string();
+            crate::i18n::set_language(&app.settings.language);
+            app.rebuild_status = Some(tr("settings-reloaded"));
+            let minimize_to_tray = app.settings.minimize_to_tray;
+            update(app, Message::ToggleMinimizeToTray(minimize_to_tray))
+        }
+        Message::SettingsReloaded(Ok(None)) => Task::none(),
+        Message::SettingsReloaded(Err(e)) => {
+            app.rebuild_status = Some(tr_args("settings-reload-failed", &[("error", &e)]));
+            Task::none()
+        }
+        Message::StatisticsLoaded(statistics) => {
+            app.statistics = Some(statistics);
+            Task::none()
+        }
+        Message::ParseErrorsLoaded(errors) => {
+            app.parse_errors = errors;
+            Task::none()
+        }
+        Message::RefreshWatcherStatus => {
+            if let Some(state) = &app.state {
+                app.watcher_status = Some(crate::commands::get_watcher_status_internal(state));
+            }
+            Task::none()
+        }
+        Message::ToggleActivityLog(enabled) => {
+            app.settings.activity_log = enabled;
+            app.save_settings()
+        }
+        Message::ActivityFilterChanged(filter) => {
+            app.activity_filter = filter;
+            app.load_activity()
+        }
+        Message::RefreshActivity => app.load_activity(),
+        Message::ActivityLoaded(entries) => {
+            app.activity = entries;
+            Task::none()
+        }
+        Message::ClearActivity => {
+            if let Err(e) = crate::commands::clear_activity_internal() {
+                app.error = Some(e);
+                return Task::none();
+            }
+            app.activity.clear();
+            Task::none()
+        }
+        Message::ClearParseErrors => {
+            if let Some(state) = &app.state
+                && let Err(e) = crate::commands::clear_parse_errors_internal(state)
+            {
+                app.error = Some(e);
+                return Task::none();
+            }
+            app.parse_errors.clear();
             Task::none()
         }
         Message::SearchQueryChanged(q) => {
             app.search_query = q;
+            app.history_recorded = false;
+            if app.search_query.is_empty() {
+                return Task::batch([app.perform_search(true), app.load_recent_files()]);
+            }
             app.perform_search(true)
         }
-        Message::SearchSubmitted => app.perform_search(false),
+        Message::SearchSubmitted => {
+            app.history_open = false;
+            Task::batch([app.record_search(), app.perform_search(false)])
+        }
         Message::SearchResultsReceived(id, results) => {
             if id == app.search_id {
                 app.results = results;
+                app.results_shown = RESULT_PAGE;
+                app.context_menu = None;
+                app.marked.clear();
+                app.apply_confidence_cutoff();
                 app.sort_results();
+                app.announcement = Some(a11y::result_count_announcement(
+                    app.results.len(),
+                    app.hidden_results.len(),
+                ));
                 app.is_searching = false;
                 app.selected_index = None;
+                app.spelling_suggestion = None;
+                if app.results.is_empty() && app.hidden_results.is_empty() {
+                    return app.suggest_spelling(id);
+                }
+            }
+            Task::none()
+        }
+        Message::SpellingSuggested(id, suggestion) => {
+            if id == app.search_id {
+                app.spelling_suggestion = suggestion;
             }
             Task::none()
         }
         Message::SortByChanged(sort) => {
+            app.sort_by = sort;
+            app.sort_reversed = false;
+            app.sort_results();
+            Task::none()
+        }
+        Message::SortColumnPressed(sort) => {
+            app.sort_reversed = app.sort_by == sort && !app.sort_reversed;
             app.sort_by = sort;
             app.sort_results();
             Task::none()
         }
+        Message::ResultsLayoutChanged(layout) => {
+            app.results_layout = layout;
+            Task::none()
+        }
+        Message::ResultsScrolled(viewport) => {
+            if viewport.relative_offset().y > 0.9 && app.results_shown < app.results.len() {
+                app.results_shown += RESULT_PAGE;
+            }
+            Task::none()
+        }
         Message::SearchError(e) => {
             app.is_searching = false;
             app.search_error = Some(e.to_string());
             Task::none()
         }
+        Message::ToggleGroupByFolder(b) => {
+            app.group_by_folder = b;
+            app.collapsed_folders.clear();
+            Task::none()
+        }
+        Message::ToggleFolderCollapsed(folder) => {
+            if !app.collapsed_folders.remove(&folder) {
+                app.collapsed_folders.insert(folder);
+            }
+            Task::none()
+        }
+        // Ctrl-click picks results, starting from the one already selected
+        Message::ResultSelected(idx) if app.modifiers.command() => {
+            if app.marked.is_empty()
+                && let Some(selected) = app.selected_index.and_then(|i| app.results.get(i))
+            {
+                app.marked.insert(selected.path.clone());
+            }
+            if let Some(item) = app.results.get(idx)
+                && !app.marked.remove(&item.path)
+            {
+                app.marked.insert(item.path.clone());
+            }
+            Task::none()
+        }
         Message::ResultSelected(idx) => {
+            app.marked.clear();
             app.selected_index = Some(idx);
+            app.file_details = None;
+            app.similar_files.clear();
+            app.content_diff = None;
+            // Arrowing past the rendered rows renders the page they're on
+            app.results_shown = app.results_shown.max((idx / RESULT_PAGE + 1) * RESULT_PAGE);
+            // Keyboard selection opens the group it lands in
+            if app.group_by_folder
+                && let Some(folder) = app
+                    .results
+                    .get(idx)
+                    .and_then(|r| std::path::Path::new(&r.path).parent())
+            {
+                app.collapsed_folders
+                    .remove(folder.to_string_lossy().as_ref());
+            }
+            app.load_annotation(idx);
             if app.settings.show_preview_panel {
                 let item = app.results[idx].clone();
                 let query = app.search_query.clone();
@@ -725,108 +1579,610 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                     app.is_loading_preview = true;
                     let next_preview_id = app.active_preview_id.fetch_add(1, Ordering::Relaxed) + 1;
                     let active_preview_id = app.active_preview_id.clone();
-                    return Task::future(async move {
-                        match get_file_preview_highlighted_internal(item.path, query, &state).await
+                    let details_state = state.clone();
+                    let preview = Task::future(async move {
+                        use crate::models::{PreviewKind, PreviewResult};
+                        let mut kind = get_preview_kind_internal(item.path.clone(), &state)
+                            .await
+                            .unwrap_or_default();
+                        // Images and binary files have no text worth parsing
+                        let mut preview = match kind {
+                            PreviewKind::Image { .. } | PreviewKind::Binary { .. } => {
+                                Ok(PreviewResult::default())
+                            }
+                            PreviewKind::Text | PreviewKind::PdfPage { .. } => {
+                                get_file_preview_highlighted_internal(
+                                    item.path.clone(),
+                                    query,
+                                    &state,
+                                )
+                                .await
+                            }
+                        };
+                        // A format the parser doesn't know still gets a hex dump
+                        if preview.is_err()
+                            && kind == PreviewKind::Text
+                            && let Ok(hex) = get_hex_preview_internal(&item.path)
                         {
+                            kind = hex;
+                            preview = Ok(PreviewResult::default());
+                        }
+                        match preview {
                             Ok(preview) => {
                                 if active_preview_id.load(Ordering::Relaxed) == next_preview_id {
-                                    Message::PreviewLoaded(next_preview_id, preview)
+                                    Message::PreviewLoaded(next_preview_id, kind, preview)
                                 } else {
                                     Message::NoOp
                                 }
                             }
                             Err(e) => {
                                 if active_preview_id.load(Ordering::Relaxed) == next_preview_id {
-                                    Message::StatusUpdate(format!("Preview error: {e}"))
+                                    Message::StatusUpdate(tr_args(
+                                        "preview-failed",
+                                        &[("error", &e)],
+                                    ))
                                 } else {
                                     Message::NoOp
                                 }
                             }
                         }
                     });
+                    let details = Task::perform(
+                        {
+                            let path = app.results[idx].path.clone();
+                            async move { get_file_details_internal(&path, &details_state) }
+                        },
+                        |res| match res {
+                            Ok(details) => Message::FileDetailsLoaded(details),
+                            Err(e) => {
+                                Message::StatusUpdate(tr_args("details-failed", &[("error", &e)]))
+                            }
+                        },
+                    );
+                    let similar = Task::perform(
+                        {
+                            let path = app.results[idx].path.clone();
+                            let state = details_state.clone();
+                            async move {
+                                let similar = crate::commands::more_like_this_internal(
+                                    path.clone(),
+                                    SIMILAR_FILES,
+                                    &state,
+                                )
+                                .await
+                                .unwrap_or_default();
+                                (path, similar)
+                            }
+                        },
+                        |(path, similar)| {
+                            Message::SimilarFilesLoaded(
+                                path,
+                                similar.into_iter().map(FileItem::from).collect(),
+                            )
+                        },
+                    );
+                    return Task::batch([preview, details, similar]);
                 }
             }
             Task::none()
         }
-        Message::PreviewLoaded(id, preview) => {
+        Message::FileDetailsLoaded(details) => {
+            // Drop details for a result that is no longer selected
+            if app
+                .selected_index
+                .and_then(|i| app.results.get(i))
+                .is_some_and(|r| r.path == details.path)
+            {
+                app.file_details = Some(details);
+            }
+            Task::none()
+        }
+        Message::CompareContents(left, right) => {
+            app.context_menu = None;
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            app.rebuild_status = Some(tr("compare-running"));
+            Task::perform(
+                async move { crate::commands::diff_files_internal(left, right, &state).await },
+                |res| match res {
+                    Ok(diff) => Message::ContentDiffLoaded(diff),
+                    Err(e) => Message::StatusUpdate(tr_args("compare-failed", &[("error", &e)])),
+                },
+            )
+        }
+        Message::ContentDiffLoaded(diff) => {
+            app.rebuild_status = None;
+            app.content_diff = Some(diff);
+            Task::none()
+        }
+        Message::CloseContentDiff => {
+            app.content_diff = None;
+            Task::none()
+        }
+        Message::SimilarFilesLoaded(path, similar) => {
+            if app
+                .selected_index
+                .and_then(|i| app.results.get(i))
+                .is_some_and(|r| r.path == path)
+            {
+                app.similar_files = similar;
+            }
+            Task::none()
+        }
+        Message::PreviewLoaded(id, kind, preview) => {
             if id == app.active_preview_id.load(Ordering::Relaxed) {
+                app.preview_kind = kind;
                 app.preview_result = Some(preview);
+                app.preview_match = 0;
                 app.is_loading_preview = false;
             }
             Task::none()
         }
+        Message::NextPreviewMatch | Message::PreviousPreviewMatch => {
+            let Some(preview) = &app.preview_result else {
+                return Task::none();
+            };
+            let matches = preview_matches(preview);
+            if matches.is_empty() {
+                return Task::none();
+            }
+            let current = if matches!(message, Message::NextPreviewMatch) {
+                (app.preview_match + 1) % matches.len()
+            } else {
+                app.preview_match
+                    .checked_sub(1)
+                    .unwrap_or(matches.len() - 1)
+            };
+            let y = preview_match_offset(preview, matches[current]);
+            app.preview_match = current;
+            iced::widget::operation::snap_to(
+                get_preview_scroll_id(),
+                iced::widget::scrollable::RelativeOffset { x: 0.0, y },
+            )
+        }
+        Message::AnnotationNoteChanged(s) => {
+            app.annotation_note = s;
+            Task::none()
+        }
+        Message::AnnotationAttributesChanged(s) => {
+            app.annotation_attributes = s;
+            Task::none()
+        }
+        Message::SaveAnnotation => {
+            let (Some(state), Some(item)) = (
+                &app.state,
+                app.selected_index.and_then(|i| app.results.get(i)),
+            ) else {
+                return Task::none();
+            };
+            let state = state.clone();
+            let path = item.path.clone();
+            let note = app.annotation_note.clone();
+            let attributes = app.annotation_attributes.clone();
+            Task::future(async move {
+                match set_file_annotation_internal(path, note, attributes, state).await {
+                    Ok(()) => Message::StatusUpdate(tr("note-saved")),
+                    Err(e) => Message::StatusUpdate(tr_args("note-save-failed", &[("error", &e)])),
+                }
+            })
+        }
         Message::ItemHovered(idx) => {
             app.hovered_item_index = idx;
             Task::none()
         }
+        // The palette goes away once it has done its job
         Message::OpenFile(path) => {
-            let _ = opener::open(std::path::Path::new(&path));
-            Task::none()
+            app.context_menu = None;
+            let terms = app
+                .results
+                .iter()
+                .find(|r| r.path == path)
+                .map(|r| r.matched_terms.clone())
+                .unwrap_or_default();
+            let settings = app.settings.clone();
+            let open = Task::future(async move {
+                match crate::commands::open_file_internal(path, terms, &settings).await {
+                    Ok(()) => Message::NoOp,
+                    Err(e) => Message::StatusUpdate(tr_args("open-failed", &[("error", &e)])),
+                }
+            });
+            Task::batch([
+                open,
+                app.record_search(),
+                Task::done(Message::CloseQuickSearch),
+            ])
         }
         Message::OpenFolder(path) => {
+            app.context_menu = None;
             let _ = crate::commands::open_folder_internal(&path);
-            Task::none()
+            Task::done(Message::CloseQuickSearch)
         }
         Message::CopyPath(path) => {
+            app.context_menu = None;
             let _ = crate::commands::copy_to_clipboard_internal(&path);
             Task::none()
         }
-        Message::FilterExtensionChanged(ext) => {
-            app.filter_extension = ext;
-            app.perform_search(true)
+        Message::CopyContents(path) => {
+            app.context_menu = None;
+            let enable_ocr = app.settings.enable_ocr;
+            Task::perform(
+                async move {
+                    let text = crate::commands::get_file_text_internal(path, enable_ocr).await?;
+                    crate::commands::copy_to_clipboard_internal(&text)?;
+                    Ok::<_, String>(text.chars().count())
+                },
+                |res| match res {
+                    Ok(chars) => {
+                        Message::StatusUpdate(tr_args("text-copied", &[("count", &chars)]))
+                    }
+                    Err(e) => Message::StatusUpdate(tr_args("text-copy-failed", &[("error", &e)])),
+                },
+            )
         }
-        Message::ToggleFilterExtension(ext) => {
-            if app.filter_extensions.contains(&ext) {
-                app.filter_extensions.remove(&ext);
-            } else {
-                app.filter_extensions.insert(ext);
+        Message::CopyText(text) => {
+            app.context_menu = None;
+            let _ = crate::commands::copy_to_clipboard_internal(&text);
+            Task::none()
+        }
+        Message::CopyFiles(paths) => {
+            app.context_menu = None;
+            match crate::commands::copy_files_to_clipboard_internal(&paths) {
+                Ok(()) => Task::none(),
+                Err(e) => Task::done(Message::StatusUpdate(tr_args(
+                    "files-copy-failed",
+                    &[("error", &e)],
+                ))),
             }
-            app.perform_search(false)
         }
-        Message::ToggleCategory(exts) => {
-            let all_present = exts.iter().all(|e| app.filter_extensions.contains(e));
-            if all_present {
-                for e in &exts {
-                    app.filter_extensions.remove(e);
-                }
+        Message::ShowContextMenu(idx) => {
+            app.context_menu = (app.context_menu != Some(idx)).then_some(idx);
+            if app.selected_index == Some(idx) {
+                Task::none()
             } else {
-                for e in exts {
-                    app.filter_extensions.insert(e);
-                }
+                Task::done(Message::ResultSelected(idx))
             }
-            app.perform_search(false)
-        }
-        Message::MinSizeChanged(s) => {
-            app.min_size = s;
-            app.perform_search(true)
         }
-        Message::MaxSizeChanged(s) => {
-            app.max_size = s;
-            app.perform_search(true)
+        Message::CloseContextMenu => {
+            app.context_menu = None;
+            Task::none()
         }
-        Message::SizeUnitChanged(u) => {
-            app.size_unit = u;
-            app.perform_search(false)
+        Message::EscapePressed if app.quick_search => Task::done(Message::CloseQuickSearch),
+        Message::EscapePressed if app.scan_estimate.is_some() => Task::done(Message::CancelScan),
+        Message::EscapePressed if app.trash_confirm.is_some() => Task::done(Message::CancelTrash),
+        Message::EscapePressed if app.open_with.is_some() => Task::done(Message::CloseOpenWith),
+        Message::EscapePressed if app.reconnect.is_some() => Task::done(Message::CloseReconnect),
+        Message::EscapePressed if app.new_volume.is_some() => Task::done(Message::DismissVolume),
+        Message::EscapePressed if app.history_open => {
+            app.history_open = false;
+            Task::none()
         }
-        Message::DateFilterChanged(d) => {
-            app.date_filter = d;
-            app.perform_search(false)
+        Message::EscapePressed if app.context_menu.is_some() => {
+            Task::done(Message::CloseContextMenu)
         }
-        Message::SearchModeChanged(m) => {
-            app.search_mode = m;
-            app.perform_search(false)
+        Message::EscapePressed if !app.marked.is_empty() => {
+            app.marked.clear();
+            Task::none()
         }
-        Message::ToggleCaseSensitive(b) => {
-            app.settings.case_sensitive = b;
-            app.perform_search(false)
+        Message::EscapePressed if !app.search_query.is_empty() => {
+            Task::done(Message::SearchQueryChanged(String::new()))
         }
-        Message::ToggleWholeWord(b) => {
-            app.settings.whole_word = b;
-            app.perform_search(false)
+        Message::EscapePressed => match app.window_id {
+            Some(id) if app.tray_icon.is_some() => {
+                iced::window::set_mode(id, iced::window::Mode::Hidden)
+            }
+            _ => Task::none(),
+        },
+        Message::KeyPressed(key, modifiers) => {
+            shortcuts::action_for(&app.settings.shortcuts, &key, modifiers)
+                .map_or_else(Task::none, |action| Task::done(action.message()))
+        }
+        Message::OpenWith(path) => {
+            app.context_menu = None;
+            Task::perform(
+                async move {
+                    let lookup = path.clone();
+                    let apps = tokio::task::spawn_blocking(move || {
+                        crate::commands::open_with_choices_internal(&lookup)
+                    })
+                    .await
+                    .unwrap_or_default();
+                    (path, apps)
+                },
+                |(path, apps)| Message::OpenWithChoices(path, apps),
+            )
+        }
+        Message::OpenWithChoices(path, apps) => {
+            if apps.is_empty() {
+                return Task::done(Message::OpenWithOther(path));
+            }
+            app.open_with = Some((path, apps));
+            Task::none()
+        }
+        Message::LaunchWith(path, choice) => {
+            app.open_with = None;
+            match crate::commands::open_with_app_internal(&path, &choice) {
+                Ok(()) => Task::none(),
+                Err(e) => Task::done(Message::StatusUpdate(tr_args(
+                    "open-with-app-failed",
+                    &[("app", &choice.name), ("error", &e)],
+                ))),
+            }
+        }
+        Message::CloseOpenWith => {
+            app.open_with = None;
+            Task::none()
+        }
+        Message::PromptReconnect(path, volume) => {
+            app.reconnect = Some((path, volume));
+            Task::none()
+        }
+        Message::CloseReconnect => {
+            app.reconnect = None;
+            Task::none()
+        }
+        Message::CheckReconnect => {
+            let (Some((path, volume)), Some(state)) = (app.reconnect.take(), app.state.clone())
+            else {
+                return Task::none();
+            };
+            let roots = app.settings.index_roots.clone();
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        let plugged = crate::scanner::volumes::refresh();
+                        let offline = state.indexer.offline_roots();
+                        for root in &roots {
+                            let root_path = std::path::Path::new(&root.path);
+                            if !offline.is_root_offline(root_path) {
+                                continue;
+                            }
+                            let drive_back = root
+                                .volume_id
+                                .as_ref()
+                                .is_none_or(|id| plugged.iter().any(|v| &v.id == id));
+                            let back = drive_back
+                                && if crate::scanner::remote::is_remote(&root.path) {
+                                    crate::scanner::remote::is_reachable(&root.path)
+                                } else {
+                                    crate::scanner::network::is_reachable(root_path)
+                                };
+                            offline.set_offline(root_path, !back);
+                        }
+                        let available = !offline.contains(std::path::Path::new(&path))
+                            && !volume
+                                .as_deref()
+                                .is_some_and(crate::scanner::volumes::is_offline);
+                        (path, available)
+                    })
+                    .await
+                    .unwrap_or_default()
+                },
+                |(path, available)| Message::ReconnectChecked(path, available),
+            )
+        }
+        Message::ReconnectChecked(path, true) => {
+            app.rebuild_status = Some(tr_args("reconnect-done", &[("path", &path)]));
+            if app.search_query.is_empty() {
+                return Task::none();
+            }
+            app.perform_search(false)
+        }
+        Message::ReconnectChecked(path, false) => {
+            app.rebuild_status = Some(tr_args("reconnect-still", &[("path", &path)]));
+            Task::none()
+        }
+        Message::OpenWithOther(path) => {
+            app.open_with = None;
+            Task::future(async move {
+                match crate::commands::open_with_internal(path).await {
+                    Ok(()) => Message::NoOp,
+                    Err(e) => Message::StatusUpdate(tr_args("open-with-failed", &[("error", &e)])),
+                }
+            })
+        }
+        Message::ReindexPath(path) => {
+            app.context_menu = None;
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            Task::perform(
+                async move {
+                    let res =
+                        crate::commands::reindex_path_internal(std::path::Path::new(&path), state)
+                            .await;
+                    (path, res)
+                },
+                |(path, res)| match res {
+                    Ok(found) => Message::Reindexed(path, found),
+                    Err(e) => Message::StatusUpdate(tr_args("reindex-failed", &[("error", &e)])),
+                },
+            )
+        }
+        Message::Reindexed(path, found) => {
+            // The results now match what is on disk; drop their stale marks
+            let root = std::path::Path::new(&path);
+            for item in &mut app.results {
+                if std::path::Path::new(&item.path).starts_with(root) {
+                    item.stale = false;
+                }
+            }
+            app.rebuild_status = Some(tr_args("reindex-done", &[("count", &found)]));
+            Task::none()
+        }
+        Message::RemoveFromIndex(path) => {
+            app.context_menu = None;
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            app.results.retain(|r| r.path != path);
+            app.selected_index = None;
+            Task::perform(
+                async move { crate::commands::purge_path_internal(std::path::Path::new(&path), &state) },
+                |res| match res {
+                    Ok(_) => Message::StatusUpdate(tr("index-removed")),
+                    Err(e) => {
+                        Message::StatusUpdate(tr_args("index-remove-failed", &[("error", &e)]))
+                    }
+                },
+            )
+        }
+        Message::MoveToTrash(paths) => {
+            app.context_menu = None;
+            app.trash_confirm = Some(paths);
+            Task::none()
+        }
+        Message::CancelTrash => {
+            app.trash_confirm = None;
+            Task::none()
+        }
+        Message::ConfirmTrash => {
+            let (Some(paths), Some(state)) = (app.trash_confirm.take(), app.state.clone()) else {
+                return Task::none();
+            };
+            let moved = paths.clone();
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        crate::commands::trash_paths_internal(&paths, &state)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?
+                },
+                move |res| Message::Trashed(moved, res),
+            )
+        }
+        Message::Trashed(paths, Ok(_)) => {
+            let gone = |path: &str| {
+                paths
+                    .iter()
+                    .any(|p| std::path::Path::new(path).starts_with(p))
+            };
+            app.results.retain(|r| !gone(&r.path));
+            app.marked.retain(|p| !gone(p));
+            app.selected_index = None;
+            app.file_details = None;
+            app.similar_files.clear();
+            app.rebuild_status = Some(if paths.len() == 1 {
+                tr("trash-moved-one")
+            } else {
+                tr_args("trash-moved", &[("count", &paths.len())])
+            });
+            Task::none()
+        }
+        Message::Trashed(_, Err(e)) => {
+            app.rebuild_status = Some(tr_args("trash-failed", &[("error", &e)]));
+            Task::none()
+        }
+        Message::ModifiersChanged(modifiers) => {
+            app.modifiers = modifiers;
+            Task::none()
+        }
+        Message::ExcludeFromIndex(pattern) => {
+            app.context_menu = None;
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            // The command saves the pattern; keep the settings form in step
+            if !app.settings.exclude_patterns.contains(&pattern) {
+                app.settings.exclude_patterns.push(pattern.clone());
+            }
+            if std::path::Path::new(&pattern).is_absolute() {
+                app.results
+                    .retain(|r| !std::path::Path::new(&r.path).starts_with(&pattern));
+                app.selected_index = None;
+            }
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        crate::commands::exclude_from_index_internal(&pattern, &state)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?
+                },
+                Message::Excluded,
+            )
+        }
+        Message::Excluded(Ok(count)) => {
+            app.rebuild_status = Some(tr_args("exclude-done", &[("count", &count)]));
+            app.perform_search(false)
+        }
+        Message::Excluded(Err(e)) => {
+            app.rebuild_status = Some(tr_args("exclude-failed", &[("error", &e)]));
+            Task::none()
+        }
+        Message::FilterExtensionChanged(ext) => {
+            app.filter_extension = ext;
+            app.perform_search(true)
+        }
+        Message::ToggleFilterExtension(ext) => {
+            if app.filter_extensions.contains(&ext) {
+                app.filter_extensions.remove(&ext);
+            } else {
+                app.filter_extensions.insert(ext);
+            }
+            app.perform_search(false)
+        }
+        Message::ToggleCategory(category) => {
+            if !app.filter_categories.remove(&category) {
+                app.filter_categories.insert(category);
+            }
+            app.perform_search(false)
+        }
+        Message::MinSizeChanged(s) => {
+            app.min_size = s;
+            app.perform_search(true)
+        }
+        Message::MaxSizeChanged(s) => {
+            app.max_size = s;
+            app.perform_search(true)
+        }
+        Message::SizeUnitChanged(u) => {
+            app.size_unit = u;
+            app.perform_search(false)
+        }
+        Message::DateFilterChanged(d) => {
+            app.date_filter = d;
+            app.perform_search(false)
+        }
+        Message::SearchModeChanged(m) => {
+            app.search_mode = m;
+            app.perform_search(false)
+        }
+        Message::ToggleCaseSensitive(b) => {
+            app.settings.case_sensitive = b;
+            app.perform_search(false)
+        }
+        Message::ToggleWholeWord(b) => {
+            app.settings.whole_word = b;
+            app.perform_search(false)
+        }
+        Message::ToggleConfidenceCutoff(b) => {
+            app.settings.confidence_cutoff = b;
+            Task::batch([app.save_settings(), app.perform_search(false)])
+        }
+        Message::ToggleMatchFullPath(b) => {
+            app.settings.match_full_path = b;
+            Task::batch([app.save_settings(), app.perform_search(false)])
+        }
+        Message::ShowAllResults => {
+            app.results.append(&mut app.hidden_results);
+            app.sort_results();
+            app.announcement = Some(a11y::result_count_announcement(app.results.len(), 0));
+            Task::none()
+        }
+        Message::FocusNextRegion => {
+            let region = app.focus_region.next();
+            app.focus_region_changed(region)
+        }
+        Message::FocusPreviousRegion => {
+            let region = app.focus_region.previous();
+            app.focus_region_changed(region)
         }
         Message::ClearFilters => {
             app.filter_extension.clear();
             app.filter_extensions.clear();
+            app.filter_categories.clear();
             app.min_size.clear();
             app.max_size.clear();
             app.date_filter = DateFilter::Anytime;
@@ -846,6 +2202,30 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                 .collect();
             Task::none()
         }
+        Message::SensitiveFilesChanged(mode) => {
+            app.settings.sensitive_files = mode;
+            Task::none()
+        }
+        Message::IndexEncryptionChanged(mode) => {
+            app.settings.index_encryption = mode;
+            Task::none()
+        }
+        Message::SensitivePatternsChanged(s) => {
+            app.settings.sensitive_patterns = s
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            Task::none()
+        }
+        Message::CloudContentFoldersChanged(s) => {
+            app.settings.cloud_content_folders = s
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            Task::none()
+        }
         Message::CustomExtensionsChanged(s) => {
             app.settings.custom_extensions = s;
             Task::none()
@@ -854,13 +2234,290 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
             app.settings.global_hotkey = s;
             Task::none()
         }
+        Message::QuickSearchHotkeyChanged(s) => {
+            app.settings.quick_search_hotkey = s;
+            Task::none()
+        }
+        Message::EditorCommandChanged(s) => {
+            app.settings.editor_command = s;
+            Task::none()
+        }
+        Message::PdfViewerCommandChanged(s) => {
+            app.settings.pdf_viewer_command = s;
+            Task::none()
+        }
+        Message::OpenerExtensionChanged(s) => {
+            app.new_opener_extension = s;
+            Task::none()
+        }
+        Message::OpenerCommandChanged(s) => {
+            app.new_opener_command = s;
+            Task::none()
+        }
+        Message::AddFileOpener => {
+            let extension = app
+                .new_opener_extension
+                .trim()
+                .trim_start_matches('.')
+                .to_lowercase();
+            let command = app.new_opener_command.trim().to_string();
+            if extension.is_empty() || command.is_empty() {
+                return Task::none();
+            }
+            app.settings
+                .file_openers
+                .retain(|o| !o.extension.eq_ignore_ascii_case(&extension));
+            app.settings
+                .file_openers
+                .push(crate::settings::FileOpener { extension, command });
+            app.new_opener_extension.clear();
+            app.new_opener_command.clear();
+            app.save_settings()
+        }
+        Message::RemoveFileOpener(i) => {
+            if i < app.settings.file_openers.len() {
+                app.settings.file_openers.remove(i);
+                return app.save_settings();
+            }
+            Task::none()
+        }
+        Message::LanguageChanged(language) => {
+            crate::i18n::set_language(&language);
+            app.settings.language = language;
+            app.save_settings()
+        }
+        Message::SwitchProfile(name) => {
+            if name == crate::system::profile::active() {
+                return Task::none();
+            }
+            match crate::system::profile::relaunch(&name) {
+                Ok(()) => iced::exit(),
+                Err(e) => {
+                    app.rebuild_status =
+                        Some(tr_args("profile-failed", &[("error", &e.to_string())]));
+                    Task::none()
+                }
+            }
+        }
+        Message::NewProfileNameChanged(name) => {
+            app.new_profile_name = name;
+            Task::none()
+        }
+        Message::CreateProfile => {
+            let name = app.new_profile_name.trim().to_string();
+            let created = crate::get_base_data_dir()
+                .and_then(|base| crate::system::profile::create(&base, &name));
+            match created {
+                Ok(_) => {
+                    app.new_profile_name.clear();
+                    Task::done(Message::SwitchProfile(name))
+                }
+                Err(e) => {
+                    app.rebuild_status =
+                        Some(tr_args("profile-failed", &[("error", &e.to_string())]));
+                    Task::none()
+                }
+            }
+        }
+        Message::UnlockPassphraseChanged(passphrase) => {
+            if let Some(prompt) = &mut app.unlock {
+                prompt.passphrase = passphrase;
+            }
+            Task::none()
+        }
+        Message::Unlock => {
+            let Some(prompt) = &mut app.unlock else {
+                return Task::none();
+            };
+            if prompt.passphrase.is_empty() || prompt.busy {
+                return Task::none();
+            }
+            prompt.busy = true;
+            prompt.error = None;
+            let passphrase = prompt.passphrase.clone();
+            // Converting the stores to or from a passphrase can take a while
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || crate::unlock_stores(passphrase))
+                        .await
+                        .map_err(|e| FlashError::config("index_encryption", e.to_string()))?
+                },
+                Message::Unlocked,
+            )
+        }
+        Message::Unlocked(result) => match result.and_then(|()| crate::setup_app()) {
+            Ok((state, rx)) => {
+                crate::start_services(&state);
+                let window_id = app.window_id;
+                *app = App::new(Ok(state), Some(rx), None);
+                app.window_id = window_id;
+                startup_tasks(app)
+            }
+            Err(e) => {
+                if let Some(prompt) = &mut app.unlock {
+                    prompt.failed(&e);
+                }
+                Task::none()
+            }
+        },
+        Message::ShortcutChanged(action, combo) => {
+            *action.binding_mut(&mut app.settings.shortcuts) = combo;
+            Task::none()
+        }
+        Message::EditIndexRoot(i, edit) => {
+            if let Some(root) = app.settings.index_roots.get_mut(i) {
+                let split_globs = |s: &str| -> Vec<String> {
+                    s.split(',')
+                        .map(|p| p.trim().to_string())
+                        .filter(|p| !p.is_empty())
+                        .collect()
+                };
+                match edit {
+                    RootEdit::Include(s) => root.include = split_globs(&s),
+                    RootEdit::Exclude(s) => root.exclude = split_globs(&s),
+                    RootEdit::MaxDepth(s) => {
+                        if s.trim().is_empty() {
+                            root.max_depth = None;
+                        } else if let Ok(depth) = s.trim().parse::<usize>() {
+                            root.max_depth = Some(depth);
+                        }
+                    }
+                    RootEdit::IndexContent(on) => root.index_content = on,
+                    RootEdit::FollowSymlinks(on) => root.follow_symlinks = on,
+                    RootEdit::IncludeHidden(on) => root.include_hidden = on,
+                    RootEdit::IncludeSystem(on) => root.include_system = on,
+                    RootEdit::ElevatedAccess(on) => root.elevated_access = on,
+                    RootEdit::SeparateIndex(on) => root.separate_index = on,
+                    RootEdit::Rescan(schedule) => root.rescan = schedule,
+                }
+            }
+            Task::none()
+        }
+        Message::ScheduleChanged(s) => {
+            if let Ok(schedule) = s.parse() {
+                app.settings.reindex_schedule = schedule;
+            }
+            app.schedule_input = s;
+            Task::none()
+        }
+        Message::MacroNameChanged(s) => {
+            app.new_macro_name = s;
+            Task::none()
+        }
+        Message::MacroExpansionChanged(s) => {
+            app.new_macro_expansion = s;
+            Task::none()
+        }
+        Message::AddQueryMacro => {
+            let name = app
+                .new_macro_name
+                .trim()
+                .trim_start_matches('@')
+                .to_string();
+            let expansion = app.new_macro_expansion.trim().to_string();
+            if name.is_empty()
+                || expansion.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Task::none();
+            }
+
+            app.settings
+                .query_macros
+                .retain(|m| !m.name.eq_ignore_ascii_case(&name));
+            app.settings
+                .query_macros
+                .push(crate::settings::QueryMacro { name, expansion });
+            app.new_macro_name.clear();
+            app.new_macro_expansion.clear();
+            app.save_settings()
+        }
+        Message::RemoveQueryMacro(i) => {
+            if i < app.settings.query_macros.len() {
+                app.settings.query_macros.remove(i);
+                return app.save_settings();
+            }
+            Task::none()
+        }
+        Message::ToggleSearchHistory => {
+            app.history_open = !app.history_open;
+            Task::none()
+        }
+        Message::RunHistoryQuery(query) => {
+            app.history_open = false;
+            app.search_query = query;
+            app.history_recorded = false;
+            Task::batch([app.record_search(), app.perform_search(false)])
+        }
+        Message::DeleteHistoryEntry(query) => app.edit_search_history(|state| {
+            crate::commands::delete_search_history_internal(&query, state)
+        }),
+        Message::PinHistoryEntry(query, pinned) => app.edit_search_history(|state| {
+            crate::commands::pin_search_history_internal(&query, pinned, state)
+        }),
+        Message::ClearSearchHistory => {
+            app.edit_search_history(crate::commands::clear_search_history_internal)
+        }
+        Message::ToggleSearchHistoryEnabled(enabled) => {
+            app.settings.search_history_enabled = enabled;
+            if !enabled {
+                app.history_open = false;
+            }
+            app.save_settings()
+        }
+        Message::ApplyQueryMacro(name) => {
+            // Replace the partially typed `@prefix` with the full macro name
+            let keep = app
+                .search_query
+                .rfind(char::is_whitespace)
+                .map_or(0, |i| i + 1);
+            app.search_query.truncate(keep);
+            app.search_query.push('@');
+            app.search_query.push_str(&name);
+            app.search_query.push(' ');
+            app.history_recorded = false;
+            app.perform_search(false)
+        }
         Message::AddFolder => Task::done(Message::PickFolder),
+        Message::RemoteRootChanged(input) => {
+            app.remote_root_input = input;
+            Task::none()
+        }
+        Message::RemotePasswordChanged(input) => {
+            app.remote_password_input = input;
+            Task::none()
+        }
+        Message::AddRemoteRoot => {
+            let Some(url) = crate::scanner::remote::RemoteUrl::parse(&app.remote_root_input) else {
+                return Task::done(Message::StatusUpdate(tr("remote-invalid")));
+            };
+            if !url.scheme.is_supported() {
+                return Task::done(Message::StatusUpdate(tr("remote-unsupported")));
+            }
+            let password = std::mem::take(&mut app.remote_password_input);
+            if !password.is_empty()
+                && let Err(e) = crate::scanner::remote::credentials::store(&url, &password)
+            {
+                return Task::done(Message::StatusUpdate(tr_args(
+                    "remote-password-failed",
+                    &[("error", &e.to_string())],
+                )));
+            }
+            app.remote_root_input.clear();
+            // Nothing to walk locally for an estimate; the scan connects
+            update(app, Message::AddIndexRoot(url.to_string()))
+        }
         Message::ToggleMinimizeToTray(b) => {
             app.settings.minimize_to_tray = b;
             if b {
                 if app.tray_icon.is_none() {
                     app.tray_icon = crate::system::tray::create_tray_icon().ok();
                 }
+                if let Some(tray) = &app.tray_icon {
+                    tray.set_paused(app.indexing_paused);
+                }
             } else {
                 app.tray_icon = None;
             }
@@ -878,6 +2535,21 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
             app.settings.use_gitignore = b;
             Task::none()
         }
+        Message::ToggleMftEnumeration(b) => {
+            app.settings.use_mft_enumeration = b;
+            Task::none()
+        }
+        Message::ToggleOfferRemovableIndexing(b) => {
+            app.settings.offer_removable_indexing = b;
+            Task::none()
+        }
+        Message::ToggleCodeAwareIndexing(b) => {
+            app.settings.code_aware_indexing = b;
+            if let Some(state) = &app.state {
+                state.indexer.set_code_aware_indexing(b);
+            }
+            Task::none()
+        }
         Message::ToggleTheme => {
             app.is_dark = !app.is_dark;
             app.settings.theme = if app.is_dark {
@@ -889,10 +2561,11 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
         }
         Message::RebuildIndex => {
             if let Some(state) = &app.state {
+                state.reset_scan_control();
                 let state = state.clone();
-                let index_dirs = app.settings.index_dirs.clone();
+                let index_roots = app.settings.index_roots.clone();
                 app.rebuild_progress = Some(0.0);
-                app.rebuild_status = Some("Rebuilding index...".to_string());
+                app.rebuild_status = Some(tr("status-rebuilding"));
                 return Task::future(async move {
                     if let Err(e) = state.indexer.clear() {
                         tracing::error!("Failed to clear search index: {e}");
@@ -908,35 +2581,114 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                         }
                     }
 
-                    let dirs_to_scan = if index_dirs.is_empty() {
-                        crate::commands::get_home_dir_internal()
-                            .ok()
-                            .into_iter()
-                            .collect::<Vec<String>>()
-                    } else {
-                        index_dirs
-                    };
-
-                    for dir in dirs_to_scan {
-                        let _ = state
-                            .scanner
-                            .scan_directory(
-                                std::path::PathBuf::from(dir),
-                                vec![],
-                                state.indexing_cancel.clone(),
-                            )
-                            .await;
+                    let roots = crate::commands::index_roots_internal(index_roots);
+                    for (root, e) in state
+                        .scanner
+                        .scan_roots(roots, vec![], state.indexing_cancel.clone())
+                        .await
+                    {
+                        tracing::error!("Rebuilding {root} failed: {e}");
                     }
                     Message::IndexRebuilt
                 });
             }
             Task::none()
         }
+        Message::RefreshIndex => {
+            if let Some(state) = &app.state {
+                state.reset_scan_control();
+                let state = state.clone();
+                let index_roots = app.settings.index_roots.clone();
+                let exclude_patterns = app.settings.exclude_list();
+                app.rebuild_progress = Some(0.0);
+                app.rebuild_status = Some(tr("status-refreshing"));
+                return Task::future(async move {
+                    let roots = crate::commands::index_roots_internal(index_roots);
+                    for (root, e) in state
+                        .scanner
+                        .refresh_roots(roots, exclude_patterns, state.indexing_cancel.clone())
+                        .await
+                    {
+                        tracing::error!("Incremental refresh of {root} failed: {e}");
+                    }
+                    Message::IndexRebuilt
+                });
+            }
+            Task::none()
+        }
+        Message::PauseIndexing => {
+            if let Some(state) = &app.state {
+                crate::commands::pause_indexing_internal(state);
+                app.indexing_paused = true;
+                app.rebuild_status = Some(tr("status-paused"));
+                if let Some(tray) = &app.tray_icon {
+                    tray.set_paused(true);
+                }
+            }
+            Task::none()
+        }
+        Message::TogglePauseIndexing => Task::done(if app.indexing_paused {
+            Message::ResumeIndexing
+        } else {
+            Message::PauseIndexing
+        }),
+        Message::ResumeIndexing => {
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            if app.indexing_paused {
+                state.scanner.resume();
+                app.indexing_paused = false;
+                if let Some(tray) = &app.tray_icon {
+                    tray.set_paused(false);
+                }
+                app.rebuild_status = Some(tr("status-resuming"));
+                return Task::none();
+            }
+
+            // Nothing is running: restart the scans a cancel or exit cut short
+            let roots: Vec<crate::settings::IndexRoot> = state
+                .metadata_db
+                .get_pending_scans()
+                .unwrap_or_default()
+                .iter()
+                .map(|checkpoint| {
+                    app.settings
+                        .root_profile(std::path::Path::new(&checkpoint.root))
+                })
+                .collect();
+            state.reset_scan_control();
+            app.pending_scan_files = None;
+            app.rebuild_progress = Some(0.0);
+            app.rebuild_status = Some(tr("status-resuming-interrupted"));
+            Task::future(async move {
+                let roots = roots
+                    .into_iter()
+                    .filter(|root| std::path::Path::new(&root.path).exists())
+                    .collect();
+                let _ = state
+                    .scanner
+                    .scan_roots(roots, vec![], state.indexing_cancel.clone())
+                    .await;
+                Message::IndexRebuilt
+            })
+        }
+        Message::CancelIndexing => {
+            if let Some(state) = &app.state {
+                crate::commands::cancel_indexing_internal(state);
+                app.indexing_paused = false;
+                app.rebuild_status = Some(tr("status-cancelling"));
+            }
+            Task::none()
+        }
         Message::IndexDirAdded(dir) => {
-            if !dir.is_empty() && !app.settings.index_dirs.contains(&dir) {
-                app.settings.index_dirs.push(dir.clone());
+            if !dir.is_empty() && !app.settings.index_roots.iter().any(|r| r.path == dir) {
+                app.settings
+                    .index_roots
+                    .push(crate::settings::IndexRoot::new(dir.clone()));
                 app.new_index_dir.clear();
                 if let Some(state) = &app.state {
+                    state.reset_scan_control();
                     let state = state.clone();
                     let path_clone = dir;
                     let save_task = app.save_settings();
@@ -944,7 +2696,7 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                         let _ = state
                             .scanner
                             .scan_directory(
-                                std::path::PathBuf::from(path_clone),
+                                crate::settings::IndexRoot::new(path_clone),
                                 vec![],
                                 state.indexing_cancel.clone(),
                             )
@@ -966,6 +2718,7 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
         Message::SaveSettings => app.save_settings(),
         Message::ResetSettings => {
             app.settings = AppSettings::default();
+            app.schedule_input = app.settings.reindex_schedule.to_string();
             Task::none()
         }
         Message::ThemeChanged(t) => {
@@ -1009,8 +2762,44 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
             app.rebuild_progress = None;
             app.rebuild_status = None;
             app.rebuild_eta = None;
+            app.indexing_paused = false;
+            app.pending_scan_files = app.state.as_ref().and_then(pending_scan_files);
+            app.load_recent_files()
+        }
+        Message::RecentFilesLoaded(files) => {
+            app.recent_files = files;
             Task::none()
         }
+        Message::PinFile(path) => {
+            app.context_menu = None;
+            if !app.pinned_files.contains(&path) {
+                app.pinned_files.push(path.clone());
+            }
+            match &app.state {
+                Some(state) => match crate::commands::pin_file_internal(path, state) {
+                    Ok(()) => Task::none(),
+                    Err(e) => Task::done(Message::StatusUpdate(tr_args(
+                        "pin-failed",
+                        &[("error", &e)],
+                    ))),
+                },
+                None => Task::none(),
+            }
+        }
+        Message::UnpinFile(path) => {
+            app.context_menu = None;
+            app.pinned_files.retain(|p| *p != path);
+            match &app.state {
+                Some(state) => match crate::commands::unpin_file_internal(&path, state) {
+                    Ok(()) => Task::none(),
+                    Err(e) => Task::done(Message::StatusUpdate(tr_args(
+                        "unpin-failed",
+                        &[("error", &e)],
+                    ))),
+                },
+                None => Task::none(),
+            }
+        }
         Message::StatusUpdate(s) => {
             app.rebuild_status = Some(s);
             Task::none()
@@ -1021,54 +2810,241 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
             }
             Task::none()
         }
+        Message::WindowUnfocused(_) if app.quick_search => Task::done(Message::CloseQuickSearch),
         Message::WindowUnfocused(id) => iced::window::minimize(id, true),
-        Message::ToggleWindow | Message::RestoreWindow => app
-            .window_id
-            .map_or_else(Task::none, |id| iced::window::minimize(id, false)),
+        Message::ToggleQuickSearch if app.quick_search => Task::done(Message::CloseQuickSearch),
+        Message::ToggleQuickSearch => app.window_id.map_or_else(Task::none, |id| {
+            iced::window::size(id).map(Message::QuickSearchOpened)
+        }),
+        Message::QuickSearchOpened(size) => {
+            let Some(id) = app.window_id else {
+                return Task::none();
+            };
+            app.quick_search = true;
+            app.restore_size = Some(size);
+            app.active_tab = Tab::Search;
+            app.search_query.clear();
+            app.results.clear();
+            app.selected_index = None;
+            Task::batch([
+                show_window(id),
+                iced::window::toggle_decorations(id),
+                iced::window::set_level(id, iced::window::Level::AlwaysOnTop),
+                iced::window::resize(id, QUICK_SEARCH_SIZE),
+                // Top center of the screen, where palettes usually sit
+                iced::window::monitor_size(id).and_then(move |monitor| {
+                    iced::window::move_to(
+                        id,
+                        iced::Point::new(
+                            ((monitor.width - QUICK_SEARCH_SIZE.width) / 2.0).max(0.0),
+                            monitor.height / 5.0,
+                        ),
+                    )
+                }),
+            ])
+            .chain(iced::window::gain_focus(id))
+            .chain(iced::widget::operation::focus(get_search_input_id()))
+        }
+        Message::CloseQuickSearch => {
+            let Some(id) = app.window_id.filter(|_| app.quick_search) else {
+                return Task::none();
+            };
+            app.quick_search = false;
+            let size = app.restore_size.take().unwrap_or(DEFAULT_WINDOW_SIZE);
+            Task::batch([
+                iced::window::set_level(id, iced::window::Level::Normal),
+                iced::window::toggle_decorations(id),
+                iced::window::resize(id, size),
+            ])
+            .chain(iced::window::minimize(id, true))
+        }
+        Message::ToggleWindow => app.window_id.map_or_else(Task::none, show_window),
+        Message::RestoreWindow => app.window_id.map_or_else(Task::none, |id| {
+            show_window(id).chain(iced::window::gain_focus(id))
+        }),
+        // Hidden rather than closed, so the watcher and any scan keep going
+        // and the tray can bring the window back
+        Message::WindowCloseRequested(id) if app.tray_icon.is_some() => {
+            iced::window::set_mode(id, iced::window::Mode::Hidden)
+        }
+        Message::WindowCloseRequested(id) => iced::window::close(id),
         Message::DismissError => {
             app.error = None;
             app.search_error = None;
             app.db_corrupted_dismissed = true;
             Task::none()
         }
-        Message::Quit => app.window_id.map_or_else(Task::none, iced::window::close),
+        Message::Quit => iced::exit(),
         Message::PickFolder => Task::future(async move {
             let handle = rfd::AsyncFileDialog::new()
-                .set_title("Select Folder to Index")
+                .set_title(tr("folder-picker-title"))
                 .pick_folder()
                 .await;
             Message::FolderPicked(handle.map(|h| h.path().to_string_lossy().to_string()))
         }),
         Message::FolderPicked(Some(path)) => {
-            if !app.settings.index_dirs.contains(&path) {
-                app.settings.index_dirs.push(path.clone());
-                if let Some(state) = &app.state {
-                    let state = state.clone();
-                    let path_clone = path;
-                    let save_task = app.save_settings();
-                    let scan_task = Task::future(async move {
-                        let _ = state
-                            .scanner
-                            .scan_directory(
-                                std::path::PathBuf::from(path_clone),
-                                vec![],
-                                state.indexing_cancel.clone(),
-                            )
-                            .await;
-                        Message::IndexRebuilt
-                    });
-                    return Task::batch(vec![save_task, scan_task]);
+            if app.settings.index_roots.iter().any(|r| r.path == path) {
+                return Task::done(Message::StatusUpdate(tr_args(
+                    "drop-already-indexed",
+                    &[("path", &path)],
+                )));
+            }
+            let Some(state) = app.state.clone() else {
+                return Task::none();
+            };
+            app.rebuild_status = Some(tr_args("estimate-running", &[("path", &path)]));
+            Task::perform(
+                async move {
+                    crate::commands::estimate_scan_internal(path.clone(), &state)
+                        .await
+                        .map_err(|error| {
+                            tr_args("estimate-failed", &[("path", &path), ("error", &error)])
+                        })
+                },
+                Message::ScanEstimated,
+            )
+        }
+        Message::ScanEstimated(Ok(estimate)) => {
+            app.rebuild_status = None;
+            app.scan_estimate = Some(estimate);
+            Task::none()
+        }
+        Message::ScanEstimated(Err(error)) => Task::done(Message::StatusUpdate(error)),
+        Message::ConfirmScan => app
+            .scan_estimate
+            .take()
+            .map_or_else(Task::none, |estimate| {
+                update(app, Message::AddIndexRoot(estimate.root))
+            }),
+        Message::CancelScan => {
+            app.scan_estimate = None;
+            Task::none()
+        }
+        Message::PollVolumes => Task::perform(
+            async {
+                tokio::task::spawn_blocking(crate::scanner::volumes::refresh)
+                    .await
+                    .unwrap_or_default()
+            },
+            Message::VolumesPolled,
+        ),
+        Message::VolumesPolled(volumes) => {
+            // Roots on a drive that was unplugged go offline, so their
+            // results stay listed, grayed out, instead of being pruned
+            let mut changed = false;
+            if let Some(state) = &app.state {
+                for root in &app.settings.index_roots {
+                    let Some(id) = &root.volume_id else {
+                        continue;
+                    };
+                    let path = std::path::Path::new(&root.path);
+                    let plugged = volumes
+                        .iter()
+                        .any(|v| &v.id == id && path.starts_with(&v.mount_point));
+                    changed |= state.indexer.offline_roots().set_offline(path, !plugged);
                 }
             }
+            let seen = volumes.iter().map(|v| v.id.clone()).collect();
+            if let Some(previous) = app.seen_volumes.replace(seen)
+                && app.settings.offer_removable_indexing
+                && app.new_volume.is_none()
+            {
+                let roots = &app.settings.index_roots;
+                app.new_volume = volumes.into_iter().find(|v| {
+                    !previous.contains(&v.id)
+                        && !roots.iter().any(|r| {
+                            r.volume_id.as_ref() == Some(&v.id)
+                                || v.mount_point.starts_with(&r.path)
+                        })
+                });
+            }
+            if changed && !app.search_query.is_empty() {
+                return app.perform_search(false);
+            }
+            Task::none()
+        }
+        Message::IndexVolume => {
+            let Some(volume) = app.new_volume.take() else {
+                return Task::none();
+            };
+            app.add_index_root(crate::settings::IndexRoot {
+                index_content: false,
+                volume_id: Some(volume.id),
+                ..crate::settings::IndexRoot::new(volume.mount_point.to_string_lossy())
+            })
+        }
+        Message::DismissVolume => {
+            app.new_volume = None;
             Task::none()
         }
+        Message::AddIndexRoot(path) => {
+            if app.settings.index_roots.iter().any(|r| r.path == path) {
+                return Task::none();
+            }
+            app.add_index_root(crate::settings::IndexRoot::new(path))
+        }
+        Message::FolderDropped(path) => {
+            if !path.is_dir() {
+                return Task::done(Message::StatusUpdate(tr("drop-not-folder")));
+            }
+            let dir = path.to_string_lossy().to_string();
+            if app.settings.index_roots.iter().any(|r| r.path == dir) {
+                return Task::done(Message::StatusUpdate(tr_args(
+                    "drop-already-indexed",
+                    &[("path", &dir)],
+                )));
+            }
+            let profile = crate::settings::IndexRoot::new(dir.clone());
+            let excludes = app.settings.exclude_list();
+            let use_gitignore = app.settings.use_gitignore;
+            let count_task = Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        crate::scanner::drive_scanner::estimate_file_count(
+                            &profile,
+                            &excludes,
+                            use_gitignore,
+                            DROP_ESTIMATE_CAP,
+                        )
+                    })
+                    .await
+                    .unwrap_or_default()
+                },
+                move |count| {
+                    let (key, count) = if count >= DROP_ESTIMATE_CAP {
+                        ("drop-indexing-over", DROP_ESTIMATE_CAP)
+                    } else {
+                        ("drop-indexing-about", count)
+                    };
+                    Message::StatusUpdate(tr_args(key, &[("path", &dir), ("count", &count)]))
+                },
+            );
+            let add_task = update(
+                app,
+                Message::AddIndexRoot(path.to_string_lossy().to_string()),
+            );
+            Task::batch(vec![add_task, count_task])
+        }
         Message::ToggleSidebar => {
             app.sidebar_collapsed = !app.sidebar_collapsed;
             Task::none()
         }
         Message::RemoveFolder(i) | Message::RemoveIndexDir(i) => {
-            if i < app.settings.index_dirs.len() {
-                let removed_dir = app.settings.index_dirs.remove(i);
+            if i < app.settings.index_roots.len() {
+                let removed_dir = app.settings.index_roots.remove(i).path;
+                if let Some(url) = crate::scanner::remote::RemoteUrl::parse(&removed_dir) {
+                    use crate::scanner::remote::{RemoteUrl, credentials};
+                    // Other roots on the same share still log in with it
+                    let shared = app
+                        .settings
+                        .index_roots
+                        .iter()
+                        .filter_map(|root| RemoteUrl::parse(&root.path))
+                        .any(|other| credentials::same_login(&url, &other));
+                    if !shared && let Err(e) = credentials::forget(&url) {
+                        tracing::warn!("Failed to delete the saved password for {url}: {e}");
+                    }
+                }
                 if let Some(state) = &app.state {
                     let state = state.clone();
                     let save_task = app.save_settings();
@@ -1095,7 +3071,10 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                             }
                             if removed_any {
                                 let _ = state.indexer.commit();
-                                state.indexer.invalidate_cache();
+                            }
+                            if let Some(filename_index) = &state.filename_index {
+                                let _ = filename_index.remove_file(&removed_dir);
+                                let _ = filename_index.commit();
                             }
                         }
                         Message::IndexRebuilt
@@ -1123,14 +3102,29 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
                     extension: item.extension.clone(),
                     modified: item.modified,
                     size: item.size,
-                    matched_terms: Vec::new(),
+                    matched_terms: item.matched_terms.clone(),
                     snippets: item.snippets.clone(),
+                    owner: item.owner.clone(),
+                    readonly: item.readonly,
+                    hidden: item.hidden,
+                    category: item.category,
+                    mime: item.mime.clone(),
+                    created: item.created,
+                    volume: item.volume.clone(),
+                    unavailable: item.unavailable,
+                    stale: item.stale,
+                    is_dir: item.is_dir,
                 })
                 .collect();
             Task::future(async move {
+                let count = results.len();
                 match crate::commands::export_results_internal(results, format).await {
-                    Ok(()) => Message::StatusUpdate("Results exported successfully".to_string()),
-                    Err(e) => Message::StatusUpdate(format!("Export failed: {e}")),
+                    Ok(Some(path)) => Message::StatusUpdate(tr_args(
+                        "export-done",
+                        &[("count", &count), ("path", &path)],
+                    )),
+                    Ok(None) => Message::NoOp,
+                    Err(e) => Message::StatusUpdate(tr_args("export-failed", &[("error", &e)])),
                 }
             })
         }
@@ -1198,12 +3192,71 @@ pub fn update(app: &mut App, message: Message) -> Task<Message> {
 }
 
 pub fn view(app: &App) -> Element<'_, Message> {
-    match app.active_tab {
+    if let Some(prompt) = &app.unlock {
+        return unlock::unlock_view(prompt);
+    }
+    if app.quick_search {
+        return search::quick_search_view(app);
+    }
+    let content = match app.active_tab {
         Tab::Search => search::search_view(app),
         Tab::Settings => settings::settings_view(app),
+        Tab::Statistics => statistics::statistics_view(app),
+    };
+    let dialog = match (&app.scan_estimate, &app.trash_confirm, &app.open_with) {
+        (Some(estimate), _, _) => Some(settings::scan_estimate_dialog(estimate, &app.settings)),
+        (None, Some(paths), _) => Some(search::trash_dialog(paths)),
+        (None, None, Some((path, apps))) => Some(search::open_with_dialog(path, apps)),
+        (None, None, None) => app
+            .reconnect
+            .as_ref()
+            .map(|(path, volume)| search::reconnect_dialog(path, volume.as_deref()))
+            .or_else(|| app.new_volume.as_ref().map(settings::new_volume_dialog)),
+    };
+    match dialog {
+        Some(dialog) => iced::widget::stack![
+            content,
+            iced::widget::opaque(iced::widget::center(dialog).style(theme::modal_backdrop))
+        ]
+        .into(),
+        None => content,
     }
 }
 
+/// Results rendered at once. Laying out thousands of cards makes every frame
+/// slow, so further pages are added as the list is scrolled.
+pub(crate) const RESULT_PAGE: usize = 200;
+
+/// Brings the window back from the tray or the taskbar.
+fn show_window(id: iced::window::Id) -> Task<Message> {
+    iced::window::set_mode(id, iced::window::Mode::Windowed)
+        .chain(iced::window::minimize(id, false))
+}
+
+/// Size of the window while it is the quick search palette
+const QUICK_SEARCH_SIZE: iced::Size = iced::Size::new(640.0, 420.0);
+
+/// Size the window goes back to if its own size couldn't be read
+const DEFAULT_WINDOW_SIZE: iced::Size = iced::Size::new(1024.0, 768.0);
+
+/// Recently modified files listed on the home view
+const HOME_RECENT_FILES: usize = 15;
+
+/// Similar files listed under the preview
+const SIMILAR_FILES: usize = 8;
+
+/// Files counted for the estimate shown when a folder is dropped
+const DROP_ESTIMATE_CAP: usize = 100_000;
+
+/// Wait after a write to the settings file before reading it back
+const SETTINGS_RELOAD_DELAY: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// How often the status bar's live-update indicator is refreshed.
+const WATCHER_STATUS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How often removable drives are checked for being plugged in or out.
+const VOLUME_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
 #[allow(clippy::too_many_lines)]
 pub fn subscription(app: &App) -> Subscription<Message> {
     let progress_sub = app
@@ -1228,6 +3281,8 @@ pub fn subscription(app: &App) -> Subscription<Message> {
 
     let event_sub = iced::window::events().map(|(id, event)| match event {
         iced::window::Event::Unfocused => Message::WindowUnfocused(id),
+        iced::window::Event::CloseRequested => Message::WindowCloseRequested(id),
+        iced::window::Event::FileDropped(path) => Message::FolderDropped(path),
         iced::window::Event::Opened { .. } | iced::window::Event::Focused => {
             Message::WindowIdCaptured(id)
         }
@@ -1235,52 +3290,64 @@ pub fn subscription(app: &App) -> Subscription<Message> {
     });
 
     let hotkey_str = app.settings.global_hotkey.clone();
+    let quick_hotkey_str = app.settings.quick_search_hotkey.clone();
     let minimize_to_tray = app.settings.minimize_to_tray;
     let system_sub = Subscription::run_with(
         SystemSubscriptionData {
             hotkey_str,
+            quick_hotkey_str,
             minimize_to_tray,
         },
         |data| {
             let hotkey_str = data.hotkey_str.clone();
+            let quick_hotkey_str = data.quick_hotkey_str.clone();
             iced::stream::channel(
                 10,
                 move |mut output: iced::futures::channel::mpsc::Sender<Message>| {
                     let hotkey_str = hotkey_str.clone();
+                    let quick_hotkey_str = quick_hotkey_str.clone();
                     async move {
                         let (tx, mut rx) = tokio::sync::mpsc::channel(10);
 
                         std::thread::spawn(move || {
                             let manager = global_hotkey::GlobalHotKeyManager::new().ok();
-                            let registered_hotkey = if let Some(ref m) = manager
-                                && let Some(hk) = parse_hotkey(&hotkey_str)
-                                && m.register(hk).is_ok()
-                            {
-                                Some(hk)
-                            } else {
-                                None
+                            let register = |s: &str| {
+                                let m = manager.as_ref()?;
+                                let hk = parse_hotkey(s)?;
+                                m.register(hk).ok().map(|()| hk)
                             };
+                            let registered_hotkey = register(&hotkey_str);
+                            let quick_hotkey = register(&quick_hotkey_str);
 
                             loop {
                                 if let Ok(event) =
                                     global_hotkey::GlobalHotKeyEvent::receiver().try_recv()
-                                    && let Some(hk) = registered_hotkey
-                                    && event.id == hk.id()
                                     && event.state == global_hotkey::HotKeyState::Released
                                 {
-                                    let _ = tx.blocking_send(Message::ToggleWindow);
+                                    if registered_hotkey.is_some_and(|hk| event.id == hk.id()) {
+                                        let _ = tx.blocking_send(Message::ToggleWindow);
+                                    } else if quick_hotkey.is_some_and(|hk| event.id == hk.id()) {
+                                        let _ = tx.blocking_send(Message::ToggleQuickSearch);
+                                    }
                                 }
 
                                 if let Ok(event) = tray_icon::menu::MenuEvent::receiver().try_recv()
                                 {
-                                    match event.id.0.as_str() {
-                                        "show" => {
-                                            let _ = tx.blocking_send(Message::RestoreWindow);
+                                    let message = match event.id.0.as_str() {
+                                        crate::system::tray::MENU_SHOW => {
+                                            Some(Message::RestoreWindow)
+                                        }
+                                        crate::system::tray::MENU_PAUSE => {
+                                            Some(Message::TogglePauseIndexing)
                                         }
-                                        "quit" => {
-                                            let _ = tx.blocking_send(Message::Quit);
+                                        crate::system::tray::MENU_REBUILD => {
+                                            Some(Message::RebuildIndex)
                                         }
-                                        _ => {}
+                                        crate::system::tray::MENU_QUIT => Some(Message::Quit),
+                                        _ => None,
+                                    };
+                                    if let Some(message) = message {
+                                        let _ = tx.blocking_send(message);
                                     }
                                 }
 
@@ -1314,25 +3381,96 @@ pub fn subscription(app: &App) -> Subscription<Message> {
                 iced::keyboard::Key::Named(iced::keyboard::key::Named::ArrowDown) => {
                     Message::SelectNextResult
                 }
-                iced::keyboard::Key::Named(iced::keyboard::key::Named::Enter) => {
-                    if modifiers.control() {
-                        Message::ShowSelectedInFolder
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::F3) => {
+                    if modifiers.shift() {
+                        Message::PreviousPreviewMatch
                     } else {
-                        Message::OpenSelectedResult
+                        Message::NextPreviewMatch
                     }
                 }
-                iced::keyboard::Key::Character(ref c)
-                    if c.eq_ignore_ascii_case("c") && modifiers.control() =>
-                {
-                    Message::CopySelectedPath
-                }
-                _ => Message::NoOp,
+                key => Message::KeyPressed(key, modifiers),
             }
         }
+        iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+            Message::ModifiersChanged(modifiers)
+        }
         _ => Message::NoOp,
     });
 
-    Subscription::batch(vec![progress_sub, event_sub, system_sub, keyboard_sub])
+    // A second launch brings this window to the front instead of opening one
+    let ipc_sub = Subscription::run(|| {
+        crate::system::ipc::focus_requests()
+            .into_stream()
+            .map(|()| Message::RestoreWindow)
+    });
+
+    let settings_sub = app.state.as_ref().map_or_else(Subscription::none, |state| {
+        Subscription::run_with(state.settings_manager.path().to_path_buf(), |path| {
+            watch_settings_file(path.clone())
+        })
+    });
+
+    let watcher_sub = if app.state.is_some() {
+        iced::time::every(WATCHER_STATUS_INTERVAL).map(|_| Message::RefreshWatcherStatus)
+    } else {
+        Subscription::none()
+    };
+
+    let volume_sub = if app.state.is_some() {
+        iced::time::every(VOLUME_POLL_INTERVAL).map(|_| Message::PollVolumes)
+    } else {
+        Subscription::none()
+    };
+
+    Subscription::batch(vec![
+        progress_sub,
+        event_sub,
+        system_sub,
+        keyboard_sub,
+        watcher_sub,
+        ipc_sub,
+        settings_sub,
+        volume_sub,
+    ])
+}
+
+/// Reports each write to the settings file. The folder is watched rather
+/// than the file, since saves replace the file by renaming over it.
+fn watch_settings_file(path: std::path::PathBuf) -> impl iced::futures::Stream<Item = Message> {
+    use notify::Watcher;
+    iced::stream::channel(
+        1,
+        move |mut output: iced::futures::channel::mpsc::Sender<Message>| async move {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let file = path.clone();
+            let Ok(mut watcher) =
+                notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                    if let Ok(event) = res
+                        && event.paths.contains(&file)
+                    {
+                        let _ = tx.send(());
+                    }
+                })
+            else {
+                return;
+            };
+            let Some(dir) = path.parent() else {
+                return;
+            };
+            if let Err(e) = watcher.watch(dir, notify::RecursiveMode::NonRecursive) {
+                tracing::warn!("Not watching {} for settings changes: {e}", dir.display());
+                return;
+            }
+            while rx.recv().await.is_some() {
+                // A save is several events; read the file once they settle
+                tokio::time::sleep(SETTINGS_RELOAD_DELAY).await;
+                while rx.try_recv().is_ok() {}
+                if output.send(Message::SettingsFileChanged).await.is_err() {
+                    break;
+                }
+            }
+        },
+    )
 }
 
 pub const fn app_theme(app: &App) -> iced::Theme {
@@ -1344,17 +3482,24 @@ pub const fn app_theme(app: &App) -> iced::Theme {
 }
 
 pub fn app_title(app: &App) -> String {
-    app.rebuild_status.as_ref().map_or_else(
-        || "Flash Search".to_string(),
-        |status| format!("Flash Search - {status}"),
-    )
+    let profile = crate::system::profile::active();
+    let name = if profile == crate::system::profile::DEFAULT_PROFILE {
+        "Flash Search".to_string()
+    } else {
+        format!("Flash Search ({profile})")
+    };
+    // Screen readers announce title changes, so result counts are surfaced here too
+    app.rebuild_status
+        .as_ref()
+        .or(app.announcement.as_ref())
+        .map_or_else(|| name.clone(), |status| format!("{name} - {status}"))
 }
 
 /// # Panics
 ///
 /// Panics if the application fails to run.
 pub fn run_ui(
-    state: &Result<std::sync::Arc<AppState>, String>,
+    state: &Result<std::sync::Arc<AppState>, FlashError>,
     progress_rx: flume::Receiver<ProgressEvent>,
     initial_dir: Option<String>,
 ) {
@@ -1365,11 +3510,7 @@ pub fn run_ui(
         move || {
             let rx = progress_rx.lock().take();
             let app = App::new(state_clone.clone(), rx, initial_dir_clone.clone());
-            let task = if app.settings.auto_index_on_startup {
-                Task::done(Message::RebuildIndex)
-            } else {
-                Task::none()
-            };
+            let task = startup_tasks(&app);
             (app, task)
         },
         update,
@@ -1378,6 +3519,11 @@ pub fn run_ui(
     .title(app_title)
     .theme(app_theme)
     .subscription(subscription)
+    // Close requests are handled in `update` so the window can go to the tray
+    .window(iced::window::Settings {
+        exit_on_close_request: false,
+        ..iced::window::Settings::default()
+    })
     .run()
     {
         tracing::error!("Iced application failed to run: {e}");
@@ -1385,6 +3531,51 @@ pub fn run_ui(
     }
 }
 
+/// What a window starts with once the stores are open.
+fn startup_tasks(app: &App) -> Task<Message> {
+    crate::i18n::set_language(&app.settings.language);
+    // Without auto-indexing an interrupted scan is only offered in the
+    // status bar; with it, finishing that scan replaces the refresh
+    let task = if app.settings.auto_index_on_startup && app.pending_scan_files.is_some() {
+        Task::done(Message::ResumeIndexing)
+    } else if app.settings.auto_index_on_startup {
+        Task::done(Message::RefreshIndex)
+    } else {
+        Task::none()
+    };
+    let recent = app.load_recent_files();
+    Task::batch([task, recent])
+}
+
+/// Status bar label for the watcher and whether every root is being watched,
+/// or `None` when there are no roots to watch.
+pub(crate) fn watcher_summary(status: &crate::models::WatcherStatus) -> Option<(String, bool)> {
+    let watched = status.watched_roots.len();
+    let total = watched + status.unwatched_roots.len();
+    if total == 0 {
+        return None;
+    }
+    Some(if watched < total {
+        (
+            tr_args("live-partial", &[("watched", &watched), ("total", &total)]),
+            false,
+        )
+    } else if status.pending_events > 0 {
+        (
+            tr_args("live-pending", &[("count", &status.pending_events)]),
+            true,
+        )
+    } else {
+        (tr("live"), true)
+    })
+}
+
+/// Files committed so far by unfinished scans, or `None` when nothing is pending.
+fn pending_scan_files(state: &Arc<AppState>) -> Option<u64> {
+    let pending = state.metadata_db.get_pending_scans().ok()?;
+    (!pending.is_empty()).then(|| pending.iter().map(|c| c.files_committed).sum())
+}
+
 fn parse_inline_query_filters(
     query_str: &str,
     min_size: &mut Option<u64>,
@@ -1495,6 +3686,31 @@ mod tests {
         assert_eq!(fi.extension.as_deref(), Some("txt"));
     }
 
+    #[test]
+    fn test_actions_take_picked_results() {
+        let item = |path: &str| {
+            FileItem::from(
+                SearchResult::builder()
+                    .file_path(path.to_string())
+                    .score(1.0)
+                    .matched_terms(vec![])
+                    .snippets(Vec::new())
+                    .build(),
+            )
+        };
+        let mut app = App {
+            results: vec![item("/a.txt"), item("/b.txt"), item("/c.txt")],
+            ..App::default()
+        };
+        assert_eq!(app.picked_targets(1), ["/b.txt"]);
+        assert!(app.picked_targets(7).is_empty());
+
+        app.marked = ["/c.txt", "/a.txt"].map(String::from).into();
+        assert_eq!(app.picked_targets(2), ["/a.txt", "/c.txt"]);
+        // A result outside the picked ones goes on its own
+        assert_eq!(app.picked_targets(1), ["/b.txt"]);
+    }
+
     #[test]
     fn test_parse_size_filter() {
         let (min, max) = App::parse_size_filter("> 1MB");
@@ -1510,6 +3726,27 @@ mod tests {
         assert_eq!(max, Some(10 * 1024 - 1));
     }
 
+    #[test]
+    fn test_watcher_summary() {
+        let mut status = crate::models::WatcherStatus::default();
+        assert_eq!(watcher_summary(&status), None);
+
+        status.watched_roots = vec!["/docs".to_string()];
+        assert_eq!(watcher_summary(&status), Some(("Live".to_string(), true)));
+
+        status.pending_events = 3;
+        assert_eq!(
+            watcher_summary(&status),
+            Some(("Live · 3 pending".to_string(), true))
+        );
+
+        status.unwatched_roots = vec!["//nas/share".to_string()];
+        assert_eq!(
+            watcher_summary(&status),
+            Some(("Live updates: 1 of 2 folders".to_string(), false))
+        );
+    }
+
     #[test]
     fn test_parse_size_val() {
         assert_eq!(parse_size_val("500"), Some(500));
@@ -1544,19 +3781,23 @@ mod tests {
 #[derive(Debug, Clone)]
 struct SystemSubscriptionData {
     hotkey_str: String,
+    quick_hotkey_str: String,
     minimize_to_tray: bool,
 }
 
 impl std::hash::Hash for SystemSubscriptionData {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.hotkey_str.hash(state);
+        self.quick_hotkey_str.hash(state);
         self.minimize_to_tray.hash(state);
     }
 }
 
 impl PartialEq for SystemSubscriptionData {
     fn eq(&self, other: &Self) -> bool {
-        self.hotkey_str == other.hotkey_str && self.minimize_to_tray == other.minimize_to_tray
+        self.hotkey_str == other.hotkey_str
+            && self.quick_hotkey_str == other.quick_hotkey_str
+            && self.minimize_to_tray == other.minimize_to_tray
     }
 }
 
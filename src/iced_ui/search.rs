@@ -1,8 +1,15 @@
-use super::{App, DateFilter, Message, SearchMode, SortBy, Tab, theme};
-use crate::models::{DocumentElementHighlight, ElementType};
+use super::a11y::{FocusRegion, with_tooltip};
+use super::{
+    App, DateFilter, Message, ResultsLayout, SearchMode, SortBy, Tab, theme, watcher_summary,
+};
+use crate::i18n::{tr, tr_args};
+use crate::indexer::category::FileCategory;
+use crate::models::{
+    DocumentElementHighlight, ElementType, OpenWithApp, PreviewKind, WatcherStatus,
+};
 use iced::widget::{
     Space, TextInput, button, checkbox, column, container, mouse_area, rich_text, row, scrollable,
-    span, text,
+    span, text, tooltip,
 };
 use iced::{Alignment, Element, Font, Length, Padding, font};
 
@@ -10,6 +17,7 @@ use iced::{Alignment, Element, Font, Length, Padding, font};
 use crate::iced_ui::icons::{load_icon, load_icon_size};
 
 use std::ops::Range;
+use strum::IntoEnumIterator;
 
 #[allow(dead_code)]
 struct TermHighlighter {
@@ -75,7 +83,7 @@ impl TermHighlighter {
 }
 
 fn sidebar_section<'a>(
-    title: &'a str,
+    title: String,
     content: impl Into<Element<'a, Message>>,
 ) -> Element<'a, Message> {
     column![
@@ -95,11 +103,17 @@ fn sidebar_section<'a>(
     .into()
 }
 
-fn render_element(element: &DocumentElementHighlight) -> Element<'_, Message> {
+/// Renders a preview element. Search matches get a backdrop, a stronger one
+/// for `current`, the span match navigation is on.
+fn render_element(
+    element: &DocumentElementHighlight,
+    current: Option<usize>,
+) -> Element<'_, Message> {
     let spans = element
         .spans
         .iter()
-        .map(|(text_part, color_opt)| {
+        .enumerate()
+        .map(|(i, (text_part, color_opt))| {
             let mut s: iced::widget::text::Span<'_, Message> = span(text_part).size(13);
             if element.element_type == ElementType::CodeBlock {
                 s = s.font(Font::MONOSPACE);
@@ -107,6 +121,13 @@ fn render_element(element: &DocumentElementHighlight) -> Element<'_, Message> {
             if let Some([r, g, b, a]) = color_opt {
                 s = s.color(iced::Color::from_rgba(*r, *g, *b, *a));
             }
+            if element.matches.contains(&i) {
+                let alpha = if current == Some(i) { 0.45 } else { 0.18 };
+                s = s.background(iced::Color {
+                    a: alpha,
+                    ..theme::HIT_AMBER
+                });
+            }
             s
         })
         .collect::<Vec<iced::widget::text::Span<'_, Message>>>();
@@ -159,21 +180,21 @@ pub fn search_view(app: &App) -> Element<'_, Message> {
             container(
                 row![
                     load_icon_size("warning", 16.0),
-                    text(" Metadata database was corrupted and has been reset. Full re-index recommended.")
+                    text(tr("db-corrupted"))
                         .size(13)
                         .style(theme::danger_text_style()),
                     Space::new().width(Length::Fill),
-                    button(text("Dismiss").size(12))
+                    button(text(tr("banner-dismiss")).size(12))
                         .on_press(Message::DismissError)
                         .padding(Padding::from([4, 8]))
                         .style(theme::ghost_button())
                 ]
                 .align_y(Alignment::Center)
-                .spacing(8)
+                .spacing(8),
             )
             .padding(10)
             .style(theme::warning_banner)
-            .width(Length::Fill)
+            .width(Length::Fill),
         );
     }
 
@@ -182,11 +203,11 @@ pub fn search_view(app: &App) -> Element<'_, Message> {
             container(
                 row![
                     load_icon_size("warning", 16.0),
-                    text(format!("Error: {err}"))
+                    text(tr_args("search-error", &[("error", err)]))
                         .size(13)
                         .style(theme::danger_text_style()),
                     Space::new().width(Length::Fill),
-                    button(text("Dismiss").size(12))
+                    button(text(tr("banner-dismiss")).size(12))
                         .on_press(Message::DismissError)
                         .padding(Padding::from([4, 8]))
                         .style(theme::ghost_button())
@@ -200,6 +221,16 @@ pub fn search_view(app: &App) -> Element<'_, Message> {
         );
     }
 
+    if let Some(hint) = query_error_hint(app) {
+        col = col.push(hint);
+    }
+    if let Some(suggestions) = macro_suggestions_bar(app) {
+        col = col.push(suggestions);
+    }
+    if let Some(history) = history_dropdown(app) {
+        col = col.push(history);
+    }
+
     col.push(main_layout(app))
         .push(status_bar(app))
         .width(Length::Fill)
@@ -207,6 +238,81 @@ pub fn search_view(app: &App) -> Element<'_, Message> {
         .into()
 }
 
+/// The window as the quick search palette: an input and the best few
+/// results, nothing else. Enter opens the highlighted result, or the first.
+pub fn quick_search_view(app: &App) -> Element<'_, Message> {
+    const SHOWN: usize = 8;
+
+    let target = app
+        .selected_index
+        .and_then(|i| app.results.get(i))
+        .or_else(|| app.results.first())
+        .map(|res| res.path.clone());
+    let input = row![
+        load_icon_size("search", 16.0),
+        TextInput::new(&tr("quick-placeholder"), &app.search_query)
+            .id(crate::iced_ui::get_search_input_id())
+            .on_input(Message::SearchQueryChanged)
+            .on_submit(target.map_or(Message::SearchSubmitted, Message::OpenFile))
+            .padding(Padding::from([10, 8]))
+            .size(16)
+            .style(theme::search_input())
+            .width(Length::Fill),
+    ]
+    .spacing(6)
+    .align_y(Alignment::Center);
+
+    let mut list = column![].spacing(2);
+    for (i, res) in app.results.iter().take(SHOWN).enumerate() {
+        let folder = std::path::Path::new(&res.path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entry = container(
+            column![
+                text(res.title.as_str()).size(14),
+                text(folder).size(11).style(theme::dim_text_style()),
+            ]
+            .spacing(2),
+        )
+        .padding(Padding::from([6, 10]))
+        .width(Length::Fill)
+        .style(if app.selected_index == Some(i) {
+            theme::result_card_selected
+        } else {
+            theme::result_card_normal
+        });
+        list = list.push(
+            mouse_area(entry)
+                .on_press(Message::OpenFile(res.path.clone()))
+                .on_enter(Message::ItemHovered(Some(i))),
+        );
+    }
+
+    let footer = if app.results.len() > SHOWN {
+        text(tr_args(
+            "quick-more",
+            &[("count", &(app.results.len() - SHOWN))],
+        ))
+    } else {
+        text(tr("quick-footer"))
+    };
+
+    container(
+        column![
+            input,
+            scrollable(list).height(Length::Fill),
+            footer.size(11).style(theme::dim_text_style()),
+        ]
+        .spacing(10),
+    )
+    .padding(Padding::new(14.0))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(theme::main_content_container)
+    .into()
+}
+
 #[allow(clippy::too_many_lines)]
 fn top_navigation(app: &App) -> Element<'_, Message> {
     let logo = row![
@@ -218,7 +324,7 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
                 weight: font::Weight::Bold,
                 ..Font::default()
             }),
-            text("Instant Local Search")
+            text(tr("app-tagline"))
                 .size(10)
                 .style(theme::dim_text_style()),
         ]
@@ -232,10 +338,11 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
             load_icon_size("search", 16.0),
             Space::new().width(Length::Fixed(4.0)),
             TextInput::new(
-                match app.search_mode {
-                    SearchMode::FullText => "Search everything (text, documents, code)...",
-                    SearchMode::Filename => "Search filenames...",
-                },
+                &tr(match app.search_mode {
+                    SearchMode::All => "search-placeholder-all",
+                    SearchMode::FullText => "search-placeholder-text",
+                    SearchMode::Filename => "search-placeholder-name",
+                }),
                 &app.search_query,
             )
             .id(crate::iced_ui::get_search_input_id())
@@ -253,43 +360,63 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
             if app.search_query.is_empty() {
                 Element::from(Space::new().width(0).height(0))
             } else {
-                Element::from(
+                with_tooltip(
                     button(load_icon_size("x", 14.0))
                         .on_press(Message::SearchQueryChanged(String::new()))
                         .style(theme::ghost_button())
                         .padding(Padding::new(6.0)),
+                    tr("search-clear"),
+                )
+            },
+            if app.settings.search_history_enabled {
+                with_tooltip(
+                    button(load_icon_size("clock", 14.0))
+                        .on_press(Message::ToggleSearchHistory)
+                        .style(move |t, s| theme::nav_button(app.history_open)(t, s))
+                        .padding(Padding::new(6.0)),
+                    tr("history-button"),
                 )
+            } else {
+                Element::from(Space::new().width(0).height(0))
             },
             // Case Match Toggle Button ("Aa")
-            button(text("Aa").size(12).font(Font {
-                weight: font::Weight::Bold,
-                ..Font::default()
-            }))
-            .on_press(Message::ToggleCaseSensitive(!app.settings.case_sensitive))
-            .style(move |t, s| theme::nav_button(app.settings.case_sensitive)(t, s))
-            .padding(Padding::from([5, 8])),
+            with_tooltip(
+                button(text("Aa").size(12).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }))
+                .on_press(Message::ToggleCaseSensitive(!app.settings.case_sensitive))
+                .style(move |t, s| theme::nav_button(app.settings.case_sensitive)(t, s))
+                .padding(Padding::from([5, 8])),
+                tr("match-case-hint"),
+            ),
             // Whole Word Toggle Button ("W")
-            button(text("W").size(12).font(Font {
-                weight: font::Weight::Bold,
-                ..Font::default()
-            }))
-            .on_press(Message::ToggleWholeWord(!app.settings.whole_word))
-            .style(move |t, s| theme::nav_button(app.settings.whole_word)(t, s))
-            .padding(Padding::from([5, 8])),
+            with_tooltip(
+                button(text("W").size(12).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }))
+                .on_press(Message::ToggleWholeWord(!app.settings.whole_word))
+                .style(move |t, s| theme::nav_button(app.settings.whole_word)(t, s))
+                .padding(Padding::from([5, 8])),
+                tr("match-word-hint"),
+            ),
             // Search Mode Toggle Button
             button(
                 row![
                     load_icon_size(
                         match app.search_mode {
+                            SearchMode::All => "search",
                             SearchMode::FullText => "file-text",
                             SearchMode::Filename => "file",
                         },
                         12.0
                     ),
-                    text(match app.search_mode {
-                        SearchMode::FullText => "Text",
-                        SearchMode::Filename => "File",
-                    })
+                    text(tr(match app.search_mode {
+                        SearchMode::All => "mode-all",
+                        SearchMode::FullText => "mode-text",
+                        SearchMode::Filename => "mode-file",
+                    }))
                     .size(11)
                     .font(Font {
                         weight: font::Weight::Bold,
@@ -300,25 +427,30 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
                 .align_y(Alignment::Center)
             )
             .on_press(Message::SearchModeChanged(match app.search_mode {
+                SearchMode::All => SearchMode::FullText,
                 SearchMode::FullText => SearchMode::Filename,
-                SearchMode::Filename => SearchMode::FullText,
+                SearchMode::Filename => SearchMode::All,
             }))
             .style(move |t, s| {
-                let active = matches!(app.search_mode, SearchMode::Filename);
+                let active = app.search_mode != SearchMode::All;
                 theme::nav_button(active)(t, s)
             })
             .padding(Padding::from([5, 10])),
             if app.is_searching {
                 Element::from(
-                    container(text("Searching...").size(12).style(theme::dim_text_style()))
-                        .padding(Padding::from([4, 12])),
+                    container(
+                        text(tr("search-searching"))
+                            .size(12)
+                            .style(theme::dim_text_style()),
+                    )
+                    .padding(Padding::from([4, 12])),
                 )
             } else {
                 Element::from(
                     button(
                         row![
                             load_icon_size("arrow-right", 14.0),
-                            text("Search").size(12).font(Font {
+                            text(tr("search-button")).size(12).font(Font {
                                 weight: font::Weight::Bold,
                                 ..Font::default()
                             })
@@ -340,20 +472,38 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
     .width(Length::FillPortion(3))
     .max_width(850.0);
 
+    let search_bar = container(search_bar)
+        .padding(2)
+        .style(theme::focus_ring_container(
+            app.focus_region == FocusRegion::Search,
+        ))
+        .width(Length::FillPortion(3))
+        .max_width(854.0);
+
     let menu_items = row![
         // Direct Client Theme Switcher (Dark 🌙 / Light ☀️)
-        button(load_icon_size(
-            if app.is_dark { "sun" } else { "moon" },
-            18.0
-        ))
-        .on_press(Message::ToggleTheme)
-        .style(theme::ghost_button())
-        .padding(10.0),
-        // Settings Button
-        button(load_icon_size("settings", 18.0))
-            .on_press(Message::TabChanged(Tab::Settings))
+        with_tooltip(
+            button(load_icon_size(
+                if app.is_dark { "sun" } else { "moon" },
+                18.0
+            ))
+            .on_press(Message::ToggleTheme)
             .style(theme::ghost_button())
             .padding(10.0),
+            tr(if app.is_dark {
+                "theme-switch-light"
+            } else {
+                "theme-switch-dark"
+            }),
+        ),
+        // Settings Button
+        with_tooltip(
+            button(load_icon_size("settings", 18.0))
+                .on_press(Message::TabChanged(Tab::Settings))
+                .style(theme::ghost_button())
+                .padding(10.0),
+            tr("open-settings"),
+        ),
     ]
     .spacing(6);
 
@@ -378,6 +528,206 @@ fn top_navigation(app: &App) -> Element<'_, Message> {
     .into()
 }
 
+/// What is wrong with the query being typed, and a one-click fix when
+/// there is one. Filename searches don't use the full-text syntax.
+fn query_error_hint(app: &App) -> Option<Element<'_, Message>> {
+    if app.search_mode == SearchMode::Filename {
+        return None;
+    }
+    // Macros are expanded before searching, so their expansions are what gets checked
+    let query = app.state.as_ref().map_or_else(
+        || app.search_query.clone(),
+        |state| crate::commands::expand_query_macros(&app.search_query, state),
+    );
+    let err = crate::indexer::query_parser::ParsedQuery::validate(&query).err()?;
+
+    let mut hint_row = row![
+        load_icon_size("warning", 14.0),
+        text(tr_args(
+            "query-error",
+            &[("message", &err.message), ("column", &(err.position + 1))]
+        ))
+        .size(12)
+        .style(theme::danger_text_style()),
+    ]
+    .spacing(8)
+    .padding(Padding {
+        top: 6.0,
+        bottom: 6.0,
+        left: 16.0,
+        right: 16.0,
+    })
+    .align_y(Alignment::Center);
+
+    if let Some(suggestion) = err.suggestion {
+        hint_row = hint_row.push(
+            button(
+                row![
+                    text(tr("query-try"))
+                        .size(11)
+                        .style(theme::dim_text_style()),
+                    text(suggestion.clone()).size(12).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::SearchQueryChanged(suggestion))
+            .padding(Padding::from([3, 8]))
+            .style(theme::ghost_button()),
+        );
+    }
+
+    Some(
+        container(hint_row)
+            .width(Length::Fill)
+            .style(theme::header_container)
+            .into(),
+    )
+}
+
+fn macro_suggestions_bar(app: &App) -> Option<Element<'_, Message>> {
+    let suggestions = crate::indexer::query_parser::macro_suggestions(
+        &app.search_query,
+        &app.settings.query_macros,
+    );
+    if suggestions.is_empty() {
+        return None;
+    }
+
+    let mut chips_row = row![
+        load_icon_size("sparkles", 14.0),
+        text(tr("macros-label"))
+            .size(12)
+            .style(theme::dim_text_style())
+    ]
+    .spacing(8)
+    .padding(Padding {
+        top: 6.0,
+        bottom: 6.0,
+        left: 16.0,
+        right: 16.0,
+    })
+    .align_y(Alignment::Center);
+
+    for m in suggestions {
+        chips_row = chips_row.push(
+            button(
+                row![
+                    text(format!("@{}", m.name)).size(12).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                    text(&m.expansion).size(11).style(theme::dim_text_style()),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::ApplyQueryMacro(m.name.clone()))
+            .padding(Padding::from([3, 8]))
+            .style(theme::ghost_button()),
+        );
+    }
+
+    Some(
+        container(chips_row)
+            .width(Length::Fill)
+            .style(theme::header_container)
+            .into(),
+    )
+}
+
+/// Searches shown in the history dropdown
+const HISTORY_DROPDOWN_ENTRIES: usize = 8;
+
+/// Past searches containing the typed text, most used first, shown under the
+/// search box while the history button is on.
+fn history_dropdown(app: &App) -> Option<Element<'_, Message>> {
+    if !app.history_open {
+        return None;
+    }
+    let typed = app.search_query.trim().to_lowercase();
+    let entries: Vec<_> = app
+        .search_history
+        .iter()
+        .filter(|item| item.query.to_lowercase().contains(&typed))
+        .take(HISTORY_DROPDOWN_ENTRIES)
+        .collect();
+
+    let mut list = column![].spacing(2);
+    if entries.is_empty() {
+        list = list.push(
+            text(tr(if app.search_history.is_empty() {
+                "history-dropdown-empty"
+            } else {
+                "history-no-match"
+            }))
+            .size(12)
+            .style(theme::dim_text_style()),
+        );
+    }
+    for item in entries {
+        list = list.push(history_entry_row(item));
+    }
+    list = list.push(
+        button(text(tr("history-manage")).size(11))
+            .on_press(Message::TabChanged(Tab::Settings))
+            .style(theme::ghost_button())
+            .padding(Padding::from([3, 8])),
+    );
+
+    Some(
+        container(list)
+            .padding(Padding::from([6, 16]))
+            .width(Length::Fill)
+            .style(theme::header_container)
+            .into(),
+    )
+}
+
+/// A past search: runs it again on click, with pin and delete buttons.
+pub fn history_entry_row(item: &crate::models::SearchHistoryItem) -> Element<'_, Message> {
+    let uses = if item.frequency == 1 {
+        tr("history-uses-one")
+    } else {
+        tr_args("history-uses", &[("count", &item.frequency)])
+    };
+    row![
+        button(
+            row![
+                load_icon_size(if item.pinned { "star" } else { "clock" }, 12.0),
+                text(&item.query).size(12),
+                text(uses).size(10).style(theme::dim_text_style()),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        )
+        .on_press(Message::RunHistoryQuery(item.query.clone()))
+        .style(theme::ghost_button())
+        .padding(Padding::from([3, 8]))
+        .width(Length::Fill),
+        with_tooltip(
+            button(text(tr(if item.pinned { "unpin" } else { "pin" })).size(11))
+                .on_press(Message::PinHistoryEntry(item.query.clone(), !item.pinned))
+                .style(theme::ghost_button())
+                .padding(Padding::from([3, 8])),
+            tr("history-pin-hint"),
+        ),
+        with_tooltip(
+            button(load_icon_size("x", 12.0))
+                .on_press(Message::DeleteHistoryEntry(item.query.clone()))
+                .style(theme::ghost_button())
+                .padding(Padding::from([3, 6])),
+            tr("history-delete"),
+        ),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center)
+    .into()
+}
+
 fn main_layout(app: &App) -> Element<'_, Message> {
     let sidebar = if app.sidebar_collapsed {
         collapsed_sidebar(app)
@@ -385,15 +735,27 @@ fn main_layout(app: &App) -> Element<'_, Message> {
         left_sidebar(app)
     };
 
+    let focus = app.focus_region;
+
     row![
-        sidebar,
+        container(sidebar)
+            .style(theme::focus_ring_container(focus == FocusRegion::Filters))
+            .height(Length::Fill),
         column![
             filter_chips(app),
             row![
-                results_panel(app),
-                container(right_panel(app))
-                    .style(theme::sidebar_container)
-                    .width(Length::FillPortion(3)),
+                container(results_panel(app))
+                    .style(theme::focus_ring_container(focus == FocusRegion::Results))
+                    .width(Length::FillPortion(2))
+                    .height(Length::Fill),
+                container(
+                    container(right_panel(app))
+                        .style(theme::sidebar_container)
+                        .width(Length::Fill)
+                )
+                .style(theme::focus_ring_container(focus == FocusRegion::Preview))
+                .width(Length::FillPortion(3))
+                .height(Length::Fill),
             ]
             .height(Length::Fill)
         ]
@@ -411,7 +773,7 @@ fn filter_chips(app: &App) -> Element<'_, Message> {
 
     let mut chips_row = row![
         load_icon_size("filter", 14.0),
-        text("Active Filters:")
+        text(tr("filters-active"))
             .size(12)
             .style(theme::dim_text_style())
     ]
@@ -452,12 +814,13 @@ fn filter_chips(app: &App) -> Element<'_, Message> {
 
 fn collapsed_sidebar(_app: &App) -> Element<'_, Message> {
     container(
-        column![
+        column![with_tooltip(
             button(load_icon_size("filter", 18.0))
                 .on_press(Message::ToggleSidebar)
                 .style(theme::ghost_button())
                 .padding(Padding::new(12.0)),
-        ]
+            tr("filters-show"),
+        ),]
         .spacing(16)
         .padding(Padding::new(4.0))
         .align_x(Alignment::Center),
@@ -471,15 +834,18 @@ fn collapsed_sidebar(_app: &App) -> Element<'_, Message> {
 fn left_sidebar(app: &App) -> Element<'_, Message> {
     let filter_header = row![
         load_icon_size("filter", 16.0),
-        text("Filter Options").size(15).font(Font {
+        text(tr("filters-title")).size(15).font(Font {
             weight: font::Weight::Bold,
             ..Font::default()
         }),
         Space::new().width(Length::Fill),
-        button(load_icon_size("chevron-left", 16.0))
-            .on_press(Message::ToggleSidebar)
-            .style(theme::ghost_button())
-            .padding(Padding::new(6.0))
+        with_tooltip(
+            button(load_icon_size("chevron-left", 16.0))
+                .on_press(Message::ToggleSidebar)
+                .style(theme::ghost_button())
+                .padding(Padding::new(6.0)),
+            tr("filters-hide"),
+        )
     ]
     .align_y(Alignment::Center)
     .spacing(8);
@@ -496,7 +862,7 @@ fn left_sidebar(app: &App) -> Element<'_, Message> {
             button(
                 row![
                     load_icon_size("x", 14.0),
-                    text("Reset All Filters").size(12)
+                    text(tr("filters-reset")).size(12)
                 ]
                 .spacing(6)
                 .align_y(Alignment::Center)
@@ -527,7 +893,7 @@ fn left_sidebar(app: &App) -> Element<'_, Message> {
 
 fn extension_filter_section(app: &App) -> Element<'_, Message> {
     sidebar_section(
-        "File Extension",
+        tr("filter-extension"),
         column![
             row![
                 extension_checkbox("pdf", app),
@@ -557,17 +923,18 @@ fn extension_filter_section(app: &App) -> Element<'_, Message> {
 
 fn size_filter_section(app: &App) -> Element<'_, Message> {
     sidebar_section(
-        "Size Range",
+        tr("filter-size"),
         column![
             row![
-                TextInput::new("Min", &app.min_size)
+                TextInput::new(&tr("filter-size-min"), &app.min_size)
+                    .id(super::a11y::get_filters_input_id())
                     .on_input(Message::MinSizeChanged)
                     .padding(Padding::new(7.0))
                     .size(12)
                     .style(theme::search_input())
                     .width(Length::Fill),
                 text("-").size(14).style(theme::dim_text_style()),
-                TextInput::new("Max", &app.max_size)
+                TextInput::new(&tr("filter-size-max"), &app.max_size)
                     .on_input(Message::MaxSizeChanged)
                     .padding(Padding::new(7.0))
                     .size(12)
@@ -589,12 +956,12 @@ fn size_filter_section(app: &App) -> Element<'_, Message> {
 
 fn date_filter_section(app: &App) -> Element<'_, Message> {
     sidebar_section(
-        "Last Modified",
+        tr("filter-date"),
         column![
-            date_filter_button("Anytime", DateFilter::Anytime, app),
-            date_filter_button("Today", DateFilter::Today, app),
-            date_filter_button("Past Week", DateFilter::Last7Days, app),
-            date_filter_button("Past Month", DateFilter::Last30Days, app),
+            date_filter_button(tr("date-anytime"), DateFilter::Anytime, app),
+            date_filter_button(tr("date-today"), DateFilter::Today, app),
+            date_filter_button(tr("date-week"), DateFilter::Last7Days, app),
+            date_filter_button(tr("date-month"), DateFilter::Last30Days, app),
         ]
         .spacing(4),
     )
@@ -602,7 +969,7 @@ fn date_filter_section(app: &App) -> Element<'_, Message> {
 
 fn match_options_section(app: &App) -> iced::widget::Column<'_, Message> {
     column![
-        text("Search Scope")
+        text(tr("filter-scope"))
             .size(12)
             .font(Font {
                 weight: font::Weight::Bold,
@@ -611,8 +978,9 @@ fn match_options_section(app: &App) -> iced::widget::Column<'_, Message> {
             .style(theme::muted_text_style()),
         container(
             row![
-                search_mode_button("Full Text", SearchMode::FullText, app),
-                search_mode_button("Filename", SearchMode::Filename, app),
+                search_mode_button(tr("scope-all"), SearchMode::All, app),
+                search_mode_button(tr("scope-text"), SearchMode::FullText, app),
+                search_mode_button(tr("scope-name"), SearchMode::Filename, app),
             ]
             .spacing(4)
         )
@@ -620,7 +988,7 @@ fn match_options_section(app: &App) -> iced::widget::Column<'_, Message> {
         .style(theme::sidebar_panel_container)
         .width(Length::Fill),
         Space::new().height(Length::Fixed(6.0)),
-        text("Match Flags")
+        text(tr("filter-flags"))
             .size(12)
             .font(Font {
                 weight: font::Weight::Bold,
@@ -630,15 +998,25 @@ fn match_options_section(app: &App) -> iced::widget::Column<'_, Message> {
         container(
             column![
                 checkbox(app.settings.case_sensitive)
-                    .label("Match Case")
+                    .label(tr("flag-case"))
                     .on_toggle(Message::ToggleCaseSensitive)
                     .size(16)
                     .text_size(12),
                 checkbox(app.settings.whole_word)
-                    .label("Whole Word")
+                    .label(tr("flag-word"))
                     .on_toggle(Message::ToggleWholeWord)
                     .size(16)
                     .text_size(12),
+                checkbox(app.settings.confidence_cutoff)
+                    .label(tr("flag-best"))
+                    .on_toggle(Message::ToggleConfidenceCutoff)
+                    .size(16)
+                    .text_size(12),
+                checkbox(app.settings.match_full_path)
+                    .label(tr("flag-full-path"))
+                    .on_toggle(Message::ToggleMatchFullPath)
+                    .size(16)
+                    .text_size(12),
             ]
             .spacing(8)
         )
@@ -649,7 +1027,7 @@ fn match_options_section(app: &App) -> iced::widget::Column<'_, Message> {
     .spacing(6)
 }
 
-fn search_mode_button<'a>(label: &'a str, mode: SearchMode, app: &App) -> Element<'a, Message> {
+fn search_mode_button(label: String, mode: SearchMode, app: &App) -> Element<'_, Message> {
     let is_active = app.search_mode == mode;
     button(text(label).size(11).font(Font {
         weight: font::Weight::Bold,
@@ -676,14 +1054,75 @@ fn results_panel(app: &App) -> Element<'_, Message> {
         return no_results_view(app);
     }
 
-    let results = scrollable(column(
-        app.results
-            .iter()
-            .enumerate()
-            .map(|(i, res)| result_item_view(app.selected_index, app.hovered_item_index, i, res))
-            .collect::<Vec<Element<Message>>>(),
-    ))
-    .height(Length::Fill);
+    // A result, followed by its action menu when that is open
+    let item = |i: usize| {
+        let row = match app.results_layout {
+            ResultsLayout::Cards => result_item_view(
+                app.selected_index,
+                app.hovered_item_index,
+                app.marked.contains(&app.results[i].path),
+                i,
+                &app.results[i],
+            ),
+            ResultsLayout::Table => table_row_view(
+                app.selected_index == Some(i) || app.marked.contains(&app.results[i].path),
+                i,
+                &app.results[i],
+            ),
+        };
+        let menu = (app.context_menu == Some(i)).then(|| {
+            let pinned = app.pinned_files.contains(&app.results[i].path);
+            context_menu_view(&app.results[i], pinned, app.picked_targets(i))
+        });
+        std::iter::once(row).chain(menu)
+    };
+    let shown = &app.results[..app.results.len().min(app.results_shown)];
+    let mut items = if app.group_by_folder {
+        let mut items = Vec::new();
+        for group in crate::commands::group_by_folder(shown, |r| r.path.as_str()) {
+            let collapsed = app.collapsed_folders.contains(&group.folder);
+            items.push(folder_header(&group.folder, group.indices.len(), collapsed));
+            if !collapsed {
+                items.extend(group.indices.into_iter().flat_map(item));
+            }
+        }
+        items
+    } else {
+        (0..shown.len())
+            .flat_map(item)
+            .collect::<Vec<Element<Message>>>()
+    };
+
+    if !app.hidden_results.is_empty() {
+        items.push(
+            container(
+                button(
+                    text(tr_args(
+                        "results-show-all",
+                        &[
+                            ("total", &(app.results.len() + app.hidden_results.len())),
+                            ("hidden", &app.hidden_results.len()),
+                        ],
+                    ))
+                    .size(12),
+                )
+                .on_press(Message::ShowAllResults)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            )
+            .padding(Padding::new(12.0))
+            .center_x(Length::Fill)
+            .into(),
+        );
+    }
+
+    let results = scrollable(column(items))
+        .on_scroll(Message::ResultsScrolled)
+        .height(Length::Fill);
+    let results: Element<'_, Message> = match app.results_layout {
+        ResultsLayout::Cards => results.into(),
+        ResultsLayout::Table => column![table_header(app), results].into(),
+    };
 
     container(results)
         .width(Length::FillPortion(2))
@@ -691,50 +1130,460 @@ fn results_panel(app: &App) -> Element<'_, Message> {
         .into()
 }
 
-#[allow(clippy::too_many_lines)]
-fn welcome_hero_view(app: &App) -> Element<'_, Message> {
-    let hero = column![
-        Space::new().height(Length::Fixed(16.0)),
-        row![
-            container(load_icon_size("sparkles", 32.0))
-                .padding(14)
-                .style(theme::accent_badge_container),
-            column![
-                text("FindAll Instant Search").size(22).font(Font {
-                    weight: font::Weight::Bold,
-                    ..Font::default()
-                }),
-                text("Ultrafast local text, document, and filename search engine")
-                    .size(13)
-                    .style(theme::dim_text_style()),
-            ]
-            .spacing(4),
-        ]
-        .spacing(16)
-        .align_y(Alignment::Center),
-        Space::new().height(Length::Fixed(24.0)),
-        // Client Feature & Shortcut Cards
-        row![
-            // Shortcuts Card
-            container(
-                column![
-                    row![
-                        load_icon_size("keyboard", 16.0),
-                        text("Keyboard Shortcuts").size(14).font(Font {
-                            weight: font::Weight::Bold,
-                            ..Font::default()
-                        }),
-                    ]
-                    .spacing(8)
-                    .align_y(Alignment::Center),
-                    Space::new().height(Length::Fixed(8.0)),
-                    shortcut_row("Alt + Space", "Global Search Window"),
-                    shortcut_row("Ctrl + F", "Focus Search Input"),
-                    shortcut_row("↑ / ↓", "Navigate Results"),
-                    shortcut_row("Enter", "Open Selected File"),
-                    shortcut_row("Ctrl + Enter", "Open Containing Folder"),
-                    shortcut_row("Ctrl + C", "Copy File Path"),
-                ]
+/// Actions for one result, shown under it on right-click. `picked` is what
+/// copying the file or moving it to the trash takes along: the picked
+/// results it belongs to.
+fn context_menu_view(
+    res: &super::FileItem,
+    pinned: bool,
+    picked: Vec<String>,
+) -> Element<'_, Message> {
+    let entry = |icon: &str, label: &str, message: Message| {
+        button(
+            row![load_icon_size(icon, 13.0), text(label.to_string()).size(12)]
+                .spacing(8)
+                .align_y(Alignment::Center),
+        )
+        .on_press(message)
+        .style(theme::ghost_button())
+        .padding(Padding::from([5, 10]))
+        .width(Length::Fill)
+    };
+    let folder = std::path::Path::new(&res.path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string());
+
+    let mut menu = column![
+        entry(
+            "external-link",
+            &tr("menu-open"),
+            Message::OpenFile(res.path.clone())
+        ),
+        entry(
+            "file",
+            &tr("menu-open-with"),
+            Message::OpenWith(res.path.clone())
+        ),
+        entry(
+            "folder-open",
+            &tr("menu-open-folder"),
+            Message::OpenFolder(res.path.clone())
+        ),
+        entry(
+            "copy",
+            &tr("menu-copy-path"),
+            Message::CopyPath(res.path.clone())
+        ),
+        entry(
+            "copy",
+            &if picked.len() > 1 {
+                tr_args("menu-copy-picked", &[("count", &picked.len())])
+            } else if res.is_dir {
+                tr("menu-copy-folder")
+            } else {
+                tr("menu-copy-file")
+            },
+            Message::CopyFiles(picked.clone())
+        ),
+    ]
+    .spacing(2);
+    if !res.is_dir {
+        menu = menu.push(entry(
+            "copy",
+            &tr("menu-copy-text"),
+            Message::CopyContents(res.path.clone()),
+        ));
+    }
+    if let [left, right] = picked.as_slice() {
+        menu = menu.push(entry(
+            "file-text",
+            &tr("menu-compare"),
+            Message::CompareContents(left.clone(), right.clone()),
+        ));
+    }
+    if !res.snippets.is_empty() {
+        let matched = res
+            .snippets
+            .iter()
+            .map(|html| crate::system::ipc::plain_snippet(html))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        menu = menu.push(entry(
+            "copy",
+            &tr("menu-copy-matched"),
+            Message::CopyText(matched),
+        ));
+    }
+    menu = menu
+        .push(if pinned {
+            entry("star", &tr("unpin"), Message::UnpinFile(res.path.clone()))
+        } else {
+            entry("star", &tr("menu-pin"), Message::PinFile(res.path.clone()))
+        })
+        .push(entry(
+            "refresh",
+            &tr("menu-reindex"),
+            Message::ReindexPath(res.path.clone()),
+        ))
+        .push(entry(
+            "trash",
+            &tr("menu-remove"),
+            Message::RemoveFromIndex(res.path.clone()),
+        ))
+        .push(entry(
+            "trash",
+            &if picked.len() > 1 {
+                tr_args(
+                    "menu-trash-picked",
+                    &[("count", &picked.len()), ("bin", &trash_name())],
+                )
+            } else {
+                tr_args("menu-trash", &[("bin", &trash_name())])
+            },
+            Message::MoveToTrash(picked),
+        ));
+    menu = menu.push(entry(
+        "x",
+        &tr(if res.is_dir {
+            "menu-exclude-folder"
+        } else {
+            "menu-exclude-file"
+        }),
+        Message::ExcludeFromIndex(res.path.clone()),
+    ));
+    if let Some(folder) = folder {
+        menu = menu.push(entry(
+            "x",
+            &tr("menu-exclude-parent"),
+            Message::ExcludeFromIndex(folder),
+        ));
+    }
+    if let Some(ext) = res.extension.as_deref().filter(|_| !res.is_dir) {
+        menu = menu.push(entry(
+            "x",
+            &tr_args("menu-exclude-ext", &[("ext", &ext)]),
+            Message::ExcludeFromIndex(format!("*.{ext}")),
+        ));
+    }
+
+    container(
+        container(menu)
+            .padding(Padding::new(6.0))
+            .width(Length::Fixed(280.0))
+            .style(theme::padded_card_container),
+    )
+    .padding(Padding {
+        top: 0.0,
+        bottom: 6.0,
+        left: 24.0,
+        right: 10.0,
+    })
+    .into()
+}
+
+/// What the system calls its trash.
+fn trash_name() -> String {
+    tr(if cfg!(windows) {
+        "trash-bin-windows"
+    } else {
+        "trash-bin"
+    })
+}
+
+/// Paths of results listed in the trash confirmation before the rest are
+/// summed up
+const TRASH_LISTED: usize = 5;
+
+/// Asks before moving `paths` to the trash.
+pub fn trash_dialog(paths: &[String]) -> Element<'_, Message> {
+    let mut body = column![super::settings::section_header(
+        "trash",
+        tr_args("trash-title", &[("bin", &trash_name())])
+    )]
+    .spacing(6);
+    for path in paths.iter().take(TRASH_LISTED) {
+        body = body.push(text(path).size(12));
+    }
+    if paths.len() > TRASH_LISTED {
+        body = body.push(
+            text(tr_args(
+                "trash-more",
+                &[("count", &(paths.len() - TRASH_LISTED))],
+            ))
+            .size(12)
+            .style(theme::dim_text_style()),
+        );
+    }
+    body = body.push(
+        text(tr("trash-note"))
+            .size(13)
+            .style(theme::muted_text_style()),
+    );
+    body = body.push(
+        row![
+            Space::new().width(Length::Fill),
+            button(text(tr("trash-cancel")).size(13))
+                .on_press(Message::CancelTrash)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            button(text(tr("trash-confirm")).size(13))
+                .on_press(Message::ConfirmTrash)
+                .padding(Padding::from([6, 14]))
+                .style(theme::primary_button()),
+        ]
+        .spacing(8),
+    );
+
+    container(body)
+        .padding(20)
+        .width(Length::Fixed(440.0))
+        .style(theme::padded_card_container)
+        .into()
+}
+
+/// Lists the applications registered for `path` to pick one to open it with.
+pub fn open_with_dialog<'a>(path: &'a str, apps: &'a [OpenWithApp]) -> Element<'a, Message> {
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |n| n.to_string_lossy().to_string());
+    let mut body = column![super::settings::section_header(
+        "file",
+        tr_args("open-with-title", &[("name", &name)])
+    )]
+    .spacing(6);
+    for app in apps {
+        body = body.push(
+            button(text(&app.name).size(13))
+                .on_press(Message::LaunchWith(path.to_string(), app.clone()))
+                .width(Length::Fill)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+        );
+    }
+    body = body.push(
+        row![
+            Space::new().width(Length::Fill),
+            button(text(tr("open-with-cancel")).size(13))
+                .on_press(Message::CloseOpenWith)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            button(text(tr("open-with-other")).size(13))
+                .on_press(Message::OpenWithOther(path.to_string()))
+                .padding(Padding::from([6, 14]))
+                .style(theme::primary_button()),
+        ]
+        .spacing(8),
+    );
+
+    container(scrollable(body))
+        .padding(20)
+        .width(Length::Fixed(440.0))
+        .max_height(520.0)
+        .style(theme::padded_card_container)
+        .into()
+}
+
+/// Asks to bring back the drive or share an offline result is on, then
+/// check whether it is reachable again.
+pub fn reconnect_dialog<'a>(path: &'a str, volume: Option<&str>) -> Element<'a, Message> {
+    let note = volume.map_or_else(
+        || tr("reconnect-share"),
+        |volume| tr_args("reconnect-drive", &[("volume", &volume)]),
+    );
+    let body = column![
+        super::settings::section_header("warning", tr("reconnect-title")),
+        text(path).size(13),
+        text(note).size(13).style(theme::muted_text_style()),
+        row![
+            Space::new().width(Length::Fill),
+            button(text(tr("reconnect-cancel")).size(13))
+                .on_press(Message::CloseReconnect)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            button(text(tr("reconnect-check")).size(13))
+                .on_press(Message::CheckReconnect)
+                .padding(Padding::from([6, 14]))
+                .style(theme::primary_button()),
+        ]
+        .spacing(8),
+    ]
+    .spacing(6);
+
+    container(body)
+        .padding(20)
+        .width(Length::Fixed(440.0))
+        .style(theme::padded_card_container)
+        .into()
+}
+
+/// Widths of the table layout's columns, in the order of [`TABLE_COLUMNS`]
+const TABLE_WIDTHS: [Length; 6] = [
+    Length::FillPortion(4),
+    Length::FillPortion(5),
+    Length::Fixed(60.0),
+    Length::Fixed(80.0),
+    Length::Fixed(130.0),
+    Length::Fixed(60.0),
+];
+
+/// Catalog keys of the table layout's column headers
+const TABLE_COLUMNS: [(&str, SortBy); 6] = [
+    ("column-name", SortBy::Name),
+    ("column-folder", SortBy::Folder),
+    ("column-ext", SortBy::Extension),
+    ("column-size", SortBy::Size),
+    ("column-modified", SortBy::DateModified),
+    ("column-score", SortBy::Relevance),
+];
+
+/// Column headers of the table layout. Pressing one sorts by it, pressing it
+/// again reverses the order.
+fn table_header(app: &App) -> Element<'_, Message> {
+    let mut header = row![].spacing(8);
+    for ((key, sort), width) in TABLE_COLUMNS.into_iter().zip(TABLE_WIDTHS) {
+        let arrow = match (app.sort_by == sort, app.sort_reversed) {
+            (false, _) => "",
+            (true, false) => " ▼",
+            (true, true) => " ▲",
+        };
+        header = header.push(
+            button(text(format!("{}{arrow}", tr(key))).size(11).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }))
+            .on_press(Message::SortColumnPressed(sort))
+            .style(theme::ghost_button())
+            .padding(Padding::from([4, 2]))
+            .width(width),
+        );
+    }
+    container(header)
+        .padding(Padding::from([4, 18]))
+        .width(Length::Fill)
+        .style(theme::table_header_container)
+        .into()
+}
+
+fn table_row_view(is_selected: bool, i: usize, res: &super::FileItem) -> Element<'_, Message> {
+    let folder = std::path::Path::new(&res.path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let cells: [String; 6] = [
+        res.title.clone(),
+        folder,
+        res.extension.as_deref().unwrap_or_default().to_string(),
+        res.size.map(super::format_size).unwrap_or_default(),
+        res.modified.map(super::format_date).unwrap_or_default(),
+        format!("{:.2}", res.score),
+    ];
+    let mut cols = row![].spacing(8).align_y(Alignment::Center);
+    for (cell, width) in cells.into_iter().zip(TABLE_WIDTHS) {
+        cols = cols.push(
+            text(cell)
+                .size(12)
+                .width(width)
+                .wrapping(iced::widget::text::Wrapping::None),
+        );
+    }
+    mouse_area(
+        container(cols)
+            .padding(Padding::from([5, 20]))
+            .width(Length::Fill)
+            .style(theme::table_row_container(is_selected)),
+    )
+    .on_press(Message::ResultSelected(i))
+    .on_double_click(Message::OpenFile(res.path.clone()))
+    .on_right_press(Message::ShowContextMenu(i))
+    .into()
+}
+
+/// Header of a folder group; pressing it opens or closes the group.
+fn folder_header(folder: &str, count: usize, collapsed: bool) -> Element<'static, Message> {
+    let label = if folder.is_empty() {
+        tr("group-no-folder")
+    } else {
+        folder.to_string()
+    };
+    container(
+        button(
+            row![
+                load_icon_size(
+                    if collapsed {
+                        "chevron-right"
+                    } else {
+                        "chevron-down"
+                    },
+                    13.0
+                ),
+                load_icon_size("folder", 13.0),
+                text(label).size(12).width(Length::Fill),
+                container(text(count.to_string()).size(10))
+                    .padding(Padding::from([2, 6]))
+                    .style(theme::badge_container),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        )
+        .on_press(Message::ToggleFolderCollapsed(folder.to_string()))
+        .style(theme::ghost_button())
+        .padding(Padding::from([6, 8]))
+        .width(Length::Fill),
+    )
+    .padding(Padding {
+        top: 6.0,
+        bottom: 0.0,
+        left: 10.0,
+        right: 10.0,
+    })
+    .into()
+}
+
+#[allow(clippy::too_many_lines)]
+fn welcome_hero_view(app: &App) -> Element<'_, Message> {
+    let hero = column![
+        Space::new().height(Length::Fixed(16.0)),
+        row![
+            container(load_icon_size("sparkles", 32.0))
+                .padding(14)
+                .style(theme::accent_badge_container),
+            column![
+                text(tr("welcome-title")).size(22).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }),
+                text(tr("welcome-subtitle"))
+                    .size(13)
+                    .style(theme::dim_text_style()),
+            ]
+            .spacing(4),
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center),
+        Space::new().height(Length::Fixed(24.0)),
+        home_files(app),
+        // Client Feature & Shortcut Cards
+        row![
+            // Shortcuts Card
+            container(
+                column![
+                    row![
+                        load_icon_size("keyboard", 16.0),
+                        text(tr("welcome-shortcuts")).size(14).font(Font {
+                            weight: font::Weight::Bold,
+                            ..Font::default()
+                        }),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                    Space::new().height(Length::Fixed(8.0)),
+                    shortcut_row("Alt + Space", tr("shortcut-global")),
+                    shortcut_row("Ctrl + F", tr("shortcut-focus")),
+                    shortcut_row("↑ / ↓", tr("shortcut-navigate")),
+                    shortcut_row("Enter", tr("shortcut-open")),
+                    shortcut_row("Ctrl + Enter", tr("shortcut-open-folder")),
+                    shortcut_row("Ctrl + C", tr("shortcut-copy-path")),
+                ]
                 .spacing(8)
             )
             .padding(18)
@@ -745,7 +1594,7 @@ fn welcome_hero_view(app: &App) -> Element<'_, Message> {
                 column![
                     row![
                         load_icon_size("star", 16.0),
-                        text("Pro Search Capabilities").size(14).font(Font {
+                        text(tr("welcome-features")).size(14).font(Font {
                             weight: font::Weight::Bold,
                             ..Font::default()
                         }),
@@ -753,16 +1602,10 @@ fn welcome_hero_view(app: &App) -> Element<'_, Message> {
                     .spacing(8)
                     .align_y(Alignment::Center),
                     Space::new().height(Length::Fixed(8.0)),
-                    feature_tip("Full Text vs Filename", "Toggle search scope in top bar"),
-                    feature_tip(
-                        "Extension Filter",
-                        "Filter PDF, MD, RS, TXT, Code in sidebar"
-                    ),
-                    feature_tip("Exact & Case Match", "Use 'Aa' and 'W' flags for precision"),
-                    feature_tip(
-                        "Instant Document Preview",
-                        "Inspect text snippets & tables live"
-                    ),
+                    feature_tip(tr("tip-scope-title"), tr("tip-scope")),
+                    feature_tip(tr("tip-extension-title"), tr("tip-extension")),
+                    feature_tip(tr("tip-exact-title"), tr("tip-exact")),
+                    feature_tip(tr("tip-preview-title"), tr("tip-preview")),
                 ]
                 .spacing(8)
             )
@@ -777,9 +1620,9 @@ fn welcome_hero_view(app: &App) -> Element<'_, Message> {
         container(
             row![
                 load_icon_size("database", 16.0),
-                text(format!(
-                    "Index Status: {} files indexed ({})",
-                    app.files_indexed, app.index_size
+                text(tr_args(
+                    "welcome-status",
+                    &[("count", &app.files_indexed), ("size", &app.index_size)]
                 ))
                 .size(12)
                 .style(theme::muted_text_style()),
@@ -802,7 +1645,106 @@ fn welcome_hero_view(app: &App) -> Element<'_, Message> {
         .into()
 }
 
-fn shortcut_row<'a>(key: &'a str, desc: &'a str) -> Element<'a, Message> {
+/// Pinned and recently modified files, listed while the search box is empty
+fn home_files(app: &App) -> Element<'_, Message> {
+    let pinned = &app.pinned_files;
+    if pinned.is_empty() && app.recent_files.is_empty() {
+        return Space::new().into();
+    }
+
+    let mut lists = row![].spacing(16).width(Length::Fill);
+    if !pinned.is_empty() {
+        let mut list = column![home_list_title("star", tr("home-pinned"))].spacing(4);
+        for path in pinned {
+            list = list.push(home_file_row(path, None, true));
+        }
+        lists = lists.push(
+            container(list)
+                .padding(18)
+                .style(theme::padded_card_container)
+                .width(Length::FillPortion(1)),
+        );
+    }
+    if !app.recent_files.is_empty() {
+        let mut list = column![home_list_title("clock", tr("home-recent"))].spacing(4);
+        for file in &app.recent_files {
+            list = list.push(home_file_row(
+                &file.path,
+                Some(file.modified),
+                pinned.contains(&file.path),
+            ));
+        }
+        lists = lists.push(
+            container(list)
+                .padding(18)
+                .style(theme::padded_card_container)
+                .width(Length::FillPortion(1)),
+        );
+    }
+    column![lists, Space::new().height(Length::Fixed(4.0))].into()
+}
+
+fn home_list_title(icon: &str, title: String) -> Element<'_, Message> {
+    row![
+        load_icon_size(icon, 16.0),
+        text(title).size(14).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn home_file_row(path: &str, modified: Option<u64>, pinned: bool) -> Element<'static, Message> {
+    let file_path = std::path::Path::new(path);
+    let name = file_path
+        .file_name()
+        .map_or_else(|| path.to_string(), |n| n.to_string_lossy().to_string());
+    let folder = file_path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let detail = match modified {
+        Some(m) => format!("{} · {folder}", super::format_date(m)),
+        None => folder,
+    };
+    let pin = if pinned {
+        Message::UnpinFile(path.to_string())
+    } else {
+        Message::PinFile(path.to_string())
+    };
+
+    row![
+        button(
+            column![
+                text(name).size(12),
+                text(detail).size(10).style(theme::dim_text_style()),
+            ]
+            .spacing(2),
+        )
+        .on_press(Message::OpenFile(path.to_string()))
+        .style(theme::ghost_button())
+        .padding(Padding::from([4, 6]))
+        .width(Length::Fill),
+        tooltip(
+            button(load_icon_size("star", 12.0))
+                .on_press(pin)
+                .style(theme::ghost_button())
+                .padding(Padding::from([4, 6])),
+            container(text(tr(if pinned { "unpin" } else { "pin" })).size(12))
+                .padding(Padding::from([4, 8]))
+                .style(theme::badge_container),
+            tooltip::Position::Left,
+        ),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn shortcut_row(key: &str, desc: String) -> Element<'_, Message> {
     row![
         container(text(key).size(11).font(Font {
             weight: font::Weight::Bold,
@@ -817,7 +1759,7 @@ fn shortcut_row<'a>(key: &'a str, desc: &'a str) -> Element<'a, Message> {
     .into()
 }
 
-fn feature_tip<'a>(title: &'a str, desc: &'a str) -> Element<'a, Message> {
+fn feature_tip(title: String, desc: String) -> Element<'static, Message> {
     column![
         text(title).size(12).font(Font {
             weight: font::Weight::Bold,
@@ -829,31 +1771,54 @@ fn feature_tip<'a>(title: &'a str, desc: &'a str) -> Element<'a, Message> {
     .into()
 }
 
-fn no_results_view(_app: &App) -> Element<'_, Message> {
+fn no_results_view(app: &App) -> Element<'_, Message> {
+    let did_you_mean = app.spelling_suggestion.as_ref().map_or_else(
+        || Element::from(Space::new().height(0)),
+        |suggestion| {
+            button(
+                row![
+                    text(tr("did-you-mean"))
+                        .size(13)
+                        .style(theme::dim_text_style()),
+                    text(suggestion.describe()).size(13).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::SearchQueryChanged(suggestion.query.clone()))
+            .padding(Padding::from([4, 10]))
+            .style(theme::ghost_button())
+            .into()
+        },
+    );
     container(
         column![
             load_icon_size("warning", 40.0),
-            text("No matching results found").size(17).font(Font {
+            text(tr("no-results-title")).size(17).font(Font {
                 weight: font::Weight::Bold,
                 ..Font::default()
             }),
-            text("Try adjusting your query or expanding search filters")
+            text(tr("no-results-hint"))
                 .size(13)
                 .style(theme::dim_text_style()),
+            did_you_mean,
             Space::new().height(Length::Fixed(12.0)),
             container(
                 column![
-                    text("Troubleshooting Suggestions:").size(12).font(Font {
+                    text(tr("no-results-tips")).size(12).font(Font {
                         weight: font::Weight::Bold,
                         ..Font::default()
                     }),
-                    text("• Check spelling or try simpler keywords")
+                    text(tr("no-results-tip-spelling"))
                         .size(12)
                         .style(theme::muted_text_style()),
-                    text("• Switch between Full Text and Filename search modes")
+                    text(tr("no-results-tip-mode"))
                         .size(12)
                         .style(theme::muted_text_style()),
-                    text("• Clear active file extension filters in the left sidebar")
+                    text(tr("no-results-tip-filters"))
                         .size(12)
                         .style(theme::muted_text_style()),
                 ]
@@ -877,10 +1842,11 @@ fn no_results_view(_app: &App) -> Element<'_, Message> {
 fn result_item_view<'a>(
     selected_index: Option<usize>,
     hovered_item_index: Option<usize>,
+    marked: bool,
     i: usize,
     res: &'a super::FileItem,
 ) -> Element<'a, Message> {
-    let is_selected = selected_index == Some(i);
+    let is_selected = selected_index == Some(i) || marked;
     let is_hovered = hovered_item_index == Some(i);
 
     let mut actions_row = row![].spacing(8);
@@ -888,17 +1854,23 @@ fn result_item_view<'a>(
         actions_row = actions_row.push(
             row![
                 button(
-                    row![load_icon_size("external-link", 13.0), text("Open").size(11)]
-                        .spacing(4)
-                        .align_y(Alignment::Center)
+                    row![
+                        load_icon_size("external-link", 13.0),
+                        text(tr("result-open")).size(11)
+                    ]
+                    .spacing(4)
+                    .align_y(Alignment::Center)
                 )
                 .on_press(Message::OpenFile(res.path.clone()))
                 .style(theme::ghost_button())
                 .padding(Padding::from([4, 8])),
                 button(
-                    row![load_icon_size("folder-open", 13.0), text("Folder").size(11)]
-                        .spacing(4)
-                        .align_y(Alignment::Center)
+                    row![
+                        load_icon_size("folder-open", 13.0),
+                        text(tr("result-folder")).size(11)
+                    ]
+                    .spacing(4)
+                    .align_y(Alignment::Center)
                 )
                 .on_press(Message::OpenFolder(res.path.clone()))
                 .style(theme::ghost_button())
@@ -912,8 +1884,12 @@ fn result_item_view<'a>(
         );
     }
 
-    let ext_str = res.extension.as_deref().unwrap_or("FILE");
+    let ext_str = res
+        .extension
+        .as_deref()
+        .unwrap_or(if res.is_dir { "FOLDER" } else { "FILE" });
     let file_icon_name = match ext_str.to_lowercase().as_str() {
+        _ if res.is_dir => "folder",
         "pdf" | "txt" | "md" | "doc" | "docx" => "file-text",
         "rs" | "py" | "js" | "ts" | "cpp" | "c" | "cs" | "java" | "go" | "html" | "css"
         | "json" | "toml" => "file-code",
@@ -946,7 +1922,7 @@ fn result_item_view<'a>(
             container(
                 text(
                     res.size
-                        .map_or_else(|| "Unknown size".to_string(), crate::iced_ui::format_size)
+                        .map_or_else(|| tr("unknown-size"), crate::iced_ui::format_size)
                 )
                 .size(10)
             )
@@ -955,12 +1931,51 @@ fn result_item_view<'a>(
             container(
                 text(
                     res.modified
-                        .map_or_else(|| "Unknown date".to_string(), crate::iced_ui::format_date)
+                        .map_or_else(|| tr("unknown-date"), crate::iced_ui::format_date)
                 )
                 .size(10)
             )
             .padding(Padding::from([2, 6]))
             .style(theme::badge_container),
+            if res.unavailable {
+                Element::from(
+                    button(
+                        row![
+                            load_icon_size("warning", 11.0),
+                            text(tr("result-offline"))
+                                .size(10)
+                                .style(theme::muted_text_style())
+                        ]
+                        .spacing(4)
+                        .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::PromptReconnect(
+                        res.path.clone(),
+                        res.volume.clone(),
+                    ))
+                    .style(theme::ghost_button())
+                    .padding(Padding::from([2, 6])),
+                )
+            } else {
+                Element::from(Space::new().width(0))
+            },
+            if res.stale {
+                Element::from(
+                    button(
+                        row![
+                            load_icon_size("refresh", 11.0),
+                            text(tr("result-changed")).size(10)
+                        ]
+                        .spacing(4)
+                        .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::ReindexPath(res.path.clone()))
+                    .style(theme::ghost_button())
+                    .padding(Padding::from([2, 6])),
+                )
+            } else {
+                Element::from(Space::new().width(0))
+            },
         ]
         .spacing(6),
         if res.snippets.is_empty() {
@@ -1084,24 +2099,113 @@ fn parse_snippet<'a>(content: &'a str) -> Element<'a, Message> {
     rich_text(spans).into()
 }
 
+/// Two results' text side by side in one column: lines only the first has
+/// in red, lines only the second has in green.
+fn diff_panel(diff: &crate::models::ContentDiff) -> Element<'_, Message> {
+    use crate::models::DiffLine;
+
+    let name = |path: &str| {
+        std::path::Path::new(path)
+            .file_name()
+            .map_or_else(|| path.to_string(), |n| n.to_string_lossy().to_string())
+    };
+    let header = row![
+        load_icon_size("file-text", 16.0),
+        column![
+            text(format!("{} → {}", name(&diff.left), name(&diff.right)))
+                .size(14)
+                .font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }),
+            text(tr_args(
+                "diff-summary",
+                &[("removed", &diff.removed), ("added", &diff.added)]
+            ))
+            .size(11)
+            .style(theme::dim_text_style()),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        button(load_icon_size("x", 14.0))
+            .on_press(Message::CloseContentDiff)
+            .style(theme::ghost_button())
+            .padding(Padding::new(6.0)),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+    .padding(Padding::new(14.0));
+
+    let mut lines = column![].spacing(1);
+    if diff.lines.is_empty() {
+        lines = lines.push(
+            text(tr("diff-identical"))
+                .size(12)
+                .style(theme::success_text_style()),
+        );
+    }
+    for line in &diff.lines {
+        let row: Element<'_, Message> = match line {
+            DiffLine::Same(s) => text(format!("  {s}"))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(theme::muted_text_style())
+                .into(),
+            DiffLine::Removed(s) => text(format!("- {s}"))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(theme::danger_text_style())
+                .into(),
+            DiffLine::Added(s) => text(format!("+ {s}"))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(theme::success_text_style())
+                .into(),
+            DiffLine::Skipped(n) => text(tr_args("diff-skipped", &[("count", n)]))
+                .size(11)
+                .style(theme::dim_text_style())
+                .into(),
+        };
+        lines = lines.push(row);
+    }
+
+    column![
+        header,
+        scrollable(
+            container(lines)
+                .padding(Padding::new(12.0))
+                .width(Length::Fill)
+                .style(theme::code_block_container),
+        )
+        .height(Length::Fill),
+    ]
+    .padding(Padding::from([0, 12]))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
 #[allow(clippy::too_many_lines)]
 fn right_panel(app: &App) -> Element<'_, Message> {
+    if let Some(diff) = &app.content_diff {
+        return diff_panel(diff);
+    }
     app.preview_result.as_ref().map_or_else(
         || {
             container(
                 column![
                     load_icon_size("file-text", 44.0),
-                    text(if app.is_loading_preview {
-                        "Loading document contents..."
+                    text(tr(if app.is_loading_preview {
+                        "preview-loading"
                     } else {
-                        "Select a search result to preview"
-                    })
+                        "preview-select"
+                    }))
                     .size(16)
                     .font(Font {
                         weight: font::Weight::Bold,
                         ..Font::default()
                     }),
-                    text("Snippets and document preview will appear here")
+                    text(tr("preview-hint"))
                         .size(12)
                         .style(theme::dim_text_style()),
                 ]
@@ -1116,7 +2220,7 @@ fn right_panel(app: &App) -> Element<'_, Message> {
             let res = app.selected_index.and_then(|i| app.results.get(i));
 
             let ext = res.and_then(|r| r.extension.as_deref()).unwrap_or("txt");
-            let title = res.map_or("Document Preview", |r| &*r.title);
+            let title = res.map_or_else(|| tr("preview-title"), |r| r.title.clone());
 
             let file_icon = match ext.to_lowercase().as_str() {
                 "pdf" | "txt" | "md" | "doc" => "file-text",
@@ -1130,17 +2234,23 @@ fn right_panel(app: &App) -> Element<'_, Message> {
                 |r| {
                     row![
                         button(
-                            row![load_icon_size("external-link", 13.0), text("Open").size(11)]
-                                .spacing(4)
-                                .align_y(Alignment::Center)
+                            row![
+                                load_icon_size("external-link", 13.0),
+                                text(tr("result-open")).size(11)
+                            ]
+                            .spacing(4)
+                            .align_y(Alignment::Center)
                         )
                         .on_press(Message::OpenFile(r.path.clone()))
                         .style(theme::ghost_button())
                         .padding(Padding::from([4, 8])),
                         button(
-                            row![load_icon_size("folder-open", 13.0), text("Folder").size(11)]
-                                .spacing(4)
-                                .align_y(Alignment::Center)
+                            row![
+                                load_icon_size("folder-open", 13.0),
+                                text(tr("result-folder")).size(11)
+                            ]
+                            .spacing(4)
+                            .align_y(Alignment::Center)
                         )
                         .on_press(Message::OpenFolder(r.path.clone()))
                         .style(theme::ghost_button())
@@ -1166,6 +2276,9 @@ fn right_panel(app: &App) -> Element<'_, Message> {
                         text(res.map_or("", |r| &*r.path))
                             .size(11)
                             .style(theme::dim_text_style()),
+                        text(res.map(ownership_line).unwrap_or_default())
+                            .size(11)
+                            .style(theme::dim_text_style()),
                     ]
                     .spacing(2)
                     .width(Length::Fill),
@@ -1183,10 +2296,52 @@ fn right_panel(app: &App) -> Element<'_, Message> {
             .style(theme::header_container)
             .width(Length::Fill);
 
-            let content: Element<'_, Message> =
-                column(preview_result.elements.iter().map(render_element))
-                    .spacing(10)
-                    .into();
+            let matches = super::preview_matches(preview_result);
+            let current = matches.get(app.preview_match).copied();
+            let content: Element<'_, Message> = column(
+                preview_result
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(e, element)| {
+                        render_element(element, current.filter(|c| c.0 == e).map(|c| c.1))
+                    }),
+            )
+            .spacing(10)
+            .into();
+
+            let match_nav: Element<'_, Message> = if matches.is_empty() {
+                Space::new().width(0).into()
+            } else {
+                row![
+                    text(tr_args(
+                        "preview-match",
+                        &[
+                            ("current", &(app.preview_match + 1)),
+                            ("total", &matches.len()),
+                        ],
+                    ))
+                    .size(11)
+                    .style(theme::dim_text_style()),
+                    with_tooltip(
+                        button(load_icon_size("chevron-up", 13.0))
+                            .on_press(Message::PreviousPreviewMatch)
+                            .style(theme::ghost_button())
+                            .padding(Padding::new(4.0)),
+                        tr("preview-previous"),
+                    ),
+                    with_tooltip(
+                        button(load_icon_size("chevron-down", 13.0))
+                            .on_press(Message::NextPreviewMatch)
+                            .style(theme::ghost_button())
+                            .padding(Padding::new(4.0)),
+                        tr("preview-next"),
+                    ),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center)
+                .into()
+            };
 
             let snippets: Element<'_, Message> = res.map_or_else(
                 || column![].into(),
@@ -1197,7 +2352,7 @@ fn right_panel(app: &App) -> Element<'_, Message> {
                         column![
                             row![
                                 load_icon_size("sparkles", 14.0),
-                                text("Matching Snippets")
+                                text(tr("preview-snippets"))
                                     .size(13)
                                     .font(Font {
                                         weight: font::Weight::Bold,
@@ -1221,14 +2376,43 @@ fn right_panel(app: &App) -> Element<'_, Message> {
                 },
             );
 
+            let has_text = matches!(
+                app.preview_kind,
+                PreviewKind::Text | PreviewKind::PdfPage { .. }
+            );
+            let document_content: Element<'_, Message> = if has_text {
+                column![
+                    row![
+                        text(tr("preview-content"))
+                            .size(13)
+                            .font(Font {
+                                weight: font::Weight::Bold,
+                                ..Font::default()
+                            })
+                            .style(theme::muted_text_style())
+                            .width(Length::Fill),
+                        match_nav,
+                    ]
+                    .align_y(Alignment::Center),
+                    container(content)
+                        .padding(Padding::new(18.0))
+                        .style(theme::main_content_container),
+                ]
+                .spacing(18)
+                .into()
+            } else {
+                Space::new().height(0).into()
+            };
+
             let body = scrollable(
                 column![
+                    preview_visual(&app.preview_kind),
                     container(
                         row![
                             load_icon("file-text"),
-                            text(format!(
-                                "{} structural elements parsed",
-                                preview_result.elements.len()
+                            text(tr_args(
+                                "preview-elements",
+                                &[("count", &preview_result.elements.len())]
                             ))
                             .size(11)
                         ]
@@ -1242,22 +2426,18 @@ fn right_panel(app: &App) -> Element<'_, Message> {
                         left: 10.0,
                         right: 10.0,
                     }),
+                    preview_window_notice(preview_result),
                     snippets,
+                    details_section(app),
+                    notes_section(app),
                     Space::new().height(6.0),
-                    text("Document Content")
-                        .size(13)
-                        .font(Font {
-                            weight: font::Weight::Bold,
-                            ..Font::default()
-                        })
-                        .style(theme::muted_text_style()),
-                    container(content)
-                        .padding(Padding::new(18.0))
-                        .style(theme::main_content_container),
+                    document_content,
+                    similar_section(app),
                 ]
                 .spacing(18)
                 .padding(Padding::new(18.0)),
             )
+            .id(super::get_preview_scroll_id())
             .height(Length::Fill);
 
             column![header, body]
@@ -1268,6 +2448,297 @@ fn right_panel(app: &App) -> Element<'_, Message> {
     )
 }
 
+/// Picture of the file above its text: the image itself, the first page of
+/// a PDF, or a hex dump of a binary file.
+fn preview_visual(kind: &PreviewKind) -> Element<'_, Message> {
+    const HEIGHT: f32 = 340.0;
+    match kind {
+        PreviewKind::Text => Space::new().height(0).into(),
+        PreviewKind::Image { path } => {
+            let picture: Element<'_, Message> = if path.to_lowercase().ends_with(".svg") {
+                iced::widget::svg(iced::widget::svg::Handle::from_path(path))
+                    .height(Length::Fixed(HEIGHT))
+                    .into()
+            } else {
+                iced::widget::image(iced::widget::image::Handle::from_path(path))
+                    .content_fit(iced::ContentFit::Contain)
+                    .height(Length::Fixed(HEIGHT))
+                    .into()
+            };
+            container(picture)
+                .padding(Padding::new(12.0))
+                .center_x(Length::Fill)
+                .style(theme::main_content_container)
+                .into()
+        }
+        PreviewKind::PdfPage { text: page, pages } => column![
+            container(
+                container(text(page.as_str()).size(8))
+                    .padding(Padding::new(18.0))
+                    .width(Length::Fixed(HEIGHT / 1.414))
+                    .height(Length::Fixed(HEIGHT))
+                    .clip(true)
+                    .style(theme::pdf_page_container),
+            )
+            .center_x(Length::Fill),
+            text(tr_args("preview-page", &[("pages", pages)]))
+                .size(11)
+                .style(theme::dim_text_style())
+                .width(Length::Fill)
+                .center(),
+        ]
+        .spacing(6)
+        .into(),
+        PreviewKind::Binary { mime, hex, size } => {
+            let shown = hex.lines().count() * 16;
+            column![
+                row![
+                    load_icon_size("file", 16.0),
+                    text(tr_args(
+                        "preview-binary",
+                        &[
+                            ("mime", mime),
+                            ("shown", &super::format_size((shown as u64).min(*size))),
+                            ("size", &super::format_size(*size)),
+                        ],
+                    ))
+                    .size(12)
+                    .style(theme::dim_text_style()),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+                container(
+                    scrollable(text(hex.as_str()).size(11).font(Font::MONOSPACE)).direction(
+                        iced::widget::scrollable::Direction::Horizontal(
+                            iced::widget::scrollable::Scrollbar::default(),
+                        )
+                    ),
+                )
+                .padding(Padding::new(12.0))
+                .width(Length::Fill)
+                .style(theme::code_block_container),
+            ]
+            .spacing(8)
+            .into()
+        }
+    }
+}
+
+/// Says which part of a long document the preview shows.
+fn preview_window_notice(preview: &crate::models::PreviewResult) -> Element<'_, Message> {
+    if preview.start == 0 && preview.end >= preview.total_len {
+        return Space::new().height(0).into();
+    }
+    let key = if preview.matched_terms.is_empty() || preview.start == 0 {
+        "preview-window-start"
+    } else {
+        "preview-window-match"
+    };
+    text(tr_args(
+        key,
+        &[
+            (
+                "size",
+                &super::format_size((preview.end - preview.start) as u64),
+            ),
+            ("start", &super::format_size(preview.start as u64)),
+            ("end", &super::format_size(preview.end as u64)),
+            ("total", &super::format_size(preview.total_len as u64)),
+        ],
+    ))
+    .size(11)
+    .style(theme::dim_text_style())
+    .into()
+}
+
+/// "Owner: alice · Read-only · Hidden" for the details header.
+fn ownership_line(item: &super::FileItem) -> String {
+    let mut parts = Vec::new();
+    if let Some(owner) = &item.owner {
+        parts.push(tr_args("owner-line", &[("owner", owner)]));
+    }
+    if item.readonly {
+        parts.push(tr("read-only"));
+    }
+    if item.hidden {
+        parts.push(tr("hidden"));
+    }
+    if item.unavailable {
+        parts.push(tr("offline-detail"));
+    }
+    if item.stale {
+        parts.push(tr("changed-detail"));
+    }
+    parts.join(" · ")
+}
+
+/// Files whose content resembles the selected one's; each opens on click.
+fn similar_section(app: &App) -> Element<'_, Message> {
+    if app.similar_files.is_empty() {
+        return Space::new().height(0).into();
+    }
+    let mut strip = row![].spacing(8);
+    for item in &app.similar_files {
+        strip = strip.push(tooltip(
+            button(
+                row![
+                    load_icon_size("file-text", 13.0),
+                    text(&item.title).size(11),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenFile(item.path.clone()))
+            .padding(Padding::from([4, 8]))
+            .style(theme::ghost_button()),
+            container(text(&item.path).size(12))
+                .padding(Padding::from([4, 8]))
+                .style(theme::badge_container),
+            tooltip::Position::Top,
+        ));
+    }
+
+    column![
+        row![
+            load_icon_size("copy", 14.0),
+            text(tr("similar-title"))
+                .size(13)
+                .font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                })
+                .style(theme::muted_text_style()),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center),
+        scrollable(strip).direction(iced::widget::scrollable::Direction::Horizontal(
+            iced::widget::scrollable::Scrollbar::default(),
+        )),
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// Size, dates, hash and index status of the selected file.
+fn details_section(app: &App) -> Element<'_, Message> {
+    let Some(details) = &app.file_details else {
+        return Space::new().height(0).into();
+    };
+    let date = |secs: Option<u64>| secs.map_or_else(|| "—".to_string(), super::format_date);
+    let field = |label: String, value: String| {
+        row![
+            text(label)
+                .size(11)
+                .width(Length::Fixed(96.0))
+                .style(theme::dim_text_style()),
+            text(value).size(11).width(Length::Fill),
+        ]
+        .spacing(8)
+    };
+    let stale = details.stale;
+    let status = match (details.indexed_at, stale) {
+        (None, _) => tr("details-not-indexed"),
+        (Some(at), false) => tr_args("details-current", &[("date", &super::format_date(at))]),
+        (Some(at), true) => tr_args("details-changed", &[("date", &super::format_date(at))]),
+    };
+
+    column![
+        row![
+            load_icon_size("info", 14.0),
+            text(tr("details-title"))
+                .size(13)
+                .font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                })
+                .style(theme::muted_text_style()),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center),
+        container(
+            column![
+                field(
+                    tr("details-size"),
+                    details
+                        .size
+                        .map_or_else(|| "—".to_string(), super::format_size)
+                ),
+                field(tr("details-modified"), date(details.modified)),
+                field(tr("details-created"), date(details.created)),
+                field(
+                    tr("details-hash"),
+                    details
+                        .content_hash
+                        .clone()
+                        .unwrap_or_else(|| "—".to_string())
+                ),
+                field(tr("details-parser"), details.parser.clone()),
+                row![
+                    text(tr("details-index"))
+                        .size(11)
+                        .width(Length::Fixed(96.0))
+                        .style(theme::dim_text_style()),
+                    text(status)
+                        .size(11)
+                        .width(Length::Fill)
+                        .style(move |t: &iced::Theme| {
+                            if stale {
+                                theme::danger_text_style()(t)
+                            } else {
+                                theme::muted_text_style()(t)
+                            }
+                        }),
+                ]
+                .spacing(8),
+            ]
+            .spacing(6),
+        )
+        .padding(Padding::new(12.0))
+        .width(Length::Fill)
+        .style(theme::padded_card_container),
+    ]
+    .spacing(10)
+    .into()
+}
+
+fn notes_section(app: &App) -> Element<'_, Message> {
+    column![
+        row![
+            load_icon_size("tag", 14.0),
+            text(tr("notes-title"))
+                .size(13)
+                .font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                })
+                .style(theme::muted_text_style()),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center),
+        TextInput::new(&tr("notes-placeholder"), &app.annotation_note)
+            .on_input(Message::AnnotationNoteChanged)
+            .on_submit(Message::SaveAnnotation)
+            .padding(8)
+            .size(12),
+        row![
+            TextInput::new(&tr("notes-attributes"), &app.annotation_attributes)
+                .on_input(Message::AnnotationAttributesChanged)
+                .on_submit(Message::SaveAnnotation)
+                .padding(8)
+                .size(12)
+                .width(Length::Fill),
+            button(text(tr("notes-save")).size(12))
+                .on_press(Message::SaveAnnotation)
+                .style(theme::secondary_button())
+                .padding(Padding::from([6, 12])),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(8)
+    .into()
+}
+
 fn hit_row(idx: usize, content: &str) -> Element<'_, Message> {
     container(
         row![
@@ -1289,12 +2760,51 @@ fn hit_row(idx: usize, content: &str) -> Element<'_, Message> {
     .into()
 }
 
+/// Dot and label showing whether live updates are working; hovering lists
+/// the folders that aren't watched or the last watcher error.
+fn watcher_indicator(status: &WatcherStatus) -> Option<Element<'_, Message>> {
+    let (label, healthy) = watcher_summary(status)?;
+    let details = if !status.unwatched_roots.is_empty() {
+        tr_args(
+            "watcher-unwatched",
+            &[("folders", &status.unwatched_roots.join(", "))],
+        )
+    } else if let Some(error) = &status.last_error {
+        tr_args("watcher-error", &[("error", error)])
+    } else {
+        tr_args("watcher-events", &[("count", &status.events_processed)])
+    };
+
+    let dot = text("●").size(10);
+    let dot = if healthy {
+        dot.style(theme::success_text_style())
+    } else {
+        dot.style(theme::danger_text_style())
+    };
+    let indicator = container(
+        row![dot, text(label).size(11).style(theme::dim_text_style())]
+            .spacing(6)
+            .align_y(Alignment::Center),
+    );
+    Some(
+        tooltip(
+            indicator,
+            container(text(details).size(12))
+                .padding(Padding::from([4, 8]))
+                .style(theme::badge_container),
+            tooltip::Position::Top,
+        )
+        .gap(4)
+        .into(),
+    )
+}
+
 fn status_bar(app: &App) -> Element<'_, Message> {
     let mut status_row = row![
         container(
             row![
                 load_icon_size("database", 12.0),
-                text(format!("{} files indexed", app.files_indexed)).size(11),
+                text(tr_args("status-files", &[("count", &app.files_indexed)])).size(11),
             ]
             .spacing(6)
             .align_y(Alignment::Center)
@@ -1303,17 +2813,25 @@ fn status_bar(app: &App) -> Element<'_, Message> {
         text(&app.index_size)
             .size(11)
             .style(theme::dim_text_style()),
-        Space::new().width(Length::Fill),
     ];
 
+    if let Some(indicator) = app.watcher_status.as_ref().and_then(watcher_indicator) {
+        status_row = status_row
+            .push(Space::new().width(Length::Fixed(16.0)))
+            .push(indicator);
+    }
+    status_row = status_row.push(Space::new().width(Length::Fill));
+
     if !app.results.is_empty() {
         status_row = status_row.push(
             row![
-                text(format!("{} results found", app.results.len()))
+                text(tr_args("results-found", &[("count", &app.results.len())]))
                     .size(11)
                     .style(theme::dim_text_style()),
                 Space::new().width(Length::Fixed(12.0)),
-                text("Export:").size(11).style(theme::dim_text_style()),
+                text(tr("export-label"))
+                    .size(11)
+                    .style(theme::dim_text_style()),
                 button(text("CSV").size(10).font(Font {
                     weight: font::Weight::Bold,
                     ..Font::default()
@@ -1328,6 +2846,13 @@ fn status_bar(app: &App) -> Element<'_, Message> {
                 .on_press(Message::ExportResults("json".to_string()))
                 .style(theme::secondary_button())
                 .padding(Padding::from([2, 8])),
+                button(text("Markdown").size(10).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }))
+                .on_press(Message::ExportResults("md".to_string()))
+                .style(theme::secondary_button())
+                .padding(Padding::from([2, 8])),
             ]
             .spacing(6)
             .align_y(Alignment::Center),
@@ -1342,11 +2867,17 @@ fn status_bar(app: &App) -> Element<'_, Message> {
 
         if let Some(eta) = app.rebuild_eta {
             let eta_str = if eta >= 3600 {
-                format!("ETA: {}h {}m", eta / 3600, (eta % 3600) / 60)
+                tr_args(
+                    "eta-hours",
+                    &[("hours", &(eta / 3600)), ("minutes", &((eta % 3600) / 60))],
+                )
             } else if eta >= 60 {
-                format!("ETA: {}m {}s", eta / 60, eta % 60)
+                tr_args(
+                    "eta-minutes",
+                    &[("minutes", &(eta / 60)), ("seconds", &(eta % 60))],
+                )
             } else {
-                format!("ETA: {eta}s")
+                tr_args("eta-seconds", &[("seconds", &eta)])
             };
             status_row = status_row.push(text(eta_str).size(11));
             status_row = status_row.push(Space::new().width(Length::Fixed(8.0)));
@@ -1357,6 +2888,36 @@ fn status_bar(app: &App) -> Element<'_, Message> {
         status_row = status_row.push(text(status).size(11));
     }
 
+    if app.rebuild_progress.is_some() {
+        let (label, message) = if app.indexing_paused {
+            (tr("indexing-resume"), Message::ResumeIndexing)
+        } else {
+            (tr("indexing-pause"), Message::PauseIndexing)
+        };
+        status_row = status_row.push(Space::new().width(Length::Fixed(8.0)));
+        status_row = status_row.push(
+            row![
+                button(text(label).size(10))
+                    .on_press(message)
+                    .style(theme::secondary_button())
+                    .padding(Padding::from([2, 8])),
+                button(text(tr("indexing-cancel")).size(10))
+                    .on_press(Message::CancelIndexing)
+                    .style(theme::secondary_button())
+                    .padding(Padding::from([2, 8])),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    } else if let Some(files) = app.pending_scan_files {
+        status_row = status_row.push(
+            button(text(tr_args("indexing-resume-previous", &[("files", &files)])).size(10))
+                .on_press(Message::ResumeIndexing)
+                .style(theme::secondary_button())
+                .padding(Padding::from([2, 8])),
+        );
+    }
+
     container(status_row.padding(Padding {
         top: 6.0,
         bottom: 6.0,
@@ -1395,7 +2956,7 @@ fn size_unit_button<'a>(unit: &'a str, app: &App) -> Element<'a, Message> {
     .into()
 }
 
-fn date_filter_button<'a>(label: &'a str, filter: DateFilter, app: &App) -> Element<'a, Message> {
+fn date_filter_button(label: String, filter: DateFilter, app: &App) -> Element<'_, Message> {
     let is_active = app.date_filter == filter;
     button(text(label).size(12))
         .on_press(Message::DateFilterChanged(filter))
@@ -1413,18 +2974,39 @@ fn date_filter_button<'a>(label: &'a str, filter: DateFilter, app: &App) -> Elem
 
 fn sort_order_section(app: &App) -> Element<'_, Message> {
     sidebar_section(
-        "Sort Results By",
+        tr("sort-title"),
         column![
-            sort_button("Relevance Score", SortBy::Relevance, app),
-            sort_button("Date Modified", SortBy::DateModified, app),
-            sort_button("File Size", SortBy::Size, app),
-            sort_button("File Name", SortBy::Name, app),
+            sort_button(tr("sort-relevance"), SortBy::Relevance, app),
+            sort_button(tr("sort-date"), SortBy::DateModified, app),
+            sort_button(tr("sort-size"), SortBy::Size, app),
+            sort_button(tr("sort-name"), SortBy::Name, app),
+            Space::new().height(Length::Fixed(4.0)),
+            row![
+                layout_button(tr("layout-cards"), ResultsLayout::Cards, app),
+                layout_button(tr("layout-table"), ResultsLayout::Table, app),
+            ]
+            .spacing(4),
+            checkbox(app.group_by_folder)
+                .label(tr("group-by-folder"))
+                .on_toggle(Message::ToggleGroupByFolder)
+                .size(16)
+                .text_size(12),
         ]
         .spacing(4),
     )
 }
 
-fn sort_button<'a>(label: &'a str, sort: SortBy, app: &App) -> Element<'a, Message> {
+fn layout_button(label: String, layout: ResultsLayout, app: &App) -> Element<'_, Message> {
+    let is_active = app.results_layout == layout;
+    button(text(label).size(12).center())
+        .on_press(Message::ResultsLayoutChanged(layout))
+        .style(move |t: &iced::Theme, s| theme::nav_button(is_active)(t, s))
+        .width(Length::Fill)
+        .padding(Padding::new(6.0))
+        .into()
+}
+
+fn sort_button(label: String, sort: SortBy, app: &App) -> Element<'_, Message> {
     let is_active = app.sort_by == sort;
     button(text(label).size(12))
         .on_press(Message::SortByChanged(sort))
@@ -1442,27 +3024,21 @@ fn sort_button<'a>(label: &'a str, sort: SortBy, app: &App) -> Element<'a, Messa
 
 fn category_filter_section(app: &App) -> Element<'_, Message> {
     sidebar_section(
-        "Quick Categories",
-        column![
-            category_preset_button("📄 Documents", &["pdf", "docx", "md", "txt"], app),
-            category_preset_button("💻 Source Code", &["rs", "py", "js", "ts", "cpp"], app),
-            category_preset_button("📊 Data & Logs", &["json", "csv", "log", "xml"], app),
-            category_preset_button("🖼️ Images", &["png", "jpg", "jpeg", "svg"], app),
-        ]
+        tr("categories"),
+        column(
+            FileCategory::iter()
+                .filter(|c| *c != FileCategory::Other)
+                .map(|c| category_button(c, app)),
+        )
         .spacing(4),
     )
 }
 
-fn category_preset_button<'a>(
-    label: &'a str,
-    exts: &'static [&'static str],
-    app: &App,
-) -> Element<'a, Message> {
-    let is_active = exts.iter().all(|e| app.filter_extensions.contains(*e));
-    let exts_vec: Vec<String> = exts.iter().map(|s| (*s).to_string()).collect();
+fn category_button(category: FileCategory, app: &App) -> Element<'_, Message> {
+    let is_active = app.filter_categories.contains(&category);
 
-    button(text(label).size(12))
-        .on_press(Message::ToggleCategory(exts_vec))
+    button(text(category.label()).size(12))
+        .on_press(Message::ToggleCategory(category))
         .style(move |t: &iced::Theme, s| {
             if is_active {
                 theme::nav_button(true)(t, s)
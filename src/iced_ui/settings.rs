@@ -1,7 +1,16 @@
-use super::{App, Message, Tab, theme};
+use super::{App, Message, RootEdit, Tab, theme};
+use crate::i18n::{tr, tr_args};
 use crate::iced_ui::icons::load_icon_size;
-use iced::widget::{Scrollable, Space, TextInput, button, checkbox, column, container, row, text};
+use crate::iced_ui::shortcuts::{KeyCombo, ShortcutAction};
+use crate::metadata::ParseErrorKind;
+use crate::models::ScanEstimate;
+use crate::scanner::volumes::Volume;
+use crate::settings::{AppSettings, IndexEncryption, ReindexSchedule, SensitiveFiles};
+use iced::widget::{
+    Scrollable, Space, TextInput, button, checkbox, column, container, pick_list, row, text,
+};
 use iced::{Alignment, Element, Font, Length, Padding, font};
+use std::path::Path;
 
 pub fn settings_view(app: &App) -> Element<'_, Message> {
     let content = column![
@@ -12,11 +21,11 @@ pub fn settings_view(app: &App) -> Element<'_, Message> {
                 .padding(10)
                 .style(theme::accent_badge_container),
             column![
-                text("Application Settings").size(24).font(Font {
+                text(tr("settings-title")).size(24).font(Font {
                     weight: font::Weight::Bold,
                     ..Font::default()
                 }),
-                text("Configure search preferences, indexed locations, and desktop options")
+                text(tr("settings-subtitle"))
                     .size(13)
                     .style(theme::dim_text_style()),
             ]
@@ -45,34 +54,34 @@ pub fn settings_view(app: &App) -> Element<'_, Message> {
         .into()
 }
 
-fn settings_tabs(app: &App) -> Element<'_, Message> {
-    row![
-        button(
-            row![load_icon_size("search", 14.0), text("Search View").size(13)]
-                .spacing(8)
-                .align_y(Alignment::Center)
-        )
-        .on_press(Message::TabChanged(Tab::Search))
-        .padding(Padding::from([8, 16]))
-        .style(theme::tab_button(false)),
+/// Row of page tabs, with a theme switch, heading the settings and
+/// statistics pages.
+pub fn settings_tabs(app: &App) -> Element<'_, Message> {
+    let tab = |icon: &str, label: &str, target: Tab| {
+        let active = app.active_tab == target;
         button(
-            row![load_icon_size("settings", 14.0), text("Settings").size(13)]
+            row![load_icon_size(icon, 14.0), text(tr(label)).size(13)]
                 .spacing(8)
-                .align_y(Alignment::Center)
+                .align_y(Alignment::Center),
         )
-        .on_press(Message::TabChanged(Tab::Settings))
+        .on_press(Message::TabChanged(target))
         .padding(Padding::from([8, 16]))
-        .style(theme::tab_button(true)),
+        .style(theme::tab_button(active))
+    };
+    row![
+        tab("search", "tab-search", Tab::Search),
+        tab("settings", "tab-settings", Tab::Settings),
+        tab("database", "tab-statistics", Tab::Statistics),
         Space::new().width(Length::Fill),
         // Direct theme switcher in settings header
         button(
             row![
                 load_icon_size(if app.is_dark { "sun" } else { "moon" }, 14.0),
-                text(if app.is_dark {
-                    "Light Mode"
+                text(tr(if app.is_dark {
+                    "theme-light"
                 } else {
-                    "Dark Mode"
-                })
+                    "theme-dark"
+                }))
                 .size(12)
             ]
             .spacing(6)
@@ -89,37 +98,85 @@ fn settings_tabs(app: &App) -> Element<'_, Message> {
 
 fn settings_form(app: &App) -> Element<'_, Message> {
     column![
-        section_header("sliders", "Search Configuration"),
+        section_header("sliders", tr("section-search")),
         container(search_settings_fields(app))
             .padding(20)
             .style(theme::padded_card_container)
             .width(Length::Fill),
         Space::new().height(Length::Fixed(32.0)),
-        section_header("folder", "Index Directories"),
+        section_header("sparkles", tr("section-macros")),
+        container(query_macros_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("clock", tr("section-history")),
+        container(search_history_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("tag", tr("section-profile")),
+        container(profile_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("folder", tr("section-index-dirs")),
         container(index_directories_section(app))
             .padding(20)
             .style(theme::padded_card_container)
             .width(Length::Fill),
         Space::new().height(Length::Fixed(32.0)),
-        section_header("gear", "System & Desktop Preferences"),
+        section_header("gear", tr("section-system")),
         container(system_integration_section(app))
             .padding(20)
             .style(theme::padded_card_container)
             .width(Length::Fill),
         Space::new().height(Length::Fixed(32.0)),
-        section_header("sun", "Appearance & Theme"),
+        section_header("external-link", tr("section-opening")),
+        container(opening_files_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("keyboard", tr("section-shortcuts")),
+        container(shortcuts_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("text", tr("section-language")),
+        container(language_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("sun", tr("section-appearance")),
         container(appearance_section(app))
             .padding(20)
             .style(theme::padded_card_container)
             .width(Length::Fill),
         Space::new().height(Length::Fixed(32.0)),
-        section_header("database", "Data Management"),
+        section_header("warning", tr("section-problems")),
+        container(problems_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("clock", tr("section-activity")),
+        container(activity_section(app))
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+        Space::new().height(Length::Fixed(32.0)),
+        section_header("database", tr("section-data")),
         container(data_management_section(app))
             .padding(20)
             .style(theme::padded_card_container)
             .width(Length::Fill),
         Space::new().height(Length::Fixed(32.0)),
-        section_header("info", "Privacy & Local Security"),
+        section_header("info", tr("section-privacy")),
         container(privacy_security_section())
             .padding(20)
             .style(theme::padded_card_container)
@@ -129,7 +186,7 @@ fn settings_form(app: &App) -> Element<'_, Message> {
             button(
                 row![
                     load_icon_size("check", 16.0),
-                    text("Save All Changes").size(15).font(Font {
+                    text(tr("settings-save")).size(15).font(Font {
                         weight: font::Weight::Bold,
                         ..Font::default()
                     })
@@ -149,7 +206,7 @@ fn settings_form(app: &App) -> Element<'_, Message> {
     .into()
 }
 
-fn section_header<'a>(icon: &'a str, title: &'a str) -> Element<'a, Message> {
+pub fn section_header(icon: &str, title: String) -> Element<'_, Message> {
     column![
         row![
             load_icon_size(icon, 18.0),
@@ -165,15 +222,136 @@ fn section_header<'a>(icon: &'a str, title: &'a str) -> Element<'a, Message> {
     .into()
 }
 
+/// Asks whether to index a picked folder, showing what its dry run found.
+pub fn scan_estimate_dialog<'a>(
+    estimate: &'a ScanEstimate,
+    settings: &AppSettings,
+) -> Element<'a, Message> {
+    let muted = |line: String| text(line).size(13).style(theme::muted_text_style());
+    let size = super::format_size(estimate.indexable_bytes);
+    let files_key = if estimate.truncated {
+        "estimate-files-over"
+    } else {
+        "estimate-files"
+    };
+    let mut body = column![
+        section_header("folder", tr("estimate-title")),
+        text(&estimate.root).size(13),
+        muted(tr_args(
+            files_key,
+            &[
+                ("indexable", &estimate.indexable_files),
+                ("files", &estimate.files_seen),
+                ("size", &size),
+            ],
+        )),
+    ]
+    .spacing(6);
+
+    if !settings
+        .root_profile(Path::new(&estimate.root))
+        .indexes_content()
+    {
+        body = body.push(muted(tr("estimate-names-only")));
+    }
+    for category in &estimate.by_category {
+        body = body.push(
+            row![
+                text(category.category.to_string())
+                    .size(12)
+                    .width(Length::Fixed(120.0)),
+                text(category.files.to_string())
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(super::format_size(category.bytes))
+                    .size(12)
+                    .style(theme::dim_text_style()),
+            ]
+            .spacing(8),
+        );
+    }
+    if estimate.too_large > 0 {
+        body = body.push(muted(tr_args(
+            "estimate-too-large",
+            &[
+                ("count", &estimate.too_large),
+                ("limit", &settings.index_file_size_limit_mb),
+            ],
+        )));
+    }
+    body = body.push(muted(tr_args(
+        "estimate-time",
+        &[
+            ("time", &super::format_eta(estimate.estimated_secs)),
+            ("size", &super::format_size(estimate.estimated_index_bytes)),
+        ],
+    )));
+    body = body.push(
+        row![
+            Space::new().width(Length::Fill),
+            button(text(tr("estimate-cancel")).size(13))
+                .on_press(Message::CancelScan)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            button(text(tr("estimate-confirm")).size(13))
+                .on_press(Message::ConfirmScan)
+                .padding(Padding::from([6, 14]))
+                .style(theme::primary_button()),
+        ]
+        .spacing(8),
+    );
+
+    container(body)
+        .padding(20)
+        .width(Length::Fixed(440.0))
+        .style(theme::padded_card_container)
+        .into()
+}
+
+/// Offers to index the file names on a drive that was just plugged in.
+pub fn new_volume_dialog(volume: &Volume) -> Element<'_, Message> {
+    let path = volume.mount_point.to_string_lossy();
+    let body = column![
+        section_header("database", tr("volume-title")),
+        text(tr_args(
+            "volume-found",
+            &[("label", &volume.label), ("path", &path)],
+        ))
+        .size(13),
+        text(tr("volume-note"))
+            .size(13)
+            .style(theme::muted_text_style()),
+        row![
+            Space::new().width(Length::Fill),
+            button(text(tr("volume-cancel")).size(13))
+                .on_press(Message::DismissVolume)
+                .padding(Padding::from([6, 14]))
+                .style(theme::secondary_button()),
+            button(text(tr("volume-confirm")).size(13))
+                .on_press(Message::IndexVolume)
+                .padding(Padding::from([6, 14]))
+                .style(theme::primary_button()),
+        ]
+        .spacing(8),
+    ]
+    .spacing(6);
+
+    container(body)
+        .padding(20)
+        .width(Length::Fixed(440.0))
+        .style(theme::padded_card_container)
+        .into()
+}
+
 fn search_settings_fields(app: &App) -> Element<'_, Message> {
     column![
         row![
             column![
-                text("Maximum Search Results").size(14).font(Font {
+                text(tr("max-results-title")).size(14).font(Font {
                     weight: font::Weight::Bold,
                     ..Font::default()
                 }),
-                text("Limits total search results returned for performance")
+                text(tr("max-results-hint"))
                     .size(12)
                     .style(theme::dim_text_style()),
             ]
@@ -188,14 +366,13 @@ fn search_settings_fields(app: &App) -> Element<'_, Message> {
         ]
         .spacing(12)
         .align_y(Alignment::Center),
-
         Space::new().height(Length::Fixed(16.0)),
         column![
-            text("Exclude Patterns (comma separated)").size(14).font(Font {
+            text(tr("exclude-title")).size(14).font(Font {
                 weight: font::Weight::Bold,
                 ..Font::default()
             }),
-            text("Folder and file patterns to skip during indexing (e.g. *.git, target, node_modules)")
+            text(tr("exclude-hint"))
                 .size(12)
                 .style(theme::dim_text_style()),
         ]
@@ -209,33 +386,40 @@ fn search_settings_fields(app: &App) -> Element<'_, Message> {
         .size(13)
         .on_input(Message::ExcludePatternsChanged)
         .style(theme::search_input()),
-
+        Space::new().height(Length::Fixed(16.0)),
+        sensitive_files_fields(app),
+        Space::new().height(Length::Fixed(16.0)),
+        cloud_files_fields(app),
+        Space::new().height(Length::Fixed(16.0)),
+        index_encryption_fields(app),
         Space::new().height(Length::Fixed(16.0)),
         column![
-            text("Custom File Extensions").size(14).font(Font {
+            text(tr("extensions-title")).size(14).font(Font {
                 weight: font::Weight::Bold,
                 ..Font::default()
             }),
-            text("Additional file formats to index as plain text (comma separated)")
+            text(tr("extensions-hint"))
                 .size(12)
                 .style(theme::dim_text_style()),
         ]
         .spacing(2),
         Space::new().height(Length::Fixed(6.0)),
-        TextInput::new("e.g. log, env, conf, sdp", &app.settings.custom_extensions)
-            .padding(Padding::new(12.0))
-            .size(13)
-            .on_input(Message::CustomExtensionsChanged)
-            .style(theme::search_input()),
-
+        TextInput::new(
+            &tr("extensions-placeholder"),
+            &app.settings.custom_extensions
+        )
+        .padding(Padding::new(12.0))
+        .size(13)
+        .on_input(Message::CustomExtensionsChanged)
+        .style(theme::search_input()),
         Space::new().height(Length::Fixed(16.0)),
         row![
             column![
-                text("Global Search Hotkey").size(14).font(Font {
+                text(tr("hotkey-title")).size(14).font(Font {
                     weight: font::Weight::Bold,
                     ..Font::default()
                 }),
-                text("Keyboard shortcut to summon FindAll from anywhere")
+                text(tr("hotkey-hint"))
                     .size(12)
                     .style(theme::dim_text_style()),
             ]
@@ -250,17 +434,294 @@ fn search_settings_fields(app: &App) -> Element<'_, Message> {
         ]
         .spacing(12)
         .align_y(Alignment::Center),
+        Space::new().height(Length::Fixed(16.0)),
+        row![
+            column![
+                text(tr("quick-hotkey-title")).size(14).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }),
+                text(tr("quick-hotkey-hint"))
+                    .size(12)
+                    .style(theme::dim_text_style()),
+            ]
+            .spacing(2)
+            .width(Length::Fill),
+            TextInput::new("Ctrl+Shift+F", &app.settings.quick_search_hotkey)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .width(Length::Fixed(200.0))
+                .on_input(Message::QuickSearchHotkeyChanged)
+                .style(theme::search_input())
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center),
+    ]
+    .into()
+}
+
+fn query_macros_section(app: &App) -> Element<'_, Message> {
+    let mut macros_col = column![
+        text(tr("macros-hint"))
+            .size(12)
+            .style(theme::dim_text_style())
+    ]
+    .spacing(10);
+
+    for (i, m) in app.settings.query_macros.iter().enumerate() {
+        macros_col = macros_col.push(
+            container(
+                row![
+                    text(format!("@{}", m.name)).size(13).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                    text(&m.expansion)
+                        .size(13)
+                        .font(Font::MONOSPACE)
+                        .width(Length::Fill),
+                    button(load_icon_size("trash", 15.0))
+                        .on_press(Message::RemoveQueryMacro(i))
+                        .padding(Padding::new(6.0))
+                        .style(theme::ghost_button())
+                ]
+                .spacing(12)
+                .align_y(Alignment::Center),
+            )
+            .style(theme::badge_container)
+            .padding(Padding::new(10.0))
+            .width(Length::Fill),
+        );
+    }
+
+    column![
+        macros_col,
+        Space::new().height(Length::Fixed(8.0)),
+        row![
+            TextInput::new("invoices", &app.new_macro_name)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .width(Length::Fixed(160.0))
+                .on_input(Message::MacroNameChanged)
+                .style(theme::search_input()),
+            TextInput::new(
+                "ext:pdf path:Accounting (invoice OR rechnung)",
+                &app.new_macro_expansion
+            )
+            .padding(Padding::new(10.0))
+            .size(13)
+            .on_input(Message::MacroExpansionChanged)
+            .on_submit(Message::AddQueryMacro)
+            .style(theme::search_input()),
+            button(
+                row![
+                    load_icon_size("plus", 14.0),
+                    text(tr("macros-add")).size(13)
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center)
+            )
+            .on_press(Message::AddQueryMacro)
+            .padding(Padding::from([8, 16]))
+            .style(theme::secondary_button())
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn search_history_section(app: &App) -> Element<'_, Message> {
+    let history = &app.search_history;
+    let mut col = column![
+        checkbox(app.settings.search_history_enabled)
+            .label(tr("history-enabled"))
+            .on_toggle(Message::ToggleSearchHistoryEnabled)
+            .size(18)
+            .text_size(13),
+    ]
+    .spacing(6);
+
+    if history.is_empty() {
+        col = col.push(
+            text(tr("history-empty"))
+                .size(12)
+                .style(theme::dim_text_style()),
+        );
+    } else {
+        for item in history {
+            col = col.push(super::search::history_entry_row(item));
+        }
+        col = col.push(
+            button(text(tr("history-clear")).size(12))
+                .on_press(Message::ClearSearchHistory)
+                .padding(Padding::from([6, 12]))
+                .style(theme::secondary_button()),
+        );
+    }
+    col.into()
+}
+
+fn profile_section(app: &App) -> Element<'_, Message> {
+    column![
+        text(tr("profile-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        row![
+            text(tr("profile-active")).size(13).width(Length::Fill),
+            pick_list(
+                app.profiles.as_slice(),
+                Some(crate::system::profile::active().to_string()),
+                Message::SwitchProfile,
+            )
+            .text_size(13)
+            .padding(Padding::from([6, 12])),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center),
+        row![
+            TextInput::new(&tr("profile-new-placeholder"), &app.new_profile_name)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .on_input(Message::NewProfileNameChanged)
+                .on_submit(Message::CreateProfile)
+                .style(theme::search_input()),
+            button(
+                row![
+                    load_icon_size("plus", 14.0),
+                    text(tr("profile-create")).size(13)
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center)
+            )
+            .on_press(Message::CreateProfile)
+            .padding(Padding::from([8, 16]))
+            .style(theme::secondary_button()),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// Sensitive file handling and the patterns it applies to.
+fn sensitive_files_fields(app: &App) -> Element<'_, Message> {
+    let choice = |label: String, mode: SensitiveFiles| {
+        let active = app.settings.sensitive_files == mode;
+        button(text(label).size(12))
+            .on_press(Message::SensitiveFilesChanged(mode))
+            .padding(Padding::from([6, 12]))
+            .style(move |t: &iced::Theme, s| {
+                if active {
+                    theme::primary_button()(t, s)
+                } else {
+                    theme::secondary_button()(t, s)
+                }
+            })
+    };
+
+    column![
+        column![
+            text(tr("sensitive-title")).size(14).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+            text(tr("sensitive-hint"))
+                .size(12)
+                .style(theme::dim_text_style()),
+        ]
+        .spacing(2),
+        row![
+            choice(tr("sensitive-names-only"), SensitiveFiles::FilenameOnly),
+            choice(tr("sensitive-skip"), SensitiveFiles::Skip),
+            choice(tr("sensitive-index"), SensitiveFiles::Index),
+        ]
+        .spacing(8),
+        TextInput::new(
+            "*.key, .env, *password*",
+            &app.settings.sensitive_patterns.join(", ")
+        )
+        .padding(Padding::new(12.0))
+        .size(13)
+        .on_input(Message::SensitivePatternsChanged)
+        .style(theme::search_input()),
+    ]
+    .spacing(6)
+    .into()
+}
+
+/// Cloud folders whose online-only files may be downloaded for indexing.
+fn cloud_files_fields(app: &App) -> Element<'_, Message> {
+    column![
+        column![
+            text(tr("cloud-title")).size(14).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+            text(tr("cloud-hint"))
+                .size(12)
+                .style(theme::dim_text_style()),
+        ]
+        .spacing(2),
+        TextInput::new(
+            &tr("cloud-placeholder"),
+            &app.settings.cloud_content_folders.join(", ")
+        )
+        .padding(Padding::new(12.0))
+        .size(13)
+        .on_input(Message::CloudContentFoldersChanged)
+        .style(theme::search_input()),
     ]
+    .spacing(6)
+    .into()
+}
+
+fn index_encryption_fields(app: &App) -> Element<'_, Message> {
+    let choice = |label: String, mode: IndexEncryption| {
+        let active = app.settings.index_encryption == mode;
+        button(text(label).size(12))
+            .on_press(Message::IndexEncryptionChanged(mode))
+            .padding(Padding::from([6, 12]))
+            .style(move |t: &iced::Theme, s| {
+                if active {
+                    theme::primary_button()(t, s)
+                } else {
+                    theme::secondary_button()(t, s)
+                }
+            })
+    };
+
+    column![
+        column![
+            text(tr("encryption-title")).size(14).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+            text(tr("encryption-hint"))
+                .size(12)
+                .style(theme::dim_text_style()),
+        ]
+        .spacing(2),
+        row![
+            choice(tr("encryption-off"), IndexEncryption::Off),
+            choice(tr("encryption-keychain"), IndexEncryption::Keychain),
+            choice(tr("encryption-passphrase"), IndexEncryption::Passphrase),
+        ]
+        .spacing(8),
+    ]
+    .spacing(6)
     .into()
 }
 
 fn index_directories_section(app: &App) -> Element<'_, Message> {
     let mut dirs_col = column![].spacing(10);
 
-    if app.settings.index_dirs.is_empty() {
+    if app.settings.index_roots.is_empty() {
         dirs_col = dirs_col.push(
             container(
-                text("No directories configured for indexing.")
+                text(tr("dirs-empty"))
                     .size(13)
                     .style(theme::dim_text_style()),
             )
@@ -269,23 +730,117 @@ fn index_directories_section(app: &App) -> Element<'_, Message> {
             .width(Length::Fill),
         );
     } else {
-        for (i, dir) in app.settings.index_dirs.iter().enumerate() {
+        for (i, root) in app.settings.index_roots.iter().enumerate() {
+            let depth = root.max_depth.map(|d| d.to_string()).unwrap_or_default();
+            let mut card = column![
+                row![
+                    load_icon_size("folder-open", 16.0),
+                    text(&root.path).size(13).width(Length::Fill),
+                    button(load_icon_size("trash", 15.0))
+                        .on_press(Message::RemoveFolder(i))
+                        .padding(Padding::new(6.0))
+                        .style(theme::ghost_button())
+                ]
+                .spacing(12)
+                .align_y(Alignment::Center),
+                row![
+                    checkbox(root.index_content)
+                        .label(tr("root-content"))
+                        .on_toggle(move |on| Message::EditIndexRoot(i, RootEdit::IndexContent(on)))
+                        .size(16)
+                        .text_size(12),
+                    checkbox(root.follow_symlinks)
+                        .label(tr("root-symlinks"))
+                        .on_toggle(move |on| Message::EditIndexRoot(
+                            i,
+                            RootEdit::FollowSymlinks(on)
+                        ))
+                        .size(16)
+                        .text_size(12),
+                    checkbox(root.include_hidden)
+                        .label(tr("root-hidden"))
+                        .on_toggle(move |on| Message::EditIndexRoot(i, RootEdit::IncludeHidden(on)))
+                        .size(16)
+                        .text_size(12),
+                    checkbox(root.include_system)
+                        .label(tr("root-system"))
+                        .on_toggle(move |on| Message::EditIndexRoot(i, RootEdit::IncludeSystem(on)))
+                        .size(16)
+                        .text_size(12),
+                    checkbox(root.separate_index)
+                        .label(tr("root-separate"))
+                        .on_toggle(move |on| Message::EditIndexRoot(i, RootEdit::SeparateIndex(on)))
+                        .size(16)
+                        .text_size(12),
+                    Space::new().width(Length::Fill),
+                    text(tr("root-depth"))
+                        .size(12)
+                        .style(theme::dim_text_style()),
+                    TextInput::new("20", &depth)
+                        .padding(Padding::new(6.0))
+                        .size(12)
+                        .width(Length::Fixed(60.0))
+                        .on_input(move |s| Message::EditIndexRoot(i, RootEdit::MaxDepth(s)))
+                        .style(theme::search_input()),
+                ]
+                .spacing(16)
+                .align_y(Alignment::Center),
+                row![
+                    TextInput::new(&tr("root-include"), &root.include.join(", "))
+                        .padding(Padding::new(6.0))
+                        .size(12)
+                        .on_input(move |s| Message::EditIndexRoot(i, RootEdit::Include(s)))
+                        .style(theme::search_input()),
+                    TextInput::new(&tr("root-exclude"), &root.exclude.join(", "))
+                        .padding(Padding::new(6.0))
+                        .size(12)
+                        .on_input(move |s| Message::EditIndexRoot(i, RootEdit::Exclude(s)))
+                        .style(theme::search_input()),
+                ]
+                .spacing(10),
+                row![
+                    text(tr("root-rescan"))
+                        .size(12)
+                        .style(theme::dim_text_style()),
+                    pick_list(
+                        rescan_choices(root.rescan),
+                        Some(RootRescan(root.rescan)),
+                        move |choice: RootRescan| Message::EditIndexRoot(
+                            i,
+                            RootEdit::Rescan(choice.0)
+                        ),
+                    )
+                    .text_size(12)
+                    .padding(Padding::from([4, 10])),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(8);
+            if root.volume_id.is_some() {
+                card = card.push(
+                    text(tr("root-removable"))
+                        .size(12)
+                        .style(theme::dim_text_style()),
+                );
+            }
+            // Backup rights only exist on Windows
+            if cfg!(windows) {
+                card = card.push(
+                    checkbox(root.elevated_access)
+                        .label(tr("root-elevated"))
+                        .on_toggle(move |on| {
+                            Message::EditIndexRoot(i, RootEdit::ElevatedAccess(on))
+                        })
+                        .size(16)
+                        .text_size(12),
+                );
+            }
             dirs_col = dirs_col.push(
-                container(
-                    row![
-                        load_icon_size("folder-open", 16.0),
-                        text(dir).size(13).width(Length::Fill),
-                        button(load_icon_size("trash", 15.0))
-                            .on_press(Message::RemoveFolder(i))
-                            .padding(Padding::new(6.0))
-                            .style(theme::ghost_button())
-                    ]
-                    .spacing(12)
-                    .align_y(Alignment::Center),
-                )
-                .style(theme::badge_container)
-                .padding(Padding::new(10.0))
-                .width(Length::Fill),
+                container(card)
+                    .style(theme::badge_container)
+                    .padding(Padding::new(10.0))
+                    .width(Length::Fill),
             );
         }
     }
@@ -294,16 +849,43 @@ fn index_directories_section(app: &App) -> Element<'_, Message> {
         dirs_col,
         Space::new().height(Length::Fixed(8.0)),
         button(
-            row![
-                load_icon_size("plus", 14.0),
-                text("Add Directory to Index").size(13)
-            ]
-            .spacing(8)
-            .align_y(Alignment::Center)
+            row![load_icon_size("plus", 14.0), text(tr("dirs-add")).size(13)]
+                .spacing(8)
+                .align_y(Alignment::Center)
         )
         .on_press(Message::AddFolder)
         .padding(Padding::from([8, 16]))
-        .style(theme::secondary_button())
+        .style(theme::secondary_button()),
+        row![
+            TextInput::new(&tr("remote-placeholder"), &app.remote_root_input)
+                .padding(Padding::new(8.0))
+                .size(13)
+                .on_input(Message::RemoteRootChanged)
+                .on_submit(Message::AddRemoteRoot)
+                .style(theme::search_input()),
+            TextInput::new(&tr("remote-password"), &app.remote_password_input)
+                .secure(true)
+                .padding(Padding::new(8.0))
+                .size(13)
+                .width(Length::Fixed(180.0))
+                .on_input(Message::RemotePasswordChanged)
+                .on_submit(Message::AddRemoteRoot)
+                .style(theme::search_input()),
+            button(text(tr("remote-add")).size(13))
+                .on_press(Message::AddRemoteRoot)
+                .padding(Padding::from([8, 16]))
+                .style(theme::secondary_button()),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        text(tr("remote-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        checkbox(app.settings.offer_removable_indexing)
+            .label(tr("volume-offer"))
+            .on_toggle(Message::ToggleOfferRemovableIndexing)
+            .size(18)
+            .text_size(13),
     ]
     .spacing(8)
     .into()
@@ -312,46 +894,239 @@ fn index_directories_section(app: &App) -> Element<'_, Message> {
 fn system_integration_section(app: &App) -> Element<'_, Message> {
     column![
         checkbox(app.settings.minimize_to_tray)
-            .label("Minimize to system tray on window close")
+            .label(tr("system-tray"))
             .on_toggle(Message::ToggleMinimizeToTray)
             .size(18)
             .text_size(13),
         checkbox(app.settings.auto_start_on_boot)
-            .label("Start FindAll automatically when system starts")
+            .label(tr("system-autostart"))
             .on_toggle(Message::ToggleAutoStart)
             .size(18)
             .text_size(13),
         checkbox(app.settings.context_menu_enabled)
-            .label("Add 'Search with FindAll' to Windows right-click context menu")
+            .label(tr("system-context-menu"))
             .on_toggle(Message::ToggleContextMenu)
             .size(18)
             .text_size(13),
         checkbox(app.settings.use_gitignore)
-            .label("Respect .gitignore rules when scanning repository folders")
+            .label(tr("system-gitignore"))
             .on_toggle(Message::ToggleGitignore)
             .size(18)
             .text_size(13),
+        checkbox(app.settings.use_mft_enumeration)
+            .label(tr("system-mft"))
+            .on_toggle(Message::ToggleMftEnumeration)
+            .size(18)
+            .text_size(13),
+        checkbox(app.settings.code_aware_indexing)
+            .label(tr("system-code-aware"))
+            .on_toggle(Message::ToggleCodeAwareIndexing)
+            .size(18)
+            .text_size(13),
     ]
     .spacing(14)
     .into()
 }
 
+fn opening_files_section(app: &App) -> Element<'_, Message> {
+    let mut openers = column![
+        text(tr("openers-hint"))
+            .size(12)
+            .style(theme::dim_text_style())
+    ]
+    .spacing(10);
+    for (i, opener) in app.settings.file_openers.iter().enumerate() {
+        openers = openers.push(
+            container(
+                row![
+                    text(format!(".{}", opener.extension.trim_start_matches('.')))
+                        .size(13)
+                        .font(Font {
+                            weight: font::Weight::Bold,
+                            ..Font::default()
+                        }),
+                    text(&opener.command)
+                        .size(13)
+                        .font(Font::MONOSPACE)
+                        .width(Length::Fill),
+                    button(load_icon_size("trash", 15.0))
+                        .on_press(Message::RemoveFileOpener(i))
+                        .padding(Padding::new(6.0))
+                        .style(theme::ghost_button())
+                ]
+                .spacing(12)
+                .align_y(Alignment::Center),
+            )
+            .style(theme::badge_container)
+            .padding(Padding::new(10.0))
+            .width(Length::Fill),
+        );
+    }
+
+    column![
+        open_at_match_field(
+            tr("opening-editor-title"),
+            tr("opening-editor-hint"),
+            "code -g {file}:{line}",
+            &app.settings.editor_command,
+            Message::EditorCommandChanged,
+        ),
+        open_at_match_field(
+            tr("opening-pdf-title"),
+            tr("opening-pdf-hint"),
+            "SumatraPDF -page {page} {file}",
+            &app.settings.pdf_viewer_command,
+            Message::PdfViewerCommandChanged,
+        ),
+        openers,
+        row![
+            TextInput::new("md", &app.new_opener_extension)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .width(Length::Fixed(100.0))
+                .on_input(Message::OpenerExtensionChanged)
+                .style(theme::search_input()),
+            TextInput::new("obsidian://open?path={}", &app.new_opener_command)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .on_input(Message::OpenerCommandChanged)
+                .on_submit(Message::AddFileOpener)
+                .style(theme::search_input()),
+            button(
+                row![
+                    load_icon_size("plus", 14.0),
+                    text(tr("openers-add")).size(13)
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center)
+            )
+            .on_press(Message::AddFileOpener)
+            .padding(Padding::from([8, 16]))
+            .style(theme::secondary_button())
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(14)
+    .into()
+}
+
+/// A command line that opens results where they matched. Left empty, files
+/// open with their default application.
+fn open_at_match_field<'a>(
+    title: String,
+    hint: String,
+    placeholder: &'a str,
+    value: &'a str,
+    on_input: fn(String) -> Message,
+) -> Element<'a, Message> {
+    row![
+        column![
+            text(title).size(14).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+            text(hint).size(12).style(theme::dim_text_style()),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        TextInput::new(placeholder, value)
+            .padding(Padding::new(10.0))
+            .size(13)
+            .width(Length::Fixed(260.0))
+            .on_input(on_input)
+            .style(theme::search_input())
+    ]
+    .spacing(12)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn shortcuts_section(app: &App) -> Element<'_, Message> {
+    let mut rows = column![
+        text(tr("shortcuts-hint"))
+            .size(12)
+            .style(theme::dim_text_style())
+    ]
+    .spacing(10);
+
+    for action in ShortcutAction::ALL {
+        let binding = action.binding(&app.settings.shortcuts);
+        let status = if binding.is_empty() || KeyCombo::parse(binding).is_some() {
+            text("")
+        } else {
+            text(tr("shortcuts-invalid"))
+                .size(12)
+                .style(theme::error_text_style())
+        };
+        rows = rows.push(
+            row![
+                text(action.label()).size(13).width(Length::Fill),
+                status,
+                TextInput::new(&tr("shortcuts-off"), binding)
+                    .padding(Padding::new(10.0))
+                    .size(13)
+                    .width(Length::Fixed(200.0))
+                    .on_input(move |combo| Message::ShortcutChanged(action, combo))
+                    .style(theme::search_input()),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+        );
+    }
+    rows.into()
+}
+
+fn language_section(app: &App) -> Element<'_, Message> {
+    let choice = |label: String, code: &str| {
+        let active = app.settings.language == code;
+        button(text(label).size(12))
+            .on_press(Message::LanguageChanged(code.to_string()))
+            .padding(Padding::from([6, 12]))
+            .style(move |t: &iced::Theme, s| {
+                if active {
+                    theme::primary_button()(t, s)
+                } else {
+                    theme::secondary_button()(t, s)
+                }
+            })
+    };
+    let detected = crate::i18n::detect().map_or("English", |l| l.name);
+    let mut choices = row![choice(
+        tr_args("language-auto", &[("language", &detected)]),
+        crate::i18n::AUTO
+    )]
+    .spacing(8);
+    for language in crate::i18n::LANGUAGES {
+        choices = choices.push(choice(language.name.to_string(), language.code));
+    }
+
+    column![
+        text(tr("language-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        choices,
+    ]
+    .spacing(10)
+    .into()
+}
+
 fn appearance_section(app: &App) -> Element<'_, Message> {
     column![
         row![
             column![
-                text("Color Theme").size(14).font(Font {
+                text(tr("appearance-theme-title")).size(14).font(Font {
                     weight: font::Weight::Bold,
                     ..Font::default()
                 }),
-                text("Switch between Windows 11 Dark mode and Light mode")
+                text(tr("appearance-theme-hint"))
                     .size(12)
                     .style(theme::dim_text_style()),
             ]
             .spacing(2)
             .width(Length::Fill),
             checkbox(app.is_dark)
-                .label("Dark Theme")
+                .label(tr("appearance-dark"))
                 .on_toggle(|_| Message::ToggleTheme)
                 .size(20)
                 .text_size(13),
@@ -362,19 +1137,230 @@ fn appearance_section(app: &App) -> Element<'_, Message> {
     .into()
 }
 
-fn data_management_section(_app: &App) -> Element<'_, Message> {
+/// Maximum number of problem files rendered at once
+const MAX_PROBLEMS_SHOWN: usize = 200;
+
+fn problems_section(app: &App) -> Element<'_, Message> {
+    if app.parse_errors.is_empty() {
+        return container(
+            text(tr("problems-none"))
+                .size(13)
+                .style(theme::dim_text_style()),
+        )
+        .padding(16.0)
+        .style(theme::hit_highlight_container)
+        .width(Length::Fill)
+        .into();
+    }
+
+    let mut list = column![].spacing(8);
+    for record in app.parse_errors.iter().take(MAX_PROBLEMS_SHOWN) {
+        let kind = match record.kind {
+            ParseErrorKind::Failed => tr("problems-failed"),
+            ParseErrorKind::Timeout => tr("problems-timeout"),
+            ParseErrorKind::Panic => tr("problems-crashed"),
+        };
+        list = list.push(
+            container(
+                column![
+                    row![
+                        text(kind).size(12).font(Font {
+                            weight: font::Weight::Bold,
+                            ..Font::default()
+                        }),
+                        text(&record.path)
+                            .size(12)
+                            .font(Font::MONOSPACE)
+                            .width(Length::Fill),
+                        text(super::format_date(record.occurred_at))
+                            .size(11)
+                            .style(theme::dim_text_style()),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                    text(&record.message)
+                        .size(11)
+                        .style(theme::dim_text_style()),
+                ]
+                .spacing(4),
+            )
+            .style(theme::badge_container)
+            .padding(Padding::new(10.0))
+            .width(Length::Fill),
+        );
+    }
+
     column![
-        text("Force Complete Index Rebuild")
-            .size(14)
-            .font(Font { weight: font::Weight::Bold, ..Font::default() }),
-        text("Clears all cached search index data and performs a fresh scan across all configured directories.")
+        text(tr_args(
+            "problems-summary",
+            &[("count", &app.parse_errors.len())]
+        ))
+        .size(12)
+        .style(theme::dim_text_style()),
+        list,
+        button(
+            row![
+                load_icon_size("refresh", 14.0),
+                text(tr("problems-retry")).size(13)
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        )
+        .on_press(Message::ClearParseErrors)
+        .padding(Padding::from([8, 16]))
+        .style(theme::secondary_button())
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// Maximum number of activity log entries rendered at once
+pub const MAX_ACTIVITY_SHOWN: usize = 200;
+
+fn activity_section(app: &App) -> Element<'_, Message> {
+    let mut col = column![
+        checkbox(app.settings.activity_log)
+            .label(tr("activity-enabled"))
+            .on_toggle(Message::ToggleActivityLog)
+            .size(18)
+            .text_size(13),
+        text(tr("activity-note"))
             .size(12)
             .style(theme::dim_text_style()),
-        Space::new().height(Length::Fixed(10.0)),
-        button(
-            row![load_icon_size("database", 14.0), text("Rebuild Search Index").size(13)]
+        row![
+            TextInput::new(&tr("activity-filter"), &app.activity_filter)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .on_input(Message::ActivityFilterChanged)
+                .style(theme::search_input()),
+            button(
+                row![
+                    load_icon_size("refresh", 14.0),
+                    text(tr("refresh-button")).size(13)
+                ]
                 .spacing(8)
                 .align_y(Alignment::Center)
+            )
+            .on_press(Message::RefreshActivity)
+            .padding(Padding::from([8, 16]))
+            .style(theme::secondary_button()),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(8);
+
+    if app.activity.is_empty() {
+        return col
+            .push(
+                text(tr("activity-empty"))
+                    .size(12)
+                    .style(theme::dim_text_style()),
+            )
+            .into();
+    }
+
+    let mut list = column![].spacing(4);
+    for entry in &app.activity {
+        list = list.push(
+            row![
+                text(super::format_date(entry.at))
+                    .size(11)
+                    .style(theme::dim_text_style())
+                    .width(Length::Fixed(110.0)),
+                text(entry.activity.name())
+                    .size(12)
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    })
+                    .width(Length::Fixed(100.0)),
+                text(entry.activity.subject())
+                    .size(12)
+                    .font(Font::MONOSPACE)
+                    .width(Length::Fill),
+                text(entry.activity.detail())
+                    .size(11)
+                    .style(theme::dim_text_style()),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        );
+    }
+    col.push(
+        container(list)
+            .style(theme::badge_container)
+            .padding(Padding::new(10.0))
+            .width(Length::Fill),
+    )
+    .push(
+        button(text(tr("activity-clear")).size(12))
+            .on_press(Message::ClearActivity)
+            .padding(Padding::from([6, 12]))
+            .style(theme::secondary_button()),
+    )
+    .into()
+}
+
+fn data_management_section(app: &App) -> Element<'_, Message> {
+    column![
+        text(tr("schedule-title")).size(14).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+        text(tr("schedule-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        Space::new().height(Length::Fixed(6.0)),
+        row![
+            TextInput::new("off", &app.schedule_input)
+                .padding(Padding::new(10.0))
+                .size(13)
+                .width(Length::Fixed(200.0))
+                .on_input(Message::ScheduleChanged)
+                .style(theme::search_input()),
+            text(schedule_summary(app))
+                .size(12)
+                .style(theme::dim_text_style()),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center),
+        Space::new().height(Length::Fixed(16.0)),
+        text(tr("refresh-title")).size(14).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+        text(tr("refresh-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        Space::new().height(Length::Fixed(10.0)),
+        button(
+            row![
+                load_icon_size("refresh", 14.0),
+                text(tr("refresh-button")).size(13)
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        )
+        .on_press(Message::RefreshIndex)
+        .padding(Padding::from([8, 18]))
+        .style(theme::secondary_button()),
+        Space::new().height(Length::Fixed(16.0)),
+        text(tr("rebuild-title")).size(14).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+        text(tr("rebuild-hint"))
+            .size(12)
+            .style(theme::dim_text_style()),
+        Space::new().height(Length::Fixed(10.0)),
+        button(
+            row![
+                load_icon_size("database", 14.0),
+                text(tr("rebuild-button")).size(13)
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
         )
         .on_press(Message::RebuildIndex)
         .padding(Padding::from([8, 18]))
@@ -384,16 +1370,65 @@ fn data_management_section(_app: &App) -> Element<'_, Message> {
     .into()
 }
 
+/// An index root's own rescan schedule as listed in its picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RootRescan(Option<ReindexSchedule>);
+
+impl std::fmt::Display for RootRescan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            None => f.write_str(&tr("root-rescan-global")),
+            Some(schedule) => write!(f, "{schedule}"),
+        }
+    }
+}
+
+/// Preset rescan schedules, plus `current` when it was set outside the app.
+fn rescan_choices(current: Option<ReindexSchedule>) -> Vec<RootRescan> {
+    let mut choices = vec![
+        RootRescan(None),
+        RootRescan(Some(ReindexSchedule::Interval { hours: 1 })),
+        RootRescan(Some(ReindexSchedule::Interval { hours: 6 })),
+        RootRescan(Some(ReindexSchedule::Daily { hour: 2, minute: 0 })),
+        RootRescan(Some(ReindexSchedule::Off)),
+    ];
+    if !choices.contains(&RootRescan(current)) {
+        choices.push(RootRescan(current));
+    }
+    choices
+}
+
+/// Last and next scheduled run, or why the typed schedule isn't applied.
+fn schedule_summary(app: &App) -> String {
+    if app
+        .schedule_input
+        .parse::<crate::settings::ReindexSchedule>()
+        .is_err()
+    {
+        return tr("schedule-invalid");
+    }
+    let last = app
+        .schedule_last_run
+        .map_or_else(|| tr("schedule-never"), super::format_date);
+    let next = crate::system::scheduler::next_run(
+        app.settings.reindex_schedule,
+        app.schedule_last_run,
+        &jiff::Zoned::now(),
+    )
+    .map_or_else(|| tr("schedule-none"), super::format_date);
+    tr_args("schedule-summary", &[("last", &last), ("next", &next)])
+}
+
 fn privacy_security_section() -> Element<'static, Message> {
     let app_dir_str = crate::get_app_data_dir().map_or_else(
-        |_| "Unknown".to_string(),
+        |_| tr("privacy-unknown"),
         |p| p.to_string_lossy().to_string(),
     );
 
     column![
         row![
             load_icon_size("check", 16.0),
-            text("FindAll operates 100% locally. Zero data or search queries ever leave your machine.")
+            text(tr("privacy-local"))
                 .size(13)
                 .style(theme::muted_text_style()),
         ]
@@ -403,18 +1438,35 @@ fn privacy_security_section() -> Element<'static, Message> {
         container(
             column![
                 row![
-                    text("Local App Data Path: ").size(12).font(Font { weight: font::Weight::Bold, ..Font::default() }),
+                    text(tr("privacy-data-path")).size(12).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
                     text(app_dir_str.clone()).size(12).font(Font::MONOSPACE),
-                ].spacing(8),
+                ]
+                .spacing(8),
                 row![
-                    text("Tantivy Search Index: ").size(12).font(Font { weight: font::Weight::Bold, ..Font::default() }),
-                    text(format!("{app_dir_str}/index")).size(12).font(Font::MONOSPACE),
-                ].spacing(8),
+                    text(tr("privacy-index-path")).size(12).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                    text(format!("{app_dir_str}/index"))
+                        .size(12)
+                        .font(Font::MONOSPACE),
+                ]
+                .spacing(8),
                 row![
-                    text("Redb KV Store: ").size(12).font(Font { weight: font::Weight::Bold, ..Font::default() }),
-                    text(format!("{app_dir_str}/metadata.redb")).size(12).font(Font::MONOSPACE),
-                ].spacing(8),
-            ].spacing(6)
+                    text(tr("privacy-db-path")).size(12).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+                    text(format!("{app_dir_str}/metadata.redb"))
+                        .size(12)
+                        .font(Font::MONOSPACE),
+                ]
+                .spacing(8),
+            ]
+            .spacing(6)
         )
         .padding(14)
         .style(theme::badge_container)
@@ -0,0 +1,200 @@
+use super::Message;
+use crate::settings::Shortcuts;
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+/// An action that can be bound in [`Shortcuts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    Open,
+    Reveal,
+    CopyPath,
+    Clear,
+    NextPane,
+    PreviousPane,
+}
+
+impl ShortcutAction {
+    pub const ALL: [Self; 6] = [
+        Self::Open,
+        Self::Reveal,
+        Self::CopyPath,
+        Self::Clear,
+        Self::NextPane,
+        Self::PreviousPane,
+    ];
+
+    pub fn label(self) -> String {
+        crate::i18n::tr(match self {
+            Self::Open => "action-open",
+            Self::Reveal => "action-reveal",
+            Self::CopyPath => "action-copy-path",
+            Self::Clear => "action-clear",
+            Self::NextPane => "action-next-pane",
+            Self::PreviousPane => "action-previous-pane",
+        })
+    }
+
+    pub fn binding(self, shortcuts: &Shortcuts) -> &str {
+        match self {
+            Self::Open => &shortcuts.open,
+            Self::Reveal => &shortcuts.reveal,
+            Self::CopyPath => &shortcuts.copy_path,
+            Self::Clear => &shortcuts.clear,
+            Self::NextPane => &shortcuts.next_pane,
+            Self::PreviousPane => &shortcuts.previous_pane,
+        }
+    }
+
+    pub const fn binding_mut(self, shortcuts: &mut Shortcuts) -> &mut String {
+        match self {
+            Self::Open => &mut shortcuts.open,
+            Self::Reveal => &mut shortcuts.reveal,
+            Self::CopyPath => &mut shortcuts.copy_path,
+            Self::Clear => &mut shortcuts.clear,
+            Self::NextPane => &mut shortcuts.next_pane,
+            Self::PreviousPane => &mut shortcuts.previous_pane,
+        }
+    }
+
+    pub const fn message(self) -> Message {
+        match self {
+            Self::Open => Message::OpenSelectedResult,
+            Self::Reveal => Message::ShowSelectedInFolder,
+            Self::CopyPath => Message::CopySelectedPath,
+            Self::Clear => Message::EscapePressed,
+            Self::NextPane => Message::FocusNextRegion,
+            Self::PreviousPane => Message::FocusPreviousRegion,
+        }
+    }
+}
+
+/// A parsed combination such as `Ctrl+Shift+C`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    modifiers: Modifiers,
+    key: Key,
+}
+
+impl KeyCombo {
+    /// Parses a combination. Modifier and key names are case-insensitive.
+    pub fn parse(combo: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::empty();
+        let mut key = None;
+        for part in combo.split('+').map(str::trim) {
+            let lower = part.to_ascii_lowercase();
+            match lower.as_str() {
+                "ctrl" | "control" => modifiers |= Modifiers::CTRL,
+                "shift" => modifiers |= Modifiers::SHIFT,
+                "alt" | "option" => modifiers |= Modifiers::ALT,
+                "cmd" | "command" | "super" | "win" | "meta" => modifiers |= Modifiers::LOGO,
+                _ if key.is_none() => key = Some(parse_key(&lower)?),
+                _ => return None,
+            }
+        }
+        Some(Self {
+            modifiers,
+            key: key?,
+        })
+    }
+
+    fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let same_key = match (&self.key, key) {
+            (Key::Character(bound), Key::Character(pressed)) => bound.eq_ignore_ascii_case(pressed),
+            (bound, pressed) => bound == pressed,
+        };
+        same_key && self.modifiers == modifiers
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let named = match name {
+        "enter" | "return" => Named::Enter,
+        "escape" | "esc" => Named::Escape,
+        "tab" => Named::Tab,
+        "space" => Named::Space,
+        "backspace" => Named::Backspace,
+        "delete" | "del" => Named::Delete,
+        "insert" => Named::Insert,
+        "home" => Named::Home,
+        "end" => Named::End,
+        "pageup" => Named::PageUp,
+        "pagedown" => Named::PageDown,
+        "up" | "arrowup" => Named::ArrowUp,
+        "down" | "arrowdown" => Named::ArrowDown,
+        "left" | "arrowleft" => Named::ArrowLeft,
+        "right" | "arrowright" => Named::ArrowRight,
+        "f1" => Named::F1,
+        "f2" => Named::F2,
+        "f3" => Named::F3,
+        "f4" => Named::F4,
+        "f5" => Named::F5,
+        "f6" => Named::F6,
+        "f7" => Named::F7,
+        "f8" => Named::F8,
+        "f9" => Named::F9,
+        "f10" => Named::F10,
+        "f11" => Named::F11,
+        "f12" => Named::F12,
+        _ if name.chars().count() == 1 => return Some(Key::Character(name.into())),
+        _ => return None,
+    };
+    Some(Key::Named(named))
+}
+
+/// The bound action a key press triggers, if any. Unparseable bindings never
+/// match.
+pub fn action_for(
+    shortcuts: &Shortcuts,
+    key: &Key,
+    modifiers: Modifiers,
+) -> Option<ShortcutAction> {
+    ShortcutAction::ALL.into_iter().find(|action| {
+        KeyCombo::parse(action.binding(shortcuts))
+            .is_some_and(|combo| combo.matches(key, modifiers))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_bindings_resolve() {
+        let shortcuts = Shortcuts::default();
+        let enter = Key::Named(Named::Enter);
+        assert_eq!(
+            action_for(&shortcuts, &enter, Modifiers::empty()),
+            Some(ShortcutAction::Open)
+        );
+        assert_eq!(
+            action_for(&shortcuts, &enter, Modifiers::CTRL),
+            Some(ShortcutAction::Reveal)
+        );
+        assert_eq!(
+            action_for(&shortcuts, &Key::Character("C".into()), Modifiers::CTRL),
+            Some(ShortcutAction::CopyPath)
+        );
+        assert_eq!(
+            action_for(&shortcuts, &Key::Named(Named::Tab), Modifiers::SHIFT),
+            Some(ShortcutAction::PreviousPane)
+        );
+        assert_eq!(
+            action_for(&shortcuts, &Key::Character("c".into()), Modifiers::empty()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_combos_parse() {
+        let combo = KeyCombo::parse("ctrl + Shift+K").unwrap();
+        assert!(combo.matches(
+            &Key::Character("k".into()),
+            Modifiers::CTRL | Modifiers::SHIFT
+        ));
+        assert!(!combo.matches(&Key::Character("k".into()), Modifiers::CTRL));
+        assert!(KeyCombo::parse("").is_none());
+        assert!(KeyCombo::parse("Ctrl").is_none());
+        assert!(KeyCombo::parse("Ctrl+A+B").is_none());
+        assert!(KeyCombo::parse("Hyper+A").is_none());
+    }
+}
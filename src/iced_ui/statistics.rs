@@ -0,0 +1,215 @@
+use super::{App, Message, Tab, format_size, settings::settings_tabs, theme};
+use crate::i18n::{tr, tr_args};
+use crate::iced_ui::icons::load_icon_size;
+use crate::models::{DetailedStatistics, UsageGroup};
+use iced::widget::{Scrollable, Space, button, column, container, progress_bar, row, text};
+use iced::{Alignment, Element, Font, Length, Padding, font};
+
+/// Extensions listed before the rest are left out
+const TOP_EXTENSIONS: usize = 15;
+
+pub fn statistics_view(app: &App) -> Element<'_, Message> {
+    let body: Element<'_, Message> = match &app.statistics {
+        None => text(tr("stats-loading"))
+            .size(13)
+            .style(theme::dim_text_style())
+            .into(),
+        Some(Err(error)) => text(tr_args("stats-failed", &[("error", error)]))
+            .size(13)
+            .style(theme::error_text_style())
+            .into(),
+        Some(Ok(stats)) => statistics_sections(stats),
+    };
+
+    let content = column![
+        settings_tabs(app),
+        Space::new().height(Length::Fixed(28.0)),
+        row![
+            container(load_icon_size("database", 24.0))
+                .padding(10)
+                .style(theme::accent_badge_container),
+            column![
+                text(tr("stats-title")).size(24).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }),
+                text(tr("stats-subtitle"))
+                    .size(13)
+                    .style(theme::dim_text_style()),
+            ]
+            .spacing(2),
+            Space::new().width(Length::Fill),
+            button(
+                row![
+                    load_icon_size("refresh", 13.0),
+                    text(tr("stats-refresh")).size(12)
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center)
+            )
+            .on_press(Message::TabChanged(Tab::Statistics))
+            .padding(Padding::from([6, 12]))
+            .style(theme::secondary_button()),
+        ]
+        .spacing(14)
+        .align_y(Alignment::Center),
+        Space::new().height(Length::Fixed(32.0)),
+        body,
+    ]
+    .width(Length::Fill)
+    .max_width(820.0);
+
+    container(Scrollable::new(
+        container(content)
+            .width(Length::Fill)
+            .center_x(Length::Fill),
+    ))
+    .style(theme::main_content_container)
+    .padding(Padding::new(32.0))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
+fn statistics_sections(stats: &DetailedStatistics) -> Element<'_, Message> {
+    let mut sections = column![
+        card("database", tr("stats-storage"), storage_rows(stats)),
+        card("folder", tr("stats-by-root"), groups(&stats.by_root, None)),
+        card(
+            "file",
+            tr("stats-by-extension"),
+            groups(&stats.by_extension, Some(TOP_EXTENSIONS))
+        ),
+    ]
+    .spacing(24);
+
+    if !stats.largest_documents.is_empty() {
+        let mut largest = column![].spacing(2);
+        for file in &stats.largest_documents {
+            largest = largest.push(
+                button(
+                    row![
+                        text(&file.path).size(12).width(Length::Fill),
+                        text(format_size(file.size))
+                            .size(12)
+                            .style(theme::dim_text_style()),
+                    ]
+                    .spacing(12)
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::OpenFile(file.path.clone()))
+                .padding(Padding::from([4, 8]))
+                .width(Length::Fill)
+                .style(theme::ghost_button()),
+            );
+        }
+        sections = sections.push(card("star", tr("stats-largest"), largest.into()));
+    }
+    sections.into()
+}
+
+fn card<'a>(icon: &str, title: String, body: Element<'a, Message>) -> Element<'a, Message> {
+    column![
+        row![
+            load_icon_size(icon, 18.0),
+            text(title).size(18).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        container(body)
+            .padding(20)
+            .style(theme::padded_card_container)
+            .width(Length::Fill),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn storage_rows(stats: &DetailedStatistics) -> Element<'_, Message> {
+    let summary = &stats.summary;
+    let line = |label: String, value: String| {
+        row![
+            text(label).size(13).width(Length::Fill),
+            text(value).size(13).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            }),
+        ]
+    };
+    let total = summary.total_size_bytes + stats.metadata_db_bytes + stats.filename_index_bytes;
+    let cache = summary.cache_hit_ratio().map_or_else(
+        || tr("stats-cache-none"),
+        |ratio| {
+            tr_args(
+                "stats-cache-ratio",
+                &[
+                    ("percent", &format!("{:.0}", ratio * 100.0)),
+                    ("count", &(summary.cache_hits + summary.cache_misses)),
+                ],
+            )
+        },
+    );
+    column![
+        line(tr("stats-documents"), summary.total_documents.to_string()),
+        line(
+            tr("stats-search-index"),
+            format_size(summary.total_size_bytes)
+        ),
+        line(
+            tr("stats-metadata-db"),
+            format_size(stats.metadata_db_bytes)
+        ),
+        line(
+            tr("stats-filename-index"),
+            format_size(stats.filename_index_bytes)
+        ),
+        line(tr("stats-total"), format_size(total)),
+        line(tr("stats-cache"), cache),
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// One row per group with a bar for its share of the documents, keeping the
+/// first `limit` if given.
+fn groups(groups: &[UsageGroup], limit: Option<usize>) -> Element<'_, Message> {
+    if groups.iter().all(|g| g.documents == 0) {
+        return text(tr("stats-no-documents"))
+            .size(12)
+            .style(theme::dim_text_style())
+            .into();
+    }
+    let most = groups.iter().map(|g| g.documents).max().unwrap_or(1).max(1);
+    let mut list = column![].spacing(6);
+    for group in groups.iter().take(limit.unwrap_or(usize::MAX)) {
+        list = list.push(
+            row![
+                text(&group.label).size(12).width(Length::FillPortion(3)),
+                container(progress_bar(0.0..=1.0, group.documents as f32 / most as f32).girth(6.0))
+                    .width(Length::FillPortion(2)),
+                text(tr_args("stats-files", &[("count", &group.documents)]))
+                    .size(12)
+                    .width(Length::Fixed(90.0)),
+                text(format_size(group.bytes))
+                    .size(12)
+                    .style(theme::dim_text_style())
+                    .width(Length::Fixed(80.0)),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+        );
+    }
+    if let Some(limit) = limit
+        && groups.len() > limit
+    {
+        list = list.push(
+            text(tr_args("stats-more", &[("count", &(groups.len() - limit))]))
+                .size(12)
+                .style(theme::dim_text_style()),
+        );
+    }
+    list.into()
+}
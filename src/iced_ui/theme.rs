@@ -464,6 +464,12 @@ pub fn danger_text_style() -> impl Fn(&Theme) -> text::Style + use<> {
     error_text_style()
 }
 
+pub fn success_text_style() -> impl Fn(&Theme) -> text::Style + use<> {
+    |_| text::Style {
+        color: Some(Color::from_rgb(0.13, 0.70, 0.33)),
+    }
+}
+
 #[must_use]
 pub fn error_container(_theme: &Theme) -> container::Style {
     container::Style {
@@ -495,6 +501,15 @@ pub fn warning_banner(_theme: &Theme) -> container::Style {
     }
 }
 
+/// Dims the window behind a dialog.
+#[must_use]
+pub fn modal_backdrop(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.45))),
+        ..Default::default()
+    }
+}
+
 // --- Badge / Pill Styles ---
 #[must_use]
 pub fn badge_container(theme: &Theme) -> container::Style {
@@ -641,6 +656,61 @@ pub fn sidebar_panel_container(theme: &Theme) -> container::Style {
     padded_card_container(theme)
 }
 
+/// Outline drawn around the keyboard-focused region.
+pub fn focus_ring_container(is_focused: bool) -> impl Fn(&Theme) -> container::Style + use<> {
+    move |_theme| container::Style {
+        border: Border {
+            color: if is_focused {
+                ACCENT_BLUE
+            } else {
+                Color::TRANSPARENT
+            },
+            width: 2.0,
+            radius: Radius::from(4.0),
+        },
+        ..Default::default()
+    }
+}
+
+/// A PDF page in the preview: white paper with a thin edge, in either theme.
+#[must_use]
+pub fn pdf_page_container(theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(Color::WHITE)),
+        text_color: Some(Color::from_rgb(0.1, 0.1, 0.12)),
+        border: Border {
+            color: border_color(theme),
+            width: 1.0,
+            radius: Radius::from(2.0),
+        },
+        shadow: iced::Shadow {
+            color: Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+            offset: iced::Vector::new(0.0, 2.0),
+            blur_radius: 6.0,
+        },
+        ..Default::default()
+    }
+}
+
+/// A row of the table results layout; the selected row is tinted.
+pub fn table_row_container(is_selected: bool) -> impl Fn(&Theme) -> container::Style + use<> {
+    move |theme| container::Style {
+        background: is_selected.then(|| {
+            Background::Color(if is_dark_theme(theme) {
+                Color::from_rgba(0.0, 0.47, 0.83, 0.12)
+            } else {
+                Color::from_rgba(0.0, 0.47, 0.83, 0.08)
+            })
+        }),
+        border: Border {
+            color: border_color(theme),
+            width: 0.0,
+            radius: Radius::from(0.0),
+        },
+        ..Default::default()
+    }
+}
+
 #[must_use]
 pub fn error_container_style() -> container::Style {
     error_container(&Theme::Dark)
@@ -0,0 +1,103 @@
+//! The passphrase prompt shown at startup while the index is encrypted with
+//! a passphrase that wasn't given on the command line or in the environment.
+
+use super::{Message, theme};
+use crate::error::FlashError;
+use crate::i18n::tr;
+use crate::iced_ui::icons::load_icon_size;
+use iced::widget::{Space, TextInput, button, column, container, text};
+use iced::{Alignment, Element, Font, Length, Padding, font};
+
+#[derive(Debug, Default)]
+pub struct UnlockPrompt {
+    /// The passphrase is being chosen, as when switching to one
+    pub new: bool,
+    pub passphrase: String,
+    pub error: Option<String>,
+    /// The stores are being unlocked or converted
+    pub busy: bool,
+}
+
+impl UnlockPrompt {
+    pub fn new(new: bool, wrong: bool) -> Self {
+        Self {
+            new,
+            error: wrong.then(|| tr("unlock-wrong")),
+            ..Self::default()
+        }
+    }
+
+    /// Shows why unlocking with the passphrase given failed.
+    pub fn failed(&mut self, error: &FlashError) {
+        self.busy = false;
+        self.error = Some(match error {
+            FlashError::Locked { new, wrong } => {
+                self.new = *new;
+                if *wrong {
+                    tr("unlock-wrong")
+                } else {
+                    tr("unlock-needed")
+                }
+            }
+            other => other.to_string(),
+        });
+    }
+}
+
+pub fn unlock_view(prompt: &UnlockPrompt) -> Element<'_, Message> {
+    let (title, hint) = if prompt.new {
+        (tr("unlock-choose-title"), tr("unlock-choose-hint"))
+    } else {
+        (tr("unlock-title"), tr("unlock-hint"))
+    };
+    let mut input = TextInput::new(&tr("unlock-placeholder"), &prompt.passphrase)
+        .secure(true)
+        .padding(Padding::new(12.0))
+        .size(14)
+        .style(theme::search_input());
+    let mut unlock = button(text(tr("unlock-button")).size(13))
+        .padding(Padding::from([8, 18]))
+        .style(theme::primary_button());
+    if !prompt.busy {
+        input = input
+            .on_input(Message::UnlockPassphraseChanged)
+            .on_submit(Message::Unlock);
+        unlock = unlock.on_press(Message::Unlock);
+    }
+
+    let mut card = column![
+        container(load_icon_size("database", 24.0))
+            .padding(10)
+            .style(theme::accent_badge_container),
+        text(title).size(20).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+        text(hint).size(13).style(theme::dim_text_style()),
+        Space::new().height(Length::Fixed(8.0)),
+        input,
+        unlock,
+    ]
+    .spacing(10)
+    .align_x(Alignment::Center)
+    .max_width(420.0);
+    if prompt.busy {
+        card = card.push(
+            text(tr("unlock-busy"))
+                .size(12)
+                .style(theme::dim_text_style()),
+        );
+    }
+    if let Some(error) = &prompt.error {
+        card = card.push(text(error).size(12).style(theme::error_text_style()));
+    }
+
+    container(
+        container(card)
+            .padding(28)
+            .style(theme::padded_card_container),
+    )
+    .center(Length::Fill)
+    .style(theme::main_content_container)
+    .into()
+}
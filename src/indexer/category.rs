@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+/// Broad file type used for `category:` filters and the category sidebar.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::EnumIter,
+    strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Document,
+    Spreadsheet,
+    Presentation,
+    Code,
+    Data,
+    Image,
+    Audio,
+    Video,
+    Archive,
+    Email,
+    Ebook,
+    Other,
+}
+
+impl FileCategory {
+    /// Category and MIME type for a file extension (case-insensitive).
+    #[must_use]
+    pub fn classify(ext: &str) -> (Self, &'static str) {
+        let ext = ext.to_ascii_lowercase();
+        match ext.as_str() {
+            "pdf" => (Self::Document, "application/pdf"),
+            "doc" => (Self::Document, "application/msword"),
+            "docx" => (
+                Self::Document,
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            ),
+            "odt" => (Self::Document, "application/vnd.oasis.opendocument.text"),
+            "rtf" => (Self::Document, "application/rtf"),
+            "txt" | "log" => (Self::Document, "text/plain"),
+            "md" => (Self::Document, "text/markdown"),
+            "html" | "htm" => (Self::Document, "text/html"),
+            "xls" => (Self::Spreadsheet, "application/vnd.ms-excel"),
+            "xlsx" => (
+                Self::Spreadsheet,
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ),
+            "ods" => (
+                Self::Spreadsheet,
+                "application/vnd.oasis.opendocument.spreadsheet",
+            ),
+            "ppt" => (Self::Presentation, "application/vnd.ms-powerpoint"),
+            "pptx" => (
+                Self::Presentation,
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            ),
+            "odp" => (
+                Self::Presentation,
+                "application/vnd.oasis.opendocument.presentation",
+            ),
+            "json" => (Self::Data, "application/json"),
+            "xml" => (Self::Data, "application/xml"),
+            "csv" => (Self::Data, "text/csv"),
+            "tsv" => (Self::Data, "text/tab-separated-values"),
+            "yaml" | "yml" => (Self::Data, "application/yaml"),
+            "toml" => (Self::Data, "application/toml"),
+            "jpg" | "jpeg" => (Self::Image, "image/jpeg"),
+            "png" => (Self::Image, "image/png"),
+            "gif" => (Self::Image, "image/gif"),
+            "svg" => (Self::Image, "image/svg+xml"),
+            "tif" | "tiff" => (Self::Image, "image/tiff"),
+            "heic" => (Self::Image, "image/heic"),
+            "heif" => (Self::Image, "image/heif"),
+            "webp" => (Self::Image, "image/webp"),
+            "bmp" => (Self::Image, "image/bmp"),
+            "mp3" => (Self::Audio, "audio/mpeg"),
+            "wav" => (Self::Audio, "audio/wav"),
+            "flac" => (Self::Audio, "audio/flac"),
+            "ogg" => (Self::Audio, "audio/ogg"),
+            "m4a" => (Self::Audio, "audio/mp4"),
+            "mp4" => (Self::Video, "video/mp4"),
+            "mkv" => (Self::Video, "video/x-matroska"),
+            "mov" => (Self::Video, "video/quicktime"),
+            "avi" => (Self::Video, "video/x-msvideo"),
+            "webm" => (Self::Video, "video/webm"),
+            "zip" => (Self::Archive, "application/zip"),
+            "7z" => (Self::Archive, "application/x-7z-compressed"),
+            "rar" => (Self::Archive, "application/vnd.rar"),
+            "tar" => (Self::Archive, "application/x-tar"),
+            "gz" => (Self::Archive, "application/gzip"),
+            "eml" => (Self::Email, "message/rfc822"),
+            "msg" => (Self::Email, "application/vnd.ms-outlook"),
+            "pst" => (Self::Email, "application/vnd.ms-outlook-pst"),
+            "epub" => (Self::Ebook, "application/epub+zip"),
+            "mobi" => (Self::Ebook, "application/x-mobipocket-ebook"),
+            "azw3" => (Self::Ebook, "application/vnd.amazon.ebook"),
+            "css" => (Self::Code, "text/css"),
+            "js" | "jsx" => (Self::Code, "text/javascript"),
+            ext if super::code_tokenizer::is_code_extension(ext) => (Self::Code, "text/plain"),
+            _ => (Self::Other, "application/octet-stream"),
+        }
+    }
+
+    /// Sidebar label, in the current language.
+    #[must_use]
+    pub fn label(self) -> String {
+        crate::i18n::tr(match self {
+            Self::Document => "category-documents",
+            Self::Spreadsheet => "category-spreadsheets",
+            Self::Presentation => "category-presentations",
+            Self::Code => "category-code",
+            Self::Data => "category-data",
+            Self::Image => "category-images",
+            Self::Audio => "category-audio",
+            Self::Video => "category-video",
+            Self::Archive => "category-archives",
+            Self::Email => "category-email",
+            Self::Ebook => "category-ebooks",
+            Self::Other => "category-other",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(FileCategory::classify("PDF").0, FileCategory::Document);
+        assert_eq!(FileCategory::classify("rs").0, FileCategory::Code);
+        assert_eq!(FileCategory::classify("xlsx").0, FileCategory::Spreadsheet);
+        assert_eq!(FileCategory::classify("zip").1, "application/zip");
+        assert_eq!(FileCategory::classify("nope").0, FileCategory::Other);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let category: FileCategory = "Image".parse().unwrap();
+        assert_eq!(category, FileCategory::Image);
+        assert_eq!(category.to_string(), "image");
+    }
+}
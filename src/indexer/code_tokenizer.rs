@@ -0,0 +1,156 @@
+use tantivy::tokenizer::{
+    LowerCaser, RemoveLongFilter, TextAnalyzer, Token, TokenStream, Tokenizer,
+};
+
+/// Name the code analyzer is registered under in the index's tokenizer manager
+pub const CODE_TOKENIZER: &str = "code";
+
+/// Extensions whose content is additionally indexed through [`CodeTokenizer`]
+pub const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "kts", "scala", "swift", "c", "cpp",
+    "cc", "cxx", "h", "hpp", "hh", "cs", "fs", "rb", "php", "lua", "dart", "m", "mm", "sh", "ps1",
+    "sql", "vue", "svelte", "zig", "nim", "ex", "exs", "erl", "hs", "ml", "clj", "groovy", "r",
+];
+
+#[must_use]
+pub fn is_code_extension(ext: &str) -> bool {
+    CODE_EXTENSIONS
+        .iter()
+        .any(|code_ext| code_ext.eq_ignore_ascii_case(ext))
+}
+
+/// Analyzer splitting identifiers into their words and lowercasing them, so
+/// `getUserName`, `get_user_name` and `GetUserName` all index as `get user name`.
+#[must_use]
+pub fn code_analyzer() -> TextAnalyzer {
+    TextAnalyzer::builder(CodeTokenizer::default())
+        .filter(RemoveLongFilter::limit(40))
+        .filter(LowerCaser)
+        .build()
+}
+
+/// Tokenizer that splits on non-alphanumeric characters and on camelCase boundaries.
+#[derive(Clone, Default)]
+pub struct CodeTokenizer {
+    token: Token,
+}
+
+pub struct CodeTokenStream<'a> {
+    text: &'a str,
+    spans: std::vec::IntoIter<(usize, usize)>,
+    token: &'a mut Token,
+}
+
+impl Tokenizer for CodeTokenizer {
+    type TokenStream<'a> = CodeTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream<'a> {
+        self.token.reset();
+        CodeTokenStream {
+            text,
+            spans: split_identifiers(text).into_iter(),
+            token: &mut self.token,
+        }
+    }
+}
+
+impl TokenStream for CodeTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        let Some((start, end)) = self.spans.next() else {
+            return false;
+        };
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[start..end]);
+        self.token.offset_from = start;
+        self.token.offset_to = end;
+        self.token.position = self.token.position.wrapping_add(1);
+        self.token.position_length = 1;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+/// Byte spans of every word in `text`, with identifiers broken at `_`, `-`,
+/// lower-to-upper transitions (`userName`) and acronym ends (`HTTPServer`).
+fn split_identifiers(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut start: Option<usize> = None;
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(s) = start.take() {
+                spans.push((s, offset));
+            }
+            continue;
+        }
+
+        let Some(s) = start else {
+            start = Some(offset);
+            continue;
+        };
+
+        let prev = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|&(_, n)| n);
+        let camel_boundary = c.is_uppercase() && (prev.is_lowercase() || prev.is_numeric());
+        let acronym_end =
+            c.is_uppercase() && prev.is_uppercase() && next.is_some_and(char::is_lowercase);
+
+        if camel_boundary || acronym_end {
+            spans.push((s, offset));
+            start = Some(offset);
+        }
+    }
+
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        let mut analyzer = code_analyzer();
+        let mut stream = analyzer.token_stream(text);
+        let mut out = Vec::new();
+        while stream.advance() {
+            out.push(stream.token().text.clone());
+        }
+        out
+    }
+
+    #[test]
+    fn test_camel_and_snake_case_match() {
+        assert_eq!(words("getUserName"), vec!["get", "user", "name"]);
+        assert_eq!(words("get_user_name"), vec!["get", "user", "name"]);
+        assert_eq!(words("GetUserName()"), vec!["get", "user", "name"]);
+    }
+
+    #[test]
+    fn test_acronyms_and_digits() {
+        assert_eq!(words("HTTPServer"), vec!["http", "server"]);
+        assert_eq!(words("utf8Decode"), vec!["utf8", "decode"]);
+        assert_eq!(
+            words("parse_XMLFile v2"),
+            vec!["parse", "xml", "file", "v2"]
+        );
+    }
+
+    #[test]
+    fn test_is_code_extension() {
+        assert!(is_code_extension("rs"));
+        assert!(is_code_extension("PY"));
+        assert!(!is_code_extension("pdf"));
+    }
+}
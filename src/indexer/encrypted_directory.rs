@@ -0,0 +1,144 @@
+//! Search index storage that encrypts every file it writes, in the chunked
+//! format of [`crate::system::vault`]. Reads decrypt just the chunks they
+//! cover, so a query never decrypts a whole segment. Files written before
+//! the index was encrypted are read as they are.
+
+use crate::system::vault::{self, Key, SealWriter};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use tantivy::HasLen;
+use tantivy::directory::error::{
+    DeleteError, LockError, OpenDirectoryError, OpenReadError, OpenWriteError,
+};
+use tantivy::directory::{
+    AntiCallToken, Directory, DirectoryLock, FileHandle, Lock, MmapDirectory, OwnedBytes,
+    TerminatingWrite, WatchCallback, WatchHandle, WritePtr,
+};
+
+/// Opens the index directory at `path`, encrypted if the stores are.
+pub fn open_directory(path: &Path) -> Result<Box<dyn Directory>, OpenDirectoryError> {
+    let directory = MmapDirectory::open(path)?;
+    Ok(match vault::key() {
+        Some(key) => Box::new(EncryptedDirectory {
+            inner: directory,
+            key: key.clone(),
+        }),
+        None => Box::new(directory),
+    })
+}
+
+#[derive(Clone, Debug)]
+struct EncryptedDirectory {
+    inner: MmapDirectory,
+    key: Key,
+}
+
+/// A sealed file, decrypted a chunk range at a time
+#[derive(Debug)]
+struct SealedFile {
+    inner: Arc<dyn FileHandle>,
+    key: Key,
+    sealed_len: usize,
+    len: usize,
+}
+
+impl HasLen for SealedFile {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl FileHandle for SealedFile {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        vault::open_range(&self.key, self.sealed_len, range, |sealed| {
+            self.inner.read_bytes(sealed)
+        })
+        .map(OwnedBytes::new)
+    }
+}
+
+/// Seals what Tantivy writes, finishing the file when Tantivy does
+struct SealedWrite(Option<SealWriter<WritePtr>>);
+
+fn terminated() -> io::Error {
+    io::Error::other("write to a file that was already finished")
+}
+
+impl Write for SealedWrite {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.as_mut().ok_or_else(terminated)?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl TerminatingWrite for SealedWrite {
+    fn terminate_ref(&mut self, token: AntiCallToken) -> io::Result<()> {
+        let writer = self.0.take().ok_or_else(terminated)?;
+        writer.finish()?.terminate_ref(token)
+    }
+}
+
+impl Directory for EncryptedDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        let handle = self.inner.get_file_handle(path)?;
+        let wrap = |e| OpenReadError::wrap_io_error(e, path.to_path_buf());
+        let sealed_len = handle.len();
+        let sealed = sealed_len >= vault::MAGIC.len()
+            && vault::is_sealed(&handle.read_bytes(0..vault::MAGIC.len()).map_err(wrap)?);
+        if !sealed {
+            return Ok(handle);
+        }
+        Ok(Arc::new(SealedFile {
+            inner: handle,
+            key: self.key.clone(),
+            sealed_len,
+            len: vault::plain_len(sealed_len).map_err(wrap)?,
+        }))
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        self.inner.delete(path)
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        self.inner.exists(path)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        let inner = self.inner.open_write(path)?;
+        let writer = SealWriter::new(&self.key, inner)
+            .map_err(|e| OpenWriteError::wrap_io_error(e, path.to_path_buf()))?;
+        Ok(io::BufWriter::new(Box::new(SealedWrite(Some(writer)))))
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        let bytes = self.inner.atomic_read(path)?;
+        if !vault::is_sealed(&bytes) {
+            return Ok(bytes);
+        }
+        vault::open(&self.key, &bytes)
+            .map_err(|e| OpenReadError::wrap_io_error(e, path.to_path_buf()))
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.inner
+            .atomic_write(path, &vault::seal(&self.key, data)?)
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.inner.acquire_lock(lock)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        self.inner.watch(watch_callback)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        self.inner.sync_directory()
+    }
+}
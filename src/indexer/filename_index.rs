@@ -1,24 +1,92 @@
-use crate::error::Result;
+use crate::error::{FlashError, Result};
 use arc_swap::ArcSwap;
 use compact_str::CompactString;
-use fst::automaton::Subsequence;
-use fst::{IntoStreamer, Streamer};
+use segment::{SEGMENT_EXTENSION, Segment, Target, path_text, posting_keys};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+mod query;
+mod segment;
+
+pub use query::FilenameQuery;
+
+/// Whether an entry is a file or a directory
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    #[default]
+    File,
+    Directory,
+}
+
+impl From<&ArchivedEntryKind> for EntryKind {
+    fn from(kind: &ArchivedEntryKind) -> Self {
+        match kind {
+            ArchivedEntryKind::File => Self::File,
+            ArchivedEntryKind::Directory => Self::Directory,
+        }
+    }
+}
+
 #[derive(
     Serialize, Deserialize, Debug, Clone, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize,
 )]
 pub struct FilenameEntry {
     pub path: String,
     pub name: CompactString,
+    #[serde(default)]
+    pub kind: EntryKind,
+}
+
+impl FilenameEntry {
+    /// Entry for the file or directory at `path`, named after its last
+    /// component. A drive or filesystem root has no name and no entry.
+    pub fn new(path: &Path, kind: EntryKind) -> Option<Self> {
+        path.file_name().map(|name| Self {
+            path: path.to_string_lossy().to_string(),
+            name: CompactString::from(name.to_string_lossy()),
+            kind,
+        })
+    }
+}
+
+/// Entries as older versions wrote them, before directories were indexed
+#[derive(rkyv::Archive, rkyv::Serialize)]
+struct LegacyFilenameEntry {
+    path: String,
+    name: CompactString,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilenameSearchResult {
     pub file_path: String,
     pub file_name: CompactString,
+    pub kind: EntryKind,
+    /// Match score, lower is better: 0 for the exact name
+    pub score: f32,
+}
+
+impl FilenameSearchResult {
+    /// How closely the name matched, from 1 for the exact name down towards
+    /// 0 for a loose subsequence.
+    #[must_use]
+    pub fn relevance(&self) -> f32 {
+        1.0 / (1.0 + self.score)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -27,110 +95,354 @@ pub struct FilenameIndexStats {
     pub index_size_bytes: u64,
 }
 
-/// File extension for the binary index file
-const INDEX_FILENAME: &str = "filenames.bin";
+/// Every entry in one file: the format older versions kept the index in and
+/// backups carry it in. A file found at open replaces the segments.
+pub const INDEX_FILENAME: &str = "filenames.bin";
 /// Legacy JSON filename for migration
 const LEGACY_INDEX_FILENAME: &str = "filenames.json";
+/// Live segments and the entries deleted from each, rewritten at every commit
+const MANIFEST_FILENAME: &str = "segments.json";
+/// Staged entries that trigger a commit on their own
+const STAGING_LIMIT: usize = 10_000;
+/// Segments kept before the smallest are merged
+const MAX_SEGMENTS: usize = 8;
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    next_id: u64,
+    segments: Vec<ManifestSegment>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestSegment {
+    id: u64,
+    #[serde(default)]
+    deleted: Vec<u32>,
+}
+
+fn io_error(e: std::io::Error) -> FlashError {
+    FlashError::Io(Arc::new(e))
+}
+
+/// Writes `bytes` to `path` through a temporary file that reaches the disk
+/// before it is renamed over `path`, so a crash leaves the old file or the new
+/// one but never part of either. The directory is flushed too, so the rename
+/// itself is durable once this returns.
+fn write_durably(path: &Path, bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = std::fs::File::create(&tmp).map_err(io_error)?;
+    file.write_all(bytes)
+        .and_then(|()| file.sync_all())
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            io_error(e)
+        })?;
+    // Directories can't be opened for syncing on Windows, where the rename
+    // is already written through
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        std::fs::File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(io_error)?;
+    }
+    Ok(())
+}
+
+/// Entries of a segment removed or replaced since it was written, as a bitset
+#[derive(Clone, Default)]
+struct Deleted {
+    words: Vec<u64>,
+    count: usize,
+}
+
+impl Deleted {
+    fn slot(ord: u32) -> (usize, u64) {
+        let ord = usize::try_from(ord).unwrap_or(usize::MAX);
+        (ord / 64, 1 << (ord % 64))
+    }
+
+    fn contains(&self, ord: u32) -> bool {
+        let (word, mask) = Self::slot(ord);
+        self.words.get(word).is_some_and(|w| w & mask != 0)
+    }
+
+    fn insert(&mut self, ord: u32) {
+        let (word, mask) = Self::slot(ord);
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+        if self.words[word] & mask == 0 {
+            self.words[word] |= mask;
+            self.count += 1;
+        }
+    }
+
+    fn ords(&self) -> Vec<u32> {
+        (0..u32::try_from(self.words.len() * 64).unwrap_or(u32::MAX))
+            .filter(|&ord| self.contains(ord))
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+struct LiveSegment {
+    segment: Arc<Segment>,
+    deleted: Arc<Deleted>,
+}
+
+impl LiveSegment {
+    fn live(&self) -> usize {
+        self.segment.entry_count() - self.deleted.count
+    }
+
+    /// Whether most of its entries are gone, so rewriting it pays off
+    fn is_sparse(&self) -> bool {
+        self.deleted.count * 2 > self.segment.entry_count()
+    }
+
+    fn entries(&self) -> impl Iterator<Item = FilenameEntry> + '_ {
+        self.segment
+            .entries()
+            .filter(|(ord, _)| !self.deleted.contains(*ord))
+            .map(|(_, entry)| entry)
+    }
+}
+
+/// The committed state searches read: the live segments, with the id the
+/// next one gets.
+#[derive(Default)]
+struct Segments {
+    list: Vec<LiveSegment>,
+    next_id: u64,
+}
 
+/// File names and paths for instant name search, kept on disk as immutable
+/// memory-mapped segments. A commit writes its additions as a new segment and
+/// marks replaced and removed entries deleted in the older ones, so nothing
+/// but the segment list and its deletions lives in memory. Small segments
+/// are merged as they pile up.
+///
+/// Changes are written one at a time, on the caller's thread: when
+/// [`Self::commit`] returns, the segment and the manifest naming it are on
+/// disk, and a crash at any point before leaves the previous commit intact.
 pub struct FilenameIndex {
-    committed: ArcSwap<Vec<FilenameEntry>>,
-    data_path: std::path::PathBuf,
-    fst_map: Arc<ArcSwap<Arc<[u8]>>>,
+    committed: ArcSwap<Segments>,
+    data_path: PathBuf,
     staging: parking_lot::Mutex<Vec<FilenameEntry>>,
+    /// Paths to drop at the next commit; a directory drops everything under it
+    removals: parking_lot::Mutex<HashSet<String>>,
+    /// Held while segments are written, so changes apply one at a time
+    writing: parking_lot::Mutex<()>,
 }
 
 impl FilenameIndex {
     pub fn open(data_path: &Path) -> Result<Self> {
         let data_path = data_path.to_path_buf();
+        std::fs::create_dir_all(&data_path).map_err(io_error)?;
 
-        let entries = if data_path.exists() {
-            // Try rkyv first, then fall back to legacy JSON
-            let bin_path = data_path.join(INDEX_FILENAME);
-            let json_path = data_path.join(LEGACY_INDEX_FILENAME);
-
-            if bin_path.exists() {
-                std::fs::File::open(&bin_path)
-                    .and_then(|file| unsafe { memmap2::MmapOptions::new().map(&file) })
-                    .map_or_else(
-                        |_| Vec::new(),
-                        |mmap| {
-                            // Ensure byte alignment for rkyv
-                            let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
-                            aligned_bytes.extend_from_slice(&mmap);
-
-                            match rkyv::access::<
-                                rkyv::Archived<Vec<FilenameEntry>>,
-                                rkyv::rancor::Error,
-                            >(&aligned_bytes)
-                            {
-                                Ok(archived) => {
-                                    let entries: Vec<FilenameEntry> = archived
-                                        .iter()
-                                        .map(|item| FilenameEntry {
-                                            path: item.path.as_str().to_string(),
-                                            name: CompactString::from(item.name.as_str()),
-                                        })
-                                        .collect();
-                                    tracing::info!(
-                                        "Loaded {} filenames from rkyv index (mmap)",
-                                        entries.len()
-                                    );
-                                    entries
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Failed to parse rkyv filename index: {}", e);
-                                    Vec::new()
-                                }
-                            }
-                        },
-                    )
-            } else if json_path.exists() {
-                // Migrate from legacy JSON
-                std::fs::read_to_string(&json_path).map_or_else(
-                    |_| Vec::new(),
-                    |content| match serde_json::from_str::<Vec<FilenameEntry>>(&content) {
-                        Ok(entries) => {
-                            tracing::info!(
-                                "Migrated {} filenames from legacy JSON index",
-                                entries.len()
-                            );
-                            // Save as rkyv immediately and remove JSON
-                            Self::save_to_disk_sync(&entries, &data_path);
-                            let _ = std::fs::remove_file(&json_path);
-                            entries
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to parse legacy JSON filename index: {}", e);
-                            Vec::new()
-                        }
-                    },
-                )
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
+        let mut segments = Self::load_segments(&data_path);
+        let duplicates = Self::drop_duplicates(&mut segments);
+        let index = Self {
+            committed: ArcSwap::from_pointee(segments),
+            data_path,
+            staging: parking_lot::Mutex::new(Vec::new()),
+            removals: parking_lot::Mutex::new(HashSet::new()),
+            writing: parking_lot::Mutex::new(()),
         };
 
-        let fst_map = Arc::new(ArcSwap::from_pointee(Arc::from(Self::build_fst(&entries))));
+        // An index from an older version, or one just restored from a
+        // backup, replaces whatever segments there are
+        if let Some(entries) = Self::read_single_file(&index.data_path) {
+            index.replace_all(entries)?;
+            let _ = std::fs::remove_file(index.data_path.join(INDEX_FILENAME));
+            let _ = std::fs::remove_file(index.data_path.join(LEGACY_INDEX_FILENAME));
+        } else if duplicates > 0 {
+            tracing::warn!("Dropped {} duplicate filename entries", duplicates);
+            let segments = index.committed.load_full();
+            index.publish(Segments {
+                list: segments.list.clone(),
+                next_id: segments.next_id,
+            })?;
+        }
+        index.remove_stray_files();
+        Ok(index)
+    }
+
+    /// Marks deleted every entry whose path has a live entry in a newer
+    /// segment too, and returns how many there were. Commits never leave
+    /// such pairs, but an interrupted one or an older version might.
+    fn drop_duplicates(segments: &mut Segments) -> usize {
+        segments
+            .list
+            .sort_by_key(|live| std::cmp::Reverse(live.segment.id));
+        let mut dropped = 0;
+        for older in 1..segments.list.len() {
+            let (newer, rest) = segments.list.split_at_mut(older);
+            let live = &mut rest[0];
+            let mut deleted = (*live.deleted).clone();
+            for (ord, entry) in live.segment.entries() {
+                let shadowed = newer.iter().any(|newer| {
+                    newer
+                        .segment
+                        .find(&entry.path)
+                        .is_some_and(|ord| !newer.deleted.contains(ord))
+                });
+                if !deleted.contains(ord) && shadowed {
+                    deleted.insert(ord);
+                    dropped += 1;
+                }
+            }
+            live.deleted = Arc::new(deleted);
+        }
+        dropped
+    }
 
-        Ok(Self {
-            committed: ArcSwap::from_pointee(entries),
-            data_path,
-            fst_map,
-            staging: parking_lot::Mutex::new(Vec::new()),
-        })
+    fn load_segments(data_path: &Path) -> Segments {
+        let manifest: Manifest = std::fs::read(data_path.join(MANIFEST_FILENAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let list = manifest
+            .segments
+            .into_iter()
+            .filter_map(|entry| match Segment::open(data_path, entry.id) {
+                Ok(segment) => {
+                    let mut deleted = Deleted::default();
+                    for ord in entry.deleted {
+                        deleted.insert(ord);
+                    }
+                    Some(LiveSegment {
+                        segment: Arc::new(segment),
+                        deleted: Arc::new(deleted),
+                    })
+                }
+                Err(e) => {
+                    tracing::warn!("Dropping filename index segment {}: {}", entry.id, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let total: usize = list.iter().map(LiveSegment::live).sum();
+        tracing::info!("Loaded {} filenames from {} segment(s)", total, list.len());
+        Segments {
+            list,
+            next_id: manifest.next_id,
+        }
+    }
+
+    /// Entries of the single-file format, from a backup or an older version.
+    fn read_single_file(data_path: &Path) -> Option<Vec<FilenameEntry>> {
+        let bin_path = data_path.join(INDEX_FILENAME);
+        let json_path = data_path.join(LEGACY_INDEX_FILENAME);
+
+        if let Ok(bytes) = crate::system::vault::read(&bin_path) {
+            // Ensure byte alignment for rkyv
+            let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+            aligned_bytes.extend_from_slice(&bytes);
+            // Files from before directories were indexed have no kinds, and
+            // fail validation as the current layout
+            let current = rkyv::access::<rkyv::Archived<Vec<FilenameEntry>>, rkyv::rancor::Error>(
+                &aligned_bytes,
+            )
+            .map(|archived| {
+                archived
+                    .iter()
+                    .map(|item| FilenameEntry {
+                        path: item.path.as_str().to_string(),
+                        name: CompactString::from(item.name.as_str()),
+                        kind: EntryKind::from(&item.kind),
+                    })
+                    .collect::<Vec<_>>()
+            });
+            let parsed = current.or_else(|_| {
+                rkyv::access::<rkyv::Archived<Vec<LegacyFilenameEntry>>, rkyv::rancor::Error>(
+                    &aligned_bytes,
+                )
+                .map(|archived| {
+                    archived
+                        .iter()
+                        .map(|item| FilenameEntry {
+                            path: item.path.as_str().to_string(),
+                            name: CompactString::from(item.name.as_str()),
+                            kind: EntryKind::File,
+                        })
+                        .collect()
+                })
+            });
+            return match parsed {
+                Ok(entries) => {
+                    tracing::info!(
+                        "Imported {} filenames from {}",
+                        entries.len(),
+                        INDEX_FILENAME
+                    );
+                    Some(entries)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to parse rkyv filename index: {}", e);
+                    None
+                }
+            };
+        }
+
+        let content = std::fs::read_to_string(&json_path).ok()?;
+        match serde_json::from_str::<Vec<FilenameEntry>>(&content) {
+            Ok(entries) => {
+                tracing::info!(
+                    "Migrated {} filenames from legacy JSON index",
+                    entries.len()
+                );
+                Some(entries)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse legacy JSON filename index: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Deletes segment files the committed state no longer refers to, and the
+    /// temporary files of writes a crash interrupted. A segment a search still
+    /// maps can't be deleted on Windows, and goes at the next commit or open
+    /// instead.
+    fn remove_stray_files(&self) {
+        let live: HashSet<PathBuf> = self
+            .committed
+            .load()
+            .list
+            .iter()
+            .map(|l| l.segment.path().to_path_buf())
+            .collect();
+        for entry in std::fs::read_dir(&self.data_path)
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let path = entry.path();
+            let is_segment = path
+                .extension()
+                .is_some_and(|ext| ext == SEGMENT_EXTENSION || ext == "tmp");
+            if is_segment && !live.contains(&path) {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
     }
 
     pub fn add_file(&self, path: &str, name: &str) -> Result<()> {
         let entry = FilenameEntry {
             path: path.to_string(),
             name: CompactString::from(name),
+            kind: EntryKind::File,
         };
 
         let mut staging = self.staging.lock();
         staging.push(entry);
-        if staging.len() >= 10000 {
+        if staging.len() >= STAGING_LIMIT {
             drop(staging);
             let _ = self.commit();
         }
@@ -144,184 +456,371 @@ impl FilenameIndex {
         }
         let mut staging = self.staging.lock();
         staging.extend(entries);
-        if staging.len() >= 10000 {
+        if staging.len() >= STAGING_LIMIT {
             drop(staging);
             let _ = self.commit();
         }
         Ok(())
     }
 
+    /// Stage a file or directory that was deleted or renamed away. Like
+    /// additions, it takes effect at the next [`Self::commit`].
+    pub fn remove_file(&self, path: &str) -> Result<()> {
+        self.remove_files_batch([path.to_string()])
+    }
+
+    /// Stage several removals at once. Entries staged under them since the
+    /// last commit are dropped as well, so they don't come back with it.
+    pub fn remove_files_batch(&self, paths: impl IntoIterator<Item = String>) -> Result<()> {
+        let paths: Vec<String> = paths.into_iter().collect();
+        if paths.is_empty() {
+            return Ok(());
+        }
+        self.staging.lock().retain(|entry| {
+            !paths
+                .iter()
+                .any(|removed| Path::new(&entry.path).starts_with(removed))
+        });
+        self.removals.lock().extend(paths);
+        Ok(())
+    }
+
     pub fn commit(&self) -> Result<()> {
+        let _writing = self.writing.lock();
         let mut staging = self.staging.lock();
-        if staging.is_empty() {
+        let mut removals = self.removals.lock();
+        if staging.is_empty() && removals.is_empty() {
             return Ok(());
         }
 
         let new_items = std::mem::take(&mut *staging);
+        let removed = std::mem::take(&mut *removals);
+        drop(removals);
         drop(staging);
 
-        // Update committed list
-        let mut current = self.committed.load().as_ref().clone();
-        current.extend(new_items);
-
-        let data_path = self.data_path.clone();
-        let data_to_save = current.clone();
+        self.apply(new_items, |segment, deleted| {
+            for root in &removed {
+                for ord in segment.under(root) {
+                    deleted.insert(ord);
+                }
+            }
+        })
+    }
 
-        let fst_map_clone = Arc::clone(&self.fst_map);
+    /// Marks what `drop_from` picks out of each segment deleted, along with
+    /// older entries of every path in `added`, then writes `added` as a new
+    /// segment and publishes the result.
+    fn apply(
+        &self,
+        added: Vec<FilenameEntry>,
+        drop_from: impl Fn(&Segment, &mut Deleted),
+    ) -> Result<()> {
+        let current = self.committed.load_full();
+        let mut list = Vec::with_capacity(current.list.len() + 1);
+        for live in &current.list {
+            let mut deleted = (*live.deleted).clone();
+            drop_from(&live.segment, &mut deleted);
+            for entry in &added {
+                if let Some(ord) = live.segment.find(&entry.path) {
+                    deleted.insert(ord);
+                }
+            }
+            list.push(LiveSegment {
+                segment: live.segment.clone(),
+                deleted: Arc::new(deleted),
+            });
+        }
 
-        self.committed.store(Arc::new(current));
+        let mut next_id = current.next_id;
+        if !added.is_empty() {
+            list.push(LiveSegment {
+                segment: Arc::new(Segment::write(&self.data_path, next_id, added)?),
+                deleted: Arc::new(Deleted::default()),
+            });
+            next_id += 1;
+        }
+        list.retain(|live| live.live() > 0);
 
-        let task = move || {
-            let fst_bytes = Self::build_fst(&data_to_save);
-            fst_map_clone.store(Arc::new(Arc::from(fst_bytes)));
-            Self::save_to_disk_sync(&data_to_save, &data_path);
-        };
+        let next = self.compact(Segments { list, next_id })?;
+        self.publish(next)
+    }
 
-        if let Ok(handle) = tokio::runtime::Handle::try_current() {
-            handle.spawn_blocking(task);
+    /// Merges the smallest segments into one once there are more than
+    /// [`MAX_SEGMENTS`], along with any that are mostly deleted entries.
+    fn compact(&self, mut segments: Segments) -> Result<Segments> {
+        segments.list.sort_by_key(LiveSegment::live);
+        let excess = if segments.list.len() > MAX_SEGMENTS {
+            segments.list.len() - MAX_SEGMENTS / 2
         } else {
-            std::thread::spawn(task);
+            0
+        };
+        let (merge, keep): (Vec<_>, Vec<_>) = segments
+            .list
+            .into_iter()
+            .enumerate()
+            .partition(|(i, live)| *i < excess || live.is_sparse());
+        let mut list: Vec<LiveSegment> = keep.into_iter().map(|(_, live)| live).collect();
+        if merge.is_empty() {
+            return Ok(Segments {
+                list,
+                next_id: segments.next_id,
+            });
         }
 
-        Ok(())
+        let entries: Vec<FilenameEntry> =
+            merge.iter().flat_map(|(_, live)| live.entries()).collect();
+        let mut next_id = segments.next_id;
+        if !entries.is_empty() {
+            list.push(LiveSegment {
+                segment: Arc::new(Segment::write(&self.data_path, next_id, entries)?),
+                deleted: Arc::new(Deleted::default()),
+            });
+            next_id += 1;
+        }
+        Ok(Segments { list, next_id })
     }
 
-    fn build_fst(entries: &[FilenameEntry]) -> Vec<u8> {
-        let mut items: Vec<(String, u64)> = entries
-            .iter()
-            .enumerate()
-            .map(|(i, e)| (format!("{}\0{}", e.name.to_lowercase(), i), i as u64))
-            .collect();
-        items.sort_by(|a, b| a.0.cmp(&b.0));
+    /// Records `next` in the manifest, makes it what searches read and
+    /// deletes the files of segments it no longer has.
+    fn publish(&self, next: Segments) -> Result<()> {
+        let manifest = Manifest {
+            next_id: next.next_id,
+            segments: next
+                .list
+                .iter()
+                .map(|live| ManifestSegment {
+                    id: live.segment.id,
+                    deleted: live.deleted.ords(),
+                })
+                .collect(),
+        };
+        let bytes = serde_json::to_vec(&manifest).map_err(|e| {
+            FlashError::index(format!("Failed to serialize filename index manifest: {e}"))
+        })?;
+        write_durably(&self.data_path.join(MANIFEST_FILENAME), &bytes)?;
 
-        let mut build = fst::MapBuilder::memory();
-        for (k, v) in items {
-            let _ = build.insert(k, v);
-        }
-        build.into_inner().unwrap_or_default()
+        self.committed.store(Arc::new(next));
+        self.remove_stray_files();
+        Ok(())
     }
 
-    /// Save entries to disk using rkyv (consolidated from bincode)
-    fn save_to_disk_sync(entries: &Vec<FilenameEntry>, data_path: &std::path::Path) {
-        match rkyv::to_bytes::<rkyv::rancor::Error>(entries) {
-            Ok(bytes) => {
-                let _ = std::fs::write(data_path.join(INDEX_FILENAME), bytes.as_slice());
-            }
-            Err(e) => {
-                tracing::warn!("Failed to serialize filename index: {}", e);
-            }
+    /// Replaces every segment with one holding `entries`.
+    fn replace_all(&self, entries: Vec<FilenameEntry>) -> Result<()> {
+        let current = self.committed.load_full();
+        let mut next = Segments {
+            list: Vec::new(),
+            next_id: current.next_id,
+        };
+        if !entries.is_empty() {
+            next.list.push(LiveSegment {
+                segment: Arc::new(Segment::write(&self.data_path, next.next_id, entries)?),
+                deleted: Arc::new(Deleted::default()),
+            });
+            next.next_id += 1;
         }
+        self.publish(next)
     }
 
+    /// Names containing the query outrank every looser match. Its trigrams
+    /// find those first, and only when too few turn up are the candidates
+    /// widened to every name with all of the query's characters, which
+    /// subsequence matches need.
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<FilenameSearchResult>> {
-        let fst_guard = self.fst_map.load();
-        if fst_guard.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        // FST Map - use reference borrow from the guard
-        let Ok(map) = fst::Map::new(&**fst_guard) else {
-            return Ok(Vec::new());
-        };
+        Ok(self.search_in(
+            Target::Name,
+            &query.to_lowercase(),
+            limit,
+            &[],
+            &|_, _, _| true,
+        ))
+    }
 
-        // Fuzzy / Subsequence matching
-        let query_lower = query.to_lowercase();
-        let aut = Subsequence::new(&query_lower);
+    /// Like [`Self::search`], but matches the query against whole paths, with
+    /// `/` separating components on every platform. A directory's path ends
+    /// in a separator, so `src/config/` finds the folder and everything in it.
+    pub fn search_paths(&self, query: &str, limit: usize) -> Result<Vec<FilenameSearchResult>> {
+        let query = query.to_lowercase().replace('\\', "/");
+        Ok(self.search_in(Target::Path, &query, limit, &[], &|_, _, _| true))
+    }
 
-        let mut stream = map.search(aut).into_stream();
+    /// Entries matching a parsed query, best first. `keep` is asked about
+    /// each file that passes the query's own filters, for what the index
+    /// can't tell, like sizes.
+    pub fn query(
+        &self,
+        query: &FilenameQuery,
+        limit: usize,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<Vec<FilenameSearchResult>> {
+        let required = query.required_keys();
+        Ok(self.search_in(
+            query.target(),
+            query.text(),
+            limit,
+            &required,
+            &|path, name, kind| {
+                query.matches(path, name, kind) && (kind == EntryKind::Directory || keep(path))
+            },
+        ))
+    }
 
-        let entries_lock = self.committed.load();
+    /// Ranks entries by how well `query` matches their `target`. Only those
+    /// with all of the `required` posting keys are considered, and only those
+    /// `keep` accepts are returned.
+    fn search_in(
+        &self,
+        target: Target,
+        query: &str,
+        limit: usize,
+        required: &[Vec<u8>],
+        keep: &dyn Fn(&str, &str, EntryKind) -> bool,
+    ) -> Vec<FilenameSearchResult> {
+        let segments = self.committed.load();
+        let (mut chars, mut trigrams) = posting_keys(query, target);
+        let has_trigrams = !trigrams.is_empty();
+        chars.extend_from_slice(required);
+        trigrams.extend_from_slice(required);
 
-        // Collect matching candidates to sort them later
-        let mut candidates = Vec::new();
-        while let Some((_, v)) = stream.next() {
-            if let Some(entry) = entries_lock.get(usize::try_from(v).unwrap_or(usize::MAX)) {
-                let score = calculate_match_score(&entry.name, &query_lower);
-                candidates.push((entry, score));
-            }
+        let mut matches = Vec::new();
+        if has_trigrams {
+            matches =
+                score_candidates(&segments, target, &trigrams, query, SUBSEQUENCE_SCORE, keep);
+        }
+        if matches.len() < limit {
+            matches = score_candidates(&segments, target, &chars, query, NO_MATCH_SCORE, keep);
         }
 
-        // Sort by score ascending (lower score means better match)
-        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let by_score =
+            |a: &Match<'_>, b: &Match<'_>| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(b.1));
+        if matches.len() > limit {
+            matches.select_nth_unstable_by(limit, by_score);
+            matches.truncate(limit);
+        }
+        matches.sort_by(by_score);
 
-        let results = candidates
+        matches
             .into_iter()
-            .take(limit)
-            .map(|(entry, _)| FilenameSearchResult {
-                file_path: entry.path.clone(),
-                file_name: entry.name.clone(),
+            .map(|(score, path, name, kind)| FilenameSearchResult {
+                file_path: path.to_string(),
+                file_name: CompactString::from(name),
+                kind,
+                score,
             })
-            .collect();
-
-        Ok(results)
+            .collect()
     }
 
     pub fn clear(&self) -> Result<()> {
-        self.committed.store(Arc::new(Vec::new()));
-        self.fst_map
-            .store(Arc::new(Arc::from(Vec::new().into_boxed_slice())));
+        let _writing = self.writing.lock();
         self.staging.lock().clear();
-
-        let data_path = self.data_path.clone();
-        let task = move || {
-            let _ = std::fs::remove_file(data_path.join(INDEX_FILENAME));
-            let _ = std::fs::remove_file(data_path.join(LEGACY_INDEX_FILENAME));
-        };
-
-        if let Ok(handle) = tokio::runtime::Handle::try_current() {
-            handle.spawn_blocking(task);
-        } else {
-            std::thread::spawn(task);
-        }
-
-        Ok(())
+        self.removals.lock().clear();
+        self.replace_all(Vec::new())
     }
 
     pub fn get_stats(&self) -> Result<FilenameIndexStats> {
-        let entries = self.committed.load();
-
-        let size: u64 = entries
-            .iter()
-            .map(|e| e.path.len() as u64 + e.name.len() as u64 + 32)
-            .sum();
-
+        let segments = self.committed.load();
         Ok(FilenameIndexStats {
-            total_files: entries.len(),
-            index_size_bytes: size,
+            total_files: segments.list.iter().map(LiveSegment::live).sum(),
+            index_size_bytes: segments
+                .list
+                .iter()
+                .map(|live| live.segment.file_size())
+                .sum(),
         })
     }
 
-    pub fn rebuild_index(&self, paths: Vec<(String, String)>) -> Result<()> {
-        let data_path = self.data_path.clone();
-        let new_entries: Vec<FilenameEntry> = paths
-            .into_iter()
-            .map(|(path, name)| FilenameEntry {
-                path,
-                name: CompactString::from(name),
-            })
+    /// The committed entries in the single-file format of [`INDEX_FILENAME`],
+    /// for backups taken while the index is in use.
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        let entries: Vec<FilenameEntry> = self
+            .committed
+            .load()
+            .list
+            .iter()
+            .flat_map(LiveSegment::entries)
             .collect();
+        rkyv::to_bytes::<rkyv::rancor::Error>(&entries)
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| FlashError::index(format!("Failed to serialize filename index: {e}")))
+    }
+
+    /// Paths of every committed file; directories are left out.
+    pub fn paths(&self) -> HashSet<String> {
+        self.committed
+            .load()
+            .list
+            .iter()
+            .flat_map(LiveSegment::entries)
+            .filter(|e| e.kind == EntryKind::File)
+            .map(|e| e.path)
+            .collect()
+    }
 
-        let data = new_entries.clone();
+    /// Replaces every entry under `root` with `entries`, the result of a full
+    /// enumeration of the root, so files deleted since the last one drop out.
+    pub fn replace_root(&self, root: &Path, entries: Vec<FilenameEntry>) -> Result<()> {
+        let _writing = self.writing.lock();
+        let root = root.to_string_lossy();
+        self.apply(entries, |segment, deleted| {
+            for ord in segment.under(&root) {
+                deleted.insert(ord);
+            }
+        })
+    }
 
-        self.fst_map
-            .store(Arc::new(Arc::from(Self::build_fst(&new_entries))));
-        self.committed.store(Arc::new(new_entries));
+    pub fn rebuild_index(&self, paths: Vec<(String, String)>) -> Result<()> {
+        let _writing = self.writing.lock();
         self.staging.lock().clear();
+        self.removals.lock().clear();
+        self.replace_all(
+            paths
+                .into_iter()
+                .map(|(path, name)| FilenameEntry {
+                    path,
+                    name: CompactString::from(name),
+                    kind: EntryKind::File,
+                })
+                .collect(),
+        )
+    }
+}
 
-        let task = move || {
-            Self::save_to_disk_sync(&data, &data_path);
-        };
+/// Score, path, name and kind of a scored entry
+type Match<'a> = (f32, &'a str, &'a str, EntryKind);
 
-        if let Ok(handle) = tokio::runtime::Handle::try_current() {
-            handle.spawn_blocking(task);
-        } else {
-            std::thread::spawn(task);
+/// Scores the live entries that have all of `keys` on their `target`,
+/// keeping those scoring below `below` that `keep` accepts.
+fn score_candidates<'a>(
+    segments: &'a Segments,
+    target: Target,
+    keys: &[Vec<u8>],
+    query: &str,
+    below: f32,
+    keep: &dyn Fn(&str, &str, EntryKind) -> bool,
+) -> Vec<Match<'a>> {
+    let mut matches = Vec::new();
+    for live in &segments.list {
+        for ord in live.segment.candidates(keys) {
+            if live.deleted.contains(ord) {
+                continue;
+            }
+            if let Some((path, name, kind)) = live.segment.entry(ord) {
+                let score = match target {
+                    Target::Name => calculate_match_score(name, query),
+                    Target::Path => calculate_match_score(&path_text(path, kind), query),
+                };
+                if score < below && keep(path, name, kind) {
+                    matches.push((score, path, name, kind));
+                }
+            }
         }
-
-        Ok(())
     }
+    matches
 }
 
+/// Exact, prefix and substring matches score below this
+const SUBSEQUENCE_SCORE: f32 = 3.0;
+const NO_MATCH_SCORE: f32 = 100.0;
+
 fn find_subsequence_span(name: &str, query: &str) -> Option<(usize, usize)> {
     let mut query_chars = query.chars().peekable();
     let mut first_match = None;
@@ -362,8 +861,295 @@ fn calculate_match_score(name: &str, query: &str) -> f32 {
     if let Some((start, end)) = find_subsequence_span(&name_lower, &query_lower) {
         let span = end - start + 1;
         let gap_penalty = (span - query_lower.len()) as f32;
-        return 3.0 + gap_penalty * 0.1 + start as f32 * 0.01 + name_lower.len() as f32 * 0.001;
+        return SUBSEQUENCE_SCORE
+            + gap_penalty * 0.1
+            + start as f32 * 0.01
+            + name_lower.len() as f32 * 0.001;
+    }
+
+    NO_MATCH_SCORE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> FilenameEntry {
+        FilenameEntry::new(Path::new(path), EntryKind::File).unwrap()
+    }
+
+    fn sorted_paths(index: &FilenameIndex) -> Vec<String> {
+        let mut paths: Vec<String> = index.paths().into_iter().collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_replace_root_and_commit_keep_one_entry_per_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+
+        index
+            .add_files_batch(vec![entry("/data/a.txt"), entry("/other/b.txt")])
+            .unwrap();
+        index.commit().unwrap();
+        index
+            .replace_root(
+                Path::new("/data"),
+                vec![entry("/data/c.txt"), entry("/data/d.txt")],
+            )
+            .unwrap();
+        index.add_files_batch(vec![entry("/data/c.txt")]).unwrap();
+        index.commit().unwrap();
+
+        let paths = sorted_paths(&index);
+        assert_eq!(paths, ["/data/c.txt", "/data/d.txt", "/other/b.txt"]);
+        assert_eq!(index.search("a.txt", 10).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_removals_drop_files_and_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+
+        index
+            .add_files_batch(vec![
+                entry("/data/a.txt"),
+                entry("/data/sub/b.txt"),
+                entry("/data/sub/c.txt"),
+                entry("/data/subway.txt"),
+            ])
+            .unwrap();
+        index.commit().unwrap();
+
+        // A rename is a removal of the old path and an addition of the new one
+        index
+            .remove_files_batch(["/data/a.txt".to_string(), "/data/sub".to_string()])
+            .unwrap();
+        index
+            .add_files_batch(vec![entry("/data/renamed.txt")])
+            .unwrap();
+        index.commit().unwrap();
+
+        let paths = sorted_paths(&index);
+        assert_eq!(paths, ["/data/renamed.txt", "/data/subway.txt"]);
+    }
+
+    #[test]
+    fn test_rescans_upsert_and_removal_drops_staged_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+
+        for _ in 0..3 {
+            index.add_file("/data/a.txt", "a.txt").unwrap();
+            index.commit().unwrap();
+        }
+        assert_eq!(index.get_stats().unwrap().total_files, 1);
+        assert_eq!(index.search("a.txt", 10).unwrap().len(), 1);
+
+        // Created and deleted again before the next commit
+        index.add_file("/data/b.txt", "b.txt").unwrap();
+        index.remove_file("/data/b.txt").unwrap();
+        index.remove_file("/data/a.txt").unwrap();
+        index.commit().unwrap();
+        assert!(sorted_paths(&index).is_empty());
+    }
+
+    #[test]
+    fn test_directories_are_found_by_name_and_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        index
+            .add_files_batch(vec![
+                entry("/repo/src/config/mod.rs"),
+                FilenameEntry::new(Path::new("/repo/src/config"), EntryKind::Directory).unwrap(),
+                entry("/repo/docs/config.md"),
+            ])
+            .unwrap();
+        index.commit().unwrap();
+
+        let by_name = index.search("config", 10).unwrap();
+        assert_eq!(by_name.len(), 2);
+        assert_eq!(by_name[0].file_path, "/repo/src/config");
+        assert_eq!(by_name[0].kind, EntryKind::Directory);
+        assert_eq!(by_name[1].kind, EntryKind::File);
+
+        // Either separator finds the folder, ahead of what it holds
+        for query in ["src/config/", "SRC\\config"] {
+            let by_path: Vec<String> = index
+                .search_paths(query, 10)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.file_path)
+                .collect();
+            assert_eq!(by_path, ["/repo/src/config", "/repo/src/config/mod.rs"]);
+        }
+        // Only files are checked against the metadata
+        assert_eq!(
+            sorted_paths(&index),
+            ["/repo/docs/config.md", "/repo/src/config/mod.rs"]
+        );
     }
 
-    100.0
+    #[test]
+    fn test_queries_filter_before_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        index
+            .add_files_batch(vec![
+                entry("/repo/src/main.rs"),
+                entry("/repo/src/main_test.rs"),
+                entry("/repo/src/main.py"),
+                entry("/repo/tests/big.rs"),
+            ])
+            .unwrap();
+        index.commit().unwrap();
+
+        let paths = |input: &str, keep: &dyn Fn(&str) -> bool| -> Vec<String> {
+            index
+                .query(&FilenameQuery::parse(input, false), 2, keep)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.file_path)
+                .collect()
+        };
+        // Without text to rank by, shorter names come first
+        assert_eq!(
+            paths("*.rs !test", &|_| true),
+            ["/repo/tests/big.rs", "/repo/src/main.rs"]
+        );
+        assert_eq!(paths("main ext:py", &|_| true), ["/repo/src/main.py"]);
+        // What the caller rules out never takes up a place in the results
+        assert_eq!(
+            paths("ext:rs", &|path| path.ends_with("big.rs")),
+            ["/repo/tests/big.rs"]
+        );
+    }
+
+    #[test]
+    fn test_segments_and_deletions_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let index = FilenameIndex::open(dir.path()).unwrap();
+            index
+                .add_files_batch(vec![entry("/data/a.txt"), entry("/data/b.txt")])
+                .unwrap();
+            index.commit().unwrap();
+            index
+                .remove_files_batch(["/data/a.txt".to_string()])
+                .unwrap();
+            index.add_files_batch(vec![entry("/data/c.txt")]).unwrap();
+            index.commit().unwrap();
+        }
+
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        assert_eq!(sorted_paths(&index), ["/data/b.txt", "/data/c.txt"]);
+        assert!(index.search("a.txt", 10).unwrap().is_empty());
+        assert_eq!(index.get_stats().unwrap().total_files, 2);
+    }
+
+    #[test]
+    fn test_search_ranks_substrings_before_subsequences() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        index
+            .add_files_batch(vec![
+                entry("/docs/report-final.pdf"),
+                entry("/docs/Report.docx"),
+                entry("/docs/rapport.txt"),
+                entry("/docs/notes.md"),
+            ])
+            .unwrap();
+        index.commit().unwrap();
+
+        let names = |query: &str, limit| -> Vec<String> {
+            index
+                .search(query, limit)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.file_name.to_string())
+                .collect()
+        };
+        assert_eq!(names("report", 10), ["Report.docx", "report-final.pdf"]);
+        // Too few substring matches widen the search to subsequences
+        assert_eq!(
+            names("rprt", 10),
+            ["Report.docx", "report-final.pdf", "rapport.txt"]
+        );
+        assert_eq!(names("rep", 1), ["Report.docx"]);
+        assert!(names("xyz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_small_segments_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        for i in 0..=MAX_SEGMENTS * 2 {
+            index
+                .add_files_batch(vec![entry(&format!("/data/file{i}.txt"))])
+                .unwrap();
+            index.commit().unwrap();
+        }
+
+        assert!(index.committed.load().list.len() <= MAX_SEGMENTS);
+        assert_eq!(index.paths().len(), MAX_SEGMENTS * 2 + 1);
+        let segment_files = std::fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .is_some_and(|ext| ext == SEGMENT_EXTENSION)
+            })
+            .count();
+        assert_eq!(segment_files, index.committed.load().list.len());
+    }
+
+    #[test]
+    fn test_interrupted_writes_leave_the_last_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let index = FilenameIndex::open(dir.path()).unwrap();
+            index.add_files_batch(vec![entry("/data/a.txt")]).unwrap();
+            index.commit().unwrap();
+        }
+        // What a crash halfway through the next commit leaves behind
+        let torn = [
+            segment::segment_path(dir.path(), 7).with_extension("seg.tmp"),
+            dir.path().join("segments.json.tmp"),
+        ];
+        for path in &torn {
+            std::fs::write(path, b"partial").unwrap();
+        }
+
+        let index = FilenameIndex::open(dir.path()).unwrap();
+        assert_eq!(sorted_paths(&index), ["/data/a.txt"]);
+        assert!(torn.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn test_single_file_from_a_backup_is_imported() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = FilenameIndex::open(&dir.path().join("source")).unwrap();
+        source.add_files_batch(vec![entry("/data/a.txt")]).unwrap();
+        source.commit().unwrap();
+
+        let restored = dir.path().join("restored");
+        std::fs::create_dir(&restored).unwrap();
+        std::fs::write(restored.join(INDEX_FILENAME), source.snapshot().unwrap()).unwrap();
+
+        let index = FilenameIndex::open(&restored).unwrap();
+        assert_eq!(sorted_paths(&index), ["/data/a.txt"]);
+        assert!(!restored.join(INDEX_FILENAME).exists());
+
+        // Backups from before directories were indexed
+        let legacy = vec![LegacyFilenameEntry {
+            path: "/data/b.txt".to_string(),
+            name: "b.txt".into(),
+        }];
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&legacy).unwrap();
+        std::fs::write(restored.join(INDEX_FILENAME), bytes.as_slice()).unwrap();
+        let index = FilenameIndex::open(&restored).unwrap();
+        assert_eq!(sorted_paths(&index), ["/data/b.txt"]);
+    }
 }
@@ -0,0 +1,243 @@
+//! Everything-style queries for the filename index. The operators of the
+//! full-text parser (`ext:`, `path:`, `size:`) filter entries, words with `*`
+//! or `?` are wildcards over the whole name, `!word` drops entries whose name
+//! has it, and the rest is matched fuzzily as before. A pattern or exclusion
+//! with a separator in it applies to the whole path instead of the name.
+
+use super::EntryKind;
+use super::segment::{Target, path_text, posting_keys};
+use crate::indexer::query_parser::ParsedQuery;
+use globset::{GlobBuilder, GlobMatcher};
+use tracing::warn;
+
+/// A wildcard pattern or excluded text, applied to a name or to a path
+#[derive(Debug, Clone)]
+enum Pattern {
+    Glob {
+        matcher: GlobMatcher,
+        literals: Vec<String>,
+        on_path: bool,
+    },
+    Text {
+        text: String,
+        on_path: bool,
+    },
+}
+
+impl Pattern {
+    fn parse(token: &str) -> Option<Self> {
+        let on_path = token.contains(['/', '\\']);
+        let text = if on_path {
+            token.to_lowercase().replace('\\', "/")
+        } else {
+            token.to_lowercase()
+        };
+        if !text.contains(['*', '?']) {
+            return Some(Self::Text { text, on_path });
+        }
+
+        // A relative path pattern may start in any folder
+        let anchored = text.starts_with(['*', '/']) || text.get(1..2) == Some(":");
+        let glob = if on_path && !anchored {
+            format!("*/{text}")
+        } else {
+            text
+        };
+        let matcher = GlobBuilder::new(&glob)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| warn!("Invalid filename pattern '{}': {}", token, e))
+            .ok()?
+            .compile_matcher();
+        // Text between wildcards has to appear in every match, unless a class
+        // or alternation makes it optional
+        let literals = if glob.contains(['[', '{']) {
+            Vec::new()
+        } else {
+            glob.split(['*', '?'])
+                .filter(|literal| !literal.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Some(Self::Glob {
+            matcher,
+            literals,
+            on_path,
+        })
+    }
+
+    const fn on_path(&self) -> bool {
+        match self {
+            Self::Glob { on_path, .. } | Self::Text { on_path, .. } => *on_path,
+        }
+    }
+
+    /// Matches `name`, or `path` as [`path_text`] gives it.
+    fn matches(&self, name: &str, path: &str) -> bool {
+        match self {
+            Self::Glob { matcher, .. } => {
+                matcher.is_match(if self.on_path() { path } else { name })
+            }
+            Self::Text { text, .. } if self.on_path() => path.contains(text.as_str()),
+            Self::Text { text, .. } => name.to_lowercase().contains(text.as_str()),
+        }
+    }
+}
+
+/// A parsed filename query.
+#[derive(Debug, Clone)]
+pub struct FilenameQuery {
+    /// Lowercased text to rank entries by
+    text: String,
+    /// What the text is matched against
+    target: Target,
+    filters: ParsedQuery,
+    patterns: Vec<Pattern>,
+    excludes: Vec<Pattern>,
+}
+
+impl FilenameQuery {
+    /// Parses `input`, ranking by whole paths instead of names when
+    /// `match_path` is set.
+    #[must_use]
+    pub fn parse(input: &str, match_path: bool) -> Self {
+        let filters = ParsedQuery::new(input, false);
+        let mut words = Vec::new();
+        let mut patterns = Vec::new();
+        let mut excludes = Vec::new();
+        for token in filters.text_query.split_whitespace() {
+            if let Some(excluded) = token.strip_prefix('!') {
+                if !excluded.is_empty() {
+                    excludes.extend(Pattern::parse(excluded));
+                }
+            } else if token.contains(['*', '?']) {
+                // A lone `*` is what the parser leaves for an empty query
+                if token != "*" {
+                    patterns.extend(Pattern::parse(token));
+                }
+            } else {
+                words.push(token);
+            }
+        }
+
+        let text = words.join(" ").to_lowercase();
+        let (target, text) = if match_path {
+            (Target::Path, text.replace('\\', "/"))
+        } else {
+            (Target::Name, text)
+        };
+        Self {
+            text,
+            target,
+            filters,
+            patterns,
+            excludes,
+        }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[must_use]
+    pub const fn target(&self) -> Target {
+        self.target
+    }
+
+    /// Whether only some sizes are wanted, which has to be looked up outside
+    /// the filename index.
+    #[must_use]
+    pub const fn has_size_filter(&self) -> bool {
+        self.filters.min_size.is_some() || self.filters.max_size.is_some()
+    }
+
+    #[must_use]
+    pub fn matches_size(&self, size: u64) -> bool {
+        self.filters.min_size.is_none_or(|min| size >= min)
+            && self.filters.max_size.is_none_or(|max| size <= max)
+    }
+
+    /// Posting keys every match has, from the extension and the text between
+    /// the wildcards of each pattern.
+    #[must_use]
+    pub fn required_keys(&self) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        let mut require = |text: &str, target: Target| {
+            let (chars, trigrams) = posting_keys(text, target);
+            keys.extend(chars);
+            keys.extend(trigrams);
+        };
+        if let Some(ext) = &self.filters.extension {
+            require(&format!(".{ext}"), Target::Name);
+        }
+        for pattern in &self.patterns {
+            if let Pattern::Glob { literals, .. } = pattern {
+                let target = if pattern.on_path() {
+                    Target::Path
+                } else {
+                    Target::Name
+                };
+                for literal in literals {
+                    require(literal, target);
+                }
+            }
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Whether an entry passes every filter but size. Directories have no
+    /// extension or size, so those filters leave them out.
+    #[must_use]
+    pub fn matches(&self, path: &str, name: &str, kind: EntryKind) -> bool {
+        if kind == EntryKind::Directory
+            && (self.filters.extension.is_some() || self.has_size_filter())
+        {
+            return false;
+        }
+        if !self.filters.matches_extension(path) || !self.filters.matches_path(path) {
+            return false;
+        }
+        let path = path_text(path, kind);
+        self.patterns.iter().all(|p| p.matches(name, &path))
+            && !self.excludes.iter().any(|p| p.matches(name, &path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators_patterns_and_exclusions_are_split_out() {
+        let query = FilenameQuery::parse("report ext:pdf *2024* !draft size:>1KB", false);
+        assert_eq!(query.text(), "report");
+        assert!(query.has_size_filter());
+        assert!(query.matches_size(4096));
+        assert!(!query.matches_size(10));
+
+        let file = EntryKind::File;
+        assert!(query.matches("/docs/report-2024.pdf", "report-2024.pdf", file));
+        assert!(!query.matches("/docs/report-2024-draft.pdf", "report-2024-draft.pdf", file));
+        assert!(!query.matches("/docs/report-2023.pdf", "report-2023.pdf", file));
+        assert!(!query.matches("/docs/report-2024.txt", "report-2024.txt", file));
+        assert!(!query.matches("/docs/2024.pdf", "2024.pdf", EntryKind::Directory));
+    }
+
+    #[test]
+    fn test_patterns_with_separators_match_paths() {
+        let query = FilenameQuery::parse("*.rs src/*/mod.rs !target/", false);
+        assert_eq!(query.text(), "");
+        let file = EntryKind::File;
+        assert!(query.matches("/repo/src/config/mod.rs", "mod.rs", file));
+        assert!(query.matches("C:\\repo\\src\\config\\mod.rs", "mod.rs", file));
+        assert!(!query.matches("/repo/lib/config/mod.rs", "mod.rs", file));
+        assert!(!query.matches("/repo/target/src/x/mod.rs", "mod.rs", file));
+
+        let keys = query.required_keys();
+        assert!(keys.contains(&posting_keys(".rs", Target::Name).1[0]));
+        assert!(keys.contains(&posting_keys("mod", Target::Path).1[0]));
+    }
+}
@@ -0,0 +1,325 @@
+//! One immutable run of the filename index, memory-mapped from disk, or read
+//! into memory and decrypted if the index is encrypted. Entries
+//! are sorted by path, so a file or a whole directory is found by binary
+//! search. Next to them, postings list which entries have each character and
+//! each trigram in their lowercased name and path, keyed through an FST, so a
+//! query is narrowed to a few candidates before any of them is scored.
+
+use super::{EntryKind, FilenameEntry, write_durably};
+use crate::error::{FlashError, Result};
+use crate::system::vault;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Segment files are named by their id with this extension
+pub const SEGMENT_EXTENSION: &str = "seg";
+
+/// What posting keys are taken from. Prefixes keep the character and
+/// trigram keys of names and paths apart in one FST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Name,
+    Path,
+}
+
+impl Target {
+    /// Character and trigram key prefixes
+    const fn prefixes(self) -> (u8, u8) {
+        match self {
+            Self::Name => (b'1', b'3'),
+            Self::Path => (b'2', b'4'),
+        }
+    }
+}
+
+#[derive(rkyv::Archive, rkyv::Serialize)]
+struct SegmentData {
+    /// Sorted by path, one per path
+    entries: Vec<FilenameEntry>,
+    /// FST from posting key to the range of `postings` holding its entries,
+    /// as offset << 32 | length
+    grams: Vec<u8>,
+    postings: Vec<u32>,
+}
+
+/// A segment file's contents
+enum SegmentBytes {
+    Mapped(memmap2::Mmap),
+    Decrypted(rkyv::util::AlignedVec<16>),
+}
+
+impl std::ops::Deref for SegmentBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mmap) => mmap,
+            Self::Decrypted(bytes) => bytes,
+        }
+    }
+}
+
+/// A range of a segment's contents, so the FST reads straight from them
+#[derive(Clone)]
+struct MappedBytes {
+    bytes: Arc<SegmentBytes>,
+    start: usize,
+    len: usize,
+}
+
+impl AsRef<[u8]> for MappedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[self.start..self.start + self.len]
+    }
+}
+
+/// The text path queries are matched against: lowercased, with `/` between
+/// components and after a directory.
+pub fn path_text(path: &str, kind: EntryKind) -> String {
+    let mut text = path.to_lowercase().replace('\\', "/");
+    if kind == EntryKind::Directory && !text.ends_with('/') {
+        text.push('/');
+    }
+    text
+}
+
+/// Character and trigram keys of a lowercased name, path text or query.
+pub fn posting_keys(text: &str, target: Target) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let (char_key, trigram_key) = target.prefixes();
+    let chars: Vec<char> = text.chars().collect();
+    let key = |prefix: u8, chars: &[char]| {
+        let mut key = vec![prefix];
+        key.extend(chars.iter().collect::<String>().bytes());
+        key
+    };
+    let mut singles: Vec<Vec<u8>> = chars
+        .iter()
+        .map(|c| key(char_key, std::slice::from_ref(c)))
+        .collect();
+    let mut trigrams: Vec<Vec<u8>> = chars.windows(3).map(|w| key(trigram_key, w)).collect();
+    singles.sort_unstable();
+    singles.dedup();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    (singles, trigrams)
+}
+
+fn segment_error(path: &Path, e: impl std::fmt::Display) -> FlashError {
+    FlashError::index(format!(
+        "Filename index segment {} is unreadable: {e}",
+        path.display()
+    ))
+}
+
+pub fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{id:08}.{SEGMENT_EXTENSION}"))
+}
+
+pub struct Segment {
+    pub id: u64,
+    path: PathBuf,
+    bytes: Arc<SegmentBytes>,
+    grams: fst::Map<MappedBytes>,
+}
+
+impl Segment {
+    /// Writes `entries` as segment `id` in `dir` and opens it. Of several
+    /// entries for one path, the last wins.
+    pub fn write(dir: &Path, id: u64, entries: Vec<FilenameEntry>) -> Result<Self> {
+        let mut entries = entries;
+        // Stable, so the last of a path's entries ends its run
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut unique: Vec<FilenameEntry> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match unique.last_mut() {
+                Some(last) if last.path == entry.path => *last = entry,
+                _ => unique.push(entry),
+            }
+        }
+
+        let mut postings: BTreeMap<Vec<u8>, Vec<u32>> = BTreeMap::new();
+        for (ord, entry) in unique.iter().enumerate() {
+            let ord = u32::try_from(ord)
+                .map_err(|_| FlashError::index("Filename index segment is too large"))?;
+            let (name_chars, name_trigrams) =
+                posting_keys(&entry.name.to_lowercase(), Target::Name);
+            let (path_chars, path_trigrams) =
+                posting_keys(&path_text(&entry.path, entry.kind), Target::Path);
+            for key in name_chars
+                .into_iter()
+                .chain(name_trigrams)
+                .chain(path_chars)
+                .chain(path_trigrams)
+            {
+                postings.entry(key).or_default().push(ord);
+            }
+        }
+        let mut flat = Vec::new();
+        let mut grams = fst::MapBuilder::memory();
+        for (key, ords) in postings {
+            let offset = u64::try_from(flat.len()).unwrap_or(u64::MAX);
+            let len = u64::try_from(ords.len()).unwrap_or(u64::MAX);
+            grams
+                .insert(&key, (offset << 32) | len)
+                .map_err(|e| FlashError::index(format!("Failed to build name postings: {e}")))?;
+            flat.extend(ords);
+        }
+        let data = SegmentData {
+            entries: unique,
+            grams: grams
+                .into_inner()
+                .map_err(|e| FlashError::index(format!("Failed to build name postings: {e}")))?,
+            postings: flat,
+        };
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&data)
+            .map_err(|e| FlashError::index(format!("Failed to serialize filename index: {e}")))?;
+        match vault::key() {
+            Some(key) => write_durably(
+                &segment_path(dir, id),
+                &vault::seal(key, &bytes).map_err(|e| FlashError::Io(Arc::new(e)))?,
+            )?,
+            None => write_durably(&segment_path(dir, id), &bytes)?,
+        }
+        Self::open(dir, id)
+    }
+
+    /// Maps segment `id` in `dir`, or decrypts it if it is sealed, validating
+    /// it once so later reads can skip the checks.
+    pub fn open(dir: &Path, id: u64) -> Result<Self> {
+        let path = segment_path(dir, id);
+        let file = std::fs::File::open(&path).map_err(|e| FlashError::Io(Arc::new(e)))?;
+        // SAFETY: segment files are written once under a temporary name and
+        // never modified after the rename
+        let mmap = unsafe { memmap2::MmapOptions::new().map(&file) }
+            .map_err(|e| FlashError::Io(Arc::new(e)))?;
+        let bytes = if vault::is_sealed(&mmap) {
+            let key = vault::key()
+                .ok_or_else(|| segment_error(&path, "it is encrypted and the index is locked"))?;
+            let plain = vault::open(key, &mmap).map_err(|e| segment_error(&path, e))?;
+            let mut aligned = rkyv::util::AlignedVec::<16>::with_capacity(plain.len());
+            aligned.extend_from_slice(&plain);
+            SegmentBytes::Decrypted(aligned)
+        } else {
+            SegmentBytes::Mapped(mmap)
+        };
+        let bytes = Arc::new(bytes);
+        // The map is page-aligned and the decrypted copy 16-byte aligned,
+        // which satisfies the archive's alignment
+        let data = rkyv::access::<ArchivedSegmentData, rkyv::rancor::Error>(&bytes)
+            .map_err(|e| segment_error(&path, e))?;
+        let start = data.grams.as_ptr() as usize - bytes.as_ptr() as usize;
+        let grams = fst::Map::new(MappedBytes {
+            bytes: bytes.clone(),
+            start,
+            len: data.grams.len(),
+        })
+        .map_err(|e| segment_error(&path, e))?;
+        Ok(Self {
+            id,
+            path,
+            bytes,
+            grams,
+        })
+    }
+
+    fn data(&self) -> &ArchivedSegmentData {
+        // SAFETY: validated by `rkyv::access` in `open`, and the contents are
+        // never modified
+        unsafe { rkyv::access_unchecked::<ArchivedSegmentData>(&self.bytes) }
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.data().entries.len()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    /// Path, name and kind of the entry at `ord`.
+    pub fn entry(&self, ord: u32) -> Option<(&str, &str, EntryKind)> {
+        self.data()
+            .entries
+            .get(usize::try_from(ord).ok()?)
+            .map(|e| (e.path.as_str(), e.name.as_str(), EntryKind::from(&e.kind)))
+    }
+
+    pub fn find(&self, path: &str) -> Option<u32> {
+        let entries = &self.data().entries;
+        let ord = entries.partition_point(|e| e.path.as_str() < path);
+        if entries.get(ord)?.path.as_str() == path {
+            u32::try_from(ord).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Entries at `root` or anywhere below it.
+    pub fn under(&self, root: &str) -> Vec<u32> {
+        let entries = &self.data().entries;
+        let start = entries.partition_point(|e| e.path.as_str() < root);
+        entries[start..]
+            .iter()
+            .take_while(|e| e.path.as_str().starts_with(root))
+            .enumerate()
+            .filter(|(_, e)| Path::new(e.path.as_str()).starts_with(root))
+            .filter_map(|(i, _)| u32::try_from(start + i).ok())
+            .collect()
+    }
+
+    fn postings(&self, key: &[u8]) -> &[rkyv::Archived<u32>] {
+        let Some(range) = self.grams.get(key) else {
+            return &[];
+        };
+        let offset = usize::try_from(range >> 32).unwrap_or(usize::MAX);
+        let len = usize::try_from(range & u64::from(u32::MAX)).unwrap_or(0);
+        self.data()
+            .postings
+            .get(offset..offset.saturating_add(len))
+            .unwrap_or(&[])
+    }
+
+    /// Entries that have every one of `keys`, in order; every entry when
+    /// `keys` is empty.
+    pub fn candidates(&self, keys: &[Vec<u8>]) -> Vec<u32> {
+        if keys.is_empty() {
+            return (0..u32::try_from(self.entry_count()).unwrap_or(u32::MAX)).collect();
+        }
+        let mut lists: Vec<&[rkyv::Archived<u32>]> =
+            keys.iter().map(|key| self.postings(key)).collect();
+        lists.sort_by_key(|list| list.len());
+        let mut found: Vec<u32> = lists[0].iter().map(|ord| ord.to_native()).collect();
+        for list in &lists[1..] {
+            if found.is_empty() {
+                break;
+            }
+            let mut rest = *list;
+            found.retain(|&ord| {
+                let skip = rest.partition_point(|o| o.to_native() < ord);
+                rest = &rest[skip..];
+                rest.first().is_some_and(|o| o.to_native() == ord)
+            });
+        }
+        found
+    }
+
+    /// Every entry, for compaction and snapshots.
+    pub fn entries(&self) -> impl Iterator<Item = (u32, FilenameEntry)> + '_ {
+        self.data().entries.iter().enumerate().map(|(ord, e)| {
+            (
+                u32::try_from(ord).unwrap_or(u32::MAX),
+                FilenameEntry {
+                    path: e.path.as_str().to_string(),
+                    name: e.name.as_str().into(),
+                    kind: EntryKind::from(&e.kind),
+                },
+            )
+        })
+    }
+}
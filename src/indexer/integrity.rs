@@ -0,0 +1,230 @@
+//! Cross-checks the three stores that describe an indexed file: the metadata
+//! records, the search index and the filename index. They are written one
+//! after another, so a crash, a failed commit or a lost watcher event can
+//! leave them disagreeing about a path.
+
+use super::IndexManager;
+use super::filename_index::FilenameIndex;
+use crate::error::Result;
+use crate::metadata::{FileMetadata, MetadataDb};
+use crate::models::IntegrityReport;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Compares every metadata record, indexed document and filename entry.
+/// Paths under offline roots are skipped, since their files can't be checked.
+pub fn verify_index(
+    indexer: &IndexManager,
+    metadata_db: &MetadataDb,
+    filename_index: Option<&FilenameIndex>,
+) -> Result<IntegrityReport> {
+    let records = metadata_db.get_all_metadata()?;
+    let indexed_files = indexer.get_indexed_files()?;
+    let filenames = filename_index.map(FilenameIndex::paths);
+    let offline = indexer.offline_roots();
+
+    let mut report = IntegrityReport {
+        metadata_records: records.len(),
+        indexed_documents: indexed_files.len(),
+        filename_entries: filenames.as_ref().map_or(0, HashSet::len),
+        ..IntegrityReport::default()
+    };
+
+    let mut indexed: HashMap<&str, (u64, u64)> = HashMap::with_capacity(indexed_files.len());
+    let mut duplicates = BTreeSet::new();
+    for (path, modified, size) in &indexed_files {
+        if indexed.insert(path.as_str(), (*modified, *size)).is_some() {
+            duplicates.insert(path.clone());
+        }
+    }
+    report.duplicate_documents = duplicates.into_iter().collect();
+
+    let recorded: HashSet<&str> = records.iter().map(|r| r.path.as_str()).collect();
+    for record in &records {
+        let path = Path::new(&record.path);
+        if offline.contains(path) {
+            continue;
+        }
+        let Ok(meta) = std::fs::metadata(path) else {
+            report.deleted_files.push(record.path.clone());
+            continue;
+        };
+        match indexed.get(record.path.as_str()) {
+            None => report.missing_documents.push(record.path.clone()),
+            Some(&(modified, size)) if modified != record.modified || size != record.size => {
+                report.hash_mismatches.push(record.path.clone());
+            }
+            Some(_) if content_changed(record, &meta) => {
+                report.hash_mismatches.push(record.path.clone());
+            }
+            Some(_) => {}
+        }
+        if filenames
+            .as_ref()
+            .is_some_and(|names| !names.contains(&record.path))
+        {
+            report.missing_filenames.push(record.path.clone());
+        }
+    }
+
+    for path in indexed.keys().filter(|path| !recorded.contains(*path)) {
+        let file = Path::new(path);
+        if offline.contains(file) {
+            continue;
+        }
+        if file.exists() {
+            report.orphaned_documents.push((*path).to_string());
+        } else {
+            report.deleted_files.push((*path).to_string());
+        }
+    }
+
+    if let Some(names) = &filenames {
+        report.orphaned_filenames = names
+            .iter()
+            .filter(|path| {
+                let file = Path::new(path);
+                !offline.contains(file) && !file.exists()
+            })
+            .cloned()
+            .collect();
+    }
+
+    for list in [
+        &mut report.missing_documents,
+        &mut report.orphaned_documents,
+        &mut report.hash_mismatches,
+        &mut report.deleted_files,
+        &mut report.missing_filenames,
+        &mut report.orphaned_filenames,
+    ] {
+        list.sort_unstable();
+    }
+    Ok(report)
+}
+
+/// Queues the offenders in `report` as an unfinished batch in the metadata
+/// journal, so they go through crash recovery: files that exist are
+/// re-indexed into all three stores, files that are gone are pruned from
+/// them. Recovery runs at the next start, or at once through
+/// `WatcherManager::recover_pending_batches`. Returns how many paths were queued.
+pub fn repair_index(report: &IntegrityReport, metadata_db: &MetadataDb) -> Result<usize> {
+    let reindex: BTreeSet<&String> = report
+        .missing_documents
+        .iter()
+        .chain(&report.orphaned_documents)
+        .chain(&report.duplicate_documents)
+        .chain(&report.hash_mismatches)
+        .chain(&report.missing_filenames)
+        .collect();
+    let prune: BTreeSet<&String> = report
+        .deleted_files
+        .iter()
+        .chain(&report.orphaned_filenames)
+        .collect();
+    if reindex.is_empty() && prune.is_empty() {
+        return Ok(0);
+    }
+
+    let reindex: Vec<String> = reindex.into_iter().cloned().collect();
+    let prune: Vec<String> = prune.into_iter().cloned().collect();
+    metadata_db.begin_batch(&reindex, &prune)?;
+    Ok(reindex.len() + prune.len())
+}
+
+/// Whether the file changed on disk since it was recorded: its size or
+/// modification time differs and so does its hash. Filename-only records
+/// store a hash of the path, so for them a changed stat is enough.
+fn content_changed(record: &FileMetadata, meta: &std::fs::Metadata) -> bool {
+    let modified = meta
+        .modified()
+        .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if modified == record.modified && meta.len() == record.size {
+        return false;
+    }
+    let path_hash: [u8; 32] = blake3::hash(record.path.as_bytes()).into();
+    record.content_hash == path_hash
+        || crate::scanner::get_file_hash(Path::new(&record.path)) != record.content_hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::filename_index::{EntryKind, FilenameEntry};
+    use crate::metadata::FileAttributes;
+    use crate::parsers::ParsedDocument;
+
+    fn stat(path: &Path) -> (u64, u64) {
+        let meta = std::fs::metadata(path).unwrap();
+        let modified = meta
+            .modified()
+            .unwrap()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        (modified, meta.len())
+    }
+
+    #[test]
+    fn test_verify_reports_each_kind_of_discrepancy() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(&dir.path().join("index"), 64).unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let names = FilenameIndex::open(&dir.path().join("names")).unwrap();
+
+        let files = dir.path().join("files");
+        std::fs::create_dir(&files).unwrap();
+        let [ok, unindexed, unrecorded, deleted] =
+            ["ok.txt", "unindexed.txt", "unrecorded.txt", "deleted.txt"].map(|name| {
+                let path = files.join(name);
+                std::fs::write(&path, name).unwrap();
+                path
+            });
+
+        let mut docs = Vec::new();
+        let mut records = Vec::new();
+        for path in [&ok, &unindexed, &unrecorded, &deleted] {
+            let (modified, size) = stat(path);
+            let key = path.to_string_lossy().to_string();
+            if path != &unindexed {
+                docs.push((ParsedDocument::filename_only(path), modified, size));
+            }
+            if path != &unrecorded {
+                let hash = crate::scanner::get_file_hash(path);
+                records.push((key, modified, size, hash, FileAttributes::default()));
+            }
+        }
+        indexer.add_documents_batch(&docs).unwrap();
+        indexer.commit().unwrap();
+        db.batch_update_metadata(&records).unwrap();
+        names
+            .add_files_batch(vec![
+                FilenameEntry::new(&ok, EntryKind::File).unwrap(),
+                FilenameEntry::new(&files, EntryKind::Directory).unwrap(),
+            ])
+            .unwrap();
+        names.commit().unwrap();
+        std::fs::remove_file(&deleted).unwrap();
+
+        let report = verify_index(&indexer, &db, Some(&names)).unwrap();
+        let key = |path: &Path| vec![path.to_string_lossy().to_string()];
+        assert_eq!(report.missing_documents, key(&unindexed));
+        assert_eq!(report.orphaned_documents, key(&unrecorded));
+        assert_eq!(report.deleted_files, key(&deleted));
+        assert_eq!(report.missing_filenames, key(&unindexed));
+        assert!(report.duplicate_documents.is_empty());
+        assert!(report.hash_mismatches.is_empty());
+        assert!(report.orphaned_filenames.is_empty());
+
+        assert_eq!(repair_index(&report, &db).unwrap(), 3);
+        let pending = db.get_pending_batches().unwrap();
+        assert_eq!(
+            pending[0].indexed,
+            [key(&unindexed), key(&unrecorded)].concat()
+        );
+        assert_eq!(pending[0].removed, key(&deleted));
+    }
+}
@@ -0,0 +1,327 @@
+//! Carries an index written under an older schema over to the current one.
+//! Stored and fast fields are copied document by document into a fresh index
+//! next to the old one, which is then swapped in. Fields that are indexed but
+//! not stored (the extracted content) can't be copied, so only documents that
+//! had text in one of them are queued for re-parsing.
+
+use super::encrypted_directory::open_directory;
+use super::schema::{OWNER_TOKENIZER, create_schema, owner_analyzer};
+use super::writer::IndexWriterManager;
+use super::{SCHEMA_VERSION, code_tokenizer, write_schema_version};
+use crate::error::{FlashError, Result};
+use crate::metadata::FileAttributes;
+use crate::parsers::ParsedDocument;
+use crate::system::vault;
+use std::path::{Path, PathBuf};
+use tantivy::schema::{Field, Schema, Value};
+use tantivy::{DocAddress, Index, TantivyDocument};
+use tracing::{info, warn};
+
+/// Documents handed to the writer per batch
+const BATCH_SIZE: usize = 1000;
+
+/// Paths left for the scanner to re-parse, written next to the migrated index
+/// so they survive a crash before they are journaled.
+pub const REPARSE_FILENAME: &str = ".migration_reparse";
+
+/// Outcome of a completed migration.
+#[derive(Debug, Default)]
+pub struct MigrationOutcome {
+    pub migrated: usize,
+    /// Documents whose content has to be extracted again
+    pub reparse: Vec<String>,
+}
+
+fn register_tokenizers(index: &Index) {
+    index.tokenizers().register(
+        code_tokenizer::CODE_TOKENIZER,
+        code_tokenizer::code_analyzer(),
+    );
+    index
+        .tokenizers()
+        .register(OWNER_TOKENIZER, owner_analyzer());
+}
+
+/// Fields the writer fills from parsed content without storing it. A document
+/// needs re-parsing if it had tokens in any of them.
+fn unstored_fields(schema: &Schema) -> Vec<&str> {
+    schema
+        .fields()
+        .filter(|(_, entry)| entry.is_indexed() && !entry.is_stored() && !entry.is_fast())
+        .map(|(_, entry)| entry.name())
+        .collect()
+}
+
+fn first_text(doc: &TantivyDocument, field: Option<Field>) -> Option<String> {
+    field
+        .and_then(|f| doc.get_first(f))
+        .and_then(|v| v.as_str())
+        .map(ToString::to_string)
+}
+
+/// Rebuilds the index at `index_path` under the current schema and swaps it
+/// in, keeping the old one as `index.backup`. On error the old index is left
+/// where it was.
+pub fn migrate_index(index_path: &Path, memory_limit_mb: u32) -> Result<MigrationOutcome> {
+    let staging = index_path.with_extension("migrating");
+    if let Err(e) = std::fs::remove_dir_all(&staging)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        return Err(FlashError::Io(std::sync::Arc::new(e)));
+    }
+    std::fs::create_dir_all(&staging).map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+
+    let outcome = match copy_documents(index_path, &staging, memory_limit_mb) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+    write_schema_version(&staging, SCHEMA_VERSION)?;
+    if !outcome.reparse.is_empty() {
+        let list = serde_json::to_vec(&outcome.reparse)
+            .map_err(|e| FlashError::index(format!("Failed to record re-parse list: {e}")))?;
+        // Sealed like the index it lists paths of
+        vault::protect(list)
+            .and_then(|list| std::fs::write(staging.join(REPARSE_FILENAME), list))
+            .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+    }
+
+    swap_in(index_path, &staging)?;
+    info!(
+        "Migrated {} document(s) to schema {}, {} queued for re-parsing",
+        outcome.migrated,
+        SCHEMA_VERSION,
+        outcome.reparse.len()
+    );
+    Ok(outcome)
+}
+
+/// Streams every live document of the old index into a new one at `staging`.
+/// The old index is only read, and is closed again before the swap.
+fn copy_documents(
+    index_path: &Path,
+    staging: &Path,
+    memory_limit_mb: u32,
+) -> Result<MigrationOutcome> {
+    let old = open_directory(index_path)
+        .map_err(tantivy::TantivyError::from)
+        .and_then(Index::open)
+        .map_err(|e| FlashError::index(format!("Failed to open old index: {e}")))?;
+    let old_schema = old.schema();
+    let new_schema = create_schema();
+    let new = open_directory(staging)
+        .map_err(tantivy::TantivyError::from)
+        .and_then(|dir| Index::builder().schema(new_schema.clone()).create(dir))
+        .map_err(|e| FlashError::index(format!("Failed to create migrated index: {e}")))?;
+    register_tokenizers(&new);
+    let writer = IndexWriterManager::new(&new, memory_limit_mb)?;
+
+    let field = |name: &str| old_schema.get_field(name).ok();
+    let path_field = field("file_path")
+        .ok_or_else(|| FlashError::index_field("file_path", "Field not found in old schema"))?;
+    let title = field("title");
+    let language = field("language");
+    let keywords = field("keywords");
+    let annotations = field("annotations");
+    let layout = field("layout");
+    let code_metadata = field("code_metadata");
+    let owner = field("owner");
+    let flags = field("flags");
+    let volume = field("volume");
+    // A content field the old schema lacked can't be checked, so every
+    // document is re-parsed for it
+    let reparse_fields: Option<Vec<Field>> = unstored_fields(&new_schema)
+        .into_iter()
+        .map(field)
+        .collect();
+
+    let reader = old
+        .reader()
+        .map_err(|e| FlashError::index(format!("Failed to read old index: {e}")))?;
+    let searcher = reader.searcher();
+    let mut outcome = MigrationOutcome::default();
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+
+    for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+        let fast_fields = segment.fast_fields();
+        let sizes = fast_fields.u64("size").ok();
+        let modified = fast_fields.date("modified").ok();
+        let created = fast_fields.date("created").ok();
+        let fieldnorms = reparse_fields.as_ref().and_then(|fields| {
+            fields
+                .iter()
+                .map(|f| segment.get_fieldnorms_reader(*f))
+                .collect::<tantivy::Result<Vec<_>>>()
+                .ok()
+        });
+
+        for doc_id in segment.doc_ids_alive() {
+            let address = DocAddress::new(u32::try_from(segment_ord).unwrap_or(u32::MAX), doc_id);
+            let doc: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| FlashError::index(format!("Failed to read document: {e}")))?;
+            let Some(path) = first_text(&doc, Some(path_field)) else {
+                continue;
+            };
+
+            let flag_names: Vec<&str> = flags
+                .map(|f| doc.get_all(f).filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            let secs = |date: tantivy::DateTime| u64::try_from(date.into_timestamp_secs()).ok();
+            let attributes = FileAttributes {
+                owner: first_text(&doc, owner),
+                readonly: flag_names.contains(&"readonly"),
+                hidden: flag_names.contains(&"hidden"),
+                created: created
+                    .as_ref()
+                    .and_then(|c| c.first(doc_id))
+                    .and_then(secs),
+                volume: first_text(&doc, volume),
+            };
+            let needs_reparse = fieldnorms
+                .as_ref()
+                .is_none_or(|readers| readers.iter().any(|reader| reader.fieldnorm_id(doc_id) > 0));
+
+            if needs_reparse {
+                outcome.reparse.push(path.clone());
+            }
+            let parsed = ParsedDocument {
+                path,
+                content: String::new(),
+                title: first_text(&doc, title).map(Into::into),
+                language: first_text(&doc, language).map(Into::into),
+                keywords: first_text(&doc, keywords),
+                layout: first_text(&doc, layout),
+                code_metadata: first_text(&doc, code_metadata),
+                embeddings: None,
+                annotations: first_text(&doc, annotations),
+                attributes,
+            };
+            let mtime = modified
+                .as_ref()
+                .and_then(|m| m.first(doc_id))
+                .and_then(secs)
+                .unwrap_or(0);
+            let size = sizes.as_ref().and_then(|s| s.first(doc_id)).unwrap_or(0);
+
+            batch.push((parsed, mtime, size));
+            if batch.len() >= BATCH_SIZE {
+                outcome.migrated += batch.len();
+                writer.add_documents_batch(&std::mem::take(&mut batch))?;
+            }
+        }
+    }
+    outcome.migrated += batch.len();
+    writer.add_documents_batch(&batch)?;
+    writer.commit()?;
+    Ok(outcome)
+}
+
+/// Moves the old index aside and the migrated one into its place, putting the
+/// old one back if the second rename fails.
+fn swap_in(index_path: &Path, staging: &Path) -> Result<()> {
+    let backup_path = index_path.with_extension("backup");
+    if let Err(e) = std::fs::remove_dir_all(&backup_path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Failed to remove old backup at {:?}: {}", backup_path, e);
+    }
+    std::fs::rename(index_path, &backup_path)
+        .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+    if let Err(e) = std::fs::rename(staging, index_path) {
+        if let Err(restore) = std::fs::rename(&backup_path, index_path) {
+            warn!("Failed to put the old index back: {}", restore);
+        }
+        return Err(FlashError::Io(std::sync::Arc::new(e)));
+    }
+    Ok(())
+}
+
+fn reparse_path(index_path: &Path) -> PathBuf {
+    index_path.join(REPARSE_FILENAME)
+}
+
+/// Paths a migration left for re-parsing, until [`clear_reparse_list`].
+pub fn read_reparse_list(index_path: &Path) -> Vec<String> {
+    vault::read(&reparse_path(index_path))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn clear_reparse_list(index_path: &Path) -> Result<()> {
+    match std::fs::remove_file(reparse_path(index_path)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(FlashError::Io(std::sync::Arc::new(e)))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::IndexManager;
+    use tantivy::doc;
+    use tantivy::schema::{FAST, INDEXED, STORED, STRING, TEXT};
+
+    /// Writes an index under a schema from before owner, flags and categories
+    fn write_legacy_index(index_path: &Path) {
+        let mut builder = Schema::builder();
+        let path = builder.add_text_field("file_path", STRING | STORED);
+        let content = builder.add_text_field("content", TEXT);
+        builder.add_text_field("identifiers", TEXT);
+        let title = builder.add_text_field("title", TEXT | STORED);
+        let modified = builder.add_date_field("modified", FAST | INDEXED);
+        let size = builder.add_u64_field("size", FAST | INDEXED);
+        std::fs::create_dir_all(index_path).unwrap();
+        let index = Index::create_in_dir(index_path, builder.build()).unwrap();
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        writer
+            .add_document(doc!(
+                path => "/docs/report.txt",
+                content => "quarterly figures",
+                title => "Report",
+                modified => tantivy::DateTime::from_timestamp_secs(1_700_000_000),
+                size => 42u64,
+            ))
+            .unwrap();
+        writer
+            .add_document(doc!(
+                path => "/docs/archive.bin",
+                content => "",
+                modified => tantivy::DateTime::from_timestamp_secs(1_600_000_000),
+                size => 7u64,
+            ))
+            .unwrap();
+        writer.commit().unwrap();
+        write_schema_version(index_path, "1.0.0").unwrap();
+    }
+
+    #[test]
+    fn test_old_schema_is_migrated_and_content_queued() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_path = dir.path().join("index");
+        write_legacy_index(&index_path);
+
+        let indexer = IndexManager::open(&index_path, 64).unwrap();
+        let mut files = indexer.get_indexed_files().unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                ("/docs/archive.bin".to_string(), 1_600_000_000, 7),
+                ("/docs/report.txt".to_string(), 1_700_000_000, 42),
+            ]
+        );
+        // Only the document that had content needs it extracted again
+        assert_eq!(read_reparse_list(&index_path), ["/docs/report.txt"]);
+        assert!(index_path.with_extension("backup").exists());
+        assert!(!index_path.with_extension("migrating").exists());
+
+        clear_reparse_list(&index_path).unwrap();
+        assert!(read_reparse_list(&index_path).is_empty());
+    }
+}
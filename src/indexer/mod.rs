@@ -1,21 +1,33 @@
+pub mod category;
+pub mod code_tokenizer;
+mod encrypted_directory;
 pub mod filename_index;
+pub mod integrity;
+pub mod migration;
 pub mod query_parser;
 pub mod schema;
 pub mod searcher;
+mod shard;
 pub mod writer;
 
-use self::schema::create_schema;
-use self::searcher::{IndexSearcher, IndexStatistics, SearchResult};
-use self::writer::IndexWriterManager;
+use self::searcher::{IndexStatistics, SearchResult};
+use self::shard::Shard;
 use crate::error::{FlashError, Result};
 use crate::parsers::ParsedDocument;
+use crate::scanner::network::OfflineRoots;
+use crate::scanner::volumes;
+use crate::settings::{IndexRoot, IndexWriterSettings};
+use parking_lot::{Mutex, RwLock};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tantivy::{Index, directory::MmapDirectory};
-use tracing::{error, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
 
 /// Current schema version - bump this when schema changes
-pub const SCHEMA_VERSION: &str = "1.3.0";
+pub const SCHEMA_VERSION: &str = "1.9.0";
+
+/// Directory next to the default index holding one index per separate root
+pub const SHARDS_DIR: &str = "index_shards";
 
 fn get_schema_version_path(index_path: &Path) -> PathBuf {
     index_path.join(".schema_version")
@@ -32,204 +44,582 @@ fn write_schema_version(index_path: &Path, version: &str) -> Result<()> {
         .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))
 }
 
-/// Central manager for the Tantivy search index
+/// Shard directory name for `root`, stable across runs
+fn shard_dir_name(root: &Path) -> String {
+    let hash = blake3::hash(root.to_string_lossy().as_bytes());
+    hash.to_hex()[..16].to_string()
+}
+
+/// Central manager for the Tantivy search index. Documents live in the
+/// default index unless their root is indexed separately, in which case they
+/// go to that root's shard. Searches run over every shard.
 pub struct IndexManager {
-    #[allow(dead_code)]
-    index: Index,
-    writer: IndexWriterManager,
-    searcher: Arc<IndexSearcher>,
+    /// The default index first, then one shard per separate root
+    shards: RwLock<Vec<Arc<Shard>>>,
+    offline_roots: OfflineRoots,
+    shards_dir: PathBuf,
+    memory_limit_mb: u32,
+    code_aware: AtomicBool,
+    writer_settings: Mutex<IndexWriterSettings>,
 }
 
 impl IndexManager {
-    fn rebuild_index_internal(index_path: &Path) -> Result<()> {
-        // Try to backup the index before destroying it
-        let backup_path = index_path.with_extension("backup");
-        if let Err(e) = std::fs::remove_dir_all(&backup_path)
-            && e.kind() != std::io::ErrorKind::NotFound
-        {
-            warn!("Failed to remove old backup at {:?}: {}", backup_path, e);
+    /// Open or create the default index at the specified path. Shards for
+    /// separate roots are attached by [`Self::sync_shards`].
+    pub fn open(index_path: &Path, memory_limit_mb: u32) -> Result<Self> {
+        let default = Shard::open(index_path, None, memory_limit_mb)?;
+        Ok(Self {
+            shards: RwLock::new(vec![Arc::new(default)]),
+            offline_roots: OfflineRoots::default(),
+            shards_dir: index_path.with_file_name(SHARDS_DIR),
+            memory_limit_mb,
+            code_aware: AtomicBool::new(true),
+            writer_settings: Mutex::new(IndexWriterSettings::default()),
+        })
+    }
+
+    fn shards(&self) -> Vec<Arc<Shard>> {
+        self.shards.read().clone()
+    }
+
+    fn default_shard(&self) -> Arc<Shard> {
+        self.shards.read()[0].clone()
+    }
+
+    /// The shard holding `path`: the innermost separate root containing it,
+    /// or the default index.
+    fn shard_for(&self, path: &str) -> Arc<Shard> {
+        let path = Path::new(path);
+        let shards = self.shards.read();
+        shards
+            .iter()
+            .filter(|shard| shard.holds(path))
+            .max_by_key(|shard| shard.root.as_ref().map_or(0, |r| r.as_os_str().len()))
+            .unwrap_or(&shards[0])
+            .clone()
+    }
+
+    /// Splits `items` by the shard each one's path belongs to
+    fn group_by_shard<'a, T>(
+        &self,
+        items: &'a [T],
+        path: impl Fn(&T) -> &str,
+    ) -> Vec<(Arc<Shard>, Vec<&'a T>)> {
+        let mut groups: Vec<(Arc<Shard>, Vec<&'a T>)> = Vec::new();
+        for item in items {
+            let shard = self.shard_for(path(item));
+            match groups.iter_mut().find(|(s, _)| Arc::ptr_eq(s, &shard)) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((shard, vec![item])),
+            }
         }
-        if index_path.exists()
-            && let Err(e) = std::fs::rename(index_path, &backup_path)
+        groups
+    }
+
+    /// Opens a shard for every root marked as indexed separately and drops
+    /// the shards of roots that no longer are. Returns the roots whose
+    /// documents changed index and must be re-indexed; their documents are
+    /// already gone from the index they moved out of.
+    pub fn sync_shards(&self, roots: &[IndexRoot]) -> Result<Vec<PathBuf>> {
+        let wanted: Vec<PathBuf> = roots
+            .iter()
+            .filter(|root| root.separate_index)
+            .map(|root| PathBuf::from(&root.path))
+            .collect();
+        let mut moved = Vec::new();
+
+        let detached: Vec<Arc<Shard>> = {
+            let mut shards = self.shards.write();
+            let (keep, detached): (Vec<_>, Vec<_>) = std::mem::take(&mut *shards)
+                .into_iter()
+                .partition(|shard| shard.root.as_ref().is_none_or(|r| wanted.contains(r)));
+            *shards = keep;
+            detached
+        };
+        for shard in detached {
+            let root = shard.root.clone().unwrap_or_default();
+            info!("{} is no longer indexed separately", root.display());
+            let path = shard.path.clone();
+            drop(shard);
+            if let Err(e) = std::fs::remove_dir_all(&path) {
+                warn!("Failed to remove shard at {:?}: {}", path, e);
+            }
+            moved.push(root);
+        }
+
+        for root in wanted {
+            if self
+                .shards
+                .read()
+                .iter()
+                .any(|s| s.root.as_ref() == Some(&root))
+            {
+                continue;
+            }
+            let path = self.shards_dir.join(shard_dir_name(&root));
+            let is_new = !path.join("meta.json").exists();
+            let shard = Shard::open(&path, Some(root.clone()), self.memory_limit_mb)?;
+            shard
+                .writer
+                .set_code_aware(self.code_aware.load(Ordering::Relaxed));
+            shard.writer.configure(&self.writer_settings.lock());
+            self.shards.write().push(Arc::new(shard));
+            if is_new {
+                info!("Indexing {} separately", root.display());
+                Self::remove_under(&self.default_shard(), &root)?;
+                moved.push(root);
+            }
+        }
+
+        // Shards left from a previous session: the root was dropped from the
+        // settings, or lost its separate index, while the app was closed.
+        // Migration backups next to them are left alone.
+        let live: Vec<PathBuf> = self.shards().iter().map(|s| s.path.clone()).collect();
+        for entry in std::fs::read_dir(&self.shards_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
         {
-            warn!(
-                "Failed to atomic rename index to {:?}: {}. Falling back to copy.",
-                backup_path, e
-            );
-            if let Err(e) = copy_dir(index_path, &backup_path) {
-                warn!("Failed to backup index to {:?}: {}", backup_path, e);
+            let path = entry.path();
+            if path.extension().is_some() || live.contains(&path) {
+                continue;
             }
-            if let Err(e) = std::fs::remove_dir_all(index_path) {
-                error!(
-                    "Failed to remove corrupted index at {:?}: {}",
-                    index_path, e
-                );
-                return Err(FlashError::Io(std::sync::Arc::new(e)));
+            if let Err(e) = std::fs::remove_dir_all(&path) {
+                warn!("Failed to remove stale shard at {:?}: {}", path, e);
+            }
+            let name = entry.file_name();
+            if let Some(root) = roots
+                .iter()
+                .map(|root| PathBuf::from(&root.path))
+                .find(|root| name.to_string_lossy() == shard_dir_name(root))
+            {
+                moved.push(root);
             }
         }
+        Ok(moved)
+    }
 
-        if let Err(e) = std::fs::create_dir_all(index_path) {
-            error!(
-                "Failed to re-create index directory at {:?}: {}",
-                index_path, e
-            );
-            return Err(FlashError::Io(std::sync::Arc::new(e)));
+    /// Deletes the documents under `root` from `shard` and commits.
+    fn remove_under(shard: &Shard, root: &Path) -> Result<usize> {
+        let paths: Vec<String> = shard
+            .searcher
+            .get_indexed_files()?
+            .into_iter()
+            .map(|(path, ..)| path)
+            .filter(|path| Path::new(path).starts_with(root))
+            .collect();
+        if !paths.is_empty() {
+            shard.writer.remove_documents_batch(&paths)?;
+            shard.writer.commit()?;
         }
-        write_schema_version(index_path, SCHEMA_VERSION)?;
-        Ok(())
+        Ok(paths.len())
     }
 
-    /// Open or create index at the specified path
-    pub fn open(index_path: &Path, memory_limit_mb: u32) -> Result<Self> {
-        let schema = create_schema();
-
-        // Ensure directory exists
-        if !index_path.exists() {
-            std::fs::create_dir_all(index_path)
-                .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
-        }
-
-        // Check schema version - if mismatch, rebuild index
-        let stored_version = read_schema_version(index_path);
-        if let Some(ref ver) = stored_version {
-            if ver != SCHEMA_VERSION {
-                warn!(
-                    "Schema version mismatch: stored={}, current={}. Rebuilding index...",
-                    ver, SCHEMA_VERSION
-                );
-                Self::rebuild_index_internal(index_path)?;
+    /// Empties the index holding `root`: its own shard at once, or its
+    /// documents in the default index. The root then needs a fresh scan.
+    pub fn clear_root(&self, root: &Path) -> Result<()> {
+        let shard = self
+            .shards()
+            .into_iter()
+            .find(|shard| shard.root.as_deref() == Some(root));
+        match shard {
+            Some(shard) => {
+                shard.writer.delete_all_documents()?;
+                shard.writer.commit()?;
             }
-        } else if index_path.join("meta.json").exists() {
-            // Old index without version - rebuild
-            warn!("No schema version found. Rebuilding index...");
-            Self::rebuild_index_internal(index_path)?;
-        } else {
-            // New index - write version
-            write_schema_version(index_path, SCHEMA_VERSION)?;
-        }
-
-        let directory = MmapDirectory::open(index_path)
-            .map_err(|e| FlashError::index(format!("Failed to open index directory: {e}")))?;
-
-        let index = match Index::open_or_create(directory, schema.clone()) {
-            Ok(idx) => idx,
-            Err(e) => {
-                // Check if it's a schema mismatch error
-                let err_str = e.to_string();
-                if err_str.contains("Schema error") || err_str.contains("Inconsistent") {
-                    warn!(
-                        "Tantivy detected schema mismatch: {}. Forcing index rebuild...",
-                        err_str
-                    );
-
-                    Self::rebuild_index_internal(index_path)?;
-
-                    let new_directory = MmapDirectory::open(index_path).map_err(|e| {
-                        FlashError::index(format!("Failed to re-open index directory: {e}"))
-                    })?;
-                    Index::open_or_create(new_directory, schema).map_err(|e| {
-                        FlashError::index(format!("Failed to create new index after reset: {e}"))
-                    })?
-                } else {
-                    return Err(FlashError::index(format!(
-                        "Failed to open or create index: {e}"
-                    )));
-                }
+            None => {
+                Self::remove_under(&self.default_shard(), root)?;
             }
-        };
-
-        info!(
-            "Opened index at {} with schema version {}",
-            index_path.display(),
-            SCHEMA_VERSION
-        );
-
-        let writer = IndexWriterManager::new(&index, memory_limit_mb)?;
-        let searcher = IndexSearcher::new(&index, index_path.to_path_buf())?;
+        }
+        Ok(())
+    }
 
-        Ok(Self {
-            index,
-            writer,
-            searcher: Arc::new(searcher),
-        })
+    /// Roots currently indexed in a shard of their own
+    pub fn separate_roots(&self) -> Vec<PathBuf> {
+        self.shards()
+            .iter()
+            .filter_map(|s| s.root.clone())
+            .collect()
     }
 
     /// Add a document to the index
     pub fn add_document(&self, doc: &ParsedDocument, modified: u64, size: u64) -> Result<()> {
-        self.writer.add_document(doc, modified, size)
+        self.shard_for(&doc.path)
+            .writer
+            .add_document(doc, modified, size)
     }
 
-    /// Add multiple documents in a single lock acquisition (much more efficient)
+    /// Add multiple documents in a single lock acquisition per shard
     pub fn add_documents_batch(&self, docs: &[(ParsedDocument, u64, u64)]) -> Result<()> {
-        self.writer.add_documents_batch(docs)
+        for (shard, group) in self.group_by_shard(docs, |(doc, ..)| doc.path.as_str()) {
+            shard.writer.add_documents_batch(group)?;
+        }
+        Ok(())
+    }
+
+    /// Toggle identifier splitting for source files added from now on
+    pub fn set_code_aware_indexing(&self, enabled: bool) {
+        self.code_aware.store(enabled, Ordering::Relaxed);
+        for shard in self.shards() {
+            shard.writer.set_code_aware(enabled);
+        }
     }
 
     /// Remove a document from the index
     pub fn remove_document(&self, path: &str) -> Result<()> {
-        self.writer.remove_document(path)
+        self.shard_for(path).writer.remove_document(path)
+    }
+
+    /// Remove multiple documents, e.g. everything under a deleted directory
+    pub fn remove_documents_batch(&self, paths: &[String]) -> Result<()> {
+        for (shard, group) in self.group_by_shard(paths, String::as_str) {
+            let group: Vec<String> = group.into_iter().cloned().collect();
+            shard.writer.remove_documents_batch(&group)?;
+        }
+        Ok(())
     }
 
-    /// Clear all documents from the index
+    /// Clear all documents from every shard
     pub fn clear(&self) -> Result<()> {
-        self.writer.delete_all_documents()
+        for shard in self.shards() {
+            shard.writer.delete_all_documents()?;
+        }
+        Ok(())
     }
 
-    /// Commit pending changes
+    /// Commit pending changes and make them searchable
     pub fn commit(&self) -> Result<()> {
-        self.writer.commit()
+        for shard in self.shards() {
+            shard.writer.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Commit if the configured size or interval was reached. Documents added
+    /// in between stay invisible to search, and the cache stays valid.
+    pub fn commit_if_due(&self) -> Result<bool> {
+        let mut committed = false;
+        for shard in self.shards() {
+            committed |= shard.writer.commit_if_due()?;
+        }
+        Ok(committed)
+    }
+
+    /// Apply commit pacing and the segment merge policy
+    pub fn configure_writer(&self, settings: &IndexWriterSettings) {
+        *self.writer_settings.lock() = *settings;
+        for shard in self.shards() {
+            shard.writer.configure(settings);
+        }
+    }
+
+    /// Force-merge each shard into one segment; returns how many were merged
+    pub fn optimize(&self) -> Result<usize> {
+        let mut merged = 0;
+        for shard in self.shards() {
+            merged += shard.writer.optimize()?;
+        }
+        Ok(merged)
+    }
+
+    /// Warm every shard's segments so the first searches don't stall on
+    /// them; returns how many were warmed. Commits warm their shard in the
+    /// background on their own, so this is for startup.
+    pub fn warm(&self) -> Result<usize> {
+        let mut warmed = 0;
+        for shard in self.shards() {
+            warmed += shard.searcher.warm()?;
+        }
+        Ok(warmed)
     }
 
-    /// Search the index (async with caching)
+    /// Number of segments across all shards
+    pub fn segment_count(&self) -> usize {
+        self.shards()
+            .iter()
+            .map(|shard| shard.searcher.segment_count())
+            .sum()
+    }
+
+    /// Run `f` with commits held off in every shard, e.g. while the index
+    /// files are copied
+    pub fn with_commits_paused<T>(&self, f: impl FnOnce() -> T) -> T {
+        fn pause<T>(shards: &[Arc<Shard>], f: Box<dyn FnOnce() -> T + '_>) -> T {
+            match shards.split_first() {
+                Some((first, rest)) => first.writer.with_commits_paused(move || pause(rest, f)),
+                None => f(),
+            }
+        }
+        pause(&self.shards(), Box::new(f))
+    }
+
+    /// Search every shard (async with caching). Scores come from each
+    /// shard's own term statistics, so they are close but not exact across
+    /// shards.
     pub async fn search(
         self: &Arc<Self>,
         params: searcher::SearchParams<'_>,
     ) -> Result<Vec<SearchResult>> {
-        self.searcher.search(params).await
+        let shards = self.shards();
+        let limit = params.limit;
+        let mut results = Vec::new();
+        for shard in &shards {
+            results.extend(shard.searcher.search(params.clone()).await?);
+        }
+        if shards.len() > 1 {
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(limit);
+        }
+        self.mark_unavailable(&mut results);
+        Ok(results)
     }
 
     /// Get recent files
     pub fn get_recent_files(&self, limit: usize) -> Result<Vec<SearchResult>> {
-        self.searcher.get_recent_files(limit)
+        let shards = self.shards();
+        let mut results = Vec::new();
+        for shard in &shards {
+            results.extend(shard.searcher.get_recent_files(limit)?);
+        }
+        if shards.len() > 1 {
+            results.sort_by(|a, b| b.modified.cmp(&a.modified));
+            results.truncate(limit);
+        }
+        self.mark_unavailable(&mut results);
+        Ok(results)
+    }
+
+    /// Documents across all shards most like `text`, the content of `path`.
+    /// Scores come from each shard's own statistics, as in [`Self::search`].
+    pub fn more_like_this(
+        &self,
+        path: &str,
+        text: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let shards = self.shards();
+        let mut results = Vec::new();
+        for shard in &shards {
+            results.extend(shard.searcher.more_like_this(path, text, limit)?);
+        }
+        if shards.len() > 1 {
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(limit);
+        }
+        self.mark_unavailable(&mut results);
+        Ok(results)
     }
 
-    /// Invalidate search cache (call after index updates)
+    /// How many documents across all shards have `term` in their content
+    pub fn term_doc_freq(&self, term: &str) -> Result<u64> {
+        let mut total = 0;
+        for shard in self.shards() {
+            total += shard.searcher.term_doc_freq(term)?;
+        }
+        Ok(total)
+    }
+
+    /// Content terms close to `word` across all shards, with their document
+    /// counts summed
+    pub fn similar_terms(&self, word: &str, max_distance: usize) -> Result<Vec<(String, u64)>> {
+        let mut found: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+        for shard in self.shards() {
+            for (term, freq) in shard.searcher.similar_terms(word, max_distance)? {
+                *found.entry(term).or_default() += freq;
+            }
+        }
+        Ok(found.into_iter().collect())
+    }
+
+    /// Every indexed document as (path, modified, size), duplicates included
+    pub fn get_indexed_files(&self) -> Result<Vec<(String, u64, u64)>> {
+        let mut files = Vec::new();
+        for shard in self.shards() {
+            files.extend(shard.searcher.get_indexed_files()?);
+        }
+        Ok(files)
+    }
+
+    /// Documents with extracted content across all shards, modified longest
+    /// ago first
+    pub fn get_files_with_content(&self) -> Result<Vec<(String, u64, u64)>> {
+        let mut files = Vec::new();
+        for shard in self.shards() {
+            files.extend(shard.searcher.get_files_with_content()?);
+        }
+        files.sort_by_key(|(_, modified, _)| *modified);
+        Ok(files)
+    }
+
+    /// Paths a schema migration copied without their content, to be re-parsed
+    pub fn pending_reparse(&self) -> Vec<String> {
+        self.shards()
+            .iter()
+            .flat_map(|shard| migration::read_reparse_list(&shard.path))
+            .collect()
+    }
+
+    /// Forget the re-parse list once it has been handed to the journal
+    pub fn clear_pending_reparse(&self) -> Result<()> {
+        for shard in self.shards() {
+            migration::clear_reparse_list(&shard.path)?;
+        }
+        Ok(())
+    }
+
+    /// Roots whose share is currently unreachable
+    pub const fn offline_roots(&self) -> &OfflineRoots {
+        &self.offline_roots
+    }
+
+    // Flagged after the search cache so results flip back once a share or
+    // drive returns
+    fn mark_unavailable(&self, results: &mut [SearchResult]) {
+        for result in results {
+            result.unavailable = self.offline_roots.contains(Path::new(&result.file_path))
+                || result.volume.as_deref().is_some_and(volumes::is_offline);
+        }
+    }
+
+    /// Drop cached search results. Commits already do this for their shard.
     pub fn invalidate_cache(&self) {
-        self.searcher.invalidate_cache();
+        for shard in self.shards() {
+            shard.searcher.invalidate_cache();
+        }
     }
 
-    /// Get index statistics
-    pub fn get_statistics(&self) -> Result<IndexStatistics> {
-        self.searcher.get_statistics()
+    /// Reload every shard's reader so the last commit is searchable now, and
+    /// drop cached results from before it.
+    pub fn reload_readers(&self) {
+        for shard in self.shards() {
+            shard.searcher.refresh();
+        }
     }
 
-    /// Get the searcher for direct document access
-    pub const fn get_searcher(&self) -> &Arc<IndexSearcher> {
-        &self.searcher
+    /// Get index statistics, summed over every shard
+    pub fn get_statistics(&self) -> Result<IndexStatistics> {
+        let mut total = IndexStatistics::default();
+        for shard in self.shards() {
+            let stats = shard.searcher.get_statistics()?;
+            total.total_documents += stats.total_documents;
+            total.total_size_bytes += stats.total_size_bytes;
+            total.cache_hits += stats.cache_hits;
+            total.cache_misses += stats.cache_misses;
+        }
+        Ok(total)
     }
 }
 
-fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
-    std::fs::create_dir_all(dst)?;
-    for entry in ignore::WalkBuilder::new(src)
-        .hidden(false)
-        .git_ignore(false)
-        .ignore(false)
-        .parents(false)
-        .build()
-        .skip(1)
-    {
-        let entry = entry.map_err(std::io::Error::other)?;
-        let ty = entry.file_type().ok_or_else(|| {
-            std::io::Error::new(std::io::ErrorKind::NotFound, "Could not get file type")
-        })?;
-        let path = entry.path();
-        let relative = path.strip_prefix(src).map_err(std::io::Error::other)?;
-        let target = dst.join(relative);
-        if ty.is_dir() {
-            std::fs::create_dir_all(target)?;
-        } else {
-            std::fs::copy(path, target)?;
-        }
-    }
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(path: &str) -> (ParsedDocument, u64, u64) {
+        (ParsedDocument::filename_only(Path::new(path)), 0, 1)
+    }
+
+    fn indexed(indexer: &IndexManager) -> Vec<String> {
+        let mut paths: Vec<String> = indexer
+            .get_indexed_files()
+            .unwrap()
+            .into_iter()
+            .map(|(path, ..)| path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_separate_roots_get_their_own_shard() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(&dir.path().join("index"), 64).unwrap();
+        indexer
+            .add_documents_batch(&[doc("/data/a/x.txt"), doc("/data/b/y.txt")])
+            .unwrap();
+        indexer.commit().unwrap();
+
+        let roots = [
+            IndexRoot::new("/data/a"),
+            IndexRoot {
+                separate_index: true,
+                ..IndexRoot::new("/data/b")
+            },
+        ];
+        // The root's documents leave the default index for a fresh scan
+        assert_eq!(
+            indexer.sync_shards(&roots).unwrap(),
+            [PathBuf::from("/data/b")]
+        );
+        assert!(indexer.sync_shards(&roots).unwrap().is_empty());
+        assert_eq!(indexed(&indexer), ["/data/a/x.txt"]);
+
+        indexer
+            .add_documents_batch(&[doc("/data/a/z.txt"), doc("/data/b/y.txt")])
+            .unwrap();
+        indexer.commit().unwrap();
+        assert_eq!(
+            indexed(&indexer),
+            ["/data/a/x.txt", "/data/a/z.txt", "/data/b/y.txt"]
+        );
+        assert_eq!(indexer.separate_roots(), [PathBuf::from("/data/b")]);
+
+        // Clearing one root leaves the others alone
+        indexer.clear_root(Path::new("/data/b")).unwrap();
+        assert_eq!(indexed(&indexer), ["/data/a/x.txt", "/data/a/z.txt"]);
+        indexer.clear_root(Path::new("/data/a")).unwrap();
+        assert!(indexed(&indexer).is_empty());
+
+        let merged = [IndexRoot::new("/data/a"), IndexRoot::new("/data/b")];
+        assert_eq!(
+            indexer.sync_shards(&merged).unwrap(),
+            [PathBuf::from("/data/b")]
+        );
+        assert!(indexer.separate_roots().is_empty());
+        assert_eq!(
+            std::fs::read_dir(dir.path().join(SHARDS_DIR))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_files_with_content_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(&dir.path().join("index"), 64).unwrap();
+        let with_content = |path: &str, modified| {
+            let (mut parsed, _, size) = doc(path);
+            parsed.content = "quarterly figures".to_string();
+            (parsed, modified, size)
+        };
+        indexer
+            .add_documents_batch(&[
+                with_content("/data/new.txt", 200),
+                with_content("/data/old.txt", 100),
+                doc("/data/name-only.bin"),
+            ])
+            .unwrap();
+        indexer.commit().unwrap();
+
+        let paths: Vec<String> = indexer
+            .get_files_with_content()
+            .unwrap()
+            .into_iter()
+            .map(|(path, ..)| path)
+            .collect();
+        assert_eq!(paths, ["/data/old.txt", "/data/new.txt"]);
+    }
+
+    #[test]
+    fn test_warm_covers_every_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(&dir.path().join("index"), 64).unwrap();
+        assert_eq!(indexer.warm().unwrap(), 0);
+
+        indexer.add_documents_batch(&[doc("/data/a.txt")]).unwrap();
+        indexer.commit().unwrap();
+        indexer.add_documents_batch(&[doc("/data/b.txt")]).unwrap();
+        indexer.commit().unwrap();
+        assert_eq!(indexer.warm().unwrap(), 2);
+    }
 }
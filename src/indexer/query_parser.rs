@@ -1,8 +1,59 @@
+use super::category::FileCategory;
+use crate::settings::QueryMacro;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 
 static OPERATOR_REGEX: OnceLock<Regex> = OnceLock::new();
 static SIZE_REGEX: OnceLock<Regex> = OnceLock::new();
+static MACRO_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Operators: ext:pdf, path:docs, title:report, size:>1MB, owner:alice, is:hidden,
+/// category:image, created:>2023-06-01
+fn operator_regex() -> &'static Regex {
+    OPERATOR_REGEX.get_or_init(|| {
+        Regex::new(r#"(?i)\b(ext|path|title|size|owner|is|category|created):(?:"([^"]*)"|(\S+))"#)
+            .unwrap()
+    })
+}
+
+/// A `size:` value: optional comparison, number and unit
+fn size_regex() -> &'static Regex {
+    SIZE_REGEX.get_or_init(|| Regex::new(r"(?i)^([<>]?)(\d+(?:\.\d+)?)(MB|KB|GB|B)?$").unwrap())
+}
+
+/// A mistake in the query syntax, found before the query reaches the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryError {
+    /// Character offset in the query where the mistake is
+    pub position: usize,
+    /// What is wrong, in words
+    pub message: String,
+    /// The whole query with the mistake fixed, when there's an obvious fix
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at column {}", self.message, self.position + 1)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "; try `{suggestion}`")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl QueryError {
+    fn at(input: &str, byte: usize, message: String, suggestion: Option<String>) -> Self {
+        Self {
+            position: input[..byte].chars().count(),
+            message,
+            suggestion,
+        }
+    }
+}
 
 /// Parsed query with operators and search terms
 #[derive(Debug, Clone)]
@@ -18,6 +69,16 @@ pub struct ParsedQuery {
     /// Size filters
     pub min_size: Option<u64>,
     pub max_size: Option<u64>,
+    /// Owner filter (e.g., "alice")
+    pub owner: Option<String>,
+    /// Required file flags from `is:` ("readonly", "hidden")
+    pub flags: Vec<String>,
+    /// Type categories from `category:` (any of them may match)
+    pub categories: Vec<FileCategory>,
+    /// Creation time bounds from `created:` as Unix seconds, lower inclusive and
+    /// upper exclusive
+    pub created_after: Option<u64>,
+    pub created_before: Option<u64>,
     /// Whether fuzzy matching is enabled
     pub fuzzy: bool,
     pub case_sensitive: bool,
@@ -36,15 +97,15 @@ impl ParsedQuery {
         let mut title_filter = None;
         let mut min_size = None;
         let mut max_size = None;
+        let mut owner = None;
+        let mut flags = Vec::new();
+        let mut categories = Vec::new();
+        let mut created_after = None;
+        let mut created_before = None;
         let fuzzy = true;
 
-        // Parse operators: ext:pdf, path:docs, title:report, size:>1MB
-        let operator_regex = OPERATOR_REGEX.get_or_init(|| {
-            Regex::new(r#"(?i)(ext|path|title|size):(?:"([^"]*)"|(\S+))"#).unwrap()
-        });
-
-        let size_regex = SIZE_REGEX
-            .get_or_init(|| Regex::new(r"(?i)^([<>]?)(\d+(?:\.\d+)?)(MB|KB|GB|B)?$").unwrap());
+        let operator_regex = operator_regex();
+        let size_regex = size_regex();
 
         let mut remaining = input.to_string();
 
@@ -87,6 +148,53 @@ impl ParsedQuery {
                         remaining = remaining.replace(m.as_str(), "");
                     }
                 }
+                "owner" => {
+                    owner = Some(value.to_lowercase());
+                    if let Some(m) = cap.get(0) {
+                        remaining = remaining.replace(m.as_str(), "");
+                    }
+                }
+                "is" => {
+                    let flag = value.to_lowercase();
+                    if matches!(flag.as_str(), "readonly" | "hidden") {
+                        flags.push(flag);
+                        if let Some(m) = cap.get(0) {
+                            remaining = remaining.replace(m.as_str(), "");
+                        }
+                    }
+                }
+                "category" => {
+                    if let Ok(category) = value.parse::<FileCategory>() {
+                        if !categories.contains(&category) {
+                            categories.push(category);
+                        }
+                        if let Some(m) = cap.get(0) {
+                            remaining = remaining.replace(m.as_str(), "");
+                        }
+                    }
+                }
+                "created" => {
+                    let (op, date) = if let Some(d) = value.strip_prefix('>') {
+                        (">", d)
+                    } else if let Some(d) = value.strip_prefix('<') {
+                        ("<", d)
+                    } else {
+                        ("", value.as_str())
+                    };
+                    if let Some((day_start, next_day_start)) = day_bounds(date) {
+                        match op {
+                            ">" => created_after = Some(next_day_start),
+                            "<" => created_before = Some(day_start),
+                            _ => {
+                                created_after = Some(day_start);
+                                created_before = Some(next_day_start);
+                            }
+                        }
+                        if let Some(m) = cap.get(0) {
+                            remaining = remaining.replace(m.as_str(), "");
+                        }
+                    }
+                }
                 "size" => {
                     if let Some(scap) = size_regex.captures(&value) {
                         let op = scap.get(1).map_or("", |m| m.as_str());
@@ -141,11 +249,130 @@ impl ParsedQuery {
             title_filter,
             min_size,
             max_size,
+            owner,
+            flags,
+            categories,
+            created_after,
+            created_before,
             fuzzy,
             case_sensitive,
         }
     }
 
+    /// Checks `input` for mistakes that would otherwise fail deep inside the
+    /// index, or be silently dropped: unclosed quotes, unbalanced
+    /// parentheses, `size:` values without a known unit, `created:` values
+    /// that aren't dates and `/regex/` terms that don't compile. Reports the
+    /// first one found.
+    ///
+    /// # Errors
+    ///
+    /// Returns where the mistake is and, when there's an obvious fix, the
+    /// corrected query.
+    pub fn validate(input: &str) -> Result<(), QueryError> {
+        let mut offset = 0;
+        for token in input.split_inclusive(char::is_whitespace) {
+            let start = offset;
+            offset += token.len();
+            let term = token.trim_end();
+            let term = term.split_once(':').map_or(term, |(_, value)| value);
+            let Some(pattern) = term
+                .strip_prefix('/')
+                .and_then(|t| t.strip_suffix('/'))
+                .filter(|p| !p.is_empty())
+            else {
+                continue;
+            };
+            if let Err(e) = Regex::new(pattern) {
+                let reason = e.to_string();
+                let reason = reason.lines().last().unwrap_or_default().trim();
+                return Err(QueryError::at(
+                    input,
+                    start,
+                    format!("Invalid regular expression /{pattern}/: {reason}"),
+                    None,
+                ));
+            }
+        }
+
+        let mut open_quote = None;
+        let mut open_parens = Vec::new();
+        for (i, c) in input.char_indices() {
+            match c {
+                '"' => open_quote = if open_quote.is_some() { None } else { Some(i) },
+                '(' if open_quote.is_none() => open_parens.push(i),
+                ')' if open_quote.is_none() && open_parens.pop().is_none() => {
+                    let fixed = format!("{}{}", &input[..i], &input[i + 1..]);
+                    return Err(QueryError::at(
+                        input,
+                        i,
+                        "Closing parenthesis without an opening one".to_string(),
+                        Some(fixed.trim().to_string()),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        if let Some(i) = open_quote {
+            return Err(QueryError::at(
+                input,
+                i,
+                "Unclosed quote".to_string(),
+                Some(format!("{}\"", input.trim_end())),
+            ));
+        }
+        if let Some(&i) = open_parens.first() {
+            return Err(QueryError::at(
+                input,
+                i,
+                "Unclosed parenthesis".to_string(),
+                Some(format!(
+                    "{}{}",
+                    input.trim_end(),
+                    ")".repeat(open_parens.len())
+                )),
+            ));
+        }
+
+        for cap in operator_regex().captures_iter(input) {
+            let (Some(whole), Some(value)) = (cap.get(0), cap.get(2).or_else(|| cap.get(3))) else {
+                continue;
+            };
+            match cap[1].to_lowercase().as_str() {
+                "size" if !size_regex().is_match(value.as_str()) => {
+                    let suggestion = fix_size(value.as_str()).map(|fixed| {
+                        format!(
+                            "{}{fixed}{}",
+                            &input[..value.start()],
+                            &input[value.end()..]
+                        )
+                    });
+                    return Err(QueryError::at(
+                        input,
+                        whole.start(),
+                        format!(
+                            "`{}` is not a size; use a number with B, KB, MB or GB",
+                            value.as_str()
+                        ),
+                        suggestion,
+                    ));
+                }
+                "created"
+                    if day_bounds(value.as_str().trim_start_matches(['<', '>'])).is_none() =>
+                {
+                    return Err(QueryError::at(
+                        input,
+                        whole.start(),
+                        format!("`{}` is not a date; use YYYY-MM-DD", value.as_str()),
+                        None,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Check if a path matches the extension filter
     #[must_use]
     pub fn matches_extension(&self, path: &str) -> bool {
@@ -182,6 +409,104 @@ impl ParsedQuery {
     }
 }
 
+/// Replace `@name` references with their saved expansions.
+///
+/// Expansion is a single pass, so a macro that mentions another macro (or itself)
+/// is left as literal text rather than recursing. Unknown names are kept as-is.
+#[must_use]
+pub fn expand_macros(input: &str, macros: &[QueryMacro]) -> String {
+    if macros.is_empty() || !input.contains('@') {
+        return input.to_string();
+    }
+
+    macro_regex()
+        .replace_all(input, |cap: &regex::Captures<'_>| {
+            let name = &cap[2];
+            macros
+                .iter()
+                .find(|m| m.name.eq_ignore_ascii_case(name))
+                .map_or_else(
+                    || cap[0].to_string(),
+                    |m| format!("{}{}", &cap[1], m.expansion.trim()),
+                )
+        })
+        .into_owned()
+}
+
+/// Whether `input` references one of `macros` by `@name`, as [`expand_macros`]
+/// would expand it.
+#[must_use]
+pub fn has_macro(input: &str, macros: &[QueryMacro]) -> bool {
+    !macros.is_empty()
+        && input.contains('@')
+        && macro_regex()
+            .captures_iter(input)
+            .any(|cap| macros.iter().any(|m| m.name.eq_ignore_ascii_case(&cap[2])))
+}
+
+fn macro_regex() -> &'static Regex {
+    MACRO_REGEX.get_or_init(|| Regex::new(r"(^|\s)@([A-Za-z0-9_-]+)").unwrap())
+}
+
+/// Macros whose name starts with the `@prefix` currently being typed at the end of `input`.
+#[must_use]
+pub fn macro_suggestions<'a>(input: &str, macros: &'a [QueryMacro]) -> Vec<&'a QueryMacro> {
+    let Some(prefix) = input
+        .rsplit(char::is_whitespace)
+        .next()
+        .and_then(|token| token.strip_prefix('@'))
+    else {
+        return Vec::new();
+    };
+
+    let prefix = prefix.to_lowercase();
+    macros
+        .iter()
+        .filter(|m| m.name.to_lowercase().starts_with(&prefix))
+        .collect()
+}
+
+/// A `size:` value rewritten with a unit [`size_regex`] accepts, e.g. `5M`
+/// as `5MB` or `>=2gib` as `>2GB`; `None` when there is no number to keep.
+fn fix_size(value: &str) -> Option<String> {
+    let (op, rest) = match value.chars().next() {
+        Some(c @ ('<' | '>')) => (c.to_string(), value[1..].trim_start_matches('=')),
+        _ => (String::new(), value),
+    };
+    let split = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let (number, unit) = rest.split_at(split);
+    number.parse::<f64>().ok()?;
+    let unit = match unit.trim().to_uppercase().as_str() {
+        "" | "B" | "BYTE" | "BYTES" => "B",
+        "K" | "KIB" | "KILOBYTES" => "KB",
+        "M" | "MIB" | "MEG" | "MEGABYTES" => "MB",
+        "G" | "GIB" | "GIG" | "GIGABYTES" => "GB",
+        _ => return None,
+    };
+    Some(format!("{op}{number}{unit}"))
+}
+
+/// Start of `date` (`YYYY-MM-DD`) and of the following day in local time, as
+/// Unix seconds.
+fn day_bounds(date: &str) -> Option<(u64, u64)> {
+    let date: jiff::civil::Date = date.parse().ok()?;
+    let tz = jiff::tz::TimeZone::system();
+    let start = date.to_zoned(tz.clone()).ok()?.timestamp().as_second();
+    let end = date
+        .tomorrow()
+        .ok()?
+        .to_zoned(tz)
+        .ok()?
+        .timestamp()
+        .as_second();
+    Some((
+        u64::try_from(start).unwrap_or(0),
+        u64::try_from(end).unwrap_or(0),
+    ))
+}
+
 /// Extract search terms for highlighting from a query
 #[must_use]
 pub fn extract_highlight_terms(query: &str, case_sensitive: bool) -> Vec<String> {
@@ -250,6 +575,44 @@ mod tests {
         assert_eq!(parsed.text_query, "annual");
     }
 
+    #[test]
+    fn test_owner_and_flag_operators() {
+        let parsed = ParsedQuery::new("owner:Alice is:hidden is:bogus budget", false);
+        assert_eq!(parsed.owner, Some("alice".to_string()));
+        assert_eq!(parsed.flags, vec!["hidden".to_string()]);
+        assert_eq!(parsed.text_query, "is:bogus budget");
+    }
+
+    #[test]
+    fn test_category_operator() {
+        let parsed = ParsedQuery::new("category:Image category:code category:nope logo", false);
+        assert_eq!(
+            parsed.categories,
+            vec![FileCategory::Image, FileCategory::Code]
+        );
+        assert_eq!(parsed.text_query, "category:nope logo");
+    }
+
+    #[test]
+    fn test_created_operator() {
+        let parsed = ParsedQuery::new("created:>2023-06-01 invoice", false);
+        let (_, next_day) = day_bounds("2023-06-01").unwrap();
+        assert_eq!(parsed.created_after, Some(next_day));
+        assert_eq!(parsed.created_before, None);
+        assert_eq!(parsed.text_query, "invoice");
+
+        let parsed = ParsedQuery::new("created:2023-06-01", false);
+        let (start, end) = day_bounds("2023-06-01").unwrap();
+        assert_eq!(
+            (parsed.created_after, parsed.created_before),
+            (Some(start), Some(end))
+        );
+        assert_eq!(end - start, 86400);
+
+        let parsed = ParsedQuery::new("created:soon", false);
+        assert_eq!(parsed.created_after, None);
+    }
+
     #[test]
     fn test_matches_extension() {
         let parsed = ParsedQuery::new("ext:pdf", false);
@@ -280,6 +643,73 @@ mod tests {
         assert!(terms.contains(&"annual".to_string()));
     }
 
+    #[test]
+    fn test_validate_reports_position_and_fix() {
+        assert!(ParsedQuery::validate("\"annual report\" size:>5MB (a OR b)").is_ok());
+
+        let err = ParsedQuery::validate("budget \"q3 plan").unwrap_err();
+        assert_eq!(err.position, 7);
+        assert_eq!(err.suggestion.as_deref(), Some("budget \"q3 plan\""));
+
+        let err = ParsedQuery::validate("(rust OR go").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert_eq!(err.suggestion.as_deref(), Some("(rust OR go)"));
+
+        let err = ParsedQuery::validate("report size:>=5M").unwrap_err();
+        assert_eq!(err.position, 7);
+        assert_eq!(err.suggestion.as_deref(), Some("report size:>5MB"));
+
+        let err = ParsedQuery::validate("size:huge").unwrap_err();
+        assert!(err.suggestion.is_none());
+
+        assert!(ParsedQuery::validate("created:2023-13-45").is_err());
+        assert!(ParsedQuery::validate("/inv[0-9]+/").is_ok());
+        let err = ParsedQuery::validate("note /inv(oice/").unwrap_err();
+        assert_eq!(err.position, 5);
+    }
+
+    fn invoices_macro() -> Vec<QueryMacro> {
+        vec![QueryMacro {
+            name: "invoices".to_string(),
+            expansion: "ext:pdf path:Accounting (invoice OR rechnung)".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_expand_macros() {
+        let macros = invoices_macro();
+        let expanded = expand_macros("@invoices 2024", &macros);
+        assert_eq!(
+            expanded,
+            "ext:pdf path:Accounting (invoice OR rechnung) 2024"
+        );
+
+        let parsed = ParsedQuery::new(&expanded, false);
+        assert_eq!(parsed.extension, Some("pdf".to_string()));
+        assert_eq!(parsed.path_filter, Some("accounting".to_string()));
+
+        // Unknown macros and e-mail addresses are left untouched
+        assert_eq!(expand_macros("@unknown", &macros), "@unknown");
+        assert_eq!(expand_macros("me@invoices.com", &macros), "me@invoices.com");
+    }
+
+    #[test]
+    fn test_has_macro() {
+        let macros = invoices_macro();
+        assert!(has_macro("@Invoices 2024", &macros));
+        assert!(!has_macro("@unknown 2024", &macros));
+        assert!(!has_macro("me@invoices.com", &macros));
+        assert!(!has_macro("@invoices", &[]));
+    }
+
+    #[test]
+    fn test_macro_suggestions() {
+        let macros = invoices_macro();
+        assert_eq!(macro_suggestions("report @inv", &macros).len(), 1);
+        assert!(macro_suggestions("report @x", &macros).is_empty());
+        assert!(macro_suggestions("report inv", &macros).is_empty());
+    }
+
     #[cfg(test)]
     mod proptests {
         use super::*;
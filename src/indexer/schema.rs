@@ -1,6 +1,19 @@
+use super::code_tokenizer::CODE_TOKENIZER;
 use tantivy::schema::{
     FAST, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, TextFieldIndexing, TextOptions,
 };
+use tantivy::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
+
+/// Name the owner analyzer is registered under in the index's tokenizer manager
+pub const OWNER_TOKENIZER: &str = "owner";
+
+/// Keeps the owner name whole but lowercased, so `owner:Alice` matches `alice`.
+#[must_use]
+pub fn owner_analyzer() -> TextAnalyzer {
+    TextAnalyzer::builder(RawTokenizer::default())
+        .filter(LowerCaser)
+        .build()
+}
 
 /// Create Tantivy schema optimized for file search
 #[must_use]
@@ -19,12 +32,33 @@ pub fn create_schema() -> Schema {
     );
     schema_builder.add_text_field("content", text_options);
 
+    // Identifiers - source files only, split on camelCase/snake_case boundaries
+    let identifier_options = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(CODE_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    schema_builder.add_text_field("identifiers", identifier_options);
+
+    // Annotations - user notes and attributes kept in the metadata store
+    let annotation_options = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+    schema_builder.add_text_field("annotations", annotation_options);
+
     // Title - stored for display, indexed for search
     schema_builder.add_text_field("title", TEXT | STORED);
 
     // Modified timestamp - indexed for sorting
     schema_builder.add_date_field("modified", FAST | INDEXED);
 
+    // Creation date - only present where the filesystem records birth time
+    schema_builder.add_date_field("created", FAST | INDEXED);
+
     // File size - indexed for range queries
     schema_builder.add_u64_field("size", FAST | INDEXED);
 
@@ -34,6 +68,26 @@ pub fn create_schema() -> Schema {
     // Language code - indexed as keyword for filtering (e.g., lang:eng)
     schema_builder.add_text_field("language", STRING | STORED);
 
+    // Owner - account name, matched whole and case-insensitively by owner:
+    let owner_options = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(OWNER_TOKENIZER)
+                .set_index_option(IndexRecordOption::Basic),
+        )
+        .set_stored();
+    schema_builder.add_text_field("owner", owner_options);
+
+    // Flags - "readonly" / "hidden", one value each, matched by is:
+    schema_builder.add_text_field("flags", STRING | STORED);
+
+    // Removable drive the file is on, so its results outlive the drive being unplugged
+    schema_builder.add_text_field("volume", STRING | STORED);
+
+    // MIME type and broad category - derived from the extension, matched by category:
+    schema_builder.add_text_field("mime", STRING | STORED);
+    schema_builder.add_text_field("category", STRING | STORED);
+
     // Keywords - indexed and tokenized for search visibility
     let keywords_options = TextOptions::default()
         .set_indexing_options(
@@ -1,9 +1,13 @@
+use super::category::FileCategory;
 use super::query_parser::{ParsedQuery, extract_highlight_terms};
 use crate::error::{FlashError, Result};
 use compact_str::CompactString;
 use mini_moka::sync::Cache;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ops::Bound;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use tantivy::collector::TopDocs;
 use tantivy::query::{Occur, RangeQuery};
@@ -21,6 +25,31 @@ pub struct SearchResult {
     pub size: Option<u64>,
     pub matched_terms: Vec<String>,
     pub snippets: Vec<String>,
+    #[serde(default)]
+    pub owner: Option<CompactString>,
+    #[serde(default)]
+    pub readonly: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub category: Option<FileCategory>,
+    #[serde(default)]
+    pub mime: Option<CompactString>,
+    #[serde(default)]
+    pub created: Option<u64>,
+    /// Removable drive the file is on, by its volume identifier
+    #[serde(default)]
+    pub volume: Option<CompactString>,
+    /// The file's root or drive is offline; the entry is kept but can't be
+    /// opened
+    #[serde(default)]
+    pub unavailable: bool,
+    /// The file changed on disk after it was indexed
+    #[serde(default)]
+    pub stale: bool,
+    /// A folder found by name in a combined search, rather than a document
+    #[serde(default)]
+    pub is_dir: bool,
 }
 
 impl SearchResult {
@@ -39,6 +68,13 @@ pub struct SearchResultBuilder {
     size: Option<u64>,
     matched_terms: Option<Vec<String>>,
     snippets: Option<Vec<String>>,
+    owner: Option<CompactString>,
+    readonly: bool,
+    hidden: bool,
+    category: Option<FileCategory>,
+    mime: Option<CompactString>,
+    created: Option<u64>,
+    is_dir: bool,
 }
 
 impl SearchResultBuilder {
@@ -100,6 +136,48 @@ impl SearchResultBuilder {
         self
     }
 
+    #[must_use]
+    pub fn owner(mut self, owner: Option<CompactString>) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    #[must_use]
+    pub const fn readonly(mut self, readonly: bool) -> Self {
+        self.readonly = readonly;
+        self
+    }
+
+    #[must_use]
+    pub const fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    #[must_use]
+    pub const fn category(mut self, category: Option<FileCategory>) -> Self {
+        self.category = category;
+        self
+    }
+
+    #[must_use]
+    pub fn mime(mut self, mime: Option<CompactString>) -> Self {
+        self.mime = mime;
+        self
+    }
+
+    #[must_use]
+    pub const fn created(mut self, created: Option<u64>) -> Self {
+        self.created = created;
+        self
+    }
+
+    #[must_use]
+    pub const fn is_dir(mut self, is_dir: bool) -> Self {
+        self.is_dir = is_dir;
+        self
+    }
+
     /// Builds the `SearchResult`.
     ///
     /// # Panics
@@ -115,8 +193,67 @@ impl SearchResultBuilder {
             size: self.size,
             matched_terms: self.matched_terms.expect("matched_terms is required"),
             snippets: self.snippets.expect("snippets is required"),
+            owner: self.owner,
+            readonly: self.readonly,
+            hidden: self.hidden,
+            category: self.category,
+            mime: self.mime,
+            created: self.created,
+            volume: None,
+            unavailable: false,
+            stale: false,
+            is_dir: self.is_dir,
+        }
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
         }
     }
+    row[b.len()]
+}
+
+/// Smallest drop, as a fraction of the top score, treated as a confidence knee.
+const KNEE_MIN_DROP: f32 = 0.25;
+
+/// Find the score below which results are likely noise.
+///
+/// Looks for the single largest gap between consecutive scores (sorted
+/// descending). If that gap is at least [`KNEE_MIN_DROP`] of the top score the
+/// score just above it is returned; otherwise the curve has no clear knee and
+/// `None` means "keep everything".
+#[must_use]
+pub fn confidence_threshold(scores: &[f32]) -> Option<f32> {
+    if scores.len() < 3 {
+        return None;
+    }
+
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let top = sorted[0];
+    if top <= 0.0 {
+        return None;
+    }
+
+    let (knee, drop) = sorted
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| (i, w[0] - w[1]))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    (drop / top >= KNEE_MIN_DROP).then_some(sorted[knee])
 }
 
 /// Statistics about the index
@@ -124,6 +261,25 @@ impl SearchResultBuilder {
 pub struct IndexStatistics {
     pub total_documents: usize,
     pub total_size_bytes: u64,
+    /// Size cap from the settings, if one is set
+    pub size_limit_bytes: Option<u64>,
+    /// Roots indexed by name only since the index hit its size cap
+    pub content_capped_roots: Vec<String>,
+    /// Searches answered from the query cache since startup
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// Searches that missed the query cache since startup
+    #[serde(default)]
+    pub cache_misses: u64,
+}
+
+impl IndexStatistics {
+    /// Share of searches answered from the cache, once there were any.
+    #[must_use]
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
 }
 
 /// Cache key for search queries
@@ -135,6 +291,7 @@ pub(crate) struct CacheKey {
     pub(crate) max_size: Option<u64>,
     pub(crate) min_modified: Option<u64>,
     pub(crate) extensions: Option<smallvec::SmallVec<[CompactString; 8]>>,
+    pub(crate) categories: Option<smallvec::SmallVec<[FileCategory; 4]>>,
     pub(crate) case_sensitive: bool,
 }
 
@@ -152,6 +309,7 @@ pub struct SearchParams<'a> {
     pub max_size: Option<u64>,
     pub min_modified: Option<u64>,
     pub file_extensions: Option<&'a [String]>,
+    pub categories: Option<&'a [FileCategory]>,
     pub case_sensitive: bool,
 }
 
@@ -169,6 +327,7 @@ pub struct SearchParamsBuilder<'a> {
     max_size: Option<u64>,
     min_modified: Option<u64>,
     file_extensions: Option<&'a [String]>,
+    categories: Option<&'a [FileCategory]>,
     case_sensitive: Option<bool>,
 }
 
@@ -233,6 +392,21 @@ impl<'a> SearchParamsBuilder<'a> {
         }
     }
 
+    #[must_use]
+    pub const fn categories(mut self, categories: &'a [FileCategory]) -> Self {
+        self.categories = Some(categories);
+        self
+    }
+
+    #[must_use]
+    pub const fn maybe_categories(self, categories: Option<&'a [FileCategory]>) -> Self {
+        if let Some(cats) = categories {
+            self.categories(cats)
+        } else {
+            self
+        }
+    }
+
     #[must_use]
     pub const fn case_sensitive(mut self, case_sensitive: bool) -> Self {
         self.case_sensitive = Some(case_sensitive);
@@ -261,15 +435,19 @@ impl<'a> SearchParamsBuilder<'a> {
             max_size: self.max_size,
             min_modified: self.min_modified,
             file_extensions: self.file_extensions,
+            categories: self.categories,
             case_sensitive: self.case_sensitive.expect("case_sensitive is required"),
         }
     }
 }
 
-/// LRU-style query result cache using moka + ahash
+/// LRU-style query result cache using moka + ahash. Clones share entries and
+/// hit/miss counts.
 #[derive(Clone)]
 pub struct QueryCache {
     cache: Cache<u64, Vec<SearchResult>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
 }
 
 impl Default for QueryCache {
@@ -285,11 +463,20 @@ impl QueryCache {
                 .max_capacity(100)
                 .time_to_live(Duration::from_mins(5)) // 5 minutes TTL
                 .build(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
         }
     }
 
     pub(crate) fn get(&self, key: &CacheKey) -> Option<Vec<SearchResult>> {
-        self.cache.get(&key.compute_hash())
+        let cached = self.cache.get(&key.compute_hash());
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
     }
 
     pub(crate) fn insert(&self, key: &CacheKey, results: Vec<SearchResult>) {
@@ -299,6 +486,15 @@ impl QueryCache {
     pub fn invalidate(&self) {
         self.cache.invalidate_all();
     }
+
+    /// Lookups answered from the cache and lookups that missed it
+    #[must_use]
+    pub fn stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
 }
 
 /// Handles search operations on the index
@@ -306,12 +502,24 @@ pub struct IndexSearcher {
     reader: IndexReader,
     index_path: std::path::PathBuf,
     cache: QueryCache,
+    /// A warm-up thread is running
+    warming: AtomicBool,
+    /// A commit landed since the warm-up thread last started a pass
+    warm_requested: AtomicBool,
     path_field: Field,
     content_field: Field,
+    identifiers_field: Field,
+    annotations_field: Field,
     title_field: Field,
     modified_field: Field,
+    created_field: Field,
     size_field: Field,
     extension_field: Field,
+    owner_field: Field,
+    flags_field: Field,
+    volume_field: Field,
+    category_field: Field,
+    mime_field: Field,
 }
 
 impl IndexSearcher {
@@ -329,29 +537,63 @@ impl IndexSearcher {
         let content_field = schema
             .get_field("content")
             .map_err(|_| FlashError::index_field("content", "Field not found"))?;
+        let identifiers_field = schema
+            .get_field("identifiers")
+            .map_err(|_| FlashError::index_field("identifiers", "Field not found"))?;
+        let annotations_field = schema
+            .get_field("annotations")
+            .map_err(|_| FlashError::index_field("annotations", "Field not found"))?;
         let title_field = schema
             .get_field("title")
             .map_err(|_| FlashError::index_field("title", "Field not found"))?;
         let modified_field = schema
             .get_field("modified")
             .map_err(|_| FlashError::index_field("modified", "Field not found"))?;
+        let created_field = schema
+            .get_field("created")
+            .map_err(|_| FlashError::index_field("created", "Field not found"))?;
         let size_field = schema
             .get_field("size")
             .map_err(|_| FlashError::index_field("size", "Field not found"))?;
         let extension_field = schema
             .get_field("extension")
             .map_err(|_| FlashError::index_field("extension", "Field not found"))?;
+        let owner_field = schema
+            .get_field("owner")
+            .map_err(|_| FlashError::index_field("owner", "Field not found"))?;
+        let flags_field = schema
+            .get_field("flags")
+            .map_err(|_| FlashError::index_field("flags", "Field not found"))?;
+        let volume_field = schema
+            .get_field("volume")
+            .map_err(|_| FlashError::index_field("volume", "Field not found"))?;
+        let category_field = schema
+            .get_field("category")
+            .map_err(|_| FlashError::index_field("category", "Field not found"))?;
+        let mime_field = schema
+            .get_field("mime")
+            .map_err(|_| FlashError::index_field("mime", "Field not found"))?;
 
         Ok(Self {
             reader,
             index_path,
             cache: QueryCache::new(),
+            warming: AtomicBool::new(false),
+            warm_requested: AtomicBool::new(false),
             path_field,
             content_field,
+            identifiers_field,
+            annotations_field,
             title_field,
             modified_field,
+            created_field,
             size_field,
             extension_field,
+            owner_field,
+            flags_field,
+            volume_field,
+            category_field,
+            mime_field,
         })
     }
 
@@ -364,6 +606,8 @@ impl IndexSearcher {
 
         let query_owned = params.query.to_string();
         let extensions_owned: Option<Vec<String>> = params.file_extensions.map(<[String]>::to_vec);
+        let categories_owned: Option<Vec<FileCategory>> =
+            params.categories.map(<[FileCategory]>::to_vec);
         let limit = params.limit;
         let min_size = params.min_size;
         let max_size = params.max_size;
@@ -378,6 +622,7 @@ impl IndexSearcher {
                 max_size,
                 min_modified,
                 file_extensions: extensions_owned.as_deref(),
+                categories: categories_owned.as_deref(),
                 case_sensitive,
             };
             this.search_sync(&params)
@@ -399,6 +644,20 @@ impl IndexSearcher {
                 .collect::<smallvec::SmallVec<[CompactString; 8]>>()
         });
 
+        // Categories from `category:` operators join the ones passed by the caller
+        let parsed = ParsedQuery::new(params.query, params.case_sensitive);
+        let mut categories: smallvec::SmallVec<[FileCategory; 4]> = params
+            .categories
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .collect();
+        for category in &parsed.categories {
+            if !categories.contains(category) {
+                categories.push(*category);
+            }
+        }
+
         // Create cache key
         let cache_key = CacheKey {
             query: params.query.to_string(),
@@ -407,6 +666,7 @@ impl IndexSearcher {
             max_size: params.max_size,
             min_modified: params.min_modified,
             extensions: file_extensions.clone(),
+            categories: params.categories.map(|c| c.iter().copied().collect()),
             case_sensitive: params.case_sensitive,
         };
 
@@ -415,7 +675,6 @@ impl IndexSearcher {
             return Ok(cached);
         }
 
-        let parsed = ParsedQuery::new(params.query, params.case_sensitive);
         let highlight_terms = extract_highlight_terms(params.query, params.case_sensitive);
 
         let searcher = self.reader.searcher();
@@ -478,6 +737,63 @@ impl IndexSearcher {
                 }
             }
 
+            if parsed.created_after.is_some() || parsed.created_before.is_some() {
+                let to_date = |secs: u64| {
+                    tantivy::DateTime::from_timestamp_secs(i64::try_from(secs).unwrap_or(i64::MAX))
+                };
+                let lower = parsed.created_after.map_or(Bound::Unbounded, |secs| {
+                    Bound::Included(Term::from_field_date(self.created_field, to_date(secs)))
+                });
+                let upper = parsed.created_before.map_or(Bound::Unbounded, |secs| {
+                    Bound::Excluded(Term::from_field_date(self.created_field, to_date(secs)))
+                });
+                combine.push((Occur::Must, Box::new(RangeQuery::new(lower, upper))));
+            }
+
+            if !categories.is_empty() {
+                let category_queries: Vec<(Occur, Box<dyn tantivy::query::Query>)> = categories
+                    .iter()
+                    .map(|category| {
+                        let term =
+                            Term::from_field_text(self.category_field, &category.to_string());
+                        (
+                            Occur::Should,
+                            Box::new(tantivy::query::TermQuery::new(
+                                term,
+                                IndexRecordOption::Basic,
+                            )) as Box<dyn tantivy::query::Query>,
+                        )
+                    })
+                    .collect();
+                combine.push((
+                    Occur::Must,
+                    Box::new(tantivy::query::BooleanQuery::new(category_queries)),
+                ));
+            }
+
+            // Owner names are indexed lowercased through the owner analyzer
+            if let Some(ref owner) = parsed.owner {
+                let term = Term::from_field_text(self.owner_field, &owner.to_lowercase());
+                combine.push((
+                    Occur::Must,
+                    Box::new(tantivy::query::TermQuery::new(
+                        term,
+                        IndexRecordOption::Basic,
+                    )),
+                ));
+            }
+
+            for flag in &parsed.flags {
+                let term = Term::from_field_text(self.flags_field, flag);
+                combine.push((
+                    Occur::Must,
+                    Box::new(tantivy::query::TermQuery::new(
+                        term,
+                        IndexRecordOption::Basic,
+                    )),
+                ));
+            }
+
             let final_query = tantivy::query::BooleanQuery::new(combine);
             let top_docs = searcher
                 .search(&final_query, &TopDocs::with_limit(limit).order_by_score())
@@ -496,8 +812,16 @@ impl IndexSearcher {
                 params.query,
             )?
         } else {
-            let mut query_parser =
-                tantivy::query::QueryParser::for_index(searcher.index(), vec![self.content_field]);
+            // Identifiers are searched alongside content so `getUserName` also finds `get_user_name`,
+            // and annotations so a user's own notes find the file they're attached to
+            let mut query_parser = tantivy::query::QueryParser::for_index(
+                searcher.index(),
+                vec![
+                    self.content_field,
+                    self.identifiers_field,
+                    self.annotations_field,
+                ],
+            );
             query_parser.set_conjunction_by_default();
 
             let query_result = query_parser.parse_query(&parsed.text_query);
@@ -645,15 +969,51 @@ impl IndexSearcher {
                     .and_then(|v| v.as_u64())
             });
 
+        // Documents written without a modification time have no value in
+        // the column, rather than the epoch
         let modified = searcher
             .segment_reader(doc_address.segment_ord)
             .fast_fields()
             .date("modified")
             .ok()
-            .map(|f| {
-                let date = f.values.get_val(doc_address.doc_id);
-                u64::try_from(date.into_timestamp_secs()).unwrap_or(0)
-            });
+            .and_then(|f| f.first(doc_address.doc_id))
+            .and_then(|date| u64::try_from(date.into_timestamp_secs()).ok());
+
+        let created = searcher
+            .segment_reader(doc_address.segment_ord)
+            .fast_fields()
+            .date("created")
+            .ok()
+            .and_then(|f| f.first(doc_address.doc_id))
+            .and_then(|date| u64::try_from(date.into_timestamp_secs()).ok());
+
+        let owner = tantivy_doc
+            .get_first(self.owner_field)
+            .and_then(|v| v.as_str())
+            .map(CompactString::from);
+
+        let category = tantivy_doc
+            .get_first(self.category_field)
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse().ok());
+
+        let mime = tantivy_doc
+            .get_first(self.mime_field)
+            .and_then(|v| v.as_str())
+            .map(CompactString::from);
+
+        let volume = tantivy_doc
+            .get_first(self.volume_field)
+            .and_then(|v| v.as_str())
+            .map(CompactString::from);
+
+        let has_flag = |flag: &str| {
+            tantivy_doc
+                .get_all(self.flags_field)
+                .any(|v| v.as_str() == Some(flag))
+        };
+        let readonly = has_flag("readonly");
+        let hidden = has_flag("hidden");
 
         let snippets = snippet_generator
             .map(|sg| {
@@ -676,6 +1036,16 @@ impl IndexSearcher {
             size,
             matched_terms: highlight_terms.to_vec(),
             snippets,
+            owner,
+            readonly,
+            hidden,
+            category,
+            mime,
+            created,
+            volume,
+            unavailable: false,
+            stale: false,
+            is_dir: false,
         }
     }
 
@@ -694,12 +1064,93 @@ impl IndexSearcher {
             }
         }
 
+        let (cache_hits, cache_misses) = self.cache.stats();
         Ok(IndexStatistics {
             total_documents: total_docs,
             total_size_bytes: total_size,
+            cache_hits,
+            cache_misses,
+            ..IndexStatistics::default()
         })
     }
 
+    pub fn segment_count(&self) -> usize {
+        self.reader.searcher().segment_readers().len()
+    }
+
+    /// Every live document as its path with the indexed modification time and
+    /// size. A path indexed more than once has all its copies listed.
+    pub fn get_indexed_files(&self) -> Result<Vec<(String, u64, u64)>> {
+        // Read the latest commit rather than waiting for the delayed reload
+        self.reader
+            .reload()
+            .map_err(|e| FlashError::index(format!("Failed to reload index reader: {e}")))?;
+        let searcher = self.reader.searcher();
+        let mut files = Vec::new();
+        for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+            let fast_fields = segment.fast_fields();
+            let sizes = fast_fields.u64("size").ok();
+            let modified = fast_fields.date("modified").ok();
+            for doc_id in segment.doc_ids_alive() {
+                let address = tantivy::DocAddress::new(
+                    u32::try_from(segment_ord).unwrap_or(u32::MAX),
+                    doc_id,
+                );
+                let doc: tantivy::TantivyDocument = searcher
+                    .doc(address)
+                    .map_err(|e| FlashError::index(format!("Failed to read document: {e}")))?;
+                let Some(path) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let size = sizes.as_ref().map_or(0, |f| f.values.get_val(doc_id));
+                let mtime = modified.as_ref().map_or(0, |f| {
+                    u64::try_from(f.values.get_val(doc_id).into_timestamp_secs()).unwrap_or(0)
+                });
+                files.push((path.to_string(), mtime, size));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Like [`Self::get_indexed_files`], but only documents that have
+    /// extracted content, told apart by the content field's length norm.
+    pub fn get_files_with_content(&self) -> Result<Vec<(String, u64, u64)>> {
+        self.reader
+            .reload()
+            .map_err(|e| FlashError::index(format!("Failed to reload index reader: {e}")))?;
+        let searcher = self.reader.searcher();
+        let mut files = Vec::new();
+        for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+            let norms = segment
+                .get_fieldnorms_reader(self.content_field)
+                .map_err(|e| FlashError::index(format!("Failed to read field norms: {e}")))?;
+            let fast_fields = segment.fast_fields();
+            let sizes = fast_fields.u64("size").ok();
+            let modified = fast_fields.date("modified").ok();
+            for doc_id in segment.doc_ids_alive() {
+                if norms.fieldnorm_id(doc_id) == 0 {
+                    continue;
+                }
+                let address = tantivy::DocAddress::new(
+                    u32::try_from(segment_ord).unwrap_or(u32::MAX),
+                    doc_id,
+                );
+                let doc: tantivy::TantivyDocument = searcher
+                    .doc(address)
+                    .map_err(|e| FlashError::index(format!("Failed to read document: {e}")))?;
+                let Some(path) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let size = sizes.as_ref().map_or(0, |f| f.values.get_val(doc_id));
+                let mtime = modified.as_ref().map_or(0, |f| {
+                    u64::try_from(f.values.get_val(doc_id).into_timestamp_secs()).unwrap_or(0)
+                });
+                files.push((path.to_string(), mtime, size));
+            }
+        }
+        Ok(files)
+    }
+
     pub fn get_recent_files(&self, limit: usize) -> Result<Vec<SearchResult>> {
         let searcher = self.reader.searcher();
         let query = tantivy::query::AllQuery;
@@ -724,9 +1175,175 @@ impl IndexSearcher {
         Ok(results)
     }
 
+    /// Documents whose content shares the most distinctive terms with `text`,
+    /// the parsed content of `path`, best first. `path` itself is left out.
+    pub fn more_like_this(
+        &self,
+        path: &str,
+        text: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let searcher = self.reader.searcher();
+        let query = tantivy::query::MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(2)
+            .with_min_term_frequency(2)
+            .with_min_word_length(3)
+            .with_max_query_terms(25)
+            .with_document_fields(vec![(
+                self.content_field,
+                vec![tantivy::schema::OwnedValue::Str(text.to_string())],
+            )]);
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit + 1).order_by_score())
+            .map_err(|e| FlashError::index(format!("Failed to find similar files: {e}")))?;
+
+        let mut results = Vec::new();
+        for (score, doc_address) in top_docs {
+            if let Ok(doc) = searcher.doc(doc_address) {
+                let res = self.retrieve_result_with_doc(
+                    &searcher,
+                    "",
+                    score,
+                    doc_address,
+                    &doc,
+                    &[],
+                    None,
+                );
+                if res.file_path != path {
+                    results.push(res);
+                }
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// How many documents have `term`, as the content tokenizer would index
+    /// it, in their content
+    pub fn term_doc_freq(&self, term: &str) -> Result<u64> {
+        self.reader
+            .searcher()
+            .doc_freq(&Term::from_field_text(self.content_field, term))
+            .map_err(|e| FlashError::index(format!("Failed to read term frequency: {e}")))
+    }
+
+    /// Content terms at most `max_distance` edits away from `word`, each with
+    /// how many documents have it. Only terms starting with the same letter
+    /// are compared, so a single slice of each term dictionary is read.
+    pub fn similar_terms(&self, word: &str, max_distance: usize) -> Result<Vec<(String, u64)>> {
+        let Some(first) = word.chars().next() else {
+            return Ok(Vec::new());
+        };
+        let lower = first.to_string();
+        let upper = char::from_u32(u32::from(first) + 1).map(String::from);
+        let word_len = word.chars().count();
+
+        let mut found: HashMap<String, u64> = HashMap::new();
+        for segment in self.reader.searcher().segment_readers() {
+            let inverted = segment
+                .inverted_index(self.content_field)
+                .map_err(|e| FlashError::index(format!("Failed to read term index: {e}")))?;
+            let mut range = inverted.terms().range().ge(lower.as_bytes());
+            if let Some(upper) = &upper {
+                range = range.lt(upper.as_bytes());
+            }
+            let mut stream = range
+                .into_stream()
+                .map_err(|e| FlashError::index(format!("Failed to read terms: {e}")))?;
+            while stream.advance() {
+                let Ok(term) = std::str::from_utf8(stream.key()) else {
+                    continue;
+                };
+                if term.chars().count().abs_diff(word_len) > max_distance
+                    || edit_distance(term, word) > max_distance
+                {
+                    continue;
+                }
+                *found.entry(term.to_string()).or_default() += u64::from(stream.value().doc_freq);
+            }
+        }
+        Ok(found.into_iter().collect())
+    }
+
     pub fn invalidate_cache(&self) {
         self.cache.invalidate();
     }
+
+    /// Makes a fresh commit searchable now rather than after the reader's
+    /// delayed reload, and drops results cached from before it.
+    pub fn refresh(&self) {
+        if let Err(e) = self.reader.reload() {
+            tracing::warn!("Failed to reload index reader: {e}");
+        }
+        self.cache.invalidate();
+    }
+
+    /// Opens the term dictionaries of the searched fields and reads the
+    /// fast-field columns behind the filters and sorting in every segment,
+    /// so the first search after a commit or a restart doesn't page them in.
+    /// Returns how many segments were warmed.
+    pub fn warm(&self) -> Result<usize> {
+        let searcher = self.reader.searcher();
+        let fields = [
+            self.content_field,
+            self.identifiers_field,
+            self.annotations_field,
+            self.title_field,
+            self.path_field,
+        ];
+        for segment in searcher.segment_readers() {
+            for field in fields {
+                let inverted = segment
+                    .inverted_index(field)
+                    .map_err(|e| FlashError::index(format!("Failed to warm term index: {e}")))?;
+                std::hint::black_box(inverted.terms().num_terms());
+            }
+            let fast = segment.fast_fields();
+            let modified = fast
+                .date("modified")
+                .map_err(|e| FlashError::index(format!("Failed to warm fast fields: {e}")))?;
+            let size = fast
+                .u64("size")
+                .map_err(|e| FlashError::index(format!("Failed to warm fast fields: {e}")))?;
+            for doc in 0..segment.max_doc() {
+                std::hint::black_box((modified.first(doc), size.first(doc)));
+            }
+        }
+        Ok(searcher.segment_readers().len())
+    }
+
+    /// Warms the reader's segments on a background thread. Commits landing
+    /// while it runs are covered by another pass of the same thread.
+    pub fn warm_in_background(self: &std::sync::Arc<Self>) {
+        self.warm_requested.store(true, Ordering::Release);
+        if self.warming.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let this = self.clone();
+        let spawned = std::thread::Builder::new()
+            .name("index-warmup".to_string())
+            .spawn(move || {
+                loop {
+                    while this.warm_requested.swap(false, Ordering::AcqRel) {
+                        if let Err(e) = this.warm() {
+                            tracing::warn!("Failed to warm index segments: {e}");
+                        }
+                    }
+                    this.warming.store(false, Ordering::Release);
+                    // A request made after the last pass but before the flag
+                    // was cleared would otherwise be lost
+                    if !this.warm_requested.load(Ordering::Acquire)
+                        || this.warming.swap(true, Ordering::AcqRel)
+                    {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("Failed to start index warm-up: {e}");
+            self.warming.store(false, Ordering::Release);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -742,6 +1359,7 @@ mod tests {
             max_size: None,
             min_modified: None,
             extensions: None,
+            categories: None,
             case_sensitive: false,
         };
         let key2 = CacheKey {
@@ -751,8 +1369,30 @@ mod tests {
             max_size: None,
             min_modified: None,
             extensions: None,
+            categories: None,
             case_sensitive: false,
         };
         assert_eq!(key1, key2);
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("budgt", "budget"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("größe", "grösse"), 2);
+    }
+
+    #[test]
+    fn test_confidence_threshold() {
+        // Two strong hits followed by a long tail of weak ones
+        let scores = [9.5, 9.1, 2.0, 1.9, 1.8, 1.7];
+        assert_eq!(confidence_threshold(&scores), Some(9.1));
+
+        // Gradual decay has no knee
+        let flat = [5.0, 4.8, 4.6, 4.4, 4.2];
+        assert_eq!(confidence_threshold(&flat), None);
+
+        assert_eq!(confidence_threshold(&[3.0, 0.1]), None);
+    }
 }
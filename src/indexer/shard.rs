@@ -0,0 +1,199 @@
+//! One Tantivy index on disk. The default index holds every root that isn't
+//! indexed on its own; a root with a separate index gets its own shard, which
+//! can be cleared and rebuilt without touching the others.
+
+use super::encrypted_directory::open_directory;
+use super::schema::{self, create_schema};
+use super::searcher::IndexSearcher;
+use super::writer::IndexWriterManager;
+use super::{SCHEMA_VERSION, code_tokenizer, migration, read_schema_version, write_schema_version};
+use crate::error::{FlashError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tantivy::Index;
+use tracing::{error, info, warn};
+
+pub struct Shard {
+    /// Root whose files this shard holds; `None` for the default index
+    pub root: Option<PathBuf>,
+    pub path: PathBuf,
+    pub writer: IndexWriterManager,
+    pub searcher: Arc<IndexSearcher>,
+}
+
+impl Shard {
+    /// Whether this shard holds `path`
+    pub fn holds(&self, path: &Path) -> bool {
+        self.root
+            .as_ref()
+            .is_some_and(|root| path.starts_with(root))
+    }
+
+    /// Migrates an index written under another schema, falling back to a
+    /// rebuild from scratch if the old documents can't be carried over.
+    fn upgrade_index(index_path: &Path, memory_limit_mb: u32) -> Result<()> {
+        match migration::migrate_index(index_path, memory_limit_mb) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                warn!("Schema migration failed: {}. Rebuilding index...", e);
+                Self::rebuild_index_internal(index_path)
+            }
+        }
+    }
+
+    fn rebuild_index_internal(index_path: &Path) -> Result<()> {
+        // Try to backup the index before destroying it
+        let backup_path = index_path.with_extension("backup");
+        if let Err(e) = std::fs::remove_dir_all(&backup_path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove old backup at {:?}: {}", backup_path, e);
+        }
+        if index_path.exists()
+            && let Err(e) = std::fs::rename(index_path, &backup_path)
+        {
+            warn!(
+                "Failed to atomic rename index to {:?}: {}. Falling back to copy.",
+                backup_path, e
+            );
+            if let Err(e) = copy_dir(index_path, &backup_path) {
+                warn!("Failed to backup index to {:?}: {}", backup_path, e);
+            }
+            if let Err(e) = std::fs::remove_dir_all(index_path) {
+                error!(
+                    "Failed to remove corrupted index at {:?}: {}",
+                    index_path, e
+                );
+                return Err(FlashError::Io(std::sync::Arc::new(e)));
+            }
+        }
+
+        if let Err(e) = std::fs::create_dir_all(index_path) {
+            error!(
+                "Failed to re-create index directory at {:?}: {}",
+                index_path, e
+            );
+            return Err(FlashError::Io(std::sync::Arc::new(e)));
+        }
+        write_schema_version(index_path, SCHEMA_VERSION)?;
+        Ok(())
+    }
+
+    /// Opens or creates the index at `index_path`, migrating one written
+    /// under an older schema.
+    pub fn open(index_path: &Path, root: Option<PathBuf>, memory_limit_mb: u32) -> Result<Self> {
+        let schema = create_schema();
+
+        // Ensure directory exists
+        if !index_path.exists() {
+            std::fs::create_dir_all(index_path)
+                .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+        }
+
+        // Check schema version - if mismatch, rebuild index
+        let stored_version = read_schema_version(index_path);
+        if let Some(ref ver) = stored_version {
+            if ver != SCHEMA_VERSION {
+                warn!(
+                    "Schema version mismatch: stored={}, current={}. Migrating index...",
+                    ver, SCHEMA_VERSION
+                );
+                Self::upgrade_index(index_path, memory_limit_mb)?;
+            }
+        } else if index_path.join("meta.json").exists() {
+            // Old index without version - rebuild
+            warn!("No schema version found. Migrating index...");
+            Self::upgrade_index(index_path, memory_limit_mb)?;
+        } else {
+            // New index - write version
+            write_schema_version(index_path, SCHEMA_VERSION)?;
+        }
+
+        let directory = open_directory(index_path)
+            .map_err(|e| FlashError::index(format!("Failed to open index directory: {e}")))?;
+
+        let index = match Index::open_or_create(directory, schema.clone()) {
+            Ok(idx) => idx,
+            Err(e) => {
+                // Check if it's a schema mismatch error
+                let err_str = e.to_string();
+                if err_str.contains("Schema error") || err_str.contains("Inconsistent") {
+                    warn!(
+                        "Tantivy detected schema mismatch: {}. Forcing index rebuild...",
+                        err_str
+                    );
+
+                    Self::rebuild_index_internal(index_path)?;
+
+                    let new_directory = open_directory(index_path).map_err(|e| {
+                        FlashError::index(format!("Failed to re-open index directory: {e}"))
+                    })?;
+                    Index::open_or_create(new_directory, schema).map_err(|e| {
+                        FlashError::index(format!("Failed to create new index after reset: {e}"))
+                    })?
+                } else {
+                    return Err(FlashError::index(format!(
+                        "Failed to open or create index: {e}"
+                    )));
+                }
+            }
+        };
+
+        index.tokenizers().register(
+            code_tokenizer::CODE_TOKENIZER,
+            code_tokenizer::code_analyzer(),
+        );
+        index
+            .tokenizers()
+            .register(schema::OWNER_TOKENIZER, schema::owner_analyzer());
+
+        info!(
+            "Opened index at {} with schema version {}",
+            index_path.display(),
+            SCHEMA_VERSION
+        );
+
+        let writer = IndexWriterManager::new(&index, memory_limit_mb)?;
+        let searcher = Arc::new(IndexSearcher::new(&index, index_path.to_path_buf())?);
+        writer.on_commit({
+            let searcher = searcher.clone();
+            move || {
+                searcher.refresh();
+                searcher.warm_in_background();
+            }
+        });
+
+        Ok(Self {
+            root,
+            path: index_path.to_path_buf(),
+            writer,
+            searcher,
+        })
+    }
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in ignore::WalkBuilder::new(src)
+        .hidden(false)
+        .git_ignore(false)
+        .ignore(false)
+        .parents(false)
+        .build()
+        .skip(1)
+    {
+        let entry = entry.map_err(std::io::Error::other)?;
+        let ty = entry.file_type().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Could not get file type")
+        })?;
+        let path = entry.path();
+        let relative = path.strip_prefix(src).map_err(std::io::Error::other)?;
+        let target = dst.join(relative);
+        if ty.is_dir() {
+            std::fs::create_dir_all(target)?;
+        } else {
+            std::fs::copy(path, target)?;
+        }
+    }
+    Ok(())
+}
@@ -1,10 +1,19 @@
+use super::category::FileCategory;
+use super::code_tokenizer::is_code_extension;
 use crate::error::{FlashError, Result};
 use crate::parsers::ParsedDocument;
+use crate::settings::IndexWriterSettings;
 use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tantivy::merge_policy::LogMergePolicy;
 use tantivy::schema::{Field, Schema};
 use tantivy::{Index, IndexWriter, TantivyDocument};
 use tracing::info;
 
+/// Called after every commit, e.g. to make it searchable
+type CommitHook = Box<dyn Fn() + Send + Sync>;
+
 /// Manages writing documents to the Tantivy index with batch support
 pub struct IndexWriterManager {
     writer: Mutex<IndexWriter>,
@@ -12,12 +21,28 @@ pub struct IndexWriterManager {
     schema: Schema,
     path_field: Field,
     content_field: Field,
+    identifiers_field: Field,
+    annotations_field: Field,
     title_field: Field,
     modified_field: Field,
+    created_field: Field,
     size_field: Field,
     extension_field: Field,
     language_field: Field,
     keywords_field: Field,
+    owner_field: Field,
+    flags_field: Field,
+    volume_field: Field,
+    mime_field: Field,
+    category_field: Field,
+    /// Whether source files also feed the identifier-splitting field
+    code_aware: AtomicBool,
+    /// Approximate bytes of text added since the last commit
+    uncommitted_bytes: AtomicU64,
+    last_commit: Mutex<Instant>,
+    commit_every_bytes: AtomicU64,
+    commit_interval_secs: AtomicU64,
+    commit_hooks: Mutex<Vec<CommitHook>>,
 }
 
 impl IndexWriterManager {
@@ -49,6 +74,7 @@ impl IndexWriterManager {
 
     pub fn new(index: &Index, memory_limit_mb: u32) -> Result<Self> {
         let schema = index.schema();
+        let pacing = IndexWriterSettings::default();
 
         // Use user-provided memory limit if it's within reasonable bounds,
         // otherwise fall back to adaptive calculation.
@@ -78,12 +104,21 @@ impl IndexWriterManager {
         let content_field = schema
             .get_field("content")
             .map_err(|_| FlashError::index_field("content", "Field not found in schema"))?;
+        let identifiers_field = schema
+            .get_field("identifiers")
+            .map_err(|_| FlashError::index_field("identifiers", "Field not found in schema"))?;
+        let annotations_field = schema
+            .get_field("annotations")
+            .map_err(|_| FlashError::index_field("annotations", "Field not found in schema"))?;
         let title_field = schema
             .get_field("title")
             .map_err(|_| FlashError::index_field("title", "Field not found in schema"))?;
         let modified_field = schema
             .get_field("modified")
             .map_err(|_| FlashError::index_field("modified", "Field not found in schema"))?;
+        let created_field = schema
+            .get_field("created")
+            .map_err(|_| FlashError::index_field("created", "Field not found in schema"))?;
         let size_field = schema
             .get_field("size")
             .map_err(|_| FlashError::index_field("size", "Field not found in schema"))?;
@@ -97,49 +132,130 @@ impl IndexWriterManager {
             .get_field("keywords")
             .map_err(|_| FlashError::index_field("keywords", "Field not found in schema"))?;
 
+        let owner_field = schema
+            .get_field("owner")
+            .map_err(|_| FlashError::index_field("owner", "Field not found in schema"))?;
+        let flags_field = schema
+            .get_field("flags")
+            .map_err(|_| FlashError::index_field("flags", "Field not found in schema"))?;
+        let volume_field = schema
+            .get_field("volume")
+            .map_err(|_| FlashError::index_field("volume", "Field not found in schema"))?;
+        let mime_field = schema
+            .get_field("mime")
+            .map_err(|_| FlashError::index_field("mime", "Field not found in schema"))?;
+        let category_field = schema
+            .get_field("category")
+            .map_err(|_| FlashError::index_field("category", "Field not found in schema"))?;
+
         Ok(Self {
             writer: Mutex::new(writer),
             schema,
             path_field,
             content_field,
+            identifiers_field,
+            annotations_field,
             title_field,
             modified_field,
+            created_field,
             size_field,
             extension_field,
             language_field,
             keywords_field,
+            owner_field,
+            flags_field,
+            volume_field,
+            mime_field,
+            category_field,
+            code_aware: AtomicBool::new(true),
+            uncommitted_bytes: AtomicU64::new(0),
+            last_commit: Mutex::new(Instant::now()),
+            commit_every_bytes: AtomicU64::new(pacing.commit_every_bytes()),
+            commit_interval_secs: AtomicU64::new(pacing.commit_interval_secs),
+            commit_hooks: Mutex::new(Vec::new()),
         })
     }
 
-    /// Add a single document to the index
+    /// Applies the commit pacing used by [`Self::commit_if_due`] and the merge
+    /// policy for segments written from now on.
+    pub fn configure(&self, settings: &IndexWriterSettings) {
+        self.commit_every_bytes
+            .store(settings.commit_every_bytes(), Ordering::Relaxed);
+        self.commit_interval_secs
+            .store(settings.commit_interval_secs, Ordering::Relaxed);
+
+        let mut policy = LogMergePolicy::default();
+        policy.set_min_num_segments(settings.merge_min_segments.max(2));
+        policy.set_min_layer_size(settings.merge_min_layer_docs);
+        policy.set_max_docs_before_merge(settings.merge_max_docs);
+        self.writer.lock().set_merge_policy(Box::new(policy));
+    }
+
+    /// Runs `hook` after every commit and merge from now on, so no write
+    /// path can leave readers or caches behind the index.
+    pub fn on_commit(&self, hook: impl Fn() + Send + Sync + 'static) {
+        self.commit_hooks.lock().push(Box::new(hook));
+    }
+
+    fn run_commit_hooks(&self) {
+        for hook in self.commit_hooks.lock().iter() {
+            hook();
+        }
+    }
+
+    pub fn set_code_aware(&self, enabled: bool) {
+        self.code_aware.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Add a single document to the index, replacing any earlier one for the path
     /// Note: For better performance, use `add_documents_batch` for multiple docs
     pub fn add_document(&self, doc: &ParsedDocument, modified: u64, size: u64) -> Result<()> {
         let tantivy_doc = self.create_tantivy_document(doc, modified, size);
 
-        self.writer
-            .lock()
+        let writer = self.writer.lock();
+        writer.delete_term(tantivy::Term::from_field_text(self.path_field, &doc.path));
+        writer
             .add_document(tantivy_doc)
             .map_err(|e| FlashError::index(format!("Failed to add document: {e}")))?;
+        drop(writer);
+        self.uncommitted_bytes
+            .fetch_add(Self::text_bytes(doc), Ordering::Relaxed);
 
         Ok(())
     }
 
+    /// Runs `f` while holding the writer, so no commit changes the index files
+    /// underneath it.
+    pub fn with_commits_paused<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _writer = self.writer.lock();
+        f()
+    }
+
     /// Add multiple documents in a single lock acquisition (much more efficient)
-    pub fn add_documents_batch(&self, docs: &[(ParsedDocument, u64, u64)]) -> Result<()> {
-        if docs.is_empty() {
+    pub fn add_documents_batch<'a>(
+        &self,
+        docs: impl IntoIterator<Item = &'a (ParsedDocument, u64, u64)>,
+    ) -> Result<()> {
+        let mut docs = docs.into_iter().peekable();
+        if docs.peek().is_none() {
             return Ok(());
         }
 
         let writer = self.writer.lock();
+        let mut bytes = 0;
 
         for (doc, modified, size) in docs {
+            bytes += Self::text_bytes(doc);
             let tantivy_doc = self.create_tantivy_document(doc, *modified, *size);
+            // A re-indexed file replaces its old document instead of duplicating it
+            writer.delete_term(tantivy::Term::from_field_text(self.path_field, &doc.path));
             writer
                 .add_document(tantivy_doc)
                 .map_err(|e| FlashError::index(format!("Failed to add document: {e}")))?;
         }
 
         drop(writer);
+        self.uncommitted_bytes.fetch_add(bytes, Ordering::Relaxed);
         Ok(())
     }
 
@@ -168,17 +284,48 @@ impl IndexWriterManager {
             document.add_text(self.keywords_field, keywords);
         }
 
+        if let Some(ref annotations) = doc.annotations {
+            document.add_text(self.annotations_field, annotations);
+        }
+
+        if let Some(ref owner) = doc.attributes.owner {
+            document.add_text(self.owner_field, owner);
+        }
+
+        for flag in doc.attributes.flag_names() {
+            document.add_text(self.flags_field, flag);
+        }
+
+        if let Some(ref volume) = doc.attributes.volume {
+            document.add_text(self.volume_field, volume);
+        }
+
         let modified_date =
             tantivy::DateTime::from_timestamp_secs(i64::try_from(modified).unwrap_or(i64::MAX));
         document.add_date(self.modified_field, modified_date);
+        if let Some(created) = doc.attributes.created {
+            document.add_date(
+                self.created_field,
+                tantivy::DateTime::from_timestamp_secs(i64::try_from(created).unwrap_or(i64::MAX)),
+            );
+        }
         document.add_u64(self.size_field, size);
 
         // Index file extension for fast filtering
-        if let Some(ext) = std::path::Path::new(&doc.path)
+        let ext = std::path::Path::new(&doc.path)
             .extension()
-            .and_then(|e| e.to_str())
-        {
+            .and_then(|e| e.to_str());
+
+        let (category, mime) = FileCategory::classify(ext.unwrap_or_default());
+        document.add_text(self.mime_field, mime);
+        document.add_text(self.category_field, category.to_string());
+
+        if let Some(ext) = ext {
             document.add_text(self.extension_field, ext.to_lowercase());
+
+            if self.code_aware.load(Ordering::Relaxed) && is_code_extension(ext) {
+                document.add_text(self.identifiers_field, &doc.content);
+            }
         }
 
         document
@@ -192,6 +339,16 @@ impl IndexWriterManager {
         Ok(())
     }
 
+    /// Remove several documents under a single lock acquisition
+    pub fn remove_documents_batch(&self, paths: &[String]) -> Result<()> {
+        let writer = self.writer.lock();
+        for path in paths {
+            writer.delete_term(tantivy::Term::from_field_text(self.path_field, path));
+        }
+
+        Ok(())
+    }
+
     /// Delete all documents from the index
     pub fn delete_all_documents(&self) -> Result<()> {
         self.writer
@@ -204,11 +361,155 @@ impl IndexWriterManager {
 
     /// Commit pending changes to disk
     pub fn commit(&self) -> Result<()> {
-        self.writer
-            .lock()
+        let mut writer = self.writer.lock();
+        writer
             .commit()
             .map_err(|e| FlashError::index(format!("Failed to commit index: {e}")))?;
+        self.uncommitted_bytes.store(0, Ordering::Relaxed);
+        *self.last_commit.lock() = Instant::now();
+        drop(writer);
 
+        self.run_commit_hooks();
         Ok(())
     }
+
+    /// Text a document feeds the index, the part that grows the writer's
+    /// buffers and the next segment. Every document counts for at least one byte.
+    fn text_bytes(doc: &ParsedDocument) -> u64 {
+        u64::try_from(doc.text_len()).unwrap_or(u64::MAX).max(1)
+    }
+
+    /// Commits once enough text was added or enough time passed since the
+    /// last commit. Returns whether it committed.
+    pub fn commit_if_due(&self) -> Result<bool> {
+        let pending = self.uncommitted_bytes.load(Ordering::Relaxed);
+        if pending == 0 {
+            return Ok(false);
+        }
+        let interval = Duration::from_secs(self.commit_interval_secs.load(Ordering::Relaxed));
+        if pending < self.commit_every_bytes.load(Ordering::Relaxed)
+            && self.last_commit.lock().elapsed() < interval
+        {
+            return Ok(false);
+        }
+        self.commit()?;
+        Ok(true)
+    }
+
+    /// Commits, then merges every segment into one and deletes the files the
+    /// merge replaced. Returns how many segments were merged, 0 if the index
+    /// already had one.
+    pub fn optimize(&self) -> Result<usize> {
+        self.commit()?;
+        let mut writer = self.writer.lock();
+        let segments = writer
+            .index()
+            .searchable_segment_ids()
+            .map_err(|e| FlashError::index(format!("Failed to list segments: {e}")))?;
+        if segments.len() < 2 {
+            return Ok(0);
+        }
+        // Wait for the merge without holding the writer, so indexing goes on
+        let merge = writer.merge(&segments);
+        drop(writer);
+        merge
+            .wait()
+            .map_err(|e| FlashError::index(format!("Failed to merge segments: {e}")))?;
+
+        let collect = self.writer.lock().garbage_collect_files();
+        collect
+            .wait()
+            .map_err(|e| FlashError::index(format!("Failed to remove merged segments: {e}")))?;
+        self.run_commit_hooks();
+        Ok(segments.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indexer::IndexManager;
+    use crate::indexer::searcher::SearchParams;
+    use crate::parsers::ParsedDocument;
+    use crate::settings::IndexWriterSettings;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn doc(name: &str) -> (ParsedDocument, u64, u64) {
+        (ParsedDocument::filename_only(Path::new(name)), 0, 1)
+    }
+
+    #[test]
+    fn test_commit_pacing_and_optimize() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(dir.path(), 64).unwrap();
+        indexer.configure_writer(&IndexWriterSettings {
+            commit_every_mb: 1,
+            commit_interval_secs: 3600,
+            ..IndexWriterSettings::default()
+        });
+
+        indexer.add_documents_batch(&[doc("/a.txt")]).unwrap();
+        assert!(!indexer.commit_if_due().unwrap());
+        let mut large = doc("/b.txt");
+        large.0.content = "x".repeat(1 << 20);
+        indexer.add_documents_batch(&[large]).unwrap();
+        assert!(indexer.commit_if_due().unwrap());
+        assert!(!indexer.commit_if_due().unwrap());
+
+        // Every commit leaves a segment behind
+        indexer.add_documents_batch(&[doc("/c.txt")]).unwrap();
+        indexer.commit().unwrap();
+        assert_eq!(indexer.optimize().unwrap(), 2);
+        assert_eq!(indexer.optimize().unwrap(), 0);
+        assert_eq!(indexer.get_indexed_files().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_every_commit_drops_cached_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = Arc::new(IndexManager::open(dir.path(), 64).unwrap());
+        let search = || {
+            let indexer = indexer.clone();
+            async move {
+                let params = SearchParams::builder()
+                    .query("report")
+                    .limit(10)
+                    .case_sensitive(false)
+                    .build();
+                indexer.search(params).await.unwrap().len()
+            }
+        };
+
+        assert_eq!(search().await, 0);
+        assert_eq!(search().await, 0);
+        // Committed straight through the shard's writer, as the scanner does
+        let mut report = doc("/report.txt");
+        report.0.content = "quarterly report".to_string();
+        let shard = indexer.default_shard();
+        shard.writer.add_documents_batch(&[report]).unwrap();
+        shard.writer.commit().unwrap();
+        assert_eq!(search().await, 1);
+
+        let stats = indexer.get_statistics().unwrap();
+        assert_eq!((stats.cache_hits, stats.cache_misses), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_volume_is_stored_per_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = IndexManager::open(dir.path(), 64).unwrap();
+        let mut report = doc("/media/usb/report.txt");
+        report.0.content = "quarterly report".to_string();
+        report.0.attributes.volume = Some("1A2B-3C4D".to_string());
+        indexer.add_documents_batch(&[report]).unwrap();
+        indexer.commit().unwrap();
+
+        let params = SearchParams::builder()
+            .query("report")
+            .limit(10)
+            .case_sensitive(false)
+            .build();
+        let results = indexer.search(params).await.unwrap();
+        assert_eq!(results[0].volume.as_deref(), Some("1A2B-3C4D"));
+    }
 }
@@ -5,8 +5,10 @@
 #![allow(clippy::module_name_repetitions)]
 #![allow(clippy::large_futures)]
 
+pub mod cli;
 pub mod commands;
 pub mod error;
+pub mod i18n;
 pub mod iced_ui;
 pub mod indexer;
 pub mod metadata;
@@ -25,13 +27,13 @@ pub fn is_shutting_down() -> bool {
 }
 
 use crate::error::FlashError;
-use crate::indexer::searcher::SearchParams;
 use commands::AppState;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
-pub fn get_app_data_dir() -> std::result::Result<PathBuf, FlashError> {
+/// Directory holding the data of every profile, and the default profile's own.
+pub fn get_base_data_dir() -> std::result::Result<PathBuf, FlashError> {
     #[cfg(target_os = "windows")]
     let path = dirs::config_dir()
         .ok_or_else(|| FlashError::config("config_dir", "Could not find config directory"))?;
@@ -46,6 +48,12 @@ pub fn get_app_data_dir() -> std::result::Result<PathBuf, FlashError> {
     Ok(path)
 }
 
+/// Data directory of the profile this process serves.
+pub fn get_app_data_dir() -> std::result::Result<PathBuf, FlashError> {
+    let base = get_base_data_dir()?;
+    Ok(system::profile::data_dir(&base, system::profile::active()))
+}
+
 pub fn setup_app() -> std::result::Result<
     (
         Arc<AppState>,
@@ -59,6 +67,13 @@ pub fn setup_app() -> std::result::Result<
         std::fs::create_dir_all(&app_data_dir)
             .map_err(|e| FlashError::config("create_dir", e.to_string()))?;
     }
+    // Two processes writing the index or the metadata store corrupt them
+    system::instance::acquire(&app_data_dir)?;
+    // A restore is staged by the previous session and swapped in before
+    // anything opens the stores
+    if let Err(e) = system::backup::apply_pending_restore(&app_data_dir) {
+        error!("Failed to apply staged restore: {}", e);
+    }
 
     info!("App data directory: {:?}", app_data_dir);
 
@@ -67,6 +82,12 @@ pub fn setup_app() -> std::result::Result<
         warn!("Failed to load settings (using defaults): {}", e);
         settings::AppSettings::default()
     });
+    // Encrypted stores can't be opened without their key, and stores whose
+    // encryption setting changed are converted before they are opened
+    system::vault::unlock(&app_data_dir, settings.index_encryption)?;
+    system::activity::init(&app_data_dir, settings.activity_log);
+    scanner::access::set_elevated_roots(&settings.index_roots);
+    scanner::cloud::set_content_folders(&settings.cloud_content_folders);
     let index_path = app_data_dir.join("index");
     let indexer =
         indexer::IndexManager::open(&index_path, settings.memory_limit_mb).map_err(|e| {
@@ -75,6 +96,8 @@ pub fn setup_app() -> std::result::Result<
                 field: None,
             }
         })?;
+    indexer.set_code_aware_indexing(settings.code_aware_indexing);
+    indexer.configure_writer(&settings.index_writer);
     let db_path = app_data_dir.join("metadata.redb");
     let (metadata_db, db_corrupted) = metadata::MetadataDb::open(&db_path)
         .map_err(|e| FlashError::database("open", "metadata.redb", e.to_string()))?;
@@ -95,12 +118,18 @@ pub fn setup_app() -> std::result::Result<
     let watcher = watcher::WatcherManager::new_with_excludes(
         indexer_shared.clone(),
         metadata_db_shared.clone(),
+        filename_index.clone(),
         settings.get_allowed_extensions().clone(),
-        &settings.exclude_patterns,
+        &settings.exclude_list(),
+        settings.watcher,
         settings.enable_ocr,
     );
+    watcher.set_sensitive_files(scanner::sensitive::SensitiveMatcher::from_settings(
+        &settings,
+    ));
 
     let (progress_tx, progress_rx) = flume::bounded(100);
+    let index_roots = settings.index_roots.clone();
 
     let scanner = Arc::new(crate::scanner::Scanner::new(
         indexer_shared.clone(),
@@ -123,132 +152,87 @@ pub fn setup_app() -> std::result::Result<
             .build(),
     );
 
+    if let Err(e) = commands::sync_index_shards(&state, &index_roots) {
+        error!("Failed to open separate root indexes: {}", e);
+    }
+
+    // Documents a schema migration carried over without their content are
+    // journaled, so the recovery below re-parses them
+    let reparse = state.indexer.pending_reparse();
+    if !reparse.is_empty() {
+        match state.metadata_db.begin_batch(&reparse, &[]) {
+            Ok(_) => {
+                if let Err(e) = state.indexer.clear_pending_reparse() {
+                    warn!("Failed to clear migration re-parse list: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to queue migrated documents for re-parsing: {}", e),
+        }
+    }
+
+    // Bring the stores back in step if the last session died mid-batch
+    match state.watcher.lock().recover_pending_batches() {
+        Ok(0) => {}
+        Ok(count) => info!("Replaying {} unfinished file change(s)", count),
+        Err(e) => warn!("Failed to recover unfinished index batches: {}", e),
+    }
+
     Ok((state, progress_rx))
 }
 
+/// Starts what runs beside the window once the stores are open: the IPC
+/// server, index warming, the re-index scheduler and the connectivity
+/// monitor.
+pub fn start_services(state: &Arc<AppState>) {
+    tokio::spawn(system::ipc::serve(state.clone()));
+    tokio::spawn({
+        let state = state.clone();
+        async move {
+            match commands::warm_index_internal(&state).await {
+                Ok(segments) => info!("Warmed {} index segment(s)", segments),
+                Err(e) => warn!("Failed to warm the index: {}", e),
+            }
+        }
+    });
+    system::scheduler::spawn_scheduler(state.clone());
+    system::connectivity::spawn_connectivity_monitor(state.clone());
+}
+
+/// Unlocks the stores of the active profile with `passphrase`, converting
+/// them first if their encryption setting changed. [`setup_app`] can open
+/// them afterwards.
+pub fn unlock_stores(passphrase: String) -> std::result::Result<(), FlashError> {
+    system::vault::provide_passphrase(passphrase);
+    let app_data_dir = get_app_data_dir()?;
+    let settings = settings::SettingsManager::new(&app_data_dir)
+        .load()
+        .unwrap_or_default();
+    system::vault::unlock(&app_data_dir, settings.index_encryption)
+}
+
 /// Main entry point for the Iced GUI
 ///
 /// # Errors
 ///
-/// Returns a `FlashError` if the GUI fails to initialize or run.
+/// Returns a `FlashError` if the GUI fails to initialize or run, and
+/// [`FlashError::AlreadyRunning`] if another instance has the data directory;
+/// that instance's window is brought to the front instead.
 pub fn run_ui(initial_dir: Option<String>) -> std::result::Result<(), FlashError> {
     let (state_res, rx) = match setup_app() {
+        Err(e @ FlashError::AlreadyRunning { .. }) => {
+            if let Err(err) = system::ipc::request_focus() {
+                warn!("Failed to bring the running instance to the front: {}", err);
+            }
+            return Err(e);
+        }
         Ok((state, rx)) => {
-            tokio::spawn(start_ipc_server(state.clone()));
+            start_services(&state);
             (Ok(state), rx)
         }
-        Err(e) => (Err(e.to_string()), flume::bounded(1).1),
+        // A locked index has the window ask for its passphrase
+        Err(e) => (Err(e), flume::bounded(1).1),
     };
 
     iced_ui::run_ui(&state_res, rx, initial_dir);
     Ok(())
 }
-
-pub async fn run_cli(
-    query: Option<String>,
-    is_json: bool,
-    _index_path: Option<String>,
-) -> crate::error::Result<()> {
-    if let Some(query_str) = query {
-        let (state, _) = setup_app()?;
-        let results = state
-            .indexer
-            .search(
-                SearchParams::builder()
-                    .query(&query_str)
-                    .limit(20)
-                    .case_sensitive(false)
-                    .build(),
-            )
-            .await?;
-
-        if is_json {
-            let json_results: Vec<serde_json::Value> = results
-                .into_iter()
-                .map(|res| {
-                    serde_json::json!({
-                        "score": res.score,
-                        "path": res.file_path,
-                        "title": res.title
-                    })
-                })
-                .collect();
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json_results).unwrap_or_default()
-            );
-        } else {
-            for res in results {
-                println!("{} | {}", res.score, res.file_path);
-            }
-        }
-    } else {
-        println!("Usage: flash-search --cli <query> [--json]");
-    }
-    Ok(())
-}
-
-async fn start_ipc_server(state: Arc<AppState>) {
-    let addr = "127.0.0.1:9095";
-    let listener = match tokio::net::TcpListener::bind(addr).await {
-        Ok(l) => l,
-        Err(e) => {
-            tracing::error!("Failed to bind IPC TCP listener at {}: {}", addr, e);
-            return;
-        }
-    };
-
-    tracing::info!("IPC TCP Server listening on {}", addr);
-
-    loop {
-        let Ok((mut socket, _)) = listener.accept().await else {
-            continue;
-        };
-
-        let state_clone = state.clone();
-        tokio::spawn(async move {
-            use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-            let (reader, mut writer) = socket.split();
-            let mut reader = BufReader::new(reader);
-            let mut line = String::new();
-
-            if reader.read_line(&mut line).await.is_ok() {
-                let query = line.trim();
-                if !query.is_empty() {
-                    let search_params = SearchParams::builder()
-                        .query(query)
-                        .limit(50)
-                        .case_sensitive(false)
-                        .build();
-
-                    match state_clone.indexer.search(search_params).await {
-                        Ok(results) => {
-                            let json_results: Vec<serde_json::Value> = results
-                                .into_iter()
-                                .map(|res| {
-                                    serde_json::json!({
-                                        "score": res.score,
-                                        "path": res.file_path,
-                                        "title": res.title
-                                    })
-                                })
-                                .collect();
-
-                            if let Ok(serialized) = serde_json::to_string(&json_results) {
-                                let _ = writer.write_all(serialized.as_bytes()).await;
-                                let _ = writer.write_all(b"\n").await;
-                            }
-                        }
-                        Err(e) => {
-                            let err_json = serde_json::json!({ "error": e.to_string() });
-                            if let Ok(serialized) = serde_json::to_string(&err_json) {
-                                let _ = writer.write_all(serialized.as_bytes()).await;
-                                let _ = writer.write_all(b"\n").await;
-                            }
-                        }
-                    }
-                }
-            }
-        });
-    }
-}
@@ -1,5 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use clap::Parser;
 use mimalloc::MiMalloc;
 
 use std::sync::atomic::Ordering;
@@ -83,102 +84,28 @@ fn spawn_update_checker() {
 }
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let is_cli = args.iter().any(|arg| arg == "--cli" || arg == "-c");
-    if is_cli {
-        let is_json = args.iter().any(|arg| arg == "--json" || arg == "-j");
-        // Find the query
-        let mut query = None;
-        for i in 1..args.len() {
-            if (args[i] == "--cli" || args[i] == "-c") && i + 1 < args.len() {
-                query = Some(args[i + 1].clone());
-                break;
-            }
-        }
-
+    let cli = flash_search::cli::Cli::parse();
+    if let Some(profile) = &cli.profile
+        && let Err(e) = flash_search::system::profile::select(profile)
+    {
+        eprintln!("{e}");
+        std::process::exit(flash_search::cli::exit_code::USAGE);
+    }
+    if let Some(command) = cli.command() {
         let rt = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
             .expect("Failed to create tokio runtime");
 
-        let run_result = rt.block_on(async { flash_search::run_cli(query, is_json, None).await });
-
-        if let Err(e) = run_result {
-            eprintln!("CLI Error: {e}");
-            std::process::exit(1);
-        }
-        std::process::exit(0);
-    }
-
-    let mut initial_dir = None;
-    if args.len() > 1 {
-        let first_arg = &args[1];
-        if std::path::Path::new(first_arg).is_dir() {
-            initial_dir = Some(first_arg.clone());
-        }
+        let code = rt.block_on(flash_search::cli::run(command, cli.json));
+        std::process::exit(code);
     }
+    let initial_dir = cli.initial_dir();
 
     let app_dir = dirs::data_local_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
         .join("com.flashsearch");
     std::fs::create_dir_all(&app_dir).ok();
-    let lock_path = app_dir.join("app.lock");
-
-    let lock_file = match std::fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&lock_path)
-    {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!(
-                "Error: Failed to open lock file at {}.",
-                lock_path.display()
-            );
-            eprintln!("Details: {e}");
-            std::process::exit(1);
-        }
-    };
-
-    let mut lock = fd_lock::RwLock::new(lock_file);
-
-    // We must keep the guard alive for the entire program to hold the OS lock.
-    // If it fails, another instance holds it.
-    let _guard_lock = lock.try_write().map_or_else(
-        |_| {
-            // App might already be running. Check for a stale PID.
-            if let Ok(pid_str) = std::fs::read_to_string(&lock_path)
-                && let Ok(pid) = pid_str.trim().parse::<u32>()
-            {
-                use sysinfo::System;
-                let mut sys = System::new();
-                sys.refresh_processes(
-                    sysinfo::ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(pid)]),
-                    true,
-                );
-                if let Some(process) = sys.process(sysinfo::Pid::from_u32(pid))
-                    && process.name().to_string_lossy().contains("flash-search")
-                {
-                    // Alive and is flash-search - just exit
-                    std::process::exit(0);
-                }
-            }
-            // If we reach here, it's either a stale lock or unreadable.
-            tracing::warn!("Lock is blocked but PID appears stale. Continuing anyway...");
-            None
-        },
-        |mut guard| {
-            use std::io::{Seek, SeekFrom, Write};
-            let _ = guard.seek(SeekFrom::Start(0));
-            let _ = guard.set_len(0);
-            let _ = write!(&mut *guard, "{}", std::process::id());
-            let _ = guard.flush();
-            Some(guard)
-        },
-    );
-
     init_logging(&app_dir);
 
     let rt = tokio::runtime::Builder::new_multi_thread()
@@ -198,8 +125,16 @@ fn main() {
     .expect("Error setting Ctrl-C handler");
 
     // Run the UI
-    if let Err(e) = flash_search::run_ui(initial_dir) {
-        error!("Application error: {}", e);
-        std::process::exit(1);
+    match flash_search::run_ui(initial_dir) {
+        Ok(()) => {}
+        // The open window is the one the user wanted
+        Err(e @ flash_search::error::FlashError::AlreadyRunning { .. }) => {
+            info!("{}", e);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            error!("Application error: {}", e);
+            std::process::exit(1);
+        }
     }
 }
@@ -0,0 +1,255 @@
+use std::path::Path;
+
+/// Ownership, permission flags and creation time captured for a file at scan time.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct FileAttributes {
+    /// Account name of the owner, without any domain prefix
+    pub owner: Option<String>,
+    pub readonly: bool,
+    pub hidden: bool,
+    /// Creation (birth) time as a Unix timestamp, where the filesystem records one
+    pub created: Option<u64>,
+    /// Identifier of the removable drive the file is on, if any
+    pub volume: Option<String>,
+}
+
+impl FileAttributes {
+    /// Reads owner and flags for `path`, leaving fields at their defaults when
+    /// the platform refuses to say.
+    #[must_use]
+    pub fn read(path: &Path) -> Self {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return Self::default();
+        };
+
+        Self {
+            owner: platform::owner_name(path, &metadata),
+            readonly: metadata.permissions().readonly(),
+            hidden: platform::is_hidden(path, &metadata),
+            created: metadata
+                .created()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            volume: crate::scanner::volumes::volume_id_of(path),
+        }
+    }
+
+    /// Flag names as indexed into the `flags` field and matched by `is:`.
+    #[must_use]
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+        if self.readonly {
+            flags.push("readonly");
+        }
+        if self.hidden {
+            flags.push("hidden");
+        }
+        flags
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::collections::HashMap;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    /// Local accounts from `/etc/passwd`. Directory-service users (LDAP, NIS)
+    /// aren't listed there and fall back to their numeric uid.
+    fn passwd_users() -> &'static HashMap<u32, String> {
+        static USERS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+        USERS.get_or_init(|| {
+            std::fs::read_to_string("/etc/passwd")
+                .map(|passwd| {
+                    passwd
+                        .lines()
+                        .filter_map(|line| {
+                            let mut fields = line.split(':');
+                            let name = fields.next()?;
+                            let uid = fields.nth(1)?.parse().ok()?;
+                            Some((uid, name.to_string()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn owner_name(_path: &Path, metadata: &std::fs::Metadata) -> Option<String> {
+        let uid = metadata.uid();
+        Some(
+            passwd_users()
+                .get(&uid)
+                .cloned()
+                .unwrap_or_else(|| uid.to_string()),
+        )
+    }
+
+    pub fn is_hidden(path: &Path, _metadata: &std::fs::Metadata) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::fs::MetadataExt;
+    use std::path::Path;
+    use std::sync::OnceLock;
+    use windows::Win32::Foundation::{ERROR_SUCCESS, HLOCAL, LocalFree};
+    use windows::Win32::Security::Authorization::{
+        ConvertSidToStringSidW, GetNamedSecurityInfoW, SE_FILE_OBJECT,
+    };
+    use windows::Win32::Security::{
+        GetLengthSid, LookupAccountSidW, OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID,
+        SID_NAME_USE,
+    };
+    use windows::core::{PCWSTR, PWSTR};
+
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+    /// SID bytes to account name; lookups can hit the domain controller, so
+    /// each owner is resolved once per process.
+    fn sid_cache() -> &'static Mutex<HashMap<Vec<u8>, Option<String>>> {
+        static CACHE: OnceLock<Mutex<HashMap<Vec<u8>, Option<String>>>> = OnceLock::new();
+        CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    pub fn owner_name(path: &Path, _metadata: &std::fs::Metadata) -> Option<String> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut owner = PSID::default();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+
+        // SAFETY: `wide` is NUL-terminated and outlives the call; on success the
+        // descriptor is freed below and `owner` points into it until then.
+        let status = unsafe {
+            GetNamedSecurityInfoW(
+                PCWSTR(wide.as_ptr()),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION,
+                Some(&raw mut owner),
+                None,
+                None,
+                None,
+                &raw mut descriptor,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+
+        // SAFETY: `owner` is a valid SID owned by `descriptor`.
+        let sid_bytes = unsafe {
+            let len = GetLengthSid(owner) as usize;
+            std::slice::from_raw_parts(owner.0.cast::<u8>(), len).to_vec()
+        };
+
+        let name = sid_cache()
+            .lock()
+            .entry(sid_bytes)
+            .or_insert_with(|| lookup_account(owner))
+            .clone();
+
+        // SAFETY: `descriptor` was allocated by GetNamedSecurityInfoW.
+        unsafe {
+            let _ = LocalFree(HLOCAL(descriptor.0));
+        }
+
+        name
+    }
+
+    fn lookup_account(sid: PSID) -> Option<String> {
+        let mut name = [0u16; 256];
+        let mut domain = [0u16; 256];
+        let mut name_len = name.len() as u32;
+        let mut domain_len = domain.len() as u32;
+        let mut sid_use = SID_NAME_USE::default();
+
+        // SAFETY: buffers and their lengths describe valid writable memory.
+        let found = unsafe {
+            LookupAccountSidW(
+                PCWSTR::null(),
+                sid,
+                PWSTR(name.as_mut_ptr()),
+                &raw mut name_len,
+                PWSTR(domain.as_mut_ptr()),
+                &raw mut domain_len,
+                &raw mut sid_use,
+            )
+        };
+
+        if found.is_ok() {
+            return Some(String::from_utf16_lossy(&name[..name_len as usize]));
+        }
+
+        // Orphaned SIDs (deleted accounts) are still worth showing as S-1-5-...
+        let mut sid_string = PWSTR::null();
+        // SAFETY: on success the returned string is freed with LocalFree.
+        unsafe {
+            ConvertSidToStringSidW(sid, &raw mut sid_string).ok()?;
+            let text = sid_string.to_string().ok();
+            let _ = LocalFree(HLOCAL(sid_string.0.cast()));
+            text
+        }
+    }
+
+    pub fn is_hidden(_path: &Path, metadata: &std::fs::Metadata) -> bool {
+        metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::path::Path;
+
+    pub const fn owner_name(_path: &Path, _metadata: &std::fs::Metadata) -> Option<String> {
+        None
+    }
+
+    pub const fn is_hidden(_path: &Path, _metadata: &std::fs::Metadata) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let hidden = dir.path().join(".secret.txt");
+        std::fs::write(&hidden, "x").unwrap();
+        let mut perms = std::fs::metadata(&hidden).unwrap().permissions();
+        perms.set_readonly(true);
+        std::fs::set_permissions(&hidden, perms).unwrap();
+
+        let attrs = FileAttributes::read(&hidden);
+        assert!(attrs.readonly);
+        assert_eq!(attrs.hidden, cfg!(unix));
+        if cfg!(unix) {
+            assert!(attrs.owner.is_some());
+        }
+        assert!(attrs.flag_names().contains(&"readonly"));
+        if std::fs::metadata(&hidden).unwrap().created().is_ok() {
+            assert!(attrs.created.is_some());
+        }
+    }
+}
@@ -1,13 +1,41 @@
+use super::attributes::FileAttributes;
+use super::encrypted_backend::EncryptedBackend;
 use crate::error::{FlashError, Result};
-use redb::{Database, ReadableTable, TableDefinition};
+use crate::models::SearchHistoryItem;
+use crate::system::vault::{self, Key};
+use redb::{Database, DatabaseError, ReadableTable, StorageError, TableDefinition};
 use rkyv;
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::SystemTime;
 
 const FILES_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("files");
+const PARSE_ERRORS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("parse_errors");
+const ANNOTATIONS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("annotations");
+/// Directory path to its mtime when an incremental scan last brought its files up to date
+const DIRS_TABLE: TableDefinition<&str, u64> = TableDefinition::new("dirs");
+/// Scan roots that started but never finished, with how far they got
+const SCAN_CHECKPOINTS_TABLE: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("scan_checkpoints");
+/// Bookkeeping for the re-index scheduler, e.g. when it last ran
+const SCHEDULE_TABLE: TableDefinition<&str, u64> = TableDefinition::new("schedule");
+const LAST_RUN_KEY: &str = "last_run";
+/// Prefix of the keys holding when roots on a schedule of their own last ran
+const ROOT_RUN_PREFIX: &str = "root:";
+/// Volume (e.g. "C:") to the change journal id and the USN read up to
+const USN_TABLE: TableDefinition<&str, (u64, i64)> = TableDefinition::new("usn_journal");
+/// Write-ahead journal: batches whose index, metadata and filename-index
+/// writes have not all completed, keyed by an increasing batch id
+const PENDING_BATCHES_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("pending_batches");
+/// Past searches: query to (times run, last run, pinned)
+const SEARCH_HISTORY_TABLE: TableDefinition<&str, (u32, u64, bool)> =
+    TableDefinition::new("search_history");
+/// Recent queries to their place in the list, most recent first
+const RECENT_SEARCHES_TABLE: TableDefinition<&str, u64> = TableDefinition::new("recent_searches");
+/// Pinned file paths to their place in the list, in the order they were pinned
+const PINNED_FILES_TABLE: TableDefinition<&str, u64> = TableDefinition::new("pinned_files");
 
 #[derive(Debug, Clone, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
 pub struct FileMetadata {
@@ -16,6 +44,7 @@ pub struct FileMetadata {
     pub size: u64,              // File size in bytes
     pub content_hash: [u8; 32], // Blake3 hash for content deduplication
     pub indexed_at: u64,        // When this file was last indexed
+    pub attributes: FileAttributes,
 }
 
 impl FileMetadata {
@@ -31,6 +60,7 @@ pub struct FileMetadataBuilder {
     size: Option<u64>,
     content_hash: Option<[u8; 32]>,
     indexed_at: Option<u64>,
+    attributes: FileAttributes,
 }
 
 impl FileMetadataBuilder {
@@ -64,6 +94,12 @@ impl FileMetadataBuilder {
         self
     }
 
+    #[must_use]
+    pub fn attributes(mut self, attributes: FileAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     /// Builds the `FileMetadata`.
     ///
     /// # Panics
@@ -76,12 +112,298 @@ impl FileMetadataBuilder {
             size: self.size.expect("size is required"),
             content_hash: self.content_hash.expect("content_hash is required"),
             indexed_at: self.indexed_at.expect("indexed_at is required"),
+            attributes: self.attributes,
         }
     }
 }
 
 pub type RecentFileEntry = (String, Option<String>, u64, u64);
 
+/// Why a file could not be parsed during the last scan.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub enum ParseErrorKind {
+    Failed,
+    Timeout,
+    Panic,
+}
+
+impl ParseErrorKind {
+    pub const fn from_error(err: &FlashError) -> Self {
+        match err {
+            FlashError::ParseTimeout { .. } => Self::Timeout,
+            FlashError::ParserPanic { .. } => Self::Panic,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// A parse failure remembered so the file is skipped until it changes on disk.
+#[derive(
+    Debug,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct ParseErrorRecord {
+    pub path: String,
+    pub kind: ParseErrorKind,
+    pub message: String,
+    pub modified: u64,
+    pub size: u64,
+    pub occurred_at: u64,
+}
+
+/// User-authored note and `key: value` attributes attached to a file.
+///
+/// Progress of a scan that has not finished yet. Files are committed to the
+/// index before they are counted here, so everything counted survives a crash
+/// and is skipped when the scan resumes.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct ScanCheckpoint {
+    pub root: String,
+    pub started_at: u64,
+    /// Files committed to the index so far, across resumes
+    pub files_committed: u64,
+    /// When the last batch was committed
+    pub updated_at: u64,
+}
+
+/// A batch of index writes recorded before it is applied. One left behind at
+/// startup means the process died part-way, so the search index, the metadata
+/// and the filename index may disagree about these paths.
+#[derive(Debug, Clone, PartialEq, Eq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+pub struct PendingBatch {
+    pub id: u64,
+    /// Files being (re-)indexed
+    pub indexed: Vec<String>,
+    /// Files or directories being removed
+    pub removed: Vec<String>,
+}
+
+/// How far the change journal of an NTFS volume has been read. The id changes
+/// when the journal is deleted and re-created, invalidating the USN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsnPosition {
+    pub journal_id: u64,
+    pub next_usn: i64,
+}
+
+/// Annotations outlive index rebuilds and file removals so a file that is
+/// re-created (e.g. by an editor saving via rename) keeps its notes.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct FileAnnotation {
+    pub note: String,
+    pub attributes: Vec<(String, String)>,
+    pub updated_at: u64,
+}
+
+impl FileAnnotation {
+    /// Parse `key: value` pairs separated by commas or newlines, skipping
+    /// entries without a key.
+    #[must_use]
+    pub fn parse_attributes(input: &str) -> Vec<(String, String)> {
+        input
+            .split([',', '\n'])
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once(':')?;
+                let key = key.trim();
+                (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Attributes rendered back into the form [`Self::parse_attributes`] reads.
+    #[must_use]
+    pub fn attributes_text(&self) -> String {
+        self.attributes
+            .iter()
+            .map(|(k, v)| format!("{k}: {v}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Text fed into the index's `annotations` field.
+    #[must_use]
+    pub fn search_text(&self) -> String {
+        let mut text = self.note.clone();
+        for (key, value) in &self.attributes {
+            text.push('\n');
+            text.push_str(key);
+            text.push(' ');
+            text.push_str(value);
+        }
+        text
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.note.trim().is_empty() && self.attributes.is_empty()
+    }
+}
+
+fn decode_pending_batch(bytes: &[u8]) -> Option<PendingBatch> {
+    let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+    aligned_bytes.extend_from_slice(bytes);
+    rkyv::from_bytes::<PendingBatch, rkyv::rancor::Error>(&aligned_bytes).ok()
+}
+
+fn decode_checkpoint(bytes: &[u8]) -> Option<ScanCheckpoint> {
+    let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+    aligned_bytes.extend_from_slice(bytes);
+    rkyv::from_bytes::<ScanCheckpoint, rkyv::rancor::Error>(&aligned_bytes).ok()
+}
+
+/// Opens or creates the database at `path`, encrypted if the stores are.
+fn create_database(path: &Path) -> std::result::Result<Database, DatabaseError> {
+    create_database_with(path, vault::key())
+}
+
+fn create_database_with(
+    path: &Path,
+    key: Option<&Key>,
+) -> std::result::Result<Database, DatabaseError> {
+    match key {
+        Some(key) => {
+            let backend = EncryptedBackend::open(path, key)
+                .map_err(|e| DatabaseError::Storage(StorageError::Io(e)))?;
+            Database::builder().create_with_backend(backend)
+        }
+        None => Database::create(path),
+    }
+}
+
+/// Copies every table of `source` into `target`. A table missing from
+/// `source`, as in a store from an older version, is left out.
+fn copy_tables(
+    source: &redb::ReadTransaction,
+    target: &redb::WriteTransaction,
+) -> std::result::Result<(), redb::Error> {
+    fn copy_table<K: redb::Key + 'static, V: redb::Value + 'static>(
+        source: &redb::ReadTransaction,
+        target: &redb::WriteTransaction,
+        table: TableDefinition<K, V>,
+    ) -> std::result::Result<(), redb::Error> {
+        let from = match source.open_table(table) {
+            Ok(from) => from,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut to = target.open_table(table)?;
+        for entry in from.iter()? {
+            let (key, value) = entry?;
+            to.insert(key.value(), value.value())?;
+        }
+        Ok(())
+    }
+
+    copy_table(source, target, FILES_TABLE)?;
+    copy_table(source, target, PARSE_ERRORS_TABLE)?;
+    copy_table(source, target, ANNOTATIONS_TABLE)?;
+    copy_table(source, target, DIRS_TABLE)?;
+    copy_table(source, target, SCAN_CHECKPOINTS_TABLE)?;
+    copy_table(source, target, SCHEDULE_TABLE)?;
+    copy_table(source, target, USN_TABLE)?;
+    copy_table(source, target, PENDING_BATCHES_TABLE)?;
+    copy_table(source, target, SEARCH_HISTORY_TABLE)?;
+    copy_table(source, target, RECENT_SEARCHES_TABLE)?;
+    copy_table(source, target, PINNED_FILES_TABLE)?;
+    Ok(())
+}
+
+/// Rewrites the metadata store at `db_path` encrypted with `to`, or
+/// unencrypted without it, opening it with whichever of `keys` it is
+/// encrypted with. The copy replaces the store only once it is complete.
+pub fn convert_store(db_path: &Path, keys: &[&Key], to: Option<&Key>) -> Result<()> {
+    let error =
+        |e: &dyn std::fmt::Display| FlashError::database("convert", "metadata.redb", e.to_string());
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let from = EncryptedBackend::key_of(db_path, keys)
+        .map_err(|e| error(&e))?
+        .ok_or_else(|| error(&"none of the keys opens it"))?;
+    // Already converted by an earlier run that was cut short
+    let done = match (from, to) {
+        (None, None) => true,
+        (Some(from), Some(to)) => std::ptr::eq(from, to),
+        _ => false,
+    };
+    if done {
+        return Ok(());
+    }
+
+    let tmp = db_path.with_extension("redb.tmp");
+    let _ = std::fs::remove_file(&tmp);
+    let copy = || -> std::result::Result<(), redb::Error> {
+        let source = create_database_with(db_path, from)?;
+        let target = create_database_with(&tmp, to)?;
+        let read = source.begin_read()?;
+        let write = target.begin_write()?;
+        copy_tables(&read, &write)?;
+        write.commit()?;
+        Ok(())
+    };
+    copy().map_err(|e| error(&e))?;
+    std::fs::rename(&tmp, db_path).map_err(|e| error(&e))
+}
+
+/// Whether the store at `db_path` is encrypted, or would be when created.
+fn is_encrypted_store(db_path: &Path) -> bool {
+    let mut magic = [0u8; 8];
+    vault::key().is_some()
+        || std::fs::File::open(db_path)
+            .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+            .is_ok_and(|()| vault::is_sealed(&magic))
+}
+
+/// The error for an encrypted store that didn't open. Such a store is never
+/// wiped and rebuilt: without its key, or with the wrong one, it only looks
+/// damaged.
+fn encrypted_open_error(cause: impl std::fmt::Display) -> FlashError {
+    if vault::key().is_none() {
+        return FlashError::Locked {
+            new: false,
+            wrong: false,
+        };
+    }
+    FlashError::database("database_operation", "files_table", cause.to_string())
+}
+
 /// Manages file metadata database using redb
 /// Implements connection pooling pattern for redb (even though it's embedded)
 /// to ensure proper resource management and monitoring
@@ -90,16 +412,18 @@ pub struct MetadataDb {
 }
 
 impl MetadataDb {
-    /// Open or create the metadata database
+    /// Open or create the metadata database. A store that fails to open is
+    /// wiped and recreated, unless it is encrypted.
     pub fn open(db_path: &Path) -> Result<(Self, bool)> {
         let mut reset_occurred = false;
-        let db = match Database::create(db_path) {
+        let db = match create_database(db_path) {
             Ok(db) => Arc::new(db),
+            Err(e) if is_encrypted_store(db_path) => return Err(encrypted_open_error(e)),
             Err(e) => {
                 reset_occurred = true;
                 tracing::warn!("Failed to open metadata database: {}. Forcing reset...", e);
                 let _ = std::fs::remove_file(db_path);
-                Arc::new(Database::create(db_path).map_err(|e| {
+                Arc::new(create_database(db_path).map_err(|e| {
                     FlashError::database("database_operation", "files_table", e.to_string())
                 })?)
             }
@@ -115,6 +439,36 @@ impl MetadataDb {
                 let _table = txn.open_table(FILES_TABLE).map_err(|e| {
                     FlashError::database("database_operation", "files_table", e.to_string())
                 })?;
+                let _errors = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?;
+                let _annotations = txn.open_table(ANNOTATIONS_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "annotations", e.to_string())
+                })?;
+                let _dirs = txn.open_table(DIRS_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "dirs", e.to_string())
+                })?;
+                let _checkpoints = txn.open_table(SCAN_CHECKPOINTS_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+                })?;
+                let _schedule = txn.open_table(SCHEDULE_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "schedule", e.to_string())
+                })?;
+                let _usn = txn.open_table(USN_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "usn_journal", e.to_string())
+                })?;
+                let _pending = txn.open_table(PENDING_BATCHES_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "pending_batches", e.to_string())
+                })?;
+                let _history = txn.open_table(SEARCH_HISTORY_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "search_history", e.to_string())
+                })?;
+                let _recent = txn.open_table(RECENT_SEARCHES_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "recent_searches", e.to_string())
+                })?;
+                let _pinned = txn.open_table(PINNED_FILES_TABLE).map_err(|e| {
+                    FlashError::database("database_operation", "pinned_files", e.to_string())
+                })?;
             }
             txn.commit().map_err(|e| {
                 FlashError::database("database_operation", "files_table", e.to_string())
@@ -122,6 +476,9 @@ impl MetadataDb {
         };
 
         if let Err(e) = init_table(&db) {
+            if is_encrypted_store(db_path) {
+                return Err(encrypted_open_error(e));
+            }
             reset_occurred = true;
             tracing::warn!(
                 "Failed to initialize database tables: {}. Wiping and recreating...",
@@ -130,7 +487,7 @@ impl MetadataDb {
             drop(db); // Ensure file is not locked
             let _ = std::fs::remove_file(db_path);
 
-            let db = Arc::new(Database::create(db_path).map_err(|e| {
+            let db = Arc::new(create_database(db_path).map_err(|e| {
                 FlashError::database("database_operation", "files_table", e.to_string())
             })?);
 
@@ -184,6 +541,7 @@ impl MetadataDb {
         modified: u64,
         size: u64,
         content_hash: [u8; 32],
+        attributes: &FileAttributes,
     ) -> Result<()> {
         let txn = self.db.begin_write().map_err(|e| {
             FlashError::database("database_operation", "files_table", e.to_string())
@@ -199,6 +557,7 @@ impl MetadataDb {
                 .modified(modified)
                 .size(size)
                 .content_hash(content_hash)
+                .attributes(attributes.clone())
                 .indexed_at(
                     SystemTime::now()
                         .duration_since(SystemTime::UNIX_EPOCH)
@@ -244,6 +603,14 @@ impl MetadataDb {
             let removed = table.remove(path_str).map_err(|e| {
                 FlashError::database("database_operation", "files_table", e.to_string())
             })?;
+
+            let mut errors = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            errors.remove(path_str).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+
             removed.is_some()
         };
 
@@ -254,6 +621,139 @@ impl MetadataDb {
         Ok(existed)
     }
 
+    /// Removes every file whose path `matches`, with its parse error, in one
+    /// transaction. Returns the removed file paths.
+    pub fn remove_matching(&self, matches: impl Fn(&str) -> bool) -> Result<Vec<String>> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let removed = {
+            let mut table = txn.open_table(FILES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })?;
+            let removed: Vec<String> = table
+                .iter()
+                .map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?
+                .filter_map(std::result::Result::ok)
+                .map(|(k, _)| k.value().to_string())
+                .filter(|key| matches(key))
+                .collect();
+            for key in &removed {
+                table.remove(key.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?;
+            }
+
+            let mut errors = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            let failed: Vec<String> = errors
+                .iter()
+                .map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?
+                .filter_map(std::result::Result::ok)
+                .map(|(k, _)| k.value().to_string())
+                .filter(|key| matches(key))
+                .collect();
+            for key in &failed {
+                errors.remove(key.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?;
+            }
+
+            removed
+        };
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        Ok(removed)
+    }
+
+    /// Removes `path` and, if it was a directory, every file and directory
+    /// recorded beneath it, in one transaction. Returns the removed file paths.
+    pub fn remove_tree(&self, path: &Path) -> Result<Vec<String>> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let prefix = path.to_string_lossy();
+        let removed = {
+            let mut table = txn.open_table(FILES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })?;
+            let mut removed = Vec::new();
+            for entry in table.range::<&str>(prefix.as_ref()..).map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })? {
+                let (k, _) = entry.map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?;
+                let key = k.value();
+                if !key.starts_with(prefix.as_ref()) {
+                    break;
+                }
+                if Path::new(key).starts_with(path) {
+                    removed.push(key.to_string());
+                }
+            }
+            for key in &removed {
+                table.remove(key.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?;
+            }
+
+            let mut errors = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            let failed: Vec<String> = errors
+                .range::<&str>(prefix.as_ref()..)
+                .map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?
+                .filter_map(std::result::Result::ok)
+                .map(|(k, _)| k.value().to_string())
+                .take_while(|key| key.starts_with(prefix.as_ref()))
+                .filter(|key| Path::new(key).starts_with(path))
+                .collect();
+            for key in &failed {
+                errors.remove(key.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?;
+            }
+
+            let mut dirs = txn
+                .open_table(DIRS_TABLE)
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+            let subdirs: Vec<String> = dirs
+                .range::<&str>(prefix.as_ref()..)
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?
+                .filter_map(std::result::Result::ok)
+                .map(|(k, _)| k.value().to_string())
+                .take_while(|key| key.starts_with(prefix.as_ref()))
+                .filter(|key| Path::new(key).starts_with(path))
+                .collect();
+            for key in &subdirs {
+                dirs.remove(key.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "dirs", e.to_string())
+                })?;
+            }
+
+            removed
+        };
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        Ok(removed)
+    }
+
     /// Clear all metadata (nuke the table)
     pub fn clear(&self) -> Result<()> {
         let txn = self.db.begin_write().map_err(|e| {
@@ -271,6 +771,26 @@ impl MetadataDb {
             let _ = txn.open_table(FILES_TABLE).map_err(|e| {
                 FlashError::database("database_operation", "files_table", e.to_string())
             })?;
+
+            txn.delete_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            let _ = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+
+            txn.delete_table(DIRS_TABLE)
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+            let _ = txn
+                .open_table(DIRS_TABLE)
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+
+            txn.delete_table(PENDING_BATCHES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            let _ = txn.open_table(PENDING_BATCHES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
         }
 
         txn.commit().map_err(|e| {
@@ -280,6 +800,21 @@ impl MetadataDb {
         Ok(())
     }
 
+    /// Copies every table into a new database at `dest`. The copy is read
+    /// in one transaction, so it is a consistent snapshot even while the
+    /// app keeps writing.
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        let copy = || -> std::result::Result<(), redb::Error> {
+            let source = self.db.begin_read()?;
+            let backup = create_database(dest)?;
+            let target = backup.begin_write()?;
+            copy_tables(&source, &target)?;
+            target.commit()?;
+            Ok(())
+        };
+        copy().map_err(|e| FlashError::database("backup", "metadata.redb", e.to_string()))
+    }
+
     /// Get all file paths currently stored in the metadata database
     pub fn get_all_file_paths(&self) -> Result<Vec<String>> {
         let txn = self.db.begin_read().map_err(|e| {
@@ -304,8 +839,8 @@ impl MetadataDb {
         Ok(paths)
     }
 
-    /// Get metadata for a specific file
-    pub fn get_metadata(&self, path: &Path) -> Result<Option<FileMetadata>> {
+    /// Every file record, for cross-checking against the search index.
+    pub fn get_all_metadata(&self) -> Result<Vec<FileMetadata>> {
         let txn = self.db.begin_read().map_err(|e| {
             FlashError::database("database_operation", "files_table", e.to_string())
         })?;
@@ -314,98 +849,683 @@ impl MetadataDb {
             FlashError::database("database_operation", "files_table", e.to_string())
         })?;
 
-        let result = table
-            .get(path.to_str().unwrap_or(""))
+        let mut records = Vec::new();
+        let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+        for entry in table
+            .iter()
             .map_err(|e| FlashError::database("database_operation", "files_table", e.to_string()))?
-            .and_then(|metadata| {
-                let bytes = metadata.value();
-                rkyv::access::<rkyv::Archived<FileMetadata>, rkyv::rancor::Error>(bytes)
-                    .ok()
-                    .map(|meta| FileMetadata {
-                        path: meta.path.as_str().to_string(),
-                        modified: meta.modified.to_native(),
-                        size: meta.size.to_native(),
-                        content_hash: meta.content_hash,
-                        indexed_at: meta.indexed_at.to_native(),
-                    })
-            });
+        {
+            let (_, v) = entry.map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })?;
+            aligned_bytes.clear();
+            aligned_bytes.extend_from_slice(v.value());
+            if let Ok(record) =
+                rkyv::from_bytes::<FileMetadata, rkyv::rancor::Error>(&aligned_bytes)
+            {
+                records.push(record);
+            }
+        }
 
-        Ok(result)
+        Ok(records)
     }
 
-    /// Batch update metadata for multiple files (much more efficient)
-    /// Updates all files in a single transaction to minimize I/O overhead
-    pub fn batch_update_metadata(
-        &self,
-        entries: &[(String, u64, u64, [u8; 32])], // (path, modified, size, hash)
-    ) -> Result<usize> {
-        if entries.is_empty() {
-            return Ok(0);
-        }
-
-        let txn = self.db.begin_write().map_err(|e| {
+    /// Tracked files whose parent directory is exactly `dir`.
+    pub fn get_files_in_dir(&self, dir: &Path) -> Result<Vec<String>> {
+        let txn = self.db.begin_read().map_err(|e| {
             FlashError::database("database_operation", "files_table", e.to_string())
         })?;
 
-        let indexed_at = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        let table = txn.open_table(FILES_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
 
+        let prefix = dir.to_string_lossy();
+        let mut paths = Vec::new();
+        for entry in table
+            .range::<&str>(prefix.as_ref()..)
+            .map_err(|e| FlashError::database("database_operation", "files_table", e.to_string()))?
         {
-            let mut table = txn.open_table(FILES_TABLE).map_err(|e| {
+            let (k, _) = entry.map_err(|e| {
                 FlashError::database("database_operation", "files_table", e.to_string())
             })?;
+            let path = k.value();
+            if !path.starts_with(prefix.as_ref()) {
+                break;
+            }
+            if Path::new(path).parent() == Some(dir) {
+                paths.push(path.to_string());
+            }
+        }
 
-            for (path, modified, size, content_hash) in entries {
-                let metadata = FileMetadata::builder()
-                    .path(path.clone())
-                    .modified(*modified)
-                    .size(*size)
-                    .content_hash(*content_hash)
-                    .indexed_at(indexed_at)
-                    .build();
+        Ok(paths)
+    }
 
-                let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&metadata).map_err(|e| {
-                    FlashError::database(
-                        "database_operation",
-                        "files_table",
-                        format!("Serialization error: {e}"),
-                    )
-                })?;
+    /// Directory mtimes recorded under `root` (inclusive) by earlier incremental scans.
+    pub fn get_dir_mtimes(&self, root: &Path) -> Result<HashMap<String, u64>> {
+        let txn = self
+            .db
+            .begin_read()
+            .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
 
-                table.insert(path.as_str(), bytes.as_slice()).map_err(|e| {
-                    FlashError::database("database_operation", "files_table", e.to_string())
+        let table = txn
+            .open_table(DIRS_TABLE)
+            .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+
+        let prefix = root.to_string_lossy();
+        let mut dirs = HashMap::new();
+        for entry in table
+            .range::<&str>(prefix.as_ref()..)
+            .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?
+        {
+            let (k, v) = entry
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+            let path = k.value();
+            if !path.starts_with(prefix.as_ref()) {
+                break;
+            }
+            if Path::new(path).starts_with(root) {
+                dirs.insert(path.to_string(), v.value());
+            }
+        }
+
+        Ok(dirs)
+    }
+
+    /// Records directory mtimes and forgets `removed` directories in one transaction.
+    pub fn update_dir_mtimes(&self, dirs: &[(String, u64)], removed: &[String]) -> Result<()> {
+        let txn = self
+            .db
+            .begin_write()
+            .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+
+        {
+            let mut table = txn
+                .open_table(DIRS_TABLE)
+                .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
+
+            for (dir, mtime) in dirs {
+                table.insert(dir.as_str(), *mtime).map_err(|e| {
+                    FlashError::database("database_operation", "dirs", e.to_string())
+                })?;
+            }
+            for dir in removed {
+                table.remove(dir.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "dirs", e.to_string())
                 })?;
             }
         }
 
-        txn.commit().map_err(|e| {
-            FlashError::database("database_operation", "files_table", e.to_string())
-        })?;
+        txn.commit()
+            .map_err(|e| FlashError::database("database_operation", "dirs", e.to_string()))?;
 
-        Ok(entries.len())
+        Ok(())
     }
 
-    /// Batch check which files need reindexing
-    /// Returns a vector of booleans indicating if each file needs reindexing
-    pub fn batch_needs_reindex(
-        &self,
-        entries: &[(String, u64, u64)], // (path, modified, size)
-    ) -> Result<Vec<bool>> {
-        if entries.is_empty() {
-            return Ok(vec![]);
+    /// Records that a scan of `root` started. An existing checkpoint is kept, so
+    /// a resumed scan carries on counting from where the interrupted one stopped.
+    /// The checkpoint stays until [`Self::mark_scan_finished`].
+    pub fn mark_scan_started(&self, root: &Path) -> Result<()> {
+        let root_str = root.to_string_lossy();
+        if self.get_scan_checkpoint(&root_str)?.is_some() {
+            return Ok(());
         }
 
-        let txn = self.db.begin_read().map_err(|e| {
-            FlashError::database("database_operation", "files_table", e.to_string())
-        })?;
-
-        let table = txn.open_table(FILES_TABLE).map_err(|e| {
-            FlashError::database("database_operation", "files_table", e.to_string())
-        })?;
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.put_scan_checkpoint(&ScanCheckpoint {
+            root: root_str.to_string(),
+            started_at: now,
+            files_committed: 0,
+            updated_at: now,
+        })
+    }
 
-        let results: Vec<bool> = entries
+    /// Adds a committed batch of `files` to the checkpoint of `root`.
+    pub fn record_scan_progress(&self, root: &Path, files: u64) -> Result<()> {
+        let Some(mut checkpoint) = self.get_scan_checkpoint(&root.to_string_lossy())? else {
+            return Ok(());
+        };
+        checkpoint.files_committed += files;
+        checkpoint.updated_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.put_scan_checkpoint(&checkpoint)
+    }
+
+    pub fn mark_scan_finished(&self, root: &Path) -> Result<()> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        {
+            let mut table = txn.open_table(SCAN_CHECKPOINTS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+            })?;
+            table.remove(root.to_string_lossy().as_ref()).map_err(|e| {
+                FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+            })?;
+        }
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })
+    }
+
+    /// Checkpoints of scans that were cancelled or cut short by an exit.
+    pub fn get_pending_scans(&self) -> Result<Vec<ScanCheckpoint>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        let table = txn.open_table(SCAN_CHECKPOINTS_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+
+        let mut checkpoints = Vec::new();
+        for entry in table.iter().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })? {
+            let (_, v) = entry.map_err(|e| {
+                FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+            })?;
+            if let Some(checkpoint) = decode_checkpoint(v.value()) {
+                checkpoints.push(checkpoint);
+            }
+        }
+
+        Ok(checkpoints)
+    }
+
+    fn get_scan_checkpoint(&self, root: &str) -> Result<Option<ScanCheckpoint>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        let table = txn.open_table(SCAN_CHECKPOINTS_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+
+        let value = table.get(root).map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        Ok(value.and_then(|v| decode_checkpoint(v.value())))
+    }
+
+    fn put_scan_checkpoint(&self, checkpoint: &ScanCheckpoint) -> Result<()> {
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(checkpoint).map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })?;
+        {
+            let mut table = txn.open_table(SCAN_CHECKPOINTS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+            })?;
+            table
+                .insert(checkpoint.root.as_str(), bytes.as_slice())
+                .map_err(|e| {
+                    FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+                })?;
+        }
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "scan_checkpoints", e.to_string())
+        })
+    }
+
+    /// Unix timestamp at which the last scheduled refresh started, if any ran.
+    pub fn get_last_scheduled_run(&self) -> Result<Option<u64>> {
+        let txn = self
+            .db
+            .begin_read()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        let table = txn
+            .open_table(SCHEDULE_TABLE)
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+
+        let value = table
+            .get(LAST_RUN_KEY)
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        Ok(value.map(|v| v.value()))
+    }
+
+    pub fn set_last_scheduled_run(&self, started_at: u64) -> Result<()> {
+        let txn = self
+            .db
+            .begin_write()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        {
+            let mut table = txn.open_table(SCHEDULE_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "schedule", e.to_string())
+            })?;
+            table.insert(LAST_RUN_KEY, started_at).map_err(|e| {
+                FlashError::database("database_operation", "schedule", e.to_string())
+            })?;
+        }
+        txn.commit()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))
+    }
+
+    /// Unix timestamp at which the root `root` last started a refresh on its
+    /// own schedule, if it has.
+    pub fn get_root_last_run(&self, root: &str) -> Result<Option<u64>> {
+        let txn = self
+            .db
+            .begin_read()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        let table = txn
+            .open_table(SCHEDULE_TABLE)
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+
+        let value = table
+            .get(format!("{ROOT_RUN_PREFIX}{root}").as_str())
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        Ok(value.map(|v| v.value()))
+    }
+
+    pub fn set_root_last_run(&self, root: &str, started_at: u64) -> Result<()> {
+        let txn = self
+            .db
+            .begin_write()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))?;
+        {
+            let mut table = txn.open_table(SCHEDULE_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "schedule", e.to_string())
+            })?;
+            table
+                .insert(format!("{ROOT_RUN_PREFIX}{root}").as_str(), started_at)
+                .map_err(|e| {
+                    FlashError::database("database_operation", "schedule", e.to_string())
+                })?;
+        }
+        txn.commit()
+            .map_err(|e| FlashError::database("database_operation", "schedule", e.to_string()))
+    }
+
+    /// The search history, in no particular order.
+    pub fn get_search_history(&self) -> Result<Vec<SearchHistoryItem>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "search_history", e.to_string())
+        })?;
+        let table = txn.open_table(SEARCH_HISTORY_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "search_history", e.to_string())
+        })?;
+        let history = table
+            .iter()
+            .map_err(|e| {
+                FlashError::database("database_operation", "search_history", e.to_string())
+            })?
+            .filter_map(std::result::Result::ok)
+            .map(|(k, v)| {
+                let (frequency, last_used, pinned) = v.value();
+                SearchHistoryItem {
+                    query: k.value().to_string(),
+                    frequency,
+                    last_used,
+                    pinned,
+                }
+            })
+            .collect();
+        Ok(history)
+    }
+
+    /// Replaces the search history with `history`.
+    pub fn set_search_history(&self, history: &[SearchHistoryItem]) -> Result<()> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "search_history", e.to_string())
+        })?;
+        {
+            let mut table = txn.open_table(SEARCH_HISTORY_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "search_history", e.to_string())
+            })?;
+            table.retain(|_, _| false).map_err(|e| {
+                FlashError::database("database_operation", "search_history", e.to_string())
+            })?;
+            for item in history {
+                table
+                    .insert(
+                        item.query.as_str(),
+                        (item.frequency, item.last_used, item.pinned),
+                    )
+                    .map_err(|e| {
+                        FlashError::database("database_operation", "search_history", e.to_string())
+                    })?;
+            }
+        }
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "search_history", e.to_string())
+        })
+    }
+
+    fn read_list(&self, definition: TableDefinition<&str, u64>, name: &str) -> Result<Vec<String>> {
+        let txn = self
+            .db
+            .begin_read()
+            .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+        let table = txn
+            .open_table(definition)
+            .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+        let mut entries: Vec<(u64, String)> = table
+            .iter()
+            .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?
+            .filter_map(std::result::Result::ok)
+            .map(|(k, v)| (v.value(), k.value().to_string()))
+            .collect();
+        entries.sort();
+        Ok(entries.into_iter().map(|(_, item)| item).collect())
+    }
+
+    fn write_list(
+        &self,
+        definition: TableDefinition<&str, u64>,
+        name: &str,
+        items: &[String],
+    ) -> Result<()> {
+        let txn = self
+            .db
+            .begin_write()
+            .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+        {
+            let mut table = txn
+                .open_table(definition)
+                .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+            table
+                .retain(|_, _| false)
+                .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+            for (position, item) in (0u64..).zip(items) {
+                table
+                    .insert(item.as_str(), position)
+                    .map_err(|e| FlashError::database("database_operation", name, e.to_string()))?;
+            }
+        }
+        txn.commit()
+            .map_err(|e| FlashError::database("database_operation", name, e.to_string()))
+    }
+
+    /// Recent queries, most recent first.
+    pub fn get_recent_searches(&self) -> Result<Vec<String>> {
+        self.read_list(RECENT_SEARCHES_TABLE, "recent_searches")
+    }
+
+    /// Replaces the recent queries, most recent first.
+    pub fn set_recent_searches(&self, queries: &[String]) -> Result<()> {
+        self.write_list(RECENT_SEARCHES_TABLE, "recent_searches", queries)
+    }
+
+    /// Pinned file paths, in the order they were pinned.
+    pub fn get_pinned_files(&self) -> Result<Vec<String>> {
+        self.read_list(PINNED_FILES_TABLE, "pinned_files")
+    }
+
+    /// Replaces the pinned file paths.
+    pub fn set_pinned_files(&self, paths: &[String]) -> Result<()> {
+        self.write_list(PINNED_FILES_TABLE, "pinned_files", paths)
+    }
+
+    /// Records a batch before any of its writes are applied and returns its id.
+    /// Pass it to [`Self::finish_batches`] once the search index, the metadata and the
+    /// filename index have all been updated.
+    pub fn begin_batch(&self, indexed: &[String], removed: &[String]) -> Result<u64> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+        let id = {
+            let mut table = txn.open_table(PENDING_BATCHES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            let id = table
+                .last()
+                .map_err(|e| {
+                    FlashError::database("database_operation", "pending_batches", e.to_string())
+                })?
+                .map_or(1, |(k, _)| k.value() + 1);
+            let batch = PendingBatch {
+                id,
+                indexed: indexed.to_vec(),
+                removed: removed.to_vec(),
+            };
+            let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&batch).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            table.insert(id, bytes.as_slice()).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            id
+        };
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+        Ok(id)
+    }
+
+    /// Drops the journal entries of batches that were fully applied.
+    pub fn finish_batches(&self, ids: &[u64]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+        {
+            let mut table = txn.open_table(PENDING_BATCHES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            for id in ids {
+                table.remove(id).map_err(|e| {
+                    FlashError::database("database_operation", "pending_batches", e.to_string())
+                })?;
+            }
+        }
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })
+    }
+
+    /// Batches begun but never finished, oldest first.
+    pub fn get_pending_batches(&self) -> Result<Vec<PendingBatch>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+        let table = txn.open_table(PENDING_BATCHES_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+
+        let mut batches = Vec::new();
+        for entry in table.iter().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })? {
+            let (_, v) = entry.map_err(|e| {
+                FlashError::database("database_operation", "pending_batches", e.to_string())
+            })?;
+            if let Some(batch) = decode_pending_batch(v.value()) {
+                batches.push(batch);
+            }
+        }
+        Ok(batches)
+    }
+
+    /// Rolls back the metadata of incomplete batches: their files lose their
+    /// records, so the next look at them re-indexes instead of trusting a
+    /// record whose index write may never have landed. The journal entries
+    /// are kept until [`Self::finish_batches`] is called once the replay has
+    /// landed, so a replay cut short is rolled back and run again.
+    pub fn rollback_batches(&self, batches: &[PendingBatch]) -> Result<()> {
+        if batches.is_empty() {
+            return Ok(());
+        }
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })?;
+        {
+            let mut files = txn.open_table(FILES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })?;
+            for path in batches
+                .iter()
+                .flat_map(|batch| batch.indexed.iter().chain(&batch.removed))
+            {
+                files.remove(path.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?;
+            }
+        }
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "pending_batches", e.to_string())
+        })
+    }
+
+    /// Where reading the change journal of `volume` last stopped.
+    pub fn get_usn_position(&self, volume: &str) -> Result<Option<UsnPosition>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "usn_journal", e.to_string())
+        })?;
+        let table = txn.open_table(USN_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "usn_journal", e.to_string())
+        })?;
+
+        let value = table.get(volume).map_err(|e| {
+            FlashError::database("database_operation", "usn_journal", e.to_string())
+        })?;
+        Ok(value.map(|v| {
+            let (journal_id, next_usn) = v.value();
+            UsnPosition {
+                journal_id,
+                next_usn,
+            }
+        }))
+    }
+
+    pub fn set_usn_position(&self, volume: &str, position: UsnPosition) -> Result<()> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "usn_journal", e.to_string())
+        })?;
+        {
+            let mut table = txn.open_table(USN_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "usn_journal", e.to_string())
+            })?;
+            table
+                .insert(volume, (position.journal_id, position.next_usn))
+                .map_err(|e| {
+                    FlashError::database("database_operation", "usn_journal", e.to_string())
+                })?;
+        }
+        txn.commit()
+            .map_err(|e| FlashError::database("database_operation", "usn_journal", e.to_string()))
+    }
+
+    /// Get metadata for a specific file
+    pub fn get_metadata(&self, path: &Path) -> Result<Option<FileMetadata>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let table = txn.open_table(FILES_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let result = table
+            .get(path.to_str().unwrap_or(""))
+            .map_err(|e| FlashError::database("database_operation", "files_table", e.to_string()))?
+            .and_then(|metadata| {
+                let bytes = metadata.value();
+                rkyv::access::<rkyv::Archived<FileMetadata>, rkyv::rancor::Error>(bytes)
+                    .ok()
+                    .map(|meta| FileMetadata {
+                        path: meta.path.as_str().to_string(),
+                        modified: meta.modified.to_native(),
+                        size: meta.size.to_native(),
+                        content_hash: meta.content_hash,
+                        indexed_at: meta.indexed_at.to_native(),
+                    })
+            });
+
+        Ok(result)
+    }
+
+    /// Batch update metadata for multiple files (much more efficient)
+    /// Updates all files in a single transaction to minimize I/O overhead
+    pub fn batch_update_metadata(
+        &self,
+        entries: &[(String, u64, u64, [u8; 32], FileAttributes)], // (path, modified, size, hash, attributes)
+    ) -> Result<usize> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let indexed_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        {
+            let mut table = txn.open_table(FILES_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "files_table", e.to_string())
+            })?;
+
+            for (path, modified, size, content_hash, attributes) in entries {
+                let metadata = FileMetadata::builder()
+                    .path(path.clone())
+                    .modified(*modified)
+                    .size(*size)
+                    .content_hash(*content_hash)
+                    .attributes(attributes.clone())
+                    .indexed_at(indexed_at)
+                    .build();
+
+                let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&metadata).map_err(|e| {
+                    FlashError::database(
+                        "database_operation",
+                        "files_table",
+                        format!("Serialization error: {e}"),
+                    )
+                })?;
+
+                table.insert(path.as_str(), bytes.as_slice()).map_err(|e| {
+                    FlashError::database("database_operation", "files_table", e.to_string())
+                })?;
+            }
+
+            // A successful index supersedes any earlier parse failure
+            let mut errors = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            for (path, ..) in entries {
+                errors.remove(path.as_str()).map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?;
+            }
+        }
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        Ok(entries.len())
+    }
+
+    /// Batch check which files need reindexing
+    /// Returns a vector of booleans indicating if each file needs reindexing
+    pub fn batch_needs_reindex(
+        &self,
+        entries: &[(String, u64, u64)], // (path, modified, size)
+    ) -> Result<Vec<bool>> {
+        if entries.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let table = txn.open_table(FILES_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let results: Vec<bool> = entries
             .iter()
             .map(|(path, modified, size)| {
                 table.get(path.as_str()).ok().is_none_or(|opt_metadata| {
@@ -439,10 +1559,27 @@ impl MetadataDb {
             FlashError::database("database_operation", "files_table", e.to_string())
         })?;
 
+        let errors = txn.open_table(PARSE_ERRORS_TABLE).ok();
+
         let results: Vec<bool> = entries
             .iter()
             .map(|(path, modified, size)| {
                 let path_str = path.to_str().unwrap_or("");
+
+                // Files that already failed at this exact version are not retried
+                let known_failure = errors
+                    .as_ref()
+                    .and_then(|t| t.get(path_str).ok().flatten())
+                    .is_some_and(|record| {
+                        rkyv::access::<rkyv::Archived<ParseErrorRecord>, rkyv::rancor::Error>(
+                            record.value(),
+                        )
+                        .is_ok_and(|r| r.modified == *modified && r.size == *size)
+                    });
+                if known_failure {
+                    return false;
+                }
+
                 table.get(path_str).ok().is_none_or(|opt_metadata| {
                     opt_metadata.is_none_or(|metadata| {
                         let bytes = metadata.value();
@@ -457,6 +1594,236 @@ impl MetadataDb {
         Ok(results)
     }
 
+    /// Modification time recorded for each of `paths` when it was last
+    /// indexed, in one read transaction. `None` for paths with no record.
+    pub fn batch_indexed_mtimes(&self, paths: &[&str]) -> Result<Vec<Option<u64>>> {
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        let table = txn.open_table(FILES_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "files_table", e.to_string())
+        })?;
+
+        Ok(paths
+            .iter()
+            .map(|path| {
+                table.get(*path).ok().flatten().and_then(|metadata| {
+                    rkyv::access::<rkyv::Archived<FileMetadata>, rkyv::rancor::Error>(
+                        metadata.value(),
+                    )
+                    .ok()
+                    .map(|meta| meta.modified.to_native())
+                })
+            })
+            .collect())
+    }
+
+    /// Remember that a file failed to parse so later scans skip it until it
+    /// changes, and note the failure in the activity log
+    pub fn record_parse_error(
+        &self,
+        path: &Path,
+        kind: ParseErrorKind,
+        message: &str,
+        modified: u64,
+        size: u64,
+    ) -> Result<()> {
+        crate::system::activity::record(|| crate::system::activity::Activity::Failed {
+            path: path.to_string_lossy().to_string(),
+            error: message.to_string(),
+        });
+        let record = ParseErrorRecord {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            message: message.to_string(),
+            modified,
+            size,
+            occurred_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&record).map_err(|e| {
+            FlashError::database(
+                "database_operation",
+                "parse_errors",
+                format!("Serialization error: {e}"),
+            )
+        })?;
+
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        {
+            let mut table = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            table
+                .insert(record.path.as_str(), bytes.as_slice())
+                .map_err(|e| {
+                    FlashError::database("database_operation", "parse_errors", e.to_string())
+                })?;
+        }
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Get every recorded parse failure, most recent first
+    pub fn get_parse_errors(&self) -> Result<Vec<ParseErrorRecord>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        let table = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        let mut records = Vec::new();
+        for entry in table.iter().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })? {
+            let (_, v) = entry.map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+            aligned_bytes.extend_from_slice(v.value());
+            if let Ok(record) =
+                rkyv::from_bytes::<ParseErrorRecord, rkyv::rancor::Error>(&aligned_bytes)
+            {
+                records.push(record);
+            }
+        }
+
+        records.sort_by_key(|r| Reverse(r.occurred_at));
+        Ok(records)
+    }
+
+    /// Forget all recorded parse failures so the next scan retries those files
+    pub fn clear_parse_errors(&self) -> Result<()> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        {
+            txn.delete_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+            let _ = txn.open_table(PARSE_ERRORS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "parse_errors", e.to_string())
+            })?;
+        }
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "parse_errors", e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Store the annotation for a file, or delete it when `annotation` is empty
+    pub fn set_annotation(&self, path: &Path, annotation: &FileAnnotation) -> Result<()> {
+        let txn = self.db.begin_write().map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        {
+            let mut table = txn.open_table(ANNOTATIONS_TABLE).map_err(|e| {
+                FlashError::database("database_operation", "annotations", e.to_string())
+            })?;
+            let path_str = path.to_str().unwrap_or("");
+
+            if annotation.is_empty() {
+                table.remove(path_str).map_err(|e| {
+                    FlashError::database("database_operation", "annotations", e.to_string())
+                })?;
+            } else {
+                let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(annotation).map_err(|e| {
+                    FlashError::database(
+                        "database_operation",
+                        "annotations",
+                        format!("Serialization error: {e}"),
+                    )
+                })?;
+                table.insert(path_str, bytes.as_slice()).map_err(|e| {
+                    FlashError::database("database_operation", "annotations", e.to_string())
+                })?;
+            }
+        }
+
+        txn.commit().map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Get the annotation attached to a file, if any
+    pub fn get_annotation(&self, path: &Path) -> Result<Option<FileAnnotation>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        let table = txn.open_table(ANNOTATIONS_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        let Some(value) = table.get(path.to_str().unwrap_or("")).map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+        aligned_bytes.extend_from_slice(value.value());
+        Ok(rkyv::from_bytes::<FileAnnotation, rkyv::rancor::Error>(&aligned_bytes).ok())
+    }
+
+    /// Paths whose annotation carries a `tag: <tag>` attribute, compared
+    /// without regard to case
+    pub fn files_with_tag(&self, tag: &str) -> Result<Vec<String>> {
+        let txn = self.db.begin_read().map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        let table = txn.open_table(ANNOTATIONS_TABLE).map_err(|e| {
+            FlashError::database("database_operation", "annotations", e.to_string())
+        })?;
+
+        let mut paths = Vec::new();
+        for entry in table
+            .iter()
+            .map_err(|e| FlashError::database("database_operation", "annotations", e.to_string()))?
+        {
+            let (k, v) = entry.map_err(|e| {
+                FlashError::database("database_operation", "annotations", e.to_string())
+            })?;
+            let mut aligned_bytes = rkyv::util::AlignedVec::<16>::new();
+            aligned_bytes.extend_from_slice(v.value());
+            let tagged = rkyv::from_bytes::<FileAnnotation, rkyv::rancor::Error>(&aligned_bytes)
+                .is_ok_and(|annotation| {
+                    annotation.attributes.iter().any(|(key, value)| {
+                        key.eq_ignore_ascii_case("tag") && value.eq_ignore_ascii_case(tag)
+                    })
+                });
+            if tagged {
+                paths.push(k.value().to_string());
+            }
+        }
+        Ok(paths)
+    }
+
     /// Get recently modified files sorted by modification time
     /// Uses a bounded min-heap to avoid loading all files into memory.
     pub fn get_recent_files(&self, limit: usize) -> Result<Vec<RecentFileEntry>> {
@@ -518,3 +1885,292 @@ impl MetadataDb {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_skips_until_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let path = std::path::PathBuf::from("/data/broken.pdf");
+
+        db.record_parse_error(&path, ParseErrorKind::Timeout, "timed out", 10, 100)
+            .unwrap();
+
+        let needs = db
+            .batch_needs_reindex_paths(&[(path.clone(), 10, 100), (path.clone(), 11, 100)])
+            .unwrap();
+        assert_eq!(needs, vec![false, true]);
+
+        let errors = db.get_parse_errors().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::Timeout);
+
+        db.batch_update_metadata(&[(
+            path.to_string_lossy().to_string(),
+            11,
+            100,
+            [0; 32],
+            FileAttributes::default(),
+        )])
+        .unwrap();
+        assert!(db.get_parse_errors().unwrap().is_empty());
+
+        db.record_parse_error(&path, ParseErrorKind::Panic, "boom", 12, 100)
+            .unwrap();
+        db.clear_parse_errors().unwrap();
+        assert!(db.get_parse_errors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_batch_indexed_mtimes() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        db.batch_update_metadata(&[(
+            "/data/a.txt".to_string(),
+            42,
+            7,
+            [0; 32],
+            FileAttributes::default(),
+        )])
+        .unwrap();
+
+        let mtimes = db
+            .batch_indexed_mtimes(&["/data/a.txt", "/data/missing.txt"])
+            .unwrap();
+        assert_eq!(mtimes, vec![Some(42), None]);
+    }
+
+    #[test]
+    fn test_annotation_round_trip_survives_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let path = std::path::PathBuf::from("/data/invoice.pdf");
+
+        let annotation = FileAnnotation {
+            note: "sent to accountant".to_string(),
+            attributes: FileAnnotation::parse_attributes("client: Acme, year:2025, bogus"),
+            updated_at: 1,
+        };
+        assert_eq!(annotation.attributes.len(), 2);
+        db.set_annotation(&path, &annotation).unwrap();
+
+        db.clear().unwrap();
+        assert_eq!(db.get_annotation(&path).unwrap(), Some(annotation));
+
+        db.set_annotation(&path, &FileAnnotation::default())
+            .unwrap();
+        assert!(db.get_annotation(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_files_with_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let tagged = |attributes: &str| FileAnnotation {
+            note: String::new(),
+            attributes: FileAnnotation::parse_attributes(attributes),
+            updated_at: 1,
+        };
+        db.set_annotation(Path::new("/a.txt"), &tagged("tag: ProjectX, tag: draft"))
+            .unwrap();
+        db.set_annotation(Path::new("/b.txt"), &tagged("client: projectx"))
+            .unwrap();
+
+        assert_eq!(db.files_with_tag("projectx").unwrap(), vec!["/a.txt"]);
+        assert!(db.files_with_tag("final").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove_tree_drops_everything_under_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let file = |path: &str| (path.to_string(), 1, 1, [0; 32], FileAttributes::default());
+        db.batch_update_metadata(&[
+            file("/data/a.txt"),
+            file("/data/old/b.txt"),
+            file("/data/old/deep/c.txt"),
+            file("/data/older.txt"),
+        ])
+        .unwrap();
+        db.record_parse_error(
+            Path::new("/data/old/bad.pdf"),
+            ParseErrorKind::Panic,
+            "boom",
+            1,
+            1,
+        )
+        .unwrap();
+        db.update_dir_mtimes(&[("/data/old/deep".to_string(), 1)], &[])
+            .unwrap();
+
+        let mut removed = db.remove_tree(Path::new("/data/old")).unwrap();
+        removed.sort();
+        assert_eq!(removed, ["/data/old/b.txt", "/data/old/deep/c.txt"]);
+
+        let mut left = db.get_all_file_paths().unwrap();
+        left.sort();
+        assert_eq!(left, ["/data/a.txt", "/data/older.txt"]);
+        assert!(db.get_parse_errors().unwrap().is_empty());
+        assert!(db.get_dir_mtimes(Path::new("/data")).unwrap().is_empty());
+
+        // A single file is a tree of one
+        assert_eq!(
+            db.remove_tree(Path::new("/data/a.txt")).unwrap(),
+            ["/data/a.txt"]
+        );
+    }
+
+    #[test]
+    fn test_remove_matching_drops_matched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let file = |path: &str| (path.to_string(), 1, 1, [0; 32], FileAttributes::default());
+        db.batch_update_metadata(&[
+            file("/data/a.log"),
+            file("/data/b.txt"),
+            file("/data/c/d.log"),
+        ])
+        .unwrap();
+        db.record_parse_error(
+            Path::new("/data/e.log"),
+            ParseErrorKind::Panic,
+            "boom",
+            1,
+            1,
+        )
+        .unwrap();
+
+        let mut removed = db.remove_matching(|path| path.ends_with(".log")).unwrap();
+        removed.sort();
+        assert_eq!(removed, ["/data/a.log", "/data/c/d.log"]);
+        assert_eq!(db.get_all_file_paths().unwrap(), ["/data/b.txt"]);
+        assert!(db.get_parse_errors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_user_lists_keep_their_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let list = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        db.set_pinned_files(&list(&["/z.txt", "/a.txt", "/m.txt"]))
+            .unwrap();
+        assert_eq!(
+            db.get_pinned_files().unwrap(),
+            ["/z.txt", "/a.txt", "/m.txt"]
+        );
+        db.set_pinned_files(&list(&["/a.txt"])).unwrap();
+        assert_eq!(db.get_pinned_files().unwrap(), ["/a.txt"]);
+
+        db.set_recent_searches(&list(&["newest", "older"])).unwrap();
+        assert_eq!(db.get_recent_searches().unwrap(), ["newest", "older"]);
+
+        let item = SearchHistoryItem {
+            query: "budget".to_string(),
+            frequency: 3,
+            last_used: 9,
+            pinned: true,
+        };
+        db.set_search_history(std::slice::from_ref(&item)).unwrap();
+        assert_eq!(db.get_search_history().unwrap(), [item]);
+    }
+
+    #[test]
+    fn test_unfinished_batches_roll_back_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let a = "/data/a.txt".to_string();
+        let b = "/data/b.txt".to_string();
+
+        let done = db.begin_batch(std::slice::from_ref(&a), &[]).unwrap();
+        db.batch_update_metadata(&[(a.clone(), 10, 100, [0; 32], FileAttributes::default())])
+            .unwrap();
+        db.finish_batches(&[done]).unwrap();
+
+        // Crash after the metadata write, before the batch was finished
+        let torn = db.begin_batch(std::slice::from_ref(&b), &[]).unwrap();
+        assert!(torn > done);
+        db.batch_update_metadata(&[(b.clone(), 10, 100, [0; 32], FileAttributes::default())])
+            .unwrap();
+
+        let pending = db.get_pending_batches().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].indexed, vec![b.clone()]);
+
+        db.rollback_batches(&pending).unwrap();
+        assert!(db.get_metadata(Path::new(&a)).unwrap().is_some());
+        assert!(db.get_metadata(Path::new(&b)).unwrap().is_none());
+        // The entry stays until the replay lands
+        assert_eq!(db.get_pending_batches().unwrap().len(), 1);
+        db.finish_batches(&[torn]).unwrap();
+        assert!(db.get_pending_batches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_backup_copies_every_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let path = "/data/a.txt".to_string();
+        db.batch_update_metadata(&[(path.clone(), 10, 100, [1; 32], FileAttributes::default())])
+            .unwrap();
+        db.set_last_scheduled_run(42).unwrap();
+
+        let copy_path = dir.path().join("copy.redb");
+        db.backup_to(&copy_path).unwrap();
+        drop(db);
+        let (copy, reset) = MetadataDb::open(&copy_path).unwrap();
+        assert!(!reset);
+        assert_eq!(copy.get_all_file_paths().unwrap(), vec![path]);
+        assert_eq!(copy.get_last_scheduled_run().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn test_root_runs_are_kept_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let share = "smb://bob@filer/team";
+        assert_eq!(db.get_root_last_run(share).unwrap(), None);
+
+        db.set_last_scheduled_run(42).unwrap();
+        db.set_root_last_run(share, 7).unwrap();
+        assert_eq!(db.get_root_last_run(share).unwrap(), Some(7));
+        assert_eq!(db.get_root_last_run("/data/docs").unwrap(), None);
+        assert_eq!(db.get_last_scheduled_run().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn test_pending_scan_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, _) = MetadataDb::open(&dir.path().join("meta.redb")).unwrap();
+        let root = Path::new("/data/docs");
+
+        db.mark_scan_started(root).unwrap();
+        db.record_scan_progress(root, 5000).unwrap();
+
+        // Restarting an interrupted scan keeps its progress
+        db.mark_scan_started(root).unwrap();
+        db.record_scan_progress(root, 42).unwrap();
+        let pending = db.get_pending_scans().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].root, "/data/docs");
+        assert_eq!(pending[0].files_committed, 5042);
+
+        db.mark_scan_finished(root).unwrap();
+        assert!(db.get_pending_scans().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encrypted_store_that_fails_to_open_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meta.redb");
+        let mut sealed = vault::MAGIC.to_vec();
+        sealed.extend([0x5A; 200]);
+        std::fs::write(&path, &sealed).unwrap();
+
+        assert!(MetadataDb::open(&path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), sealed);
+    }
+}
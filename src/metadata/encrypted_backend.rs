@@ -0,0 +1,518 @@
+//! Storage for the metadata store that encrypts it on disk.
+//!
+//! redb rewrites pages in place, so the file is split into sectors that are
+//! sealed one by one, each under a fresh nonce whenever it is written and
+//! bound to its position and version.
+//!
+//! Every sector has two slots. Writes go to the one not holding the copy the
+//! last sync made durable, however often the sector changes in between, and
+//! only a sync moves that copy over; a write torn by a crash so leaves the
+//! synced copy to read instead of a sector that no longer opens. The length
+//! redb sees is kept the same way, in two checksummed records in the header.
+
+use crate::system::vault::{self, Key};
+use parking_lot::Mutex;
+use redb::StorageBackend;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Plaintext bytes per sector, redb's page size
+const SECTOR_LEN: u64 = 4096;
+
+const SEALED_SECTOR_LEN: u64 = SECTOR_LEN + vault::OVERHEAD as u64;
+
+/// The version of the copy in a slot, then the sealed sector
+const SLOT_LEN: u64 = 8 + SEALED_SECTOR_LEN;
+
+/// Sequence number, length and checksum
+const LEN_RECORD_LEN: usize = 24;
+
+/// Magic, the two length records, and room to spare
+const HEADER_LEN: u64 = 64;
+
+#[derive(Debug)]
+pub struct EncryptedBackend {
+    key: Key,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    file: File,
+    /// Copies of each sector looked at so far
+    sectors: HashMap<u64, Sector>,
+    /// The length redb sees
+    len: u64,
+    /// Slot and sequence number of the newest length record
+    len_record: Option<(u64, u64)>,
+    /// Slot of the length record the last sync made durable
+    synced_len_slot: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sector {
+    /// Slot and version of the newest copy
+    slot: u64,
+    version: u64,
+    /// Slot of the copy the last sync made durable; `None` until a copy of
+    /// the sector has been synced
+    synced_slot: Option<u64>,
+}
+
+impl Sector {
+    /// A sector whose newest copy was already on disk when first looked at,
+    /// which makes it the one to fall back to.
+    const fn on_disk(slot: u64, version: u64) -> Self {
+        Self {
+            slot,
+            version,
+            synced_slot: Some(slot),
+        }
+    }
+
+    /// The slot the next copy goes to: the one not holding the synced copy,
+    /// or, before any, the one not holding the newest.
+    const fn next_slot(self) -> u64 {
+        match self.synced_slot {
+            Some(slot) => 1 - slot,
+            None => 1 - self.slot,
+        }
+    }
+}
+
+fn sector_aad(index: u64, version: u64) -> [u8; 16] {
+    let mut aad = [0u8; 16];
+    aad[..8].copy_from_slice(&index.to_le_bytes());
+    aad[8..].copy_from_slice(&version.to_le_bytes());
+    aad
+}
+
+const fn slot_offset(index: u64, slot: u64) -> u64 {
+    HEADER_LEN + (index * 2 + slot) * SLOT_LEN
+}
+
+fn len_checksum(seq: u64, len: u64) -> [u8; 8] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&seq.to_le_bytes());
+    hasher.update(&len.to_le_bytes());
+    let mut checksum = [0u8; 8];
+    checksum.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+    checksum
+}
+
+/// Sequence number and length of a length record, if it is intact.
+fn parse_len_record(record: &[u8]) -> Option<(u64, u64)> {
+    let seq = u64::from_le_bytes(record.get(..8)?.try_into().ok()?);
+    let len = u64::from_le_bytes(record.get(8..16)?.try_into().ok()?);
+    (record.get(16..24)? == len_checksum(seq, len)).then_some((seq, len))
+}
+
+/// Version and plaintext of the copy in a slot of sector `index`, if it is
+/// intact.
+fn open_slot(key: &Key, index: u64, slot: &[u8]) -> Option<(u64, Vec<u8>)> {
+    let (version, sealed) = slot.split_at_checked(8)?;
+    let version = u64::from_le_bytes(version.try_into().ok()?);
+    key.open_chunk(&sector_aad(index, version), sealed)
+        .ok()
+        .map(|plain| (version, plain))
+}
+
+/// Fills `buf` from `offset`, leaving what lies past the end of the file as
+/// it is.
+fn read_at(file: &mut File, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(())
+}
+
+fn damaged(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{what} of the metadata store is damaged"),
+    )
+}
+
+fn to_usize(n: u64) -> io::Result<usize> {
+    usize::try_from(n).map_err(|_| io::Error::other("offset out of range"))
+}
+
+impl EncryptedBackend {
+    /// Opens the store at `path` with `key`, creating it if it doesn't
+    /// exist.
+    pub fn open(path: &Path, key: &Key) -> io::Result<Self> {
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let record = Self::len_record(&mut file)?;
+        Ok(Self {
+            key: key.clone(),
+            state: Mutex::new(State {
+                file,
+                sectors: HashMap::new(),
+                len: record.map_or(0, |(_, _, len)| len),
+                len_record: record.map(|(slot, seq, _)| (slot, seq)),
+                // What is on disk when the store opens is all there is to
+                // fall back to
+                synced_len_slot: record.map(|(slot, _, _)| slot),
+            }),
+        })
+    }
+
+    /// Which of `keys` the store at `path` is encrypted with: `Some(None)`
+    /// if it isn't encrypted, and `None` if none of them opens it.
+    pub fn key_of<'a>(path: &Path, keys: &[&'a Key]) -> io::Result<Option<Option<&'a Key>>> {
+        let mut file = File::open(path)?;
+        let mut magic = [0u8; 8];
+        read_at(&mut file, 0, &mut magic)?;
+        if file.metadata()?.len() > 0 && !vault::is_sealed(&magic) {
+            return Ok(Some(None));
+        }
+        if Self::logical_len(&mut file)? == 0 {
+            return Ok(keys.first().map(|key| Some(*key)));
+        }
+        let mut slots = vec![0u8; to_usize(2 * SLOT_LEN)?];
+        read_at(&mut file, slot_offset(0, 0), &mut slots)?;
+        let slot_len = to_usize(SLOT_LEN)?;
+        Ok(keys
+            .iter()
+            .find(|key| {
+                slots
+                    .chunks(slot_len)
+                    .any(|slot| open_slot(key, 0, slot).is_some())
+            })
+            .map(|key| Some(*key)))
+    }
+
+    /// The newest intact length record: its slot, sequence number and the
+    /// length. `None` for a store nothing was written to yet.
+    fn len_record(file: &mut File) -> io::Result<Option<(u64, u64, u64)>> {
+        let mut header = [0u8; 8 + 2 * LEN_RECORD_LEN];
+        read_at(file, 0, &mut header)?;
+        if header.iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        if !vault::is_sealed(&header) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the metadata store isn't encrypted",
+            ));
+        }
+        (0u64..)
+            .zip(header[8..].chunks(LEN_RECORD_LEN))
+            .filter_map(|(slot, record)| {
+                parse_len_record(record).map(|(seq, len)| (slot, seq, len))
+            })
+            .max_by_key(|(_, seq, _)| *seq)
+            .map(Some)
+            .ok_or_else(|| damaged("the length"))
+    }
+
+    fn logical_len(file: &mut File) -> io::Result<u64> {
+        Ok(Self::len_record(file)?.map_or(0, |(_, _, len)| len))
+    }
+
+    /// Writes `len` to the record not holding the synced one.
+    fn write_len(state: &mut State, len: u64) -> io::Result<()> {
+        let (slot, seq) = if let Some((slot, seq)) = state.len_record {
+            (1 - state.synced_len_slot.unwrap_or(slot), seq + 1)
+        } else {
+            state.file.seek(SeekFrom::Start(0))?;
+            state.file.write_all(vault::MAGIC)?;
+            (0, 1)
+        };
+        let mut record = [0u8; LEN_RECORD_LEN];
+        record[..8].copy_from_slice(&seq.to_le_bytes());
+        record[8..16].copy_from_slice(&len.to_le_bytes());
+        record[16..].copy_from_slice(&len_checksum(seq, len));
+        state
+            .file
+            .seek(SeekFrom::Start(8 + slot * LEN_RECORD_LEN as u64))?;
+        state.file.write_all(&record)?;
+        state.len = len;
+        state.len_record = Some((slot, seq));
+        Ok(())
+    }
+
+    /// The newest intact copy of sector `index`: its slot, version and
+    /// plaintext. `None` if the sector was never written.
+    fn newest_copy(&self, file: &mut File, index: u64) -> io::Result<Option<(u64, u64, Vec<u8>)>> {
+        let mut slots = vec![0u8; to_usize(2 * SLOT_LEN)?];
+        read_at(file, slot_offset(index, 0), &mut slots)?;
+        let mut newest: Option<(u64, u64, Vec<u8>)> = None;
+        let mut written = false;
+        for (slot, bytes) in (0u64..).zip(slots.chunks(to_usize(SLOT_LEN)?)) {
+            // A sealed copy has a version and a random nonce, so all zeros is
+            // a slot never written, or a gap left by growing the file
+            if bytes.iter().all(|b| *b == 0) {
+                continue;
+            }
+            written = true;
+            if let Some((version, plain)) = open_slot(&self.key, index, bytes)
+                && newest.as_ref().is_none_or(|(_, newer, _)| version > *newer)
+            {
+                newest = Some((slot, version, plain));
+            }
+        }
+        if written && newest.is_none() {
+            return Err(damaged("a sector"));
+        }
+        Ok(newest)
+    }
+
+    /// Plaintext of sector `index`; one never written reads as zeros.
+    fn read_sector(&self, state: &mut State, index: u64) -> io::Result<Vec<u8>> {
+        if let Some(&sector) = state.sectors.get(&index) {
+            let mut bytes = vec![0u8; to_usize(SLOT_LEN)?];
+            read_at(&mut state.file, slot_offset(index, sector.slot), &mut bytes)?;
+            return match open_slot(&self.key, index, &bytes) {
+                Some((found, plain)) if found == sector.version => Ok(plain),
+                _ => Err(damaged("a sector")),
+            };
+        }
+        match self.newest_copy(&mut state.file, index)? {
+            Some((slot, version, plain)) => {
+                state.sectors.insert(index, Sector::on_disk(slot, version));
+                Ok(plain)
+            }
+            None => Ok(vec![0u8; to_usize(SECTOR_LEN)?]),
+        }
+    }
+
+    /// Seals `plain` as the next version of sector `index`, into the slot
+    /// not holding the synced copy.
+    fn write_sector(&self, state: &mut State, index: u64, plain: &[u8]) -> io::Result<()> {
+        let known = match state.sectors.get(&index) {
+            Some(&sector) => Some(sector),
+            None => self
+                .newest_copy(&mut state.file, index)?
+                .map(|(slot, version, _)| Sector::on_disk(slot, version)),
+        };
+        let sector = known.map_or(
+            Sector {
+                slot: 0,
+                version: 1,
+                synced_slot: None,
+            },
+            |sector| Sector {
+                slot: sector.next_slot(),
+                version: sector.version + 1,
+                synced_slot: sector.synced_slot,
+            },
+        );
+        let mut bytes = Vec::with_capacity(to_usize(SLOT_LEN)?);
+        bytes.extend_from_slice(&sector.version.to_le_bytes());
+        bytes.extend(
+            self.key
+                .seal_chunk(&sector_aad(index, sector.version), plain)?,
+        );
+        state
+            .file
+            .seek(SeekFrom::Start(slot_offset(index, sector.slot)))?;
+        state.file.write_all(&bytes)?;
+        state.sectors.insert(index, sector);
+        Ok(())
+    }
+}
+
+impl StorageBackend for EncryptedBackend {
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.state.lock().len)
+    }
+
+    fn read(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut state = self.state.lock();
+        let mut out = Vec::with_capacity(len);
+        let end = offset + len as u64;
+        let mut pos = offset;
+        while pos < end {
+            let index = pos / SECTOR_LEN;
+            let sector = self.read_sector(&mut state, index)?;
+            let start = to_usize(pos % SECTOR_LEN)?;
+            let take = to_usize((SECTOR_LEN - pos % SECTOR_LEN).min(end - pos))?;
+            out.extend_from_slice(&sector[start..start + take]);
+            pos += take as u64;
+        }
+        drop(state);
+        Ok(out)
+    }
+
+    fn set_len(&self, len: u64) -> io::Result<()> {
+        let mut state = self.state.lock();
+        let old = state.len;
+        // Bytes cut off in the last sector have to read as zeros if the
+        // store grows again
+        if len < old && !len.is_multiple_of(SECTOR_LEN) {
+            let index = len / SECTOR_LEN;
+            let mut sector = self.read_sector(&mut state, index)?;
+            sector[to_usize(len % SECTOR_LEN)?..].fill(0);
+            self.write_sector(&mut state, index, &sector)?;
+        }
+        let sectors = len.div_ceil(SECTOR_LEN);
+        state.sectors.retain(|index, _| *index < sectors);
+        state.file.set_len(HEADER_LEN + sectors * 2 * SLOT_LEN)?;
+        Self::write_len(&mut state, len)?;
+        drop(state);
+        Ok(())
+    }
+
+    fn sync_data(&self, _eventual: bool) -> io::Result<()> {
+        let mut state = self.state.lock();
+        state.file.sync_data()?;
+        // Everything written so far is durable now, so the next writes go
+        // to the other slots
+        for sector in state.sectors.values_mut() {
+            sector.synced_slot = Some(sector.slot);
+        }
+        state.synced_len_slot = state.len_record.map(|(slot, _)| slot);
+        drop(state);
+        Ok(())
+    }
+
+    fn write(&self, offset: u64, data: &[u8]) -> io::Result<()> {
+        let mut state = self.state.lock();
+        let end = offset + data.len() as u64;
+        let mut pos = offset;
+        while pos < end {
+            let index = pos / SECTOR_LEN;
+            let start = to_usize(pos % SECTOR_LEN)?;
+            let take = to_usize((SECTOR_LEN - pos % SECTOR_LEN).min(end - pos))?;
+            let from = to_usize(pos - offset)?;
+            let chunk = &data[from..from + take];
+            if take == to_usize(SECTOR_LEN)? {
+                self.write_sector(&mut state, index, chunk)?;
+            } else {
+                let mut sector = self.read_sector(&mut state, index)?;
+                sector[start..start + take].copy_from_slice(chunk);
+                self.write_sector(&mut state, index, &sector)?;
+            }
+            pos += take as u64;
+        }
+        if end > state.len {
+            Self::write_len(&mut state, end)?;
+        }
+        drop(state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sectors_round_trip_and_stay_sealed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store");
+        let key = Key::from_bytes(&[1; 32]);
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        assert_eq!(backend.len().unwrap(), 0);
+
+        backend.set_len(3 * SECTOR_LEN).unwrap();
+        assert_eq!(backend.read(100, 50).unwrap(), vec![0u8; 50]);
+        let data: Vec<u8> = (0..6000).map(|i| u8::try_from(i % 199).unwrap()).collect();
+        backend.write(1000, &data).unwrap();
+        assert_eq!(backend.read(1000, data.len()).unwrap(), data);
+        assert_eq!(backend.len().unwrap(), 3 * SECTOR_LEN);
+
+        backend.set_len(1500).unwrap();
+        backend.set_len(2 * SECTOR_LEN).unwrap();
+        assert_eq!(backend.read(1000, 500).unwrap(), data[..500]);
+        assert_eq!(backend.read(1500, 100).unwrap(), vec![0u8; 100]);
+        drop(backend);
+
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(64).any(|w| w == &data[..64]));
+        let other = Key::from_bytes(&[2; 32]);
+        assert!(
+            EncryptedBackend::key_of(&path, &[&other])
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            EncryptedBackend::key_of(&path, &[&other, &key])
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_torn_writes_fall_back_to_the_previous_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store");
+        let key = Key::from_bytes(&[1; 32]);
+        let sector = to_usize(SECTOR_LEN).unwrap();
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        backend.set_len(SECTOR_LEN).unwrap();
+        backend.write(0, &vec![1u8; sector]).unwrap();
+        backend.write(0, &vec![2u8; sector]).unwrap();
+        backend.set_len(2 * SECTOR_LEN).unwrap();
+        drop(backend);
+
+        // Tear the newest copy of sector 0 and the newest length record, as a
+        // crash halfway through writing them would
+        let mut raw = std::fs::read(&path).unwrap();
+        let word = |at: usize| u64::from_le_bytes(raw[at..at + 8].try_into().unwrap());
+        let slot_at = |slot| to_usize(slot_offset(0, slot)).unwrap();
+        let newest_slot = u64::from(word(slot_at(0)) <= word(slot_at(1)));
+        let newest_record = usize::from(word(8) <= word(8 + LEN_RECORD_LEN));
+        let torn = slot_at(newest_slot) + 100;
+        raw[torn..torn + 64].fill(0xAA);
+        raw[8 + newest_record * LEN_RECORD_LEN + 10] ^= 0xFF;
+        std::fs::write(&path, &raw).unwrap();
+
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        assert_eq!(backend.len().unwrap(), SECTOR_LEN);
+        assert_eq!(backend.read(0, 16).unwrap(), vec![1u8; 16]);
+        // The next write replaces the torn copy
+        backend.write(0, &[3u8; 16]).unwrap();
+        drop(backend);
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        assert_eq!(backend.read(0, 16).unwrap(), vec![3u8; 16]);
+        assert_eq!(backend.read(16, 16).unwrap(), vec![1u8; 16]);
+    }
+
+    #[test]
+    fn test_writes_between_syncs_keep_the_synced_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store");
+        let key = Key::from_bytes(&[1; 32]);
+        let sector = to_usize(SECTOR_LEN).unwrap();
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        backend.set_len(SECTOR_LEN).unwrap();
+        backend.write(0, &vec![1u8; sector]).unwrap();
+        backend.sync_data(false).unwrap();
+        backend.write(0, &vec![2u8; sector]).unwrap();
+        backend.write(0, &vec![3u8; sector]).unwrap();
+        backend.set_len(2 * SECTOR_LEN).unwrap();
+        backend.set_len(3 * SECTOR_LEN).unwrap();
+        assert_eq!(backend.read(0, 16).unwrap(), vec![3u8; 16]);
+        drop(backend);
+
+        // Both unsynced copies went to the same slot; tearing it, as a crash
+        // during the second write would, leaves the synced copy
+        let mut raw = std::fs::read(&path).unwrap();
+        let word = |at: usize| u64::from_le_bytes(raw[at..at + 8].try_into().unwrap());
+        let slot_at = |slot| to_usize(slot_offset(0, slot)).unwrap();
+        let newest_slot = u64::from(word(slot_at(0)) <= word(slot_at(1)));
+        let newest_record = usize::from(word(8) <= word(8 + LEN_RECORD_LEN));
+        let torn = slot_at(newest_slot) + 100;
+        raw[torn..torn + 64].fill(0xAA);
+        raw[8 + newest_record * LEN_RECORD_LEN + 10] ^= 0xFF;
+        std::fs::write(&path, &raw).unwrap();
+
+        let backend = EncryptedBackend::open(&path, &key).unwrap();
+        assert_eq!(backend.len().unwrap(), SECTOR_LEN);
+        assert_eq!(backend.read(0, 16).unwrap(), vec![1u8; 16]);
+    }
+}
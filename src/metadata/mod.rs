@@ -1,3 +1,10 @@
+pub mod attributes;
 pub mod db;
+pub mod encrypted_backend;
 
-pub use db::{FileMetadata, MetadataDb};
+pub use attributes::FileAttributes;
+
+pub use db::{
+    FileAnnotation, FileMetadata, MetadataDb, ParseErrorKind, ParseErrorRecord, PendingBatch,
+    ScanCheckpoint, UsnPosition,
+};
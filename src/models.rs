@@ -1,7 +1,66 @@
+pub use crate::indexer::filename_index::EntryKind;
 pub use crate::indexer::searcher::{IndexStatistics, SearchResult};
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 
+/// A past search, kept in the metadata database
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchHistoryItem {
+    pub query: String,
+    pub frequency: u32,
+    pub last_used: u64,
+    /// Listed first, so trimming the history drops it last
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A respelling of a query that found nothing, made from terms in the index
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpellingSuggestion {
+    /// The query with every correction applied
+    pub query: String,
+    /// Each word the index doesn't have, with the indexed term offered for it
+    pub corrections: Vec<(String, String)>,
+}
+
+impl SpellingSuggestion {
+    /// "budgt → budget, reprot → report"
+    #[must_use]
+    pub fn describe(&self) -> String {
+        self.corrections
+            .iter()
+            .map(|(from, to)| format!("{from} → {to}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One line of a [`ContentDiff`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DiffLine {
+    /// In both files
+    Same(String),
+    /// Only in the first file
+    Removed(String),
+    /// Only in the second file
+    Added(String),
+    /// This many unchanged lines left out between changes
+    Skipped(usize),
+}
+
+/// Line-by-line comparison of the parsed text of two files, showing the
+/// changes with a few unchanged lines around each
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContentDiff {
+    pub left: String,
+    pub right: String,
+    pub lines: Vec<DiffLine>,
+    /// Lines only the second file has
+    pub added: usize,
+    /// Lines only the first file has
+    pub removed: usize,
+}
+
 /// Recent file from metadata DB
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RecentFile {
@@ -75,6 +134,7 @@ impl RecentFileBuilder {
 pub struct FilenameSearchResult {
     pub file_path: String,
     pub file_name: CompactString,
+    pub kind: EntryKind,
 }
 
 /// Filename index statistics
@@ -102,6 +162,48 @@ pub enum ElementType {
 pub struct DocumentElementHighlight {
     pub element_type: ElementType,
     pub spans: Vec<(String, Option<[f32; 4]>)>,
+    /// Positions in `spans` of the search matches, in reading order
+    #[serde(default)]
+    pub matches: Vec<usize>,
+}
+
+/// A file as it is on disk next to what the index recorded for it
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDetails {
+    pub path: String,
+    /// On disk; `None` when the file can't be read
+    pub size: Option<u64>,
+    pub modified: Option<u64>,
+    pub created: Option<u64>,
+    /// Blake3 hash of the indexed content, in hex
+    pub content_hash: Option<String>,
+    /// When the index last took the file in; `None` if it never did
+    pub indexed_at: Option<u64>,
+    /// Extractor and the MIME type it treated the file as
+    pub parser: String,
+    /// The file changed on disk after it was indexed
+    pub stale: bool,
+}
+
+/// What the preview pane shows for a file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PreviewKind {
+    /// Parsed text, as returned by the highlighted preview
+    #[default]
+    Text,
+    /// An image the UI decodes and scales itself
+    Image { path: String },
+    /// The first page of a PDF, as text laid out like a page
+    PdfPage { text: String, pages: usize },
+    /// Nothing readable: a hex and ASCII dump of the file's first bytes
+    Binary {
+        mime: String,
+        /// Lines of offset, hex bytes and their printable characters
+        hex: String,
+        /// Size of the whole file, in bytes
+        size: u64,
+    },
 }
 
 /// Preview result with highlighting
@@ -109,6 +211,15 @@ pub struct DocumentElementHighlight {
 pub struct PreviewResult {
     pub elements: Vec<DocumentElementHighlight>,
     pub matched_terms: Vec<String>,
+    /// Byte range of the parsed text that `elements` cover. Long documents
+    /// are cut to a window around the first match.
+    #[serde(default)]
+    pub start: usize,
+    #[serde(default)]
+    pub end: usize,
+    /// Bytes of parsed text in the whole document
+    #[serde(default)]
+    pub total_len: usize,
 }
 
 /// Index status
@@ -116,4 +227,251 @@ pub struct PreviewResult {
 pub struct IndexStatus {
     pub status: String,
     pub files_indexed: usize,
+    /// What the current or last scan run does
+    #[serde(default)]
+    pub operation: Option<ScanOperation>,
+    /// Root that reported progress last while the run is going
+    #[serde(default)]
+    pub current_root: Option<String>,
+    /// Files the run has indexed and found so far, over all its roots
+    #[serde(default)]
+    pub processed: usize,
+    #[serde(default)]
+    pub total: usize,
+    /// When the run started, in seconds since the Unix epoch
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    /// Roots of the current scan run, or of the last one once it finished
+    #[serde(default)]
+    pub roots: Vec<RootScanStatus>,
+    #[serde(default)]
+    pub pipeline: PipelineStatus,
+    /// Files the run's roots skipped as too large or unreadable
+    #[serde(default)]
+    pub skipped: usize,
+    /// Files the run's roots left out because the user can't read them
+    #[serde(default)]
+    pub no_access: usize,
+    /// Files the run's roots failed to parse
+    #[serde(default)]
+    pub errors: usize,
+    /// Files indexed per second across the run's roots, one sample a second,
+    /// oldest first
+    #[serde(default)]
+    pub throughput: Vec<f64>,
+}
+
+/// Kind of work a scan run does
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanOperation {
+    /// Full walk indexing every new or changed file
+    Scan,
+    /// Incremental refresh of changed directories
+    Refresh,
+}
+
+/// The current scan run, or the last one once it finished
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ScanRunStatus {
+    pub running: bool,
+    pub operation: Option<ScanOperation>,
+    pub started_at: Option<u64>,
+    pub current_root: Option<String>,
+    pub roots: Vec<RootScanStatus>,
+    /// Files indexed per second, one sample a second, oldest first
+    pub throughput: Vec<f64>,
+}
+
+/// How far one stage of a root's scan got
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct StageProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub files_per_second: f64,
+}
+
+/// One root of a scan run
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RootScanStatus {
+    pub root: String,
+    /// Files parsed and written to the search index
+    pub content: StageProgress,
+    /// Names collected for the filename index
+    pub filenames: StageProgress,
+    pub skipped: usize,
+    /// Files left out because the user can't read them
+    #[serde(default)]
+    pub no_access: usize,
+    pub errors: usize,
+    pub finished: bool,
+}
+
+/// Results sharing a containing folder, for showing hits from one directory
+/// together
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderGroup {
+    pub folder: String,
+    /// Positions of the folder's results in the result list, in list order
+    pub indices: Vec<usize>,
+}
+
+/// Live-update health of the file watcher
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatcherStatus {
+    /// Roots receiving live events, from file notifications or a change journal
+    pub watched_roots: Vec<String>,
+    /// Roots that are offline or could not be watched
+    pub unwatched_roots: Vec<String>,
+    /// Events waiting in the debounce buffer
+    pub pending_events: usize,
+    pub events_processed: u64,
+    pub last_error: Option<String>,
+}
+
+/// Queue depths and memory use of the scan pipeline, for spotting which stage
+/// a slow scan is waiting on
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineStatus {
+    /// Walked paths waiting for the filter
+    pub queued_paths: usize,
+    /// Path chunks waiting for the parser
+    pub queued_chunks: usize,
+    /// Parsed documents waiting for the index writer
+    pub queued_documents: usize,
+    /// Text held by parsed documents not yet handed to the index
+    pub buffered_bytes: u64,
+    /// Highest `buffered_bytes` since the current or last scan started
+    pub peak_buffered_bytes: u64,
+    pub budget_bytes: u64,
+    /// Times the parser waited for the writer to free part of the budget,
+    /// since the current or last scan started
+    pub backpressure_waits: u64,
+}
+
+/// Result of cross-checking the metadata store, the search index and the
+/// filename index. Each list holds paths, sorted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub metadata_records: usize,
+    pub indexed_documents: usize,
+    pub filename_entries: usize,
+    /// Recorded in the metadata store but absent from the search index
+    pub missing_documents: Vec<String>,
+    /// In the search index without a metadata record
+    pub orphaned_documents: Vec<String>,
+    /// Indexed more than once
+    pub duplicate_documents: Vec<String>,
+    /// The search index and the metadata disagree on size or modification
+    /// time, or the file's content no longer matches its recorded hash
+    pub hash_mismatches: Vec<String>,
+    /// Recorded or indexed, but the file is gone from disk
+    pub deleted_files: Vec<String>,
+    /// Recorded files missing from the filename index
+    pub missing_filenames: Vec<String>,
+    /// Filename index entries whose file is gone
+    pub orphaned_filenames: Vec<String>,
+    /// Whether the offenders were queued for repair
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    #[must_use]
+    pub const fn issue_count(&self) -> usize {
+        self.missing_documents.len()
+            + self.orphaned_documents.len()
+            + self.duplicate_documents.len()
+            + self.hash_mismatches.len()
+            + self.deleted_files.len()
+            + self.missing_filenames.len()
+            + self.orphaned_filenames.len()
+    }
+
+    #[must_use]
+    pub const fn is_consistent(&self) -> bool {
+        self.issue_count() == 0
+    }
+}
+
+/// Re-index schedule with its last and next run as Unix timestamps
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScheduleStatus {
+    pub schedule: crate::settings::ReindexSchedule,
+    pub last_run: Option<u64>,
+    pub next_run: Option<u64>,
+}
+
+/// Files of one category a scan would parse
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CategoryEstimate {
+    pub category: crate::indexer::category::FileCategory,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// What indexing a folder would take, from a walk that reads no file content
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanEstimate {
+    pub root: String,
+    /// Files the walk reached, excluded folders left out
+    pub files_seen: usize,
+    /// Files whose content would be parsed
+    pub indexable_files: usize,
+    pub indexable_bytes: u64,
+    /// Indexable files by category, most files first
+    pub by_category: Vec<CategoryEstimate>,
+    /// Indexable by type but over the per-file size limit, so skipped
+    pub too_large: usize,
+    /// Rough time to parse and index everything, in seconds
+    pub estimated_secs: u64,
+    /// Rough size the content adds to the search index
+    pub estimated_index_bytes: u64,
+    /// The walk stopped at its file cap, so the figures are a lower bound
+    pub truncated: bool,
+}
+
+/// Indexed files grouped under one extension or root
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageGroup {
+    pub label: String,
+    pub documents: usize,
+    pub bytes: u64,
+}
+
+/// An indexed file and its size on disk
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexedFileSize {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Where the index's disk space goes and what it holds
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DetailedStatistics {
+    pub summary: IndexStatistics,
+    pub metadata_db_bytes: u64,
+    pub filename_index_bytes: u64,
+    /// Most documents first
+    pub by_extension: Vec<UsageGroup>,
+    /// In the order of the configured roots
+    pub by_root: Vec<UsageGroup>,
+    /// Largest first
+    pub largest_documents: Vec<IndexedFileSize>,
+}
+
+/// An application registered to open a type of file
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenWithApp {
+    pub name: String,
+    /// Command line on Windows and Linux, application bundle on macOS
+    pub launch: String,
+}
+
+/// Where a search first matched in a file, for opening it there
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchLocation {
+    /// Line of a text or source file, counted from 1
+    pub line: Option<usize>,
+    /// Page of a PDF, counted from 1
+    pub page: Option<usize>,
 }
@@ -1,6 +1,6 @@
 use crate::error::{FlashError, Result};
+use crate::scanner::access;
 use memmap2::Mmap;
-use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
@@ -48,7 +48,7 @@ pub fn read_file_as_string(path: &Path) -> Result<String> {
 }
 
 fn read_with_buffer(path: &Path) -> Result<FileData> {
-    let mut file = File::open(path).map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+    let mut file = access::open(path).map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
     let mut bytes = Vec::new();
     file.read_to_end(&mut bytes)
         .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
@@ -56,7 +56,7 @@ fn read_with_buffer(path: &Path) -> Result<FileData> {
 }
 
 fn read_with_mmap(path: &Path) -> Result<FileData> {
-    let file = File::open(path)
+    let file = access::open(path)
         .map_err(|e| FlashError::parse(path, format!("Failed to open file: {e}")))?;
 
     // SAFETY: Memory mapping a file is unsafe because another process could modify it
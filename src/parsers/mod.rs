@@ -1,5 +1,6 @@
 use crate::error::{FlashError, Result};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 pub mod memory_map;
 
@@ -15,6 +16,40 @@ pub struct ParsedDocument {
     pub layout: Option<String>,
     pub code_metadata: Option<String>,
     pub embeddings: Option<Vec<f32>>,
+    /// Search text of the user's annotation, attached by the indexing pipeline
+    pub annotations: Option<String>,
+    /// Owner and permission flags, attached by the indexing pipeline
+    pub attributes: crate::metadata::FileAttributes,
+}
+
+impl ParsedDocument {
+    /// Document with no extracted content, for roots that index names only.
+    #[must_use]
+    pub fn filename_only(path: &Path) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            content: String::new(),
+            title: None,
+            language: None,
+            keywords: None,
+            layout: None,
+            code_metadata: None,
+            embeddings: None,
+            annotations: None,
+            attributes: crate::metadata::FileAttributes::default(),
+        }
+    }
+
+    /// Bytes of text the document feeds the index, which is also what it
+    /// holds in memory while it waits in the indexing pipeline.
+    #[must_use]
+    pub fn text_len(&self) -> usize {
+        self.path.len()
+            + self.content.len()
+            + self.title.as_ref().map_or(0, |t| t.len())
+            + self.keywords.as_ref().map_or(0, String::len)
+            + self.annotations.as_ref().map_or(0, String::len)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +58,15 @@ pub struct PreviewElement {
     pub content: String,
 }
 
+/// Which extractor handles `path`, for showing to the user.
+#[must_use]
+pub fn parser_name(path: &Path) -> String {
+    xberg::detect_mime_type(path.to_string_lossy().into_owned(), true).map_or_else(
+        |_| "Xberg (unknown type)".to_string(),
+        |mime| format!("Xberg ({mime})"),
+    )
+}
+
 /// Detect file type and route to appropriate parser using Xberg
 pub async fn parse_file(path: &Path, enable_ocr: bool) -> Result<ParsedDocument> {
     // Log the file extension for debugging
@@ -195,6 +239,94 @@ pub async fn parse_files_batch(
     Ok(results)
 }
 
+/// Parse a single file on its own Tokio task so a hanging or panicking
+/// extractor cannot take the scan down with it.
+///
+/// The task is aborted once `timeout` elapses, but that only stops waiting for
+/// it: blocking work xberg has already handed to the blocking pool cannot be
+/// interrupted. A parser that hangs there keeps its worker thread, and the
+/// memory it holds, until it returns, so enough hung files can use up the pool.
+pub async fn parse_file_isolated(
+    path: &Path,
+    enable_ocr: bool,
+    timeout: Duration,
+) -> Result<ParsedDocument> {
+    let owned = path.to_path_buf();
+    let mut handle = tokio::spawn(async move { parse_file(&owned, enable_ocr).await });
+
+    match tokio::time::timeout(timeout, &mut handle).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(join_err)) => Err(join_error_to_flash(path, join_err)),
+        Err(_) => {
+            handle.abort();
+            tracing::warn!(
+                "Parser timed out after {}s on {}",
+                timeout.as_secs(),
+                path.display()
+            );
+            Err(FlashError::parse_timeout(path, timeout.as_secs()))
+        }
+    }
+}
+
+/// Runs [`parse_files_batch`] on its own task with a deadline scaled to the
+/// batch size. If the batch crashes, panics or times out, each file is parsed
+/// again with [`parse_file_isolated`], so every failure is reported against the
+/// file that caused it.
+///
+/// The deadline has the same limit as [`parse_file_isolated`]'s: a hung parser
+/// keeps its blocking worker after the batch is given up on.
+pub async fn parse_files_batch_isolated(
+    paths: &[PathBuf],
+    max_threads: u8,
+    enable_ocr: bool,
+    per_file_timeout: Duration,
+) -> Vec<Result<ParsedDocument>> {
+    let owned = paths.to_vec();
+    let rounds = u32::try_from(paths.len().div_ceil(usize::from(max_threads.max(1))))
+        .unwrap_or(u32::MAX)
+        .max(1);
+    let deadline = per_file_timeout.saturating_mul(rounds);
+
+    let mut handle =
+        tokio::spawn(async move { parse_files_batch(&owned, max_threads, enable_ocr).await });
+
+    match tokio::time::timeout(deadline, &mut handle).await {
+        Ok(Ok(Ok(results))) => return results,
+        Ok(Ok(Err(e))) => tracing::warn!("Batch extraction failed ({e}), parsing file by file"),
+        Ok(Err(join_err)) => tracing::warn!("Batch task failed ({join_err}), parsing file by file"),
+        Err(_) => {
+            handle.abort();
+            tracing::warn!(
+                "Batch of {} files timed out after {}s, parsing file by file",
+                paths.len(),
+                deadline.as_secs()
+            );
+        }
+    }
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in paths {
+        results.push(parse_file_isolated(path, enable_ocr, per_file_timeout).await);
+    }
+    results
+}
+
+fn join_error_to_flash(path: &Path, err: tokio::task::JoinError) -> FlashError {
+    if !err.is_panic() {
+        return FlashError::parse(path, format!("Parser task cancelled: {err}"));
+    }
+
+    let payload = err.into_panic();
+    let cause = payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string());
+    tracing::error!("Parser panicked on {}: {}", path.display(), cause);
+    FlashError::parser_panic(path, cause)
+}
+
 /// Maps a `xberg::ExtractedDocument` into a `ParsedDocument`.
 fn map_extracted_document(path: &Path, doc: xberg::ExtractedDocument) -> ParsedDocument {
     let language = doc
@@ -224,6 +356,8 @@ fn map_extracted_document(path: &Path, doc: xberg::ExtractedDocument) -> ParsedD
         embeddings: doc
             .chunks
             .and_then(|c| c.into_iter().find_map(|chunk| chunk.embedding)),
+        annotations: None,
+        attributes: crate::metadata::FileAttributes::default(),
     }
 }
 
@@ -260,6 +394,28 @@ mod tests {
         assert!(doc.content.contains("Hello, world!"));
     }
 
+    #[tokio::test]
+    async fn test_parse_file_isolated_txt() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("isolated.txt");
+        std::fs::write(&file_path, "Isolated parse").unwrap();
+
+        let doc = parse_file_isolated(&file_path, false, Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert!(doc.content.contains("Isolated parse"));
+    }
+
+    #[tokio::test]
+    async fn test_join_error_reports_panic() {
+        let handle = tokio::spawn(async { panic!("zip bomb") });
+        let err = handle.await.unwrap_err();
+        let flash_err = join_error_to_flash(Path::new("bomb.zip"), err);
+        assert!(
+            matches!(flash_err, FlashError::ParserPanic { ref cause, .. } if cause == "zip bomb")
+        );
+    }
+
     #[tokio::test]
     async fn test_parse_file_unknown() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,209 @@
+//! Files the user isn't allowed to read. Scans ask before parsing so a
+//! refused file is counted as "no access" rather than reported as a parse
+//! failure, and a network share that stalls on its permission check can't
+//! hold up the scan. On Windows a root may opt in to reading such files with
+//! backup rights, which the app only holds when it runs as administrator.
+
+use crate::settings::IndexRoot;
+use parking_lot::RwLock;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Roots whose files are read with backup rights when plain reads are refused
+static ELEVATED: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// Whether `err` means the user may not open the file.
+#[must_use]
+pub fn is_denied(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// Lets files under the roots that opted in be read with backup rights. Only
+/// Windows has them, and only an administrator gets them; otherwise the roots
+/// are read like any other.
+pub fn set_elevated_roots(roots: &[IndexRoot]) {
+    let mut elevated: Vec<PathBuf> = roots
+        .iter()
+        .filter(|root| root.elevated_access)
+        .map(|root| PathBuf::from(&root.path))
+        .collect();
+    if !elevated.is_empty() {
+        if platform::enable_backup_privilege() {
+            info!("Reading {} root(s) with backup rights", elevated.len());
+        } else {
+            warn!("Reading files without access needs the app to run as administrator on Windows");
+            elevated.clear();
+        }
+    }
+    *ELEVATED.write() = elevated;
+}
+
+/// Whether files under `path` may be read with backup rights.
+#[must_use]
+pub fn is_elevated(path: &Path) -> bool {
+    let roots = ELEVATED.read();
+    !roots.is_empty() && roots.iter().any(|root| path.starts_with(root))
+}
+
+/// Opens `path` for reading, falling back to backup rights if the read is
+/// refused and its root opted in.
+///
+/// # Errors
+///
+/// Returns the error of opening the file.
+pub fn open(path: &Path) -> io::Result<File> {
+    match File::open(path) {
+        Err(e) if is_denied(&e) && is_elevated(path) => platform::open_elevated(path),
+        result => result,
+    }
+}
+
+/// Whether `path` can only be read with backup rights.
+#[must_use]
+pub fn needs_elevation(path: &Path) -> bool {
+    is_elevated(path) && File::open(path).is_err_and(|e| is_denied(&e))
+}
+
+/// Whether opening `path` is refused. With a `timeout` the file is opened on
+/// a helper thread, and a share that doesn't answer in time counts as
+/// refusing. Other errors are left for the parser to report.
+#[must_use]
+pub fn is_refused(path: &Path, timeout: Option<Duration>) -> bool {
+    let Some(timeout) = timeout else {
+        return open(path).is_err_and(|e| is_denied(&e));
+    };
+    let (tx, rx) = std::sync::mpsc::channel();
+    let owned = path.to_path_buf();
+    let spawned = std::thread::Builder::new()
+        .name("access-probe".into())
+        .spawn(move || {
+            let _ = tx.send(open(&owned).is_err_and(|e| is_denied(&e)));
+        });
+    if spawned.is_err() {
+        return false;
+    }
+    rx.recv_timeout(timeout).unwrap_or_else(|_| {
+        warn!("Timed out checking access to {}", path.display());
+        true
+    })
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::path::Path;
+    use std::sync::OnceLock;
+    use windows::Win32::Foundation::{
+        CloseHandle, ERROR_NOT_ALL_ASSIGNED, GetLastError, HANDLE, LUID,
+    };
+    use windows::Win32::Security::{
+        AdjustTokenPrivileges, LUID_AND_ATTRIBUTES, LookupPrivilegeValueW, SE_BACKUP_NAME,
+        SE_PRIVILEGE_ENABLED, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+    };
+    use windows::Win32::Storage::FileSystem::FILE_FLAG_BACKUP_SEMANTICS;
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+    use windows::core::PCWSTR;
+
+    static BACKUP_PRIVILEGE: OnceLock<bool> = OnceLock::new();
+
+    /// Enables the backup privilege on the process token, once. Only an
+    /// elevated administrator's token carries it.
+    pub fn enable_backup_privilege() -> bool {
+        *BACKUP_PRIVILEGE.get_or_init(|| {
+            // SAFETY: the token handle is closed below and every pointer
+            // passed outlives its call.
+            unsafe {
+                let mut token = HANDLE::default();
+                if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &raw mut token)
+                    .is_err()
+                {
+                    return false;
+                }
+                let mut luid = LUID::default();
+                let enabled = LookupPrivilegeValueW(PCWSTR::null(), SE_BACKUP_NAME, &raw mut luid)
+                    .is_ok()
+                    && {
+                        let privileges = TOKEN_PRIVILEGES {
+                            PrivilegeCount: 1,
+                            Privileges: [LUID_AND_ATTRIBUTES {
+                                Luid: luid,
+                                Attributes: SE_PRIVILEGE_ENABLED,
+                            }],
+                        };
+                        // Succeeds without the privilege too, reporting it
+                        // through the last error
+                        AdjustTokenPrivileges(
+                            token,
+                            false,
+                            Some(&raw const privileges),
+                            0,
+                            None,
+                            None,
+                        )
+                        .is_ok()
+                            && GetLastError() != ERROR_NOT_ALL_ASSIGNED
+                    };
+                let _ = CloseHandle(token);
+                enabled
+            }
+        })
+    }
+
+    /// Backup semantics skip the file's access checks for a holder of the
+    /// backup privilege.
+    pub fn open_elevated(path: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS.0)
+            .open(path)
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::fs::File;
+    use std::io;
+    use std::path::Path;
+
+    pub const fn enable_backup_privilege() -> bool {
+        false
+    }
+
+    pub fn open_elevated(path: &Path) -> io::Result<File> {
+        File::open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_refused_files_are_told_apart() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let open_file = dir.path().join("open.txt");
+        let locked = dir.path().join("locked.txt");
+        std::fs::write(&open_file, "hello").unwrap();
+        std::fs::write(&locked, "secret").unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Root reads everything, so there is nothing to refuse
+        if File::open(&locked).is_ok() {
+            return;
+        }
+
+        assert!(is_refused(&locked, None));
+        assert!(is_refused(&locked, Some(Duration::from_secs(5))));
+        assert!(!is_refused(&open_file, Some(Duration::from_secs(5))));
+        // A missing file isn't a permission problem
+        assert!(!is_refused(&dir.path().join("gone.txt"), None));
+        assert!(!needs_elevation(&locked));
+    }
+}
@@ -0,0 +1,107 @@
+//! Online-only files left by sync clients such as OneDrive, Dropbox, Google
+//! Drive and iCloud. Their content stays in the cloud until the file is read,
+//! and reading it downloads the whole file, so scans and live events index
+//! them by name and metadata only. Folders the user lists are exempt.
+
+use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Folders whose online-only files may be downloaded to index their content
+static CONTENT_FOLDERS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// Lets online-only files under `folders` be read like any other file.
+pub fn set_content_folders(folders: &[String]) {
+    *CONTENT_FOLDERS.write() = folders
+        .iter()
+        .map(|folder| folder.trim())
+        .filter(|folder| !folder.is_empty())
+        .map(PathBuf::from)
+        .collect();
+}
+
+/// Whether `path` is a cloud placeholder whose content isn't on this machine.
+/// Only the file's attributes are looked at, which doesn't download it.
+#[must_use]
+pub fn is_placeholder(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| platform::is_placeholder(path, &metadata))
+}
+
+/// Whether `path` is indexed by name and metadata only because reading it
+/// would download it.
+#[must_use]
+pub fn hides_content(path: &Path) -> bool {
+    let exempt = CONTENT_FOLDERS
+        .read()
+        .iter()
+        .any(|folder| path.starts_with(folder));
+    if exempt || !is_placeholder(path) {
+        return false;
+    }
+    debug!("{} is online-only, indexing its name only", path.display());
+    true
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::fs::MetadataExt;
+    use std::path::Path;
+
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+
+    /// The cloud files API marks dehydrated files with the recall attributes;
+    /// older clients set the offline flag instead.
+    pub fn is_placeholder(_path: &Path, metadata: &std::fs::Metadata) -> bool {
+        metadata.file_attributes()
+            & (FILE_ATTRIBUTE_OFFLINE
+                | FILE_ATTRIBUTE_RECALL_ON_OPEN
+                | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+            != 0
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::os::macos::fs::MetadataExt;
+    use std::path::Path;
+
+    /// Set on files whose data a file provider has evicted
+    const SF_DATALESS: u32 = 0x4000_0000;
+
+    /// File providers flag evicted files as dataless; older iCloud Drive
+    /// versions leave a hidden `.name.icloud` stub in their place.
+    pub fn is_placeholder(path: &Path, metadata: &std::fs::Metadata) -> bool {
+        metadata.st_flags() & SF_DATALESS != 0
+            || path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.') && name.ends_with(".icloud"))
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    /// Sync clients here mirror whole files, with no placeholders to detect.
+    pub fn is_placeholder(_path: &Path, _metadata: &std::fs::Metadata) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_files_are_not_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "local").unwrap();
+        assert!(!is_placeholder(&file));
+        assert!(!hides_content(&file));
+        assert!(!is_placeholder(&dir.path().join("missing.txt")));
+    }
+}
@@ -1,26 +1,44 @@
 #[cfg(target_os = "windows")]
 mod windows_usn {
     use crate::error::{FlashError, Result};
+    use crate::metadata::{MetadataDb, UsnPosition};
+    use crate::scanner::exclude::ExcludeMatcher;
+    use crate::scanner::hidden::HiddenFilter;
+    use crate::scanner::journal;
     use crate::scanner::{ProgressEvent, ProgressType};
+    use crate::watcher::WatcherAction;
     use compact_str::CompactString;
     use smallvec::SmallVec;
     use std::collections::HashMap;
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    use tracing::{error, info};
-    use windows::Win32::Foundation::{CloseHandle, GENERIC_READ, HANDLE};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+    use tracing::{error, info, warn};
+    use windows::Win32::Foundation::{
+        CloseHandle, ERROR_JOURNAL_ENTRY_DELETED, GENERIC_READ, HANDLE,
+    };
     use windows::Win32::Storage::FileSystem::{
-        CreateFileW, FILE_ATTRIBUTE_SYSTEM, FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_READ,
-        FILE_SHARE_WRITE, OPEN_EXISTING,
+        CreateFileW, FILE_ATTRIBUTE_DIRECTORY, FILE_FLAG_BACKUP_SEMANTICS, FILE_ID_DESCRIPTOR,
+        FILE_ID_DESCRIPTOR_0, FILE_NAME_NORMALIZED, FILE_SHARE_DELETE, FILE_SHARE_READ,
+        FILE_SHARE_WRITE, FileIdType, GetDriveTypeW, GetFinalPathNameByHandleW,
+        GetVolumeInformationW, OPEN_EXISTING, OpenFileById,
     };
     use windows::Win32::System::IO::DeviceIoControl;
     use windows::Win32::System::Ioctl::{
         FSCTL_ENUM_USN_DATA, FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, MFT_ENUM_DATA_V0,
         READ_USN_JOURNAL_DATA_V0, USN_JOURNAL_DATA_V0, USN_RECORD_V2,
     };
+    use windows::core::PCWSTR;
 
-    const USN_REASON_FILE_DELETE: u32 = 0x0000_0200;
+    const DRIVE_FIXED: u32 = 3;
+    /// How long the live reader sleeps once it has caught up with the journal.
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+    /// How often the read position is persisted. Longer than the watcher's
+    /// debounce window, see [`read_journal`].
+    const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+    /// Resolved directory paths kept before the cache starts over.
+    const DIR_CACHE_LIMIT: usize = 50_000;
 
     #[derive(Debug)]
     struct DirInfo {
@@ -28,11 +46,17 @@ mod windows_usn {
         parent_frn: u64,
     }
 
+    /// Lists every file on the volume from its MFT, which takes seconds where
+    /// a directory walk takes minutes. Excluded paths are skipped, as are
+    /// files and folders `hidden` leaves out.
     pub fn scan_volume(
         root: &Path,
+        excludes: &ExcludeMatcher,
+        hidden: HiddenFilter,
         path_tx: &flume::Sender<PathBuf>,
         progress_tx: Option<&flume::Sender<ProgressEvent>>,
         total_count: &Arc<AtomicUsize>,
+        cancel_flag: &AtomicBool,
     ) -> Result<()> {
         let drive_letter = root.to_string_lossy();
         let mut chars = drive_letter.chars();
@@ -62,18 +86,31 @@ mod windows_usn {
             )
             .map_err(|e| FlashError::index(format!("Failed to open volume handle: {e}")))?;
 
-            let result = iterate_mft(handle, &drive_root, path_tx, progress_tx, total_count);
+            let result = iterate_mft(
+                handle,
+                &drive_root,
+                excludes,
+                hidden,
+                path_tx,
+                progress_tx,
+                total_count,
+                cancel_flag,
+            );
             let _ = CloseHandle(handle);
             result
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     unsafe fn iterate_mft(
         handle: HANDLE,
         drive_root: &str,
+        excludes: &ExcludeMatcher,
+        hidden: HiddenFilter,
         path_tx: &flume::Sender<PathBuf>,
         progress_tx: Option<&flume::Sender<ProgressEvent>>,
         total_count: &Arc<AtomicUsize>,
+        cancel_flag: &AtomicBool,
     ) -> Result<()> {
         unsafe {
             let mut journal_data = USN_JOURNAL_DATA_V0::default();
@@ -107,6 +144,10 @@ mod windows_usn {
             info!("Enumerating MFT records...");
 
             loop {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    info!("MFT enumeration cancelled");
+                    return Ok(());
+                }
                 let mut bytes_returned = 0u32;
                 let success = DeviceIoControl(
                     handle,
@@ -145,8 +186,10 @@ mod windows_usn {
                         break;
                     }
 
-                    // Skip system files to clean up the output and increase performance
-                    if (record.FileAttributes & FILE_ATTRIBUTE_SYSTEM.0) == 0 {
+                    // A folder left out leaves out everything under it, as its
+                    // files find no parent. The root is always kept.
+                    let is_root = record.FileReferenceNumber == record.ParentFileReferenceNumber;
+                    if is_root || !hidden.skips_attributes(record.FileAttributes) {
                         let name_offset = record.FileNameOffset as usize;
                         let name_len_bytes = record.FileNameLength as usize;
 
@@ -176,6 +219,7 @@ mod windows_usn {
 
             reconstruct_paths(
                 drive_root,
+                excludes,
                 path_tx,
                 progress_tx,
                 total_count,
@@ -188,6 +232,7 @@ mod windows_usn {
 
     fn reconstruct_paths(
         drive_root: &str,
+        excludes: &ExcludeMatcher,
         path_tx: &flume::Sender<PathBuf>,
         progress_tx: Option<&flume::Sender<ProgressEvent>>,
         total_count: &Arc<AtomicUsize>,
@@ -234,6 +279,9 @@ mod windows_usn {
                             full_path.push(part);
                         }
                     }
+                    if excludes.is_excluded(&full_path, false) {
+                        return;
+                    }
 
                     let _ = path_tx.send(full_path);
                     let count = total_count.fetch_add(1, Ordering::Relaxed);
@@ -256,155 +304,355 @@ mod windows_usn {
             });
     }
 
-    #[allow(clippy::too_many_lines)]
-    pub fn watch_volume(
-        root: &Path,
-        tx: tokio::sync::mpsc::Sender<(PathBuf, crate::watcher::WatcherAction)>,
-    ) {
-        let drive_letter = root.to_string_lossy();
-        let mut chars = drive_letter.chars();
-        let (volume_path, drive_root_str) = match (chars.next(), chars.next()) {
-            (Some(c1), Some(':')) if c1.is_ascii_alphabetic() => {
-                (format!("\\\\.\\{c1}:"), format!("{c1}:\\"))
+    /// Drive letter of `root` if it sits on a local volume that keeps a change
+    /// journal (NTFS or ReFS).
+    pub fn journal_volume(root: &Path) -> Option<char> {
+        let root_str = root.to_string_lossy();
+        let mut chars = root_str.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), Some(':')) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+            _ => return None,
+        };
+
+        let drive_root: Vec<u16> = format!("{letter}:\\")
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+        let mut fs_name = [0u16; 32];
+        unsafe {
+            if GetDriveTypeW(PCWSTR(drive_root.as_ptr())) != DRIVE_FIXED {
+                return None;
             }
-            _ => {
-                error!("Invalid drive root path for watcher: {drive_letter}");
-                return;
+            GetVolumeInformationW(
+                PCWSTR(drive_root.as_ptr()),
+                None,
+                None,
+                None,
+                None,
+                Some(&mut fs_name),
+            )
+            .ok()?;
+        }
+        let len = fs_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(fs_name.len());
+        let fs_name = String::from_utf16_lossy(&fs_name[..len]);
+        matches!(fs_name.as_str(), "NTFS" | "ReFS").then_some(letter)
+    }
+
+    /// What a volume's journal reader needs from the watcher that owns it.
+    pub struct JournalWatch {
+        pub volume: char,
+        pub metadata_db: Arc<MetadataDb>,
+        pub tx: tokio::sync::mpsc::Sender<(PathBuf, WatcherAction)>,
+        /// Whether a changed path, with the file attributes it has, belongs
+        /// to a watched root whose rules let it in
+        pub accepts: Box<dyn Fn(&Path, u32) -> bool + Send>,
+        /// Called with a path whose root missed changes and needs a refresh
+        pub mark_stale: Box<dyn Fn(&Path) + Send>,
+        pub stop: Arc<AtomicBool>,
+    }
+
+    /// Starts reading the change journal of `watch.volume` from the position
+    /// saved by the last run, so changes made while the app was closed are
+    /// replayed first, then follows it live. Fails if the volume can't be
+    /// opened (usually missing admin rights) or keeps no journal.
+    pub fn watch_journal(watch: JournalWatch) -> Result<()> {
+        let volume_key = format!("{}:", watch.volume);
+        let volume_wide: Vec<u16> = format!("\\\\.\\{volume_key}")
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+
+        let (handle, journal) = unsafe {
+            let handle = CreateFileW(
+                PCWSTR(volume_wide.as_ptr()),
+                GENERIC_READ.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                None,
+            )
+            .map_err(|e| FlashError::index(format!("Failed to open volume handle: {e}")))?;
+            match query_journal(handle) {
+                Ok(journal) => (handle, journal),
+                Err(e) => {
+                    let _ = CloseHandle(handle);
+                    return Err(e);
+                }
             }
         };
 
-        let mut volume_wide: Vec<u16> = volume_path.encode_utf16().collect();
-        volume_wide.push(0);
+        let saved = watch
+            .metadata_db
+            .get_usn_position(&volume_key)
+            .ok()
+            .flatten();
+        let (start_usn, gap) = journal::resume_point(
+            saved,
+            journal.UsnJournalID,
+            journal.FirstUsn,
+            journal.NextUsn,
+        );
+        let drive_root = PathBuf::from(format!("{volume_key}\\"));
+        if gap {
+            warn!("Change journal of {volume_key} lost records since the last run");
+            (watch.mark_stale)(&drive_root);
+        }
+        info!(
+            "Reading change journal of {volume_key} from USN {start_usn} (live tail {})",
+            journal.NextUsn
+        );
 
-        std::thread::spawn(move || {
-            unsafe {
-                let handle = CreateFileW(
-                    windows::core::PCWSTR(volume_wide.as_ptr()),
-                    GENERIC_READ.0,
-                    FILE_SHARE_READ | FILE_SHARE_WRITE,
-                    None,
-                    OPEN_EXISTING,
-                    FILE_FLAG_BACKUP_SEMANTICS,
-                    None,
-                )
-                .unwrap_or(HANDLE(std::ptr::null_mut()));
+        // HANDLE isn't Send; the thread takes ownership of the raw value
+        let raw_handle = handle.0 as usize;
+        std::thread::Builder::new()
+            .name(format!("usn-{volume_key}"))
+            .spawn(move || unsafe {
+                let handle = HANDLE(raw_handle as *mut std::ffi::c_void);
+                read_journal(
+                    handle,
+                    &watch,
+                    &volume_key,
+                    &drive_root,
+                    journal.UsnJournalID,
+                    start_usn,
+                );
+                let _ = CloseHandle(handle);
+            })
+            .map_err(FlashError::from)?;
+        Ok(())
+    }
 
-                if handle == HANDLE(std::ptr::null_mut()) {
-                    error!("Failed to open handle for USN monitoring.");
-                    return;
-                }
+    unsafe fn query_journal(handle: HANDLE) -> Result<USN_JOURNAL_DATA_V0> {
+        let mut journal_data = USN_JOURNAL_DATA_V0::default();
+        let mut bytes_returned = 0u32;
+        unsafe {
+            DeviceIoControl(
+                handle,
+                FSCTL_QUERY_USN_JOURNAL,
+                None,
+                0,
+                Some(std::ptr::addr_of_mut!(journal_data).cast()),
+                u32::try_from(std::mem::size_of::<USN_JOURNAL_DATA_V0>()).unwrap_or(u32::MAX),
+                Some(&raw mut bytes_returned),
+                None,
+            )
+        }
+        .map_err(|e| FlashError::index(format!("Query USN Journal failed: {e}")))?;
+        Ok(journal_data)
+    }
 
-                let mut journal_data = USN_JOURNAL_DATA_V0::default();
-                let mut bytes_returned = 0u32;
+    /// Full path of the directory with reference number `frn`, cached until a
+    /// directory is renamed or deleted.
+    unsafe fn resolve_dir(
+        volume: HANDLE,
+        frn: u64,
+        cache: &mut HashMap<u64, PathBuf>,
+    ) -> Option<PathBuf> {
+        if let Some(path) = cache.get(&frn) {
+            return Some(path.clone());
+        }
 
-                let success = DeviceIoControl(
+        let descriptor = FILE_ID_DESCRIPTOR {
+            dwSize: u32::try_from(std::mem::size_of::<FILE_ID_DESCRIPTOR>()).unwrap_or(u32::MAX),
+            Type: FileIdType,
+            Anonymous: FILE_ID_DESCRIPTOR_0 {
+                FileId: i64::from_ne_bytes(frn.to_ne_bytes()),
+            },
+        };
+        let mut buffer = [0u16; 1024];
+        let len = unsafe {
+            let dir = OpenFileById(
+                volume,
+                &raw const descriptor,
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                FILE_FLAG_BACKUP_SEMANTICS,
+            )
+            .ok()?;
+            let len = GetFinalPathNameByHandleW(dir, &mut buffer, FILE_NAME_NORMALIZED) as usize;
+            let _ = CloseHandle(dir);
+            len
+        };
+        if len == 0 || len > buffer.len() {
+            return None;
+        }
+
+        let raw = String::from_utf16_lossy(&buffer[..len]);
+        let path = PathBuf::from(raw.strip_prefix(r"\\?\").unwrap_or(&raw));
+        if cache.len() >= DIR_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(frn, path.clone());
+        Some(path)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    unsafe fn read_journal(
+        handle: HANDLE,
+        watch: &JournalWatch,
+        volume_key: &str,
+        drive_root: &Path,
+        journal_id: u64,
+        start_usn: i64,
+    ) {
+        let mut read_data = READ_USN_JOURNAL_DATA_V0 {
+            StartUsn: start_usn,
+            ReasonMask: 0xFFFF_FFFF,
+            ReturnOnlyOnClose: 0,
+            Timeout: 0,
+            BytesToWaitFor: 0,
+            UsnJournalID: journal_id,
+        };
+        let save = |journal_id: u64, next_usn: i64| {
+            let position = UsnPosition {
+                journal_id,
+                next_usn,
+            };
+            if let Err(e) = watch.metadata_db.set_usn_position(volume_key, position) {
+                warn!("Failed to save change journal position of {volume_key}: {e}");
+            }
+        };
+
+        // Use a Vec<u64> to ensure 8-byte alignment for USN records and avoid large stack allocation
+        let mut buffer = vec![0u64; 8192]; // 8192 * 8 = 65536 bytes
+        let buffer_ptr = buffer.as_mut_ptr().cast::<u8>();
+        let buffer_len = u32::try_from(buffer.len() * 8).unwrap_or(u32::MAX);
+        let mut dir_paths: HashMap<u64, PathBuf> = HashMap::new();
+
+        // The position saved is the one reached a full interval earlier, by
+        // which time the watcher has flushed everything sent before it
+        let mut saved_usn = start_usn;
+        save(journal_id, saved_usn);
+        let mut last_save = Instant::now();
+
+        while !watch.stop.load(Ordering::Relaxed) {
+            if last_save.elapsed() >= SAVE_INTERVAL {
+                save(read_data.UsnJournalID, saved_usn);
+                saved_usn = read_data.StartUsn;
+                last_save = Instant::now();
+            }
+
+            let mut bytes_returned = 0u32;
+            let result = unsafe {
+                DeviceIoControl(
                     handle,
-                    FSCTL_QUERY_USN_JOURNAL,
-                    None,
-                    0,
-                    Some(std::ptr::addr_of_mut!(journal_data).cast()),
-                    u32::try_from(std::mem::size_of::<USN_JOURNAL_DATA_V0>()).unwrap_or(u32::MAX),
+                    FSCTL_READ_USN_JOURNAL,
+                    Some(std::ptr::addr_of!(read_data).cast()),
+                    u32::try_from(std::mem::size_of::<READ_USN_JOURNAL_DATA_V0>())
+                        .unwrap_or(u32::MAX),
+                    Some(buffer_ptr.cast()),
+                    buffer_len,
                     Some(&raw mut bytes_returned),
                     None,
-                );
+                )
+            };
 
-                if success.is_err() {
-                    let _ = CloseHandle(handle);
-                    return;
+            if let Err(e) = result {
+                // Records were overwritten before they were read; skip to the
+                // live tail and let a refresh pick up what was lost
+                if e.code() == ERROR_JOURNAL_ENTRY_DELETED.to_hresult() {
+                    if let Ok(journal) = unsafe { query_journal(handle) } {
+                        warn!("Change journal of {volume_key} wrapped while reading");
+                        (watch.mark_stale)(drive_root);
+                        read_data.StartUsn = journal.NextUsn;
+                        read_data.UsnJournalID = journal.UsnJournalID;
+                        saved_usn = journal.NextUsn;
+                        continue;
+                    }
                 }
+                error!("Stopped reading change journal of {volume_key}: {e}");
+                (watch.mark_stale)(drive_root);
+                break;
+            }
 
-                let mut read_data = READ_USN_JOURNAL_DATA_V0 {
-                    StartUsn: journal_data.NextUsn,
-                    ReasonMask: 0xFFFF_FFFF,
-                    ReturnOnlyOnClose: 1, // Only get events when file is closed (finished writing)
-                    Timeout: 0,
-                    BytesToWaitFor: 0,
-                    UsnJournalID: journal_data.UsnJournalID,
-                };
+            if bytes_returned <= 8 {
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
 
-                // Use a Vec<u64> to ensure 8-byte alignment for USN records and avoid large stack allocation
-                let mut buffer = vec![0u64; 1024]; // 1024 * 8 = 8192 bytes
-                let buffer_ptr = buffer.as_mut_ptr().cast::<u8>();
-                let buffer_len = u32::try_from(buffer.len() * 8).unwrap_or(u32::MAX);
-
-                loop {
-                    let mut bytes_returned = 0u32;
-                    let success = DeviceIoControl(
-                        handle,
-                        FSCTL_READ_USN_JOURNAL,
-                        Some(std::ptr::addr_of!(read_data).cast()),
-                        u32::try_from(std::mem::size_of::<READ_USN_JOURNAL_DATA_V0>())
-                            .unwrap_or(u32::MAX),
-                        Some(buffer_ptr.cast()),
-                        buffer_len,
-                        Some(&raw mut bytes_returned),
-                        None,
-                    );
-
-                    if success.is_ok() && bytes_returned >= 8 {
-                        let next_usn = buffer_ptr.cast::<i64>().read_unaligned();
-                        read_data.StartUsn = next_usn;
-
-                        let mut offset = 8;
-                        let record_header_size = std::mem::size_of::<USN_RECORD_V2>();
-
-                        while offset < bytes_returned as usize {
-                            if offset + record_header_size > bytes_returned as usize {
-                                break;
-                            }
+            read_data.StartUsn = unsafe { buffer_ptr.cast::<i64>().read_unaligned() };
 
-                            let record_ptr = buffer_ptr.add(offset);
-                            #[allow(clippy::cast_ptr_alignment)]
-                            let record = &*record_ptr.cast::<USN_RECORD_V2>();
+            let mut offset = 8;
+            let record_header_size = std::mem::size_of::<USN_RECORD_V2>();
+            while offset + record_header_size <= bytes_returned as usize {
+                let record_ptr = unsafe { buffer_ptr.add(offset) };
+                #[allow(clippy::cast_ptr_alignment)]
+                let record = unsafe { &*record_ptr.cast::<USN_RECORD_V2>() };
 
-                            let record_len = record.RecordLength as usize;
-                            if record_len < record_header_size
-                                || offset + record_len > bytes_returned as usize
-                            {
-                                break;
-                            }
+                let record_len = record.RecordLength as usize;
+                if record_len < record_header_size || offset + record_len > bytes_returned as usize
+                {
+                    break;
+                }
+                offset += record_len;
+
+                let is_dir = (record.FileAttributes & FILE_ATTRIBUTE_DIRECTORY.0) != 0;
+                if is_dir
+                    && record.Reason
+                        & (journal::USN_REASON_RENAME_OLD_NAME
+                            | journal::USN_REASON_RENAME_NEW_NAME
+                            | journal::USN_REASON_FILE_DELETE)
+                        != 0
+                {
+                    dir_paths.clear();
+                }
+                let Some(change) = journal::classify(record.Reason, is_dir) else {
+                    continue;
+                };
 
-                            if (record.FileAttributes & FILE_ATTRIBUTE_SYSTEM.0) == 0 {
-                                let name_offset = record.FileNameOffset as usize;
-                                let name_len_bytes = record.FileNameLength as usize;
-
-                                if name_offset + name_len_bytes <= record_len {
-                                    #[allow(clippy::cast_ptr_alignment)]
-                                    let name_ptr = record_ptr.add(name_offset).cast::<u16>();
-                                    let name_len = name_len_bytes / 2;
-                                    let name = String::from_utf16_lossy(
-                                        std::slice::from_raw_parts(name_ptr, name_len),
-                                    );
-
-                                    // Simplified path: In a full impl, we'd use the FRN map.
-                                    // For now, we only support top-level changes or
-                                    // we'd need to keep the fs_map in memory.
-                                    // As a compromise for this prototype, we'll try to get the path
-                                    // by using the parent FRN if we have a way to cache it.
-                                    let mut changed_path = PathBuf::from(&drive_root_str);
-                                    changed_path.push(name);
-
-                                    let action = if (record.Reason & USN_REASON_FILE_DELETE) != 0 {
-                                        crate::watcher::WatcherAction::Remove
-                                    } else {
-                                        crate::watcher::WatcherAction::Index
-                                    };
-
-                                    let _ = tx.blocking_send((changed_path, action));
-                                }
-                            }
+                let name_offset = record.FileNameOffset as usize;
+                let name_len_bytes = record.FileNameLength as usize;
+                if name_offset + name_len_bytes > record_len {
+                    continue;
+                }
+                #[allow(clippy::cast_ptr_alignment)]
+                let name = unsafe {
+                    let name_ptr = record_ptr.add(name_offset).cast::<u16>();
+                    String::from_utf16_lossy(std::slice::from_raw_parts(
+                        name_ptr,
+                        name_len_bytes / 2,
+                    ))
+                };
+                let Some(parent) = (unsafe {
+                    resolve_dir(handle, record.ParentFileReferenceNumber, &mut dir_paths)
+                }) else {
+                    continue;
+                };
+                let path = parent.join(name);
+                // Removals pass whatever the attributes, so files indexed
+                // before they were hidden still drop out
+                let attributes = match change {
+                    journal::JournalChange::Remove => 0,
+                    _ => record.FileAttributes,
+                };
+                if !(watch.accepts)(&path, attributes) {
+                    continue;
+                }
 
-                            offset += record_len;
-                        }
-                    } else {
-                        std::thread::sleep(std::time::Duration::from_millis(500));
+                match change {
+                    journal::JournalChange::Index => {
+                        let _ = watch.tx.blocking_send((path, WatcherAction::Index));
                     }
+                    journal::JournalChange::Remove => {
+                        let _ = watch.tx.blocking_send((path, WatcherAction::Remove));
+                    }
+                    journal::JournalChange::DirectoryMoved => (watch.mark_stale)(&path),
                 }
             }
-        });
+        }
+
+        save(read_data.UsnJournalID, saved_usn);
     }
 }
 
+#[cfg(target_os = "windows")]
+pub use windows_usn::{JournalWatch, journal_volume, watch_journal};
+
 #[cfg(target_os = "macos")]
 mod macos_fsevents {
     use crate::error::Result;
@@ -440,18 +688,124 @@ mod linux_fanotify {
 }
 
 use crate::error::Result;
+use crate::scanner::exclude::ExcludeMatcher;
+use crate::scanner::hidden::HiddenFilter;
 use crate::scanner::{ProgressEvent, ProgressType};
+use crate::settings::IndexRoot;
 use ignore::WalkBuilder;
-use std::path::PathBuf;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+/// Walk depth for roots without their own `max_depth`.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 20;
+
+/// Identity of the directory a path resolves to, so links and junctions that
+/// reach the same directory by different names compare equal.
+#[cfg(unix)]
+fn dir_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+/// Identity of the directory a path resolves to. Canonicalizing resolves
+/// symlinks and junctions alike to the target's path.
+#[cfg(not(unix))]
+fn dir_identity(path: &Path) -> Option<PathBuf> {
+    std::fs::canonicalize(path).ok()
+}
+
+/// Walker configured with the root's profile on top of the global exclude
+/// patterns and ignore-file handling every directory walk shares.
+pub(crate) fn walk_builder(
+    profile: &IndexRoot,
+    exclude_patterns: &[String],
+    use_gitignore: bool,
+) -> WalkBuilder {
+    let root = Path::new(&profile.path);
+    let mut builder = WalkBuilder::new(root);
+
+    // Whitelist overrides only drop files; directories are still descended
+    let mut override_builder = ignore::overrides::OverrideBuilder::new(root);
+    for pattern in &profile.include {
+        if let Err(e) = override_builder.add(pattern) {
+            warn!("Invalid include pattern '{}': {}", pattern, e);
+        }
+    }
+    if let Ok(overrides) = override_builder.build() {
+        builder.overrides(overrides);
+    }
+
+    // Hidden and system files follow the root's own policy, never the
+    // ignore-file setting
+    builder
+        .follow_links(profile.follow_symlinks)
+        .standard_filters(use_gitignore)
+        .git_ignore(use_gitignore)
+        .git_global(use_gitignore)
+        .git_exclude(use_gitignore)
+        .ignore(use_gitignore)
+        .hidden(false);
+    builder.max_depth(Some(profile.max_depth.unwrap_or(DEFAULT_MAX_DEPTH)));
+
+    // The walker only catches links back to an ancestor. Links between
+    // siblings, or several junctions onto one folder, would walk the same
+    // directory again under another name, so each directory is entered once.
+    let visited = Mutex::new(dir_identity(root).into_iter().collect::<HashSet<_>>());
+    // Excluded directories are pruned rather than walked and filtered
+    let excludes = ExcludeMatcher::new(root, exclude_patterns.iter().chain(&profile.exclude));
+    let hidden = HiddenFilter::for_root(profile);
+    builder.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+        if entry.depth() > 0
+            && (excludes.is_excluded(entry.path(), is_dir)
+                || hidden.skips(entry.path(), || entry.metadata().ok()))
+        {
+            return false;
+        }
+        if entry.depth() == 0 || !is_dir {
+            return true;
+        }
+        let Some(identity) = dir_identity(entry.path()) else {
+            return true;
+        };
+        let first_visit = visited.lock().insert(identity);
+        if !first_visit {
+            debug!(
+                "Skipping {}: directory already walked via another path",
+                entry.path().display()
+            );
+        }
+        first_visit
+    });
+    builder
+}
+
+/// Files a scan of `profile` would walk, counted up to `cap` so a huge tree
+/// answers quickly. Extensions aren't checked, so this is an upper bound.
+pub fn estimate_file_count(
+    profile: &IndexRoot,
+    exclude_patterns: &[String],
+    use_gitignore: bool,
+    cap: usize,
+) -> usize {
+    walk_builder(profile, exclude_patterns, use_gitignore)
+        .build()
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .take(cap)
+        .count()
+}
 
 pub trait DriveScanner: Send + Sync {
     #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
-        root: PathBuf,
+        profile: IndexRoot,
         exclude_patterns: Vec<String>,
         use_gitignore: bool,
         path_tx: flume::Sender<PathBuf>,
@@ -477,7 +831,7 @@ impl DriveScanner for DefaultDriveScanner {
     #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
-        root: PathBuf,
+        profile: IndexRoot,
         exclude_patterns: Vec<String>,
         use_gitignore: bool,
         path_tx: flume::Sender<PathBuf>,
@@ -485,30 +839,9 @@ impl DriveScanner for DefaultDriveScanner {
         total_count: Arc<AtomicUsize>,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<()> {
-        let mut builder = WalkBuilder::new(&root);
-
-        let mut override_builder = ignore::overrides::OverrideBuilder::new(&root);
-        for pattern in &exclude_patterns {
-            let ignore_pattern = format!("!{pattern}");
-            if let Err(e) = override_builder.add(&ignore_pattern) {
-                warn!("Invalid exclude pattern '{}': {}", pattern, e);
-            }
-        }
-        if let Ok(overrides) = override_builder.build() {
-            builder.overrides(overrides);
-        }
+        let builder = walk_builder(&profile, &exclude_patterns, use_gitignore);
 
-        builder
-            .follow_links(true)
-            .standard_filters(use_gitignore)
-            .git_ignore(use_gitignore)
-            .git_global(use_gitignore)
-            .git_exclude(use_gitignore)
-            .ignore(use_gitignore)
-            .hidden(!use_gitignore);
-        builder.max_depth(Some(20));
-
-        info!("Starting DefaultDriveScanner for {}", root.display());
+        info!("Starting DefaultDriveScanner for {}", profile.path);
         let walker = builder.build_parallel();
 
         walker.run(|| {
@@ -567,15 +900,19 @@ impl DriveScanner for DefaultDriveScanner {
     }
 }
 
+/// Scans whole NTFS drives from the MFT when `use_mft` is set, and walks
+/// everything else.
 #[cfg(target_os = "windows")]
-pub struct WindowsDriveScanner;
+pub struct WindowsDriveScanner {
+    pub use_mft: bool,
+}
 
 #[cfg(target_os = "windows")]
 impl DriveScanner for WindowsDriveScanner {
     #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
-        root: PathBuf,
+        profile: IndexRoot,
         exclude_patterns: Vec<String>,
         use_gitignore: bool,
         path_tx: flume::Sender<PathBuf>,
@@ -583,59 +920,46 @@ impl DriveScanner for WindowsDriveScanner {
         total_count: Arc<AtomicUsize>,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<()> {
+        let root = PathBuf::from(&profile.path);
         let root_str = root.to_string_lossy();
         let is_unc = root_str.starts_with("\\\\");
         let is_root = root.parent().is_none() || root_str.len() <= 3;
+        // The MFT lists every file on the volume, so profile globs and depth need the walker
+        let is_plain_profile =
+            profile.include.is_empty() && profile.exclude.is_empty() && profile.max_depth.is_none();
 
-        let mut is_local_drive = true;
-        if is_root && !is_unc {
-            let mut chars = root_str.chars();
-            if let (Some(c1), Some(':')) = (chars.next(), chars.next()) {
-                if c1.is_ascii_alphabetic() {
-                    let drive_root = format!("{c1}:\\");
-                    unsafe {
-                        let mut wide_root: Vec<u16> = drive_root.encode_utf16().collect();
-                        wide_root.push(0);
-
-                        let drive_type = windows::Win32::Storage::FileSystem::GetDriveTypeW(
-                            windows::core::PCWSTR(wide_root.as_ptr()),
-                        );
-
-                        if drive_type == 3 {
-                            // DRIVE_REMOTE
-                            is_local_drive = false;
-                        }
-                    }
-                } else {
-                    is_local_drive = false;
-                }
-            } else {
-                is_local_drive = false;
-            }
-        }
-
-        if is_root && !is_unc && is_local_drive && root.exists() {
+        if is_unc || crate::scanner::network::is_network_path(&root) {
             info!(
-                "Whole local drive detected, attempting MFT scan for {:?}",
+                "Network/Remote drive detected, using parallel fallback scanner for {:?}",
                 root
             );
-            if let Err(e) =
-                windows_usn::scan_volume(&root, &path_tx, progress_tx.as_ref(), &total_count)
-            {
-                warn!("MFT scan failed, falling back to parallel walk: {}", e);
-            } else {
-                return Ok(());
-            }
-        } else if is_unc || !is_local_drive {
+        } else if self.use_mft
+            && is_root
+            && is_plain_profile
+            && windows_usn::journal_volume(&root).is_some()
+        {
             info!(
-                "Network/Remote drive detected, using parallel fallback scanner for {:?}",
+                "Whole local drive detected, attempting MFT scan for {:?}",
                 root
             );
+            let excludes = ExcludeMatcher::new(&root, &exclude_patterns);
+            match windows_usn::scan_volume(
+                &root,
+                &excludes,
+                HiddenFilter::for_root(&profile),
+                &path_tx,
+                progress_tx.as_ref(),
+                &total_count,
+                &cancel_flag,
+            ) {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!("MFT scan failed, falling back to parallel walk: {}", e),
+            }
         }
 
         let fallback = DefaultDriveScanner;
         fallback.scan(
-            root,
+            profile,
             exclude_patterns,
             use_gitignore,
             path_tx,
@@ -644,49 +968,6 @@ impl DriveScanner for WindowsDriveScanner {
             cancel_flag,
         )
     }
-
-    fn watch(
-        &self,
-        root: PathBuf,
-        tx: tokio::sync::mpsc::Sender<(PathBuf, crate::watcher::WatcherAction)>,
-    ) -> Result<()> {
-        let root_str = root.to_string_lossy();
-        let is_unc = root_str.starts_with("\\\\");
-        let is_root = root.parent().is_none() || root_str.len() <= 3;
-
-        let mut is_local_drive = true;
-        if is_root && !is_unc {
-            let mut chars = root_str.chars();
-            if let (Some(c1), Some(':')) = (chars.next(), chars.next()) {
-                if c1.is_ascii_alphabetic() {
-                    let drive_root = format!("{c1}:\\");
-                    unsafe {
-                        let mut wide_root: Vec<u16> = drive_root.encode_utf16().collect();
-                        wide_root.push(0);
-
-                        let drive_type = windows::Win32::Storage::FileSystem::GetDriveTypeW(
-                            windows::core::PCWSTR(wide_root.as_ptr()),
-                        );
-
-                        if drive_type == 3 {
-                            // DRIVE_REMOTE
-                            is_local_drive = false;
-                        }
-                    }
-                } else {
-                    is_local_drive = false;
-                }
-            } else {
-                is_local_drive = false;
-            }
-        }
-
-        if is_root && !is_unc && is_local_drive && root.exists() {
-            windows_usn::watch_volume(&root, tx);
-        }
-
-        Ok(())
-    }
 }
 
 #[cfg(target_os = "macos")]
@@ -697,7 +978,7 @@ impl DriveScanner for MacDriveScanner {
     #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
-        root: PathBuf,
+        profile: IndexRoot,
         exclude_patterns: Vec<String>,
         use_gitignore: bool,
         path_tx: flume::Sender<PathBuf>,
@@ -705,10 +986,10 @@ impl DriveScanner for MacDriveScanner {
         total_count: Arc<AtomicUsize>,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<()> {
-        let _ = macos_fsevents::scan_volume(&root);
+        let _ = macos_fsevents::scan_volume(Path::new(&profile.path));
         let fallback = DefaultDriveScanner;
         fallback.scan(
-            root,
+            profile,
             exclude_patterns,
             use_gitignore,
             path_tx,
@@ -727,7 +1008,7 @@ impl DriveScanner for LinuxDriveScanner {
     #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
-        root: PathBuf,
+        profile: IndexRoot,
         exclude_patterns: Vec<String>,
         use_gitignore: bool,
         path_tx: flume::Sender<PathBuf>,
@@ -735,10 +1016,10 @@ impl DriveScanner for LinuxDriveScanner {
         total_count: Arc<AtomicUsize>,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<()> {
-        let _ = linux_fanotify::scan_volume(&root);
+        let _ = linux_fanotify::scan_volume(Path::new(&profile.path));
         let fallback = DefaultDriveScanner;
         fallback.scan(
-            root,
+            profile,
             exclude_patterns,
             use_gitignore,
             path_tx,
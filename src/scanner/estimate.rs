@@ -0,0 +1,137 @@
+//! Dry run of a scan: walks a root with the same rules a scan applies and
+//! adds up what would be parsed, without reading any file's content.
+
+use super::drive_scanner::walk_builder;
+use crate::indexer::category::FileCategory;
+use crate::models::{CategoryEstimate, ScanEstimate};
+use crate::settings::{AppSettings, IndexRoot};
+use std::collections::HashMap;
+
+/// Files a parser thread gets through per second, averaged over typical
+/// document mixes; small text files go faster, scanned PDFs far slower.
+const FILES_PER_SECOND_PER_THREAD: u64 = 20;
+
+/// Content a parser thread reads per second on top of the per-file cost
+const BYTES_PER_SECOND_PER_THREAD: u64 = 8 * 1024 * 1024;
+
+/// Share of a file's size that ends up in the search index, in percent.
+/// Plain text is stored nearly whole; containers and media mostly aren't text.
+const fn index_share_percent(category: FileCategory) -> u64 {
+    match category {
+        FileCategory::Document | FileCategory::Code | FileCategory::Data | FileCategory::Email => {
+            40
+        }
+        FileCategory::Spreadsheet | FileCategory::Presentation | FileCategory::Ebook => 15,
+        FileCategory::Image | FileCategory::Audio | FileCategory::Video | FileCategory::Archive => {
+            2
+        }
+        FileCategory::Other => 25,
+    }
+}
+
+/// Walks `profile` as a scan would and estimates the work, stopping after
+/// `cap` files.
+pub fn estimate_scan(profile: &IndexRoot, settings: &AppSettings, cap: usize) -> ScanEstimate {
+    let allowed = settings.get_allowed_extensions();
+    let limit_bytes = u64::from(settings.index_file_size_limit_mb) * 1024 * 1024;
+    let mut estimate = ScanEstimate {
+        root: profile.path.clone(),
+        ..ScanEstimate::default()
+    };
+    let mut categories: HashMap<FileCategory, (usize, u64)> = HashMap::new();
+
+    let walk = walk_builder(profile, &settings.exclude_list(), settings.use_gitignore).build();
+    for entry in walk.filter_map(std::result::Result::ok) {
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        if estimate.files_seen == cap {
+            estimate.truncated = true;
+            break;
+        }
+        estimate.files_seen += 1;
+        if !profile.indexes_content() {
+            continue;
+        }
+        let Some(ext) = entry
+            .path()
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .filter(|ext| allowed.contains(ext))
+        else {
+            continue;
+        };
+        let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+        if size > limit_bytes {
+            estimate.too_large += 1;
+            continue;
+        }
+        estimate.indexable_files += 1;
+        estimate.indexable_bytes += size;
+        let totals = categories
+            .entry(FileCategory::classify(&ext).0)
+            .or_default();
+        totals.0 += 1;
+        totals.1 += size;
+    }
+
+    estimate.estimated_index_bytes = categories
+        .iter()
+        .map(|(category, (_, bytes))| bytes * index_share_percent(*category) / 100)
+        .sum();
+    let threads = u64::from(settings.indexing_threads.max(1));
+    estimate.estimated_secs = (estimate.indexable_files as u64)
+        .div_ceil(FILES_PER_SECOND_PER_THREAD * threads)
+        + estimate
+            .indexable_bytes
+            .div_ceil(BYTES_PER_SECOND_PER_THREAD * threads);
+    estimate.by_category = categories
+        .into_iter()
+        .map(|(category, (files, bytes))| CategoryEstimate {
+            category,
+            files,
+            bytes,
+        })
+        .collect();
+    estimate
+        .by_category
+        .sort_by(|a, b| b.files.cmp(&a.files).then(b.bytes.cmp(&a.bytes)));
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_estimate_counts_what_a_scan_parses() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello world").unwrap();
+        std::fs::write(dir.path().join("b.md"), "# notes").unwrap();
+        std::fs::write(dir.path().join("c.csv"), "x,y\n1,2\n").unwrap();
+        std::fs::write(dir.path().join("blob.unknownext"), [0u8; 64]).unwrap();
+
+        let settings = AppSettings::default();
+        let profile = IndexRoot::new(dir.path().to_string_lossy());
+        let estimate = estimate_scan(&profile, &settings, 1000);
+        assert_eq!(estimate.files_seen, 4);
+        assert_eq!(estimate.indexable_files, 3);
+        assert_eq!(estimate.indexable_bytes, 11 + 7 + 8);
+        assert_eq!(estimate.by_category[0].category, FileCategory::Document);
+        assert_eq!(estimate.by_category[0].files, 2);
+        assert!(!estimate.truncated);
+
+        let capped = estimate_scan(&profile, &settings, 2);
+        assert_eq!(capped.files_seen, 2);
+        assert!(capped.truncated);
+
+        let names_only = IndexRoot {
+            index_content: false,
+            ..profile
+        };
+        let estimate = estimate_scan(&names_only, &settings, 1000);
+        assert_eq!((estimate.files_seen, estimate.indexable_files), (4, 0));
+    }
+}
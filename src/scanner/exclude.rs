@@ -0,0 +1,86 @@
+//! Exclude rules shared by the directory walker, the MFT reader and live
+//! watcher events, so a file skipped by a scan is never indexed by an event.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Exclude patterns compiled for one root. Patterns use gitignore syntax:
+/// `node_modules/` skips directories of that name at any depth, `*.tmp`
+/// skips matching files. Absolute paths skip that folder and everything in it.
+#[derive(Debug, Clone)]
+pub struct ExcludeMatcher {
+    root: PathBuf,
+    gitignore: Gitignore,
+    folders: Vec<PathBuf>,
+}
+
+impl ExcludeMatcher {
+    #[must_use]
+    pub fn new<'a>(root: &Path, patterns: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let mut folders = Vec::new();
+        for pattern in patterns {
+            let trimmed = pattern.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if Path::new(trimmed).is_absolute() {
+                folders.push(PathBuf::from(trimmed));
+            } else if let Err(e) = builder.add_line(None, trimmed) {
+                warn!("Invalid exclude pattern '{}': {}", pattern, e);
+            }
+        }
+        let gitignore = builder.build().unwrap_or_else(|e| {
+            warn!("Failed to compile exclude patterns: {}", e);
+            Gitignore::empty()
+        });
+
+        Self {
+            root: root.to_path_buf(),
+            gitignore,
+            folders,
+        }
+    }
+
+    /// Whether `path`, or any directory above it up to the root, is excluded.
+    #[must_use]
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.folders.iter().any(|folder| path.starts_with(folder)) {
+            return true;
+        }
+        path.starts_with(&self.root)
+            && path != self.root
+            && self
+                .gitignore
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_match_names_directories_and_folders() {
+        let patterns = [
+            "node_modules/".to_string(),
+            "*.tmp".to_string(),
+            "$RECYCLE.BIN".to_string(),
+            "/data/src/vendor".to_string(),
+        ];
+        let matcher = ExcludeMatcher::new(Path::new("/data/src"), &patterns);
+
+        assert!(matcher.is_excluded(Path::new("/data/src/app/node_modules/x/index.js"), false));
+        assert!(matcher.is_excluded(Path::new("/data/src/app/node_modules"), true));
+        assert!(matcher.is_excluded(Path::new("/data/src/notes.tmp"), false));
+        assert!(matcher.is_excluded(Path::new("/data/src/$RECYCLE.BIN/a.txt"), false));
+        assert!(matcher.is_excluded(Path::new("/data/src/vendor/lib.rs"), false));
+
+        assert!(!matcher.is_excluded(Path::new("/data/src/app/main.rs"), false));
+        // A file named like a directory-only pattern is kept
+        assert!(!matcher.is_excluded(Path::new("/data/src/node_modules"), false));
+        assert!(!matcher.is_excluded(Path::new("/elsewhere/notes.tmp"), false));
+    }
+}
@@ -0,0 +1,104 @@
+//! Hidden and system file rules of a root, shared by scans and live watcher
+//! events so both leave out the same files. Hidden means a name starting
+//! with a dot on Unix and the hidden attribute on Windows; only Windows marks
+//! files as system files.
+
+use crate::settings::IndexRoot;
+use std::path::Path;
+
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HiddenFilter {
+    include_hidden: bool,
+    include_system: bool,
+}
+
+impl HiddenFilter {
+    #[must_use]
+    pub const fn new(include_hidden: bool, include_system: bool) -> Self {
+        Self {
+            include_hidden,
+            include_system,
+        }
+    }
+
+    #[must_use]
+    pub const fn for_root(root: &IndexRoot) -> Self {
+        Self::new(root.include_hidden, root.include_system)
+    }
+
+    /// Whether Windows file attributes, as the change journal reports them,
+    /// mark a file this filter leaves out.
+    #[must_use]
+    pub const fn skips_attributes(self, attributes: u32) -> bool {
+        (!self.include_hidden && attributes & FILE_ATTRIBUTE_HIDDEN != 0)
+            || (!self.include_system && attributes & FILE_ATTRIBUTE_SYSTEM != 0)
+    }
+
+    /// Whether the file or folder at `path` is left out. `metadata` is only
+    /// asked for where the attributes live in it, on Windows.
+    pub fn skips(self, path: &Path, metadata: impl FnOnce() -> Option<std::fs::Metadata>) -> bool {
+        if self.include_hidden && self.include_system {
+            return false;
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::MetadataExt;
+            let _ = path;
+            metadata().is_some_and(|m| self.skips_attributes(m.file_attributes()))
+        }
+        #[cfg(not(windows))]
+        {
+            let _ = metadata;
+            !self.include_hidden
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.'))
+        }
+    }
+
+    /// Whether `path`, or a folder between it and `root`, is left out, as a
+    /// walk of `root` would have pruned it.
+    pub fn skips_under(self, root: &Path, path: &Path) -> bool {
+        if self.include_hidden && self.include_system {
+            return false;
+        }
+        path.ancestors()
+            .take_while(|dir| *dir != root && dir.starts_with(root))
+            .any(|dir| self.skips(dir, || std::fs::symlink_metadata(dir).ok()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_follow_policy() {
+        let skip_all = HiddenFilter::new(false, false);
+        assert!(skip_all.skips_attributes(FILE_ATTRIBUTE_HIDDEN));
+        assert!(skip_all.skips_attributes(FILE_ATTRIBUTE_SYSTEM | 0x20));
+        assert!(!skip_all.skips_attributes(0x20));
+
+        let hidden_only = HiddenFilter::new(true, false);
+        assert!(!hidden_only.skips_attributes(FILE_ATTRIBUTE_HIDDEN));
+        assert!(hidden_only.skips_attributes(FILE_ATTRIBUTE_SYSTEM));
+        assert!(!HiddenFilter::new(true, true).skips_attributes(0x6));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dot_folders_hide_what_is_under_them() {
+        let root = Path::new("/home/me");
+        let filter = HiddenFilter::new(false, false);
+        assert!(filter.skips_under(root, Path::new("/home/me/.config/app/settings.toml")));
+        assert!(filter.skips_under(root, Path::new("/home/me/docs/.notes.txt")));
+        assert!(!filter.skips_under(root, Path::new("/home/me/docs/notes.txt")));
+        // Only what lies below the root counts
+        assert!(!filter.skips_under(Path::new("/home/.me"), Path::new("/home/.me/notes.txt")));
+        assert!(!HiddenFilter::new(true, false).skips_under(root, Path::new("/home/me/.bashrc")));
+    }
+}
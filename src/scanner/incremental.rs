@@ -0,0 +1,219 @@
+use super::drive_scanner::walk_builder;
+use super::{ProgressEvent, ProgressType};
+use crate::settings::IndexRoot;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// What an incremental walk found, applied once the changed files are indexed.
+#[derive(Debug, Default)]
+pub struct DirChanges {
+    /// Every directory visited, with its current mtime
+    pub visited: Vec<(String, u64)>,
+    /// Directories whose mtime differs from the recorded one, or that were never recorded
+    pub changed: Vec<PathBuf>,
+    /// Recorded directories that no longer exist (or are now excluded)
+    pub removed: Vec<String>,
+}
+
+/// Nanosecond mtime, so an entry added within the same second as the last
+/// refresh still registers on filesystems that record sub-second times.
+fn mtime_nanos(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX))
+}
+
+/// Walks the root of `profile`, sends every file, and reports the directories
+/// whose mtime differs from `known`.
+///
+/// No subtree is skipped: a file rewritten in place leaves its directory's
+/// mtime untouched, so each file is sent and the indexer's mtime and size
+/// check decides what to parse again. Directory mtimes only move when entries
+/// are created, deleted or renamed, and are used to find deleted files
+/// without checking every one.
+#[allow(clippy::too_many_arguments)]
+pub fn walk_with_dir_changes(
+    profile: &IndexRoot,
+    exclude_patterns: &[String],
+    use_gitignore: bool,
+    known: &HashMap<String, u64>,
+    path_tx: &flume::Sender<PathBuf>,
+    progress_tx: Option<&flume::Sender<ProgressEvent>>,
+    total_count: &AtomicUsize,
+    cancel_flag: &AtomicBool,
+) -> DirChanges {
+    let mut changes = DirChanges::default();
+
+    for entry in walk_builder(profile, exclude_patterns, use_gitignore).build() {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return changes;
+        }
+
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path().to_string_lossy().to_string();
+            let mtime = mtime_nanos(&meta);
+            if known.get(&path) != Some(&mtime) {
+                changes.changed.push(entry.path().to_path_buf());
+            }
+            changes.visited.push((path, mtime));
+        } else if file_type.is_file() {
+            let _ = path_tx.send(entry.path().to_path_buf());
+            let count = total_count.fetch_add(1, Ordering::Relaxed);
+
+            if count.is_multiple_of(100)
+                && let Some(tx) = progress_tx
+            {
+                let _ = tx.try_send(ProgressEvent {
+                    ptype: ProgressType::Filename,
+                    current_file: entry.file_name().to_string_lossy().to_string(),
+                    current_folder: String::new(),
+                    processed: count,
+                    total: 0,
+                    status: "Checking for changes...".to_string(),
+                    eta_seconds: 0,
+                    files_per_second: 0.0,
+                });
+            }
+        }
+    }
+
+    let visited: HashSet<&str> = changes.visited.iter().map(|(p, _)| p.as_str()).collect();
+    changes.removed = known
+        .keys()
+        .filter(|dir| !visited.contains(dir.as_str()))
+        .cloned()
+        .collect();
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk(root: &Path, known: &HashMap<String, u64>) -> (DirChanges, Vec<PathBuf>) {
+        walk_profile(&IndexRoot::new(root.to_string_lossy()), known)
+    }
+
+    fn walk_profile(
+        profile: &IndexRoot,
+        known: &HashMap<String, u64>,
+    ) -> (DirChanges, Vec<PathBuf>) {
+        let (tx, rx) = flume::unbounded();
+        let changes = walk_with_dir_changes(
+            profile,
+            &[],
+            false,
+            known,
+            &tx,
+            None,
+            &AtomicUsize::new(0),
+            &AtomicBool::new(false),
+        );
+        drop(tx);
+        (changes, rx.into_iter().collect())
+    }
+
+    #[test]
+    fn test_every_file_is_sent_and_changed_dirs_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(sub.join("b.txt"), "b").unwrap();
+
+        let (first, files) = walk(dir.path(), &HashMap::new());
+        assert_eq!(first.changed.len(), 2);
+        assert_eq!(files.len(), 2);
+
+        // Files in unchanged directories are still sent, so edits made in
+        // place reach the indexer's mtime and size check
+        let mut known: HashMap<String, u64> = first.visited.into_iter().collect();
+        let (second, files) = walk(dir.path(), &known);
+        assert!(second.changed.is_empty());
+        assert_eq!(files.len(), 2);
+
+        // A recorded directory that is gone is reported, and so is one whose
+        // mtime moved
+        let gone = dir.path().join("gone").to_string_lossy().to_string();
+        known.insert(gone.clone(), 1);
+        known.insert(sub.to_string_lossy().to_string(), 0);
+        let (third, _) = walk(dir.path(), &known);
+        assert_eq!(third.removed, vec![gone]);
+        assert_eq!(third.changed, vec![sub]);
+    }
+
+    #[test]
+    fn test_profile_limits_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(dir.path().join("a.md"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("skip.md"), "s").unwrap();
+        std::fs::write(sub.join("c.md"), "c").unwrap();
+
+        let profile = IndexRoot {
+            include: vec!["*.md".to_string()],
+            exclude: vec!["skip.*".to_string()],
+            max_depth: Some(1),
+            ..IndexRoot::new(dir.path().to_string_lossy())
+        };
+        let (_, files) = walk_profile(&profile, &HashMap::new());
+        assert_eq!(files, vec![dir.path().join("a.md")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hidden_files_follow_root_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let hidden_dir = dir.path().join(".cache");
+        std::fs::create_dir(&hidden_dir).unwrap();
+        std::fs::write(hidden_dir.join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join(".b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("c.txt"), "c").unwrap();
+
+        let (_, files) = walk(dir.path(), &HashMap::new());
+        assert_eq!(files.len(), 3);
+
+        let skip_hidden = IndexRoot {
+            include_hidden: false,
+            ..IndexRoot::new(dir.path().to_string_lossy())
+        };
+        let (_, files) = walk_profile(&skip_hidden, &HashMap::new());
+        assert_eq!(files, vec![dir.path().join("c.txt")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_linked_directories_are_walked_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::write(real.join("a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("alias")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), real.join("loop")).unwrap();
+
+        let (_, files) = walk(dir.path(), &HashMap::new());
+        assert_eq!(files.len(), 1);
+
+        let no_links = IndexRoot {
+            follow_symlinks: false,
+            ..IndexRoot::new(dir.path().to_string_lossy())
+        };
+        let (_, files) = walk_profile(&no_links, &HashMap::new());
+        assert_eq!(files, vec![real.join("a.txt")]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Platform-independent handling of NTFS change journal (USN) records. The
+//! Windows reader in `drive_scanner` decodes the raw records and defers to
+//! these rules for what each one means to the index.
+
+use crate::metadata::UsnPosition;
+
+pub const USN_REASON_DATA_OVERWRITE: u32 = 0x0000_0001;
+pub const USN_REASON_DATA_EXTEND: u32 = 0x0000_0002;
+pub const USN_REASON_DATA_TRUNCATION: u32 = 0x0000_0004;
+pub const USN_REASON_FILE_CREATE: u32 = 0x0000_0100;
+pub const USN_REASON_FILE_DELETE: u32 = 0x0000_0200;
+pub const USN_REASON_RENAME_OLD_NAME: u32 = 0x0000_1000;
+pub const USN_REASON_RENAME_NEW_NAME: u32 = 0x0000_2000;
+pub const USN_REASON_CLOSE: u32 = 0x8000_0000;
+
+const CONTENT_CHANGED: u32 = USN_REASON_DATA_OVERWRITE
+    | USN_REASON_DATA_EXTEND
+    | USN_REASON_DATA_TRUNCATION
+    | USN_REASON_FILE_CREATE;
+
+/// What a journal record asks of the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalChange {
+    Index,
+    Remove,
+    /// A directory was renamed or moved; its files were not journaled
+    /// individually, so the roots on either side need a refresh.
+    DirectoryMoved,
+}
+
+/// Maps a record's reason flags to an index change. Writes are only acted on
+/// once the handle closes, so a file being saved is indexed once.
+#[must_use]
+pub const fn classify(reason: u32, is_dir: bool) -> Option<JournalChange> {
+    if is_dir {
+        return if reason & USN_REASON_FILE_DELETE != 0 {
+            // The files inside were journaled first, but the subtree goes in one batch
+            Some(JournalChange::Remove)
+        } else if reason & (USN_REASON_RENAME_OLD_NAME | USN_REASON_RENAME_NEW_NAME) != 0 {
+            Some(JournalChange::DirectoryMoved)
+        } else {
+            None
+        };
+    }
+    if reason & (USN_REASON_FILE_DELETE | USN_REASON_RENAME_OLD_NAME) != 0 {
+        Some(JournalChange::Remove)
+    } else if reason & USN_REASON_RENAME_NEW_NAME != 0
+        || (reason & USN_REASON_CLOSE != 0 && reason & CONTENT_CHANGED != 0)
+    {
+        Some(JournalChange::Index)
+    } else {
+        None
+    }
+}
+
+/// Where to start reading a journal whose live range is
+/// `first_usn..next_usn`, and whether records were lost since `saved`: the
+/// journal was re-created, or wrapped past the saved position while the app
+/// was closed. Without a saved position reading starts at the live tail.
+#[must_use]
+pub fn resume_point(
+    saved: Option<UsnPosition>,
+    journal_id: u64,
+    first_usn: i64,
+    next_usn: i64,
+) -> (i64, bool) {
+    match saved {
+        Some(pos) if pos.journal_id == journal_id && pos.next_usn >= first_usn => {
+            (pos.next_usn.min(next_usn), false)
+        }
+        Some(_) => (next_usn, true),
+        None => (next_usn, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_records() {
+        let saved = USN_REASON_DATA_EXTEND | USN_REASON_CLOSE;
+        assert_eq!(classify(saved, false), Some(JournalChange::Index));
+        assert_eq!(classify(USN_REASON_DATA_EXTEND, false), None);
+        assert_eq!(
+            classify(USN_REASON_FILE_DELETE | USN_REASON_CLOSE, false),
+            Some(JournalChange::Remove)
+        );
+        assert_eq!(
+            classify(USN_REASON_RENAME_OLD_NAME, false),
+            Some(JournalChange::Remove)
+        );
+        assert_eq!(
+            classify(USN_REASON_RENAME_NEW_NAME, false),
+            Some(JournalChange::Index)
+        );
+        assert_eq!(
+            classify(USN_REASON_RENAME_NEW_NAME, true),
+            Some(JournalChange::DirectoryMoved)
+        );
+        assert_eq!(
+            classify(USN_REASON_RENAME_OLD_NAME, true),
+            Some(JournalChange::DirectoryMoved)
+        );
+        assert_eq!(
+            classify(USN_REASON_FILE_CREATE | USN_REASON_CLOSE, true),
+            None
+        );
+        assert_eq!(
+            classify(USN_REASON_FILE_DELETE | USN_REASON_CLOSE, true),
+            Some(JournalChange::Remove)
+        );
+    }
+
+    #[test]
+    fn test_resume_point() {
+        let saved = UsnPosition {
+            journal_id: 7,
+            next_usn: 500,
+        };
+        assert_eq!(resume_point(Some(saved), 7, 100, 900), (500, false));
+        // Journal wrapped past the saved USN while the app was closed
+        assert_eq!(resume_point(Some(saved), 7, 600, 900), (900, true));
+        // Journal was deleted and re-created
+        assert_eq!(resume_point(Some(saved), 8, 0, 900), (900, true));
+        assert_eq!(resume_point(None, 7, 100, 900), (900, false));
+    }
+}
@@ -1,21 +1,41 @@
+pub mod access;
+pub mod cloud;
 pub mod drive_scanner;
-
-use crate::error::Result;
+pub mod estimate;
+pub mod exclude;
+pub mod hidden;
+mod incremental;
+pub mod journal;
+pub mod network;
+mod pipeline;
+mod progress;
+pub mod remote;
+pub mod sensitive;
+pub mod volumes;
+
+use crate::error::{FlashError, Result};
 use crate::indexer::IndexManager;
-use crate::metadata::MetadataDb;
-use crate::parsers::{ParsedDocument, parse_file};
+use crate::indexer::filename_index::{EntryKind, FilenameEntry};
+use crate::metadata::{FileAttributes, MetadataDb, ParseErrorKind};
+use crate::models::ScanOperation;
+use crate::parsers::{ParsedDocument, parse_file_isolated, parse_files_batch_isolated};
+use crate::settings::IndexRoot;
+use crate::system::activity::{self, Activity, SkipReason, Source};
 use drive_scanner::DriveScanner;
-use std::path::PathBuf;
+use pipeline::{DocumentPermit, PipelineBudget, PipelineMetrics};
+pub use progress::{ProgressAggregator, ScanProgress};
+use sensitive::SensitiveMatcher;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use std::time::Instant;
 use tokio::sync::mpsc;
 use tracing::{info, instrument, warn};
 
-fn get_file_hash(path: &std::path::Path) -> [u8; 32] {
+pub(crate) fn get_file_hash(path: &std::path::Path) -> [u8; 32] {
     let mut hasher = blake3::Hasher::new();
-    std::fs::File::open(path).map_or_else(
+    access::open(path).map_or_else(
         |_| blake3::hash(path.to_string_lossy().as_bytes()).into(),
         |mut file| {
             use std::io::Read;
@@ -31,7 +51,7 @@ fn get_file_hash(path: &std::path::Path) -> [u8; 32] {
     )
 }
 
-#[derive(Clone, Debug, serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
 pub enum ProgressType {
     Content,
     Filename,
@@ -51,44 +71,301 @@ pub struct ProgressEvent {
 
 const BATCH_SIZE: usize = 5000;
 
+/// Paths the filter stats and checks against the metadata store at once
+const CHUNK_SIZE: usize = 200;
+
+/// Downloaded remote files waiting for the parser
+const REMOTE_QUEUE: usize = 8;
+
+/// How often paused stages re-check the pause and cancel flags.
+const PAUSE_POLL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Blocks a sync stage while the scan is paused, returning early on cancel.
+fn wait_while_paused(paused: &AtomicBool, cancel_flag: &AtomicBool) {
+    while paused.load(Ordering::Relaxed) && !cancel_flag.load(Ordering::Relaxed) {
+        std::thread::sleep(PAUSE_POLL);
+    }
+}
+
+/// Async counterpart of [`wait_while_paused`] for the parser stage.
+async fn wait_while_paused_async(paused: &AtomicBool, cancel_flag: &AtomicBool) {
+    while paused.load(Ordering::Relaxed) && !cancel_flag.load(Ordering::Relaxed) {
+        tokio::time::sleep(PAUSE_POLL).await;
+    }
+}
+
+fn offline_error(root: &Path) -> FlashError {
+    FlashError::index(format!("{} is offline", root.display()))
+}
+
+/// A parse failure on an unreachable share says nothing about the file.
+fn is_real_failure(root: &Path, is_network_root: bool) -> bool {
+    !is_network_root || network::is_reachable(root)
+}
+
+/// Forwards walked paths to the content pipeline while collecting their
+/// names, along with one entry for each folder that holds any of them, then
+/// replaces the root's entries in the filename index. A cancelled
+/// walk is incomplete, so its names are only added, never replacing. Paths the
+/// pipeline can't take yet are held back instead of stalling the walk; their
+/// names are kept for the filename index anyway. Sensitive files that are
+/// skipped get no entry; the pipeline drops them itself.
+fn tee_filenames(
+    walk_rx: &flume::Receiver<PathBuf>,
+    path_tx: &flume::Sender<PathBuf>,
+    filename_index: &crate::indexer::filename_index::FilenameIndex,
+    root: &Path,
+    sensitive: &SensitiveMatcher,
+    cancel_flag: &AtomicBool,
+) {
+    let start = Instant::now();
+    let mut entries = Vec::new();
+    let mut directories = std::collections::HashSet::new();
+    let mut held_back = std::collections::VecDeque::new();
+    for path in walk_rx {
+        if !sensitive.skips(&path) {
+            entries.extend(FilenameEntry::new(&path, EntryKind::File));
+            for dir in path.ancestors().skip(1) {
+                if !dir.starts_with(root) || !directories.insert(dir.to_path_buf()) {
+                    break;
+                }
+                entries.extend(FilenameEntry::new(dir, EntryKind::Directory));
+            }
+        }
+        held_back.push_back(path);
+        while let Some(path) = held_back.pop_front() {
+            match path_tx.try_send(path) {
+                Ok(()) => {}
+                Err(flume::TrySendError::Full(path)) => {
+                    held_back.push_front(path);
+                    break;
+                }
+                Err(flume::TrySendError::Disconnected(_)) => {
+                    held_back.clear();
+                    break;
+                }
+            }
+        }
+    }
+
+    let count = entries.len();
+    let published = if cancel_flag.load(Ordering::Relaxed) {
+        filename_index
+            .add_files_batch(entries)
+            .and_then(|()| filename_index.commit())
+    } else {
+        filename_index.replace_root(root, entries)
+    };
+    match published {
+        Ok(()) => info!(
+            "Filename index updated with {} files under {} in {:.2}s",
+            count,
+            root.display(),
+            start.elapsed().as_secs_f64()
+        ),
+        Err(e) => warn!("Failed to update filename index: {}", e),
+    }
+
+    for path in held_back {
+        if path_tx.send(path).is_err() {
+            break;
+        }
+    }
+}
+
+/// Records in the activity log that `path` was found but not indexed.
+fn skipped(path: &Path, reason: SkipReason) {
+    activity::record(|| Activity::Skipped {
+        path: path.to_string_lossy().to_string(),
+        reason,
+    });
+}
+
+/// Batches added to the search index but not yet committed. Commits follow
+/// the index's commit pacing rather than every batch, which keeps segments
+/// from piling up. Each batch is journaled before it is added, and its
+/// metadata is only written and its journal entry dropped after the commit,
+/// so a crash in between is rolled back at the next start.
+#[derive(Default)]
+struct UncommittedBatches {
+    journal: Vec<u64>,
+    meta: Vec<(String, u64, u64, [u8; 32], FileAttributes)>,
+}
+
+impl UncommittedBatches {
+    fn add(
+        &mut self,
+        indexer: &IndexManager,
+        metadata_db: &MetadataDb,
+        docs: &[(ParsedDocument, u64, u64)],
+        meta: Vec<(String, u64, u64, [u8; 32], FileAttributes)>,
+    ) {
+        let paths: Vec<String> = meta.iter().map(|(path, ..)| path.clone()).collect();
+        // A failed batch keeps its journal entry and is replayed at the next start
+        let journal = metadata_db.begin_batch(&paths, &[]);
+        match (journal, indexer.add_documents_batch(docs)) {
+            (Ok(id), Ok(())) => {
+                self.journal.push(id);
+                self.meta.extend(meta);
+            }
+            (_, Err(e)) => warn!("Failed to write a batch of {} files: {}", paths.len(), e),
+            (Err(e), Ok(())) => {
+                warn!("Failed to journal a batch of {} files: {}", paths.len(), e);
+                self.meta.extend(meta);
+            }
+        }
+    }
+
+    /// Commits if the pacing says so, or unconditionally with `force`, then
+    /// records the metadata of everything committed.
+    fn commit(
+        &mut self,
+        indexer: &IndexManager,
+        metadata_db: &MetadataDb,
+        checkpoint_root: &Path,
+        force: bool,
+    ) {
+        if self.journal.is_empty() && self.meta.is_empty() {
+            return;
+        }
+        let committed = if force {
+            indexer.commit().map(|()| true)
+        } else {
+            indexer.commit_if_due()
+        };
+        match committed {
+            Ok(false) => return,
+            Ok(true) => match metadata_db.batch_update_metadata(&self.meta) {
+                Ok(_) => {
+                    let _ = metadata_db.finish_batches(&self.journal);
+                    let _ =
+                        metadata_db.record_scan_progress(checkpoint_root, self.meta.len() as u64);
+                    for (path, ..) in &self.meta {
+                        activity::record(|| Activity::Indexed {
+                            path: path.clone(),
+                            source: Source::Scan,
+                        });
+                    }
+                }
+                Err(e) => warn!("Failed to record {} indexed files: {}", self.meta.len(), e),
+            },
+            // The journal entries stay behind and are replayed at the next start
+            Err(e) => warn!("Failed to commit {} files: {}", self.meta.len(), e),
+        }
+        self.journal.clear();
+        self.meta.clear();
+    }
+}
+
 #[derive(Debug)]
 struct IndexTask {
     doc: ParsedDocument,
     modified: u64,
     size: u64,
     content_hash: [u8; 32],
+    /// Share of the pipeline budget, returned once the writer has added the doc
+    permit: Option<DocumentPermit>,
+}
+
+/// A changed file of a remote root, ready for the parser.
+struct RemoteFile {
+    /// URL the document is stored under
+    location: String,
+    modified: u64,
+    size: u64,
+    /// Local copy of the contents and their hash; `None` indexes the name only
+    copy: Option<(tempfile::NamedTempFile, [u8; 32])>,
+}
+
+/// Hands a parsed document to the writer once the pipeline budget has room
+/// for it.
+async fn send_task(
+    task_tx: &flume::Sender<IndexTask>,
+    metrics: &PipelineMetrics,
+    doc: ParsedDocument,
+    modified: u64,
+    size: u64,
+    content_hash: [u8; 32],
+) {
+    let permit = metrics.reserve(doc.text_len()).await;
+    let _ = task_tx
+        .send_async(IndexTask {
+            doc,
+            modified,
+            size,
+            content_hash,
+            permit,
+        })
+        .await;
 }
 
 pub struct Scanner {
     indexer: Arc<IndexManager>,
     metadata_db: Arc<MetadataDb>,
     filename_index: Option<Arc<crate::indexer::filename_index::FilenameIndex>>,
-    progress_tx: Option<flume::Sender<ProgressEvent>>,
+    progress: Arc<ProgressAggregator>,
     settings: crate::settings::AppSettings,
+    paused: Arc<AtomicBool>,
+    budget: PipelineBudget,
+    metrics: Arc<PipelineMetrics>,
 }
 
 impl Scanner {
     /// Creates a new Scanner instance.
-    pub const fn new(
+    pub fn new(
         indexer: Arc<IndexManager>,
         metadata_db: Arc<MetadataDb>,
         filename_index: Option<Arc<crate::indexer::filename_index::FilenameIndex>>,
         progress_tx: Option<flume::Sender<ProgressEvent>>,
         settings: crate::settings::AppSettings,
     ) -> Self {
+        let budget = PipelineBudget::new(settings.pipeline_memory_mb, CHUNK_SIZE);
         Self {
             indexer,
             metadata_db,
             filename_index,
-            progress_tx,
+            progress: Arc::new(ProgressAggregator::new(progress_tx)),
             settings,
+            paused: Arc::new(AtomicBool::new(false)),
+            budget,
+            metrics: Arc::new(PipelineMetrics::new(&budget)),
         }
     }
 
-    fn get_scanner() -> Box<dyn DriveScanner> {
+    /// Queue depths and budget use of the running scans.
+    #[must_use]
+    pub fn pipeline_status(&self) -> crate::models::PipelineStatus {
+        self.metrics.snapshot()
+    }
+
+    /// The current scan run, or the last one once it finished, whoever
+    /// started it.
+    #[must_use]
+    pub fn scan_status(&self) -> crate::models::ScanRunStatus {
+        self.progress.snapshot()
+    }
+
+    /// Holds running scans between batches until [`Self::resume`] or a cancel.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    #[cfg_attr(not(target_os = "windows"), allow(clippy::unused_self))]
+    fn get_scanner(&self) -> Box<dyn DriveScanner> {
         #[cfg(target_os = "windows")]
         {
-            Box::new(drive_scanner::WindowsDriveScanner)
+            Box::new(drive_scanner::WindowsDriveScanner {
+                use_mft: self.settings.use_mft_enumeration,
+            })
         }
         #[cfg(target_os = "macos")]
         {
@@ -104,16 +381,54 @@ impl Scanner {
         }
     }
 
+    /// Waits out a transient disconnect of `root`, backing off between
+    /// probes. A root that stays unreachable is marked offline so its
+    /// documents are kept and reported as unavailable rather than pruned.
+    /// Removable drives are looked for again, so the files scanned next are
+    /// tagged with the drive they are on.
+    async fn ensure_reachable(&self, root: &Path, cancel_flag: &Arc<AtomicBool>) -> Result<()> {
+        let probe_root = root.to_path_buf();
+        let cancel = cancel_flag.clone();
+        let reachable = tokio::task::spawn_blocking(move || {
+            volumes::refresh();
+            network::wait_until_reachable(&probe_root, &cancel)
+        })
+        .await
+        .unwrap_or(false);
+
+        if reachable {
+            self.indexer.offline_roots().set_offline(root, false);
+            return Ok(());
+        }
+        if !cancel_flag.load(Ordering::Relaxed) {
+            warn!("{} is unreachable, keeping its documents", root.display());
+            self.indexer.offline_roots().set_offline(root, true);
+        }
+        Err(offline_error(root))
+    }
+
+    /// Whether `path` was indexed from a removable drive that is unplugged
+    /// now, so its absence says nothing about the file.
+    fn on_unplugged_drive(&self, path: &str) -> bool {
+        self.metadata_db
+            .get_metadata(Path::new(path))
+            .ok()
+            .flatten()
+            .and_then(|metadata| metadata.attributes.volume)
+            .is_some_and(|volume| volumes::is_offline(&volume))
+    }
+
     #[instrument(skip(self, tx))]
     pub fn watch_drive(
         &self,
         root: PathBuf,
         tx: mpsc::Sender<(PathBuf, crate::watcher::WatcherAction)>,
     ) -> Result<()> {
-        let scanner = Self::get_scanner();
+        let scanner = self.get_scanner();
         scanner.watch(root, tx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn process_writer_loop(
         task_rx: &flume::Receiver<IndexTask>,
         filename_index: Option<&Arc<crate::indexer::filename_index::FilenameIndex>>,
@@ -121,32 +436,46 @@ impl Scanner {
         metadata_db: &Arc<MetadataDb>,
         progress_tx: Option<&flume::Sender<ProgressEvent>>,
         total_files: &Arc<AtomicUsize>,
-        cancel_flag: &Arc<std::sync::atomic::AtomicBool>,
+        cancel_flag: &Arc<AtomicBool>,
+        paused: &AtomicBool,
+        checkpoint_root: &std::path::Path,
+        metrics: &PipelineMetrics,
     ) {
         info!("Stage 2b: Batch Writing");
         let start = Instant::now();
         let mut doc_batch: Vec<(crate::parsers::ParsedDocument, u64, u64)> =
             Vec::with_capacity(BATCH_SIZE);
-        let mut meta_batch: Vec<(String, u64, u64, [u8; 32])> = Vec::with_capacity(BATCH_SIZE);
-        let mut filename_batch: Vec<crate::indexer::filename_index::FilenameEntry> =
+        let mut meta_batch: Vec<(String, u64, u64, [u8; 32], FileAttributes)> =
             Vec::with_capacity(BATCH_SIZE);
+        let mut filename_batch: Vec<FilenameEntry> = Vec::with_capacity(BATCH_SIZE);
+        let mut permits: Vec<DocumentPermit> = Vec::new();
+        let mut uncommitted = UncommittedBatches::default();
         let mut processed: usize = 0;
 
-        for task in task_rx {
+        for mut task in task_rx {
+            metrics.set_queued_documents(task_rx.len());
+            wait_while_paused(paused, cancel_flag);
             if cancel_flag.load(Ordering::Relaxed) {
                 warn!("Indexing cancelled. Flushing batches...");
                 break;
             }
 
+            // Always overwrite: docs reused from the content cache carry the source file's
+            // notes and ownership
+            let doc_path = std::path::Path::new(&task.doc.path);
+            task.doc.attributes = FileAttributes::read(doc_path);
+            task.doc.annotations = metadata_db
+                .get_annotation(doc_path)
+                .ok()
+                .flatten()
+                .map(|a| a.search_text());
+
             // Prepare for filename index
             if filename_index.is_some() {
-                let path = std::path::Path::new(&task.doc.path);
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    filename_batch.push(crate::indexer::filename_index::FilenameEntry {
-                        path: task.doc.path.clone(),
-                        name: compact_str::CompactString::from(name),
-                    });
-                }
+                filename_batch.extend(FilenameEntry::new(
+                    std::path::Path::new(&task.doc.path),
+                    EntryKind::File,
+                ));
             }
 
             let current_file = std::path::Path::new(&task.doc.path)
@@ -155,23 +484,37 @@ impl Scanner {
                 .unwrap_or("")
                 .to_string();
 
-            // Clone path before moving doc
-            let doc_path = task.doc.path.clone();
+            // Clone path and attributes before moving doc
+            meta_batch.push((
+                task.doc.path.clone(),
+                task.modified,
+                task.size,
+                task.content_hash,
+                task.doc.attributes.clone(),
+            ));
             doc_batch.push((task.doc, task.modified, task.size));
-            meta_batch.push((doc_path, task.modified, task.size, task.content_hash));
+            permits.extend(task.permit);
             processed += 1;
 
-            // Flush batch when full
-            if doc_batch.len() >= BATCH_SIZE {
-                let _ = indexer.add_documents_batch(&doc_batch);
-                let _ = metadata_db.batch_update_metadata(&meta_batch);
+            // Flush batch when full, or early when it holds so much of the
+            // budget that the parser would stall. Committing before the
+            // metadata write makes every commit a checkpoint: a cancelled or
+            // interrupted scan skips these files when it is resumed.
+            if doc_batch.len() >= BATCH_SIZE || metrics.holds_too_much(&permits) {
+                uncommitted.add(
+                    indexer,
+                    metadata_db,
+                    &doc_batch,
+                    std::mem::take(&mut meta_batch),
+                );
+                uncommitted.commit(indexer, metadata_db, checkpoint_root, false);
 
                 if let Some(f_index) = filename_index {
                     let _ = f_index.add_files_batch(std::mem::take(&mut filename_batch));
                 }
 
                 doc_batch.clear();
-                meta_batch.clear();
+                permits.clear();
             }
 
             // Progress update
@@ -209,15 +552,19 @@ impl Scanner {
 
         // Flush remaining items (B1: always commit at end)
         if !doc_batch.is_empty() {
-            let _ = indexer.add_documents_batch(&doc_batch);
-            let _ = indexer.commit();
-            indexer.invalidate_cache();
-            let _ = metadata_db.batch_update_metadata(&meta_batch);
+            uncommitted.add(indexer, metadata_db, &doc_batch, meta_batch);
 
             if let Some(f_index) = filename_index {
                 let _ = f_index.add_files_batch(filename_batch);
             }
         }
+        uncommitted.commit(indexer, metadata_db, checkpoint_root, true);
+        drop(permits);
+        metrics.set_queued_documents(0);
+
+        // Discard whatever the parser still sends after a cancel, so it never
+        // waits on budget held by queued documents
+        task_rx.iter().for_each(drop);
 
         // Final progress
         if let Some(tx) = progress_tx {
@@ -240,38 +587,722 @@ impl Scanner {
         );
     }
 
-    #[allow(clippy::too_many_lines)]
-    #[instrument(skip(self, exclude_patterns, cancel_flag), fields(root = %root.display()))]
+    /// Walks `root` with its profile applied and indexes every file that is
+    /// new or changed since the last scan.
+    #[instrument(skip(self, exclude_patterns, cancel_flag), fields(root = %root.path))]
     pub async fn scan_directory(
         &self,
-        root: PathBuf,
+        root: IndexRoot,
         exclude_patterns: Vec<String>,
-        cancel_flag: Arc<std::sync::atomic::AtomicBool>,
+        cancel_flag: Arc<AtomicBool>,
     ) -> Result<()> {
-        info!("Starting directory scan for {}", root.display());
+        info!("Starting directory scan for {}", root.path);
+        if remote::is_remote(&root.path) {
+            return self.scan_remote(root, exclude_patterns, cancel_flag).await;
+        }
+        let checkpoint_root = PathBuf::from(&root.path);
+        self.ensure_reachable(&checkpoint_root, &cancel_flag)
+            .await?;
+        self.metadata_db.mark_scan_started(&checkpoint_root)?;
+        activity::record(|| Activity::ScanStarted {
+            root: root.path.clone(),
+            refresh: false,
+        });
+        let index_content = root.indexes_content();
 
-        let (path_tx, path_rx) = flume::unbounded::<PathBuf>();
+        let (path_tx, path_rx) = flume::bounded::<PathBuf>(self.budget.path_capacity);
 
-        let root_clone = root.clone();
-        let tx_clone = self.progress_tx.clone();
-        let scanner = Self::get_scanner();
+        let progress = self.progress.track(&checkpoint_root, ScanOperation::Scan);
+        let tx_clone = Some(progress.tx.clone());
+        let scanner = self.get_scanner();
         let total = Arc::new(AtomicUsize::new(0));
         let total_for_scan = total.clone();
 
+        // With a filename index the walk is teed: every file's name is
+        // published as soon as the walk ends, long before contents are parsed
+        let (walk_tx, tee_handle) = match self.filename_index.clone() {
+            Some(filename_index) => {
+                let (walk_tx, walk_rx) = flume::bounded::<PathBuf>(self.budget.path_capacity);
+                let tee_root = checkpoint_root.clone();
+                let tee_cancel = cancel_flag.clone();
+                let sensitive = SensitiveMatcher::from_settings(&self.settings);
+                let handle = tokio::task::spawn_blocking(move || {
+                    tee_filenames(
+                        &walk_rx,
+                        &path_tx,
+                        &filename_index,
+                        &tee_root,
+                        &sensitive,
+                        &tee_cancel,
+                    );
+                });
+                (walk_tx, Some(handle))
+            }
+            None => (path_tx, None),
+        };
+
         let use_gitignore = self.settings.use_gitignore;
         let cancel_flag_for_scan = cancel_flag.clone();
         let walker_handle = tokio::task::spawn_blocking(move || {
             scanner.scan(
-                root_clone,
+                root,
                 exclude_patterns,
                 use_gitignore,
-                path_tx,
+                walk_tx,
                 tx_clone,
                 total_for_scan,
                 cancel_flag_for_scan,
             )
         });
 
+        self.index_paths(
+            path_rx,
+            total,
+            index_content,
+            progress,
+            cancel_flag.clone(),
+            checkpoint_root.clone(),
+            false,
+        )
+        .await?;
+
+        walker_handle
+            .await
+            .map_err(|e| crate::error::FlashError::index(format!("Walk task failed: {e}")))?
+            .map_err(|e| crate::error::FlashError::index(format!("Walk logic failed: {e}")))?;
+        if let Some(handle) = tee_handle {
+            let _ = handle.await;
+        }
+
+        // A cancelled scan stays pending so it can be resumed
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        if !cancelled {
+            self.metadata_db.mark_scan_finished(&checkpoint_root)?;
+        }
+        activity::record(|| Activity::ScanFinished {
+            root: checkpoint_root.to_string_lossy().to_string(),
+            cancelled,
+        });
+
+        Ok(())
+    }
+
+    /// Scans every root in `roots`, as many at a time as the settings allow,
+    /// and returns the roots that failed with their errors. Roots not started
+    /// before a cancel are skipped.
+    pub async fn scan_roots(
+        self: &Arc<Self>,
+        roots: Vec<IndexRoot>,
+        exclude_patterns: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Vec<(String, FlashError)> {
+        let cancel = cancel_flag.clone();
+        self.for_each_root(roots, &cancel, move |scanner, root| {
+            let exclude_patterns = exclude_patterns.clone();
+            let cancel_flag = cancel_flag.clone();
+            async move {
+                scanner
+                    .scan_directory(root, exclude_patterns, cancel_flag)
+                    .await
+            }
+        })
+        .await
+    }
+
+    /// [`Self::refresh_directory`] over every root in `roots`, as many at a
+    /// time as the settings allow. Returns the roots that failed.
+    pub async fn refresh_roots(
+        self: &Arc<Self>,
+        roots: Vec<IndexRoot>,
+        exclude_patterns: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Vec<(String, FlashError)> {
+        let cancel = cancel_flag.clone();
+        self.for_each_root(roots, &cancel, move |scanner, root| {
+            let exclude_patterns = exclude_patterns.clone();
+            let cancel_flag = cancel_flag.clone();
+            async move {
+                scanner
+                    .refresh_directory(root, exclude_patterns, cancel_flag)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn for_each_root<F, Fut>(
+        self: &Arc<Self>,
+        roots: Vec<IndexRoot>,
+        cancel_flag: &AtomicBool,
+        scan: F,
+    ) -> Vec<(String, FlashError)>
+    where
+        F: Fn(Arc<Self>, IndexRoot) -> Fut,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        let limit = self.settings.parallel_scans.max(1);
+        let mut roots = roots.into_iter();
+        let mut running = tokio::task::JoinSet::new();
+        let mut failures = Vec::new();
+        loop {
+            while running.len() < limit
+                && !cancel_flag.load(Ordering::Relaxed)
+                && let Some(root) = roots.next()
+            {
+                let path = root.path.clone();
+                let scan = scan(self.clone(), root);
+                running.spawn(async move { (path, scan.await) });
+            }
+            match running.join_next().await {
+                Some(Ok((path, Err(e)))) => failures.push((path, e)),
+                Some(Ok((_, Ok(())))) => {}
+                Some(Err(e)) => warn!("Scan task failed: {}", e),
+                None => break,
+            }
+        }
+        failures
+    }
+
+    /// Re-indexes only what changed under `root` since the last refresh. Every
+    /// file is checked against its recorded mtime and size; directory mtimes
+    /// narrow down where files may have been deleted. Files that disappeared
+    /// from changed or deleted directories are dropped from the index, unless
+    /// the root itself went offline.
+    #[instrument(skip(self, exclude_patterns, cancel_flag), fields(root = %root.path))]
+    pub async fn refresh_directory(
+        &self,
+        root: IndexRoot,
+        exclude_patterns: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<()> {
+        info!("Starting incremental refresh for {}", root.path);
+        // Remote folders have no directory mtimes to go by; a full listing
+        // still only downloads what changed
+        if remote::is_remote(&root.path) {
+            return self.scan_remote(root, exclude_patterns, cancel_flag).await;
+        }
+
+        let checkpoint_root = PathBuf::from(&root.path);
+        self.ensure_reachable(&checkpoint_root, &cancel_flag)
+            .await?;
+        let known = self.metadata_db.get_dir_mtimes(&checkpoint_root)?;
+        self.metadata_db.mark_scan_started(&checkpoint_root)?;
+        activity::record(|| Activity::ScanStarted {
+            root: root.path.clone(),
+            refresh: true,
+        });
+        let index_content = root.indexes_content();
+        let (path_tx, path_rx) = flume::bounded::<PathBuf>(self.budget.path_capacity);
+        let total = Arc::new(AtomicUsize::new(0));
+
+        let total_for_walk = total.clone();
+        let progress = self
+            .progress
+            .track(&checkpoint_root, ScanOperation::Refresh);
+        let progress_tx_for_walk = progress.tx.clone();
+        let use_gitignore = self.settings.use_gitignore;
+        let cancel_flag_for_walk = cancel_flag.clone();
+        let walker_handle = tokio::task::spawn_blocking(move || {
+            incremental::walk_with_dir_changes(
+                &root,
+                &exclude_patterns,
+                use_gitignore,
+                &known,
+                &path_tx,
+                Some(&progress_tx_for_walk),
+                &total_for_walk,
+                &cancel_flag_for_walk,
+            )
+        });
+
+        self.index_paths(
+            path_rx,
+            total,
+            index_content,
+            progress,
+            cancel_flag.clone(),
+            checkpoint_root.clone(),
+            false,
+        )
+        .await?;
+
+        let changes = walker_handle
+            .await
+            .map_err(|e| crate::error::FlashError::index(format!("Walk task failed: {e}")))?;
+
+        // A cancelled walk hasn't seen every directory, so nothing is recorded
+        if cancel_flag.load(Ordering::Relaxed) {
+            activity::record(|| Activity::ScanFinished {
+                root: checkpoint_root.to_string_lossy().to_string(),
+                cancelled: true,
+            });
+            return Ok(());
+        }
+
+        // A share that dropped during the walk makes every directory look
+        // deleted; keep the documents and leave the checkpoint pending
+        let probe_root = checkpoint_root.clone();
+        let reachable = tokio::task::spawn_blocking(move || network::is_reachable(&probe_root))
+            .await
+            .unwrap_or(false);
+        if !reachable {
+            self.indexer
+                .offline_roots()
+                .set_offline(&checkpoint_root, true);
+            return Err(offline_error(&checkpoint_root));
+        }
+
+        let mut removed_files = 0usize;
+        let removed_dirs = changes.removed.iter().map(PathBuf::from);
+        for dir in changes.changed.iter().cloned().chain(removed_dirs) {
+            for path in self.metadata_db.get_files_in_dir(&dir)? {
+                if !std::path::Path::new(&path).exists() && !self.on_unplugged_drive(&path) {
+                    let _ = self.indexer.remove_document(&path);
+                    if let Some(filename_index) = &self.filename_index {
+                        let _ = filename_index.remove_file(&path);
+                    }
+                    if matches!(
+                        self.metadata_db.remove_file(std::path::Path::new(&path)),
+                        Ok(true)
+                    ) {
+                        removed_files += 1;
+                        activity::record(|| Activity::Removed {
+                            path,
+                            source: Source::Scan,
+                        });
+                    }
+                }
+            }
+        }
+        if removed_files > 0 {
+            self.indexer.commit()?;
+            if let Some(filename_index) = &self.filename_index {
+                filename_index.commit()?;
+            }
+        }
+
+        self.metadata_db
+            .update_dir_mtimes(&changes.visited, &changes.removed)?;
+        self.metadata_db.mark_scan_finished(&checkpoint_root)?;
+        activity::record(|| Activity::ScanFinished {
+            root: checkpoint_root.to_string_lossy().to_string(),
+            cancelled: false,
+        });
+
+        info!(
+            "Refresh complete: {} of {} directories changed, {} files removed",
+            changes.changed.len(),
+            changes.visited.len(),
+            removed_files
+        );
+
+        Ok(())
+    }
+
+    /// Lists the remote root `root` and indexes every file that is new or
+    /// changed since the last scan, copying each one to a temporary local file
+    /// for the parsers. Files the listing no longer has are dropped. A server
+    /// that can't be reached, or drops the connection mid-scan, marks the root
+    /// offline so its documents are kept.
+    #[allow(clippy::too_many_lines)]
+    async fn scan_remote(
+        &self,
+        root: IndexRoot,
+        exclude_patterns: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<()> {
+        let url = remote::RemoteUrl::parse(&root.path).ok_or_else(|| {
+            FlashError::config("index_roots", format!("{} is not a remote URL", root.path))
+        })?;
+        let checkpoint_root = PathBuf::from(&root.path);
+        let index_content = root.indexes_content();
+
+        let connect_url = url.clone();
+        let connected = tokio::task::spawn_blocking(move || remote::connect(&connect_url))
+            .await
+            .map_err(|e| FlashError::index(format!("Connect task failed: {e}")))?;
+        let transport = match connected {
+            Ok(transport) => transport,
+            Err(e) => {
+                warn!("Cannot reach {}: {}", url, e);
+                self.indexer
+                    .offline_roots()
+                    .set_offline(&checkpoint_root, true);
+                return Err(e.into());
+            }
+        };
+        self.indexer
+            .offline_roots()
+            .set_offline(&checkpoint_root, false);
+        self.metadata_db.mark_scan_started(&checkpoint_root)?;
+        activity::record(|| Activity::ScanStarted {
+            root: root.path.clone(),
+            refresh: false,
+        });
+
+        let walk_url = url.clone();
+        let walk_cancel = cancel_flag.clone();
+        let (mut transport, listed) = tokio::task::spawn_blocking(move || {
+            let mut transport = transport;
+            let listed = remote::walk(
+                transport.as_mut(),
+                &walk_url,
+                &root,
+                &exclude_patterns,
+                &walk_cancel,
+            );
+            (transport, listed)
+        })
+        .await
+        .map_err(|e| FlashError::index(format!("Walk task failed: {e}")))?;
+        let listed = match listed {
+            Ok(listed) => listed,
+            Err(e) => {
+                warn!("Cannot list {}: {}", url, e);
+                self.indexer
+                    .offline_roots()
+                    .set_offline(&checkpoint_root, true);
+                return Err(e.into());
+            }
+        };
+
+        // Names are published before any contents, as for local roots
+        let sensitive = Arc::new(SensitiveMatcher::from_settings(&self.settings));
+        if let Some(filename_index) = &self.filename_index {
+            let entries: Vec<FilenameEntry> = listed
+                .iter()
+                .filter_map(|entry| {
+                    let location = url.location(&entry.path);
+                    let path = Path::new(&location);
+                    if entry.is_dir {
+                        FilenameEntry::new(path, EntryKind::Directory)
+                    } else if sensitive.skips(path) {
+                        None
+                    } else {
+                        FilenameEntry::new(path, EntryKind::File)
+                    }
+                })
+                .collect();
+            let published = if cancel_flag.load(Ordering::Relaxed) {
+                filename_index
+                    .add_files_batch(entries)
+                    .and_then(|()| filename_index.commit())
+            } else {
+                filename_index.replace_root(&checkpoint_root, entries)
+            };
+            if let Err(e) = published {
+                warn!("Failed to update filename index: {}", e);
+            }
+        }
+
+        let files: Vec<_> = listed.into_iter().filter(|entry| !entry.is_dir).collect();
+        let listed: std::collections::HashSet<String> = files
+            .iter()
+            .map(|entry| url.location(&entry.path))
+            .collect();
+        let total = Arc::new(AtomicUsize::new(files.len()));
+        let progress = self.progress.track(&checkpoint_root, ScanOperation::Scan);
+        self.metrics.reset();
+
+        // --- Download: changed files are copied locally, one at a time ---
+        let (file_tx, file_rx) = flume::bounded::<RemoteFile>(REMOTE_QUEUE);
+        let root_lost = Arc::new(AtomicBool::new(false));
+        let root_lost_for_fetch = root_lost.clone();
+        let allowed_extensions: std::collections::HashSet<String> = self
+            .settings
+            .get_allowed_extensions()
+            .iter()
+            .map(|e| e.to_lowercase())
+            .collect();
+        let limit_bytes = u64::from(self.settings.index_file_size_limit_mb) * 1024 * 1024;
+        let metadata_db_for_fetch = self.metadata_db.clone();
+        let sensitive_for_fetch = sensitive.clone();
+        let progress_for_fetch = progress.clone();
+        let paused_for_fetch = self.paused.clone();
+        let cancel_flag_for_fetch = cancel_flag.clone();
+        let fetch_url = url.clone();
+        let fetch_handle = tokio::task::spawn_blocking(move || {
+            for entry in files {
+                wait_while_paused(&paused_for_fetch, &cancel_flag_for_fetch);
+                if cancel_flag_for_fetch.load(Ordering::Relaxed) {
+                    break;
+                }
+                let location = fetch_url.location(&entry.path);
+                let path = Path::new(&location);
+                if sensitive_for_fetch.skips(path) {
+                    progress_for_fetch.skipped();
+                    skipped(path, SkipReason::Sensitive);
+                    continue;
+                }
+                if index_content {
+                    let allowed = path
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|ext| allowed_extensions.contains(&ext.to_lowercase()));
+                    if !allowed {
+                        skipped(path, SkipReason::Extension);
+                        continue;
+                    }
+                    if entry.size > limit_bytes {
+                        progress_for_fetch.skipped();
+                        skipped(path, SkipReason::TooLarge);
+                        continue;
+                    }
+                }
+                if !metadata_db_for_fetch
+                    .needs_reindex(path, entry.modified, entry.size)
+                    .unwrap_or(true)
+                {
+                    continue;
+                }
+
+                let copy = if !index_content || sensitive_for_fetch.hides_content(path) {
+                    None
+                } else {
+                    match remote::fetch(transport.as_mut(), &entry.path) {
+                        Ok(copy) => Some(copy),
+                        // A dropped connection fails every read after it
+                        Err(_) if transport.list(&fetch_url.path).is_err() => {
+                            warn!("{} went offline mid-scan", fetch_url);
+                            root_lost_for_fetch.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                        Err(e) => {
+                            warn!("Failed to download {}: {}", location, e);
+                            progress_for_fetch.skipped();
+                            skipped(path, SkipReason::Unreadable);
+                            continue;
+                        }
+                    }
+                };
+                let file = RemoteFile {
+                    location,
+                    modified: entry.modified,
+                    size: entry.size,
+                    copy,
+                };
+                if file_tx.send(file).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // --- Write: the same batched writer local scans use ---
+        let (task_tx, task_rx) = flume::bounded::<IndexTask>(BATCH_SIZE * 8);
+        let filename_index = self.filename_index.clone();
+        let indexer = self.indexer.clone();
+        let metadata_db = self.metadata_db.clone();
+        let progress_tx = progress.tx.clone();
+        let total_files = total.clone();
+        let cancel_flag_for_writer = cancel_flag.clone();
+        let paused_for_writer = self.paused.clone();
+        let root_for_writer = checkpoint_root.clone();
+        let metrics = self.metrics.clone();
+        let writer_handle = tokio::task::spawn_blocking(move || {
+            Self::process_writer_loop(
+                &task_rx,
+                filename_index.as_ref(),
+                &indexer,
+                &metadata_db,
+                Some(&progress_tx),
+                &total_files,
+                &cancel_flag_for_writer,
+                &paused_for_writer,
+                &root_for_writer,
+                &metrics,
+            );
+        });
+
+        // --- Parse: each local copy goes through the usual parsers ---
+        let enable_ocr = self.settings.enable_ocr;
+        let parse_timeout = std::time::Duration::from_secs(self.settings.parse_timeout_secs.max(1));
+        while let Ok(file) = file_rx.recv_async().await {
+            wait_while_paused_async(&self.paused, &cancel_flag).await;
+            if cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
+            let path = Path::new(&file.location);
+            let Some((copy, hash)) = file.copy else {
+                let hash = blake3::hash(file.location.as_bytes()).into();
+                let doc = ParsedDocument::filename_only(path);
+                send_task(&task_tx, &self.metrics, doc, file.modified, file.size, hash).await;
+                continue;
+            };
+            match parse_file_isolated(copy.path(), enable_ocr, parse_timeout).await {
+                Ok(mut parsed) => {
+                    parsed.path.clone_from(&file.location);
+                    send_task(
+                        &task_tx,
+                        &self.metrics,
+                        parsed,
+                        file.modified,
+                        file.size,
+                        hash,
+                    )
+                    .await;
+                }
+                Err(e) => {
+                    warn!("Failed to parse file {}: {}", file.location, e);
+                    progress.failed();
+                    let _ = self.metadata_db.record_parse_error(
+                        path,
+                        ParseErrorKind::from_error(&e),
+                        &e.to_string(),
+                        file.modified,
+                        file.size,
+                    );
+                }
+            }
+        }
+        // Unblocks a download waiting on a cancelled parser
+        drop(file_rx);
+        drop(task_tx);
+        fetch_handle
+            .await
+            .map_err(|e| FlashError::index(format!("Download task failed: {e}")))?;
+        writer_handle
+            .await
+            .map_err(|e| FlashError::index(format!("Write task failed: {e}")))?;
+        if let Some(filename_index) = &self.filename_index {
+            let _ = filename_index.commit();
+        }
+
+        if root_lost.load(Ordering::Relaxed) {
+            self.indexer
+                .offline_roots()
+                .set_offline(&checkpoint_root, true);
+            return Err(offline_error(&checkpoint_root));
+        }
+
+        // A cancelled scan stays pending and keeps what it hasn't reached
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        let mut removed_files = 0usize;
+        if !cancelled {
+            let prefix = format!("{}/", url.to_string().trim_end_matches('/'));
+            let gone = self
+                .metadata_db
+                .remove_matching(|path| path.starts_with(&prefix) && !listed.contains(path))?;
+            for path in gone {
+                let _ = self.indexer.remove_document(&path);
+                if let Some(filename_index) = &self.filename_index {
+                    let _ = filename_index.remove_file(&path);
+                }
+                removed_files += 1;
+                activity::record(|| Activity::Removed {
+                    path,
+                    source: Source::Scan,
+                });
+            }
+            if removed_files > 0 {
+                self.indexer.commit()?;
+                if let Some(filename_index) = &self.filename_index {
+                    filename_index.commit()?;
+                }
+            }
+            self.metadata_db.mark_scan_finished(&checkpoint_root)?;
+        }
+        activity::record(|| Activity::ScanFinished {
+            root: checkpoint_root.to_string_lossy().to_string(),
+            cancelled,
+        });
+
+        info!(
+            "Remote scan of {} complete: {} files listed, {} removed",
+            url,
+            listed.len(),
+            removed_files
+        );
+        Ok(())
+    }
+
+    /// Parses `path` again, or every file beneath it if it is a folder, even
+    /// those unchanged since they were indexed, and commits the result.
+    /// `root` is the indexed root `path` lies under; its profile decides what
+    /// the walk leaves out. Returns how many files were found.
+    #[instrument(skip(self, root, exclude_patterns, cancel_flag), fields(path = %path.display()))]
+    pub async fn reindex_path(
+        &self,
+        root: IndexRoot,
+        path: &Path,
+        exclude_patterns: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<usize> {
+        info!("Re-indexing {}", path.display());
+        let checkpoint_root = PathBuf::from(&root.path);
+        let index_content = root.indexes_content();
+        let (path_tx, path_rx) = flume::bounded::<PathBuf>(self.budget.path_capacity);
+        let progress = self.progress.track(&checkpoint_root, ScanOperation::Scan);
+        let total = Arc::new(AtomicUsize::new(0));
+
+        let walker_handle = if path.is_dir() {
+            let subtree = IndexRoot {
+                path: path.to_string_lossy().to_string(),
+                ..root
+            };
+            let scanner = self.get_scanner();
+            let use_gitignore = self.settings.use_gitignore;
+            let progress_tx = Some(progress.tx.clone());
+            let total_for_walk = total.clone();
+            let cancel_flag_for_walk = cancel_flag.clone();
+            Some(tokio::task::spawn_blocking(move || {
+                scanner.scan(
+                    subtree,
+                    exclude_patterns,
+                    use_gitignore,
+                    path_tx,
+                    progress_tx,
+                    total_for_walk,
+                    cancel_flag_for_walk,
+                )
+            }))
+        } else {
+            total.store(1, Ordering::Relaxed);
+            let _ = path_tx.send(path.to_path_buf());
+            None
+        };
+
+        self.index_paths(
+            path_rx,
+            total.clone(),
+            index_content,
+            progress,
+            cancel_flag,
+            checkpoint_root,
+            true,
+        )
+        .await?;
+
+        if let Some(handle) = walker_handle {
+            handle
+                .await
+                .map_err(|e| crate::error::FlashError::index(format!("Walk task failed: {e}")))?
+                .map_err(|e| crate::error::FlashError::index(format!("Walk logic failed: {e}")))?;
+        }
+        Ok(total.load(Ordering::Relaxed))
+    }
+
+    /// Filters, parses and writes the files arriving on `path_rx` until the
+    /// walker feeding it hangs up, counting committed batches against the
+    /// checkpoint of `checkpoint_root`. Without `index_content` files skip the
+    /// extension and size filters and are indexed by name and metadata only,
+    /// as are sensitive files unless they are skipped altogether, and
+    /// online-only cloud files outside the folders allowed to download.
+    /// On a network root a failed stat waits for the share to come back; if
+    /// it doesn't, the root is marked offline and the scan stops with an
+    /// error so its checkpoint stays pending. Files the user may not read
+    /// are counted as no access and left out. With `force` every file is
+    /// parsed again, whether or not it changed since it was indexed.
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+    async fn index_paths(
+        &self,
+        path_rx: flume::Receiver<PathBuf>,
+        total: Arc<AtomicUsize>,
+        index_content: bool,
+        progress: ScanProgress,
+        cancel_flag: Arc<AtomicBool>,
+        checkpoint_root: PathBuf,
+        force: bool,
+    ) -> Result<()> {
         // --- Stage 2: Content Indexing (Async Batched) ---
         //
         // Architecture:
@@ -281,23 +1312,32 @@ impl Scanner {
         //   - An async Tokio task receives chunks and awaits parse_files_batch(),
         //     which uses xberg's native JoinSet-based concurrency internally —
         //     no manual Rayon pool needed.
-        //   - Parsed IndexTasks are forwarded to a sync writer via crossbeam.
-        const CHUNK_SIZE: usize = 200;
+        //   - Parsed IndexTasks are forwarded to a sync writer via crossbeam,
+        //     each holding a share of the memory budget until it is written.
+        //   - Every channel is bounded, so a slow writer holds back the parser,
+        //     the filter and finally the walk.
+        self.metrics.reset();
+        let metrics = self.metrics.clone();
 
         let (task_tx, task_rx) = flume::bounded::<IndexTask>(BATCH_SIZE * 8);
         // Async channel for sending path-chunks from the blocking walker to the async parser.
-        let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::channel::<Vec<(PathBuf, u64, u64)>>(32);
+        let (chunk_tx, mut chunk_rx) =
+            tokio::sync::mpsc::channel::<Vec<(PathBuf, u64, u64)>>(self.budget.chunk_capacity);
 
         let metadata_db_for_filter = self.metadata_db.clone();
         let metadata_db_for_writer = self.metadata_db.clone();
+        let metadata_db_for_parser = self.metadata_db.clone();
         let indexer_clone = self.indexer.clone();
         let filename_index_clone = self.filename_index.clone();
-        let progress_tx_clone = self.progress_tx.clone();
+        let progress_tx_clone = progress.tx.clone();
         let total_files = total.clone();
 
         let indexing_threads = self.settings.indexing_threads;
         let enable_ocr = self.settings.enable_ocr;
+        let parse_timeout = std::time::Duration::from_secs(self.settings.parse_timeout_secs.max(1));
         let file_size_limit_mb = self.settings.index_file_size_limit_mb;
+        let sensitive = Arc::new(SensitiveMatcher::from_settings(&self.settings));
+        let sensitive_for_parser = sensitive.clone();
         let allowed_extensions: Arc<std::collections::HashSet<String>> = Arc::new(
             self.settings
                 .get_allowed_extensions()
@@ -309,42 +1349,105 @@ impl Scanner {
         // --- Stage 2a: Blocking path receiver + filter ---
         // Drains path_rx (crossbeam), applies extension/size/metadata filters,
         // checks the metadata DB for staleness, then sends chunks over chunk_tx.
+        let is_network_root = network::is_network_path(&checkpoint_root);
+        let root_lost = Arc::new(AtomicBool::new(false));
+        let root_lost_for_filter = root_lost.clone();
+        let root_for_filter = checkpoint_root.clone();
+        let root_for_parser = checkpoint_root.clone();
+        let root_elevated = access::is_elevated(&checkpoint_root);
         let cancel_flag_for_filter = cancel_flag.clone();
+        let paused_for_filter = self.paused.clone();
+        let metrics_for_filter = metrics.clone();
+        let progress_for_filter = progress.clone();
         let filter_handle = tokio::task::spawn_blocking(move || {
             info!("Stage 2a: Path filtering and chunking");
             let limit_bytes = u64::from(file_size_limit_mb) * 1024 * 1024;
             let mut chunk: Vec<(PathBuf, u64, u64)> = Vec::with_capacity(CHUNK_SIZE);
+            // Files whose contents will be read are opened once first, so a
+            // refused one is counted as no access instead of failing to parse.
+            // A share can stall on its permission check, hence the timeout.
+            let probe_timeout = is_network_root.then_some(network::PROBE_TIMEOUT);
+            let readable = |item: &(PathBuf, u64, u64)| {
+                let path = &item.0;
+                if !index_content
+                    || sensitive.hides_content(path)
+                    || cloud::hides_content(path)
+                    || !access::is_refused(path, probe_timeout)
+                {
+                    return true;
+                }
+                progress_for_filter.no_access();
+                skipped(path, SkipReason::NoAccess);
+                false
+            };
 
-            for path in path_rx {
+            for path in &path_rx {
+                wait_while_paused(&paused_for_filter, &cancel_flag_for_filter);
                 if cancel_flag_for_filter.load(Ordering::Relaxed) {
                     break;
                 }
 
-                // Extension filter (zero-allocation stack check via SmallVec)
-                let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
-                    continue;
-                };
-                let mut ext_buf = smallvec::SmallVec::<[u8; 16]>::new();
-                ext_buf.extend_from_slice(ext.as_bytes());
-                ext_buf.make_ascii_lowercase();
-                let is_allowed = std::str::from_utf8(&ext_buf)
-                    .is_ok_and(|ext_lower| allowed_extensions.contains(ext_lower));
-                if !is_allowed {
+                if sensitive.skips(&path) {
+                    progress_for_filter.skipped();
+                    skipped(&path, SkipReason::Sensitive);
                     continue;
                 }
 
+                // Extension filter (zero-allocation stack check via SmallVec)
+                if index_content {
+                    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+                        skipped(&path, SkipReason::Extension);
+                        continue;
+                    };
+                    let mut ext_buf = smallvec::SmallVec::<[u8; 16]>::new();
+                    ext_buf.extend_from_slice(ext.as_bytes());
+                    ext_buf.make_ascii_lowercase();
+                    let is_allowed = std::str::from_utf8(&ext_buf)
+                        .is_ok_and(|ext_lower| allowed_extensions.contains(ext_lower));
+                    if !is_allowed {
+                        skipped(&path, SkipReason::Extension);
+                        continue;
+                    }
+                }
+
                 // Stat the file
-                let Ok(meta) = std::fs::metadata(&path) else {
-                    continue;
+                let meta = match std::fs::metadata(&path) {
+                    Ok(meta) => meta,
+                    Err(e) if access::is_denied(&e) => {
+                        progress_for_filter.no_access();
+                        skipped(&path, SkipReason::NoAccess);
+                        continue;
+                    }
+                    Err(_) if is_network_root => {
+                        if !network::wait_until_reachable(&root_for_filter, &cancel_flag_for_filter)
+                        {
+                            warn!("{} went offline mid-scan", root_for_filter.display());
+                            root_lost_for_filter.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                        let Ok(meta) = std::fs::metadata(&path) else {
+                            progress_for_filter.skipped();
+                            skipped(&path, SkipReason::Unreadable);
+                            continue;
+                        };
+                        meta
+                    }
+                    Err(_) => {
+                        progress_for_filter.skipped();
+                        skipped(&path, SkipReason::Unreadable);
+                        continue;
+                    }
                 };
                 let size = meta.len();
-                if size > limit_bytes {
+                if index_content && size > limit_bytes {
                     warn!(
                         "Skipping large file: {} ({} bytes > {} bytes limit)",
                         path.display(),
                         size,
                         limit_bytes
                     );
+                    progress_for_filter.skipped();
+                    skipped(&path, SkipReason::TooLarge);
                     continue;
                 }
                 let modified = meta
@@ -357,15 +1460,21 @@ impl Scanner {
                 chunk.push((path, modified, size));
 
                 if chunk.len() >= CHUNK_SIZE {
+                    metrics_for_filter.set_queued_paths(path_rx.len());
                     // Batch-check staleness directly against the metadata DB without allocating Strings
-                    let needs: Vec<bool> = metadata_db_for_filter
-                        .batch_needs_reindex_paths(&chunk)
-                        .unwrap_or_else(|_| vec![true; chunk.len()]);
+                    let needs: Vec<bool> = if force {
+                        vec![true; chunk.len()]
+                    } else {
+                        metadata_db_for_filter
+                            .batch_needs_reindex_paths(&chunk)
+                            .unwrap_or_else(|_| vec![true; chunk.len()])
+                    };
                     let current_chunk = std::mem::take(&mut chunk);
                     let stale: Vec<_> = current_chunk
                         .into_iter()
                         .zip(needs)
                         .filter_map(|(item, need)| need.then_some(item))
+                        .filter(readable)
                         .collect();
                     if !stale.is_empty() {
                         let _ = chunk_tx.blocking_send(stale);
@@ -376,18 +1485,24 @@ impl Scanner {
 
             // Flush remainder
             if !chunk.is_empty() {
-                let needs: Vec<bool> = metadata_db_for_filter
-                    .batch_needs_reindex_paths(&chunk)
-                    .unwrap_or_else(|_| vec![true; chunk.len()]);
+                let needs: Vec<bool> = if force {
+                    vec![true; chunk.len()]
+                } else {
+                    metadata_db_for_filter
+                        .batch_needs_reindex_paths(&chunk)
+                        .unwrap_or_else(|_| vec![true; chunk.len()])
+                };
                 let stale: Vec<_> = chunk
                     .into_iter()
                     .zip(needs)
                     .filter_map(|(item, need)| need.then_some(item))
+                    .filter(readable)
                     .collect();
                 if !stale.is_empty() {
                     let _ = chunk_tx.blocking_send(stale);
                 }
             }
+            metrics_for_filter.set_queued_paths(0);
             // chunk_tx drops here, closing chunk_rx.
         });
 
@@ -395,24 +1510,94 @@ impl Scanner {
         // Receives chunks over the mpsc channel and awaits xberg's native
         // concurrent JoinSet-based batch extractor directly on the Tokio runtime.
         let task_tx_for_parser = task_tx.clone();
-        let progress_tx_for_parser = self.progress_tx.clone();
+        let progress_for_parser = progress.clone();
         let total_files_for_parser = total.clone();
 
         let cancel_flag_for_parser = cancel_flag.clone();
+        let paused_for_parser = self.paused.clone();
+        let metrics_for_parser = metrics.clone();
+        let cache_weight = u64::try_from(self.budget.document_bytes / 8).unwrap_or(u64::MAX);
 
         let parser_handle = tokio::spawn(async move {
             info!("Stage 2b: Async Xberg batch parsing");
+            // Weighed by text so cached duplicates stay within the budget too
             let content_cache: mini_moka::sync::Cache<[u8; 32], crate::parsers::ParsedDocument> =
                 mini_moka::sync::Cache::builder()
-                    .max_capacity(500)
+                    .weigher(|_, doc: &ParsedDocument| {
+                        u32::try_from(doc.text_len()).unwrap_or(u32::MAX)
+                    })
+                    .max_capacity(cache_weight)
                     .time_to_idle(std::time::Duration::from_mins(1))
                     .build();
 
             while let Some(chunk) = chunk_rx.recv().await {
+                metrics_for_parser.set_queued_chunks(chunk_rx.len());
+                wait_while_paused_async(&paused_for_parser, &cancel_flag_for_parser).await;
                 if cancel_flag_for_parser.load(Ordering::Relaxed) {
                     break;
                 }
 
+                // Filename-only roots, sensitive files and cloud placeholders
+                // are never opened, so the path stands in for the hash
+                let (names_only, chunk): (Vec<_>, Vec<_>) =
+                    chunk.into_iter().partition(|(path, _, _)| {
+                        !index_content
+                            || sensitive_for_parser.hides_content(path)
+                            || cloud::hides_content(path)
+                    });
+                for (path, modified, size) in names_only {
+                    let content_hash = blake3::hash(path.to_string_lossy().as_bytes()).into();
+                    send_task(
+                        &task_tx_for_parser,
+                        &metrics_for_parser,
+                        ParsedDocument::filename_only(&path),
+                        modified,
+                        size,
+                        content_hash,
+                    )
+                    .await;
+                }
+
+                // The batch extractor opens files itself, so files only
+                // backup rights can read are parsed one by one instead
+                let (elevated, chunk): (Vec<_>, Vec<_>) = if root_elevated {
+                    chunk
+                        .into_iter()
+                        .partition(|(path, _, _)| access::needs_elevation(path))
+                } else {
+                    (Vec::new(), chunk)
+                };
+                for (path, modified, size) in elevated {
+                    let hash = get_file_hash(&path);
+                    match parse_file_isolated(&path, enable_ocr, parse_timeout).await {
+                        Ok(parsed) => {
+                            send_task(
+                                &task_tx_for_parser,
+                                &metrics_for_parser,
+                                parsed,
+                                modified,
+                                size,
+                                hash,
+                            )
+                            .await;
+                        }
+                        Err(e) => {
+                            warn!("Failed to parse file {:?}: {}", path, e);
+                            progress_for_parser.failed();
+                            let _ = metadata_db_for_parser.record_parse_error(
+                                &path,
+                                ParseErrorKind::from_error(&e),
+                                &e.to_string(),
+                                modified,
+                                size,
+                            );
+                        }
+                    }
+                }
+                if chunk.is_empty() {
+                    continue;
+                }
+
                 let mut paths_to_parse = Vec::new();
                 let mut chunk_hashes = Vec::new();
 
@@ -423,12 +1608,15 @@ impl Scanner {
                     if let Some(cached_doc) = content_cache.get(&hash) {
                         let mut doc = cached_doc.clone();
                         doc.path = path.to_string_lossy().to_string();
-                        let _ = task_tx_for_parser.send(IndexTask {
+                        send_task(
+                            &task_tx_for_parser,
+                            &metrics_for_parser,
                             doc,
-                            modified: *modified,
-                            size: *size,
-                            content_hash: hash,
-                        });
+                            *modified,
+                            *size,
+                            hash,
+                        )
+                        .await;
                     } else {
                         paths_to_parse.push(path.clone());
                     }
@@ -438,115 +1626,94 @@ impl Scanner {
                     continue;
                 }
 
-                if let Some(tx) = &progress_tx_for_parser {
-                    let current_total = total_files_for_parser.load(Ordering::Relaxed);
-                    let first_file = paths_to_parse
-                        .first()
-                        .and_then(|p| p.file_name())
-                        .map_or_else(String::new, |n| n.to_string_lossy().to_string());
-
-                    let _ = tx.try_send(ProgressEvent {
-                        ptype: ProgressType::Content,
-                        current_file: first_file,
-                        current_folder: String::new(),
-                        processed: 0,
-                        total: current_total,
-                        status: format!("Parsing batch of {} files...", paths_to_parse.len()),
-                        eta_seconds: 0,
-                        files_per_second: 0.0,
-                    });
-                }
-
-                match crate::parsers::parse_files_batch(
+                let current_total = total_files_for_parser.load(Ordering::Relaxed);
+                let first_file = paths_to_parse
+                    .first()
+                    .and_then(|p| p.file_name())
+                    .map_or_else(String::new, |n| n.to_string_lossy().to_string());
+                let _ = progress_for_parser.tx.try_send(ProgressEvent {
+                    ptype: ProgressType::Content,
+                    current_file: first_file,
+                    current_folder: String::new(),
+                    processed: 0,
+                    total: current_total,
+                    status: format!("Parsing batch of {} files...", paths_to_parse.len()),
+                    eta_seconds: 0,
+                    files_per_second: 0.0,
+                });
+
+                let results = parse_files_batch_isolated(
                     &paths_to_parse,
                     indexing_threads,
                     enable_ocr,
+                    parse_timeout,
                 )
-                .await
-                {
-                    Ok(results) => {
-                        for (parsed_res, path) in
-                            results.into_iter().zip(paths_to_parse.into_iter())
-                        {
-                            if let Some(&(ref found_path, modified, size)) =
-                                chunk.iter().find(|(p, _, _)| *p == path)
-                            {
-                                let hash =
-                                    chunk.iter().position(|(p, _, _)| *p == path).map_or_else(
-                                        || get_file_hash(found_path),
-                                        |idx| chunk_hashes[idx],
-                                    );
-
-                                match parsed_res {
-                                    Ok(parsed) => {
-                                        content_cache.insert(hash, parsed.clone());
-
-                                        let _ = task_tx_for_parser.send(IndexTask {
-                                            doc: parsed,
-                                            modified,
-                                            size,
-                                            content_hash: hash,
-                                        });
-                                    }
-                                    Err(e) => {
-                                        warn!("Failed to parse file {:?}: {}", path, e);
-                                    }
-                                }
+                .await;
+                for (parsed_res, path) in results.into_iter().zip(paths_to_parse.into_iter()) {
+                    if let Some(&(ref found_path, modified, size)) =
+                        chunk.iter().find(|(p, _, _)| *p == path)
+                    {
+                        let hash = chunk
+                            .iter()
+                            .position(|(p, _, _)| *p == path)
+                            .map_or_else(|| get_file_hash(found_path), |idx| chunk_hashes[idx]);
+
+                        match parsed_res {
+                            Ok(parsed) => {
+                                content_cache.insert(hash, parsed.clone());
+
+                                send_task(
+                                    &task_tx_for_parser,
+                                    &metrics_for_parser,
+                                    parsed,
+                                    modified,
+                                    size,
+                                    hash,
+                                )
+                                .await;
                             }
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Async batch crashed ({e}), falling back to per-file sync parsing");
-                        for path in paths_to_parse {
-                            if let Some(&(ref found_path, modified, size)) =
-                                chunk.iter().find(|(p, _, _)| *p == path)
-                            {
-                                let hash =
-                                    chunk.iter().position(|(p, _, _)| *p == path).map_or_else(
-                                        || get_file_hash(found_path),
-                                        |idx| chunk_hashes[idx],
-                                    );
-
-                                if let Ok(parsed) = parse_file(&path, enable_ocr).await {
-                                    content_cache.insert(hash, parsed.clone());
-
-                                    let _ = task_tx_for_parser.send(IndexTask {
-                                        doc: parsed,
+                            Err(e) => {
+                                warn!("Failed to parse file {:?}: {}", path, e);
+                                if is_real_failure(&root_for_parser, is_network_root) {
+                                    progress_for_parser.failed();
+                                    let _ = metadata_db_for_parser.record_parse_error(
+                                        &path,
+                                        ParseErrorKind::from_error(&e),
+                                        &e.to_string(),
                                         modified,
                                         size,
-                                        content_hash: hash,
-                                    });
-                                } else {
-                                    warn!("Failed to parse file {:?}", path);
+                                    );
                                 }
                             }
                         }
                     }
                 }
             }
+            metrics_for_parser.set_queued_chunks(0);
             drop(task_tx_for_parser);
         });
 
         // --- Stage 2c: Sequential batch writer (sync) ---
         // Tantivy writes must be sequential; this separate thread drains task_rx.
         let cancel_flag_for_writer = cancel_flag.clone();
+        let paused_for_writer = self.paused.clone();
+        let root_for_writer = checkpoint_root.clone();
         let writer_handle = tokio::task::spawn_blocking(move || {
             Self::process_writer_loop(
                 &task_rx,
                 filename_index_clone.as_ref(),
                 &indexer_clone,
                 &metadata_db_for_writer,
-                progress_tx_clone.as_ref(),
+                Some(&progress_tx_clone),
                 &total_files,
                 &cancel_flag_for_writer,
+                &paused_for_writer,
+                &root_for_writer,
+                &metrics,
             );
         });
 
         // Wait for all stages to complete
-        walker_handle
-            .await
-            .map_err(|e| crate::error::FlashError::index(format!("Walk task failed: {e}")))?
-            .map_err(|e| crate::error::FlashError::index(format!("Walk logic failed: {e}")))?;
         filter_handle
             .await
             .map_err(|e| crate::error::FlashError::index(format!("Filter task failed: {e}")))?;
@@ -564,6 +1731,21 @@ impl Scanner {
             let _ = f_index.commit();
         }
 
+        let pipeline = self.metrics.snapshot();
+        info!(
+            "Pipeline buffered at most {} KiB of its {} KiB budget, parsing waited on the writer {} time(s)",
+            pipeline.peak_buffered_bytes / 1024,
+            pipeline.budget_bytes / 1024,
+            pipeline.backpressure_waits
+        );
+
+        if root_lost.load(Ordering::Relaxed) {
+            self.indexer
+                .offline_roots()
+                .set_offline(&checkpoint_root, true);
+            return Err(offline_error(&checkpoint_root));
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,223 @@
+//! Connectivity checks for roots on network shares (UNC paths, NFS/SMB
+//! mounts). A share that drops mid-scan looks exactly like a tree that was
+//! deleted, so callers probe the root before trusting a missing file.
+
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How long a probe may block before the root counts as unreachable. Stat
+/// calls on a hung SMB or hard-mounted NFS share can stall for minutes.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Backoff between reconnect attempts; one attempt is made per entry.
+const RETRY_DELAYS: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(4),
+    Duration::from_secs(8),
+    Duration::from_secs(16),
+];
+
+/// Filesystem types in `/proc/mounts` that live on another machine.
+#[cfg(target_os = "linux")]
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afs",
+    "9p",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+/// Whether `path` is a UNC path or sits on a network mount.
+#[must_use]
+pub fn is_network_path(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+    if s.starts_with(r"\\") || s.starts_with("//") {
+        return true;
+    }
+    platform::is_remote_mount(path)
+}
+
+/// Whether `root` can be listed within [`PROBE_TIMEOUT`]. The check runs on
+/// a helper thread so a hung share can't stall the caller.
+#[must_use]
+pub fn is_reachable(root: &Path) -> bool {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let root = root.to_path_buf();
+    let spawned = std::thread::Builder::new()
+        .name("root-probe".into())
+        .spawn(move || {
+            let ok = std::fs::metadata(&root).is_ok_and(|m| m.is_dir())
+                && std::fs::read_dir(&root).is_ok();
+            let _ = tx.send(ok);
+        });
+    if spawned.is_err() {
+        return false;
+    }
+    rx.recv_timeout(PROBE_TIMEOUT).unwrap_or(false)
+}
+
+/// Probes `root`, retrying network roots with exponential backoff before
+/// giving up. Local roots get a single check. Returns early on cancel.
+pub fn wait_until_reachable(root: &Path, cancel_flag: &AtomicBool) -> bool {
+    if is_reachable(root) {
+        return true;
+    }
+    if !is_network_path(root) {
+        return false;
+    }
+    for (attempt, delay) in RETRY_DELAYS.iter().enumerate() {
+        warn!(
+            "{} is unreachable, retrying in {}s (attempt {}/{})",
+            root.display(),
+            delay.as_secs(),
+            attempt + 1,
+            RETRY_DELAYS.len()
+        );
+        std::thread::sleep(*delay);
+        if cancel_flag.load(Ordering::Relaxed) {
+            return false;
+        }
+        if is_reachable(root) {
+            info!("{} is reachable again", root.display());
+            return true;
+        }
+    }
+    false
+}
+
+/// Roots that failed their last connectivity check. Documents under them are
+/// kept in the index and reported as unavailable instead of being pruned.
+#[derive(Debug, Default)]
+pub struct OfflineRoots {
+    roots: RwLock<HashSet<String>>,
+}
+
+impl OfflineRoots {
+    /// Records the outcome of a probe. Returns true if the state changed.
+    pub fn set_offline(&self, root: &Path, offline: bool) -> bool {
+        let key = root.to_string_lossy().to_string();
+        let changed = if offline {
+            self.roots.write().insert(key)
+        } else {
+            self.roots.write().remove(&key)
+        };
+        if changed {
+            debug!(
+                "{} is now {}",
+                root.display(),
+                if offline { "offline" } else { "online" }
+            );
+        }
+        changed
+    }
+
+    #[must_use]
+    pub fn is_root_offline(&self, root: &Path) -> bool {
+        self.roots.read().contains(root.to_string_lossy().as_ref())
+    }
+
+    /// Whether `path` lies under any offline root.
+    #[must_use]
+    pub fn contains(&self, path: &Path) -> bool {
+        let roots = self.roots.read();
+        !roots.is_empty() && roots.iter().any(|root| path.starts_with(root))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.roots.read().is_empty()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::NETWORK_FS_TYPES;
+    use std::path::Path;
+
+    /// Finds the longest mount point containing `path` and checks its type.
+    pub fn is_remote_mount(path: &Path) -> bool {
+        let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+            return false;
+        };
+        mounts
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let _device = fields.next()?;
+                let mount_point = fields.next()?.replace("\\040", " ");
+                let fs_type = fields.next()?;
+                path.starts_with(&mount_point)
+                    .then(|| (mount_point.len(), fs_type.to_string()))
+            })
+            .max_by_key(|(len, _)| *len)
+            .is_some_and(|(_, fs_type)| NETWORK_FS_TYPES.contains(&fs_type.as_str()))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::{Component, Path};
+    use windows::Win32::Storage::FileSystem::GetDriveTypeW;
+    use windows::core::PCWSTR;
+
+    const DRIVE_REMOTE: u32 = 4;
+
+    /// Mapped drive letters report `DRIVE_REMOTE`.
+    pub fn is_remote_mount(path: &Path) -> bool {
+        let Some(Component::Prefix(prefix)) = path.components().next() else {
+            return false;
+        };
+        let mut drive = prefix.as_os_str().to_os_string();
+        drive.push("\\");
+        let wide: Vec<u16> = drive.encode_wide().chain(Some(0)).collect();
+        // SAFETY: `wide` is NUL-terminated and outlives the call.
+        unsafe { GetDriveTypeW(PCWSTR(wide.as_ptr())) == DRIVE_REMOTE }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use std::path::Path;
+
+    pub fn is_remote_mount(path: &Path) -> bool {
+        // autofs places network shares under /net and /Volumes on macOS
+        path.starts_with("/net") || path.starts_with("/Network")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unc_paths_are_network() {
+        assert!(is_network_path(Path::new(r"\\server\share\docs")));
+        assert!(is_network_path(Path::new("//server/share")));
+    }
+
+    #[test]
+    fn test_probe_and_offline_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(is_reachable(dir.path()));
+        let missing = dir.path().join("gone");
+        assert!(!wait_until_reachable(&missing, &AtomicBool::new(false)));
+
+        let offline = OfflineRoots::default();
+        assert!(offline.set_offline(&missing, true));
+        assert!(!offline.set_offline(&missing, true));
+        assert!(offline.contains(&missing.join("a.txt")));
+        assert!(!offline.contains(&dir.path().join("a.txt")));
+        assert!(offline.set_offline(&missing, false));
+        assert!(offline.is_empty());
+    }
+}
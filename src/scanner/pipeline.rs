@@ -0,0 +1,197 @@
+//! Memory budget of the scan pipeline. Paths and path chunks travel over
+//! bounded channels sized from the budget, and every parsed document holds a
+//! share of it until the writer has handed it to the index. A parser that
+//! finds the budget spent waits for the writer, so a huge tree can't pile up
+//! documents faster than they are written.
+
+use crate::models::PipelineStatus;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Rough memory of a queued path, allocation included
+const PATH_BYTES: usize = 256;
+
+/// Budget reservations are made in KiB so large budgets stay well within the
+/// semaphore's permit range.
+const UNIT: usize = 1024;
+
+/// Channel capacities and the document budget derived from a memory budget.
+/// An eighth of it goes to walked paths, an eighth to path chunks and the rest
+/// to parsed documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineBudget {
+    /// Paths waiting between the walker and the filter
+    pub path_capacity: usize,
+    /// Chunks waiting between the filter and the parser
+    pub chunk_capacity: usize,
+    /// Parsed documents waiting for the writer, in bytes of text
+    pub document_bytes: usize,
+}
+
+impl PipelineBudget {
+    #[must_use]
+    pub fn new(memory_mb: u32, chunk_size: usize) -> Self {
+        let total = usize::try_from(memory_mb)
+            .unwrap_or(usize::MAX)
+            .max(16)
+            .saturating_mul(1024 * 1024);
+        let eighth = total / 8;
+        Self {
+            path_capacity: (eighth / PATH_BYTES).max(1024),
+            chunk_capacity: (eighth / (PATH_BYTES * chunk_size.max(1))).max(2),
+            document_bytes: total - 2 * eighth,
+        }
+    }
+}
+
+/// Share of the document budget held by one parsed document. Dropping it
+/// returns the share.
+pub type DocumentPermit = OwnedSemaphorePermit;
+
+/// Live queue depths and budget use of the scans running through a
+/// [`super::Scanner`].
+#[derive(Debug)]
+pub struct PipelineMetrics {
+    budget: Arc<Semaphore>,
+    budget_units: usize,
+    queued_paths: AtomicUsize,
+    queued_chunks: AtomicUsize,
+    queued_documents: AtomicUsize,
+    peak_buffered_units: AtomicUsize,
+    backpressure_waits: AtomicU64,
+}
+
+impl PipelineMetrics {
+    #[must_use]
+    pub fn new(budget: &PipelineBudget) -> Self {
+        let units = (budget.document_bytes / UNIT).clamp(1, Semaphore::MAX_PERMITS);
+        Self {
+            budget: Arc::new(Semaphore::new(units)),
+            budget_units: units,
+            queued_paths: AtomicUsize::new(0),
+            queued_chunks: AtomicUsize::new(0),
+            queued_documents: AtomicUsize::new(0),
+            peak_buffered_units: AtomicUsize::new(0),
+            backpressure_waits: AtomicU64::new(0),
+        }
+    }
+
+    fn units_for(&self, bytes: usize) -> u32 {
+        let units = bytes.div_ceil(UNIT).clamp(1, self.budget_units);
+        u32::try_from(units).unwrap_or(u32::MAX)
+    }
+
+    /// Takes the share of a document of `bytes`, waiting while the budget is
+    /// spent. A document larger than the whole budget waits for all of it, so
+    /// it is written on its own. Returns `None` only if the budget was closed.
+    pub async fn reserve(&self, bytes: usize) -> Option<DocumentPermit> {
+        let units = self.units_for(bytes);
+        let permit = match self.budget.clone().try_acquire_many_owned(units) {
+            Ok(permit) => permit,
+            Err(_) => {
+                self.backpressure_waits.fetch_add(1, Ordering::Relaxed);
+                self.budget.clone().acquire_many_owned(units).await.ok()?
+            }
+        };
+        self.peak_buffered_units
+            .fetch_max(self.buffered_units(), Ordering::Relaxed);
+        Some(permit)
+    }
+
+    fn buffered_units(&self) -> usize {
+        self.budget_units
+            .saturating_sub(self.budget.available_permits())
+    }
+
+    /// Whether documents held by `permits` take up half the budget or more,
+    /// at which point the writer hands them to the index early instead of
+    /// stalling the parser until its batch is full.
+    #[must_use]
+    pub fn holds_too_much(&self, permits: &[DocumentPermit]) -> bool {
+        let held: usize = permits
+            .iter()
+            .map(|p| usize::try_from(p.num_permits()).unwrap_or(usize::MAX))
+            .sum();
+        held >= self.budget_units.div_ceil(2)
+    }
+
+    pub fn set_queued_paths(&self, depth: usize) {
+        self.queued_paths.store(depth, Ordering::Relaxed);
+    }
+
+    pub fn set_queued_chunks(&self, depth: usize) {
+        self.queued_chunks.store(depth, Ordering::Relaxed);
+    }
+
+    pub fn set_queued_documents(&self, depth: usize) {
+        self.queued_documents.store(depth, Ordering::Relaxed);
+    }
+
+    /// Clears the gauges, the peak and the wait count at the start of a scan.
+    pub fn reset(&self) {
+        self.set_queued_paths(0);
+        self.set_queued_chunks(0);
+        self.set_queued_documents(0);
+        self.backpressure_waits.store(0, Ordering::Relaxed);
+        self.peak_buffered_units
+            .store(self.buffered_units(), Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> PipelineStatus {
+        PipelineStatus {
+            queued_paths: self.queued_paths.load(Ordering::Relaxed),
+            queued_chunks: self.queued_chunks.load(Ordering::Relaxed),
+            queued_documents: self.queued_documents.load(Ordering::Relaxed),
+            buffered_bytes: (self.buffered_units() * UNIT) as u64,
+            peak_buffered_bytes: (self.peak_buffered_units.load(Ordering::Relaxed) * UNIT) as u64,
+            budget_bytes: (self.budget_units * UNIT) as u64,
+            backpressure_waits: self.backpressure_waits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_is_split_between_stages() {
+        let budget = PipelineBudget::new(256, 200);
+        assert_eq!(budget.path_capacity, 32 * 1024 * 1024 / PATH_BYTES);
+        assert_eq!(budget.chunk_capacity, 32 * 1024 * 1024 / (PATH_BYTES * 200));
+        assert_eq!(budget.document_bytes, 192 * 1024 * 1024);
+
+        // Tiny budgets still leave the channels room to move
+        let tiny = PipelineBudget::new(0, 200);
+        assert!(tiny.path_capacity >= 1024);
+        assert!(tiny.chunk_capacity >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_parser_waits_until_writer_releases() {
+        let metrics = Arc::new(PipelineMetrics::new(&PipelineBudget {
+            path_capacity: 1,
+            chunk_capacity: 1,
+            document_bytes: 8 * UNIT,
+        }));
+
+        let first = metrics.reserve(6 * UNIT).await.unwrap();
+        assert!(metrics.holds_too_much(std::slice::from_ref(&first)));
+        // Larger than the budget: clamped to all of it
+        let waiting = tokio::spawn({
+            let metrics = metrics.clone();
+            async move { metrics.reserve(100 * UNIT).await.map(|p| p.num_permits()) }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        assert_eq!(waiting.await.unwrap(), Some(8));
+        let status = metrics.snapshot();
+        assert_eq!(status.backpressure_waits, 1);
+        assert_eq!(status.peak_buffered_bytes, 8 * UNIT as u64);
+        assert_eq!(status.buffered_bytes, 0);
+    }
+}
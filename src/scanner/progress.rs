@@ -0,0 +1,343 @@
+//! Progress of scans running side by side. Each scan reports on a channel of
+//! its own, and its events are folded into one event per stage that sums
+//! every root of the current run, so the UI shows one bar per stage however
+//! many roots are scanned at once. A run lasts until its last scan finishes;
+//! the next scan starts a fresh one. The run's roots, their skip, access and
+//! error counts and a short throughput history stay queryable in between.
+
+use super::{ProgressEvent, ProgressType};
+use crate::models::{RootScanStatus, ScanOperation, ScanRunStatus, StageProgress};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Throughput is sampled at most this often
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Throughput samples kept, two minutes' worth
+const MAX_SAMPLES: usize = 120;
+
+/// Files a scan passed over, counted as it goes
+#[derive(Debug, Default)]
+struct ScanCounters {
+    skipped: AtomicUsize,
+    no_access: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+/// What one scan reports its progress through. The scan counts as finished
+/// once every clone is dropped.
+#[derive(Debug, Clone)]
+pub struct ScanProgress {
+    pub tx: flume::Sender<ProgressEvent>,
+    counters: Arc<ScanCounters>,
+}
+
+impl ScanProgress {
+    /// Counts a file left out as too large or unreadable.
+    pub fn skipped(&self) {
+        self.counters.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a file the user isn't allowed to read.
+    pub fn no_access(&self) {
+        self.counters.no_access.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a file that failed to parse.
+    pub fn failed(&self) {
+        self.counters.errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+struct RootState {
+    content: StageProgress,
+    filenames: StageProgress,
+    counters: Arc<ScanCounters>,
+    finished: bool,
+}
+
+impl RootState {
+    const fn stage(&mut self, ptype: ProgressType) -> &mut StageProgress {
+        match ptype {
+            ProgressType::Content => &mut self.content,
+            ProgressType::Filename => &mut self.filenames,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Run {
+    /// Scans of this run still reporting
+    active: usize,
+    operation: Option<ScanOperation>,
+    started_at: Option<u64>,
+    /// Root of the latest event
+    current_root: Option<PathBuf>,
+    roots: BTreeMap<PathBuf, RootState>,
+    throughput: VecDeque<f64>,
+    last_sample: Option<Instant>,
+}
+
+impl Run {
+    fn sample_throughput(&mut self) {
+        if self
+            .last_sample
+            .is_some_and(|at| at.elapsed() < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        let rate = self
+            .roots
+            .values()
+            .filter(|root| !root.finished)
+            .map(|root| root.content.files_per_second)
+            .sum();
+        if self.throughput.len() == MAX_SAMPLES {
+            self.throughput.pop_front();
+        }
+        self.throughput.push_back(rate);
+        self.last_sample = Some(Instant::now());
+    }
+}
+
+pub struct ProgressAggregator {
+    out: Option<flume::Sender<ProgressEvent>>,
+    run: Mutex<Run>,
+}
+
+impl ProgressAggregator {
+    /// Folds progress into events sent on `out`, if anything listens.
+    #[must_use]
+    pub fn new(out: Option<flume::Sender<ProgressEvent>>) -> Self {
+        Self {
+            out,
+            run: Mutex::new(Run::default()),
+        }
+    }
+
+    /// Starts tracking `operation` over `root` and returns what it reports
+    /// through.
+    pub fn track(self: &Arc<Self>, root: &Path, operation: ScanOperation) -> ScanProgress {
+        let (tx, rx) = flume::bounded::<ProgressEvent>(100);
+        let counters = Arc::new(ScanCounters::default());
+        {
+            let mut run = self.run.lock();
+            if run.active == 0 {
+                *run = Run {
+                    started_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .ok()
+                        .map(|since| since.as_secs()),
+                    ..Run::default()
+                };
+            }
+            run.active += 1;
+            run.operation = Some(operation);
+            run.roots.insert(
+                root.to_path_buf(),
+                RootState {
+                    counters: counters.clone(),
+                    ..RootState::default()
+                },
+            );
+        }
+        let this = self.clone();
+        let root = root.to_path_buf();
+        tokio::spawn(async move {
+            while let Ok(event) = rx.recv_async().await {
+                let combined = this.fold(&root, event);
+                if let Some(out) = &this.out {
+                    let _ = out.try_send(combined);
+                }
+            }
+            let mut run = this.run.lock();
+            run.active -= 1;
+            if run.active == 0 {
+                run.current_root = None;
+            }
+            if let Some(state) = run.roots.get_mut(&root) {
+                state.finished = true;
+                state.content.files_per_second = 0.0;
+            }
+        });
+        ScanProgress { tx, counters }
+    }
+
+    /// Records `event` for `root` and returns the sum over the run's roots in
+    /// the same stage.
+    fn fold(&self, root: &Path, event: ProgressEvent) -> ProgressEvent {
+        let mut run = self.run.lock();
+        run.current_root = Some(root.to_path_buf());
+        let stage = run
+            .roots
+            .entry(root.to_path_buf())
+            .or_default()
+            .stage(event.ptype);
+        // Batch announcements carry no count, which mustn't set the root back
+        if event.processed > 0 {
+            stage.processed = stage.processed.max(event.processed);
+            stage.files_per_second = event.files_per_second;
+        }
+        stage.total = event.total.max(stage.processed);
+        if event.ptype == ProgressType::Content {
+            run.sample_throughput();
+        }
+
+        let stages: Vec<StageProgress> = run
+            .roots
+            .values_mut()
+            .map(|root| *root.stage(event.ptype))
+            .filter(|stage| stage.total > 0)
+            .collect();
+        drop(run);
+        if stages.len() < 2 {
+            return event;
+        }
+
+        let processed: usize = stages.iter().map(|p| p.processed).sum();
+        let total: usize = stages.iter().map(|p| p.total).sum();
+        let files_per_second: f64 = stages.iter().map(|p| p.files_per_second).sum();
+        let eta_seconds = if files_per_second > 0.0 && total > processed {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            {
+                ((total - processed) as f64 / files_per_second).round() as u64
+            }
+        } else {
+            0
+        };
+        ProgressEvent {
+            total,
+            processed,
+            status: format!(
+                "{} ({processed} / {total} across {} roots)",
+                event.status,
+                stages.len()
+            ),
+            files_per_second,
+            eta_seconds,
+            ..event
+        }
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> ScanRunStatus {
+        let run = self.run.lock();
+        let roots = run
+            .roots
+            .iter()
+            .map(|(root, state)| RootScanStatus {
+                root: root.to_string_lossy().to_string(),
+                content: state.content,
+                filenames: state.filenames,
+                skipped: state.counters.skipped.load(Ordering::Relaxed),
+                no_access: state.counters.no_access.load(Ordering::Relaxed),
+                errors: state.counters.errors.load(Ordering::Relaxed),
+                finished: state.finished,
+            })
+            .collect();
+        ScanRunStatus {
+            running: run.active > 0,
+            operation: run.operation,
+            started_at: run.started_at,
+            current_root: run
+                .current_root
+                .as_ref()
+                .map(|root| root.to_string_lossy().to_string()),
+            roots,
+            throughput: run.throughput.iter().copied().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(processed: usize, total: usize, rate: f64) -> ProgressEvent {
+        ProgressEvent {
+            total,
+            processed,
+            current_file: String::new(),
+            status: "Indexing".to_string(),
+            ptype: ProgressType::Content,
+            files_per_second: rate,
+            eta_seconds: 0,
+            current_folder: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_roots_are_summed_per_stage() {
+        let progress = ProgressAggregator::new(None);
+        let a = Path::new("/a");
+        let b = Path::new("/b");
+
+        // A single root passes through untouched
+        assert_eq!(progress.fold(a, event(10, 100, 5.0)).processed, 10);
+
+        let combined = progress.fold(b, event(30, 300, 15.0));
+        assert_eq!((combined.processed, combined.total), (40, 400));
+        assert_eq!(combined.eta_seconds, 18);
+
+        // A batch announcement without a count keeps the root's progress
+        let combined = progress.fold(a, event(0, 100, 0.0));
+        assert_eq!(combined.processed, 40);
+
+        let mut filenames = event(5, 5, 0.0);
+        filenames.ptype = ProgressType::Filename;
+        assert_eq!(progress.fold(a, filenames).total, 5);
+
+        let run = progress.snapshot();
+        let (roots, throughput) = (run.roots, run.throughput);
+        assert_eq!(run.current_root.as_deref(), Some("/a"));
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].content.processed, 10);
+        assert_eq!(roots[0].filenames.total, 5);
+        // Sampled once, then held off until a second has passed
+        assert_eq!(throughput, [5.0]);
+    }
+
+    #[tokio::test]
+    async fn test_counts_belong_to_their_root_until_the_next_run() {
+        let progress = Arc::new(ProgressAggregator::new(None));
+        let scan = progress.track(Path::new("/a"), ScanOperation::Scan);
+        assert!(progress.snapshot().running);
+        scan.skipped();
+        scan.no_access();
+        scan.failed();
+        scan.failed();
+        let _ = scan.tx.send_async(event(3, 3, 1.0)).await;
+        drop(scan);
+
+        // The run stays visible once its scan is done
+        let finished = || {
+            progress
+                .snapshot()
+                .roots
+                .first()
+                .is_some_and(|r| r.finished)
+        };
+        while !finished() {
+            tokio::task::yield_now().await;
+        }
+        let run = progress.snapshot();
+        assert!(!run.running);
+        assert_eq!(run.operation, Some(ScanOperation::Scan));
+        let roots = run.roots;
+        assert_eq!(
+            (roots[0].skipped, roots[0].no_access, roots[0].errors),
+            (1, 1, 2)
+        );
+        assert_eq!(roots[0].content.processed, 3);
+
+        let _next = progress.track(Path::new("/b"), ScanOperation::Refresh);
+        let roots = progress.snapshot().roots;
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].root, "/b");
+    }
+}
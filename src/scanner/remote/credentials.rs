@@ -0,0 +1,72 @@
+//! Passwords for remote roots, kept in the OS keychain rather than in the
+//! settings file. One password is saved per protocol, server and user, so
+//! several roots on the same share log in the same way.
+
+use super::RemoteUrl;
+use std::io;
+
+const KEYCHAIN_SERVICE: &str = "com.flashsearch.remote";
+
+fn account(url: &RemoteUrl) -> String {
+    format!(
+        "{}://{}@{}:{}",
+        url.scheme.as_str(),
+        url.user.as_deref().unwrap_or_default(),
+        url.host,
+        url.port()
+    )
+}
+
+fn entry(url: &RemoteUrl) -> io::Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, &account(url)).map_err(io::Error::other)
+}
+
+/// Saves the password for logging in to the server of `url`.
+pub fn store(url: &RemoteUrl, password: &str) -> io::Result<()> {
+    entry(url)?.set_password(password).map_err(io::Error::other)
+}
+
+/// The password saved for the server of `url`, if any.
+pub fn password(url: &RemoteUrl) -> io::Result<Option<String>> {
+    match entry(url)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(io::Error::other(e)),
+    }
+}
+
+/// Deletes the password saved for the server of `url`, if any.
+pub fn forget(url: &RemoteUrl) -> io::Result<()> {
+    match entry(url)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(io::Error::other(e)),
+    }
+}
+
+/// Whether `a` and `b` log in with the same saved password.
+#[must_use]
+pub fn same_login(a: &RemoteUrl, b: &RemoteUrl) -> bool {
+    account(a) == account(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roots_on_one_share_share_a_login() {
+        let parse = |url| RemoteUrl::parse(url).unwrap();
+        assert!(same_login(
+            &parse("smb://bob@filer/team/docs"),
+            &parse("smb://bob@filer:445/team/archive")
+        ));
+        assert!(!same_login(
+            &parse("smb://bob@filer/team"),
+            &parse("smb://alice@filer/team")
+        ));
+        assert!(!same_login(
+            &parse("webdav://bob@filer/team"),
+            &parse("webdavs://bob@filer/team")
+        ));
+    }
+}
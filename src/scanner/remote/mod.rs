@@ -0,0 +1,462 @@
+//! Roots on other machines, given as URLs such as `ssh://user@host/path`,
+//! `webdavs://user@host/path` or `smb://user@server/share/path`. A
+//! [`Transport`] lists and reads their files; scans copy each changed file to
+//! a temporary local file and hand it to the usual parsers. Documents keep the
+//! URL of the remote file as their path. Passwords live in the OS keychain,
+//! see [`credentials`]. SMB shares are reached on Windows only; elsewhere
+//! `smb://` roots are reported as unsupported.
+
+pub mod credentials;
+mod sftp;
+mod smb;
+mod webdav;
+
+use super::drive_scanner::DEFAULT_MAX_DEPTH;
+use super::exclude::ExcludeMatcher;
+use crate::settings::IndexRoot;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+/// Protocols remote roots can be reached over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// `ssh://`, read over SFTP
+    Ssh,
+    Sftp,
+    /// `webdav://`, WebDAV over plain HTTP
+    Webdav,
+    /// `webdavs://`, WebDAV over HTTPS
+    Webdavs,
+    /// `smb://`, a Windows or Samba file share
+    Smb,
+}
+
+impl Scheme {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ssh => "ssh",
+            Self::Sftp => "sftp",
+            Self::Webdav => "webdav",
+            Self::Webdavs => "webdavs",
+            Self::Smb => "smb",
+        }
+    }
+
+    /// Whether servers can be reached over the scheme on this platform.
+    #[must_use]
+    pub const fn is_supported(self) -> bool {
+        !matches!(self, Self::Smb) || cfg!(windows)
+    }
+
+    const fn default_port(self) -> u16 {
+        match self {
+            Self::Ssh | Self::Sftp => 22,
+            Self::Webdav => 80,
+            Self::Webdavs => 443,
+            Self::Smb => 445,
+        }
+    }
+}
+
+/// A parsed remote root or file URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    pub scheme: Scheme,
+    /// Login name; the local user name when left out. SMB logins in a domain
+    /// are written `DOMAIN;user`
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Absolute path on the server, without a trailing slash
+    pub path: String,
+}
+
+impl RemoteUrl {
+    /// Parses `scheme://[user@]host[:port][/path]`, decoding `%XX` escapes in
+    /// the user and path. Local paths and unknown schemes give `None`.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let (scheme, rest) = input.trim().split_once("://")?;
+        let scheme = match scheme.to_ascii_lowercase().as_str() {
+            "ssh" => Scheme::Ssh,
+            "sftp" => Scheme::Sftp,
+            "webdav" => Scheme::Webdav,
+            "webdavs" => Scheme::Webdavs,
+            "smb" => Scheme::Smb,
+            _ => return None,
+        };
+        let (authority, path) = rest
+            .find('/')
+            .map_or((rest, "/"), |slash| rest.split_at(slash));
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host)) => (Some(percent_decode(user)).filter(|u| !u.is_empty()), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => (host, Some(port.parse().ok()?)),
+            _ => (host_port, None),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return None;
+        }
+        let path = match path.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        Some(Self {
+            scheme,
+            user,
+            host: host.to_string(),
+            port,
+            path: percent_decode(path),
+        })
+    }
+
+    #[must_use]
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or_else(|| self.scheme.default_port())
+    }
+
+    /// URL of `path` on the same server, as stored for its documents.
+    #[must_use]
+    pub fn location(&self, path: &str) -> String {
+        Self {
+            path: path.to_string(),
+            ..self.clone()
+        }
+        .to_string()
+    }
+}
+
+impl fmt::Display for RemoteUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://", self.scheme.as_str())?;
+        if let Some(user) = &self.user {
+            write!(f, "{user}@")?;
+        }
+        if self.host.contains(':') {
+            write!(f, "[{}]", self.host)?;
+        } else {
+            f.write_str(&self.host)?;
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        f.write_str(&self.path)
+    }
+}
+
+/// Whether the root `path` is a remote URL rather than a local folder.
+#[must_use]
+pub fn is_remote(path: &str) -> bool {
+    RemoteUrl::parse(path).is_some()
+}
+
+/// Decodes `%XX` escapes; malformed ones are kept as written.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Escapes every byte but unreserved characters and those in `keep`.
+fn percent_encode(input: &str, keep: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+            out.push(char::from(byte));
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// A file or folder a [`Transport`] listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteEntry {
+    /// Absolute path on the server
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// Seconds since the Unix epoch
+    pub modified: u64,
+}
+
+impl RemoteEntry {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Lists and reads files on a remote server. Calls block, so scans run them
+/// on the blocking pool.
+pub trait Transport: Send {
+    /// Folders and regular files directly in `dir`, without `.` and `..`.
+    fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>>;
+
+    /// Copies the file at `path` into `out`, returning the bytes copied.
+    fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64>;
+}
+
+/// Connects to the server of `url` and logs in, with the password saved for
+/// it if there is one.
+pub fn connect(url: &RemoteUrl) -> io::Result<Box<dyn Transport>> {
+    match url.scheme {
+        Scheme::Ssh | Scheme::Sftp => Ok(Box::new(sftp::SftpTransport::connect(url)?)),
+        Scheme::Webdav | Scheme::Webdavs => Ok(Box::new(webdav::WebDavTransport::connect(url)?)),
+        Scheme::Smb => Ok(Box::new(smb::SmbTransport::connect(url)?)),
+    }
+}
+
+/// Whether the remote root `root` can be logged in to and listed right now.
+#[must_use]
+pub fn is_reachable(root: &str) -> bool {
+    RemoteUrl::parse(root).is_some_and(|url| {
+        connect(&url)
+            .and_then(|mut transport| transport.list(&url.path))
+            .is_ok()
+    })
+}
+
+/// Lists every folder and file under `url` that `root`'s profile keeps:
+/// global and root excludes, include globs, depth and hidden files apply as
+/// they do to a local walk. Folders that can't be listed are skipped, except
+/// the root itself. A cancelled walk returns what it found so far.
+pub fn walk(
+    transport: &mut dyn Transport,
+    url: &RemoteUrl,
+    root: &IndexRoot,
+    exclude_patterns: &[String],
+    cancel_flag: &AtomicBool,
+) -> io::Result<Vec<RemoteEntry>> {
+    let base = Path::new(&url.path);
+    let excludes = ExcludeMatcher::new(base, exclude_patterns.iter().chain(&root.exclude));
+    let mut include = ignore::overrides::OverrideBuilder::new(base);
+    for pattern in &root.include {
+        if let Err(e) = include.add(pattern) {
+            warn!("Invalid include pattern '{}': {}", pattern, e);
+        }
+    }
+    let include = include
+        .build()
+        .unwrap_or_else(|_| ignore::overrides::Override::empty());
+    let max_depth = root.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+
+    let mut found = Vec::new();
+    let mut pending = vec![(url.path.clone(), 0usize)];
+    while let Some((dir, depth)) = pending.pop() {
+        if cancel_flag.load(Ordering::Relaxed) {
+            break;
+        }
+        let entries = match transport.list(&dir) {
+            Ok(entries) => entries,
+            Err(e) if depth == 0 => return Err(e),
+            Err(e) => {
+                warn!("Skipping unreadable remote folder {}: {}", dir, e);
+                continue;
+            }
+        };
+        for entry in entries {
+            let path = Path::new(&entry.path);
+            if (!root.include_hidden && entry.name().starts_with('.'))
+                || excludes.is_excluded(path, entry.is_dir)
+            {
+                continue;
+            }
+            if entry.is_dir {
+                if depth + 1 < max_depth {
+                    pending.push((entry.path.clone(), depth + 1));
+                }
+                found.push(entry);
+            } else if !include.matched(path, false).is_ignore() {
+                found.push(entry);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Copies the remote file at `path` to a temporary local file with the same
+/// extension, since parsers pick their format by it. Returns the copy and the
+/// hash of its bytes.
+pub fn fetch(
+    transport: &mut dyn Transport,
+    path: &str,
+) -> io::Result<(tempfile::NamedTempFile, [u8; 32])> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut file = tempfile::Builder::new()
+        .prefix("flash-remote-")
+        .suffix(&extension)
+        .tempfile()?;
+    let hash: [u8; 32] = {
+        let mut out = HashingWriter {
+            inner: io::BufWriter::new(file.as_file_mut()),
+            hasher: blake3::Hasher::new(),
+        };
+        transport.read(path, &mut out)?;
+        out.inner.flush()?;
+        out.hasher.finalize().into()
+    };
+    Ok((file, hash))
+}
+
+/// Hashes the bytes it passes on, so a download is hashed without reading
+/// the copy back.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Serves a fixed tree from memory.
+    struct FakeTransport(HashMap<String, Vec<RemoteEntry>>);
+
+    impl Transport for FakeTransport {
+        fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>> {
+            self.0
+                .get(dir)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+
+        fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64> {
+            out.write_all(path.as_bytes())?;
+            Ok(path.len() as u64)
+        }
+    }
+
+    fn entry(path: &str, is_dir: bool) -> RemoteEntry {
+        RemoteEntry {
+            path: path.to_string(),
+            is_dir,
+            size: 1,
+            modified: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_urls() {
+        let url = RemoteUrl::parse("ssh://alice@nas.local:2222/srv/docs/").unwrap();
+        assert_eq!(url.scheme, Scheme::Ssh);
+        assert_eq!(url.user.as_deref(), Some("alice"));
+        assert_eq!(url.host, "nas.local");
+        assert_eq!(url.port(), 2222);
+        assert_eq!(url.path, "/srv/docs");
+        assert_eq!(url.to_string(), "ssh://alice@nas.local:2222/srv/docs");
+        assert_eq!(
+            url.location("/srv/docs/a.pdf"),
+            "ssh://alice@nas.local:2222/srv/docs/a.pdf"
+        );
+
+        let bare = RemoteUrl::parse("sftp://server").unwrap();
+        assert_eq!(bare.user, None);
+        assert_eq!(bare.port(), 22);
+        assert_eq!(bare.path, "/");
+
+        assert!(!is_remote("/home/alice/docs"));
+        assert!(!is_remote(r"C:\Users\alice"));
+        assert!(!is_remote("ftp://server/docs"));
+
+        let share = RemoteUrl::parse("smb://CORP;bob@filer/Team%20Share/Q3").unwrap();
+        assert_eq!(share.user.as_deref(), Some("CORP;bob"));
+        assert_eq!(share.path, "/Team Share/Q3");
+        assert_eq!(share.port(), 445);
+        assert_eq!(
+            RemoteUrl::parse("webdavs://cloud.example.com")
+                .unwrap()
+                .port(),
+            443
+        );
+        assert!(!is_remote("ssh:///docs"));
+    }
+
+    #[test]
+    fn test_walk_applies_root_profile() {
+        let tree = HashMap::from([
+            (
+                "/srv".to_string(),
+                vec![
+                    entry("/srv/notes.md", false),
+                    entry("/srv/build.log", false),
+                    entry("/srv/.cache", true),
+                    entry("/srv/target", true),
+                    entry("/srv/docs", true),
+                ],
+            ),
+            (
+                "/srv/.cache".to_string(),
+                vec![entry("/srv/.cache/x.md", false)],
+            ),
+            (
+                "/srv/target".to_string(),
+                vec![entry("/srv/target/y.md", false)],
+            ),
+            (
+                "/srv/docs".to_string(),
+                vec![entry("/srv/docs/z.md", false)],
+            ),
+        ]);
+        let url = RemoteUrl::parse("ssh://host/srv").unwrap();
+        let root = IndexRoot {
+            include: vec!["*.md".to_string()],
+            include_hidden: false,
+            ..IndexRoot::new(url.to_string())
+        };
+        let cancel = AtomicBool::new(false);
+
+        let mut transport = FakeTransport(tree);
+        let found = walk(
+            &mut transport,
+            &url,
+            &root,
+            &["target/".to_string()],
+            &cancel,
+        )
+        .unwrap();
+        let mut paths: Vec<_> = found.iter().map(|e| e.path.as_str()).collect();
+        paths.sort_unstable();
+        assert_eq!(paths, ["/srv/docs", "/srv/docs/z.md", "/srv/notes.md"]);
+
+        let missing = RemoteUrl::parse("ssh://host/gone").unwrap();
+        assert!(walk(&mut transport, &missing, &root, &[], &cancel).is_err());
+    }
+}
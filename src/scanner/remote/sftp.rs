@@ -0,0 +1,133 @@
+//! SFTP transport for `ssh://` and `sftp://` roots. The server must already be
+//! in `~/.ssh/known_hosts`. Logging in tries the SSH agent and the default key
+//! files, as `ssh` itself would, then the password saved in the keychain.
+
+use super::{RemoteEntry, RemoteUrl, Transport, credentials};
+use ssh2::{CheckResult, KnownHostFileKind, Session, Sftp};
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long connecting and each request may take before the server counts
+/// as unreachable
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Key files tried, in order, when the agent has none the server accepts
+const DEFAULT_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+pub struct SftpTransport {
+    // The channel needs its session alive
+    _session: Session,
+    sftp: Sftp,
+}
+
+impl SftpTransport {
+    pub fn connect(url: &RemoteUrl) -> io::Result<Self> {
+        let address = (url.host.as_str(), url.port())
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host has no address"))?;
+        let tcp = TcpStream::connect_timeout(&address, TIMEOUT)?;
+
+        let mut session = Session::new()?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(u32::try_from(TIMEOUT.as_millis()).unwrap_or(u32::MAX));
+        session.handshake()?;
+        verify_host_key(&session, url)?;
+
+        let user = url.user.clone().unwrap_or_else(local_user);
+        authenticate(&session, &user, url)?;
+        let sftp = session.sftp()?;
+        Ok(Self {
+            _session: session,
+            sftp,
+        })
+    }
+}
+
+impl Transport for SftpTransport {
+    fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>> {
+        let entries = self.sftp.readdir(Path::new(dir))?;
+        Ok(entries
+            .into_iter()
+            .filter(|(_, stat)| stat.is_dir() || stat.is_file())
+            .filter_map(|(path, stat)| {
+                // Joined paths use the local separator; the server's is `/`
+                let name = path.file_name()?.to_string_lossy().to_string();
+                Some(RemoteEntry {
+                    path: format!("{}/{name}", dir.trim_end_matches('/')),
+                    is_dir: stat.is_dir(),
+                    size: stat.size.unwrap_or(0),
+                    modified: stat.mtime.unwrap_or(0),
+                })
+            })
+            .collect())
+    }
+
+    fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64> {
+        let mut file = self.sftp.open(Path::new(path))?;
+        io::copy(&mut file, out)
+    }
+}
+
+/// Accepts only servers whose key `known_hosts` already lists, so a scan
+/// never trusts a key on its own.
+fn verify_host_key(session: &Session, url: &RemoteUrl) -> io::Result<()> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| io::Error::other("server sent no host key"))?;
+    let mut known = session.known_hosts()?;
+    if let Some(file) = ssh_dir().map(|dir| dir.join("known_hosts"))
+        && file.exists()
+    {
+        known.read_file(&file, KnownHostFileKind::OpenSSH)?;
+    }
+    match known.check_port(&url.host, url.port(), key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("the host key of {} changed; check the server", url.host),
+        )),
+        CheckResult::NotFound | CheckResult::Failure => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a known host; connect once with ssh to trust it",
+                url.host
+            ),
+        )),
+    }
+}
+
+fn authenticate(session: &Session, user: &str, url: &RemoteUrl) -> io::Result<()> {
+    if session.userauth_agent(user).is_ok() && session.authenticated() {
+        return Ok(());
+    }
+    let keys = ssh_dir()
+        .into_iter()
+        .flat_map(|dir| DEFAULT_KEYS.iter().map(move |name| dir.join(name)))
+        .filter(|key| key.exists());
+    for key in keys {
+        if session.userauth_pubkey_file(user, None, &key, None).is_ok() && session.authenticated() {
+            return Ok(());
+        }
+    }
+    if let Some(password) = credentials::password(url)? {
+        session.userauth_password(user, &password)?;
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("no SSH key or saved password was accepted for {user}"),
+    ))
+}
+
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh"))
+}
+
+fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
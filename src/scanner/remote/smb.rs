@@ -0,0 +1,223 @@
+//! SMB transport for `smb://[DOMAIN;]user@server/share/path` roots on Windows
+//! and Samba file servers. Windows logs in to the share itself and the files
+//! are then read through its UNC path; the password comes from the keychain.
+//! Elsewhere the share has to be mounted and added as a local folder.
+
+use super::{RemoteEntry, RemoteUrl, Transport, credentials};
+use std::io::{self, Write};
+
+pub struct SmbTransport {
+    /// `/share`, which every path the scanner passes starts with
+    share: String,
+    connection: platform::Share,
+}
+
+impl SmbTransport {
+    pub fn connect(url: &RemoteUrl) -> io::Result<Self> {
+        let (share, _) = split_share(&url.path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the URL names no share; use smb://server/share/folder",
+            )
+        })?;
+        let (domain, user) = match url.user.as_deref() {
+            Some(login) => match login.split_once(';') {
+                Some((domain, user)) => (Some(domain), Some(user)),
+                None => (None, Some(login)),
+            },
+            None => (None, None),
+        };
+        let password = credentials::password(url)?;
+        let connection = platform::Share::connect(
+            &url.host,
+            url.port(),
+            share,
+            domain,
+            user,
+            password.as_deref(),
+        )?;
+        Ok(Self {
+            share: format!("/{share}"),
+            connection,
+        })
+    }
+
+    /// The path of `path` inside the share, `""` for the share itself.
+    fn inner<'a>(&self, path: &'a str) -> io::Result<&'a str> {
+        path.strip_prefix(&self.share)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "path is outside the share"))
+    }
+}
+
+impl Transport for SmbTransport {
+    fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>> {
+        let inner = self.inner(dir)?;
+        let mut entries = self.connection.list(inner)?;
+        // The platform lists bare names
+        for entry in &mut entries {
+            entry.path = format!("{}/{}", dir.trim_end_matches('/'), entry.path);
+        }
+        Ok(entries)
+    }
+
+    fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64> {
+        let inner = self.inner(path)?;
+        self.connection.read(inner, out)
+    }
+}
+
+/// Splits `/share/rest` into the share name and the rest of the path.
+fn split_share(path: &str) -> Option<(&str, &str)> {
+    let path = path.strip_prefix('/')?;
+    let (share, rest) = path
+        .find('/')
+        .map_or((path, ""), |slash| path.split_at(slash));
+    (!share.is_empty()).then_some((share, rest))
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::RemoteEntry;
+    use std::io::{self, Write};
+    use std::path::PathBuf;
+    use std::time::UNIX_EPOCH;
+    use windows::Win32::Foundation::{ERROR_SESSION_CREDENTIAL_CONFLICT, NO_ERROR};
+    use windows::Win32::NetworkManagement::WNet::{
+        CONNECT_TEMPORARY, NETRESOURCEW, RESOURCETYPE_DISK, WNetAddConnection2W,
+    };
+    use windows::core::{PCWSTR, PWSTR};
+
+    pub struct Share {
+        /// `\\server\share`
+        unc: PathBuf,
+    }
+
+    impl Share {
+        pub fn connect(
+            server: &str,
+            _port: u16,
+            share: &str,
+            domain: Option<&str>,
+            user: Option<&str>,
+            password: Option<&str>,
+        ) -> io::Result<Self> {
+            let unc = format!(r"\\{server}\{share}");
+            let mut remote = wide(&unc);
+            let resource = NETRESOURCEW {
+                dwType: RESOURCETYPE_DISK,
+                lpRemoteName: PWSTR(remote.as_mut_ptr()),
+                ..Default::default()
+            };
+            let user = user.map(|user| match domain {
+                Some(domain) => wide(&format!(r"{domain}\{user}")),
+                None => wide(user),
+            });
+            let password = password.map(wide);
+            let as_param = |value: &Option<Vec<u16>>| {
+                value
+                    .as_ref()
+                    .map_or(PCWSTR::null(), |value| PCWSTR(value.as_ptr()))
+            };
+            // SAFETY: every string is NUL-terminated and outlives the call.
+            let result = unsafe {
+                WNetAddConnection2W(
+                    &resource,
+                    as_param(&password),
+                    as_param(&user),
+                    CONNECT_TEMPORARY,
+                )
+            };
+            // A conflict means Windows is already logged in to the server,
+            // which the share can be read with just as well
+            if result != NO_ERROR && result != ERROR_SESSION_CREDENTIAL_CONFLICT {
+                return Err(io::Error::other(windows::core::Error::from_hresult(
+                    result.to_hresult(),
+                )));
+            }
+            Ok(Self {
+                unc: PathBuf::from(unc),
+            })
+        }
+
+        pub fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>> {
+            let mut entries = Vec::new();
+            for entry in std::fs::read_dir(self.local(dir))? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if !metadata.is_dir() && !metadata.is_file() {
+                    continue;
+                }
+                entries.push(RemoteEntry {
+                    path: entry.file_name().to_string_lossy().to_string(),
+                    is_dir: metadata.is_dir(),
+                    size: metadata.len(),
+                    modified: metadata
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map_or(0, |age| age.as_secs()),
+                });
+            }
+            Ok(entries)
+        }
+
+        pub fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64> {
+            io::copy(&mut std::fs::File::open(self.local(path))?, out)
+        }
+
+        fn local(&self, path: &str) -> PathBuf {
+            path.split('/')
+                .filter(|part| !part.is_empty())
+                .fold(self.unc.clone(), |local, part| local.join(part))
+        }
+    }
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(Some(0)).collect()
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::RemoteEntry;
+    use std::io::{self, Write};
+
+    pub enum Share {}
+
+    impl Share {
+        pub fn connect(
+            _server: &str,
+            _port: u16,
+            _share: &str,
+            _domain: Option<&str>,
+            _user: Option<&str>,
+            _password: Option<&str>,
+        ) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SMB URLs are only supported on Windows; mount the share and add the mounted folder",
+            ))
+        }
+
+        pub fn list(&mut self, _dir: &str) -> io::Result<Vec<RemoteEntry>> {
+            match *self {}
+        }
+
+        pub fn read(&mut self, _path: &str, _out: &mut dyn Write) -> io::Result<u64> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_share() {
+        assert_eq!(split_share("/team"), Some(("team", "")));
+        assert_eq!(split_share("/team/docs/q3"), Some(("team", "/docs/q3")));
+        assert_eq!(split_share("/"), None);
+    }
+}
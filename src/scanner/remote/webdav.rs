@@ -0,0 +1,262 @@
+//! WebDAV transport for `webdav://` and `webdavs://` roots, as served by
+//! Nextcloud, ownCloud, NAS boxes and most web servers. Folders are listed with
+//! `PROPFIND` and files read with `GET`; a user in the URL logs in with Basic
+//! authentication and the password saved in the keychain.
+
+use super::{
+    RemoteEntry, RemoteUrl, Scheme, Transport, credentials, percent_decode, percent_encode,
+};
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use std::io::{self, Write};
+use std::time::Duration;
+use ureq::Agent;
+use ureq::http::Request;
+
+/// How long connecting and waiting for each response may take before the
+/// server counts as unreachable
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Largest folder listing read, so a broken server can't exhaust memory
+const MAX_LISTING: u64 = 64 * 1024 * 1024;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<propfind xmlns="DAV:"><prop><resourcetype/><getcontentlength/><getlastmodified/></prop></propfind>"#;
+
+pub struct WebDavTransport {
+    agent: Agent,
+    /// `http[s]://[user:password@]host:port`, which request paths are appended to
+    base: String,
+}
+
+impl WebDavTransport {
+    pub fn connect(url: &RemoteUrl) -> io::Result<Self> {
+        let agent = Agent::config_builder()
+            .allow_non_standard_methods(true)
+            .timeout_connect(Some(TIMEOUT))
+            .timeout_recv_response(Some(TIMEOUT))
+            .build()
+            .new_agent();
+        let protocol = if url.scheme == Scheme::Webdavs {
+            "https"
+        } else {
+            "http"
+        };
+        let login = match &url.user {
+            Some(user) => {
+                let password = credentials::password(url)?.unwrap_or_default();
+                format!(
+                    "{}:{}@",
+                    percent_encode(user, b""),
+                    percent_encode(&password, b"")
+                )
+            }
+            None => String::new(),
+        };
+        let host = if url.host.contains(':') {
+            format!("[{}]", url.host)
+        } else {
+            url.host.clone()
+        };
+        let transport = Self {
+            agent,
+            base: format!("{protocol}://{login}{host}:{}", url.port()),
+        };
+        // Checks the login before a scan relies on it
+        transport.propfind(&url.path, "0")?;
+        Ok(transport)
+    }
+
+    fn uri(&self, path: &str) -> String {
+        format!("{}{}", self.base, percent_encode(path, b"/"))
+    }
+
+    fn propfind(&self, path: &str, depth: &str) -> io::Result<String> {
+        let request = Request::builder()
+            .method("PROPFIND")
+            .uri(self.uri(path))
+            .header("Depth", depth)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(PROPFIND_BODY)
+            .map_err(io::Error::other)?;
+        let mut response = self.agent.run(request).map_err(http_error)?;
+        response
+            .body_mut()
+            .with_config()
+            .limit(MAX_LISTING)
+            .read_to_string()
+            .map_err(http_error)
+    }
+}
+
+impl Transport for WebDavTransport {
+    fn list(&mut self, dir: &str) -> io::Result<Vec<RemoteEntry>> {
+        let listing = self.propfind(dir, "1")?;
+        let dir = dir.trim_end_matches('/');
+        // The folder itself is the first entry of its own listing
+        Ok(parse_multistatus(&listing)?
+            .into_iter()
+            .filter(|entry| entry.path != dir && !entry.path.is_empty())
+            .collect())
+    }
+
+    fn read(&mut self, path: &str, out: &mut dyn Write) -> io::Result<u64> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(self.uri(path))
+            .body(())
+            .map_err(io::Error::other)?;
+        let response = self.agent.run(request).map_err(http_error)?;
+        io::copy(&mut response.into_body().into_reader(), out)
+    }
+}
+
+/// Turns refused logins and missing paths into the error kinds the scanner
+/// reports for any other transport.
+fn http_error(error: ureq::Error) -> io::Error {
+    match error {
+        ureq::Error::StatusCode(401 | 403) => io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the server refused the login; check the saved password",
+        ),
+        ureq::Error::StatusCode(404) => io::Error::new(
+            io::ErrorKind::NotFound,
+            "the folder doesn't exist on the server",
+        ),
+        other => other.into_io(),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Href,
+    Length,
+    Modified,
+}
+
+/// Reads the entries of a `207 Multi-Status` reply to `PROPFIND`.
+fn parse_multistatus(xml: &str) -> io::Result<Vec<RemoteEntry>> {
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut current: Option<RemoteEntry> = None;
+    let mut field = None;
+    let mut text = String::new();
+    loop {
+        match reader.read_event().map_err(io::Error::other)? {
+            Event::Start(tag) => {
+                field = match tag.local_name().as_ref() {
+                    b"response" => {
+                        current = Some(RemoteEntry {
+                            path: String::new(),
+                            is_dir: false,
+                            size: 0,
+                            modified: 0,
+                        });
+                        None
+                    }
+                    b"href" => Some(Field::Href),
+                    b"getcontentlength" => Some(Field::Length),
+                    b"getlastmodified" => Some(Field::Modified),
+                    b"collection" => {
+                        if let Some(entry) = current.as_mut() {
+                            entry.is_dir = true;
+                        }
+                        None
+                    }
+                    _ => None,
+                };
+                text.clear();
+            }
+            Event::Empty(tag) if tag.local_name().as_ref() == b"collection" => {
+                if let Some(entry) = current.as_mut() {
+                    entry.is_dir = true;
+                }
+            }
+            Event::Text(content) if field.is_some() => {
+                text.push_str(&content.decode().map_err(io::Error::other)?);
+            }
+            Event::GeneralRef(reference) if field.is_some() => {
+                if let Some(ch) = reference.resolve_char_ref().map_err(io::Error::other)? {
+                    text.push(ch);
+                } else {
+                    let name = reference.decode().map_err(io::Error::other)?;
+                    text.push_str(resolve_predefined_entity(&name).unwrap_or_default());
+                }
+            }
+            Event::End(tag) => {
+                if tag.local_name().as_ref() == b"response" {
+                    entries.extend(current.take());
+                } else if let (Some(field), Some(entry)) = (field.take(), current.as_mut()) {
+                    let value = text.trim();
+                    match field {
+                        Field::Href => entry.path = href_path(value),
+                        Field::Length => entry.size = value.parse().unwrap_or(0),
+                        Field::Modified => {
+                            entry.modified = jiff::fmt::rfc2822::parse(value)
+                                .ok()
+                                .and_then(|time| u64::try_from(time.timestamp().as_second()).ok())
+                                .unwrap_or(0);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// The decoded server path of an `href`, which may be a full URL or a path.
+fn href_path(href: &str) -> String {
+    let path = match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |slash| &rest[slash..]),
+        None => href,
+    };
+    let path = percent_decode(path);
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/docs/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://cloud.example.com/dav/docs/Q3%20Report.pdf</d:href>
+    <d:propstat><d:prop>
+      <d:resourcetype/>
+      <d:getcontentlength>2048</d:getcontentlength>
+      <d:getlastmodified>Tue, 15 Nov 1994 08:12:31 GMT</d:getlastmodified>
+    </d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/docs/R&amp;D/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+        let entries = parse_multistatus(xml).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "/dav/docs");
+        assert!(entries[0].is_dir);
+
+        assert_eq!(entries[1].path, "/dav/docs/Q3 Report.pdf");
+        assert!(!entries[1].is_dir);
+        assert_eq!(entries[1].size, 2048);
+        assert_eq!(entries[1].modified, 784_887_151);
+
+        assert_eq!(entries[2].path, "/dav/docs/R&D");
+        assert!(entries[2].is_dir);
+    }
+}
@@ -0,0 +1,109 @@
+//! Sensitive file rules shared by scans and live watcher events, so a key or
+//! password store skipped by a scan is never parsed because it changed.
+
+use crate::settings::{AppSettings, SensitiveFiles};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::Path;
+use tracing::warn;
+
+/// The sensitive patterns compiled for matching. A pattern with a `/` is
+/// matched against the whole path, any other against the file name, both
+/// ignoring case.
+#[derive(Debug, Clone)]
+pub struct SensitiveMatcher {
+    mode: SensitiveFiles,
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl SensitiveMatcher {
+    #[must_use]
+    pub fn new<'a>(mode: SensitiveFiles, patterns: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        if mode != SensitiveFiles::Index {
+            for pattern in patterns {
+                let trimmed = pattern.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                match GlobBuilder::new(trimmed).case_insensitive(true).build() {
+                    Ok(glob) if trimmed.contains('/') => {
+                        paths.add(glob);
+                    }
+                    Ok(glob) => {
+                        names.add(glob);
+                    }
+                    Err(e) => warn!("Invalid sensitive pattern '{}': {}", pattern, e),
+                }
+            }
+        }
+        Self {
+            mode,
+            names: names.build().unwrap_or_default(),
+            paths: paths.build().unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self::new(settings.sensitive_files, &settings.sensitive_patterns)
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| self.names.is_match(name))
+            || (!self.paths.is_empty()
+                && self
+                    .paths
+                    .is_match(path.to_string_lossy().replace('\\', "/")))
+    }
+
+    /// Whether `path` is kept out of the index altogether.
+    #[must_use]
+    pub fn skips(&self, path: &Path) -> bool {
+        self.mode == SensitiveFiles::Skip && self.matches(path)
+    }
+
+    /// Whether `path` is indexed by name and metadata only.
+    #[must_use]
+    pub fn hides_content(&self, path: &Path) -> bool {
+        self.mode == SensitiveFiles::FilenameOnly && self.matches(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_patterns_catch_secrets() {
+        let settings = AppSettings::default();
+        let matcher = SensitiveMatcher::from_settings(&settings);
+
+        assert!(matcher.hides_content(Path::new("/home/me/.ssh/id_ed25519")));
+        assert!(matcher.hides_content(Path::new("/srv/app/.env")));
+        assert!(matcher.hides_content(Path::new("/srv/app/.env.production")));
+        assert!(matcher.hides_content(Path::new("/docs/Passwords.kdbx")));
+        assert!(matcher.hides_content(Path::new("/docs/old PASSWORD list.txt")));
+        assert!(matcher.hides_content(Path::new("/certs/server.KEY")));
+
+        assert!(!matcher.hides_content(Path::new("/docs/report.pdf")));
+        assert!(!matcher.hides_content(Path::new("/src/keyboard.rs")));
+        assert!(!matcher.skips(Path::new("/srv/app/.env")));
+    }
+
+    #[test]
+    fn test_modes_and_path_patterns() {
+        let patterns = ["/vault/**".to_string(), "*.key".to_string()];
+        let skip = SensitiveMatcher::new(SensitiveFiles::Skip, &patterns);
+        assert!(skip.skips(Path::new("/vault/notes.txt")));
+        assert!(skip.skips(Path::new("/home/a.key")));
+        assert!(!skip.hides_content(Path::new("/home/a.key")));
+        assert!(!skip.skips(Path::new("/home/vault/notes.txt")));
+
+        let off = SensitiveMatcher::new(SensitiveFiles::Index, &patterns);
+        assert!(!off.skips(Path::new("/home/a.key")));
+        assert!(!off.hides_content(Path::new("/home/a.key")));
+    }
+}
@@ -0,0 +1,252 @@
+//! Removable drives attached to the machine. Files and roots on them carry
+//! the drive's identifier, so an unplugged drive reads as offline rather than
+//! as a tree whose files were all deleted.
+
+use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+
+/// Drives found by the last [`refresh`]; `None` before the first one
+static MOUNTED: RwLock<Option<Vec<Volume>>> = RwLock::new(None);
+
+/// A mounted removable drive: USB sticks, SD cards, external disks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Volume {
+    /// Filesystem UUID or serial number; survives unplugging and remounting
+    pub id: String,
+    pub mount_point: PathBuf,
+    /// Name shown to the user: the filesystem label, or the mount folder
+    pub label: String,
+}
+
+/// The drive among `volumes` whose mount point holds `path`, preferring the
+/// innermost when mounts nest.
+#[must_use]
+pub fn volume_holding<'a>(path: &Path, volumes: &'a [Volume]) -> Option<&'a Volume> {
+    volumes
+        .iter()
+        .filter(|volume| path.starts_with(&volume.mount_point))
+        .max_by_key(|volume| volume.mount_point.as_os_str().len())
+}
+
+/// Removable drives mounted right now.
+#[must_use]
+pub fn removable_volumes() -> Vec<Volume> {
+    platform::removable_volumes()
+}
+
+/// Looks for removable drives again and remembers them for
+/// [`volume_id_of`] and [`is_offline`].
+pub fn refresh() -> Vec<Volume> {
+    let volumes = removable_volumes();
+    *MOUNTED.write() = Some(volumes.clone());
+    volumes
+}
+
+/// Identifier of the removable drive holding `path`, as of the last
+/// [`refresh`]. Files on fixed disks have none.
+#[must_use]
+pub fn volume_id_of(path: &Path) -> Option<String> {
+    let mounted = MOUNTED.read();
+    volume_holding(path, mounted.as_deref()?).map(|volume| volume.id.clone())
+}
+
+/// Whether the drive `id` was missing at the last [`refresh`]. Before the
+/// first one nothing is known to be unplugged.
+#[must_use]
+pub fn is_offline(id: &str) -> bool {
+    MOUNTED
+        .read()
+        .as_ref()
+        .is_some_and(|mounted| !mounted.iter().any(|volume| volume.id == id))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Volume;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Where desktop environments mount drives they automount
+    const AUTOMOUNT_DIRS: &[&str] = &["/media", "/run/media"];
+
+    pub fn removable_volumes() -> Vec<Volume> {
+        let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+            return Vec::new();
+        };
+        let uuids = disk_links("/dev/disk/by-uuid");
+        let labels = disk_links("/dev/disk/by-label");
+        mounts
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let device = fields.next().filter(|d| d.starts_with("/dev/"))?;
+                let mount_point = PathBuf::from(fields.next()?.replace("\\040", " "));
+                let device = fs::canonicalize(device).ok()?;
+                let name = device.file_name()?.to_string_lossy().to_string();
+                let automounted = AUTOMOUNT_DIRS
+                    .iter()
+                    .any(|dir| mount_point.starts_with(dir));
+                if !automounted && !is_removable(&name) {
+                    return None;
+                }
+                let label = labels
+                    .get(&device)
+                    .cloned()
+                    .or_else(|| {
+                        mount_point
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_else(|| name.clone());
+                Some(Volume {
+                    id: uuids.get(&device).cloned().unwrap_or(name),
+                    mount_point,
+                    label,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the kernel flags the disk as removable. Partitions carry no
+    /// flag of their own, so their disk one level up is asked.
+    fn is_removable(name: &str) -> bool {
+        let Ok(dev) = fs::canonicalize(Path::new("/sys/class/block").join(name)) else {
+            return false;
+        };
+        [Some(dev.as_path()), dev.parent()]
+            .into_iter()
+            .flatten()
+            .any(|dir| {
+                fs::read_to_string(dir.join("removable")).is_ok_and(|flag| flag.trim() == "1")
+            })
+    }
+
+    /// Maps each device node to the name of its link in `dir`, unescaping
+    /// the `\x20` udev writes for spaces.
+    fn disk_links(dir: &str) -> HashMap<PathBuf, String> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return HashMap::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let device = fs::canonicalize(entry.path()).ok()?;
+                let name = entry.file_name().to_string_lossy().replace("\\x20", " ");
+                Some((device, name))
+            })
+            .collect()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Volume;
+    use std::path::PathBuf;
+    use windows::Win32::Storage::FileSystem::{
+        GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW,
+    };
+    use windows::core::PCWSTR;
+
+    const DRIVE_REMOVABLE: u32 = 2;
+
+    pub fn removable_volumes() -> Vec<Volume> {
+        // SAFETY: takes no arguments and only reads the drive bitmask.
+        let drives = unsafe { GetLogicalDrives() };
+        (0..26u8)
+            .filter(|bit| drives & (1 << bit) != 0)
+            .filter_map(|bit| {
+                let root = format!("{}:\\", char::from(b'A' + bit));
+                let wide: Vec<u16> = root.encode_utf16().chain(Some(0)).collect();
+                // SAFETY: `wide` is NUL-terminated and outlives the call.
+                if unsafe { GetDriveTypeW(PCWSTR(wide.as_ptr())) } != DRIVE_REMOVABLE {
+                    return None;
+                }
+                let mut name = [0u16; 261];
+                let mut serial = 0u32;
+                // SAFETY: the buffers outlive the call; an empty card reader
+                // slot fails here and is skipped.
+                unsafe {
+                    GetVolumeInformationW(
+                        PCWSTR(wide.as_ptr()),
+                        Some(&mut name),
+                        Some(&raw mut serial),
+                        None,
+                        None,
+                        None,
+                    )
+                }
+                .ok()?;
+                let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+                let label = String::from_utf16_lossy(&name[..len]);
+                Some(Volume {
+                    id: format!("{serial:08X}"),
+                    label: if label.is_empty() {
+                        root.trim_end_matches('\\').to_string()
+                    } else {
+                        label
+                    },
+                    mount_point: PathBuf::from(root),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Volume;
+
+    /// Every mounted drive but the startup disk, which is a symlink to `/`,
+    /// shows up under `/Volumes`. Without Disk Arbitration there is no UUID
+    /// at hand, so the volume name stands in for one.
+    pub fn removable_volumes() -> Vec<Volume> {
+        let Ok(entries) = std::fs::read_dir("/Volumes") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|entry| {
+                let label = entry.file_name().to_string_lossy().to_string();
+                Volume {
+                    id: label.clone(),
+                    mount_point: entry.path(),
+                    label,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::Volume;
+
+    pub fn removable_volumes() -> Vec<Volume> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_holding_prefers_innermost_mount() {
+        let volume = |id: &str, mount: &str| Volume {
+            id: id.to_string(),
+            mount_point: PathBuf::from(mount),
+            label: id.to_string(),
+        };
+        let volumes = vec![
+            volume("outer", "/media/usb"),
+            volume("inner", "/media/usb/card"),
+        ];
+        let found = |path: &str| volume_holding(Path::new(path), &volumes).map(|v| v.id.as_str());
+
+        assert_eq!(found("/media/usb/card/photo.jpg"), Some("inner"));
+        assert_eq!(found("/media/usb/notes.txt"), Some("outer"));
+        assert_eq!(found("/media/usbstick/notes.txt"), None);
+    }
+}
@@ -5,11 +5,21 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use strum::{Display, EnumIter, EnumString};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SearchHistoryItem {
-    pub query: String,
-    pub frequency: u32,
-    pub last_used: u64,
+/// A named query snippet, referenced as `@name` in the search box.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueryMacro {
+    pub name: String,
+    pub expansion: String,
+}
+
+/// What opens files of one extension instead of their default application.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileOpener {
+    /// Without the dot, matched ignoring case
+    pub extension: String,
+    /// Command line or URL with `{}` or `{file}` in place of the path, and
+    /// `{line}` or `{page}` for where a search matched
+    pub command: String,
 }
 
 pub const COMMON_EXTENSIONS: &[&str] = &[
@@ -40,7 +50,10 @@ pub struct AppSettings {
     pub version: u32,
 
     // Indexing
-    pub index_dirs: Vec<String>,
+    /// Folders to index, each with its own rules. Older settings files list
+    /// bare paths under `index_dirs`; those load as default profiles.
+    #[serde(alias = "index_dirs", deserialize_with = "deserialize_index_roots")]
+    pub index_roots: Vec<IndexRoot>,
     #[default(vec![
         ".git/".to_string(),
         "node_modules/".to_string(),
@@ -62,10 +75,41 @@ pub struct AppSettings {
     #[serde(default = "default_true")]
     #[default(true)]
     pub use_gitignore: bool,
+    /// Enumerate whole NTFS drives from the MFT instead of walking them.
+    /// Windows only, and needs administrator rights; falls back to a walk.
+    #[serde(default = "default_true")]
+    #[default(true)]
+    pub use_mft_enumeration: bool,
+    /// Offer to index the file names on removable drives as they are plugged in
+    #[serde(default = "default_true")]
+    #[default(true)]
+    pub offer_removable_indexing: bool,
     #[default(100)]
     pub index_file_size_limit_mb: u32,
     #[serde(default)]
     pub custom_extensions: String,
+    /// Cadence for background incremental refreshes of the index roots
+    #[serde(default)]
+    pub reindex_schedule: ReindexSchedule,
+    /// Cloud-synced folders whose online-only files are downloaded so their
+    /// content can be indexed. Elsewhere such files are indexed by name and
+    /// metadata only.
+    #[serde(default)]
+    pub cloud_content_folders: Vec<String>,
+    /// What happens to files matching `sensitive_patterns`
+    #[serde(default)]
+    pub sensitive_files: SensitiveFiles,
+    /// Globs matched against file names, or against whole paths if they
+    /// contain a `/`, ignoring case. Keys, password stores and the like.
+    #[default(default_sensitive_patterns())]
+    pub sensitive_patterns: Vec<String>,
+    /// Whether the index stores are encrypted on disk, applied at the next
+    /// start
+    #[serde(default)]
+    pub index_encryption: IndexEncryption,
+    /// Whether indexing and searches are recorded in the activity log
+    #[serde(default)]
+    pub activity_log: bool,
 
     // Search
     #[default(50)]
@@ -77,13 +121,27 @@ pub struct AppSettings {
     pub case_sensitive: bool,
     #[serde(default)]
     pub whole_word: bool,
-    pub default_filters: DefaultFilters,
+    /// Hide results below the point where scores drop off sharply
     #[serde(default)]
+    pub confidence_cutoff: bool,
+    pub default_filters: DefaultFilters,
+    /// Left by older versions; moved into the metadata database at startup
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub recent_searches: Vec<String>,
+    /// Left by older versions; moved into the metadata database at startup
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_history: Vec<crate::models::SearchHistoryItem>,
     #[serde(default)]
-    pub search_history: Vec<SearchHistoryItem>,
+    pub query_macros: Vec<QueryMacro>,
     #[default(true)]
     pub filename_index_enabled: bool,
+    /// Match filename searches against whole paths instead of names only
+    #[serde(default)]
+    pub match_full_path: bool,
+    /// Split camelCase/snake_case identifiers in source files so either spelling matches
+    #[serde(default = "default_true")]
+    #[default(true)]
+    pub code_aware_indexing: bool,
 
     // Appearance
     pub theme: Theme,
@@ -93,6 +151,12 @@ pub struct AppSettings {
     #[default(50)]
     pub results_per_page: usize,
 
+    /// Language code from [`crate::i18n::LANGUAGES`], or `auto` for the
+    /// system language
+    #[serde(default = "default_language")]
+    #[default(default_language())]
+    pub language: String,
+
     // Behavior
     #[default(true)]
     pub minimize_to_tray: bool,
@@ -105,22 +169,141 @@ pub struct AppSettings {
     #[serde(default = "default_global_hotkey")]
     #[default(default_global_hotkey())]
     pub global_hotkey: String,
+    /// Summons the compact, always-on-top quick search palette; empty turns
+    /// it off
+    #[serde(default = "default_quick_search_hotkey")]
+    #[default(default_quick_search_hotkey())]
+    pub quick_search_hotkey: String,
+    /// Keys for actions inside the window
+    #[serde(default)]
+    pub shortcuts: Shortcuts,
+    /// Opens a text or source file at the line a search matched, with
+    /// `{file}` and `{line}` standing for the path and line number, like
+    /// `code -g {file}:{line}`. Empty opens files the usual way
+    pub editor_command: String,
+    /// Opens a PDF at the page a search matched, with `{file}` and `{page}`,
+    /// like `SumatraPDF -page {page} {file}`. Empty opens PDFs the usual way
+    pub pdf_viewer_command: String,
+    /// Commands that open files of an extension instead of the default
+    /// application, taking precedence over the editor and PDF viewer
+    pub file_openers: Vec<FileOpener>,
 
     // Performance
     #[default(4)]
     pub indexing_threads: u8,
     #[default(512)]
     pub memory_limit_mb: u32,
+    /// Memory a scan may hold in queued paths and parsed documents waiting
+    /// for the index writer, before parsing waits for the writer to catch up
+    #[serde(default = "default_pipeline_memory_mb")]
+    #[default(default_pipeline_memory_mb())]
+    pub pipeline_memory_mb: u32,
+    /// Index roots scanned at the same time
+    #[serde(default = "default_parallel_scans")]
+    #[default(default_parallel_scans())]
+    pub parallel_scans: usize,
+    /// Largest the search index may grow on disk, 0 for no limit
+    pub max_index_size_mb: u32,
+    /// What gives way once the index outgrows `max_index_size_mb`
+    pub index_size_policy: IndexSizePolicy,
     #[default(false)]
     pub enable_ocr: bool,
+    /// Seconds a single file may spend in the parser before it is abandoned
+    #[default(30)]
+    pub parse_timeout_secs: u64,
+    /// How live file events are batched before they reach the index
+    #[serde(default)]
+    pub watcher: WatcherSettings,
+    /// Commit pacing and segment merging of the search index
+    #[serde(default)]
+    pub index_writer: IndexWriterSettings,
 
-    // Pinned files for quick access
+    /// Left by older versions; moved into the metadata database at startup
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub pinned_files: Vec<String>,
 
     #[serde(skip)]
     pub allowed_extensions_cache: AllowedExtensionsCache,
 }
 
+/// Indexing rules for one root folder.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SmartDefault)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct IndexRoot {
+    pub path: String,
+    /// Globs a file must match to be indexed; empty indexes every file
+    pub include: Vec<String>,
+    /// Globs skipped under this root on top of the global exclude patterns
+    pub exclude: Vec<String>,
+    /// Deepest directory level walked below the root; `None` uses the default of 20
+    pub max_depth: Option<usize>,
+    /// Parse file contents, or index only names and metadata
+    #[default(true)]
+    pub index_content: bool,
+    #[default(true)]
+    pub follow_symlinks: bool,
+    /// Index hidden files and folders: dotfiles, and those marked hidden on
+    /// Windows
+    #[default(true)]
+    pub include_hidden: bool,
+    /// Index files and folders Windows marks as system files
+    pub include_system: bool,
+    /// Read files the user has no access to with backup rights. Windows
+    /// only, and only while the app runs as administrator
+    pub elevated_access: bool,
+    /// Keep this root in an index of its own, so it can be rebuilt or
+    /// cleared without touching the other roots
+    pub separate_index: bool,
+    /// Identifier of the removable drive the root lives on. While that drive
+    /// is unplugged the root is offline and its documents are kept.
+    pub volume_id: Option<String>,
+    /// Set when the index outgrew its size cap and this root fell back to
+    /// names and metadata. Cleared when the cap or its policy changes.
+    pub content_capped: bool,
+    /// Refresh schedule of this root alone, such as a slow share rescanned
+    /// nightly; `None` follows the global re-index schedule
+    pub rescan: Option<ReindexSchedule>,
+}
+
+impl IndexRoot {
+    /// Default profile for `path`: content indexed, no extra globs.
+    #[must_use]
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether file contents are parsed, as asked for and the size cap allows.
+    #[must_use]
+    pub const fn indexes_content(&self) -> bool {
+        self.index_content && !self.content_capped
+    }
+}
+
+/// Accepts both profile objects and the bare path strings older versions wrote.
+fn deserialize_index_roots<'de, D>(deserializer: D) -> std::result::Result<Vec<IndexRoot>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Path(String),
+        Root(IndexRoot),
+    }
+
+    Ok(Vec::<Entry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            Entry::Path(path) => IndexRoot::new(path),
+            Entry::Root(root) => root,
+        })
+        .collect())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DefaultFilters {
     pub file_types: Vec<String>,
@@ -129,10 +312,219 @@ pub struct DefaultFilters {
     pub modified_within_days: Option<u32>,
 }
 
+/// Batching of live file events. Takes effect on the next start.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, SmartDefault)]
+#[serde(default)]
+pub struct WatcherSettings {
+    /// Quiet period, or window length, before a batch of events is applied
+    #[default(500)]
+    pub debounce_ms: u64,
+    /// Longest a continuous burst is held back under [`CoalescePolicy::Debounce`]
+    #[default(5000)]
+    pub max_wait_ms: u64,
+    /// Events held in one batch. A flood beyond this (a `git checkout`, an
+    /// unpacked archive) is dropped and the affected roots are rescanned.
+    #[default(10_000)]
+    pub max_buffered_events: usize,
+    pub coalesce: CoalescePolicy,
+}
+
+/// Commit pacing and segment merging of the search index. Takes effect on the
+/// next start.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, SmartDefault)]
+#[serde(default)]
+pub struct IndexWriterSettings {
+    /// Megabytes of extracted text a scan adds before committing. Every commit
+    /// writes a new segment, so small values leave many tiny segments behind.
+    #[default(64)]
+    pub commit_every_mb: u32,
+    /// Longest a scan holds added documents back from search
+    #[default(30)]
+    pub commit_interval_secs: u64,
+    /// Segments of a similar size merged together
+    #[default(8)]
+    pub merge_min_segments: usize,
+    /// Segments below this many documents all count as one size
+    #[default(10_000)]
+    pub merge_min_layer_docs: u32,
+    /// Segments with more documents are left alone
+    #[default(10_000_000)]
+    pub merge_max_docs: usize,
+    /// Segment count past which an idle index is force-merged; 0 turns it off
+    #[default(32)]
+    pub optimize_idle_segments: usize,
+}
+
+/// Keyboard shortcuts inside the window, written like `Ctrl+Enter`. An empty
+/// one turns its action off.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SmartDefault)]
+#[serde(default)]
+pub struct Shortcuts {
+    /// Opens the selected result
+    #[default("Enter".to_string())]
+    pub open: String,
+    /// Shows the selected result in its folder
+    #[default("Ctrl+Enter".to_string())]
+    pub reveal: String,
+    #[default("Ctrl+C".to_string())]
+    pub copy_path: String,
+    /// Closes menus, then clears the search, then hides the window
+    #[default("Escape".to_string())]
+    pub clear: String,
+    #[default("Tab".to_string())]
+    pub next_pane: String,
+    #[default("Shift+Tab".to_string())]
+    pub previous_pane: String,
+}
+
+impl IndexWriterSettings {
+    #[must_use]
+    pub fn commit_every_bytes(&self) -> u64 {
+        u64::from(self.commit_every_mb.max(1)) * 1024 * 1024
+    }
+}
+
+/// How the index is brought back under `max_index_size_mb`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    Display,
+    EnumString,
+    EnumIter,
+    PartialEq,
+    Eq,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IndexSizePolicy {
+    /// Index only names and metadata for the lowest-priority root still
+    /// indexing content, the last one in the list, one root at a time
+    #[default]
+    FilenameOnly,
+    /// Drop the content of the documents modified longest ago
+    EvictOldest,
+}
+
+/// How files matching the sensitive patterns are indexed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    Display,
+    EnumString,
+    EnumIter,
+    PartialEq,
+    Eq,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveFiles {
+    /// Index their names and metadata, never their content
+    #[default]
+    FilenameOnly,
+    /// Leave them out of the index altogether
+    Skip,
+    /// Index them like any other file
+    Index,
+}
+
+/// How the search index, metadata store and filename index are kept on disk.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    Display,
+    EnumString,
+    EnumIter,
+    PartialEq,
+    Eq,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IndexEncryption {
+    /// Unencrypted
+    #[default]
+    Off,
+    /// Encrypted with a random key kept in the OS keychain
+    Keychain,
+    /// Encrypted with a key derived from a passphrase asked for at startup
+    Passphrase,
+}
+
+/// When a batch of buffered events is flushed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    Display,
+    EnumString,
+    EnumIter,
+    PartialEq,
+    Eq,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CoalescePolicy {
+    /// Once events stop arriving for `debounce_ms`, but at least every `max_wait_ms`
+    #[default]
+    Debounce,
+    /// A fixed `debounce_ms` after the first event of the batch
+    Window,
+}
+
+fn default_language() -> String {
+    crate::i18n::AUTO.to_string()
+}
+
 fn default_global_hotkey() -> String {
     "Alt+Space".to_string()
 }
 
+fn default_quick_search_hotkey() -> String {
+    "Ctrl+Shift+F".to_string()
+}
+
+fn default_sensitive_patterns() -> Vec<String> {
+    [
+        "*.key",
+        "*.pem",
+        "*.pfx",
+        "*.p12",
+        "id_rsa*",
+        "id_dsa*",
+        "id_ecdsa*",
+        "id_ed25519*",
+        ".env",
+        ".env.*",
+        "*.kdbx",
+        "*.kdb",
+        "*password*",
+        "*passwd*",
+        "*secret*",
+        "*credential*",
+        ".netrc",
+        ".pgpass",
+        "*.gpg",
+        "wallet.dat",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
 const fn default_true() -> bool {
     true
 }
@@ -141,6 +533,14 @@ const fn default_settings_version() -> u32 {
     1
 }
 
+const fn default_pipeline_memory_mb() -> u32 {
+    256
+}
+
+const fn default_parallel_scans() -> usize {
+    2
+}
+
 #[derive(
     Debug, Clone, Serialize, Deserialize, Default, Display, EnumString, EnumIter, PartialEq, Eq,
 )]
@@ -177,11 +577,138 @@ pub enum DoubleClickAction {
     Preview,
 }
 
+/// When the background scheduler runs an incremental refresh.
+///
+/// Written as `off`, `daily HH:MM` (local time) or `every Nh` in the settings
+/// UI and the `schedule` CLI subcommand.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReindexSchedule {
+    #[default]
+    Off,
+    /// Every day at a local wall-clock time
+    Daily { hour: u8, minute: u8 },
+    /// Every `hours` hours after the previous run
+    Interval { hours: u32 },
+}
+
+impl std::fmt::Display for ReindexSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Daily { hour, minute } => write!(f, "daily {hour:02}:{minute:02}"),
+            Self::Interval { hours } => write!(f, "every {hours}h"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReindexSchedule {
+    type Err = FlashError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            FlashError::config(
+                "reindex_schedule",
+                format!("'{s}' is not a schedule; use off, daily HH:MM or every Nh"),
+            )
+        };
+
+        let lower = s.trim().to_ascii_lowercase();
+        let mut words = lower.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (None | Some("off" | "never"), None, None) => Ok(Self::Off),
+            (Some("nightly"), None, None) => Ok(Self::Daily { hour: 2, minute: 0 }),
+            (Some("daily"), time, None) => {
+                let (hour, minute) = time
+                    .unwrap_or("02:00")
+                    .split_once(':')
+                    .ok_or_else(invalid)?;
+                let hour: u8 = hour.parse().map_err(|_| invalid())?;
+                let minute: u8 = minute.parse().map_err(|_| invalid())?;
+                if hour > 23 || minute > 59 {
+                    return Err(invalid());
+                }
+                Ok(Self::Daily { hour, minute })
+            }
+            (Some("every"), Some(interval), None) => {
+                let hours: u32 = interval
+                    .trim_end_matches('h')
+                    .parse()
+                    .map_err(|_| invalid())?;
+                if hours == 0 {
+                    return Err(invalid());
+                }
+                Ok(Self::Interval { hours })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
 pub struct SettingsManager {
     path: PathBuf,
 }
 
 impl AppSettings {
+    /// Configured profile for the root at `path`, or a default one for folders
+    /// that were indexed ad hoc.
+    #[must_use]
+    pub fn root_profile(&self, path: &Path) -> IndexRoot {
+        self.index_roots
+            .iter()
+            .find(|root| Path::new(&root.path) == path)
+            .cloned()
+            .unwrap_or_else(|| IndexRoot::new(path.to_string_lossy()))
+    }
+
+    /// Profile of the innermost root containing `path`, applied to the folder
+    /// at `path` alone, so part of a root can be scanned by its rules.
+    #[must_use]
+    pub fn folder_profile(&self, path: &Path) -> IndexRoot {
+        let mut profile = self
+            .index_roots
+            .iter()
+            .filter(|root| path.starts_with(&root.path))
+            .max_by_key(|root| root.path.len())
+            .cloned()
+            .unwrap_or_default();
+        profile.path = path.to_string_lossy().to_string();
+        profile
+    }
+
+    /// The index size cap in bytes, if one is set.
+    #[must_use]
+    pub fn index_size_limit_bytes(&self) -> Option<u64> {
+        (self.max_index_size_mb > 0).then(|| u64::from(self.max_index_size_mb) * 1024 * 1024)
+    }
+
+    /// Exclude patterns and system folders together, as scans and live
+    /// events apply them.
+    #[must_use]
+    pub fn exclude_list(&self) -> Vec<String> {
+        self.exclude_patterns
+            .iter()
+            .chain(&self.exclude_folders)
+            .cloned()
+            .collect()
+    }
+
+    /// Command configured to open files of the extension `ext`, if any.
+    #[must_use]
+    pub fn opener_for(&self, ext: &str) -> Option<&str> {
+        self.file_openers
+            .iter()
+            .find(|opener| {
+                opener
+                    .extension
+                    .trim()
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(ext)
+            })
+            .map(|opener| opener.command.trim())
+            .filter(|command| !command.is_empty())
+    }
+
     pub fn get_allowed_extensions(&self) -> &std::collections::HashSet<String> {
         self.allowed_extensions_cache.0.get_or_init(|| {
             let mut exts = std::collections::HashSet::new();
@@ -207,12 +734,47 @@ impl SettingsManager {
         }
     }
 
+    /// The settings file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The previous save, kept in case the settings file is damaged.
+    fn backup_path(&self) -> PathBuf {
+        self.path.with_extension("json.bak")
+    }
+
+    fn read(path: &Path) -> Result<AppSettings> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| FlashError::config("read_settings", e.to_string()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| FlashError::config("parse_settings", e.to_string()))
+    }
+
+    /// Reads the settings file, or the backup of the previous save if the
+    /// file is damaged, as a crash mid-write can leave it. The recovered
+    /// backup replaces the file; the damaged file is kept beside it with a
+    /// `.corrupt` extension either way.
+    fn read_or_recover(&self) -> Result<AppSettings> {
+        let error = match Self::read(&self.path) {
+            Ok(settings) => return Ok(settings),
+            Err(e) => e,
+        };
+        let _ = fs::copy(&self.path, self.path.with_extension("json.corrupt"));
+        let settings = Self::read(&self.backup_path()).map_err(|_| {
+            tracing::error!("Settings file is damaged and has no usable backup: {error}");
+            error
+        })?;
+        tracing::warn!("Settings file was damaged; restored the previous save");
+        fs::copy(self.backup_path(), &self.path)
+            .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+        Ok(settings)
+    }
+
     pub fn load(&self) -> Result<AppSettings> {
         let mut settings = if self.path.exists() {
-            let content = fs::read_to_string(&self.path)
-                .map_err(|e| FlashError::config("read_settings", e.to_string()))?;
-            serde_json::from_str(&content)
-                .map_err(|e| FlashError::config("parse_settings", e.to_string()))?
+            self.read_or_recover()?
         } else {
             AppSettings::default()
         };
@@ -243,11 +805,51 @@ impl SettingsManager {
         {
             settings.memory_limit_mb = limit;
         }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__PIPELINE_MEMORY_MB")
+            && let Ok(limit) = val.parse::<u32>()
+        {
+            settings.pipeline_memory_mb = limit;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__PARALLEL_SCANS")
+            && let Ok(scans) = val.parse::<usize>()
+        {
+            settings.parallel_scans = scans;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__MAX_INDEX_SIZE_MB")
+            && let Ok(limit) = val.parse::<u32>()
+        {
+            settings.max_index_size_mb = limit;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__INDEX_SIZE_POLICY")
+            && let Ok(policy) = val.parse::<IndexSizePolicy>()
+        {
+            settings.index_size_policy = policy;
+        }
         if let Ok(val) = std::env::var("FLASH_SEARCH__ENABLE_OCR")
             && let Ok(b) = val.parse::<bool>()
         {
             settings.enable_ocr = b;
         }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__PARSE_TIMEOUT_SECS")
+            && let Ok(secs) = val.parse::<u64>()
+        {
+            settings.parse_timeout_secs = secs;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__WATCHER_DEBOUNCE_MS")
+            && let Ok(ms) = val.parse::<u64>()
+        {
+            settings.watcher.debounce_ms = ms;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__WATCHER_MAX_BUFFERED_EVENTS")
+            && let Ok(limit) = val.parse::<usize>()
+        {
+            settings.watcher.max_buffered_events = limit;
+        }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__WATCHER_COALESCE")
+            && let Ok(policy) = val.parse::<CoalescePolicy>()
+        {
+            settings.watcher.coalesce = policy;
+        }
         if let Ok(val) = std::env::var("FLASH_SEARCH__AUTO_INDEX_ON_STARTUP")
             && let Ok(b) = val.parse::<bool>()
         {
@@ -258,6 +860,11 @@ impl SettingsManager {
         {
             settings.use_gitignore = b;
         }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__USE_MFT_ENUMERATION")
+            && let Ok(b) = val.parse::<bool>()
+        {
+            settings.use_mft_enumeration = b;
+        }
         if let Ok(val) = std::env::var("FLASH_SEARCH__MAX_RESULTS")
             && let Ok(limit) = val.parse::<usize>()
         {
@@ -283,6 +890,11 @@ impl SettingsManager {
         {
             settings.minimize_to_tray = b;
         }
+        if let Ok(val) = std::env::var("FLASH_SEARCH__REINDEX_SCHEDULE")
+            && let Ok(schedule) = val.parse::<ReindexSchedule>()
+        {
+            settings.reindex_schedule = schedule;
+        }
         if let Ok(val) = std::env::var("FLASH_SEARCH__AUTO_START_ON_BOOT")
             && let Ok(b) = val.parse::<bool>()
         {
@@ -296,8 +908,22 @@ impl SettingsManager {
         let content = serde_json::to_string_pretty(settings)
             .map_err(|e| FlashError::config("serialize_settings", e.to_string()))?;
 
+        // Written in full and flushed before it replaces the file, so a crash
+        // leaves either the old settings or the new ones
         let tmp_path = self.path.with_extension("tmp");
-        fs::write(&tmp_path, content).map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+        let write = || -> std::io::Result<()> {
+            let mut file = fs::File::create(&tmp_path)?;
+            std::io::Write::write_all(&mut file, content.as_bytes())?;
+            file.sync_all()
+        };
+        write().map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+
+        // Keep the previous version, unless it is a damaged file that loading
+        // fell back to defaults over; the last good backup is worth more
+        if Self::read(&self.path).is_ok() {
+            fs::copy(&self.path, self.backup_path())
+                .map_err(|e| FlashError::Io(std::sync::Arc::new(e)))?;
+        }
         fs::rename(&tmp_path, &self.path).map_err(|e| FlashError::Io(std::sync::Arc::new(e)))
     }
 }
@@ -323,4 +949,99 @@ mod tests {
         assert_eq!(loaded.max_results, 100);
         assert_eq!(loaded.theme, Theme::Dark);
     }
+
+    #[test]
+    fn test_damaged_settings_restore_the_previous_save() {
+        let temp_dir = tempdir().unwrap();
+        let manager = SettingsManager::new(temp_dir.path());
+        let first = AppSettings {
+            max_results: 42,
+            ..Default::default()
+        };
+        manager.save(&first).unwrap();
+        manager
+            .save(&AppSettings {
+                max_results: 43,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // A write cut short by a crash
+        let path = temp_dir.path().join("settings.json");
+        fs::write(&path, "{\"max_results\": 4").unwrap();
+        assert_eq!(manager.load().unwrap().max_results, 42);
+        assert_eq!(manager.load().unwrap().max_results, 42);
+        assert!(temp_dir.path().join("settings.json.corrupt").exists());
+
+        // Without a usable backup the damage is reported, not papered over
+        fs::write(&path, "not json").unwrap();
+        fs::write(temp_dir.path().join("settings.json.bak"), "").unwrap();
+        assert!(manager.load().is_err());
+    }
+
+    #[test]
+    fn test_reindex_schedule_parse() {
+        assert_eq!(
+            "daily 02:30".parse::<ReindexSchedule>().unwrap(),
+            ReindexSchedule::Daily {
+                hour: 2,
+                minute: 30
+            }
+        );
+        assert_eq!(
+            "every 6h".parse::<ReindexSchedule>().unwrap(),
+            ReindexSchedule::Interval { hours: 6 }
+        );
+        assert_eq!(
+            "Off".parse::<ReindexSchedule>().unwrap(),
+            ReindexSchedule::Off
+        );
+        assert!("daily 25:00".parse::<ReindexSchedule>().is_err());
+        assert!("every 0h".parse::<ReindexSchedule>().is_err());
+
+        let schedule = ReindexSchedule::Daily { hour: 2, minute: 0 };
+        assert_eq!(
+            schedule.to_string().parse::<ReindexSchedule>().unwrap(),
+            schedule
+        );
+    }
+
+    #[test]
+    fn test_index_roots_load_legacy_paths() {
+        let json = r#"{"index_dirs": ["/data/src", {"path": "/mnt/nas", "index_content": false}]}"#;
+        let settings: AppSettings = serde_json::from_str(json).unwrap();
+
+        assert_eq!(settings.index_roots[0], IndexRoot::new("/data/src"));
+        assert!(settings.index_roots[0].index_content);
+        assert!(!settings.index_roots[1].index_content);
+        assert!(settings.index_roots[1].follow_symlinks);
+        assert!(settings.index_roots[1].include_hidden);
+        assert!(!settings.index_roots[1].include_system);
+        assert!(!settings.index_roots[1].elevated_access);
+        assert!(!settings.root_profile(Path::new("/mnt/nas")).index_content);
+
+        let folder = settings.folder_profile(Path::new("/mnt/nas/photos"));
+        assert_eq!(folder.path, "/mnt/nas/photos");
+        assert!(!folder.index_content);
+    }
+
+    #[test]
+    fn test_file_openers_match_extensions_ignoring_case() {
+        let settings = AppSettings {
+            file_openers: vec![
+                FileOpener {
+                    extension: ".MD".to_string(),
+                    command: "obsidian://open?path={}".to_string(),
+                },
+                FileOpener {
+                    extension: "log".to_string(),
+                    command: "  ".to_string(),
+                },
+            ],
+            ..AppSettings::default()
+        };
+        assert_eq!(settings.opener_for("md"), Some("obsidian://open?path={}"));
+        assert_eq!(settings.opener_for("log"), None);
+        assert_eq!(settings.opener_for("txt"), None);
+    }
 }
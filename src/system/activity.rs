@@ -0,0 +1,499 @@
+//! Optional log of what indexing and searching did: files indexed, removed,
+//! skipped or failed, scans run and queries searched. It answers "why isn't
+//! this file indexed" and keeps a record for users who need one. Entries are
+//! JSON lines in the data directory, rotated by size so the log never grows
+//! past a few files; while the index is encrypted each line is sealed with its
+//! key. Nothing is written unless the setting is on.
+
+use super::vault::{self, Key};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use strum::Display;
+use tracing::warn;
+
+/// Directory in the data directory holding the log
+pub const DIR_NAME: &str = "activity";
+
+/// Size at which the current file is rotated
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept besides the current one
+const ROTATED_FILES: usize = 4;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static LOG: Mutex<Option<ActivityLog>> = Mutex::new(None);
+
+/// What found a file indexed or removed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Display, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Source {
+    Scan,
+    Watcher,
+    /// Removed from the index by hand, or by a new exclude pattern
+    User,
+}
+
+/// Why a file that was found isn't indexed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Display, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SkipReason {
+    /// Matches a sensitive pattern that is skipped
+    Sensitive,
+    /// Its extension isn't one whose contents are indexed
+    Extension,
+    /// Larger than the file size limit
+    TooLarge,
+    /// Couldn't be read when it was found
+    Unreadable,
+    /// The user isn't allowed to read it
+    NoAccess,
+}
+
+/// Which search ran.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Display, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SearchKind {
+    /// Contents and file names together
+    All,
+    Content,
+    Filename,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Activity {
+    ScanStarted {
+        root: String,
+        refresh: bool,
+    },
+    ScanFinished {
+        root: String,
+        cancelled: bool,
+    },
+    Indexed {
+        path: String,
+        source: Source,
+    },
+    Removed {
+        path: String,
+        source: Source,
+    },
+    Skipped {
+        path: String,
+        reason: SkipReason,
+    },
+    Failed {
+        path: String,
+        error: String,
+    },
+    Search {
+        query: String,
+        kind: SearchKind,
+        results: usize,
+    },
+}
+
+impl Activity {
+    /// Short name of the event, as written to the log.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ScanStarted { .. } => "scan_started",
+            Self::ScanFinished { .. } => "scan_finished",
+            Self::Indexed { .. } => "indexed",
+            Self::Removed { .. } => "removed",
+            Self::Skipped { .. } => "skipped",
+            Self::Failed { .. } => "failed",
+            Self::Search { .. } => "search",
+        }
+    }
+
+    /// The path, root or query the event is about.
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        match self {
+            Self::ScanStarted { root, .. } | Self::ScanFinished { root, .. } => root,
+            Self::Indexed { path, .. }
+            | Self::Removed { path, .. }
+            | Self::Skipped { path, .. }
+            | Self::Failed { path, .. } => path,
+            Self::Search { query, .. } => query,
+        }
+    }
+
+    /// What else is known about the event, such as why a file was skipped.
+    #[must_use]
+    pub fn detail(&self) -> String {
+        match self {
+            Self::ScanStarted { refresh: true, .. } => "refresh".to_string(),
+            Self::ScanStarted { refresh: false, .. } => "full scan".to_string(),
+            Self::ScanFinished {
+                cancelled: true, ..
+            } => "cancelled".to_string(),
+            Self::ScanFinished {
+                cancelled: false, ..
+            } => String::new(),
+            Self::Indexed { source, .. } | Self::Removed { source, .. } => source.to_string(),
+            Self::Skipped { reason, .. } => reason.to_string(),
+            Self::Failed { error, .. } => error.clone(),
+            Self::Search { kind, results, .. } => format!("{kind}, {results} results"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivityEntry {
+    /// Seconds since the Unix epoch
+    pub at: u64,
+    #[serde(flatten)]
+    pub activity: Activity,
+}
+
+impl ActivityEntry {
+    /// Whether the event's name, subject or detail contains `filter`,
+    /// ignoring case. An empty filter matches everything.
+    #[must_use]
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        filter.is_empty()
+            || self.activity.name().contains(&filter)
+            || self.activity.subject().to_lowercase().contains(&filter)
+            || self.activity.detail().to_lowercase().contains(&filter)
+    }
+}
+
+/// A line of the log: the entry as JSON, or sealed with `key` and written as
+/// hex.
+fn encode_line(entry: &ActivityEntry, key: Option<&Key>) -> io::Result<Vec<u8>> {
+    let json = serde_json::to_vec(entry)?;
+    let mut line = match key {
+        Some(key) => {
+            let mut hex = String::new();
+            for b in vault::seal(key, &json)? {
+                let _ = write!(hex, "{b:02x}");
+            }
+            hex.into_bytes()
+        }
+        None => json,
+    };
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// The entry on a line of the log, if it is plain JSON or sealed with one
+/// of `keys`.
+fn decode_line(line: &str, keys: &[&Key]) -> Option<ActivityEntry> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).ok();
+    }
+    if !line.is_ascii() || !line.len().is_multiple_of(2) {
+        return None;
+    }
+    let sealed = line
+        .as_bytes()
+        .chunks(2)
+        .map(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    keys.iter()
+        .find_map(|key| vault::open(key, &sealed).ok())
+        .and_then(|json| serde_json::from_slice(&json).ok())
+}
+
+/// The log files in one directory: the current one and those rotated out.
+#[derive(Debug)]
+pub struct ActivityLog {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Key the entries are sealed with while the index is encrypted
+    key: Option<Key>,
+    file: Option<File>,
+    len: u64,
+}
+
+impl ActivityLog {
+    #[must_use]
+    pub fn new(dir: &Path, key: Option<&Key>) -> Self {
+        Self::with_limit(dir, MAX_FILE_BYTES, key)
+    }
+
+    fn with_limit(dir: &Path, max_bytes: u64, key: Option<&Key>) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            key: key.cloned(),
+            file: None,
+            len: 0,
+        }
+    }
+
+    /// `activity.jsonl`, or the `n`th rotated file.
+    fn path(&self, n: usize) -> PathBuf {
+        if n == 0 {
+            self.dir.join("activity.jsonl")
+        } else {
+            self.dir.join(format!("activity.{n}.jsonl"))
+        }
+    }
+
+    /// Appends `entry`, first rotating the current file if it is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log can't be written.
+    pub fn append(&mut self, entry: &ActivityEntry) -> io::Result<()> {
+        let line = encode_line(entry, self.key.as_ref())?;
+        if self.file.is_none() {
+            std::fs::create_dir_all(&self.dir)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.path(0))?;
+            self.len = file.metadata()?.len();
+            self.file = Some(file);
+        }
+        if self.len > 0 && self.len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            return self.append(entry);
+        }
+        if let Some(file) = &mut self.file {
+            file.write_all(&line)?;
+            self.len += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let _ = std::fs::remove_file(self.path(ROTATED_FILES));
+        for n in (0..ROTATED_FILES).rev() {
+            let from = self.path(n);
+            if from.exists() {
+                std::fs::rename(from, self.path(n + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Up to `limit` entries matching `filter`, newest first.
+    #[must_use]
+    pub fn recent(&self, limit: usize, filter: &str) -> Vec<ActivityEntry> {
+        let keys: Vec<&Key> = self.key.iter().collect();
+        let mut entries = Vec::new();
+        for n in 0..=ROTATED_FILES {
+            let Ok(file) = File::open(self.path(n)) else {
+                continue;
+            };
+            let mut lines: Vec<ActivityEntry> = BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| decode_line(&line, &keys))
+                .filter(|entry| entry.matches(filter))
+                .collect();
+            lines.reverse();
+            entries.extend(lines.into_iter().take(limit - entries.len()));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        entries
+    }
+
+    /// Deletes every log file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file can't be deleted.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.file = None;
+        self.len = 0;
+        for n in 0..=ROTATED_FILES {
+            match std::fs::remove_file(self.path(n)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rewrites the log in `dir` sealed with `to`, or as plain JSON without it,
+/// opening sealed lines with whichever of `keys` they were sealed with. Lines
+/// none of them opens are dropped.
+///
+/// # Errors
+///
+/// Returns an error if a log file can't be read or replaced.
+pub fn convert(dir: &Path, keys: &[&Key], to: Option<&Key>) -> io::Result<()> {
+    let log = ActivityLog::new(dir, None);
+    for n in 0..=ROTATED_FILES {
+        let path = log.path(n);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let mut converted = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Some(entry) = decode_line(&line?, keys) {
+                converted.extend(encode_line(&entry, to)?);
+            }
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, converted)?;
+        std::fs::rename(&tmp, &path)?;
+    }
+    Ok(())
+}
+
+/// Sets up the log of the profile whose data is in `app_data_dir`, sealing
+/// entries with the index key if it is encrypted.
+pub fn init(app_data_dir: &Path, enabled: bool) {
+    *LOG.lock() = Some(ActivityLog::new(&app_data_dir.join(DIR_NAME), vault::key()));
+    set_enabled(enabled);
+}
+
+/// Turns recording on or off; what was recorded stays until cleared.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Records the event `activity` builds, which it only does while the log is
+/// on.
+pub fn record(activity: impl FnOnce() -> Activity) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let entry = ActivityEntry {
+        at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        activity: activity(),
+    };
+    if let Some(log) = LOG.lock().as_mut()
+        && let Err(e) = log.append(&entry)
+    {
+        warn!("Failed to write the activity log: {}", e);
+    }
+}
+
+/// Up to `limit` recorded entries matching `filter`, newest first.
+#[must_use]
+pub fn recent(limit: usize, filter: &str) -> Vec<ActivityEntry> {
+    LOG.lock()
+        .as_ref()
+        .map(|log| log.recent(limit, filter))
+        .unwrap_or_default()
+}
+
+/// Deletes everything recorded so far.
+///
+/// # Errors
+///
+/// Returns an error if a log file can't be deleted.
+pub fn clear() -> io::Result<()> {
+    LOG.lock().as_mut().map_or(Ok(()), ActivityLog::clear)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed(n: usize) -> ActivityEntry {
+        ActivityEntry {
+            at: n as u64,
+            activity: Activity::Indexed {
+                path: format!("/docs/file{n}.txt"),
+                source: Source::Scan,
+            },
+        }
+    }
+
+    #[test]
+    fn test_log_rotates_and_keeps_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = ActivityLog::with_limit(dir.path(), 200, None);
+        for n in 0..40 {
+            log.append(&indexed(n)).unwrap();
+        }
+
+        assert!(log.path(1).exists());
+        assert!(log.path(ROTATED_FILES).exists());
+        assert!(!log.path(ROTATED_FILES + 1).exists());
+        for n in 0..=ROTATED_FILES {
+            assert!(std::fs::metadata(log.path(n)).unwrap().len() <= 200);
+        }
+
+        let recent = log.recent(5, "");
+        let ats: Vec<u64> = recent.iter().map(|e| e.at).collect();
+        assert_eq!(ats, vec![39, 38, 37, 36, 35]);
+
+        log.clear().unwrap();
+        assert!(log.recent(5, "").is_empty());
+    }
+
+    #[test]
+    fn test_entries_round_trip_and_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = ActivityLog::new(dir.path(), None);
+        let skipped = ActivityEntry {
+            at: 1,
+            activity: Activity::Skipped {
+                path: r"C:\Keys\Server.KEY".to_string(),
+                reason: SkipReason::Sensitive,
+            },
+        };
+        let search = ActivityEntry {
+            at: 2,
+            activity: Activity::Search {
+                query: "quarterly report".to_string(),
+                kind: SearchKind::All,
+                results: 3,
+            },
+        };
+        log.append(&skipped).unwrap();
+        log.append(&search).unwrap();
+
+        let line = std::fs::read_to_string(log.path(0)).unwrap();
+        assert!(line.starts_with(r#"{"at":1,"event":"skipped""#));
+        assert_eq!(log.recent(10, ""), vec![search.clone(), skipped.clone()]);
+        assert_eq!(log.recent(10, r"c:\keys"), vec![skipped.clone()]);
+        assert_eq!(log.recent(10, "sensitive"), vec![skipped]);
+        assert_eq!(log.recent(10, "SEARCH"), vec![search]);
+    }
+
+    #[test]
+    fn test_entries_are_sealed_with_the_index_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = Key::from_bytes(&[3; 32]);
+        let mut log = ActivityLog::new(dir.path(), Some(&key));
+        log.append(&indexed(1)).unwrap();
+        log.append(&indexed(2)).unwrap();
+
+        let raw = std::fs::read_to_string(log.path(0)).unwrap();
+        assert!(!raw.contains("file1"));
+        assert_eq!(log.recent(10, "file1"), vec![indexed(1)]);
+        assert!(ActivityLog::new(dir.path(), None).recent(10, "").is_empty());
+
+        convert(dir.path(), &[&key], None).unwrap();
+        assert!(
+            std::fs::read_to_string(log.path(0))
+                .unwrap()
+                .contains("file1")
+        );
+        assert_eq!(
+            ActivityLog::new(dir.path(), None).recent(10, ""),
+            vec![indexed(2), indexed(1)]
+        );
+    }
+}
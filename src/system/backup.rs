@@ -0,0 +1,399 @@
+//! Backups of the index stores (search index, metadata database and filename
+//! index) as one zip archive with a manifest, e.g. for moving an index to
+//! another machine. A restore is unpacked next to the live stores and swapped
+//! in at the next start, before anything opens them.
+
+use crate::commands::AppState;
+use crate::error::{FlashError, Result};
+use crate::indexer::filename_index::{FilenameIndex, INDEX_FILENAME};
+use crate::indexer::{SCHEMA_VERSION, SHARDS_DIR};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+
+/// Bumped whenever the archive layout changes
+pub const BACKUP_FORMAT: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const INDEX_DIR: &str = "index";
+const METADATA_FILE: &str = "metadata.redb";
+const FILENAME_INDEX_DIR: &str = "filename_index";
+/// Where a validated archive waits for the next start
+const RESTORE_STAGING_DIR: &str = "restore_pending";
+/// Where the stores replaced by the last restore are kept
+const PRE_RESTORE_DIR: &str = "pre_restore";
+/// Tantivy's list of live segments, copied last
+const INDEX_META: &str = "meta.json";
+/// Copies of the index to try before giving up on merges that keep finishing mid-copy
+const SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// Describes an archive; read before anything is unpacked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BackupManifest {
+    pub format: u32,
+    pub app_version: String,
+    /// Search index schema the archive was written with
+    pub schema_version: String,
+    pub created_at: u64,
+    pub documents: usize,
+}
+
+impl BackupManifest {
+    /// Rejects archives this build can't use as they are. Opening an index
+    /// with another schema would quietly rebuild it from scratch, losing
+    /// what the backup was for.
+    pub fn validate(&self) -> Result<()> {
+        if self.format != BACKUP_FORMAT {
+            return Err(FlashError::archive(
+                "backup",
+                "validate",
+                format!(
+                    "unsupported backup format {} (this version reads {BACKUP_FORMAT})",
+                    self.format
+                ),
+            ));
+        }
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(FlashError::archive(
+                "backup",
+                "validate",
+                format!(
+                    "made by Flash Search {} with index schema {}, this version uses {SCHEMA_VERSION}",
+                    self.app_version, self.schema_version
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn zip_error(operation: &str, e: impl std::fmt::Display) -> FlashError {
+    FlashError::archive("zip", operation, e.to_string())
+}
+
+/// The default index and every root's own shard, relative to the app data
+/// directory. Migration leftovers next to the shards are skipped.
+fn index_dirs(app_data_dir: &Path) -> Vec<String> {
+    let mut dirs = vec![INDEX_DIR.to_string()];
+    let shards = std::fs::read_dir(app_data_dir.join(SHARDS_DIR))
+        .into_iter()
+        .flatten();
+    for entry in shards.flatten() {
+        if entry.path().is_dir() && entry.path().extension().is_none() {
+            dirs.push(format!(
+                "{SHARDS_DIR}/{}",
+                entry.file_name().to_string_lossy()
+            ));
+        }
+    }
+    dirs
+}
+
+/// Writes a snapshot of the stores under `app_data_dir` to `dest`. Commits
+/// wait while the index is copied; batches caught half-way are in the
+/// metadata journal and are recovered when the archive is restored.
+pub fn create_backup(state: &AppState, app_data_dir: &Path, dest: &Path) -> Result<BackupManifest> {
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: SCHEMA_VERSION.to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        documents: state.indexer.get_statistics()?.total_documents,
+    };
+    let filenames = state
+        .filename_index
+        .as_deref()
+        .map(FilenameIndex::snapshot)
+        .transpose()?
+        .map(super::vault::protect)
+        .transpose()?;
+    let metadata_copy = app_data_dir.join("metadata.backup.redb");
+    let index_dirs = index_dirs(app_data_dir);
+
+    let result = state.indexer.with_commits_paused(|| {
+        let _ = std::fs::remove_file(&metadata_copy);
+        state.metadata_db.backup_to(&metadata_copy)?;
+        for attempt in 1..=SNAPSHOT_ATTEMPTS {
+            let parts = Parts {
+                app_data_dir,
+                index_dirs: &index_dirs,
+                metadata: &metadata_copy,
+                filenames: filenames.as_deref(),
+            };
+            if write_archive(dest, &manifest, &parts)? {
+                return Ok(());
+            }
+            warn!("Index changed during backup (attempt {attempt}), copying again");
+        }
+        Err(FlashError::archive(
+            "backup",
+            "snapshot",
+            "the index kept changing while it was copied",
+        ))
+    });
+    let _ = std::fs::remove_file(&metadata_copy);
+    if result.is_err() {
+        let _ = std::fs::remove_file(dest);
+    }
+    result?;
+
+    info!(
+        "Backed up {} documents to {}",
+        manifest.documents,
+        dest.display()
+    );
+    Ok(manifest)
+}
+
+struct Parts<'a> {
+    app_data_dir: &'a Path,
+    index_dirs: &'a [String],
+    metadata: &'a Path,
+    filenames: Option<&'a [u8]>,
+}
+
+/// Writes one archive. Returns false if a merge changed the index mid-copy.
+fn write_archive(dest: &Path, manifest: &BackupManifest, parts: &Parts) -> Result<bool> {
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(dest)?);
+
+    zip.start_file(MANIFEST, options)
+        .map_err(|e| zip_error("write", e))?;
+    serde_json::to_writer_pretty(&mut zip, manifest).map_err(|e| zip_error("write", e))?;
+
+    zip.start_file(METADATA_FILE, options)
+        .map_err(|e| zip_error("write", e))?;
+    std::io::copy(&mut std::fs::File::open(parts.metadata)?, &mut zip)?;
+
+    if let Some(bytes) = parts.filenames {
+        zip.start_file(format!("{FILENAME_INDEX_DIR}/{INDEX_FILENAME}"), options)
+            .map_err(|e| zip_error("write", e))?;
+        zip.write_all(bytes)?;
+    }
+
+    // A merge that finishes mid-copy rewrites the segment list and deletes
+    // the merged segments, so the copy only counts if the list is unchanged
+    for dir_name in parts.index_dirs {
+        let index_dir = parts.app_data_dir.join(dir_name);
+        let meta = std::fs::read(index_dir.join(INDEX_META))?;
+        for entry in std::fs::read_dir(&index_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == INDEX_META || name.ends_with(".lock") || !entry.file_type()?.is_file() {
+                continue;
+            }
+            let mut file = match std::fs::File::open(entry.path()) {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                Err(e) => return Err(e.into()),
+            };
+            zip.start_file(format!("{dir_name}/{name}"), options)
+                .map_err(|e| zip_error("write", e))?;
+            std::io::copy(&mut file, &mut zip)?;
+        }
+        if std::fs::read(index_dir.join(INDEX_META))? != meta {
+            return Ok(false);
+        }
+        zip.start_file(format!("{dir_name}/{INDEX_META}"), options)
+            .map_err(|e| zip_error("write", e))?;
+        zip.write_all(&meta)?;
+    }
+
+    zip.finish().map_err(|e| zip_error("write", e))?;
+    Ok(true)
+}
+
+/// Reads and validates the manifest of `archive`.
+pub fn read_manifest(archive: &Path) -> Result<BackupManifest> {
+    let mut zip =
+        zip::ZipArchive::new(std::fs::File::open(archive)?).map_err(|e| zip_error("open", e))?;
+    let manifest: BackupManifest = serde_json::from_reader(
+        zip.by_name(MANIFEST)
+            .map_err(|_| zip_error("open", "not a Flash Search backup (no manifest)"))?,
+    )
+    .map_err(|e| zip_error("read manifest", e))?;
+    manifest.validate()?;
+    Ok(manifest)
+}
+
+/// Validates `archive` and unpacks it next to the live stores; the stores
+/// are replaced at the next start by [`apply_pending_restore`]. An archive
+/// that fails validation leaves everything as it was.
+pub fn stage_restore(archive: &Path, app_data_dir: &Path) -> Result<BackupManifest> {
+    let manifest = read_manifest(archive)?;
+    let mut zip =
+        zip::ZipArchive::new(std::fs::File::open(archive)?).map_err(|e| zip_error("open", e))?;
+    if zip.by_name(METADATA_FILE).is_err()
+        || zip.by_name(&format!("{INDEX_DIR}/{INDEX_META}")).is_err()
+    {
+        return Err(zip_error("open", "backup is missing the index or metadata"));
+    }
+
+    let staging = app_data_dir.join(RESTORE_STAGING_DIR);
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| zip_error("extract", e))?;
+        // Skips entries that would land outside the staging directory
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        if relative == Path::new(MANIFEST) {
+            continue;
+        }
+        let target = staging.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut std::fs::File::create(&target)?)?;
+    }
+
+    info!(
+        "Staged restore of {} documents from {}",
+        manifest.documents,
+        archive.display()
+    );
+    Ok(manifest)
+}
+
+/// Swaps a staged restore in for the live stores. Runs at startup before
+/// any store is opened. The replaced stores are kept until the next restore.
+/// Returns whether a restore was applied.
+pub fn apply_pending_restore(app_data_dir: &Path) -> Result<bool> {
+    let staging = app_data_dir.join(RESTORE_STAGING_DIR);
+    if !staging.exists() {
+        return Ok(false);
+    }
+
+    let previous = app_data_dir.join(PRE_RESTORE_DIR);
+    if previous.exists() {
+        std::fs::remove_dir_all(&previous)?;
+    }
+    std::fs::create_dir_all(&previous)?;
+    for name in [INDEX_DIR, SHARDS_DIR, METADATA_FILE, FILENAME_INDEX_DIR] {
+        let live = app_data_dir.join(name);
+        if live.exists() {
+            std::fs::rename(&live, previous.join(name))?;
+        }
+        let staged = staging.join(name);
+        if staged.exists() {
+            std::fs::rename(&staged, &live)?;
+        }
+    }
+    std::fs::remove_dir_all(&staging)?;
+    info!("Restored index from backup");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> BackupManifest {
+        BackupManifest {
+            format: BACKUP_FORMAT,
+            app_version: "0.14.0".to_string(),
+            schema_version: SCHEMA_VERSION.to_string(),
+            created_at: 0,
+            documents: 1,
+        }
+    }
+
+    #[test]
+    fn test_manifest_rejects_other_formats_and_schemas() {
+        assert!(manifest().validate().is_ok());
+        let newer_format = BackupManifest {
+            format: BACKUP_FORMAT + 1,
+            ..manifest()
+        };
+        assert!(newer_format.validate().is_err());
+        let old_schema = BackupManifest {
+            schema_version: "0.1.0".to_string(),
+            ..manifest()
+        };
+        assert!(old_schema.validate().is_err());
+    }
+
+    #[test]
+    fn test_archive_round_trip_through_staging() {
+        let source = tempfile::tempdir().unwrap();
+        let index_dir = source.path().join(INDEX_DIR);
+        std::fs::create_dir(&index_dir).unwrap();
+        std::fs::write(index_dir.join(INDEX_META), "{}").unwrap();
+        std::fs::write(index_dir.join("segment.idx"), "segment").unwrap();
+        std::fs::write(index_dir.join(".tantivy-writer.lock"), "").unwrap();
+        let shard_dir = source.path().join(SHARDS_DIR).join("0123456789abcdef");
+        std::fs::create_dir_all(&shard_dir).unwrap();
+        std::fs::write(shard_dir.join(INDEX_META), "{}").unwrap();
+        std::fs::write(shard_dir.join("shard.idx"), "shard").unwrap();
+        std::fs::create_dir(shard_dir.with_extension("backup")).unwrap();
+        let metadata = source.path().join("copy.redb");
+        std::fs::write(&metadata, "redb").unwrap();
+
+        let archive = source.path().join("backup.zip");
+        let index_dirs = index_dirs(source.path());
+        assert_eq!(index_dirs.len(), 2);
+        let parts = Parts {
+            app_data_dir: source.path(),
+            index_dirs: &index_dirs,
+            metadata: &metadata,
+            filenames: Some(b"names"),
+        };
+        assert!(write_archive(&archive, &manifest(), &parts).unwrap());
+        assert_eq!(read_manifest(&archive).unwrap(), manifest());
+
+        let target = tempfile::tempdir().unwrap();
+        std::fs::create_dir(target.path().join(INDEX_DIR)).unwrap();
+        std::fs::write(target.path().join(METADATA_FILE), "old").unwrap();
+        stage_restore(&archive, target.path()).unwrap();
+        assert!(apply_pending_restore(target.path()).unwrap());
+        assert!(!apply_pending_restore(target.path()).unwrap());
+
+        let restored = target.path();
+        assert_eq!(
+            std::fs::read_to_string(restored.join(METADATA_FILE)).unwrap(),
+            "redb"
+        );
+        assert_eq!(
+            std::fs::read_to_string(restored.join(INDEX_DIR).join("segment.idx")).unwrap(),
+            "segment"
+        );
+        assert!(
+            !restored
+                .join(INDEX_DIR)
+                .join(".tantivy-writer.lock")
+                .exists()
+        );
+        assert_eq!(
+            std::fs::read_to_string(
+                restored
+                    .join(SHARDS_DIR)
+                    .join("0123456789abcdef")
+                    .join("shard.idx")
+            )
+            .unwrap(),
+            "shard"
+        );
+        assert_eq!(
+            std::fs::read(restored.join(FILENAME_INDEX_DIR).join(INDEX_FILENAME)).unwrap(),
+            b"names"
+        );
+        assert_eq!(
+            std::fs::read_to_string(restored.join(PRE_RESTORE_DIR).join(METADATA_FILE)).unwrap(),
+            "old"
+        );
+    }
+}
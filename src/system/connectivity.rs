@@ -0,0 +1,107 @@
+use crate::commands::AppState;
+use crate::scanner::network;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// How often every root is probed. A share that drops is marked offline within
+/// one interval, and one that returns is watched again and caught up.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Probes the index roots in the background for the lifetime of the app. A
+/// root that reconnects, or whose live events were lost, gets an incremental
+/// refresh for whatever changed in the meantime. Folders whose changes
+/// overflowed the watcher get a full scan as soon as the watcher signals
+/// them. Either waits for the next check while another scan holds the
+/// indexing handle.
+pub fn spawn_connectivity_monitor(state: Arc<AppState>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let rescan_signal = state.watcher.lock().rescan_signal();
+        let mut checks = tokio::time::interval_at(Instant::now() + CHECK_INTERVAL, CHECK_INTERVAL);
+        checks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut catch_up: Vec<PathBuf> = Vec::new();
+        let mut rescans: Vec<PathBuf> = Vec::new();
+        loop {
+            let probe = tokio::select! {
+                _ = checks.tick() => true,
+                () = rescan_signal.notified() => false,
+            };
+            if crate::is_shutting_down() {
+                break;
+            }
+
+            if probe {
+                let roots = state.watcher.lock().watched_roots();
+                for root in roots {
+                    let probe = root.clone();
+                    let online = tokio::task::spawn_blocking(move || network::is_reachable(&probe))
+                        .await
+                        .unwrap_or(false);
+                    match state.watcher.lock().set_root_online(&root, online) {
+                        Ok(true) if !catch_up.contains(&root) => catch_up.push(root),
+                        Ok(_) => {}
+                        Err(e) => tracing::warn!("Failed to re-watch {}: {e}", root.display()),
+                    }
+                }
+                let stale = state.watcher.lock().take_stale_roots();
+                for root in stale {
+                    if !catch_up.contains(&root) {
+                        catch_up.push(root);
+                    }
+                }
+            }
+            let overflowed = state.watcher.lock().take_rescans();
+            for folder in overflowed {
+                if !rescans.contains(&folder) {
+                    rescans.push(folder);
+                }
+            }
+            if catch_up.is_empty() && rescans.is_empty() {
+                continue;
+            }
+
+            let busy = state
+                .indexing_handle
+                .lock()
+                .as_ref()
+                .is_some_and(|handle| !handle.is_finished());
+            if busy {
+                continue;
+            }
+
+            let settings = state.settings_cache.load();
+            let exclude_patterns = settings.exclude_list();
+            if !rescans.is_empty() {
+                let folders = std::mem::take(&mut rescans)
+                    .iter()
+                    .map(|folder| settings.folder_profile(folder))
+                    .collect();
+                let failures = state
+                    .scanner
+                    .scan_roots(
+                        folders,
+                        exclude_patterns.clone(),
+                        state.indexing_cancel.clone(),
+                    )
+                    .await;
+                for (folder, e) in failures {
+                    tracing::error!("Rescan of {folder} after an event overflow failed: {e}");
+                }
+            }
+            if !catch_up.is_empty() {
+                let roots = std::mem::take(&mut catch_up)
+                    .iter()
+                    .map(|root| settings.root_profile(root))
+                    .collect();
+                let failures = state
+                    .scanner
+                    .refresh_roots(roots, exclude_patterns, state.indexing_cancel.clone())
+                    .await;
+                for (root, e) in failures {
+                    tracing::error!("Catch-up refresh of {root} failed: {e}");
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,55 @@
+//! Keeps a second process off the search index and the metadata store. The
+//! first process to open the data directory takes an exclusive lock on a file
+//! in it and holds it until it exits. The OS releases the lock when the
+//! process dies, so a crash never leaves a stale lock behind.
+
+use crate::error::{FlashError, Result};
+use std::fs::File;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Lock file in the app data directory
+pub const LOCK_FILENAME: &str = "instance.lock";
+
+/// Held for the rest of the process once taken
+static INSTANCE_LOCK: OnceLock<fd_lock::RwLockWriteGuard<'static, File>> = OnceLock::new();
+
+/// Takes the instance lock in `app_data_dir`, unless this process already
+/// holds it.
+///
+/// # Errors
+///
+/// Returns [`FlashError::AlreadyRunning`] while another process holds the
+/// lock, or an I/O error if the lock file cannot be opened.
+pub fn acquire(app_data_dir: &Path) -> Result<()> {
+    if INSTANCE_LOCK.get().is_some() {
+        return Ok(());
+    }
+    let lock_path = app_data_dir.join(LOCK_FILENAME);
+    // Not truncated on open: the holder's PID stays readable to others
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)?;
+    // Leaked so the guard can live in a static; there is one per process
+    let lock: &'static mut fd_lock::RwLock<File> = Box::leak(Box::new(fd_lock::RwLock::new(file)));
+    let mut guard = match lock.try_write() {
+        Ok(guard) => guard,
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+            return Err(FlashError::AlreadyRunning { lock_path });
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    {
+        use std::io::{Seek, SeekFrom, Write};
+        guard.set_len(0)?;
+        guard.seek(SeekFrom::Start(0))?;
+        write!(&mut *guard, "{}", std::process::id())?;
+        guard.flush()?;
+    }
+    let _ = INSTANCE_LOCK.set(guard);
+    Ok(())
+}
@@ -0,0 +1,310 @@
+//! Lets a second `flash-search` process talk to the one that holds the index.
+//! The running instance listens on a loopback port for one request per
+//! connection: a line with a query is answered with a line of JSON results,
+//! or `{"error": ...}`, and a focus request brings its window to the front.
+//!
+//! Any local user can reach a loopback port, so each request starts with a
+//! line holding a token the server picks at startup. It is written to the
+//! profile's data directory, readable only by the user (mode 0600 on Unix; the
+//! per-user data directory's ACL on Windows), and requests without it are
+//! refused.
+
+use crate::commands::AppState;
+use crate::error::{FlashError, Result};
+use crate::indexer::searcher::{SearchParams, SearchResult};
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::rand_core::RngCore;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Address the running instance of the active profile answers on.
+pub fn ipc_addr() -> SocketAddr {
+    SocketAddr::from((
+        Ipv4Addr::LOCALHOST,
+        crate::system::profile::ipc_port(crate::system::profile::active()),
+    ))
+}
+
+/// Results returned for a query
+const IPC_RESULT_LIMIT: usize = 50;
+
+/// Asks for the window instead of a search. No typed query starts with a
+/// control character.
+const FOCUS_REQUEST: &str = "\u{1}focus";
+
+/// How long a client waits on the running instance
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// File in the data directory holding the running instance's token
+const TOKEN_FILENAME: &str = "ipc.token";
+
+/// Writes a new random token to `dir`, readable only by the user.
+fn write_token(dir: &Path) -> io::Result<String> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+
+    let path = dir.join(TOKEN_FILENAME);
+    // Recreated rather than truncated, so an older file's permissions don't carry over
+    if let Err(e) = std::fs::remove_file(&path)
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        return Err(e);
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&path)?.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+fn read_token(dir: &Path) -> io::Result<String> {
+    Ok(std::fs::read_to_string(dir.join(TOKEN_FILENAME))?
+        .trim()
+        .to_string())
+}
+
+/// Compares in time independent of where the tokens differ.
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn data_dir() -> io::Result<std::path::PathBuf> {
+    crate::get_app_data_dir().map_err(io::Error::other)
+}
+
+/// Focus requests received, for the UI to pick up
+static FOCUS_REQUESTS: LazyLock<(flume::Sender<()>, flume::Receiver<()>)> =
+    LazyLock::new(|| flume::bounded(1));
+
+/// Fires each time another process asks for the window.
+pub fn focus_requests() -> flume::Receiver<()> {
+    FOCUS_REQUESTS.1.clone()
+}
+
+/// A search result as it is sent over IPC and printed by the CLI. The field
+/// names are part of the CLI's output formats; add to them, don't rename.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResultRecord {
+    pub path: String,
+    pub title: Option<String>,
+    pub score: f32,
+    /// In bytes
+    pub size: Option<u64>,
+    /// Seconds since the Unix epoch
+    pub modified: Option<u64>,
+    /// Best matching passage, as plain text
+    pub snippet: Option<String>,
+}
+
+impl From<&SearchResult> for ResultRecord {
+    fn from(res: &SearchResult) -> Self {
+        Self {
+            path: res.file_path.clone(),
+            title: res.title.as_ref().map(ToString::to_string),
+            score: res.score,
+            size: res.size,
+            modified: res.modified,
+            snippet: res.snippets.first().map(|html| plain_snippet(html)),
+        }
+    }
+}
+
+/// A snippet's highlighting markup stripped and its whitespace collapsed.
+pub fn plain_snippet(html: &str) -> String {
+    let text = html.replace("<b>", "").replace("</b>", "");
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Answers requests from other processes for the lifetime of the app.
+pub async fn serve(state: Arc<AppState>) {
+    let token = match data_dir().and_then(|dir| write_token(&dir)) {
+        Ok(token) => Arc::new(token),
+        Err(e) => {
+            tracing::error!("Failed to write the IPC token, not serving IPC: {}", e);
+            return;
+        }
+    };
+    let addr = ipc_addr();
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Failed to bind IPC TCP listener at {}: {}", addr, e);
+            return;
+        }
+    };
+
+    tracing::info!("IPC TCP Server listening on {}", addr);
+
+    loop {
+        let Ok((mut socket, _)) = listener.accept().await else {
+            continue;
+        };
+
+        let state_clone = state.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = socket.split();
+            let mut reader = BufReader::new(reader);
+            let mut line = String::new();
+
+            if reader.read_line(&mut line).await.is_err() {
+                return;
+            }
+            if !token_matches(line.trim_end_matches(['\r', '\n']), &token) {
+                tracing::warn!("Refused an IPC request without the session token");
+                let _ = writer.write_all(b"{\"error\":\"not authorized\"}\n").await;
+                return;
+            }
+            line.clear();
+            if reader.read_line(&mut line).await.is_err() {
+                return;
+            }
+            let request = line.trim_end_matches(['\r', '\n']);
+            if request == FOCUS_REQUEST {
+                let _ = FOCUS_REQUESTS.0.try_send(());
+                let _ = writer.write_all(b"{}\n").await;
+                return;
+            }
+            let query = request.trim();
+            if query.is_empty() {
+                return;
+            }
+
+            let search_params = SearchParams::builder()
+                .query(query)
+                .limit(IPC_RESULT_LIMIT)
+                .case_sensitive(false)
+                .build();
+
+            let reply =
+                match crate::commands::search_combined_internal(search_params, false, &state_clone)
+                    .await
+                {
+                    Ok(results) => serde_json::to_value(
+                        results.iter().map(ResultRecord::from).collect::<Vec<_>>(),
+                    )
+                    .unwrap_or_default(),
+                    Err(e) => serde_json::json!({ "error": e }),
+                };
+            if let Ok(serialized) = serde_json::to_string(&reply) {
+                let _ = writer.write_all(serialized.as_bytes()).await;
+                let _ = writer.write_all(b"\n").await;
+            }
+        });
+    }
+}
+
+fn unreachable(query: &str, e: impl std::fmt::Display) -> FlashError {
+    FlashError::search(
+        query,
+        format!("another instance holds the index and could not be reached: {e}"),
+    )
+}
+
+/// Runs `query` in the running instance and returns its results.
+///
+/// # Errors
+///
+/// Returns a search error if the instance cannot be reached or the query
+/// fails there.
+pub async fn search(query: &str) -> Result<Vec<ResultRecord>> {
+    let exchange = async {
+        let token = read_token(&data_dir()?)?;
+        let mut socket = tokio::net::TcpStream::connect(ipc_addr()).await?;
+        socket
+            .write_all(format!("{token}\n{}\n", query.replace(['\r', '\n'], " ")).as_bytes())
+            .await?;
+        let mut line = String::new();
+        BufReader::new(socket).read_line(&mut line).await?;
+        Ok::<_, std::io::Error>(line)
+    };
+    let line = tokio::time::timeout(CLIENT_TIMEOUT, exchange)
+        .await
+        .map_err(|e| unreachable(query, e))?
+        .map_err(|e| unreachable(query, e))?;
+
+    let reply: serde_json::Value =
+        serde_json::from_str(&line).map_err(|e| FlashError::search(query, e.to_string()))?;
+    match reply {
+        reply @ serde_json::Value::Array(_) => {
+            serde_json::from_value(reply).map_err(|e| FlashError::search(query, e.to_string()))
+        }
+        reply => Err(FlashError::search(
+            query,
+            reply["error"]
+                .as_str()
+                .unwrap_or("unexpected reply")
+                .to_string(),
+        )),
+    }
+}
+
+/// Asks the running instance to bring its window to the front.
+///
+/// # Errors
+///
+/// Returns an I/O error if the instance cannot be reached.
+pub fn request_focus() -> Result<()> {
+    let token = read_token(&data_dir()?)?;
+    let mut socket = std::net::TcpStream::connect_timeout(&ipc_addr(), CLIENT_TIMEOUT)?;
+    socket.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    socket.write_all(format!("{token}\n{FOCUS_REQUEST}\n").as_bytes())?;
+    // Wait for the acknowledgement so the request isn't cut off by our exit
+    let mut ack = String::new();
+    std::io::BufReader::new(socket).read_line(&mut ack)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippets_lose_their_markup() {
+        assert_eq!(
+            plain_snippet("fn <b>main</b>() -&gt;\n   &amp;str"),
+            "fn main() -> &str"
+        );
+    }
+
+    #[test]
+    fn test_token_is_private_and_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = write_token(dir.path()).unwrap();
+        let token = write_token(dir.path()).unwrap();
+        assert_ne!(first, token);
+        assert_eq!(read_token(dir.path()).unwrap(), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let meta = std::fs::metadata(dir.path().join(TOKEN_FILENAME)).unwrap();
+            assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        }
+
+        assert!(token_matches(&token, &token));
+        assert!(!token_matches(&first, &token));
+        assert!(!token_matches("", &token));
+    }
+}
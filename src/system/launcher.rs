@@ -0,0 +1,294 @@
+//! Backend for launcher tools such as PowerToys Run, Raycast and Alfred. A
+//! plugin starts `flash-search launcher` once and writes one JSON request
+//! per line to its stdin:
+//!
+//! ```text
+//! {"id": 1, "query": "budget", "limit": 8}
+//! {"id": 2, "action": "reveal", "path": "C:\\Docs\\budget.xlsx"}
+//! ```
+//!
+//! Each is answered with one line on stdout carrying the same `id`: the
+//! [`LauncherItem`]s for a query, or `{"id": 2, "ok": true}` once an action
+//! ran. Failures come back as `{"id": ..., "error": "..."}`. Queries go to
+//! the running app when it holds the index, so the window can stay open.
+
+use crate::commands::{self, AppState};
+use crate::error::{FlashError, Result};
+use crate::indexer::searcher::SearchParams;
+use crate::system::ipc::{self, ResultRecord};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Results per query unless the request asks for more or fewer
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// Where queries are answered
+pub enum Backend {
+    /// This process holds the index
+    Local(Arc<AppState>),
+    /// The running app does, and answers over IPC
+    Remote,
+}
+
+impl Backend {
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<ResultRecord>> {
+        match self {
+            Self::Local(state) => {
+                let results = commands::search_combined_internal(
+                    SearchParams::builder()
+                        .query(query)
+                        .limit(limit)
+                        .case_sensitive(false)
+                        .build(),
+                    false,
+                    state,
+                )
+                .await
+                .map_err(|e| FlashError::search(query, e))?;
+                Ok(results.iter().map(ResultRecord::from).collect())
+            }
+            Self::Remote => {
+                let mut results = ipc::search(query).await?;
+                results.truncate(limit);
+                Ok(results)
+            }
+        }
+    }
+}
+
+/// Something a launcher can do with a result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LauncherAction {
+    /// `open` or `reveal`, as sent back in an action request
+    pub id: &'static str,
+    pub title: &'static str,
+    /// Path the action applies to
+    pub target: String,
+}
+
+/// A result as launchers show it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LauncherItem {
+    /// Document title, or the file name
+    pub title: String,
+    /// Containing folder
+    pub subtitle: String,
+    pub path: String,
+    /// `file` or `folder`
+    pub kind: &'static str,
+    /// Path whose system icon represents the result
+    pub icon: String,
+    pub score: f32,
+    /// The first is the default
+    pub actions: Vec<LauncherAction>,
+}
+
+impl LauncherItem {
+    fn new(record: &ResultRecord, is_dir: bool) -> Self {
+        let path = Path::new(&record.path);
+        let folder = path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = path
+            .file_name()
+            .map_or_else(|| record.path.clone(), |n| n.to_string_lossy().to_string());
+        Self {
+            title: record
+                .title
+                .clone()
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or(name),
+            subtitle: folder,
+            path: record.path.clone(),
+            kind: if is_dir { "folder" } else { "file" },
+            icon: record.path.clone(),
+            score: record.score,
+            actions: vec![
+                LauncherAction {
+                    id: "open",
+                    title: "Open",
+                    target: record.path.clone(),
+                },
+                LauncherAction {
+                    id: "reveal",
+                    title: "Open containing folder",
+                    target: record.path.clone(),
+                },
+            ],
+        }
+    }
+
+    fn from_record(record: &ResultRecord) -> Self {
+        Self::new(record, Path::new(&record.path).is_dir())
+    }
+
+    /// The item as an Alfred script filter item. Holding ⌘ reveals the file.
+    fn to_alfred(&self) -> Value {
+        json!({
+            "uid": self.path,
+            "type": "file",
+            "title": self.title,
+            "subtitle": self.subtitle,
+            "arg": self.path,
+            "icon": { "type": "fileicon", "path": self.icon },
+            "quicklookurl": self.path,
+            "mods": {
+                "cmd": { "arg": self.path, "subtitle": "Open containing folder" }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    query: Option<String>,
+    limit: Option<usize>,
+    action: Option<String>,
+    path: Option<String>,
+}
+
+fn run_action(action: &str, path: &str) -> std::result::Result<(), String> {
+    match action {
+        "open" => opener::open(Path::new(path)).map_err(|e| e.to_string()),
+        "reveal" => commands::open_folder_internal(path),
+        _ => Err(format!("Unknown action {action}")),
+    }
+}
+
+async fn answer(line: &str, backend: &Backend) -> Value {
+    let request: Request = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return json!({ "id": null, "error": e.to_string() }),
+    };
+    let id = request.id;
+    let outcome = match (request.query, request.action, request.path) {
+        (Some(query), None, _) => items(&query, request.limit.unwrap_or(DEFAULT_LIMIT), backend)
+            .await
+            .map_err(|e| e.to_string()),
+        (None, Some(action), Some(path)) => {
+            run_action(&action, &path).map(|()| json!({ "ok": true }))
+        }
+        _ => Err("A request needs a query, or an action and a path".to_string()),
+    };
+    let mut reply = outcome.unwrap_or_else(|e| json!({ "error": e }));
+    reply["id"] = id;
+    reply
+}
+
+/// Answers requests from stdin until it closes.
+///
+/// # Errors
+///
+/// Returns an I/O error if stdin or stdout fail.
+pub async fn serve_stdio(backend: Backend) -> Result<()> {
+    let (tx, rx) = flume::unbounded::<String>();
+    std::thread::Builder::new()
+        .name("launcher-stdin".to_string())
+        .spawn(move || {
+            for line in std::io::stdin().lines().map_while(std::io::Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+    while let Ok(line) = rx.recv_async().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = answer(&line, &backend).await;
+        let mut stdout = std::io::stdout().lock();
+        serde_json::to_writer(&mut stdout, &reply)
+            .map_err(|e| FlashError::Io(Arc::new(e.into())))?;
+        stdout.write_all(b"\n")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// The items for `query`, as a query request is answered.
+///
+/// # Errors
+///
+/// Returns a search error if the query fails.
+pub async fn items(query: &str, limit: usize, backend: &Backend) -> Result<Value> {
+    let items: Vec<LauncherItem> = backend
+        .search(query, limit)
+        .await?
+        .iter()
+        .map(LauncherItem::from_record)
+        .collect();
+    Ok(json!({ "items": items }))
+}
+
+/// Answers a single query as an Alfred script filter.
+///
+/// # Errors
+///
+/// Returns a search error if the query fails.
+pub async fn alfred(query: &str, limit: usize, backend: &Backend) -> Result<Value> {
+    let items: Vec<Value> = backend
+        .search(query, limit)
+        .await?
+        .iter()
+        .map(|record| LauncherItem::from_record(record).to_alfred())
+        .collect();
+    Ok(json!({ "items": items }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(path: &str, title: Option<&str>) -> ResultRecord {
+        ResultRecord {
+            path: path.to_string(),
+            title: title.map(str::to_string),
+            score: 2.0,
+            size: None,
+            modified: None,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_items_fall_back_to_the_file_name() {
+        let item = LauncherItem::new(&record("/docs/q3/budget.xlsx", None), false);
+        assert_eq!(item.title, "budget.xlsx");
+        assert_eq!(item.subtitle, "/docs/q3");
+        assert_eq!(item.kind, "file");
+        assert_eq!(item.actions[0].id, "open");
+        assert_eq!(item.actions[1].id, "reveal");
+
+        let titled = LauncherItem::new(&record("/docs/q3", Some("Q3")), true);
+        assert_eq!((titled.title.as_str(), titled.kind), ("Q3", "folder"));
+
+        let alfred = item.to_alfred();
+        assert_eq!(alfred["arg"], "/docs/q3/budget.xlsx");
+        assert_eq!(alfred["icon"]["type"], "fileicon");
+    }
+
+    #[tokio::test]
+    async fn test_bad_requests_keep_their_id() {
+        let reply = answer(r#"{"id": 7, "limit": 3}"#, &Backend::Remote).await;
+        assert_eq!(reply["id"], 7);
+        assert!(reply["error"].is_string());
+
+        let reply = answer(
+            r#"{"id": "x", "action": "launch", "path": "/a"}"#,
+            &Backend::Remote,
+        )
+        .await;
+        assert_eq!(reply["id"], "x");
+        assert_eq!(reply["error"], "Unknown action launch");
+
+        let reply = answer("not json", &Backend::Remote).await;
+        assert!(reply["id"].is_null());
+    }
+}
@@ -0,0 +1,355 @@
+//! A Model Context Protocol server on stdin and stdout, so AI assistants can
+//! search the index and read the documents it found. Messages are JSON-RPC,
+//! one per line. The tools go through the commands layer like the UI does,
+//! and only files in the index can be read.
+
+use crate::commands::{self, AppState};
+use crate::error::{FlashError, Result};
+use crate::indexer::searcher::SearchParams;
+use crate::system::ipc::ResultRecord;
+use serde_json::{Value, json};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Protocol revisions this server speaks, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Characters of a document returned unless the caller asks for fewer
+const DEFAULT_PREVIEW_CHARS: usize = 20_000;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What to do with one incoming message
+#[derive(Debug, PartialEq)]
+enum Dispatch {
+    Reply(Value),
+    CallTool {
+        id: Value,
+        name: String,
+        args: Value,
+    },
+    /// Notifications get no answer
+    Ignore,
+}
+
+fn result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search_files",
+            "description": "Full-text search over the user's indexed local documents. \
+                Supports quoted phrases, AND/OR/NOT and filters such as ext:pdf. \
+                Returns paths, titles, scores, sizes, modification times and a matching passage.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "What to search for" },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_SEARCH_LIMIT,
+                        "description": format!("Most results to return, {DEFAULT_SEARCH_LIMIT} by default")
+                    }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "get_file_preview",
+            "description": "Extracted text of an indexed document, e.g. one returned by search_files. \
+                Works for PDFs, office documents and source files alike.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Full path of the file" },
+                    "max_chars": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": format!("Characters to return, {DEFAULT_PREVIEW_CHARS} by default")
+                    }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "list_recent_files",
+            "description": "The most recently modified documents in the index.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_SEARCH_LIMIT,
+                        "description": format!("Most files to return, {DEFAULT_SEARCH_LIMIT} by default")
+                    }
+                }
+            }
+        }
+    ])
+}
+
+/// Answers everything but tool calls, which need the index.
+fn dispatch(message: &Value) -> Dispatch {
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return match message.get("id") {
+            Some(id) => Dispatch::Reply(error(id.clone(), INVALID_REQUEST, "Missing method")),
+            // A response to a request we never send
+            None => Dispatch::Ignore,
+        };
+    };
+    let Some(id) = message.get("id").cloned() else {
+        return Dispatch::Ignore;
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str);
+            let version = requested
+                .and_then(|v| PROTOCOL_VERSIONS.iter().find(|&&known| known == v))
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            Dispatch::Reply(result(
+                id,
+                json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "flash-search", "version": env!("CARGO_PKG_VERSION") },
+                    "instructions": "Search the user's local documents with search_files, \
+                        then read the relevant ones with get_file_preview."
+                }),
+            ))
+        }
+        "ping" => Dispatch::Reply(result(id, json!({}))),
+        "tools/list" => Dispatch::Reply(result(id, json!({ "tools": tool_definitions() }))),
+        "tools/call" => match params.get("name").and_then(Value::as_str) {
+            Some(name) => Dispatch::CallTool {
+                id,
+                name: name.to_string(),
+                args: params
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_else(|| json!({})),
+            },
+            None => Dispatch::Reply(error(id, INVALID_PARAMS, "Missing tool name")),
+        },
+        _ => Dispatch::Reply(error(
+            id,
+            METHOD_NOT_FOUND,
+            format!("Unknown method {method}"),
+        )),
+    }
+}
+
+fn limit_arg(args: &Value, name: &str, default: usize, max: usize) -> usize {
+    args.get(name)
+        .and_then(Value::as_u64)
+        .and_then(|n| usize::try_from(n).ok())
+        .map_or(default, |n| n.clamp(1, max))
+}
+
+async fn search_files(args: &Value, state: &Arc<AppState>) -> std::result::Result<Value, String> {
+    let query = args
+        .get("query")
+        .and_then(Value::as_str)
+        .filter(|q| !q.trim().is_empty())
+        .ok_or("query is required")?;
+    let limit = limit_arg(args, "limit", DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT);
+    let results = commands::search_combined_internal(
+        SearchParams::builder()
+            .query(query)
+            .limit(limit)
+            .case_sensitive(false)
+            .build(),
+        false,
+        state,
+    )
+    .await?;
+    let results: Vec<ResultRecord> = results.iter().map(ResultRecord::from).collect();
+    serde_json::to_value(results).map_err(|e| e.to_string())
+}
+
+async fn get_file_preview(
+    args: &Value,
+    state: &Arc<AppState>,
+) -> std::result::Result<Value, String> {
+    let path = args
+        .get("path")
+        .and_then(Value::as_str)
+        .ok_or("path is required")?;
+    // Only what the user chose to index is handed out
+    let indexed = state
+        .metadata_db
+        .get_metadata(Path::new(path))
+        .map_err(|e| e.to_string())?
+        .is_some();
+    if !indexed {
+        return Err(format!("{path} is not in the index"));
+    }
+    let max_chars = limit_arg(args, "max_chars", DEFAULT_PREVIEW_CHARS, usize::MAX);
+
+    let enable_ocr = state.settings_cache.load().enable_ocr;
+    let text = commands::get_file_text_internal(path.to_string(), enable_ocr).await?;
+    let total_chars = text.chars().count();
+    Ok(json!({
+        "path": path,
+        "text": text.chars().take(max_chars).collect::<String>(),
+        "truncated": total_chars > max_chars,
+        "total_chars": total_chars,
+    }))
+}
+
+async fn list_recent_files(
+    args: &Value,
+    state: &Arc<AppState>,
+) -> std::result::Result<Value, String> {
+    let limit = limit_arg(args, "limit", DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT);
+    let files = commands::get_recent_files_internal(limit, state).await?;
+    serde_json::to_value(files).map_err(|e| e.to_string())
+}
+
+async fn call_tool(id: Value, name: &str, args: &Value, state: &Arc<AppState>) -> Value {
+    let outcome = match name {
+        "search_files" => search_files(args, state).await,
+        "get_file_preview" => get_file_preview(args, state).await,
+        "list_recent_files" => list_recent_files(args, state).await,
+        _ => return error(id, INVALID_PARAMS, format!("Unknown tool {name}")),
+    };
+    // Tool failures are reported to the model, not as protocol errors
+    let (content, is_error) = match outcome {
+        Ok(value) => (
+            serde_json::to_string_pretty(&value).unwrap_or_default(),
+            false,
+        ),
+        Err(e) => (e, true),
+    };
+    result(
+        id,
+        json!({ "content": [{ "type": "text", "text": content }], "isError": is_error }),
+    )
+}
+
+/// Serves MCP requests from stdin until it closes.
+///
+/// # Errors
+///
+/// Returns an I/O error if stdout cannot be written.
+pub async fn serve_stdio(state: Arc<AppState>) -> Result<()> {
+    let (tx, rx) = flume::unbounded::<String>();
+    std::thread::Builder::new()
+        .name("mcp-stdin".to_string())
+        .spawn(move || {
+            for line in std::io::stdin().lines().map_while(std::io::Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+    while let Ok(line) = rx.recv_async().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => match dispatch(&message) {
+                Dispatch::Reply(reply) => reply,
+                Dispatch::CallTool { id, name, args } => call_tool(id, &name, &args, &state).await,
+                Dispatch::Ignore => continue,
+            },
+            Err(e) => error(Value::Null, PARSE_ERROR, e.to_string()),
+        };
+        let mut stdout = std::io::stdout().lock();
+        serde_json::to_writer(&mut stdout, &reply)
+            .map_err(|e| FlashError::Io(Arc::new(e.into())))?;
+        stdout.write_all(b"\n")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+    }
+
+    #[test]
+    fn test_handshake_and_tool_listing() {
+        let Dispatch::Reply(reply) = dispatch(&request(
+            "initialize",
+            json!({ "protocolVersion": "2024-11-05", "capabilities": {} }),
+        )) else {
+            panic!("initialize is answered directly");
+        };
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert!(reply["result"]["capabilities"]["tools"].is_object());
+
+        // An unknown revision gets the newest one
+        let Dispatch::Reply(reply) = dispatch(&request(
+            "initialize",
+            json!({ "protocolVersion": "1999-01-01" }),
+        )) else {
+            panic!("initialize is answered directly");
+        };
+        assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        assert_eq!(
+            dispatch(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+            Dispatch::Ignore
+        );
+
+        let Dispatch::Reply(reply) = dispatch(&request("tools/list", Value::Null)) else {
+            panic!("tools/list is answered directly");
+        };
+        let names: Vec<&str> = reply["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["search_files", "get_file_preview", "list_recent_files"]
+        );
+    }
+
+    #[test]
+    fn test_calls_and_errors() {
+        assert_eq!(
+            dispatch(&request(
+                "tools/call",
+                json!({ "name": "search_files", "arguments": { "query": "budget" } })
+            )),
+            Dispatch::CallTool {
+                id: json!(1),
+                name: "search_files".to_string(),
+                args: json!({ "query": "budget" }),
+            }
+        );
+        let Dispatch::Reply(reply) = dispatch(&request("resources/list", Value::Null)) else {
+            panic!("unknown methods are answered directly");
+        };
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+        assert_eq!(limit_arg(&json!({ "limit": 0 }), "limit", 10, 100), 1);
+        assert_eq!(limit_arg(&json!({ "limit": 500 }), "limit", 10, 100), 100);
+        assert_eq!(limit_arg(&json!({}), "limit", 10, 100), 10);
+    }
+}
@@ -1,3 +1,14 @@
+pub mod activity;
+pub mod backup;
+pub mod connectivity;
 pub mod context_menu;
+pub mod instance;
+pub mod ipc;
+pub mod launcher;
+pub mod mcp;
+pub mod open_with;
+pub mod profile;
+pub mod scheduler;
 pub mod startup;
 pub mod tray;
+pub mod vault;
@@ -0,0 +1,608 @@
+//! Applications registered to open a type of file, for the "Open with"
+//! picker: the OpenWith lists of the registry on Windows, Launch Services on
+//! macOS and the XDG MIME application lists elsewhere. Also starts the
+//! user's own command lines, such as an editor opening a file at a line.
+
+use crate::models::OpenWithApp;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+/// Applications registered for the type of `path`, the default one first.
+#[must_use]
+pub fn applications_for(path: &Path) -> Vec<OpenWithApp> {
+    let mut apps = platform::applications_for(path);
+    let mut seen = HashSet::new();
+    apps.retain(|app| seen.insert(app.launch.clone()));
+    apps
+}
+
+/// Opens `path` with `app`, one of those listed for it.
+///
+/// # Errors
+///
+/// Returns an error if the application can't be started.
+pub fn launch(app: &OpenWithApp, path: &Path) -> io::Result<()> {
+    platform::launch(app, path)
+}
+
+/// Runs the command line `template` with each `{name}` of `values` filled
+/// in. Placeholders are filled after the line is split, so a path with
+/// spaces stays one argument.
+///
+/// # Errors
+///
+/// Returns an error if the command is empty or can't be started.
+pub fn launch_template(template: &str, values: &[(&str, String)]) -> io::Result<()> {
+    let args: Vec<String> = split_command_line(template, cfg!(not(windows)))
+        .into_iter()
+        .map(|arg| {
+            values.iter().fold(arg, |arg, (name, value)| {
+                arg.replace(&format!("{{{name}}}"), value)
+            })
+        })
+        .collect();
+    spawn(&args)
+}
+
+/// Opens `template` with each `{name}` of `values` filled in. A URL such as
+/// `obsidian://open?path={}` goes to the app handling its scheme, with the
+/// values encoded for a URL; anything else runs as a command line.
+///
+/// # Errors
+///
+/// Returns an error if the URL or command can't be opened.
+pub fn open_template(template: &str, values: &[(&str, String)]) -> io::Result<()> {
+    let template = template.trim();
+    if !is_url(template) {
+        return launch_template(template, values);
+    }
+    let url = values
+        .iter()
+        .fold(template.to_string(), |url, (name, value)| {
+            url.replace(&format!("{{{name}}}"), &percent_encode(value))
+        });
+    opener::open(&url).map_err(io::Error::other)
+}
+
+/// Whether `template` is a URL with a scheme, rather than a command line.
+fn is_url(template: &str) -> bool {
+    template.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    }) && !template.contains(char::is_whitespace)
+}
+
+/// `value` with everything but unreserved URL characters percent-encoded.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Splits a command line into its arguments. Double quotes group words;
+/// with `escapes`, a backslash keeps the character after it as it is.
+fn split_command_line(line: &str, escapes: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => {
+                if let Some(next) = chars.next() {
+                    arg.push(next);
+                }
+                in_arg = true;
+            }
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    args
+}
+
+/// Starts the program of `args` on its own.
+fn spawn(args: &[String]) -> io::Result<()> {
+    let (program, rest) = args
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+    std::process::Command::new(program).args(rest).spawn()?;
+    Ok(())
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{spawn, split_command_line};
+    use crate::models::OpenWithApp;
+    use std::io;
+    use std::path::Path;
+    use winreg::RegKey;
+    use winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER};
+
+    /// Placeholders Windows replaces with the file's path
+    const PATH_PLACEHOLDERS: [&str; 4] = ["%1", "%L", "%l", "%V"];
+
+    /// The extension's ProgIDs, then the programs listed to open it, both
+    /// from the machine's classes and the user's own choices.
+    pub fn applications_for(path: &Path) -> Vec<OpenWithApp> {
+        let Some(ext) = path
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+        else {
+            return Vec::new();
+        };
+        let classes = RegKey::predef(HKEY_CLASSES_ROOT);
+        let machine = classes.open_subkey(&ext).ok();
+        let user = RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(format!(
+                r"Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts\{ext}"
+            ))
+            .ok();
+        let value_names = |key: &RegKey, list: &str| -> Vec<String> {
+            key.open_subkey(list)
+                .map(|list| {
+                    list.enum_values()
+                        .filter_map(Result::ok)
+                        .map(|(name, _)| name)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut progids = Vec::new();
+        let mut programs = Vec::new();
+        if let Some(key) = &machine {
+            progids.extend(
+                key.get_value::<String, _>("")
+                    .ok()
+                    .filter(|id| !id.is_empty()),
+            );
+            progids.extend(value_names(key, "OpenWithProgids"));
+            if let Ok(list) = key.open_subkey("OpenWithList") {
+                programs.extend(list.enum_keys().filter_map(Result::ok));
+            }
+        }
+        if let Some(key) = &user {
+            progids.extend(value_names(key, "OpenWithProgids"));
+            if let Ok(list) = key.open_subkey("OpenWithList") {
+                programs.extend(
+                    list.enum_values()
+                        .filter_map(Result::ok)
+                        .filter(|(name, _)| name != "MRUList")
+                        .map(|(_, value)| value.to_string()),
+                );
+            }
+        }
+
+        let progid_apps = progids.iter().filter_map(|id| {
+            let key = classes.open_subkey(id).ok()?;
+            let launch = open_command(&key)?;
+            let name = key
+                .open_subkey("Application")
+                .and_then(|app| app.get_value::<String, _>("ApplicationName"))
+                .ok()
+                .filter(|name| !name.is_empty() && !name.starts_with('@'))
+                .or_else(|| program_name(&launch))
+                .unwrap_or_else(|| id.clone());
+            Some(OpenWithApp { name, launch })
+        });
+        let program_apps = programs.iter().filter_map(|exe| {
+            let key = classes.open_subkey(format!(r"Applications\{exe}")).ok()?;
+            let launch = open_command(&key)?;
+            let name = key
+                .get_value::<String, _>("FriendlyAppName")
+                .ok()
+                .filter(|name| !name.is_empty() && !name.starts_with('@'))
+                .or_else(|| program_name(&launch))
+                .unwrap_or_else(|| exe.clone());
+            Some(OpenWithApp { name, launch })
+        });
+        progid_apps.chain(program_apps).collect()
+    }
+
+    fn open_command(key: &RegKey) -> Option<String> {
+        key.open_subkey(r"shell\open\command")
+            .and_then(|command| command.get_value::<String, _>(""))
+            .ok()
+            .filter(|command| !command.is_empty())
+    }
+
+    /// The program's file name without its extension.
+    fn program_name(command: &str) -> Option<String> {
+        let program = split_command_line(command, false).into_iter().next()?;
+        Path::new(&program)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    }
+
+    /// Replaces `%NAME%` with the environment variable, as the shell does for
+    /// expandable registry strings.
+    fn expand_env(arg: &str) -> String {
+        let mut out = String::new();
+        let mut rest = arg;
+        while let Some(start) = rest.find('%') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            match after
+                .find('%')
+                .map(|end| (end, std::env::var(&after[..end])))
+            {
+                Some((end, Ok(value))) if end > 0 => {
+                    out.push_str(&value);
+                    rest = &after[end + 1..];
+                }
+                _ => {
+                    out.push('%');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    pub fn launch(app: &OpenWithApp, path: &Path) -> io::Result<()> {
+        let file = path.to_string_lossy();
+        let mut has_path = false;
+        let mut args: Vec<String> = split_command_line(&app.launch, false)
+            .into_iter()
+            .filter(|arg| arg != "%*")
+            .map(|arg| {
+                let mut arg = arg;
+                for placeholder in PATH_PLACEHOLDERS {
+                    if arg.contains(placeholder) {
+                        arg = arg.replace(placeholder, &file);
+                        has_path = true;
+                    }
+                }
+                expand_env(&arg)
+            })
+            .collect();
+        if !has_path {
+            args.push(file.to_string());
+        }
+        spawn(&args)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::models::OpenWithApp;
+    use std::ffi::{CStr, c_void};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    type CFTypeRef = *const c_void;
+
+    /// Viewers, editors and shells alike
+    const LS_ROLES_ALL: u32 = 0xFFFF_FFFF;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFURLCreateFromFileSystemRepresentation(
+            allocator: CFTypeRef,
+            buffer: *const u8,
+            len: isize,
+            is_directory: u8,
+        ) -> CFTypeRef;
+        fn CFURLGetFileSystemRepresentation(
+            url: CFTypeRef,
+            resolve_against_base: u8,
+            buffer: *mut u8,
+            max_len: isize,
+        ) -> u8;
+        fn CFArrayGetCount(array: CFTypeRef) -> isize;
+        fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    unsafe extern "C" {
+        fn LSCopyApplicationURLsForURL(url: CFTypeRef, roles: u32) -> CFTypeRef;
+    }
+
+    /// Application bundles Launch Services knows can open the file.
+    pub fn applications_for(path: &Path) -> Vec<OpenWithApp> {
+        let bytes = path.as_os_str().as_bytes();
+        let Ok(len) = isize::try_from(bytes.len()) else {
+            return Vec::new();
+        };
+        let mut apps = Vec::new();
+        // SAFETY: every object created or copied here is released, and the
+        // array's elements are only borrowed while it is alive.
+        unsafe {
+            let url =
+                CFURLCreateFromFileSystemRepresentation(std::ptr::null(), bytes.as_ptr(), len, 0);
+            if url.is_null() {
+                return apps;
+            }
+            let bundles = LSCopyApplicationURLsForURL(url, LS_ROLES_ALL);
+            CFRelease(url);
+            if bundles.is_null() {
+                return apps;
+            }
+            let mut buffer = [0u8; 1024];
+            for index in 0..CFArrayGetCount(bundles) {
+                let bundle = CFArrayGetValueAtIndex(bundles, index);
+                if CFURLGetFileSystemRepresentation(bundle, 1, buffer.as_mut_ptr(), 1024) == 0 {
+                    continue;
+                }
+                let Ok(bundle) = CStr::from_bytes_until_nul(&buffer) else {
+                    continue;
+                };
+                let bundle = PathBuf::from(std::ffi::OsStr::from_bytes(bundle.to_bytes()));
+                apps.push(OpenWithApp {
+                    name: bundle
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    launch: bundle.to_string_lossy().to_string(),
+                });
+            }
+            CFRelease(bundles);
+        }
+        apps
+    }
+
+    pub fn launch(app: &OpenWithApp, path: &Path) -> io::Result<()> {
+        std::process::Command::new("open")
+            .arg("-a")
+            .arg(&app.launch)
+            .arg(path)
+            .spawn()?;
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::{spawn, split_command_line};
+    use crate::models::OpenWithApp;
+    use std::collections::HashSet;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// `$XDG_<name>_HOME` then `$XDG_<name>_DIRS`, with the spec's defaults.
+    fn xdg_dirs(home: &str, home_default: &str, dirs: &str, dirs_default: &str) -> Vec<PathBuf> {
+        let home = std::env::var_os(home)
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|dir| dir.join(home_default)));
+        let dirs = std::env::var(dirs).unwrap_or_else(|_| dirs_default.to_string());
+        home.into_iter()
+            .chain(dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from))
+            .collect()
+    }
+
+    fn data_dirs() -> Vec<PathBuf> {
+        xdg_dirs(
+            "XDG_DATA_HOME",
+            ".local/share",
+            "XDG_DATA_DIRS",
+            "/usr/local/share:/usr/share",
+        )
+    }
+
+    fn config_dirs() -> Vec<PathBuf> {
+        xdg_dirs("XDG_CONFIG_HOME", ".config", "XDG_CONFIG_DIRS", "/etc/xdg")
+    }
+
+    /// Desktop file IDs a `mimeapps.list` or `mimeinfo.cache` associates
+    /// with `mime`, and those it removes.
+    pub fn associations(text: &str, mime: &str) -> (Vec<String>, Vec<String>) {
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut section = "";
+        for line in text.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name;
+                continue;
+            }
+            let Some(ids) = line
+                .split_once('=')
+                .filter(|(key, _)| key.trim() == mime)
+                .map(|(_, ids)| ids.split(';').map(str::trim).filter(|id| !id.is_empty()))
+            else {
+                continue;
+            };
+            match section {
+                "Default Applications" | "Added Associations" | "MIME Cache" => {
+                    added.extend(ids.map(String::from));
+                }
+                "Removed Associations" => removed.extend(ids.map(String::from)),
+                _ => {}
+            }
+        }
+        (added, removed)
+    }
+
+    /// Name and command line of a desktop entry, unless it is hidden.
+    pub fn parse_desktop_entry(text: &str) -> Option<(String, String)> {
+        let mut in_entry = false;
+        let mut name = None;
+        let mut exec = None;
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_entry {
+                continue;
+            }
+            match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("Name", value)) => name = Some(value.to_string()),
+                Some(("Exec", value)) => exec = Some(value.to_string()),
+                Some(("Hidden", "true")) => return None,
+                _ => {}
+            }
+        }
+        Some((name?, exec?))
+    }
+
+    /// Arguments of an `Exec` line opening `path`. File and URL field codes
+    /// become the path, other field codes are dropped, and the path is
+    /// added at the end if the line has no place for it.
+    pub fn exec_args(exec: &str, path: &Path) -> Vec<String> {
+        let file = path.to_string_lossy();
+        let mut has_path = false;
+        let mut args = Vec::new();
+        for arg in split_command_line(exec, true) {
+            match arg.as_str() {
+                "%f" | "%F" | "%u" | "%U" => {
+                    args.push(file.to_string());
+                    has_path = true;
+                }
+                "%i" | "%c" | "%k" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+                _ => args.push(arg.replace("%%", "%")),
+            }
+        }
+        if !has_path {
+            args.push(file.to_string());
+        }
+        args
+    }
+
+    /// Finds the desktop file of `id`; a dash in the ID may stand for a
+    /// subdirectory.
+    fn desktop_file(id: &str, data_dirs: &[PathBuf]) -> Option<String> {
+        data_dirs.iter().find_map(|dir| {
+            let apps = dir.join("applications");
+            std::fs::read_to_string(apps.join(id))
+                .or_else(|_| std::fs::read_to_string(apps.join(id.replacen('-', "/", 1))))
+                .ok()
+        })
+    }
+
+    pub fn applications_for(path: &Path) -> Vec<OpenWithApp> {
+        let Ok(mime) = xberg::detect_mime_type(path.to_string_lossy().into_owned(), true) else {
+            return Vec::new();
+        };
+        let data_dirs = data_dirs();
+        let lists = config_dirs()
+            .into_iter()
+            .map(|dir| dir.join("mimeapps.list"))
+            .chain(
+                data_dirs
+                    .iter()
+                    .map(|dir| dir.join("applications/mimeapps.list")),
+            )
+            .chain(
+                data_dirs
+                    .iter()
+                    .map(|dir| dir.join("applications/mimeinfo.cache")),
+            );
+
+        // Earlier lists take precedence, so a removal only hides what later
+        // lists add
+        let mut ids = Vec::new();
+        let mut removed = HashSet::new();
+        for list in lists {
+            let Ok(text) = std::fs::read_to_string(list) else {
+                continue;
+            };
+            let (added, gone) = associations(&text, &mime);
+            ids.extend(added.into_iter().filter(|id| !removed.contains(id)));
+            removed.extend(gone);
+        }
+        ids.iter()
+            .filter_map(|id| desktop_file(id, &data_dirs))
+            .filter_map(|text| parse_desktop_entry(&text))
+            .map(|(name, launch)| OpenWithApp { name, launch })
+            .collect()
+    }
+
+    pub fn launch(app: &OpenWithApp, path: &Path) -> io::Result<()> {
+        spawn(&exec_args(&app.launch, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_lines_split_on_unquoted_spaces() {
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\App\app.exe" /open "%1""#, false),
+            [r"C:\Program Files\App\app.exe", "/open", "%1"]
+        );
+        assert_eq!(
+            split_command_line(r#"sh -c "echo \"hi\"" """#, true),
+            ["sh", "-c", r#"echo "hi""#, ""]
+        );
+    }
+
+    #[test]
+    fn test_url_templates_are_told_from_commands() {
+        assert!(is_url("obsidian://open?path={}"));
+        assert!(!is_url("code -g {file}:{line}"));
+        // A URL passed to a program is still a command line
+        assert!(!is_url("firefox https://example.com/?q={}"));
+        assert_eq!(percent_encode("/notes/a b&c.md"), "%2Fnotes%2Fa%20b%26c.md");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_xdg_lists_and_desktop_entries() {
+        use platform::{associations, exec_args, parse_desktop_entry};
+
+        let list = "[Default Applications]\ntext/plain=gedit.desktop\n\
+                    [Added Associations]\ntext/plain=vim.desktop;code.desktop;\nimage/png=gimp.desktop;\n\
+                    [Removed Associations]\ntext/plain=nano.desktop;\n";
+        assert_eq!(
+            associations(list, "text/plain"),
+            (
+                vec![
+                    "gedit.desktop".into(),
+                    "vim.desktop".into(),
+                    "code.desktop".into()
+                ],
+                vec!["nano.desktop".into()]
+            )
+        );
+
+        let entry = "[Desktop Entry]\nName=Text Editor\nName[de]=Texteditor\n\
+                     Exec=gedit --new-window %U\n[Desktop Action new]\nExec=gedit --other\n";
+        let (name, exec) = parse_desktop_entry(entry).unwrap();
+        assert_eq!(
+            (name.as_str(), exec.as_str()),
+            ("Text Editor", "gedit --new-window %U")
+        );
+        assert!(parse_desktop_entry("[Desktop Entry]\nName=X\nExec=x\nHidden=true\n").is_none());
+
+        let path = Path::new("/docs/my notes.txt");
+        assert_eq!(
+            exec_args(&exec, path),
+            ["gedit", "--new-window", "/docs/my notes.txt"]
+        );
+        assert_eq!(
+            exec_args("viewer --icon %i --rate 100%%", path),
+            ["viewer", "--icon", "--rate", "100%", "/docs/my notes.txt"]
+        );
+    }
+}
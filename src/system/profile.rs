@@ -0,0 +1,198 @@
+//! Named profiles, each with its own settings, search index and metadata
+//! store, so that work and personal files never share results. The default
+//! profile lives in the app data directory itself and every other profile in
+//! a directory under `profiles/`. A process serves one profile, picked once at
+//! startup: the one given with `--profile`, or else the one last chosen in the
+//! window.
+
+use crate::error::{FlashError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The profile that uses the app data directory itself
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Directory in the app data directory holding the other profiles
+pub const PROFILES_DIRNAME: &str = "profiles";
+
+/// File in the app data directory naming the profile chosen in the window
+pub const ACTIVE_PROFILE_FILENAME: &str = "active_profile";
+
+/// Longest profile name accepted
+const MAX_NAME_LEN: usize = 64;
+
+/// Port the default profile's instance answers on; see [`ipc_port`]
+const DEFAULT_IPC_PORT: u16 = 9095;
+
+/// Ports other profiles are spread over, starting after the default's
+const IPC_PORT_RANGE: u16 = 900;
+
+/// Profile this process serves, fixed on first use
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+/// Checks that `name` can name a profile: letters, digits, spaces, `-` and
+/// `_`, not starting or ending with a space.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.trim() == name
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(FlashError::config(
+            "profile",
+            format!(
+                "'{name}' is not a valid profile name; use letters, digits, spaces, '-' and '_'"
+            ),
+        ))
+    }
+}
+
+/// Makes `name` the profile this process serves. Has to happen before the
+/// app data directory is first asked for.
+///
+/// # Errors
+///
+/// Returns a config error for an invalid name, or if another profile is
+/// already in use.
+pub fn select(name: &str) -> Result<()> {
+    validate_name(name)?;
+    if ACTIVE.get_or_init(|| name.to_string()) != name {
+        return Err(FlashError::config(
+            "profile",
+            format!("profile '{}' is already in use", active()),
+        ));
+    }
+    Ok(())
+}
+
+/// The profile this process serves.
+pub fn active() -> &'static str {
+    ACTIVE.get_or_init(|| {
+        crate::get_base_data_dir()
+            .ok()
+            .and_then(|base| remembered(&base))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    })
+}
+
+/// Data directory of profile `name` under the app data directory `base`.
+pub fn data_dir(base: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIRNAME).join(name)
+    }
+}
+
+/// Every profile under `base`, the default first and the rest by name.
+pub fn list(base: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(base.join(PROFILES_DIRNAME))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != DEFAULT_PROFILE && validate_name(name).is_ok())
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Creates the data directory of profile `name` under `base`, if it doesn't
+/// exist yet.
+pub fn create(base: &Path, name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    let dir = data_dir(base, name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Opens the window with profile `name` from now on.
+pub fn remember(base: &Path, name: &str) -> Result<()> {
+    validate_name(name)?;
+    std::fs::create_dir_all(base)?;
+    std::fs::write(base.join(ACTIVE_PROFILE_FILENAME), name)?;
+    Ok(())
+}
+
+/// The profile last chosen in the window, if it still exists.
+fn remembered(base: &Path) -> Option<String> {
+    let name = std::fs::read_to_string(base.join(ACTIVE_PROFILE_FILENAME)).ok()?;
+    let name = name.trim();
+    (validate_name(name).is_ok() && data_dir(base, name).is_dir()).then(|| name.to_string())
+}
+
+/// Loopback port the instance serving profile `name` answers on. Other
+/// profiles get a port derived from their name, so two windows for different
+/// profiles can run side by side.
+pub fn ipc_port(name: &str) -> u16 {
+    if name == DEFAULT_PROFILE {
+        return DEFAULT_IPC_PORT;
+    }
+    // FNV-1a, which unlike the std hasher is stable across builds
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    // The remainder is below IPC_PORT_RANGE, so it always fits
+    let offset = u16::try_from(hash % u64::from(IPC_PORT_RANGE)).unwrap_or_default();
+    DEFAULT_IPC_PORT + 1 + offset
+}
+
+/// Starts a new window for profile `name`, which the window opens with from
+/// now on. The caller exits afterwards.
+pub fn relaunch(name: &str) -> Result<()> {
+    remember(&crate::get_base_data_dir()?, name)?;
+    let exe = std::env::current_exe()?;
+    std::process::Command::new(exe)
+        .args(["--profile", name])
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names_are_checked() {
+        assert!(validate_name("Work").is_ok());
+        assert!(validate_name("side-project_2 b").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(" Work").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_profiles_live_in_their_own_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        assert_eq!(data_dir(base, DEFAULT_PROFILE), base);
+        assert_eq!(list(base), vec![DEFAULT_PROFILE]);
+
+        let work = create(base, "Work").unwrap();
+        assert_eq!(work, base.join(PROFILES_DIRNAME).join("Work"));
+        create(base, "code").unwrap();
+        assert_eq!(list(base), vec![DEFAULT_PROFILE, "code", "Work"]);
+
+        assert_eq!(remembered(base), None);
+        remember(base, "Work").unwrap();
+        assert_eq!(remembered(base).as_deref(), Some("Work"));
+        std::fs::remove_dir(&work).unwrap();
+        assert_eq!(remembered(base), None);
+    }
+
+    #[test]
+    fn test_profiles_get_their_own_port() {
+        assert_eq!(ipc_port(DEFAULT_PROFILE), DEFAULT_IPC_PORT);
+        let work = ipc_port("Work");
+        assert!(work > DEFAULT_IPC_PORT && work <= DEFAULT_IPC_PORT + IPC_PORT_RANGE);
+        assert_eq!(work, ipc_port("Work"));
+    }
+}
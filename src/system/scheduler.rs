@@ -0,0 +1,185 @@
+use crate::commands::AppState;
+use crate::settings::ReindexSchedule;
+use jiff::Zoned;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the scheduler wakes to check whether a refresh is due. Settings
+/// changes take effect within one tick.
+const TICK: Duration = Duration::from_secs(60);
+
+/// Next time `schedule` should run, as a Unix timestamp.
+///
+/// `last_run` of `None` counts from `now`, so interval schedules don't fire the
+/// moment they are switched on. A daily slot that passed since `last_run` (the
+/// machine was off or asleep at the time) is due immediately.
+#[must_use]
+pub fn next_run(schedule: ReindexSchedule, last_run: Option<u64>, now: &Zoned) -> Option<u64> {
+    let now_secs = u64::try_from(now.timestamp().as_second()).unwrap_or(0);
+    match schedule {
+        ReindexSchedule::Off => None,
+        ReindexSchedule::Interval { hours } => {
+            let step = u64::from(hours.max(1)) * 3600;
+            Some(last_run.map_or(now_secs + step, |last| (last + step).max(now_secs)))
+        }
+        ReindexSchedule::Daily { hour, minute } => {
+            let tz = now.time_zone().clone();
+            let slot_on = |date: jiff::civil::Date| {
+                let at = date
+                    .at(i8::try_from(hour).ok()?, i8::try_from(minute).ok()?, 0, 0)
+                    .to_zoned(tz.clone())
+                    .ok()?;
+                u64::try_from(at.timestamp().as_second()).ok()
+            };
+
+            let today = now.date();
+            let slot_today = slot_on(today)?;
+            let (latest, upcoming) = if slot_today <= now_secs {
+                (slot_today, slot_on(today.tomorrow().ok()?)?)
+            } else {
+                (slot_on(today.yesterday().ok()?)?, slot_today)
+            };
+
+            if last_run.is_some_and(|last| last < latest) {
+                Some(now_secs)
+            } else {
+                Some(upcoming)
+            }
+        }
+    }
+}
+
+/// Merges the index down to one segment once commits have left more than the
+/// configured number behind, provided no scan is running.
+async fn optimize_when_idle(state: &Arc<AppState>) {
+    let threshold = state
+        .settings_cache
+        .load()
+        .index_writer
+        .optimize_idle_segments;
+    if threshold == 0 || state.indexer.segment_count() <= threshold {
+        return;
+    }
+    match crate::commands::optimize_index_internal(state).await {
+        Ok(0) | Err(_) => {}
+        Ok(merged) => tracing::info!("Merged {merged} index segments while idle"),
+    }
+}
+
+/// Runs scheduled refreshes, and keeps the index under its size cap and merges
+/// segments when idle, in the background for the lifetime of the app. Roots
+/// with a schedule of their own are refreshed on it instead of the global one.
+/// A due run is skipped while another scan holds the indexing handle and
+/// retried on the next tick.
+pub fn spawn_scheduler(state: Arc<AppState>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let started = u64::try_from(Zoned::now().timestamp().as_second()).unwrap_or(0);
+        loop {
+            tokio::time::sleep(TICK).await;
+            if crate::is_shutting_down() {
+                break;
+            }
+            if let Err(e) = crate::commands::enforce_index_size_cap(&state).await {
+                tracing::error!("Failed to keep the index under its size cap: {e}");
+            }
+            optimize_when_idle(&state).await;
+
+            let now = Zoned::now();
+            let now_secs = u64::try_from(now.timestamp().as_second()).unwrap_or(0);
+            let is_due = |schedule, last_run: Option<u64>| {
+                next_run(schedule, last_run.or(Some(started)), &now)
+                    .is_some_and(|due| due <= now_secs)
+            };
+            let (schedule, roots) = {
+                let settings = state.settings_cache.load();
+                let roots: Vec<_> = settings
+                    .index_roots
+                    .iter()
+                    .filter(|root| {
+                        root.rescan.is_some_and(|rescan| {
+                            is_due(
+                                rescan,
+                                state
+                                    .metadata_db
+                                    .get_root_last_run(&root.path)
+                                    .ok()
+                                    .flatten(),
+                            )
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                (settings.reindex_schedule, roots)
+            };
+            let global_due = is_due(
+                schedule,
+                state.metadata_db.get_last_scheduled_run().ok().flatten(),
+            );
+            if !global_due && roots.is_empty() {
+                continue;
+            }
+
+            let busy = state
+                .indexing_handle
+                .lock()
+                .as_ref()
+                .is_some_and(|handle| !handle.is_finished());
+            if busy {
+                continue;
+            }
+
+            if global_due {
+                tracing::info!("Running scheduled refresh ({schedule})");
+                if let Err(e) = crate::commands::run_scheduled_refresh_internal(&state).await {
+                    tracing::error!("Scheduled refresh failed: {e}");
+                }
+            }
+            if !roots.is_empty() {
+                tracing::info!("Refreshing {} root(s) on their own schedule", roots.len());
+                if let Err(e) = crate::commands::run_root_refresh_internal(&state, roots).await {
+                    tracing::error!("Scheduled refresh failed: {e}");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> Zoned {
+        format!("{date}[UTC]").parse().unwrap()
+    }
+
+    fn secs(date: &str) -> u64 {
+        u64::try_from(at(date).timestamp().as_second()).unwrap()
+    }
+
+    #[test]
+    fn test_next_run() {
+        let daily = ReindexSchedule::Daily { hour: 2, minute: 0 };
+        let now = at("2026-03-10T14:00:00");
+
+        assert_eq!(next_run(ReindexSchedule::Off, None, &now), None);
+        assert_eq!(
+            next_run(daily, Some(secs("2026-03-10T02:00:00")), &now),
+            Some(secs("2026-03-11T02:00:00"))
+        );
+        // Slept through last night's slot
+        assert_eq!(
+            next_run(daily, Some(secs("2026-03-09T02:00:00")), &now),
+            Some(secs("2026-03-10T14:00:00"))
+        );
+
+        let every_six = ReindexSchedule::Interval { hours: 6 };
+        assert_eq!(
+            next_run(every_six, Some(secs("2026-03-10T12:00:00")), &now),
+            Some(secs("2026-03-10T18:00:00"))
+        );
+        assert_eq!(
+            next_run(every_six, None, &now),
+            Some(secs("2026-03-10T20:00:00"))
+        );
+    }
+}
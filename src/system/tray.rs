@@ -3,7 +3,31 @@ use image::ImageFormat;
 use tray_icon::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
 
-pub fn create_tray_icon() -> Result<TrayIcon> {
+/// Menu item ids, as they come back in `MenuEvent`s
+pub const MENU_SHOW: &str = "show";
+pub const MENU_PAUSE: &str = "pause";
+pub const MENU_REBUILD: &str = "rebuild";
+pub const MENU_QUIT: &str = "quit";
+
+/// The tray icon and the menu entries whose labels follow app state. Dropping
+/// it removes the icon.
+pub struct Tray {
+    _icon: TrayIcon,
+    pause_item: MenuItem,
+}
+
+impl Tray {
+    /// Shows "Resume Indexing" while indexing is paused.
+    pub fn set_paused(&self, paused: bool) {
+        self.pause_item.set_text(if paused {
+            "Resume Indexing"
+        } else {
+            "Pause Indexing"
+        });
+    }
+}
+
+pub fn create_tray_icon() -> Result<Tray> {
     let icon_data = include_bytes!("../../assets/logo.png");
     let image = image::load_from_memory_with_format(icon_data, ImageFormat::Png)
         .map_err(|e| FlashError::config("tray_icon", e.to_string()))?
@@ -14,11 +38,16 @@ pub fn create_tray_icon() -> Result<TrayIcon> {
         .map_err(|e| FlashError::config("tray_icon", e.to_string()))?;
 
     let tray_menu = Menu::new();
-    let show_i = MenuItem::with_id("show", "Show Flash Search", true, None);
-    let quit_i = MenuItem::with_id("quit", "Quit", true, None);
+    let show_i = MenuItem::with_id(MENU_SHOW, "Show Flash Search", true, None);
+    let pause_i = MenuItem::with_id(MENU_PAUSE, "Pause Indexing", true, None);
+    let rebuild_i = MenuItem::with_id(MENU_REBUILD, "Rebuild Index", true, None);
+    let quit_i = MenuItem::with_id(MENU_QUIT, "Quit", true, None);
 
     let _ = tray_menu.append(&show_i);
     let _ = tray_menu.append(&PredefinedMenuItem::separator());
+    let _ = tray_menu.append(&pause_i);
+    let _ = tray_menu.append(&rebuild_i);
+    let _ = tray_menu.append(&PredefinedMenuItem::separator());
     let _ = tray_menu.append(&quit_i);
 
     let tray_icon = TrayIconBuilder::new()
@@ -29,5 +58,8 @@ pub fn create_tray_icon() -> Result<TrayIcon> {
         .build()
         .map_err(|e| FlashError::config("tray_icon", e.to_string()))?;
 
-    Ok(tray_icon)
+    Ok(Tray {
+        _icon: tray_icon,
+        pause_item: pause_i,
+    })
 }
@@ -0,0 +1,692 @@
+//! Encryption of the index stores at rest: the search index, the metadata
+//! store and the filename index. Files are sealed in chunks with
+//! XChaCha20-Poly1305, so a store can read part of a file without decrypting
+//! all of it. The key is either random and kept in the OS keychain, or derived
+//! from a passphrase with Argon2. A marker file in the data directory records
+//! how the stores are encrypted; when the setting differs at startup, the
+//! stores are rewritten before anything opens them.
+
+use crate::error::{FlashError, Result};
+use crate::settings::IndexEncryption;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Seek, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use tracing::{info, warn};
+
+/// Leads every sealed file
+pub const MAGIC: &[u8; 8] = b"FSVAULT1";
+
+/// Plaintext bytes per chunk; every chunk but the last is full
+const CHUNK_LEN: usize = 16 * 1024;
+
+const NONCE_LEN: usize = 24;
+
+const TAG_LEN: usize = 16;
+
+/// What sealing adds to each chunk: its nonce and tag
+pub const OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+const SEALED_CHUNK_LEN: usize = CHUNK_LEN + OVERHEAD;
+
+/// File in the data directory recording how the stores are encrypted
+pub const MARKER_FILENAME: &str = "encryption.json";
+
+/// Marker of a conversion that hasn't finished, naming the new key
+const PENDING_MARKER_FILENAME: &str = "encryption.pending.json";
+
+/// Environment variable a passphrase can be given in, e.g. for scripts
+pub const PASSPHRASE_ENV: &str = "FLASH_SEARCH_PASSPHRASE";
+
+/// Keychain service the keys are stored under, one entry per profile
+const KEYCHAIN_SERVICE: &str = "com.flashsearch";
+
+/// Sealed into the marker, so a wrong passphrase is told apart from a
+/// damaged store
+const CHECK_TEXT: &[u8] = b"flash-search";
+
+const SALT_LEN: usize = 16;
+
+/// Key the stores are encrypted with, set once they are unlocked
+static KEY: OnceLock<Key> = OnceLock::new();
+
+/// Passphrase given in the window or on the command line
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// A key the stores can be encrypted with.
+#[derive(Clone)]
+pub struct Key(XChaCha20Poly1305);
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+fn damaged() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "encrypted data is damaged or was sealed with another key",
+    )
+}
+
+impl Key {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        Self(XChaCha20Poly1305::new(bytes.into()))
+    }
+
+    /// Seals `plain` as nonce, ciphertext and tag, bound to `aad`.
+    pub fn seal_chunk(&self, aad: &[u8], plain: &[u8]) -> io::Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, Payload { msg: plain, aad })
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        let mut sealed = Vec::with_capacity(OVERHEAD + plain.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    /// Opens what [`Self::seal_chunk`] sealed with the same `aad`.
+    pub fn open_chunk(&self, aad: &[u8], sealed: &[u8]) -> io::Result<Vec<u8>> {
+        if sealed.len() < OVERHEAD {
+            return Err(damaged());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.0
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| damaged())
+    }
+}
+
+/// Binds a chunk to its position, and the last one to being last, so chunks
+/// can't be reordered and a file can't be cut short unnoticed.
+fn chunk_aad(index: usize, last: bool) -> [u8; 9] {
+    let mut aad = [0; 9];
+    aad[..8].copy_from_slice(&u64::try_from(index).unwrap_or(u64::MAX).to_le_bytes());
+    aad[8] = u8::from(last);
+    aad
+}
+
+/// Whether `bytes` start like a sealed file.
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Chunks in a sealed file of `sealed_len` bytes.
+fn chunk_count(sealed_len: usize) -> io::Result<usize> {
+    let body = sealed_len
+        .checked_sub(MAGIC.len())
+        .filter(|body| *body >= OVERHEAD)
+        .ok_or_else(damaged)?;
+    Ok(body.div_ceil(SEALED_CHUNK_LEN))
+}
+
+/// Plaintext length of a sealed file of `sealed_len` bytes.
+pub fn plain_len(sealed_len: usize) -> io::Result<usize> {
+    let chunks = chunk_count(sealed_len)?;
+    (sealed_len - MAGIC.len())
+        .checked_sub(chunks * OVERHEAD)
+        .ok_or_else(damaged)
+}
+
+/// Seals a file as it is written, a chunk at a time. [`Self::finish`] seals
+/// the last chunk; a writer dropped without it leaves a file that won't open.
+pub struct SealWriter<W: Write> {
+    key: Key,
+    inner: W,
+    buffer: Vec<u8>,
+    index: usize,
+}
+
+impl<W: Write> SealWriter<W> {
+    pub fn new(key: &Key, mut inner: W) -> io::Result<Self> {
+        inner.write_all(MAGIC)?;
+        Ok(Self {
+            key: key.clone(),
+            inner,
+            buffer: Vec::with_capacity(2 * CHUNK_LEN),
+            index: 0,
+        })
+    }
+
+    fn seal_next(&mut self, len: usize, last: bool) -> io::Result<()> {
+        let sealed = self
+            .key
+            .seal_chunk(&chunk_aad(self.index, last), &self.buffer[..len])?;
+        self.inner.write_all(&sealed)?;
+        self.buffer.drain(..len);
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Seals what is left as the last chunk and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.seal_next(self.buffer.len(), true)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        // A full chunk is kept back until more follows, since the last one
+        // is sealed differently
+        while self.buffer.len() > CHUNK_LEN {
+            self.seal_next(CHUNK_LEN, false)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Seals `plain` as a whole file.
+pub fn seal(key: &Key, plain: &[u8]) -> io::Result<Vec<u8>> {
+    let chunks = plain.len() / CHUNK_LEN + 1;
+    let mut writer = SealWriter::new(
+        key,
+        Vec::with_capacity(MAGIC.len() + plain.len() + chunks * OVERHEAD),
+    )?;
+    writer.write_all(plain)?;
+    writer.finish()
+}
+
+/// Opens a whole file sealed with `key`.
+pub fn open(key: &Key, sealed: &[u8]) -> io::Result<Vec<u8>> {
+    if !is_sealed(sealed) {
+        return Err(damaged());
+    }
+    let len = plain_len(sealed.len())?;
+    open_range(key, sealed.len(), 0..len, |range| Ok(&sealed[range]))
+}
+
+/// Decrypts `range` of the plaintext of a file sealed with `key`,
+/// `sealed_len` bytes long, reading just the chunks it covers through
+/// `read`.
+pub fn open_range<B: AsRef<[u8]>>(
+    key: &Key,
+    sealed_len: usize,
+    range: Range<usize>,
+    read: impl Fn(Range<usize>) -> io::Result<B>,
+) -> io::Result<Vec<u8>> {
+    let len = plain_len(sealed_len)?;
+    if range.start > range.end || range.end > len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "read past the end of an encrypted file",
+        ));
+    }
+    if range.is_empty() {
+        return Ok(Vec::new());
+    }
+    let chunks = chunk_count(sealed_len)?;
+    let first = range.start / CHUNK_LEN;
+    let last = (range.end - 1) / CHUNK_LEN;
+    let mut plain = Vec::with_capacity((last - first + 1) * CHUNK_LEN);
+    for index in first..=last {
+        let start = MAGIC.len() + index * SEALED_CHUNK_LEN;
+        let end = (start + SEALED_CHUNK_LEN).min(sealed_len);
+        let sealed = read(start..end)?;
+        plain.extend(key.open_chunk(&chunk_aad(index, index + 1 == chunks), sealed.as_ref())?);
+    }
+    let offset = first * CHUNK_LEN;
+    plain.truncate(range.end - offset);
+    plain.drain(..range.start - offset);
+    Ok(plain)
+}
+
+/// The key the stores are encrypted with, or `None` if they aren't.
+pub fn key() -> Option<&'static Key> {
+    KEY.get()
+}
+
+/// Seals `bytes` if the stores are encrypted, for store contents written
+/// somewhere else, such as a backup.
+pub fn protect(bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    match key() {
+        Some(key) => seal(key, &bytes),
+        None => Ok(bytes),
+    }
+}
+
+/// Reads a file of one of the stores, decrypting it if it is sealed.
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    let bytes = std::fs::read(path)?;
+    if !is_sealed(&bytes) {
+        return Ok(bytes);
+    }
+    let key = key().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the file is encrypted and the index is locked",
+        )
+    })?;
+    open(key, &bytes)
+}
+
+/// Passphrase to unlock the stores with, or to encrypt them under when
+/// switching to a passphrase. Takes precedence over [`PASSPHRASE_ENV`].
+pub fn provide_passphrase(passphrase: String) {
+    *PASSPHRASE.lock().unwrap_or_else(PoisonError::into_inner) = Some(passphrase);
+}
+
+fn given_passphrase(new: bool) -> Result<String> {
+    PASSPHRASE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .or_else(|| std::env::var(PASSPHRASE_ENV).ok())
+        .filter(|passphrase| !passphrase.is_empty())
+        .ok_or(FlashError::Locked { new, wrong: false })
+}
+
+fn setting_error(cause: impl std::fmt::Display) -> FlashError {
+    FlashError::config("index_encryption", cause.to_string())
+}
+
+fn keychain_entry() -> Result<keyring::Entry> {
+    let user = format!("index-key-{}", super::profile::active());
+    keyring::Entry::new(KEYCHAIN_SERVICE, &user)
+        .map_err(|e| setting_error(format!("OS keychain: {e}")))
+}
+
+/// The key kept in the keychain for this profile, created first if `create`
+/// is set and there is none.
+fn keychain_key(create: bool) -> Result<Key> {
+    let entry = keychain_entry()?;
+    let mut bytes = [0u8; 32];
+    match entry.get_password() {
+        Ok(hex) => {
+            if hex.len() != 2 * bytes.len() || !hex.is_ascii() {
+                return Err(setting_error("the key in the OS keychain is malformed"));
+            }
+            for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+                let digits = std::str::from_utf8(digits).unwrap_or_default();
+                *byte = u8::from_str_radix(digits, 16)
+                    .map_err(|_| setting_error("the key in the OS keychain is malformed"))?;
+            }
+        }
+        Err(keyring::Error::NoEntry) if create => {
+            OsRng.fill_bytes(&mut bytes);
+            let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+            entry
+                .set_password(&hex)
+                .map_err(|e| setting_error(format!("OS keychain: {e}")))?;
+        }
+        Err(e) => return Err(setting_error(format!("OS keychain: {e}"))),
+    }
+    Ok(Key::from_bytes(&bytes))
+}
+
+fn passphrase_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut bytes = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut bytes)
+        .map_err(setting_error)?;
+    Ok(Key::from_bytes(&bytes))
+}
+
+/// How the stores are encrypted, with what it takes to get the key back.
+#[derive(Serialize, Deserialize)]
+struct Marker {
+    mode: IndexEncryption,
+    /// Argon2 salt of a passphrase key
+    #[serde(default)]
+    salt: Vec<u8>,
+    /// [`CHECK_TEXT`] sealed with the key
+    #[serde(default)]
+    check: Vec<u8>,
+}
+
+impl Marker {
+    fn read(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| setting_error(format!("{} is unreadable: {e}", path.display()))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(setting_error)?;
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(&json)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// A marker for new stores encrypted as `mode`, and their key.
+    fn create(mode: IndexEncryption) -> Result<(Self, Option<Key>)> {
+        let (key, salt) = match mode {
+            IndexEncryption::Off => {
+                return Ok((
+                    Self {
+                        mode,
+                        salt: Vec::new(),
+                        check: Vec::new(),
+                    },
+                    None,
+                ));
+            }
+            IndexEncryption::Keychain => (keychain_key(true)?, Vec::new()),
+            IndexEncryption::Passphrase => {
+                let mut salt = vec![0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                (passphrase_key(&given_passphrase(true)?, &salt)?, salt)
+            }
+        };
+        let check = key.seal_chunk(&[], CHECK_TEXT)?;
+        Ok((Self { mode, salt, check }, Some(key)))
+    }
+
+    /// The key this marker records, checked against it.
+    fn key(&self) -> Result<Option<Key>> {
+        let key = match self.mode {
+            IndexEncryption::Off => return Ok(None),
+            IndexEncryption::Keychain => keychain_key(false)?,
+            IndexEncryption::Passphrase => passphrase_key(&given_passphrase(false)?, &self.salt)?,
+        };
+        if key.open_chunk(&[], &self.check).ok().as_deref() == Some(CHECK_TEXT) {
+            Ok(Some(key))
+        } else if self.mode == IndexEncryption::Passphrase {
+            Err(FlashError::Locked {
+                new: false,
+                wrong: true,
+            })
+        } else {
+            Err(setting_error(
+                "the key in the OS keychain doesn't open the index",
+            ))
+        }
+    }
+}
+
+/// Gets the key of the stores under `app_data_dir`, rewriting them first if
+/// they aren't encrypted as `mode` says. Has to run before any store is
+/// opened.
+///
+/// # Errors
+///
+/// Returns [`FlashError::Locked`] when a passphrase is needed and none was
+/// given, or the one given is wrong, and a config error if the keychain
+/// holding the key can't be reached.
+pub fn unlock(app_data_dir: &Path, mode: IndexEncryption) -> Result<()> {
+    if KEY.get().is_some() {
+        return Ok(());
+    }
+    let marker_path = app_data_dir.join(MARKER_FILENAME);
+    let pending_path = app_data_dir.join(PENDING_MARKER_FILENAME);
+    let current = Marker::read(&marker_path)?;
+    let current_mode = current.as_ref().map_or(IndexEncryption::Off, |m| m.mode);
+    let current_key = current.as_ref().map(Marker::key).transpose()?.flatten();
+
+    // A conversion that was cut short is finished before anything else
+    let (target, target_key) = if let Some(pending) = Marker::read(&pending_path)? {
+        let key = pending.key()?;
+        (pending, key)
+    } else if mode == current_mode {
+        if let Some(key) = current_key {
+            let _ = KEY.set(key);
+        }
+        return Ok(());
+    } else {
+        match Marker::create(mode) {
+            Ok((marker, key)) => {
+                marker.write(&pending_path)?;
+                (marker, key)
+            }
+            Err(e @ FlashError::Locked { .. }) => return Err(e),
+            Err(e) => {
+                warn!("Keeping the index {} encrypted: {}", current_mode, e);
+                if let Some(key) = current_key {
+                    let _ = KEY.set(key);
+                }
+                return Ok(());
+            }
+        }
+    };
+
+    info!(
+        "Converting the index stores from {} to {} encryption",
+        current_mode, target.mode
+    );
+    let keys: Vec<&Key> = current_key.iter().chain(target_key.iter()).collect();
+    convert_stores(app_data_dir, &keys, target_key.as_ref())?;
+    if target.mode == IndexEncryption::Off {
+        remove_if_present(&marker_path)?;
+        remove_if_present(&pending_path)?;
+    } else {
+        std::fs::rename(&pending_path, &marker_path)?;
+    }
+    if let Some(key) = target_key {
+        let _ = KEY.set(key);
+    }
+    Ok(())
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Directories, or else files, directly in `dir`.
+fn entries(dir: &Path, dirs: bool) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_ok_and(|t| if dirs { t.is_dir() } else { t.is_file() })
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Files of the search index and filename index under `app_data_dir`.
+fn store_files(app_data_dir: &Path) -> Vec<PathBuf> {
+    let mut index_dirs = vec![app_data_dir.join("index")];
+    index_dirs.extend(entries(
+        &app_data_dir.join(crate::indexer::SHARDS_DIR),
+        true,
+    ));
+    let mut files = Vec::new();
+    for dir in index_dirs {
+        // Lock files and the app's own notes stay as they are; of the dot
+        // files, only Tantivy's list of managed files and the paths a
+        // migration left for re-parsing have content
+        files.extend(entries(&dir, false).into_iter().filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    !name.starts_with('.')
+                        || name == ".managed.json"
+                        || name == crate::indexer::migration::REPARSE_FILENAME
+                })
+        }));
+    }
+    files.extend(
+        entries(&app_data_dir.join("filename_index"), false)
+            .into_iter()
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "seg")
+                    || path
+                        .file_name()
+                        .is_some_and(|name| name == crate::indexer::filename_index::INDEX_FILENAME)
+            }),
+    );
+    files
+}
+
+/// Rewrites every store under `app_data_dir` sealed with `to`, or unsealed
+/// without it, opening sealed files with whichever of `keys` they were
+/// sealed with. Each file is replaced whole, so a conversion cut short can be
+/// run again.
+fn convert_stores(app_data_dir: &Path, keys: &[&Key], to: Option<&Key>) -> Result<()> {
+    for path in store_files(app_data_dir) {
+        convert_file(&path, keys, to)
+            .map_err(|e| setting_error(format!("can't convert {}: {e}", path.display())))?;
+    }
+    super::activity::convert(&app_data_dir.join(super::activity::DIR_NAME), keys, to)
+        .map_err(|e| setting_error(format!("can't convert the activity log: {e}")))?;
+    crate::metadata::db::convert_store(&app_data_dir.join("metadata.redb"), keys, to)
+}
+
+fn convert_file(path: &Path, keys: &[&Key], to: Option<&Key>) -> io::Result<()> {
+    let mut input = std::fs::File::open(path)?;
+    let mut magic = [0u8; MAGIC.len()];
+    let sealed = input.read_exact(&mut magic).is_ok() && &magic == MAGIC;
+    input.rewind()?;
+    if !sealed && to.is_none() {
+        return Ok(());
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut write = || -> io::Result<()> {
+        let mut output = io::BufWriter::new(std::fs::File::create(&tmp)?);
+        match to {
+            Some(key) => {
+                let mut writer = SealWriter::new(key, &mut output)?;
+                copy_unsealed(&mut input, sealed, keys, &mut writer)?;
+                writer.finish()?;
+            }
+            None => copy_unsealed(&mut input, sealed, keys, &mut output)?,
+        }
+        output
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+            .sync_all()
+    };
+    write()
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })
+}
+
+/// Copies the plaintext of `input` to `output` a chunk at a time.
+fn copy_unsealed(
+    input: &mut std::fs::File,
+    sealed: bool,
+    keys: &[&Key],
+    output: &mut impl Write,
+) -> io::Result<()> {
+    if !sealed {
+        io::copy(input, output)?;
+        return Ok(());
+    }
+    let sealed_len =
+        usize::try_from(input.metadata()?.len()).map_err(|_| io::Error::other("file too large"))?;
+    let chunks = chunk_count(sealed_len)?;
+    input.seek(io::SeekFrom::Start(MAGIC.len() as u64))?;
+    let mut buffer = vec![0u8; SEALED_CHUNK_LEN];
+    let mut key = None;
+    for index in 0..chunks {
+        let start = MAGIC.len() + index * SEALED_CHUNK_LEN;
+        let chunk = &mut buffer[..SEALED_CHUNK_LEN.min(sealed_len - start)];
+        input.read_exact(chunk)?;
+        let aad = chunk_aad(index, index + 1 == chunks);
+        // The first chunk tells which key the file was sealed with
+        let plain = match key {
+            Some(key) => key.open_chunk(&aad, chunk)?,
+            None => {
+                let (found, plain) = keys
+                    .iter()
+                    .find_map(|k| k.open_chunk(&aad, chunk).ok().map(|plain| (*k, plain)))
+                    .ok_or_else(damaged)?;
+                key = Some(found);
+                plain
+            }
+        };
+        output.write_all(&plain)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key(byte: u8) -> Key {
+        Key::from_bytes(&[byte; 32])
+    }
+
+    #[test]
+    fn test_sealed_files_round_trip() {
+        let key = test_key(7);
+        for len in [0, 1, CHUNK_LEN - 1, CHUNK_LEN, CHUNK_LEN + 1, 3 * CHUNK_LEN] {
+            let plain: Vec<u8> = (0..len).map(|i| u8::try_from(i % 251).unwrap()).collect();
+            let sealed = seal(&key, &plain).unwrap();
+            assert!(is_sealed(&sealed));
+            assert_eq!(plain_len(sealed.len()).unwrap(), len);
+            assert_eq!(open(&key, &sealed).unwrap(), plain);
+            if len > 10 {
+                let range = 5..len - 3;
+                let part = open_range(&key, sealed.len(), range.clone(), |r| Ok(&sealed[r]));
+                assert_eq!(part.unwrap(), plain[range]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sealed_files_reject_tampering() {
+        let key = test_key(7);
+        let plain = vec![42u8; 2 * CHUNK_LEN + 100];
+        let sealed = seal(&key, &plain).unwrap();
+
+        assert!(open(&test_key(8), &sealed).is_err());
+        let mut flipped = sealed.clone();
+        flipped[MAGIC.len() + SEALED_CHUNK_LEN + 100] ^= 1;
+        assert!(open(&key, &flipped).is_err());
+        // Cut at a chunk boundary, the new last chunk wasn't sealed as last
+        let cut = &sealed[..MAGIC.len() + 2 * SEALED_CHUNK_LEN];
+        assert!(open(&key, cut).is_err());
+    }
+
+    #[test]
+    fn test_store_files_convert_both_ways() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = dir.path().join("index");
+        std::fs::create_dir_all(&index).unwrap();
+        let segment = index.join("abc.idx");
+        let version = index.join(".schema_version");
+        let content = vec![9u8; CHUNK_LEN * 2 + 5];
+        std::fs::write(&segment, &content).unwrap();
+        std::fs::write(&version, "1").unwrap();
+
+        let key = test_key(1);
+        convert_stores(dir.path(), &[], Some(&key)).unwrap();
+        let sealed = std::fs::read(&segment).unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(open(&key, &sealed).unwrap(), content);
+        assert_eq!(std::fs::read_to_string(&version).unwrap(), "1");
+
+        // Running it again, as after a crash, finds every file sealed already
+        let other = test_key(2);
+        convert_stores(dir.path(), &[&key, &other], Some(&other)).unwrap();
+        convert_stores(dir.path(), &[&key, &other], Some(&other)).unwrap();
+        convert_stores(dir.path(), &[&other], None).unwrap();
+        assert_eq!(std::fs::read(&segment).unwrap(), content);
+    }
+}
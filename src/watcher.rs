@@ -1,32 +1,222 @@
 use crate::error::{FlashError, Result};
 use crate::indexer::IndexManager;
-use crate::metadata::MetadataDb;
-use crate::parsers::parse_file;
+use crate::indexer::filename_index::{EntryKind, FilenameEntry, FilenameIndex};
+use crate::metadata::{FileAttributes, MetadataDb, ParseErrorKind};
+use crate::models::WatcherStatus;
+use crate::parsers::{ParsedDocument, parse_file_isolated};
+use crate::scanner::access;
+use crate::scanner::cloud;
+use crate::scanner::exclude::ExcludeMatcher;
+use crate::scanner::hidden::HiddenFilter;
+use crate::scanner::network;
+use crate::scanner::remote;
+use crate::scanner::sensitive::SensitiveMatcher;
+use crate::scanner::volumes;
+use crate::settings::{AppSettings, CoalescePolicy, IndexRoot, WatcherSettings};
+use crate::system::activity::{self, Activity, SkipReason, Source};
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{Notify, mpsc};
 use tracing::{error, info, warn};
 
+/// Upper bound for parsing a single file picked up by a live event.
+const WATCHER_PARSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Folders queued for a rescan after an overflow beyond this many are
+/// rescanned as the whole root they lie in
+const MAX_RESCAN_FOLDERS: usize = 64;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WatcherAction {
     Index,
     Remove,
 }
 
+/// Paths of batches the last session left unfinished, replayed as one batch.
+/// Their journal entries are dropped only once the replay is committed.
+struct Replay {
+    events: HashMap<PathBuf, WatcherAction>,
+    journal: Vec<u64>,
+}
+
+fn compile_globs(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => warn!("Invalid glob '{}': {}", pattern, e),
+        }
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// A watched root's profile, compiled for matching live events.
+struct RootRules {
+    root: PathBuf,
+    include: GlobSet,
+    excludes: ExcludeMatcher,
+    max_depth: Option<usize>,
+    index_content: bool,
+    hidden: HiddenFilter,
+}
+
+impl RootRules {
+    fn compile(profile: &IndexRoot, exclude_patterns: &[String]) -> Self {
+        let root = PathBuf::from(&profile.path);
+        Self {
+            excludes: ExcludeMatcher::new(&root, exclude_patterns.iter().chain(&profile.exclude)),
+            root,
+            include: compile_globs(&profile.include),
+            max_depth: profile.max_depth,
+            index_content: profile.indexes_content(),
+            hidden: HiddenFilter::for_root(profile),
+        }
+    }
+
+    /// Whether a file at `relative` (to the root) passes the profile's include
+    /// globs and depth, matching the walker: includes by name or path.
+    fn allows(&self, relative: &Path) -> bool {
+        if self
+            .max_depth
+            .is_some_and(|depth| relative.components().count() > depth)
+        {
+            return false;
+        }
+        let name = relative.file_name().map(|n| n.to_string_lossy());
+        self.include.is_empty()
+            || self.include.is_match(relative)
+            || name.is_some_and(|n| self.include.is_match(n.as_ref()))
+    }
+}
+
+/// Whether `path` is skipped by the exclude rules of the root containing it,
+/// the same rules the scanner applies when walking that root.
+fn is_excluded(roots: &[RootRules], path: &Path, is_dir: bool) -> bool {
+    rules_for(roots, path).is_some_and(|rules| rules.excludes.is_excluded(path, is_dir))
+}
+
+/// Innermost watched root containing `path`.
+fn rules_for<'a>(roots: &'a [RootRules], path: &Path) -> Option<&'a RootRules> {
+    roots
+        .iter()
+        .filter(|rules| path.starts_with(&rules.root))
+        .max_by_key(|rules| rules.root.as_os_str().len())
+}
+
+/// What a live event means for its `index`-th path. The old name of a rename
+/// is removed; a rename reported as one event lists the old path first.
+const fn event_action(kind: &EventKind, index: usize) -> WatcherAction {
+    match kind {
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            WatcherAction::Remove
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if index == 0 => {
+            WatcherAction::Remove
+        }
+        _ => WatcherAction::Index,
+    }
+}
+
+/// How long the processor may wait before flushing a batch whose first
+/// event arrived `since_first` ago. A new event re-evaluates this, so under
+/// [`CoalescePolicy::Debounce`] each one restarts the quiet period.
+fn flush_delay(limits: &WatcherSettings, since_first: Duration) -> Duration {
+    let debounce = Duration::from_millis(limits.debounce_ms);
+    match limits.coalesce {
+        CoalescePolicy::Debounce => {
+            debounce.min(Duration::from_millis(limits.max_wait_ms).saturating_sub(since_first))
+        }
+        CoalescePolicy::Window => debounce.saturating_sub(since_first),
+    }
+}
+
+/// Queues every root containing `path`, or inside it, for a catch-up refresh.
+fn mark_stale(roots: &RwLock<Vec<RootRules>>, stale: &Mutex<Vec<PathBuf>>, path: &Path) {
+    let roots = roots.read();
+    let mut stale = stale.lock();
+    for rules in roots
+        .iter()
+        .filter(|rules| path.starts_with(&rules.root) || rules.root.starts_with(path))
+    {
+        if !stale.contains(&rules.root) {
+            info!(
+                "{} missed changes, queueing a refresh",
+                rules.root.display()
+            );
+            stale.push(rules.root.clone());
+        }
+    }
+}
+
+/// Queues the folder of `path` for a full rescan, unless it lies in one
+/// already queued. Past [`MAX_RESCAN_FOLDERS`] its root is queued instead.
+fn queue_rescan(roots: &RwLock<Vec<RootRules>>, rescans: &Mutex<Vec<PathBuf>>, path: &Path) {
+    let folder = path.parent().unwrap_or(path);
+    let mut rescans = rescans.lock();
+    if rescans.iter().any(|queued| folder.starts_with(queued)) {
+        return;
+    }
+    let folder = if rescans.len() >= MAX_RESCAN_FOLDERS {
+        rules_for(&roots.read(), folder)
+            .map_or_else(|| folder.to_path_buf(), |rules| rules.root.clone())
+    } else {
+        folder.to_path_buf()
+    };
+    rescans.retain(|queued| !queued.starts_with(&folder));
+    rescans.push(folder);
+}
+
+/// Counters shared by the event callbacks and the debounce processor, read
+/// by [`WatcherManager::status`].
+#[derive(Debug, Default)]
+struct WatcherHealth {
+    /// Events waiting in the debounce buffer
+    pending: AtomicUsize,
+    processed: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl WatcherHealth {
+    fn record_error(&self, message: String) {
+        *self.last_error.lock() = Some(message);
+    }
+}
+
 /// Manages active file system watching with debouncing
 pub struct WatcherManager {
     watchers: HashMap<String, RecommendedWatcher>,
-    _indexer: Arc<IndexManager>,
-    _metadata_db: Arc<MetadataDb>,
+    /// Stop flags of the change journal readers, by drive letter
+    #[cfg(target_os = "windows")]
+    journals: HashMap<char, Arc<std::sync::atomic::AtomicBool>>,
+    indexer: Arc<IndexManager>,
+    metadata_db: Arc<MetadataDb>,
     _runtime_handle: tokio::runtime::Handle,
     external_tx: mpsc::Sender<(PathBuf, WatcherAction)>,
-    /// Compiled glob set for exclude patterns (applied during live events)
-    #[allow(dead_code)]
-    exclude_globs: Arc<GlobSet>,
+    replay_tx: mpsc::Sender<Replay>,
+    /// Global exclude patterns, combined with each root's own excludes
+    exclude_patterns: Vec<String>,
+    /// Profiles of the watched roots; events outside every root use the defaults
+    root_rules: Arc<RwLock<Vec<RootRules>>>,
+    /// Files kept out of the index, or indexed by name only
+    sensitive: Arc<RwLock<SensitiveMatcher>>,
+    /// Roots whose events were lost (event queue overflow, journal gap) and
+    /// need an incremental refresh to catch up
+    stale_roots: Arc<Mutex<Vec<PathBuf>>>,
+    /// Folders whose changes overflowed a batch and need a full rescan
+    rescans: Arc<Mutex<Vec<PathBuf>>>,
+    /// Notified once an overflowed batch has settled and its folders are
+    /// queued
+    rescan_signal: Arc<Notify>,
+    health: Arc<WatcherHealth>,
 }
 
 impl WatcherManager {
@@ -41,10 +231,20 @@ impl WatcherManager {
         allowed_extensions: std::collections::HashSet<String>,
         enable_ocr: bool,
     ) -> Self {
-        Self::new_with_excludes(indexer, metadata_db, allowed_extensions, &[], enable_ocr)
+        Self::new_with_excludes(
+            indexer,
+            metadata_db,
+            None,
+            allowed_extensions,
+            &[],
+            WatcherSettings::default(),
+            enable_ocr,
+        )
     }
 
-    /// Creates a new `WatcherManager` with exclude patterns.
+    /// Creates a new `WatcherManager` with exclude patterns and batching
+    /// limits. Live events also keep `filename_index`, when given, in step
+    /// with the file system.
     ///
     /// # Panics
     ///
@@ -52,147 +252,295 @@ impl WatcherManager {
     pub fn new_with_excludes(
         indexer: Arc<IndexManager>,
         metadata_db: Arc<MetadataDb>,
+        filename_index: Option<Arc<FilenameIndex>>,
         allowed_extensions: std::collections::HashSet<String>,
         exclude_patterns: &[String],
+        limits: WatcherSettings,
         enable_ocr: bool,
     ) -> Self {
         let (external_tx, external_rx) = mpsc::channel::<(PathBuf, WatcherAction)>(1000);
+        let (replay_tx, replay_rx) = mpsc::channel::<Replay>(4);
         let runtime_handle = tokio::runtime::Handle::current();
 
-        // Compile exclude patterns into a GlobSet for O(1) matching
-        let mut glob_builder = GlobSetBuilder::new();
-        for pattern in exclude_patterns {
-            match Glob::new(pattern) {
-                Ok(glob) => {
-                    glob_builder.add(glob);
-                }
-                Err(e) => warn!("Invalid exclude glob '{}': {}", pattern, e),
-            }
-        }
-        let exclude_globs = Arc::new(glob_builder.build().unwrap_or_default());
+        let root_rules = Arc::new(RwLock::new(Vec::new()));
+        let sensitive = Arc::new(RwLock::new(SensitiveMatcher::from_settings(
+            &AppSettings::default(),
+        )));
+        let health = Arc::new(WatcherHealth::default());
+        let stale_roots = Arc::new(Mutex::new(Vec::new()));
+        let rescans = Arc::new(Mutex::new(Vec::new()));
+        let rescan_signal = Arc::new(Notify::new());
 
         // Spawn background processor for debounced events
         Self::spawn_processor_task(
             &runtime_handle,
             external_rx,
+            replay_rx,
             indexer.clone(),
             metadata_db.clone(),
+            filename_index,
             allowed_extensions,
-            Arc::clone(&exclude_globs),
+            Arc::clone(&root_rules),
+            Arc::clone(&sensitive),
+            Arc::clone(&stale_roots),
+            Arc::clone(&rescans),
+            Arc::clone(&rescan_signal),
+            Arc::clone(&health),
+            limits,
             enable_ocr,
         );
 
         Self {
             watchers: HashMap::new(),
-            _indexer: indexer,
-            _metadata_db: metadata_db,
+            #[cfg(target_os = "windows")]
+            journals: HashMap::new(),
+            indexer,
+            metadata_db,
             _runtime_handle: runtime_handle,
             external_tx,
-            exclude_globs,
+            replay_tx,
+            exclude_patterns: exclude_patterns.to_vec(),
+            root_rules,
+            sensitive,
+            stale_roots,
+            rescans,
+            rescan_signal,
+            health,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn spawn_processor_task(
         runtime_handle: &tokio::runtime::Handle,
         mut external_rx: mpsc::Receiver<(PathBuf, WatcherAction)>,
+        mut replay_rx: mpsc::Receiver<Replay>,
         indexer: Arc<IndexManager>,
         metadata_db: Arc<MetadataDb>,
+        filename_index: Option<Arc<FilenameIndex>>,
         allowed_extensions: std::collections::HashSet<String>,
-        exclude_globs: Arc<GlobSet>,
+        root_rules: Arc<RwLock<Vec<RootRules>>>,
+        sensitive: Arc<RwLock<SensitiveMatcher>>,
+        stale_roots: Arc<Mutex<Vec<PathBuf>>>,
+        rescans: Arc<Mutex<Vec<PathBuf>>>,
+        rescan_signal: Arc<Notify>,
+        health: Arc<WatcherHealth>,
+        limits: WatcherSettings,
         enable_ocr: bool,
     ) {
-        const MAX_DEBOUNCE_WAIT: Duration = Duration::from_secs(5);
-        const DEBOUNCE_GAP: Duration = Duration::from_millis(500);
-
         runtime_handle.spawn(async move {
             let mut buffer = HashMap::new();
             let mut first_event_time: Option<std::time::Instant> = None;
+            // Set once a batch overflows; its events only queue their folders
+            // for a rescan, and their roots for a refresh that drops deletions
+            let mut overflowed = false;
 
             loop {
                 let timeout_duration = first_event_time.map_or_else(
                     || Duration::from_hours(1),
-                    |first_time| {
-                        let elapsed = first_time.elapsed();
-                        if elapsed >= MAX_DEBOUNCE_WAIT {
-                            Duration::from_millis(0) // Force flush immediately
-                        } else {
-                            DEBOUNCE_GAP.min(
-                                MAX_DEBOUNCE_WAIT
-                                    .checked_sub(elapsed)
-                                    .unwrap_or(Duration::ZERO),
-                            )
-                        }
-                    },
+                    |first_time| flush_delay(&limits, first_time.elapsed()),
                 );
 
                 tokio::select! {
                     res = external_rx.recv() => {
-                        if let Some((path, action)) = res {
-                            if buffer.is_empty() {
-                                first_event_time = Some(std::time::Instant::now());
+                        let Some((path, action)) = res else {
+                            break;
+                        };
+                        if first_event_time.is_none() {
+                            first_event_time = Some(std::time::Instant::now());
+                        }
+                        if !overflowed && buffer.len() >= limits.max_buffered_events.max(1) {
+                            overflowed = true;
+                            warn!(
+                                "More than {} file changes in one batch, rescanning the affected folders instead",
+                                limits.max_buffered_events
+                            );
+                            for buffered in std::mem::take(&mut buffer).into_keys() {
+                                mark_stale(&root_rules, &stale_roots, &buffered);
+                                queue_rescan(&root_rules, &rescans, &buffered);
                             }
-                            buffer.insert(path, action);
+                        }
+                        if overflowed {
+                            mark_stale(&root_rules, &stale_roots, &path);
+                            queue_rescan(&root_rules, &rescans, &path);
                         } else {
-                            break;
+                            buffer.insert(path, action);
                         }
+                        health.pending.store(buffer.len(), Ordering::Relaxed);
+                    }
+                    Some(replay) = replay_rx.recv() => {
+                        let count = replay.events.len() as u64;
+                        let committed = Self::process_events(replay.events, &indexer, &metadata_db, filename_index.as_deref(), &allowed_extensions, &root_rules, &sensitive, &health, enable_ocr).await;
+                        // Left in the journal otherwise, to be replayed at the next start
+                        if committed && let Err(e) = metadata_db.finish_batches(&replay.journal) {
+                            warn!("Failed to clear recovered batches from the journal: {}", e);
+                        }
+                        health.processed.fetch_add(count, Ordering::Relaxed);
                     }
                     () = tokio::time::sleep(timeout_duration) => {
+                        first_event_time = None;
+                        // The flood has settled, so a rescan sees where it ended
+                        if std::mem::take(&mut overflowed) {
+                            rescan_signal.notify_one();
+                        }
                         if buffer.is_empty() {
                             continue;
                         }
-                        first_event_time = None;
                         let events = std::mem::take(&mut buffer);
-                        Self::process_events(events, &indexer, &metadata_db, &allowed_extensions, &exclude_globs, enable_ocr).await;
+                        let count = events.len() as u64;
+                        Self::process_events(events, &indexer, &metadata_db, filename_index.as_deref(), &allowed_extensions, &root_rules, &sensitive, &health, enable_ocr).await;
+                        health.pending.store(buffer.len(), Ordering::Relaxed);
+                        health.processed.fetch_add(count, Ordering::Relaxed);
                     }
                 }
             }
         });
     }
 
+    /// Applies a batch of events to every store. Returns whether it was
+    /// committed.
+    #[allow(clippy::too_many_arguments)]
     async fn process_events(
         events: HashMap<PathBuf, WatcherAction>,
         indexer: &Arc<IndexManager>,
         metadata_db: &Arc<MetadataDb>,
+        filename_index: Option<&FilenameIndex>,
         allowed_extensions: &std::collections::HashSet<String>,
-        exclude_globs: &GlobSet,
+        root_rules: &RwLock<Vec<RootRules>>,
+        sensitive: &RwLock<SensitiveMatcher>,
+        health: &WatcherHealth,
         enable_ocr: bool,
-    ) {
+    ) -> bool {
         let mut needs_commit = false;
 
-        // Filter out paths matching any exclude pattern (using the path as a string)
-        let events: HashMap<PathBuf, WatcherAction> = events
-            .into_iter()
-            .filter(|(path, _)| {
-                let path_str = path.to_string_lossy();
-                // Check each path component against the globs
-                !path.components().any(|c| {
-                    let comp = c.as_os_str().to_string_lossy();
-                    exclude_globs.is_match(comp.as_ref())
-                }) && !exclude_globs.is_match(path_str.as_ref())
-            })
-            .collect();
+        // First pass: collect all paths that need to be removed. A share that
+        // disconnects reports its files as removed, so removals under a root
+        // that no longer answers are dropped and the root is marked offline.
+        // The same goes for files on a removable drive that was unplugged.
+        let remove_paths: Vec<PathBuf> = {
+            let removals: Vec<&PathBuf> = events
+                .iter()
+                .filter(|(_, action)| matches!(action, WatcherAction::Remove))
+                .map(|(path, _)| path)
+                .collect();
+            let removal_roots: std::collections::HashSet<PathBuf> = {
+                let roots = root_rules.read();
+                removals
+                    .iter()
+                    .filter_map(|path| rules_for(&roots, path).map(|rules| rules.root.clone()))
+                    .collect()
+            };
+            for root in removal_roots {
+                let probe = root.clone();
+                let reachable = tokio::task::spawn_blocking(move || network::is_reachable(&probe))
+                    .await
+                    .unwrap_or(false);
+                if indexer.offline_roots().set_offline(&root, !reachable) && !reachable {
+                    warn!("{} went offline, keeping its documents", root.display());
+                }
+            }
+            let drives: Vec<Option<String>> = removals
+                .iter()
+                .map(|path| volumes::volume_id_of(path))
+                .collect();
+            if drives.iter().any(Option::is_some) {
+                let _ = tokio::task::spawn_blocking(volumes::refresh).await;
+            }
+            removals
+                .into_iter()
+                .zip(drives)
+                .filter(|(path, drive)| {
+                    !indexer.offline_roots().contains(path)
+                        && !drive.as_deref().is_some_and(volumes::is_offline)
+                })
+                .map(|(path, _)| path.clone())
+                .collect()
+        };
 
-        // First pass: collect all paths that need to be removed
-        let remove_paths: Vec<PathBuf> = events
-            .iter()
-            .filter(|(_, action)| matches!(action, WatcherAction::Remove))
-            .map(|(path, _)| path.clone())
-            .collect();
+        // Second pass: collect all paths that need to be indexed, with whether
+        // their content is indexed, as it is unless their root is filename-only
+        // or they are sensitive. Removals skip this so files indexed under an
+        // older profile still drop out.
+        let index_paths: Vec<(PathBuf, bool)> = {
+            let roots = root_rules.read();
+            let sensitive = sensitive.read();
+            events
+                .iter()
+                .filter(|(path, action)| {
+                    if !matches!(action, WatcherAction::Index) {
+                        return false;
+                    }
+                    if sensitive.skips(path) {
+                        activity::record(|| Activity::Skipped {
+                            path: path.to_string_lossy().to_string(),
+                            reason: SkipReason::Sensitive,
+                        });
+                        return false;
+                    }
+                    true
+                })
+                .filter_map(|(path, _)| match rules_for(&roots, path) {
+                    Some(rules) => (path
+                        .strip_prefix(&rules.root)
+                        .is_ok_and(|relative| rules.allows(relative))
+                        && !rules.hidden.skips_under(&rules.root, path))
+                    .then(|| (path.clone(), rules.index_content)),
+                    None => Some((path.clone(), true)),
+                })
+                .map(|(path, index_content)| {
+                    let index_content = index_content && !sensitive.hides_content(&path);
+                    (path, index_content)
+                })
+                .collect()
+        };
 
-        // Second pass: collect all paths that need to be indexed
-        let index_paths: Vec<PathBuf> = events
-            .iter()
-            .filter(|(_, action)| matches!(action, WatcherAction::Index))
-            .map(|(path, _)| path.clone())
-            .collect();
+        // Journal the batch so a crash before everything below lands is
+        // replayed at the next start
+        let journal = {
+            let indexed: Vec<String> = index_paths
+                .iter()
+                .map(|(path, _)| path.to_string_lossy().to_string())
+                .collect();
+            let removed: Vec<String> = remove_paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            metadata_db
+                .begin_batch(&indexed, &removed)
+                .inspect_err(|e| warn!("Failed to journal watcher batch: {}", e))
+                .ok()
+        };
+
+        // The filename index lists every file in a root, whatever its type,
+        // so it is updated before the content filters below
+        if let Some(filename_index) = filename_index {
+            Self::update_filenames(filename_index, &remove_paths, &index_paths);
+        }
 
-        // Process removes first
+        // Process removes first. A removed directory is often reported as a
+        // single event, so everything recorded under it goes in one batch.
         for path in remove_paths {
-            let path_str = path.to_string_lossy();
-            let _ = indexer.remove_document(&path_str);
-            if matches!(metadata_db.remove_file(&path), Ok(true)) {
-                needs_commit = true;
-                info!("Removed file (watcher): {:?}", path);
+            let _ = indexer.remove_document(&path.to_string_lossy());
+            match metadata_db.remove_tree(&path) {
+                Ok(removed) if removed.is_empty() => {}
+                Ok(removed) => {
+                    let _ = indexer.remove_documents_batch(&removed);
+                    needs_commit = true;
+                    for removed_path in &removed {
+                        activity::record(|| Activity::Removed {
+                            path: removed_path.clone(),
+                            source: Source::Watcher,
+                        });
+                    }
+                    info!(
+                        "Removed {} file(s) under {:?} (watcher)",
+                        removed.len(),
+                        path
+                    );
+                }
+                Err(e) => {
+                    error!("Watcher failed to remove {:?}: {}", path, e);
+                    health.record_error(format!("Failed to remove {}: {e}", path.display()));
+                }
             }
         }
 
@@ -200,22 +548,36 @@ impl WatcherManager {
         let mut docs_to_add = Vec::new();
         let mut meta_to_update = Vec::new();
 
-        for path in index_paths {
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if !allowed_extensions.contains(&ext.to_lowercase()) {
-                    continue;
-                }
-            } else {
+        for (path, index_content) in index_paths {
+            if index_content
+                && !path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|ext| allowed_extensions.contains(&ext.to_lowercase()))
+            {
+                activity::record(|| Activity::Skipped {
+                    path: path.to_string_lossy().to_string(),
+                    reason: SkipReason::Extension,
+                });
                 continue;
             }
 
-            match Self::reindex_single_file(&path, metadata_db, enable_ocr).await {
+            match Self::reindex_single_file(&path, metadata_db, enable_ocr, index_content).await {
                 Ok(Some((doc, modified, size, hash))) => {
-                    meta_to_update.push((doc.path.clone(), modified, size, hash));
+                    meta_to_update.push((
+                        doc.path.clone(),
+                        modified,
+                        size,
+                        hash,
+                        doc.attributes.clone(),
+                    ));
                     docs_to_add.push((doc, modified, size));
                 }
                 Ok(None) => {} // Skipped
-                Err(e) => error!("Watcher error indexing {:?}: {}", path, e),
+                Err(e) => {
+                    error!("Watcher error indexing {:?}: {}", path, e);
+                    health.record_error(format!("Failed to index {}: {e}", path.display()));
+                }
             }
         }
 
@@ -223,15 +585,117 @@ impl WatcherManager {
             let _ = indexer.add_documents_batch(&docs_to_add);
             let _ = metadata_db.batch_update_metadata(&meta_to_update);
             needs_commit = true;
+            for (path, ..) in meta_to_update {
+                activity::record(|| Activity::Indexed {
+                    path,
+                    source: Source::Watcher,
+                });
+            }
         }
 
-        if needs_commit {
-            if let Err(e) = indexer.commit() {
-                error!("Watcher failed to commit index: {}", e);
+        let committed = if needs_commit {
+            match indexer.commit() {
+                Ok(()) => true,
+                Err(e) => {
+                    error!("Watcher failed to commit index: {}", e);
+                    health.record_error(format!("Failed to commit index: {e}"));
+                    false
+                }
+            }
+        } else {
+            true
+        };
+        // A failed commit leaves the entry behind to be replayed
+        if let Some(id) = journal.filter(|_| committed) {
+            let _ = metadata_db.finish_batches(&[id]);
+        }
+        committed
+    }
+
+    /// Applies a debounced batch to the filename index, files and directories
+    /// alike. An index event for a path that is gone (the old side of a
+    /// rename) counts as a removal.
+    fn update_filenames(
+        filename_index: &FilenameIndex,
+        remove_paths: &[PathBuf],
+        index_paths: &[(PathBuf, bool)],
+    ) {
+        let mut removed: Vec<String> = remove_paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let mut added = Vec::new();
+        for (path, _) in index_paths {
+            match std::fs::metadata(path) {
+                Ok(meta) if meta.is_file() => {
+                    added.extend(FilenameEntry::new(path, EntryKind::File));
+                }
+                Ok(meta) if meta.is_dir() => {
+                    added.extend(FilenameEntry::new(path, EntryKind::Directory));
+                }
+                Ok(_) => {}
+                Err(_) => removed.push(path.to_string_lossy().to_string()),
+            }
+        }
+        if removed.is_empty() && added.is_empty() {
+            return;
+        }
+
+        let _ = filename_index.remove_files_batch(removed);
+        let _ = filename_index.add_files_batch(added);
+        if let Err(e) = filename_index.commit() {
+            error!("Watcher failed to commit filename index: {}", e);
+        }
+    }
+
+    /// Replays batches the last session left unfinished. Their metadata is
+    /// rolled back and their paths go through the event pipeline again as one
+    /// batch, which brings the search index, the metadata and the filename
+    /// index back in step. Paths that are gone are replayed as removals; a
+    /// directory that still exists marks its root for a catch-up refresh
+    /// instead. The journal entries are dropped only once the replay is
+    /// committed, so a process that exits first replays them at its next
+    /// start. Returns how many paths were queued.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal cannot be read or rolled back.
+    pub fn recover_pending_batches(&self) -> Result<usize> {
+        let batches = self.metadata_db.get_pending_batches()?;
+        if batches.is_empty() {
+            return Ok(0);
+        }
+
+        let mut events = HashMap::new();
+        for path in batches
+            .iter()
+            .flat_map(|batch| batch.indexed.iter().chain(&batch.removed))
+        {
+            let path = PathBuf::from(path);
+            if path.is_file() {
+                events.insert(path, WatcherAction::Index);
+            } else if path.exists() {
+                mark_stale(&self.root_rules, &self.stale_roots, &path);
             } else {
-                indexer.invalidate_cache();
+                events.insert(path, WatcherAction::Remove);
             }
         }
+        self.metadata_db.rollback_batches(&batches)?;
+        warn!(
+            "Recovering {} unfinished index batch(es) from the last session",
+            batches.len()
+        );
+
+        let count = events.len();
+        let replay = Replay {
+            events,
+            journal: batches.iter().map(|batch| batch.id).collect(),
+        };
+        // A full queue already holds a replay of these batches
+        if self.replay_tx.try_send(replay).is_err() {
+            warn!("Recovery replay already queued, the rest runs at the next start");
+        }
+        Ok(count)
     }
 
     /// Get a sender to push external events (like USN Journal) into the watcher
@@ -240,9 +704,252 @@ impl WatcherManager {
         self.external_tx.clone()
     }
 
-    /// Update the list of watched directories
-    pub fn update_watch_list(&mut self, dirs: &[String]) -> Result<()> {
-        let current_dirs: std::collections::HashSet<String> = dirs.iter().cloned().collect();
+    /// Paths of every configured root, watched or currently offline
+    #[must_use]
+    pub fn watched_roots(&self) -> Vec<PathBuf> {
+        self.root_rules
+            .read()
+            .iter()
+            .map(|rules| rules.root.clone())
+            .collect()
+    }
+
+    /// Applies the result of a connectivity probe of `root`. An offline root
+    /// loses its watcher, since the handle dies with the share; one that comes
+    /// back is watched again. Returns true if `root` just reconnected.
+    pub fn set_root_online(&mut self, root: &Path, online: bool) -> Result<bool> {
+        let was_offline = self.indexer.offline_roots().set_offline(root, !online) && online;
+        let key = root.to_string_lossy().to_string();
+        if !online {
+            if self.watchers.remove(&key).is_some() {
+                warn!("Stopped watching offline root {}", root.display());
+            }
+            return Ok(false);
+        }
+        if !self.watchers.contains_key(&key) {
+            self.start_watching(&key)?;
+        }
+        if was_offline {
+            info!("{} is back online", root.display());
+        }
+        Ok(was_offline)
+    }
+
+    /// Queues the watched roots at or under `path` for a catch-up refresh.
+    pub fn queue_refresh(&self, path: &Path) {
+        mark_stale(&self.root_rules, &self.stale_roots, path);
+    }
+
+    /// Roots queued for a catch-up refresh since the last call.
+    #[must_use]
+    pub fn take_stale_roots(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut *self.stale_roots.lock())
+    }
+
+    /// Folders queued for a full rescan since the last call, after their
+    /// changes overflowed a batch.
+    #[must_use]
+    pub fn take_rescans(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut *self.rescans.lock())
+    }
+
+    /// Notified whenever folders were queued for a rescan, so they are
+    /// rescanned without waiting for the next connectivity check.
+    #[must_use]
+    pub fn rescan_signal(&self) -> Arc<Notify> {
+        Arc::clone(&self.rescan_signal)
+    }
+
+    /// Watches `dir` through its volume's change journal where one can be
+    /// read, and with file system events otherwise.
+    fn start_watching(&mut self, dir: &str) -> Result<()> {
+        if self.watch_via_journal(Path::new(dir)) {
+            return Ok(());
+        }
+        self.watch_root(dir).inspect_err(|e| {
+            self.health
+                .record_error(format!("Failed to watch {dir}: {e}"));
+        })
+    }
+
+    /// Which roots get live updates and how the event processor is keeping up.
+    #[must_use]
+    pub fn status(&self) -> WatcherStatus {
+        let (watched, unwatched): (Vec<PathBuf>, Vec<PathBuf>) = self
+            .watched_roots()
+            .into_iter()
+            .partition(|root| self.is_watched(root));
+        let to_strings = |roots: Vec<PathBuf>| {
+            roots
+                .into_iter()
+                .map(|root| root.to_string_lossy().to_string())
+                .collect()
+        };
+        WatcherStatus {
+            watched_roots: to_strings(watched),
+            unwatched_roots: to_strings(unwatched),
+            pending_events: self.health.pending.load(Ordering::Relaxed),
+            events_processed: self.health.processed.load(Ordering::Relaxed),
+            last_error: self.health.last_error.lock().clone(),
+        }
+    }
+
+    /// Whether `root` has a live watcher, directly or through its volume's journal.
+    fn is_watched(&self, root: &Path) -> bool {
+        #[cfg(target_os = "windows")]
+        if crate::scanner::drive_scanner::journal_volume(root)
+            .is_some_and(|volume| self.journals.contains_key(&volume))
+        {
+            return true;
+        }
+        self.watchers.contains_key(root.to_string_lossy().as_ref())
+    }
+
+    /// One journal reader serves every root on its volume and replays the
+    /// changes made since the position saved by the previous run.
+    #[cfg(target_os = "windows")]
+    fn watch_via_journal(&mut self, root: &Path) -> bool {
+        use crate::scanner::drive_scanner::{JournalWatch, journal_volume, watch_journal};
+
+        let Some(volume) = journal_volume(root) else {
+            return false;
+        };
+        if self.journals.contains_key(&volume) {
+            return true;
+        }
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let accept_rules = Arc::clone(&self.root_rules);
+        let stale_rules = Arc::clone(&self.root_rules);
+        let stale_roots = Arc::clone(&self.stale_roots);
+        let watch = JournalWatch {
+            volume,
+            metadata_db: Arc::clone(&self.metadata_db),
+            tx: self.external_tx.clone(),
+            accepts: Box::new(move |path, attributes| {
+                let roots = accept_rules.read();
+                rules_for(&roots, path).is_some_and(|rules| {
+                    !rules.excludes.is_excluded(path, false)
+                        && !rules.hidden.skips_attributes(attributes)
+                })
+            }),
+            mark_stale: Box::new(move |path| mark_stale(&stale_rules, &stale_roots, path)),
+            stop: Arc::clone(&stop),
+        };
+        match watch_journal(watch) {
+            Ok(()) => {
+                info!(
+                    "Watching {} through the {volume}: change journal",
+                    root.display()
+                );
+                self.journals.insert(volume, stop);
+                true
+            }
+            Err(e) => {
+                warn!(
+                    "Change journal unavailable for {}, using file events: {e}",
+                    root.display()
+                );
+                false
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[allow(clippy::unused_self, clippy::missing_const_for_fn)]
+    fn watch_via_journal(&mut self, _root: &Path) -> bool {
+        false
+    }
+
+    /// Stops journal readers whose volume no longer holds a root.
+    #[cfg(target_os = "windows")]
+    fn release_journals(&mut self, roots: &[IndexRoot]) {
+        use crate::scanner::drive_scanner::journal_volume;
+
+        let in_use: std::collections::HashSet<char> = roots
+            .iter()
+            .filter_map(|root| journal_volume(Path::new(&root.path)))
+            .collect();
+        self.journals.retain(|volume, stop| {
+            let keep = in_use.contains(volume);
+            if !keep {
+                stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+            keep
+        });
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[allow(clippy::unused_self, clippy::missing_const_for_fn)]
+    fn release_journals(&mut self, _roots: &[IndexRoot]) {}
+
+    fn watch_root(&mut self, dir: &str) -> Result<()> {
+        let tx = self.external_tx.clone();
+        let root = PathBuf::from(dir);
+        let root_rules = Arc::clone(&self.root_rules);
+        let stale_roots = Arc::clone(&self.stale_roots);
+        let health = Arc::clone(&self.health);
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Watcher error for {}: {e}", root.display());
+                    health.record_error(format!("{}: {e}", root.display()));
+                    return;
+                }
+            };
+            // The OS dropped events (e.g. inotify queue overflow)
+            if event.need_rescan() {
+                mark_stale(&root_rules, &stale_roots, &root);
+            }
+            match event.kind {
+                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
+                    let roots = root_rules.read();
+                    for (i, path) in event.paths.iter().enumerate() {
+                        // Dropped here so excluded churn never fills the buffer
+                        if is_excluded(&roots, path, path.is_dir()) {
+                            continue;
+                        }
+                        let _ = tx.try_send((path.clone(), event_action(&event.kind, i)));
+                    }
+                }
+                _ => {}
+            }
+        })
+        .map_err(|e| FlashError::Io(std::sync::Arc::new(std::io::Error::other(e))))?;
+
+        watcher
+            .watch(Path::new(dir), RecursiveMode::Recursive)
+            .map_err(|e| FlashError::Io(std::sync::Arc::new(std::io::Error::other(e))))?;
+        self.watchers.insert(dir.to_string(), watcher);
+        Ok(())
+    }
+
+    /// Applies the sensitive file rules to events from now on.
+    pub fn set_sensitive_files(&self, sensitive: SensitiveMatcher) {
+        *self.sensitive.write() = sensitive;
+    }
+
+    /// Update the list of watched roots and the profiles and exclude patterns
+    /// applied to their events. Unreachable roots are marked offline and picked
+    /// up again by [`Self::set_root_online`] once they answer.
+    pub fn update_watch_list(
+        &mut self,
+        roots: &[IndexRoot],
+        exclude_patterns: &[String],
+    ) -> Result<()> {
+        self.exclude_patterns = exclude_patterns.to_vec();
+        *self.root_rules.write() = roots
+            .iter()
+            .map(|root| RootRules::compile(root, &self.exclude_patterns))
+            .collect();
+
+        // Remote roots have nothing to watch; rescans keep them current
+        let current_dirs: std::collections::HashSet<String> = roots
+            .iter()
+            .filter(|root| !remote::is_remote(&root.path))
+            .map(|root| root.path.clone())
+            .collect();
         let existing_dirs: std::collections::HashSet<String> =
             self.watchers.keys().cloned().collect();
 
@@ -250,34 +957,17 @@ impl WatcherManager {
         for dir in existing_dirs.difference(&current_dirs) {
             self.watchers.remove(dir);
         }
+        self.release_journals(roots);
 
         // Add watchers for new directories
         for dir in current_dirs.difference(&existing_dirs) {
-            let tx = self.external_tx.clone();
-            let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
-                if let Ok(event) = res {
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
-                            for path in &event.paths {
-                                let action = match event.kind {
-                                    EventKind::Remove(_) => WatcherAction::Remove,
-                                    _ => WatcherAction::Index,
-                                };
-                                let _ = tx.try_send((path.clone(), action));
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            })
-            .map_err(|e| FlashError::Io(std::sync::Arc::new(std::io::Error::other(e))))?;
-
             let path = Path::new(dir);
-            if path.exists() {
-                watcher
-                    .watch(path, RecursiveMode::Recursive)
-                    .map_err(|e| FlashError::Io(std::sync::Arc::new(std::io::Error::other(e))))?;
-                self.watchers.insert(dir.clone(), watcher);
+            let online = network::is_reachable(path);
+            self.indexer.offline_roots().set_offline(path, !online);
+            if online {
+                self.start_watching(dir)?;
+            } else {
+                warn!("Not watching unreachable root {}", path.display());
             }
         }
 
@@ -289,7 +979,8 @@ impl WatcherManager {
         path: &Path,
         metadata_db: &Arc<MetadataDb>,
         enable_ocr: bool,
-    ) -> Result<Option<(crate::parsers::ParsedDocument, u64, u64, [u8; 32])>> {
+        index_content: bool,
+    ) -> Result<Option<(ParsedDocument, u64, u64, [u8; 32])>> {
         if !path.exists() {
             return Ok(None);
         }
@@ -315,9 +1006,23 @@ impl WatcherManager {
             return Ok(None);
         }
 
+        // Filename-only roots never open the file, nor do online-only cloud
+        // files, which reading would download; this matches the scanner
+        if !index_content || cloud::hides_content(path) {
+            let mut doc = ParsedDocument::filename_only(path);
+            doc.attributes = FileAttributes::read(path);
+            doc.annotations = metadata_db
+                .get_annotation(path)
+                .ok()
+                .flatten()
+                .map(|a| a.search_text());
+            let hash = blake3::hash(path.to_string_lossy().as_bytes()).into();
+            return Ok(Some((doc, modified, size, hash)));
+        }
+
         // Fast hash check before calling heavy parser
         let mut hasher = blake3::Hasher::new();
-        let content_hash: [u8; 32] = match std::fs::File::open(path) {
+        let content_hash: [u8; 32] = match access::open(path) {
             Ok(mut file) => {
                 use std::io::Read;
                 let mut buf = [0; 16384];
@@ -339,27 +1044,55 @@ impl WatcherManager {
                 }
                 hasher.finalize().into()
             }
-            Err(_) => return Ok(None),
+            Err(e) => {
+                if access::is_denied(&e) {
+                    activity::record(|| Activity::Skipped {
+                        path: path.to_string_lossy().to_string(),
+                        reason: SkipReason::NoAccess,
+                    });
+                }
+                return Ok(None);
+            }
         };
 
         if let Ok(Some(existing)) = metadata_db.get_metadata(path)
             && existing.content_hash == content_hash
         {
-            let _ = metadata_db.update_metadata(path, modified, size, content_hash);
+            let _ = metadata_db.update_metadata(
+                path,
+                modified,
+                size,
+                content_hash,
+                &FileAttributes::read(path),
+            );
             return Ok(None);
         }
 
         let path_buf = path.to_path_buf();
-        let parsed_res = parse_file(&path_buf, enable_ocr).await;
+        let parsed_res = parse_file_isolated(&path_buf, enable_ocr, WATCHER_PARSE_TIMEOUT).await;
 
-        let parsed = match parsed_res {
+        let mut parsed = match parsed_res {
             Ok(p) => p,
             Err(e) => {
                 warn!("Failed to parse file {:?}: {}", path, e);
+                let _ = metadata_db.record_parse_error(
+                    path,
+                    ParseErrorKind::from_error(&e),
+                    &e.to_string(),
+                    modified,
+                    size,
+                );
                 return Ok(None);
             }
         };
 
+        parsed.attributes = FileAttributes::read(path);
+        parsed.annotations = metadata_db
+            .get_annotation(path)
+            .ok()
+            .flatten()
+            .map(|a| a.search_text());
+
         Ok(Some((parsed, modified, size, content_hash)))
     }
 }
@@ -392,16 +1125,125 @@ mod tests {
 
         assert!(
             watcher
-                .update_watch_list(&[watch_dir.to_string_lossy().to_string()])
+                .update_watch_list(&[IndexRoot::new(watch_dir.to_string_lossy())], &[])
                 .is_ok()
         );
         assert!(!watcher.watchers.is_empty());
 
         // Empty list should remove watcher
-        assert!(watcher.update_watch_list(&[]).is_ok());
+        assert!(watcher.update_watch_list(&[], &[]).is_ok());
         assert!(watcher.watchers.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_recover_pending_batches_rolls_back_and_replays() {
+        let temp = tempdir().unwrap();
+        let indexer = Arc::new(IndexManager::open(temp.path(), 256).unwrap());
+        let metadata = Arc::new(
+            MetadataDb::open(&temp.path().join("metadata.db"))
+                .unwrap()
+                .0,
+        );
+        let kept = temp.path().join("kept.txt");
+        fs::write(&kept, "still here").unwrap();
+        let kept = kept.to_string_lossy().to_string();
+        let gone = temp.path().join("gone.txt").to_string_lossy().to_string();
+        metadata
+            .batch_update_metadata(&[(kept.clone(), 1, 10, [0; 32], FileAttributes::default())])
+            .unwrap();
+        metadata
+            .begin_batch(std::slice::from_ref(&kept), &[gone])
+            .unwrap();
+
+        let watcher = WatcherManager::new(
+            indexer,
+            metadata.clone(),
+            std::collections::HashSet::new(),
+            false,
+        );
+        assert_eq!(watcher.recover_pending_batches().unwrap(), 2);
+        // The record is gone, so the replayed event re-indexes the file
+        assert!(metadata.get_metadata(Path::new(&kept)).unwrap().is_none());
+        // The journal entry outlives the rollback until the replay commits
+        assert_eq!(metadata.get_pending_batches().unwrap().len(), 1);
+        for _ in 0..100 {
+            if metadata.get_pending_batches().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(metadata.get_pending_batches().unwrap().is_empty());
+        assert_eq!(watcher.recover_pending_batches().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_watched_and_unwatched_roots() {
+        let temp = tempdir().unwrap();
+        let indexer = Arc::new(IndexManager::open(temp.path(), 256).unwrap());
+        let metadata = Arc::new(
+            MetadataDb::open(&temp.path().join("metadata.db"))
+                .unwrap()
+                .0,
+        );
+        let mut watcher =
+            WatcherManager::new(indexer, metadata, std::collections::HashSet::new(), false);
+
+        let docs = temp.path().join("docs");
+        let share = temp.path().join("share");
+        fs::create_dir(&docs).unwrap();
+        watcher
+            .update_watch_list(
+                &[
+                    IndexRoot::new(docs.to_string_lossy()),
+                    IndexRoot::new(share.to_string_lossy()),
+                ],
+                &[],
+            )
+            .unwrap();
+
+        let status = watcher.status();
+        assert_eq!(
+            status.watched_roots,
+            vec![docs.to_string_lossy().to_string()]
+        );
+        assert_eq!(
+            status.unwatched_roots,
+            vec![share.to_string_lossy().to_string()]
+        );
+        assert_eq!(status.pending_events, 0);
+        assert!(status.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_offline_root_is_watched_once_back() {
+        let temp = tempdir().unwrap();
+        let indexer = Arc::new(IndexManager::open(temp.path(), 256).unwrap());
+        let metadata = Arc::new(
+            MetadataDb::open(&temp.path().join("metadata.db"))
+                .unwrap()
+                .0,
+        );
+        let mut watcher = WatcherManager::new(
+            indexer.clone(),
+            metadata,
+            std::collections::HashSet::new(),
+            false,
+        );
+
+        let share = temp.path().join("share");
+        watcher
+            .update_watch_list(&[IndexRoot::new(share.to_string_lossy())], &[])
+            .unwrap();
+        assert!(watcher.watchers.is_empty());
+        assert!(indexer.offline_roots().contains(&share.join("a.txt")));
+        assert_eq!(watcher.watched_roots(), vec![share.clone()]);
+
+        fs::create_dir(&share).unwrap();
+        assert!(watcher.set_root_online(&share, true).unwrap());
+        assert!(!watcher.watchers.is_empty());
+        assert!(!indexer.offline_roots().contains(&share.join("a.txt")));
+    }
+
     #[tokio::test]
     async fn test_reindex_single_file() {
         use std::io::Write;
@@ -417,7 +1259,7 @@ mod tests {
         writeln!(file, "Initial content").unwrap();
 
         // Should return Some on first index
-        let result = WatcherManager::reindex_single_file(&file_path, &metadata, false).await;
+        let result = WatcherManager::reindex_single_file(&file_path, &metadata, false, true).await;
         assert!(result.is_ok());
         let option = result.unwrap();
         assert!(option.is_some());
@@ -425,12 +1267,190 @@ mod tests {
         assert_eq!(doc.content, "Initial content");
 
         metadata
-            .update_metadata(&file_path, modified, size, hash)
+            .update_metadata(&file_path, modified, size, hash, &doc.attributes)
             .unwrap();
 
         // Should return None if no change
-        let result = WatcherManager::reindex_single_file(&file_path, &metadata, false).await;
+        let result = WatcherManager::reindex_single_file(&file_path, &metadata, false, true).await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
     }
+
+    #[test]
+    fn test_rename_events_remove_the_old_path() {
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+        assert_eq!(event_action(&rename, 0), WatcherAction::Remove);
+        assert_eq!(event_action(&rename, 1), WatcherAction::Index);
+        assert_eq!(
+            event_action(&EventKind::Modify(ModifyKind::Name(RenameMode::From)), 0),
+            WatcherAction::Remove
+        );
+        assert_eq!(
+            event_action(&EventKind::Modify(ModifyKind::Name(RenameMode::To)), 0),
+            WatcherAction::Index
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_filenames_follows_renames() {
+        let temp = tempdir().unwrap();
+        let index = FilenameIndex::open(&temp.path().join("filenames")).unwrap();
+        let old = temp.path().join("draft.txt");
+        let new = temp.path().join("final.txt");
+        fs::write(&new, "text").unwrap();
+        index.add_file(&old.to_string_lossy(), "draft.txt").unwrap();
+        index.commit().unwrap();
+
+        WatcherManager::update_filenames(&index, &[old], &[(new, false)]);
+
+        assert!(index.search("draft", 10).unwrap().is_empty());
+        assert_eq!(index.search("final", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_flush_delay_follows_coalesce_policy() {
+        let debounce = WatcherSettings {
+            debounce_ms: 500,
+            max_wait_ms: 2000,
+            ..WatcherSettings::default()
+        };
+        assert_eq!(
+            flush_delay(&debounce, Duration::from_millis(100)),
+            Duration::from_millis(500)
+        );
+        // A burst may not postpone the flush past the max wait
+        assert_eq!(
+            flush_delay(&debounce, Duration::from_millis(1800)),
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            flush_delay(&debounce, Duration::from_secs(3)),
+            Duration::ZERO
+        );
+
+        let window = WatcherSettings {
+            coalesce: CoalescePolicy::Window,
+            ..debounce
+        };
+        assert_eq!(
+            flush_delay(&window, Duration::from_millis(100)),
+            Duration::from_millis(400)
+        );
+    }
+
+    #[test]
+    fn test_mark_stale_queues_affected_roots() {
+        let roots = RwLock::new(vec![
+            RootRules::compile(&IndexRoot::new("C:/docs"), &[]),
+            RootRules::compile(&IndexRoot::new("C:/code"), &[]),
+            RootRules::compile(&IndexRoot::new("D:/media"), &[]),
+        ]);
+        let stale = Mutex::new(Vec::new());
+
+        mark_stale(&roots, &stale, Path::new("C:/docs/moved"));
+        mark_stale(&roots, &stale, Path::new("C:/docs/moved"));
+        assert_eq!(*stale.lock(), vec![PathBuf::from("C:/docs")]);
+
+        // A journal gap covers every root on the volume
+        mark_stale(&roots, &stale, Path::new("C:/"));
+        assert_eq!(
+            *stale.lock(),
+            vec![PathBuf::from("C:/docs"), PathBuf::from("C:/code")]
+        );
+    }
+
+    #[test]
+    fn test_queue_rescan_keeps_outermost_folders() {
+        let roots = RwLock::new(vec![RootRules::compile(&IndexRoot::new("/docs"), &[])]);
+        let rescans = Mutex::new(Vec::new());
+
+        queue_rescan(&roots, &rescans, Path::new("/docs/a/b/one.txt"));
+        queue_rescan(&roots, &rescans, Path::new("/docs/a/b/two.txt"));
+        queue_rescan(&roots, &rescans, Path::new("/docs/a/three.txt"));
+        assert_eq!(*rescans.lock(), vec![PathBuf::from("/docs/a")]);
+
+        // Past the limit the whole root is rescanned
+        for n in 1..MAX_RESCAN_FOLDERS {
+            queue_rescan(
+                &roots,
+                &rescans,
+                &Path::new("/docs").join(format!("{n}/f.txt")),
+            );
+        }
+        queue_rescan(&roots, &rescans, Path::new("/docs/last/f.txt"));
+        assert_eq!(*rescans.lock(), vec![PathBuf::from("/docs")]);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_queues_folders_and_signals_rescan() {
+        let temp = tempdir().unwrap();
+        let indexer = Arc::new(IndexManager::open(temp.path(), 256).unwrap());
+        let metadata = Arc::new(
+            MetadataDb::open(&temp.path().join("metadata.db"))
+                .unwrap()
+                .0,
+        );
+        let limits = WatcherSettings {
+            debounce_ms: 50,
+            max_buffered_events: 2,
+            ..WatcherSettings::default()
+        };
+        let watcher = WatcherManager::new_with_excludes(
+            indexer,
+            metadata,
+            None,
+            std::collections::HashSet::new(),
+            &[],
+            limits,
+            false,
+        );
+        let signal = watcher.rescan_signal();
+        let tx = watcher.event_tx();
+        for name in ["a/1.txt", "a/2.txt", "b/3.txt", "b/4.txt"] {
+            tx.send((temp.path().join(name), WatcherAction::Index))
+                .await
+                .unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), signal.notified())
+            .await
+            .unwrap();
+        let mut rescans = watcher.take_rescans();
+        rescans.sort();
+        assert_eq!(rescans, vec![temp.path().join("a"), temp.path().join("b")]);
+    }
+
+    #[test]
+    fn test_root_rules_follow_profile() {
+        let profile = IndexRoot {
+            include: vec!["*.md".to_string()],
+            exclude: vec!["drafts".to_string()],
+            max_depth: Some(2),
+            ..IndexRoot::new("/notes")
+        };
+        let roots = vec![RootRules::compile(&profile, &[])];
+        let rules = rules_for(&roots, Path::new("/notes/a/b.md")).unwrap();
+
+        assert!(rules.allows(Path::new("a/b.md")));
+        assert!(!rules.allows(Path::new("a/b.txt")));
+        assert!(is_excluded(&roots, Path::new("/notes/drafts/b.md"), false));
+        assert!(!rules.allows(Path::new("a/b/c.md")));
+        assert!(rules_for(&roots, Path::new("/elsewhere/x.md")).is_none());
+    }
+
+    #[test]
+    fn test_global_excludes_filter_events() {
+        let excludes = vec!["node_modules/".to_string(), ".git".to_string()];
+        let roots = vec![RootRules::compile(&IndexRoot::new("/src"), &excludes)];
+
+        assert!(is_excluded(
+            &roots,
+            Path::new("/src/app/node_modules/x/a.js"),
+            false
+        ));
+        assert!(is_excluded(&roots, Path::new("/src/.git/index"), false));
+        assert!(!is_excluded(&roots, Path::new("/src/app/main.js"), false));
+        // Paths outside every root are left to the root checks
+        assert!(!is_excluded(&roots, Path::new("/other/.git/index"), false));
+    }
 }
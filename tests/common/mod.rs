@@ -0,0 +1,565 @@
+//! Shared fixtures for the end-to-end suites.
+//!
+//! The corpus is generated into a temp directory on every run so the repository
+//! carries no binary blobs. Each fixture is the smallest file its format's
+//! extractor accepts, and embeds a marker word no other fixture contains.
+
+use flash_search::indexer::IndexManager;
+use flash_search::indexer::searcher::{SearchParams, SearchResult};
+use flash_search::metadata::MetadataDb;
+use flash_search::scanner::Scanner;
+use flash_search::settings::{AppSettings, IndexRoot};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use tempfile::TempDir;
+
+/// A generated fixture and the word searching for it should find.
+pub struct Fixture {
+    pub file_name: &'static str,
+    pub marker: &'static str,
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        file_name: "plain.txt",
+        marker: "quokkatxt",
+    },
+    Fixture {
+        file_name: "notes.md",
+        marker: "quokkamd",
+    },
+    Fixture {
+        file_name: "data.json",
+        marker: "quokkajson",
+    },
+    Fixture {
+        file_name: "feed.xml",
+        marker: "quokkaxml",
+    },
+    Fixture {
+        file_name: "table.csv",
+        marker: "quokkacsv",
+    },
+    Fixture {
+        file_name: "table.tsv",
+        marker: "quokkatsv",
+    },
+    Fixture {
+        file_name: "page.html",
+        marker: "quokkahtml",
+    },
+    Fixture {
+        file_name: "styles.css",
+        marker: "quokkacss",
+    },
+    Fixture {
+        file_name: "letter.rtf",
+        marker: "quokkartf",
+    },
+    Fixture {
+        file_name: "mail.eml",
+        marker: "quokkaeml",
+    },
+    Fixture {
+        file_name: "lib.rs",
+        marker: "quokkars",
+    },
+    Fixture {
+        file_name: "script.py",
+        marker: "quokkapy",
+    },
+    Fixture {
+        file_name: "app.js",
+        marker: "quokkajs",
+    },
+    Fixture {
+        file_name: "app.ts",
+        marker: "quokkats",
+    },
+    Fixture {
+        file_name: "main.go",
+        marker: "quokkago",
+    },
+    Fixture {
+        file_name: "Main.java",
+        marker: "quokkajava",
+    },
+    Fixture {
+        file_name: "main.c",
+        marker: "quokkac",
+    },
+    Fixture {
+        file_name: "main.cpp",
+        marker: "quokkacpp",
+    },
+    Fixture {
+        file_name: "decl.h",
+        marker: "quokkah",
+    },
+    Fixture {
+        file_name: "decl.hpp",
+        marker: "quokkahpp",
+    },
+    Fixture {
+        file_name: "Program.cs",
+        marker: "quokkacs",
+    },
+    Fixture {
+        file_name: "report.pdf",
+        marker: "quokkapdf",
+    },
+    Fixture {
+        file_name: "report.docx",
+        marker: "quokkadocx",
+    },
+    Fixture {
+        file_name: "sheet.xlsx",
+        marker: "quokkaxlsx",
+    },
+    Fixture {
+        file_name: "deck.pptx",
+        marker: "quokkapptx",
+    },
+    Fixture {
+        file_name: "report.odt",
+        marker: "quokkaodt",
+    },
+    Fixture {
+        file_name: "book.epub",
+        marker: "quokkaepub",
+    },
+    Fixture {
+        file_name: "bundle.zip",
+        marker: "quokkazip",
+    },
+];
+
+/// Writes every entry of [`FIXTURES`] into `dir`.
+pub fn write_corpus(dir: &Path) {
+    for fixture in FIXTURES {
+        write_fixture(&dir.join(fixture.file_name), fixture.marker);
+    }
+}
+
+/// Writes a single fixture whose format is picked from the extension of `path`.
+///
+/// # Panics
+///
+/// Panics on an unknown extension or if the file cannot be written.
+pub fn write_fixture(path: &Path, marker: &str) {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let bytes = match ext {
+        "txt" => format!("Plain text fixture mentioning {marker}.\n").into_bytes(),
+        "md" => format!("# Fixture\n\nMarkdown body with {marker} inside.\n").into_bytes(),
+        "json" => format!("{{\"title\": \"fixture\", \"body\": \"{marker}\"}}\n").into_bytes(),
+        "xml" => {
+            format!("<?xml version=\"1.0\"?>\n<feed><entry>{marker}</entry></feed>\n").into_bytes()
+        }
+        "csv" => format!("name,value\nfixture,{marker}\n").into_bytes(),
+        "tsv" => format!("name\tvalue\nfixture\t{marker}\n").into_bytes(),
+        "html" => format!(
+            "<!DOCTYPE html><html><head><title>Fixture</title></head>\
+             <body><p>HTML paragraph with {marker}.</p></body></html>\n"
+        )
+        .into_bytes(),
+        "css" => format!("/* {marker} */\nbody {{ margin: 0; }}\n").into_bytes(),
+        "rtf" => format!(
+            "{{\\rtf1\\ansi\\deff0 {{\\fonttbl{{\\f0 Arial;}}}}\\f0 RTF text {marker}.\\par}}\n"
+        )
+        .into_bytes(),
+        "eml" => format!(
+            "From: sender@example.com\r\nTo: receiver@example.com\r\n\
+             Subject: Fixture\r\nMIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\r\nMail body with {marker}.\r\n"
+        )
+        .into_bytes(),
+        "rs" => format!("/// {marker}\npub fn fixture() -> u32 {{ 42 }}\n").into_bytes(),
+        "py" => format!("# {marker}\ndef fixture():\n    return 42\n").into_bytes(),
+        "js" | "ts" | "go" | "java" | "c" | "cpp" | "h" | "hpp" | "cs" => {
+            format!("// {marker}\nint fixture = 42;\n").into_bytes()
+        }
+        "pdf" => minimal_pdf(&format!("PDF text {marker}")),
+        "docx" => minimal_docx(&format!("Word text {marker}")),
+        "xlsx" => minimal_xlsx(marker),
+        "pptx" => minimal_pptx(&format!("Slide text {marker}")),
+        "odt" => minimal_odt(&format!("ODT text {marker}")),
+        "epub" => minimal_epub(&format!("EPUB chapter text {marker}")),
+        "zip" => zip_bytes(
+            &[(
+                "inner.txt",
+                format!("Archived text {marker}").as_bytes().to_vec(),
+            )],
+            None,
+        ),
+        other => panic!("no fixture generator for .{other}"),
+    };
+    std::fs::write(path, bytes).expect("failed to write fixture");
+}
+
+/// A one-page PDF with a single line of Helvetica text and a correct xref table.
+fn minimal_pdf(text: &str) -> Vec<u8> {
+    let stream = format!("BT /F1 18 Tf 72 720 Td ({text}) Tj ET");
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+         /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+            .to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{stream}\nendstream",
+            stream.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, body) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n{body}\nendobj\n", i + 1).as_bytes());
+    }
+
+    let xref_start = out.len();
+    out.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        out.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_start}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    out
+}
+
+fn minimal_docx(text: &str) -> Vec<u8> {
+    zip_bytes(
+        &[
+            (
+                "[Content_Types].xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#
+                    .to_vec(),
+            ),
+            (
+                "_rels/.rels",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>"#
+                    .to_vec(),
+            ),
+            (
+                "word/document.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:body><w:p><w:r><w:t>{text}</w:t></w:r></w:p></w:body>
+</w:document>"#
+                )
+                .into_bytes(),
+            ),
+        ],
+        None,
+    )
+}
+
+fn minimal_xlsx(cell: &str) -> Vec<u8> {
+    zip_bytes(
+        &[
+            (
+                "[Content_Types].xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+</Types>"#
+                    .to_vec(),
+            ),
+            (
+                "_rels/.rels",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#
+                    .to_vec(),
+            ),
+            (
+                "xl/workbook.xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets>
+</workbook>"#
+                    .to_vec(),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+</Relationships>"#
+                    .to_vec(),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<sheetData><row r="1"><c r="A1" t="inlineStr"><is><t>{cell}</t></is></c></row></sheetData>
+</worksheet>"#
+                )
+                .into_bytes(),
+            ),
+        ],
+        None,
+    )
+}
+
+fn minimal_pptx(text: &str) -> Vec<u8> {
+    zip_bytes(
+        &[
+            (
+                "[Content_Types].xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/>
+<Override PartName="/ppt/slides/slide1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>
+</Types>"#
+                    .to_vec(),
+            ),
+            (
+                "_rels/.rels",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/>
+</Relationships>"#
+                    .to_vec(),
+            ),
+            (
+                "ppt/presentation.xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<p:sldIdLst><p:sldId id="256" r:id="rId1"/></p:sldIdLst>
+</p:presentation>"#
+                    .to_vec(),
+            ),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/>
+</Relationships>"#
+                    .to_vec(),
+            ),
+            (
+                "ppt/slides/slide1.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+<p:cSld><p:spTree><p:sp><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld>
+</p:sld>"#
+                )
+                .into_bytes(),
+            ),
+        ],
+        None,
+    )
+}
+
+fn minimal_odt(text: &str) -> Vec<u8> {
+    zip_bytes(
+        &[
+            (
+                "META-INF/manifest.xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">
+<manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.text"/>
+<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>"#
+                    .to_vec(),
+            ),
+            (
+                "content.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2">
+<office:body><office:text><text:p>{text}</text:p></office:text></office:body>
+</office:document-content>"#
+                )
+                .into_bytes(),
+            ),
+        ],
+        Some("application/vnd.oasis.opendocument.text"),
+    )
+}
+
+fn minimal_epub(text: &str) -> Vec<u8> {
+    zip_bytes(
+        &[
+            (
+                "META-INF/container.xml",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#
+                    .to_vec(),
+            ),
+            (
+                "OEBPS/content.opf",
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="id">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:identifier id="id">fixture</dc:identifier><dc:title>Fixture</dc:title><dc:language>en</dc:language>
+</metadata>
+<manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+<spine><itemref idref="ch1"/></spine>
+</package>"#
+                    .to_vec(),
+            ),
+            (
+                "OEBPS/ch1.xhtml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml"><head><title>Chapter</title></head>
+<body><p>{text}</p></body></html>"#
+                )
+                .into_bytes(),
+            ),
+        ],
+        Some("application/epub+zip"),
+    )
+}
+
+/// Builds a zip archive. When `mimetype` is given it is stored uncompressed as
+/// the first entry, as ODF and EPUB require.
+fn zip_bytes(entries: &[(&str, Vec<u8>)], mimetype: Option<&str>) -> Vec<u8> {
+    use zip::write::SimpleFileOptions;
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    if let Some(mimetype) = mimetype {
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("mimetype", stored).unwrap();
+        writer.write_all(mimetype.as_bytes()).unwrap();
+    }
+    for (name, data) in entries {
+        writer
+            .start_file(*name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// A scanner pipeline wired to an isolated index, metadata store and corpus.
+pub struct Harness {
+    _workspace: TempDir,
+    pub corpus_dir: PathBuf,
+    pub indexer: Arc<IndexManager>,
+    pub metadata_db: Arc<MetadataDb>,
+    pub settings: AppSettings,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let workspace = tempfile::tempdir().unwrap();
+        let corpus_dir = workspace.path().join("corpus");
+        let index_dir = workspace.path().join("index");
+        std::fs::create_dir_all(&corpus_dir).unwrap();
+        std::fs::create_dir_all(&index_dir).unwrap();
+
+        let indexer = Arc::new(IndexManager::open(&index_dir, 50).unwrap());
+        let metadata_db = Arc::new(
+            MetadataDb::open(&index_dir.join("metadata.redb"))
+                .unwrap()
+                .0,
+        );
+        let settings = AppSettings {
+            indexing_threads: 2,
+            ..AppSettings::default()
+        };
+
+        Self {
+            _workspace: workspace,
+            corpus_dir,
+            indexer,
+            metadata_db,
+            settings,
+        }
+    }
+
+    pub fn scanner(&self) -> Scanner {
+        Scanner::new(
+            self.indexer.clone(),
+            self.metadata_db.clone(),
+            None,
+            None,
+            self.settings.clone(),
+        )
+    }
+
+    /// Default profile for the corpus directory.
+    pub fn root(&self) -> IndexRoot {
+        IndexRoot::new(self.corpus_dir.to_string_lossy())
+    }
+
+    /// Runs a full scan of the corpus directory and waits for it to finish.
+    pub async fn scan(&self) {
+        self.scan_root(self.root()).await;
+    }
+
+    /// Runs a full scan of the corpus directory under `root`'s profile.
+    pub async fn scan_root(&self, root: IndexRoot) {
+        self.scanner()
+            .scan_directory(root, Vec::new(), Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap();
+        self.settle();
+    }
+
+    /// Runs an incremental refresh of the corpus directory and waits for it to finish.
+    pub async fn refresh(&self) {
+        self.scanner()
+            .refresh_directory(self.root(), Vec::new(), Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap();
+        self.settle();
+    }
+
+    /// Makes the last commit visible to searches.
+    pub fn settle(&self) {
+        self.indexer.reload_readers();
+    }
+
+    pub async fn search(&self, query: &str) -> Vec<SearchResult> {
+        self.indexer
+            .search(SearchParams::builder().query(query).limit(50).build())
+            .await
+            .unwrap()
+    }
+
+    /// Whether searching for `query` returns a hit whose path ends in `file_name`.
+    pub async fn finds(&self, query: &str, file_name: &str) -> bool {
+        self.search(query)
+            .await
+            .iter()
+            .any(|r| Path::new(&r.file_path).ends_with(file_name))
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(clippy::large_futures)]
+
+mod common;
+
+use common::{FIXTURES, Harness, write_corpus, write_fixture};
+use flash_search::watcher::{WatcherAction, WatcherManager};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+/// Longest we wait for the watcher's debounced flush to land in the index.
+const WATCHER_WAIT: Duration = Duration::from_secs(20);
+
+fn file_names(paths: &[String]) -> HashSet<String> {
+    paths
+        .iter()
+        .filter_map(|p| Path::new(p).file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .collect()
+}
+
+async fn eventually_finds(harness: &Harness, query: &str, file_name: &str, expected: bool) -> bool {
+    let deadline = tokio::time::Instant::now() + WATCHER_WAIT;
+    while tokio::time::Instant::now() < deadline {
+        if harness.finds(query, file_name).await == expected {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_every_format_is_searchable() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    harness.scan().await;
+
+    let mut missing = Vec::new();
+    for fixture in FIXTURES {
+        if !harness.finds(fixture.marker, fixture.file_name).await {
+            missing.push(fixture.file_name);
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "fixtures not found by their marker: {missing:?}"
+    );
+
+    // Markers are unique, so each one must match exactly its own fixture.
+    let hits = harness.search("quokkadocx").await;
+    assert_eq!(hits.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_metadata_matches_index() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    harness.scan().await;
+
+    let parse_errors = harness.metadata_db.get_parse_errors().unwrap();
+    assert!(
+        parse_errors.is_empty(),
+        "unexpected parse failures: {parse_errors:?}"
+    );
+
+    let expected: HashSet<String> = FIXTURES.iter().map(|f| f.file_name.to_string()).collect();
+    let tracked = harness.metadata_db.get_all_file_paths().unwrap();
+    assert_eq!(file_names(&tracked), expected);
+
+    let stats = harness.indexer.get_statistics().unwrap();
+    assert_eq!(stats.total_documents, FIXTURES.len());
+
+    for path in &tracked {
+        let meta = harness
+            .metadata_db
+            .get_metadata(Path::new(path))
+            .unwrap()
+            .expect("tracked path has metadata");
+        let on_disk = std::fs::metadata(path).unwrap();
+        assert_eq!(meta.size, on_disk.len(), "size mismatch for {path}");
+        assert!(
+            !harness
+                .metadata_db
+                .needs_reindex(Path::new(path), meta.modified, meta.size)
+                .unwrap()
+        );
+    }
+
+    // An unchanged corpus must not produce duplicate documents on rescan.
+    harness.scan().await;
+    let stats = harness.indexer.get_statistics().unwrap();
+    assert_eq!(stats.total_documents, FIXTURES.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_watcher_applies_updates_and_removals() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    harness.scan().await;
+
+    let watcher = WatcherManager::new(
+        harness.indexer.clone(),
+        harness.metadata_db.clone(),
+        harness.settings.get_allowed_extensions().clone(),
+        false,
+    );
+    let tx = watcher.event_tx();
+
+    let edited = harness.corpus_dir.join("plain.txt");
+    write_fixture(&edited, "wombatedited");
+    let added = harness.corpus_dir.join("added.md");
+    write_fixture(&added, "wombatadded");
+    let removed = harness.corpus_dir.join("report.docx");
+    std::fs::remove_file(&removed).unwrap();
+
+    tx.send((edited, WatcherAction::Index)).await.unwrap();
+    tx.send((added, WatcherAction::Index)).await.unwrap();
+    tx.send((removed.clone(), WatcherAction::Remove))
+        .await
+        .unwrap();
+
+    assert!(eventually_finds(&harness, "wombatedited", "plain.txt", true).await);
+    assert!(eventually_finds(&harness, "wombatadded", "added.md", true).await);
+    assert!(eventually_finds(&harness, "quokkadocx", "report.docx", false).await);
+    assert!(!harness.finds("quokkatxt", "plain.txt").await);
+
+    assert!(
+        harness
+            .metadata_db
+            .get_metadata(&removed)
+            .unwrap()
+            .is_none()
+    );
+    let stats = harness.indexer.get_statistics().unwrap();
+    assert_eq!(stats.total_documents, FIXTURES.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rebuild_reflects_current_corpus() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    harness.scan().await;
+
+    std::fs::remove_file(harness.corpus_dir.join("sheet.xlsx")).unwrap();
+    write_fixture(&harness.corpus_dir.join("late.txt"), "wombatlate");
+
+    harness.indexer.clear().unwrap();
+    harness.indexer.commit().unwrap();
+    harness.metadata_db.clear().unwrap();
+    harness.settle();
+    assert_eq!(harness.indexer.get_statistics().unwrap().total_documents, 0);
+
+    harness.scan().await;
+
+    assert!(harness.finds("wombatlate", "late.txt").await);
+    assert!(!harness.finds("quokkaxlsx", "sheet.xlsx").await);
+    assert!(harness.finds("quokkapdf", "report.pdf").await);
+
+    let tracked = file_names(&harness.metadata_db.get_all_file_paths().unwrap());
+    assert!(tracked.contains("late.txt"));
+    assert!(!tracked.contains("sheet.xlsx"));
+    assert_eq!(
+        harness.indexer.get_statistics().unwrap().total_documents,
+        FIXTURES.len()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_refresh_picks_up_added_and_deleted_files() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    harness.refresh().await;
+    assert!(harness.finds("quokkapdf", "report.pdf").await);
+
+    let nested = harness.corpus_dir.join("nested");
+    std::fs::create_dir(&nested).unwrap();
+    write_fixture(&nested.join("fresh.txt"), "wombatfresh");
+    std::fs::remove_file(harness.corpus_dir.join("sheet.xlsx")).unwrap();
+
+    harness.refresh().await;
+
+    assert!(harness.finds("wombatfresh", "fresh.txt").await);
+    assert!(!harness.finds("quokkaxlsx", "sheet.xlsx").await);
+    let tracked = file_names(&harness.metadata_db.get_all_file_paths().unwrap());
+    assert!(tracked.contains("fresh.txt"));
+    assert!(!tracked.contains("sheet.xlsx"));
+
+    // Deleting the whole folder drops its files on the next refresh
+    std::fs::remove_dir_all(&nested).unwrap();
+    harness.refresh().await;
+    assert!(!harness.finds("wombatfresh", "fresh.txt").await);
+    assert_eq!(
+        harness.indexer.get_statistics().unwrap().total_documents,
+        FIXTURES.len() - 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_filename_only_root_skips_content() {
+    let harness = Harness::new();
+    write_corpus(&harness.corpus_dir);
+    write_fixture(&harness.corpus_dir.join("holiday.raw"), "wombatraw");
+
+    let mut root = harness.root();
+    root.index_content = false;
+    root.exclude = vec!["*.docx".to_string()];
+    harness.scan_root(root).await;
+
+    // Names are tracked, contents are not, and the extension list doesn't apply
+    let tracked = file_names(&harness.metadata_db.get_all_file_paths().unwrap());
+    assert!(tracked.contains("holiday.raw"));
+    assert!(tracked.contains("report.pdf"));
+    assert!(!tracked.contains("report.docx"));
+    assert!(!harness.finds("quokkapdf", "report.pdf").await);
+}